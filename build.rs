@@ -6,17 +6,75 @@ fn npm_command() -> Command {
     // On Windows, npm is a .cmd file
     if cfg!(target_os = "windows") {
         let mut cmd = Command::new("cmd");
-        cmd.args(&["/C", "npm"]);
+        cmd.args(["/C", "npm"]);
         cmd
     } else {
         Command::new("npm")
     }
 }
 
+/// Embed build info read by `src/build_info.rs`: git sha, build time, enabled features.
+fn emit_build_info() {
+    let git_sha = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=SYNBOT_GIT_SHA={}", git_sha);
+
+    // SOURCE_DATE_EPOCH keeps reproducible builds reproducible
+    let epoch = env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        });
+    println!("cargo:rustc-env=SYNBOT_BUILD_EPOCH={}", epoch);
+
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(k, _)| k.strip_prefix("CARGO_FEATURE_").map(|f| f.to_lowercase().replace('_', "-")))
+        .collect();
+    features.sort();
+    println!("cargo:rustc-env=SYNBOT_FEATURES={}", features.join(","));
+
+    // A commit moves the branch ref, not HEAD: watch the ref HEAD points to as well, and
+    // packed-refs for refs that live there. Paths that do not exist would rerun every build.
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .and_then(|o| String::from_utf8(o.stdout).ok())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    let mut watched = vec![git(&["rev-parse", "--git-path", "HEAD"]).unwrap_or_else(|| ".git/HEAD".to_string())];
+    if let Some(head_ref) = git(&["symbolic-ref", "-q", "HEAD"]) {
+        watched.extend(git(&["rev-parse", "--git-path", &head_ref]));
+    }
+    watched.extend(git(&["rev-parse", "--git-path", "packed-refs"]));
+    for path in watched.iter().filter(|p| Path::new(p).exists()) {
+        println!("cargo:rerun-if-changed={}", path);
+    }
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+}
+
 fn main() {
     // Feishu WebSocket proto (Frame/Header) — no third-party Lark crates
-    prost_build::compile_protos(&["proto/pbbp2.proto"], &["."]).expect("compile proto/pbbp2.proto");
-    println!("cargo:rerun-if-changed=proto/pbbp2.proto");
+    #[cfg(feature = "feishu")]
+    {
+        prost_build::compile_protos(&["proto/pbbp2.proto"], &["."]).expect("compile proto/pbbp2.proto");
+        println!("cargo:rerun-if-changed=proto/pbbp2.proto");
+    }
+
+    emit_build_info();
 
     // Only build frontend in release mode
     let profile = env::var("PROFILE").unwrap_or_default();
@@ -38,7 +96,7 @@ fn main() {
             println!("cargo:warning=Installing frontend dependencies...");
             let mut install_cmd = npm_command();
             let install_status = install_cmd
-                .args(&["install"])
+                .args(["install"])
                 .current_dir(frontend_dir)
                 .status();
             
@@ -59,7 +117,7 @@ fn main() {
         println!("cargo:warning=Building frontend with npm run build...");
         let mut build_cmd = npm_command();
         let build_output = build_cmd
-            .args(&["run", "build"])
+            .args(["run", "build"])
             .current_dir(frontend_dir)
             .output();
         
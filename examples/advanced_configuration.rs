@@ -101,6 +101,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
             "cdn.example.net".to_string(),
         ],
         allowed_ports: vec![80, 443, 8080],
+        host_network: false,
     };
     
     println!("   Network enabled: {}", network_config.enabled);
@@ -143,6 +144,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
         delete_on_start: false,
         requested_tool_sandbox_type: None,
         image: None,
+        hardening: Default::default(),
     };
 
     let sandbox_id = manager.create_app_sandbox(sandbox_config).await?;
@@ -36,6 +36,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
             enabled: true,
             allowed_hosts: vec!["api.example.com".to_string()],
             allowed_ports: vec![80, 443],
+            host_network: false,
         },
         resources: ResourceConfig {
             max_memory: 2 * 1024 * 1024 * 1024, // 2GB
@@ -51,6 +52,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
         delete_on_start: false,
         requested_tool_sandbox_type: None,
         image: None,
+        hardening: Default::default(),
     };
 
     let sandbox_id = manager.create_app_sandbox(app_config).await?;
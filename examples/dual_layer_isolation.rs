@@ -32,6 +32,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
             enabled: true,
             allowed_hosts: vec!["api.example.com".to_string()],
             allowed_ports: vec![443],
+            host_network: false,
         },
         resources: ResourceConfig {
             max_memory: 2 * 1024 * 1024 * 1024, // 2GB
@@ -47,6 +48,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
         delete_on_start: false,
         requested_tool_sandbox_type: None,
         image: None,
+        hardening: Default::default(),
     };
 
     let app_sandbox_id = manager.create_app_sandbox(app_config).await?;
@@ -68,6 +70,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
             enabled: false, // More restrictive than app sandbox
             allowed_hosts: vec![],
             allowed_ports: vec![],
+            host_network: false,
         },
         resources: ResourceConfig {
             max_memory: 1024 * 1024 * 1024, // 1GB - less than app
@@ -83,6 +86,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
         delete_on_start: false,
         requested_tool_sandbox_type: None,
         image: None,
+        hardening: Default::default(),
     };
 
     let tool_sandbox_id = manager.create_tool_sandbox(tool_config).await?;
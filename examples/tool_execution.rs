@@ -33,6 +33,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
             enabled: false, // Tools typically don't need network
             allowed_hosts: vec![],
             allowed_ports: vec![],
+            host_network: false,
         },
         resources: ResourceConfig {
             max_memory: 1024 * 1024 * 1024, // 1GB
@@ -48,6 +49,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
         delete_on_start: false,
         requested_tool_sandbox_type: None,
         image: None,
+        hardening: Default::default(),
     };
 
     let sandbox_id = manager.create_tool_sandbox(tool_config).await?;
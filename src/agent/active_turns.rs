@@ -0,0 +1,163 @@
+//! Active turns -- which agent turns are running right now, for operators.
+//!
+//! The agent loop registers every turn it starts together with its cancellation token. The web
+//! API (`GET /api/turns`, `DELETE /api/turns/{id}`) and `synbot turns` read this registry to show
+//! what each turn is doing (including the tool it is currently in) and to cancel a stuck one.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+
+/// A running turn as reported by the API.
+#[derive(Debug, Clone, Serialize)]
+pub struct TurnInfo {
+    pub id: String,
+    pub session_key: String,
+    pub channel: String,
+    pub chat_id: String,
+    pub started_at: DateTime<Utc>,
+    /// Tool being executed right now, `None` while waiting for the model.
+    pub current_tool: Option<String>,
+    pub elapsed_secs: u64,
+}
+
+struct Entry {
+    session_key: String,
+    channel: String,
+    chat_id: String,
+    started_at: DateTime<Utc>,
+    started: Instant,
+    current_tool: Option<String>,
+    token: CancellationToken,
+}
+
+impl Entry {
+    fn info(&self, id: &str) -> TurnInfo {
+        TurnInfo {
+            id: id.to_string(),
+            session_key: self.session_key.clone(),
+            channel: self.channel.clone(),
+            chat_id: self.chat_id.clone(),
+            started_at: self.started_at,
+            current_tool: self.current_tool.clone(),
+            elapsed_secs: self.started.elapsed().as_secs(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ActiveTurns {
+    next_id: AtomicU64,
+    turns: Mutex<HashMap<String, Entry>>,
+}
+
+impl ActiveTurns {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a turn for `channel:chat_id` and return its id. Call [`finish`](Self::finish) when it ends.
+    pub fn start(&self, channel: &str, chat_id: &str, token: CancellationToken) -> String {
+        let id = format!("turn-{}", self.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+        let entry = Entry {
+            session_key: format!("{}:{}", channel, chat_id),
+            channel: channel.to_string(),
+            chat_id: chat_id.to_string(),
+            started_at: Utc::now(),
+            started: Instant::now(),
+            current_tool: None,
+            token,
+        };
+        self.lock().insert(id.clone(), entry);
+        id
+    }
+
+    /// Forget a turn that has ended (no-op when it was already cancelled through [`cancel`](Self::cancel)).
+    pub fn finish(&self, id: &str) {
+        self.lock().remove(id);
+    }
+
+    /// Record the tool the turn of `session_key` is running (`None` when the tool returned).
+    pub fn set_current_tool(&self, session_key: &str, tool: Option<&str>) {
+        for entry in self.lock().values_mut().filter(|e| e.session_key == session_key) {
+            entry.current_tool = tool.map(str::to_string);
+        }
+    }
+
+    /// Running turns, oldest first.
+    pub fn list(&self) -> Vec<TurnInfo> {
+        let mut turns: Vec<TurnInfo> = self.lock().iter().map(|(id, e)| e.info(id)).collect();
+        turns.sort_by_key(|t| t.started_at);
+        turns
+    }
+
+    /// Cancel the turn `id` through its cancellation token. Returns the turn, or `None` when unknown.
+    pub fn cancel(&self, id: &str) -> Option<TurnInfo> {
+        let entry = self.lock().remove(id)?;
+        entry.token.cancel();
+        Some(entry.info(id))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Entry>> {
+        self.turns.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn running_turn_is_listed_and_can_be_killed() {
+        let turns = ActiveTurns::new();
+        let token = CancellationToken::new();
+        let watched = token.clone();
+        // Fake turn stuck in a tool until it is cancelled.
+        let turn = tokio::spawn(async move {
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(60)) => false,
+                _ = watched.cancelled() => true,
+            }
+        });
+        let id = turns.start("telegram", "chat-1", token);
+        turns.set_current_tool("telegram:chat-1", Some("exec"));
+
+        let listed = turns.list();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, id);
+        assert_eq!(listed[0].session_key, "telegram:chat-1");
+        assert_eq!(listed[0].current_tool.as_deref(), Some("exec"));
+
+        let killed = turns.cancel(&id).expect("turn should be known");
+        assert_eq!(killed.chat_id, "chat-1");
+        assert!(turn.await.unwrap(), "turn should observe the cancellation");
+        assert!(turns.list().is_empty());
+        assert!(turns.cancel(&id).is_none());
+        turns.finish(&id);
+    }
+
+    #[test]
+    fn tool_updates_only_touch_their_session() {
+        let turns = ActiveTurns::new();
+        let a = turns.start("telegram", "a", CancellationToken::new());
+        let b = turns.start("discord", "b", CancellationToken::new());
+        assert_ne!(a, b);
+        turns.set_current_tool("discord:b", Some("web_fetch"));
+        turns.set_current_tool("discord:b", None);
+        turns.set_current_tool("telegram:a", Some("read_file"));
+
+        let listed = turns.list();
+        let tool = |id: &str| listed.iter().find(|t| t.id == id).unwrap().current_tool.clone();
+        assert_eq!(tool(&a).as_deref(), Some("read_file"));
+        assert_eq!(tool(&b), None);
+
+        turns.finish(&a);
+        assert_eq!(turns.list().len(), 1);
+    }
+}
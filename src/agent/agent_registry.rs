@@ -10,7 +10,10 @@ use std::path::{Path, PathBuf};
 use anyhow::{Context, Result};
 
 use crate::agent::role_registry::RoleRegistry;
-use crate::config::{resolve_provider_max_tokens_cap, AgentConfig, MainAgent, ProvidersConfig};
+use crate::config::{
+    resolve_provider_max_tokens_cap, resolve_provider_tool_result_format, AgentConfig, MainAgent,
+    ProvidersConfig, ReasoningEffort, ToolResultFormat,
+};
 
 // ---------------------------------------------------------------------------
 // Resolved agent parameters
@@ -28,6 +31,42 @@ pub struct ResolvedAgentParams {
     pub max_consecutive_tool_errors: u32,
     /// Maximum number of chat history messages to send to the model (most recent N).
     pub max_chat_history_messages: u32,
+    /// Identical consecutive tool calls allowed before the run is stopped (0 = unlimited).
+    pub max_repeated_tool_calls: u32,
+    /// Extended-thinking request parameters and whether reasoning is posted to the channel.
+    pub reasoning: ReasoningSettings,
+    /// Shape of tool results sent back to the model (agent override, else the provider's).
+    pub tool_result_format: ToolResultFormat,
+    /// False for pure-chat agents (`tools_enabled: false`): no tools are offered to the model.
+    pub tools_enabled: bool,
+}
+
+/// Extended-thinking settings resolved for one agent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReasoningSettings {
+    /// Provider request parameters enabling reasoning (`None` = provider default, or unsupported).
+    pub request_params: Option<serde_json::Value>,
+    /// Post reasoning content to the channel; otherwise it is only logged.
+    pub show_in_channel: bool,
+}
+
+impl ReasoningSettings {
+    fn resolve(
+        provider: &str,
+        effort: Option<ReasoningEffort>,
+        budget: Option<u32>,
+        max_tokens: u32,
+        show_in_channel: bool,
+    ) -> Self {
+        let request_params = crate::rig_provider::reasoning_params(provider, effort, budget, max_tokens);
+        if request_params.is_none() && (effort.is_some() || budget.is_some()) {
+            tracing::debug!(provider = %provider, "reasoningEffort/thinkingBudget not supported for this provider; ignoring");
+        }
+        Self {
+            request_params,
+            show_in_channel,
+        }
+    }
 }
 
 fn apply_max_tokens_cap(requested: u32, cap: Option<u32>) -> u32 {
@@ -40,33 +79,56 @@ fn apply_max_tokens_cap(requested: u32, cap: Option<u32>) -> u32 {
 impl ResolvedAgentParams {
     /// Params for the implicit main agent (all from MainAgent).
     pub fn from_main_defaults(main_agent: &MainAgent, max_tokens_cap: Option<u32>) -> Self {
+        let max_tokens = apply_max_tokens_cap(main_agent.max_tokens, max_tokens_cap);
         Self {
             provider: main_agent.provider.clone(),
             model: main_agent.model.clone(),
-            max_tokens: apply_max_tokens_cap(main_agent.max_tokens, max_tokens_cap),
+            max_tokens,
             temperature: main_agent.temperature,
             max_iterations: main_agent.max_tool_iterations,
             max_consecutive_tool_errors: main_agent.max_consecutive_tool_errors,
             max_chat_history_messages: main_agent.max_chat_history_messages,
+            max_repeated_tool_calls: main_agent.max_repeated_tool_calls,
+            reasoning: ReasoningSettings::resolve(
+                &main_agent.provider,
+                main_agent.reasoning_effort,
+                main_agent.thinking_budget,
+                max_tokens,
+                main_agent.show_reasoning,
+            ),
+            tool_result_format: ToolResultFormat::default(),
+            tools_enabled: true,
         }
     }
 
     pub fn from_config(agent: &AgentConfig, defaults: &MainAgent, max_tokens_cap: Option<u32>) -> Self {
-        let max_tokens = agent.max_tokens.unwrap_or(defaults.max_tokens);
+        let max_tokens = apply_max_tokens_cap(agent.max_tokens.unwrap_or(defaults.max_tokens), max_tokens_cap);
+        let provider = agent
+            .provider
+            .clone()
+            .unwrap_or_else(|| defaults.provider.clone());
+        let reasoning = ReasoningSettings::resolve(
+            &provider,
+            agent.reasoning_effort.or(defaults.reasoning_effort),
+            agent.thinking_budget.or(defaults.thinking_budget),
+            max_tokens,
+            defaults.show_reasoning,
+        );
         Self {
-            provider: agent
-                .provider
-                .clone()
-                .unwrap_or_else(|| defaults.provider.clone()),
+            provider,
             model: agent
                 .model
                 .clone()
                 .unwrap_or_else(|| defaults.model.clone()),
-            max_tokens: apply_max_tokens_cap(max_tokens, max_tokens_cap),
+            max_tokens,
             temperature: agent.temperature.unwrap_or(defaults.temperature),
             max_iterations: agent.max_iterations.unwrap_or(defaults.max_tool_iterations),
             max_consecutive_tool_errors: defaults.max_consecutive_tool_errors,
             max_chat_history_messages: defaults.max_chat_history_messages,
+            max_repeated_tool_calls: defaults.max_repeated_tool_calls,
+            reasoning,
+            tool_result_format: agent.tool_result_format.unwrap_or_default(),
+            tools_enabled: agent.tools_enabled,
         }
     }
 }
@@ -94,12 +156,18 @@ pub struct AgentContext {
 /// Registry that manages all configured agents (runtime entities referencing roles).
 pub struct AgentRegistry {
     agents: HashMap<String, AgentContext>,
+    /// `mainAgent.defaultRole`: fallback for messages whose channel names no agent.
+    default_role: Option<String>,
+    /// `mainAgent.languageRoles`: role per detected message language (ISO 639-3).
+    language_roles: HashMap<String, String>,
 }
 
 impl AgentRegistry {
     pub fn new() -> Self {
         Self {
             agents: HashMap::new(),
+            default_role: None,
+            language_roles: HashMap::new(),
         }
     }
 
@@ -119,7 +187,9 @@ impl AgentRegistry {
         })?;
         let system_prompt = role_ctx.system_prompt.clone();
         let cap_main = resolve_provider_max_tokens_cap(providers, &main_agent.provider);
-        let params = ResolvedAgentParams::from_main_defaults(main_agent, cap_main);
+        let mut params = ResolvedAgentParams::from_main_defaults(main_agent, cap_main);
+        params.tool_result_format =
+            resolve_provider_tool_result_format(providers, &main_agent.provider).unwrap_or_default();
         let ctx = AgentContext {
             name: "main".to_string(),
             role_name: "main".to_string(),
@@ -145,7 +215,11 @@ impl AgentRegistry {
                 .as_ref()
                 .unwrap_or(&main_agent.provider);
             let cap = resolve_provider_max_tokens_cap(providers, provider_for_cap);
-            let params = ResolvedAgentParams::from_config(agent, main_agent, cap);
+            let mut params = ResolvedAgentParams::from_config(agent, main_agent, cap);
+            if agent.tool_result_format.is_none() {
+                params.tool_result_format =
+                    resolve_provider_tool_result_format(providers, provider_for_cap).unwrap_or_default();
+            }
             let ctx = AgentContext {
                 name: agent.name.clone(),
                 role_name: agent.role.clone(),
@@ -157,6 +231,8 @@ impl AgentRegistry {
             };
             self.agents.insert(agent.name.clone(), ctx);
         }
+        self.default_role = main_agent.default_role.clone();
+        self.language_roles = main_agent.language_roles.clone();
         Ok(())
     }
 
@@ -174,6 +250,116 @@ impl AgentRegistry {
     pub fn contains(&self, name: &str) -> bool {
         self.agents.contains_key(name)
     }
+
+    /// Name of the agent that runs `role`: "main" for role "main", otherwise the first agent
+    /// (by name) configured with that role.
+    pub fn agent_for_role(&self, role: &str) -> Option<&str> {
+        if role == "main" {
+            return self.agents.get("main").map(|a| a.name.as_str());
+        }
+        self.agents
+            .values()
+            .filter(|a| a.role_name == role)
+            .map(|a| a.name.as_str())
+            .min()
+    }
+
+    /// Agent running the role mapped to the detected language of `text`, if any.
+    fn agent_for_language(&self, text: Option<&str>) -> Option<&str> {
+        if self.language_roles.is_empty() {
+            return None;
+        }
+        let lang = crate::agent::language::detect_language(text?)?;
+        let role = self.language_roles.get(lang)?;
+        let agent = self.agent_for_role(role);
+        if agent.is_none() {
+            tracing::warn!(language = %lang, role = %role, "No agent runs the role mapped to this language; using the default role");
+        }
+        agent
+    }
+
+    /// Whether `mainAgent.languageRoles` routes messages by their detected language.
+    pub fn routes_by_language(&self) -> bool {
+        !self.language_roles.is_empty()
+    }
+
+    /// Whether language routing can hand a message to `agent`: an agent running a
+    /// `languageRoles` role, the `defaultRole` agent, or "main".
+    pub fn is_language_routed(&self, agent: &str) -> bool {
+        if agent == "main" {
+            return true;
+        }
+        self.agents.get(agent).is_some_and(|a| {
+            self.language_roles.values().any(|role| *role == a.role_name)
+                || self.default_role.as_deref() == Some(a.role_name.as_str())
+        })
+    }
+
+    /// Agent that handles a message. In order of precedence:
+    /// 1. the agent running `task_role` (heartbeat / cron tasks with a `role`);
+    /// 2. the agent running `channel_role` (the channel's `role` override);
+    /// 3. `channel_agent` (the channel's `defaultAgent`) when it names a registered agent other than
+    ///    "main" — every channel defaults to "main", so that value does not pin the agent;
+    /// 4. the agent running the `mainAgent.languageRoles` entry for the language of `text`;
+    /// 5. the agent running `mainAgent.defaultRole`;
+    /// 6. "main".
+    pub fn resolve_agent(
+        &self,
+        task_role: Option<&str>,
+        channel_role: Option<&str>,
+        channel_agent: Option<&str>,
+        text: Option<&str>,
+    ) -> &str {
+        self.resolve_conversation_agent(task_role, channel_role, channel_agent, None, text)
+    }
+
+    /// Like [`resolve_agent`](Self::resolve_agent), for a message in a conversation whose latest
+    /// session belongs to `session_agent`. When that agent is one language routing can pick, it
+    /// keeps the conversation instead of step 4-6, so the language is detected only on the first
+    /// message and a conversation that switches language keeps its session.
+    pub fn resolve_conversation_agent(
+        &self,
+        task_role: Option<&str>,
+        channel_role: Option<&str>,
+        channel_agent: Option<&str>,
+        session_agent: Option<&str>,
+        text: Option<&str>,
+    ) -> &str {
+        if let Some(role) = task_role {
+            match self.agent_for_role(role) {
+                Some(agent) => return agent,
+                None => tracing::warn!(role = %role, "No agent runs this task role; using the default agent"),
+            }
+        }
+        if let Some(role) = channel_role {
+            match self.agent_for_role(role) {
+                Some(agent) => return agent,
+                None => tracing::warn!(role = %role, "No agent runs this channel role; using the default agent"),
+            }
+        }
+        let channel_agent = channel_agent
+            .map(str::trim)
+            .filter(|id| !id.is_empty() && *id != "main");
+        if let Some(id) = channel_agent {
+            match self.agents.get_key_value(id) {
+                Some((name, _)) => return name,
+                None => tracing::warn!(agent = %id, "Channel defaultAgent is not a configured agent; using the default role"),
+            }
+        }
+        if let Some((name, _)) = session_agent
+            .filter(|id| self.is_language_routed(id))
+            .and_then(|id| self.agents.get_key_value(id))
+        {
+            return name;
+        }
+        if let Some(agent) = self.agent_for_language(text) {
+            return agent;
+        }
+        self.default_role
+            .as_deref()
+            .and_then(|role| self.agent_for_role(role))
+            .unwrap_or("main")
+    }
 }
 
 #[cfg(test)]
@@ -192,8 +378,12 @@ mod tests {
             max_tokens: None,
             temperature: None,
             max_iterations: None,
+            reasoning_effort: None,
+            thinking_budget: None,
             skills: Vec::new(),
             tools: Vec::new(),
+            tool_result_format: None,
+            tools_enabled: true,
         }
     }
 
@@ -207,8 +397,15 @@ mod tests {
             max_tool_iterations: 10,
             max_consecutive_tool_errors: 8,
             max_chat_history_messages: 20,
+            max_repeated_tool_calls: 3,
             max_concurrent_subagents: 3,
             subagent_task_timeout_secs: 600,
+            max_concurrent_turns: 4,
+            reasoning_effort: None,
+            thinking_budget: None,
+            show_reasoning: false,
+            default_role: None,
+            language_roles: HashMap::new(),
             agents: Vec::new(),
         }
     }
@@ -300,4 +497,185 @@ mod tests {
         let ctx = agent_reg.get("main").unwrap();
         assert_eq!(ctx.params.max_tokens, 196608);
     }
+
+    #[test]
+    fn tool_result_format_comes_from_provider_unless_agent_overrides() {
+        let tmp = TempDir::new().unwrap();
+        let roles_dir = TempDir::new().unwrap();
+        for role in ["main", "dev"] {
+            std::fs::create_dir_all(roles_dir.path().join(role)).unwrap();
+            std::fs::write(roles_dir.path().join(role).join("AGENTS.md"), "# Role").unwrap();
+            std::fs::write(roles_dir.path().join(role).join("SOUL.md"), "").unwrap();
+            std::fs::write(roles_dir.path().join(role).join("TOOLS.md"), "").unwrap();
+        }
+        let mut role_reg = RoleRegistry::new();
+        role_reg.load_from_dirs(roles_dir.path()).unwrap();
+
+        let mut main_agent = test_defaults();
+        main_agent.provider = "deepseek".into();
+        let mut dev = make_agent("dev", "dev");
+        dev.tool_result_format = Some(ToolResultFormat::Typed);
+        let mut plain = make_agent("plain", "dev");
+        plain.provider = Some("openai".into());
+        main_agent.agents = vec![dev, plain];
+        let providers = ProvidersConfig {
+            deepseek: ProviderEntry {
+                tool_result_format: Some(ToolResultFormat::Json),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let mut agent_reg = AgentRegistry::new();
+        agent_reg
+            .load_from_config(&main_agent, &providers, &role_reg, tmp.path())
+            .unwrap();
+
+        assert_eq!(agent_reg.get("main").unwrap().params.tool_result_format, ToolResultFormat::Json);
+        assert_eq!(agent_reg.get("dev").unwrap().params.tool_result_format, ToolResultFormat::Typed);
+        assert_eq!(agent_reg.get("plain").unwrap().params.tool_result_format, ToolResultFormat::Text);
+    }
+
+    #[test]
+    fn resolution_falls_through_channel_agent_to_default_role_then_main() {
+        let tmp = TempDir::new().unwrap();
+        let roles_dir = TempDir::new().unwrap();
+        for role in ["main", "dev", "support"] {
+            std::fs::create_dir_all(roles_dir.path().join(role)).unwrap();
+            std::fs::write(roles_dir.path().join(role).join("AGENTS.md"), "# Role").unwrap();
+        }
+        let mut role_reg = RoleRegistry::new();
+        role_reg.load_from_dirs(roles_dir.path()).unwrap();
+
+        let mut main_agent = test_defaults();
+        main_agent.agents = vec![make_agent("dev", "dev"), make_agent("helpdesk", "support")];
+        let load = |main_agent: &MainAgent| {
+            let mut agent_reg = AgentRegistry::new();
+            agent_reg
+                .load_from_config(main_agent, &ProvidersConfig::default(), &role_reg, tmp.path())
+                .unwrap();
+            agent_reg
+        };
+
+        let no_default = load(&main_agent);
+        assert_eq!(no_default.resolve_agent(None, None, None, None), "main");
+        assert_eq!(no_default.resolve_agent(None, None, Some("unknown"), None), "main");
+        assert_eq!(no_default.resolve_agent(None, None, Some("dev"), None), "dev");
+
+        main_agent.default_role = Some("support".into());
+        let with_default = load(&main_agent);
+        assert_eq!(with_default.resolve_agent(None, None, None, None), "helpdesk");
+        assert_eq!(with_default.resolve_agent(None, None, Some(" "), None), "helpdesk");
+        assert_eq!(with_default.resolve_agent(None, None, Some("unknown"), None), "helpdesk");
+        // An explicit channel mapping and a task role both win over the default role.
+        assert_eq!(with_default.resolve_agent(None, None, Some("dev"), None), "dev");
+        assert_eq!(with_default.resolve_agent(Some("dev"), None, Some("helpdesk"), None), "dev");
+        assert_eq!(with_default.resolve_agent(Some("finance"), None, None, None), "helpdesk");
+        // The channel's role override pins the agent, "main" included.
+        assert_eq!(with_default.resolve_agent(None, Some("main"), None, None), "main");
+        assert_eq!(with_default.resolve_agent(None, Some("dev"), Some("helpdesk"), None), "dev");
+        assert_eq!(with_default.resolve_agent(Some("support"), Some("dev"), None, None), "helpdesk");
+    }
+
+    #[test]
+    fn default_role_applies_to_channel_messages_with_implicit_main_agent() {
+        let tmp = TempDir::new().unwrap();
+        let roles_dir = TempDir::new().unwrap();
+        for role in ["main", "support"] {
+            std::fs::create_dir_all(roles_dir.path().join(role)).unwrap();
+            std::fs::write(roles_dir.path().join(role).join("AGENTS.md"), "# Role").unwrap();
+        }
+        let mut role_reg = RoleRegistry::new();
+        role_reg.load_from_dirs(roles_dir.path()).unwrap();
+
+        let mut main_agent = test_defaults();
+        main_agent.agents = vec![make_agent("helpdesk", "support")];
+        main_agent.default_role = Some("support".into());
+        let mut agent_reg = AgentRegistry::new();
+        agent_reg
+            .load_from_config(&main_agent, &ProvidersConfig::default(), &role_reg, tmp.path())
+            .unwrap();
+
+        // Channels without `defaultAgent` put "main" in the inbound metadata.
+        let telegram: crate::config::TelegramConfig = serde_json::from_value(serde_json::json!({})).unwrap();
+        let metadata = serde_json::json!({ "default_agent": telegram.default_agent });
+        let channel_agent = metadata.get("default_agent").and_then(|v| v.as_str());
+        assert_eq!(channel_agent, Some("main"));
+        assert_eq!(agent_reg.resolve_agent(None, None, channel_agent, Some("hello")), "helpdesk");
+    }
+
+    #[test]
+    fn language_roles_route_detected_language_and_fall_back_to_default_role() {
+        let tmp = TempDir::new().unwrap();
+        let roles_dir = TempDir::new().unwrap();
+        for role in ["main", "zh_support", "support"] {
+            std::fs::create_dir_all(roles_dir.path().join(role)).unwrap();
+            std::fs::write(roles_dir.path().join(role).join("AGENTS.md"), "# Role").unwrap();
+        }
+        let mut role_reg = RoleRegistry::new();
+        role_reg.load_from_dirs(roles_dir.path()).unwrap();
+
+        let mut main_agent = test_defaults();
+        main_agent.agents = vec![make_agent("xiaozhu", "zh_support"), make_agent("helpdesk", "support")];
+        main_agent.default_role = Some("support".into());
+        main_agent.language_roles = HashMap::from([("cmn".to_string(), "zh_support".to_string())]);
+        let mut agent_reg = AgentRegistry::new();
+        agent_reg
+            .load_from_config(&main_agent, &ProvidersConfig::default(), &role_reg, tmp.path())
+            .unwrap();
+
+        let chinese = "请帮我总结一下今天的会议记录和待办事项";
+        assert_eq!(agent_reg.resolve_agent(None, None, None, Some(chinese)), "xiaozhu");
+        // Unmapped (or undetectable) languages use the default role.
+        assert_eq!(
+            agent_reg.resolve_agent(None, None, None, Some("今日の会議の議事録をまとめてください")),
+            "helpdesk"
+        );
+        assert_eq!(agent_reg.resolve_agent(None, None, None, Some("ok")), "helpdesk");
+        // Channel messages carry the implicit "main" defaultAgent, which does not bypass language routing.
+        assert_eq!(agent_reg.resolve_agent(None, None, Some("main"), Some(chinese)), "xiaozhu");
+        assert_eq!(agent_reg.resolve_agent(None, None, Some("main"), Some("ok")), "helpdesk");
+        // The channel's role override and a task role still take precedence.
+        assert_eq!(agent_reg.resolve_agent(None, Some("main"), Some("main"), Some(chinese)), "main");
+        assert_eq!(agent_reg.resolve_agent(Some("support"), None, None, Some(chinese)), "helpdesk");
+
+        // A conversation that already has a session keeps its agent when the language changes.
+        assert_eq!(
+            agent_reg.resolve_conversation_agent(None, None, Some("main"), Some("xiaozhu"), Some("summarize the notes")),
+            "xiaozhu"
+        );
+        assert_eq!(
+            agent_reg.resolve_conversation_agent(None, None, None, Some("helpdesk"), Some(chinese)),
+            "helpdesk"
+        );
+        assert_eq!(agent_reg.resolve_conversation_agent(None, None, None, Some("main"), Some(chinese)), "main");
+        // Agents language routing never picks (e.g. reached by an @mention) do not pin the conversation.
+        assert!(!agent_reg.is_language_routed("unknown"));
+        assert_eq!(agent_reg.resolve_conversation_agent(None, None, None, Some("unknown"), Some(chinese)), "xiaozhu");
+    }
+
+    #[test]
+    fn reasoning_options_resolve_from_defaults_and_agent_overrides() {
+        let mut defaults = test_defaults();
+        defaults.provider = "anthropic".into();
+        defaults.reasoning_effort = Some(ReasoningEffort::Low);
+        defaults.show_reasoning = true;
+
+        let main = ResolvedAgentParams::from_main_defaults(&defaults, None);
+        let params = main.reasoning.request_params.expect("anthropic supports thinking");
+        assert_eq!(params["thinking"]["budget_tokens"], 1024);
+        assert!(main.reasoning.show_in_channel);
+
+        let mut agent = make_agent("dev", "dev");
+        agent.thinking_budget = Some(3000);
+        let dev = ResolvedAgentParams::from_config(&agent, &defaults, None);
+        assert_eq!(dev.reasoning.request_params.unwrap()["thinking"]["budget_tokens"], 3000);
+
+        agent.provider = Some("ollama".into());
+        let unsupported = ResolvedAgentParams::from_config(&agent, &defaults, None);
+        assert_eq!(unsupported.reasoning.request_params, None);
+
+        let plain = ResolvedAgentParams::from_main_defaults(&test_defaults(), None);
+        assert_eq!(plain.reasoning, ReasoningSettings::default());
+    }
 }
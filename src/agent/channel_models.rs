@@ -0,0 +1,66 @@
+//! Completion models for channel `provider`/`model` overrides, shared by the agent loop and
+//! tools that call the model on behalf of a conversation (e.g. `summarize_conversation`).
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tracing::{info, warn};
+
+use crate::config::{self, Config};
+use crate::rig_provider::SynbotCompletionModel;
+
+pub struct ChannelModels {
+    default: Arc<dyn SynbotCompletionModel>,
+    /// Models built for overrides, by (provider, model).
+    built: Mutex<HashMap<(String, String), Arc<dyn SynbotCompletionModel>>>,
+}
+
+impl ChannelModels {
+    /// Use `default` for channels without a `model` override.
+    pub fn new(default: Arc<dyn SynbotCompletionModel>) -> Self {
+        Self {
+            default,
+            built: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Completion model for a turn on `channel`: the channel's `model` override (with its
+    /// `provider`, else `agent_provider`), built once and reused; otherwise the default model.
+    /// A model that cannot be built is logged and the default is used.
+    pub fn for_channel(
+        &self,
+        cfg: &Config,
+        channel: &str,
+        agent_provider: &str,
+    ) -> Arc<dyn SynbotCompletionModel> {
+        let binding = cfg.channels.model_binding(channel);
+        let Some(model_name) = binding.model.filter(|m| !m.trim().is_empty()) else {
+            return Arc::clone(&self.default);
+        };
+        let provider = binding.provider.unwrap_or_else(|| agent_provider.to_string());
+        let key = (provider, model_name);
+        let mut built = self.built.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(model) = built.get(&key) {
+            return Arc::clone(model);
+        }
+        let (provider, model_name) = &key;
+        let (api_key, api_base) = config::resolve_provider(cfg, provider);
+        match crate::cli::helpers::build_rig_completion_model(
+            cfg,
+            provider,
+            model_name,
+            &api_key,
+            api_base.as_deref(),
+        ) {
+            Ok(model) => {
+                info!(channel = %channel, provider = %provider, model = %model_name, "Using channel model override");
+                built.insert(key.clone(), Arc::clone(&model));
+                model
+            }
+            Err(e) => {
+                warn!(channel = %channel, provider = %provider, model = %model_name, error = %e, "Channel model override unavailable; using the default model");
+                Arc::clone(&self.default)
+            }
+        }
+    }
+}
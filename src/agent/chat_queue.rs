@@ -0,0 +1,104 @@
+//! Chat queue -- keeps messages of one chat in arrival order while a turn is running.
+//!
+//! The agent loop runs at most one turn per session. Messages that arrive for a session
+//! whose turn is still in flight are parked here and dispatched one by one as each turn
+//! ends, so replies come back in the order the user sent them. Other sessions are not
+//! affected and keep running in parallel (bounded by the [`TurnLimiter`](super::turn_limiter::TurnLimiter)).
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::bus::InboundMessage;
+
+/// Messages queued per session before new ones are rejected.
+pub const MAX_QUEUED_PER_CHAT: usize = 20;
+
+pub struct ChatQueue {
+    max_per_chat: usize,
+    pending: HashMap<String, VecDeque<InboundMessage>>,
+}
+
+impl Default for ChatQueue {
+    fn default() -> Self {
+        Self::new(MAX_QUEUED_PER_CHAT)
+    }
+}
+
+impl ChatQueue {
+    /// Create a queue holding at most `max_per_chat` waiting messages per session (0 is treated as 1).
+    pub fn new(max_per_chat: usize) -> Self {
+        Self {
+            max_per_chat: max_per_chat.max(1),
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Append `msg` behind the messages already waiting for `session_key`.
+    /// Returns the new queue length, or the message back (boxed) when the session's queue is full.
+    pub fn push(&mut self, session_key: &str, msg: InboundMessage) -> Result<usize, Box<InboundMessage>> {
+        let queue = self.pending.entry(session_key.to_string()).or_default();
+        if queue.len() >= self.max_per_chat {
+            return Err(Box::new(msg));
+        }
+        queue.push_back(msg);
+        Ok(queue.len())
+    }
+
+    /// Take the oldest waiting message for `session_key`.
+    pub fn pop(&mut self, session_key: &str) -> Option<InboundMessage> {
+        let queue = self.pending.get_mut(session_key)?;
+        let msg = queue.pop_front();
+        if queue.is_empty() {
+            self.pending.remove(session_key);
+        }
+        msg
+    }
+
+    /// Drop every waiting message for `session_key` and return the dropped messages.
+    pub fn clear(&mut self, session_key: &str) -> Vec<InboundMessage> {
+        self.pending
+            .remove(session_key)
+            .map(Vec::from)
+            .unwrap_or_default()
+    }
+
+    /// Number of messages waiting for `session_key`.
+    pub fn len(&self, session_key: &str) -> usize {
+        self.pending.get(session_key).map(|q| q.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(chat_id: &str, content: &str) -> InboundMessage {
+        InboundMessage {
+            channel: "telegram".to_string(),
+            sender_id: "u1".to_string(),
+            chat_id: chat_id.to_string(),
+            content: content.to_string(),
+            timestamp: chrono::Utc::now(),
+            media: vec![],
+            metadata: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn full_queue_hands_message_back() {
+        let mut queue = ChatQueue::new(2);
+        assert_eq!(queue.push("telegram:c1", msg("c1", "a")).unwrap(), 1);
+        assert_eq!(queue.push("telegram:c1", msg("c1", "b")).unwrap(), 2);
+        let rejected = queue.push("telegram:c1", msg("c1", "c")).unwrap_err();
+        assert_eq!(rejected.content, "c");
+        assert_eq!(queue.push("telegram:c2", msg("c2", "d")).unwrap(), 1);
+
+        assert_eq!(queue.clear("telegram:c1").len(), 2);
+        assert_eq!(queue.len("telegram:c1"), 0);
+        assert_eq!(queue.pop("telegram:c2").unwrap().content, "d");
+        assert!(queue.is_empty());
+    }
+}
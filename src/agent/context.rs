@@ -7,7 +7,7 @@ use std::sync::Arc;
 
 use crate::agent::memory::MemoryStore;
 #[cfg(feature = "memory-index")]
-use crate::agent::memory_backend::{FileSqliteMemoryBackend, MemoryBackend, MemoryContextOptions};
+use crate::agent::memory_backend::{memory_backend_for, MemoryContextOptions};
 #[cfg(feature = "memory-index")]
 use crate::config::Config;
 use crate::agent::skills::{CompositeSkillProvider, SkillProvider};
@@ -24,6 +24,8 @@ pub struct ContextBuilder {
     full_config: Option<Arc<Config>>,
     skills: CompositeSkillProvider,
     tool_sandbox_exec_kind: Option<ToolSandboxExecKind>,
+    /// Workspace mount inside a Docker tool sandbox.
+    tool_sandbox_workspace: String,
 }
 
 #[cfg(feature = "memory-index")]
@@ -48,6 +50,7 @@ impl ContextBuilder {
             skills: CompositeSkillProvider::default_with_fs(skills_dir),
             agent_id,
             tool_sandbox_exec_kind,
+            tool_sandbox_workspace: crate::sandbox::types::DEFAULT_CONTAINER_WORKSPACE.to_string(),
         }
     }
 }
@@ -71,11 +74,18 @@ impl ContextBuilder {
             skills: CompositeSkillProvider::default_with_fs(skills_dir),
             agent_id,
             tool_sandbox_exec_kind,
+            tool_sandbox_workspace: crate::sandbox::types::DEFAULT_CONTAINER_WORKSPACE.to_string(),
         }
     }
 }
 
 impl ContextBuilder {
+    /// Container path of the workspace in a Docker tool sandbox (`toolSandbox.workspaceMountPath`).
+    pub fn with_tool_sandbox_workspace(mut self, dir: impl Into<String>) -> Self {
+        self.tool_sandbox_workspace = dir.into();
+        self
+    }
+
     /// Build the full system prompt (identity + bootstrap from workspace + memory + skills).
     pub fn build_system_prompt(&self) -> String {
         self.build_system_prompt_with_role_prompt(&self.load_bootstrap_files(), None)
@@ -116,7 +126,7 @@ impl ContextBuilder {
         #[cfg(feature = "memory-index")]
         {
             if let Some(ref cfg) = self.full_config {
-                let backend = FileSqliteMemoryBackend::new(Arc::clone(cfg));
+                let backend = memory_backend_for(Arc::clone(cfg));
                 let q = memory_query.map(|s| s.chars().take(512).collect::<String>());
                 let opts = MemoryContextOptions {
                     recent_days: cfg.memory.recent_days.max(1),
@@ -135,6 +145,7 @@ impl ContextBuilder {
     fn identity_section(&self) -> String {
         let now = Local::now().format("%Y-%m-%d %H:%M (%A)");
         let ws = self.workspace.display();
+        let mount = &self.tool_sandbox_workspace;
         let in_app_sandbox = std::env::var_os("SYNBOT_IN_APP_SANDBOX").is_some();
 
         let tool_sandbox_active = self.tool_sandbox_exec_kind.is_some();
@@ -148,11 +159,12 @@ impl ContextBuilder {
             }
             if let Some(kind) = self.tool_sandbox_exec_kind {
                 let exec_hint = match kind {
-                    ToolSandboxExecKind::Docker => "- The **exec** tool runs inside the **tool sandbox** (Docker). \
-                     **read_file** / **write_file** / **list_dir** (and related file tools) run in the **main process** on the host; \
-                     they are scoped to the workspace per config (`tools.exec.restrictToWorkspace`). \
-                     For **exec** in the container, use paths under `/workspace` (see Workspace below)."
-                        .to_string(),
+                    ToolSandboxExecKind::Docker => format!(
+                        "- The **exec** tool runs inside the **tool sandbox** (Docker). \
+                         **read_file** / **write_file** / **list_dir** run in the same container, so they see the files exec creates; \
+                         they are scoped to the workspace per config (`tools.exec.restrictToWorkspace`). \
+                         Use paths under `{mount}` in the container (see Workspace below)."
+                    ),
                     ToolSandboxExecKind::HostNative => "- The **exec** tool runs inside the **tool sandbox** (host-native isolation: \
                      Windows AppContainer, macOS Seatbelt, etc.). \
                      File tools run in the **main process** with the same host workspace path (scoped per `tools.exec.restrictToWorkspace`). \
@@ -170,8 +182,8 @@ impl ContextBuilder {
             Some(ToolSandboxExecKind::Docker) => format!(
                 "## Workspace\n\
                  - **Workspace (host, for reference):** {ws}\n\
-                 - **Workspace inside tool sandbox (use this for exec paths):** `/workspace`\n\n\
-                 When using the **exec** tool, use paths under `/workspace` (e.g. `/workspace/README.md`, `cd /workspace`).\n"
+                 - **Workspace inside tool sandbox (use this for exec paths):** `{mount}`\n\n\
+                 When using the **exec** tool, use paths under `{mount}` (e.g. `{mount}/README.md`, `cd {mount}`).\n"
             ),
             Some(ToolSandboxExecKind::HostNative) => format!(
                 "## Workspace\n\
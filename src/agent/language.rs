@@ -0,0 +1,30 @@
+//! Language detection of inbound messages, for `mainAgent.languageRoles`.
+//!
+//! Detection uses [`whatlang`] and reports ISO 639-3 codes (`eng`, `cmn`, `spa`, `jpn`, ...).
+//! Short or mixed texts give no answer rather than a guess, so they keep the default routing.
+
+/// Texts shorter than this (in characters) are not detected: "ok" or "hi" say little about a language.
+const MIN_DETECT_CHARS: usize = 8;
+
+/// ISO 639-3 code of the language `text` is written in, when the detection is reliable.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let text = text.trim();
+    if text.chars().count() < MIN_DETECT_CHARS {
+        return None;
+    }
+    let info = whatlang::detect(text)?;
+    info.is_reliable().then(|| info.lang().code())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_script_languages_and_skips_short_text() {
+        assert_eq!(detect_language("请帮我总结一下今天的会议记录和待办事项"), Some("cmn"));
+        assert_eq!(detect_language("今日の会議の議事録をまとめてください"), Some("jpn"));
+        assert_eq!(detect_language("ok"), None);
+        assert_eq!(detect_language("   "), None);
+    }
+}
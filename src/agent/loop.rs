@@ -6,23 +6,31 @@ use rig::message::{AssistantContent, Message, ToolResultContent, UserContent};
 use rig::OneOrMany;
 use crate::rig_provider::SynbotCompletionModel;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
 use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn, Instrument};
 
-use crate::agent::agent_registry::AgentRegistry;
+use crate::agent::active_turns::ActiveTurns;
+use crate::agent::agent_registry::{AgentRegistry, ReasoningSettings};
+use crate::agent::channel_models::ChannelModels;
+use crate::agent::chat_queue::ChatQueue;
 use crate::agent::context::ContextBuilder;
 use crate::agent::directive::DirectiveParser;
+use crate::agent::session_manager::LINKED_SESSION_CHANNEL;
 use crate::agent::session_state::SharedSessionState;
 use crate::agent::subagent::{SubagentManager, SubagentStatus};
+use crate::agent::turn_limiter::TurnLimiter;
 use crate::bus::{InboundMessage, OutboundMessage};
-use crate::config::{self, Config};
+use crate::channels::REPLY_TO_META;
+use crate::config::{self, ChannelCommandAction, Config, ToolResultFormat};
+use crate::cron::config_runner::{CronRunTracker, CRON_TASK_META};
 use crate::sandbox::types::ToolSandboxExecKind;
 use crate::hooks::{HookEvent, HookRegistry};
-use crate::tools::{scope, ToolContext, ToolRegistry};
+use crate::tools::truncation::{smart_truncate, truncate_chars_with, TruncationMarker};
+use crate::tools::{format_tool_result, scope, ToolContext, ToolRegistry};
 use crate::agent::control_commands::{
     busy_hint_commands, parse_control_command, slash_commands_help_text, ControlCommand,
 };
@@ -43,6 +51,212 @@ fn apply_sender_display_prefix(content: &str, metadata: &serde_json::Value) -> S
     }
 }
 
+/// Longest quoted reply kept in the user turn; a reply to a huge message should not crowd the context.
+const REPLY_CONTEXT_MAX_CHARS: usize = 2000;
+
+/// Metadata key holding the channel's `role` override, set when the message enters the loop.
+const CHANNEL_ROLE_META: &str = "channel_role";
+
+/// Appended to the system prompt when the agent or channel has `toolsEnabled: false`.
+const TOOLS_DISABLED_PROMPT: &str =
+    "\n\n## Tools\n\nTools are disabled in this conversation. Answer from your own knowledge and do not call any tool.";
+
+/// Tool result returned for a call the model makes although no tools were offered.
+const TOOLS_DISABLED_RESULT: &str =
+    "Tools are disabled in this conversation; the call was not run. Answer the user without tools.";
+
+/// Prepend the replied-to message (`metadata.reply_to`, set by channels) as a quote block so
+/// requests like "summarize this" carry their subject.
+fn apply_reply_context(content: &str, metadata: &serde_json::Value) -> String {
+    let Some(reply_to) = metadata.get(REPLY_TO_META) else {
+        return content.to_string();
+    };
+    let quoted = match reply_to.get("content").and_then(|v| v.as_str()).map(str::trim) {
+        Some(q) if !q.is_empty() => q,
+        _ => return content.to_string(),
+    };
+    let quoted = truncate_chars_with(quoted, REPLY_CONTEXT_MAX_CHARS, &TruncationMarker::ELLIPSIS);
+    let header = match reply_to.get("sender_id").and_then(|v| v.as_str()) {
+        Some(sender) if !sender.is_empty() => format!("[Replying to a message from {sender}]"),
+        _ => "[Replying to a message]".to_string(),
+    };
+    let body: String = quoted.lines().map(|line| format!("> {line}\n")).collect();
+    format!("{header}\n{body}\n{content}")
+}
+
+/// ID of the inbound message (`metadata.message_id`). Replies to it carry it as `reply_to` so
+/// channels can tell which message a reply answers (Discord completes slash commands with it).
+fn inbound_message_id(msg: &InboundMessage) -> Option<String> {
+    match msg.metadata.get("message_id")? {
+        serde_json::Value::String(id) => Some(id.clone()),
+        serde_json::Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+/// Whether `msg` may start a turn. Channels set `metadata.trigger_agent` to false for messages
+/// that are only kept in history (e.g. senders not in the allowlist, group messages not @bot).
+fn triggers_agent(msg: &InboundMessage) -> bool {
+    msg.metadata
+        .get("trigger_agent")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true)
+}
+
+/// Report a failed agent run for a background-originated message. Heartbeat tasks follow their
+/// `on_error` setting (carried in `metadata.on_error`, with the heartbeat's alert chat in
+/// `metadata.alert_channel` / `alert_chat_id`); other messages are only logged.
+fn report_run_error(
+    outbound_tx: &broadcast::Sender<OutboundMessage>,
+    msg: &InboundMessage,
+    err: &anyhow::Error,
+) {
+    if msg.metadata.get("source").and_then(|v| v.as_str()) != Some("heartbeat") {
+        tracing::debug!("Agent run ended (cancelled or error): {}", err);
+        return;
+    }
+    let on_error = msg
+        .metadata
+        .get("on_error")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default();
+    match on_error {
+        config::HeartbeatOnError::Post => {
+            warn!(channel = %msg.channel, chat_id = %msg.chat_id, "Heartbeat task failed: {err:#}");
+            let _ = outbound_tx.send(OutboundMessage::chat(
+                msg.channel.clone(),
+                msg.chat_id.clone(),
+                format!("[Heartbeat] Task failed: {err}"),
+                vec![],
+                None,
+            ));
+        }
+        config::HeartbeatOnError::Silent => {
+            warn!(channel = %msg.channel, chat_id = %msg.chat_id, "Heartbeat task failed: {err:#}");
+        }
+        config::HeartbeatOnError::System => {
+            error!(channel = %msg.channel, chat_id = %msg.chat_id, "Heartbeat task failed: {err:#}");
+            let alert = |key: &str| msg.metadata.get(key).and_then(|v| v.as_str()).map(String::from);
+            if let (Some(channel), Some(chat_id)) = (alert("alert_channel"), alert("alert_chat_id")) {
+                let _ = outbound_tx.send(OutboundMessage::chat(
+                    channel,
+                    chat_id,
+                    format!(
+                        "[Heartbeat] Task for {}:{} failed: {}",
+                        msg.channel, msg.chat_id, err
+                    ),
+                    vec![],
+                    None,
+                ));
+            }
+        }
+    }
+}
+
+/// Size cap for the final reply of a cron / heartbeat run (see [`config::ScheduledResultConfig`]).
+struct ScheduledResultCap {
+    config: config::ScheduledResultConfig,
+    /// Where full results are written when `attach_full_output` is set.
+    attachment_dir: PathBuf,
+}
+
+impl ScheduledResultCap {
+    /// Cap for `msg` when it was sent by the cron runner or the heartbeat service.
+    fn for_message(msg: &InboundMessage, cfg: &Config, workspace: &Path) -> Option<Self> {
+        let config = match msg.metadata.get("source").and_then(|v| v.as_str()) {
+            Some("cron") => cfg.cron.result.clone(),
+            Some("heartbeat") => cfg.heartbeat.result.clone(),
+            _ => return None,
+        };
+        Some(Self {
+            config,
+            attachment_dir: workspace.join("scheduled_results"),
+        })
+    }
+
+    /// Returns the text to post (at most `max_bytes`) and the full-output attachment, if any.
+    fn apply(&self, reply: &str) -> (String, Vec<String>) {
+        if reply.len() <= self.config.max_bytes {
+            return (reply.to_string(), vec![]);
+        }
+        let attachment = self
+            .config
+            .attach_full_output
+            .then(|| self.write_full_output(reply))
+            .flatten();
+        let note = match &attachment {
+            Some(_) => format!("\n...truncated ({} bytes total, full output attached)", reply.len()),
+            None => format!("\n...truncated ({} bytes total)", reply.len()),
+        };
+        let head = smart_truncate(reply, self.config.max_bytes.saturating_sub(note.len()));
+        (format!("{}{}", head.content, note), attachment.into_iter().collect())
+    }
+
+    fn write_full_output(&self, reply: &str) -> Option<String> {
+        let path = self.attachment_dir.join(format!(
+            "result-{}.txt",
+            chrono::Utc::now().format("%Y%m%d-%H%M%S%.3f")
+        ));
+        match std::fs::create_dir_all(&self.attachment_dir).and_then(|_| std::fs::write(&path, reply)) {
+            Ok(()) => Some(path.to_string_lossy().into_owned()),
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Failed to write full scheduled result");
+                None
+            }
+        }
+    }
+}
+
+/// Typing indicator to show while a message is processed. Cron and heartbeat prompts have
+/// nobody waiting on the other end, so they get none.
+fn typing_for(msg: &InboundMessage) -> Option<OutboundMessage> {
+    match msg.metadata.get("source").and_then(|v| v.as_str()) {
+        Some("cron") | Some("heartbeat") => None,
+        _ => Some(OutboundMessage::typing(msg.channel.clone(), msg.chat_id.clone())),
+    }
+}
+
+/// Reply sent when a message exceeds its channel's cap under [`config::InboundOverflowPolicy::Reject`].
+const INBOUND_TOO_LONG_REPLY: &str =
+    "Your message is too long ({len} characters, limit {max}). Please trim it or attach it as a file.";
+
+/// Apply the channel's [`config::InboundLimitConfig`] to `msg`. Oversized content is truncated in
+/// place (with a note) or, under the reject policy, left untouched and `Some(reply)` is returned.
+/// Cron and heartbeat prompts come from config, not users, and are never capped.
+fn apply_inbound_limit(msg: &mut InboundMessage, limit: &config::InboundLimitConfig) -> Option<String> {
+    if matches!(
+        msg.metadata.get("source").and_then(|v| v.as_str()),
+        Some("cron") | Some("heartbeat")
+    ) {
+        return None;
+    }
+    let len = msg.content.chars().count();
+    if len <= limit.max_chars {
+        return None;
+    }
+    match limit.policy {
+        config::InboundOverflowPolicy::Truncate => {
+            warn!(channel = %msg.channel, chat_id = %msg.chat_id, len, max = limit.max_chars, "Truncating oversized inbound message");
+            let mut content: String = msg.content.chars().take(limit.max_chars).collect();
+            content.push_str(&format!(
+                "\n\n[Message truncated: showing the first {} of {} characters.]",
+                limit.max_chars, len
+            ));
+            msg.content = content;
+            None
+        }
+        config::InboundOverflowPolicy::Reject => {
+            warn!(channel = %msg.channel, chat_id = %msg.chat_id, len, max = limit.max_chars, "Rejecting oversized inbound message");
+            Some(
+                INBOUND_TOO_LONG_REPLY
+                    .replace("{len}", &len.to_string())
+                    .replace("{max}", &limit.max_chars.to_string()),
+            )
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct AgentLoop {
     model: Arc<dyn SynbotCompletionModel>,
     workspace: PathBuf,
@@ -61,6 +275,14 @@ pub struct AgentLoop {
     workflow_user_input_timeout_secs: u64,
     /// Live config (memory, compression, embeddings).
     shared_config: Arc<RwLock<Config>>,
+    /// Bounds concurrent turns globally and serializes turns per session.
+    turn_limiter: Arc<TurnLimiter>,
+    /// In-flight tracker for config cron runs; released when a cron-triggered turn ends.
+    cron_runs: Option<Arc<CronRunTracker>>,
+    /// Running turns, listed and cancelled through the web API.
+    active_turns: Arc<ActiveTurns>,
+    /// Models for channel `provider`/`model` overrides, shared with tools that call the model.
+    channel_models: Arc<ChannelModels>,
 }
 
 impl AgentLoop {
@@ -84,9 +306,9 @@ impl AgentLoop {
         let tool_result_preview_chars = config.tool_result_preview_chars as usize;
         let workflow_store = WorkflowStore::new(config::workflows_root(config).as_path());
         let workflow_user_input_timeout_secs = config.workflow.user_input_timeout_secs;
+        let turn_limiter = Arc::new(TurnLimiter::new(config.main_agent.max_concurrent_turns));
         Self {
             workspace,
-            model,
             tools,
             max_iterations,
             outbound_tx,
@@ -101,9 +323,64 @@ impl AgentLoop {
             pending_workflow_confirm: PendingConfirmStore::new(),
             workflow_user_input_timeout_secs,
             shared_config,
+            turn_limiter,
+            cron_runs: None,
+            active_turns: Arc::new(ActiveTurns::new()),
+            channel_models: Arc::new(ChannelModels::new(Arc::clone(&model))),
+            model,
+        }
+    }
+
+    /// Share the channel model overrides with tools built before the loop (their default
+    /// model must be the loop's).
+    pub fn with_channel_models(mut self, models: Arc<ChannelModels>) -> Self {
+        self.channel_models = models;
+        self
+    }
+
+    /// Report the end of config cron runs to `tracker` so overlapping fires can be detected.
+    pub fn with_cron_runs(mut self, tracker: Arc<CronRunTracker>) -> Self {
+        self.cron_runs = Some(tracker);
+        self
+    }
+
+    /// End the config cron run `msg` belongs to when the message is dropped instead of run as a turn,
+    /// so the task is not left "in flight" and skipped forever.
+    fn finish_cron_run(&self, msg: &InboundMessage) {
+        if let (Some(key), Some(tracker)) = (
+            msg.metadata.get(CRON_TASK_META).and_then(|v| v.as_str()),
+            self.cron_runs.as_ref(),
+        ) {
+            tracker.finish(key);
         }
     }
 
+    /// Register running turns in `turns` (shared with the web API) instead of a private registry.
+    pub fn with_active_turns(mut self, turns: Arc<ActiveTurns>) -> Self {
+        self.active_turns = turns;
+        self
+    }
+
+    /// Action of the first `commands` entry of the message's channel that matches its content.
+    async fn channel_command(&self, msg: &InboundMessage) -> Option<ChannelCommandAction> {
+        let commands = self.shared_config.read().await.channels.commands(&msg.channel);
+        commands
+            .into_iter()
+            .find(|c| c.matches(&msg.content))
+            .map(|c| c.action)
+    }
+
+    /// Reply to the `health` channel command.
+    fn health_summary(&self) -> String {
+        format!(
+            "[Health] OK. synbot {}, {} turn(s) running, {} of {} turn slot(s) free.",
+            env!("CARGO_PKG_VERSION"),
+            self.active_turns.list().len(),
+            self.turn_limiter.available(),
+            self.turn_limiter.max_concurrent(),
+        )
+    }
+
     /// Run the agent loop. Requires the loop to be in an `Arc<Mutex<>>` so that /stop (or /cancel)
     /// can cancel a running agent task by spawning and tracking it. Pass the receiver so the
     /// loop can recv without holding the lock (allowing the spawned task to run).
@@ -114,6 +391,11 @@ impl AgentLoop {
         info!("Agent loop started");
         let mut join_set: JoinSet<String> = JoinSet::new();
         let mut cancel_by_session: HashMap<String, CancellationToken> = HashMap::new();
+        // Messages that arrived while their session's turn was running, dispatched in order.
+        let mut chat_queue = ChatQueue::default();
+        // Messages that don't trigger the agent and arrived during their session's turn; saved to
+        // history when it ends (the turn holds the history until then), never queued as turns.
+        let mut history_only: HashMap<String, Vec<InboundMessage>> = HashMap::new();
 
         loop {
             tokio::select! {
@@ -121,6 +403,17 @@ impl AgentLoop {
                     match join_result {
                         Ok(session_key) => {
                             cancel_by_session.remove(&session_key);
+                            for msg in history_only.remove(&session_key).unwrap_or_default() {
+                                Self::dispatch(&loop_ref, msg, &mut join_set, &mut cancel_by_session)
+                                    .await;
+                            }
+                            while !cancel_by_session.contains_key(&session_key) {
+                                let Some(next) = chat_queue.pop(&session_key) else {
+                                    break;
+                                };
+                                Self::dispatch(&loop_ref, next, &mut join_set, &mut cancel_by_session)
+                                    .await;
+                            }
                         }
                         Err(e) => {
                             warn!(error = %e, "Agent background task ended with error");
@@ -128,24 +421,91 @@ impl AgentLoop {
                     }
                 }
                 msg_opt = inbound_rx.recv() => {
-                    let msg = match msg_opt {
+                    let mut msg = match msg_opt {
                         None => break,
                         Some(m) => m,
                     };
-                    let sk = msg.session_key();
-
-                    if let Some(cmd) = parse_control_command(&msg.content) {
+                    {
+                        let guard = loop_ref.lock().await;
+                        let (limit, binding) = {
+                            let cfg = guard.shared_config.read().await;
+                            (cfg.channels.inbound_limit(&msg.channel), cfg.channels.model_binding(&msg.channel))
+                        };
+                        if let (Some(role), Some(meta)) = (binding.role, msg.metadata.as_object_mut()) {
+                            meta.insert(CHANNEL_ROLE_META.to_string(), role.into());
+                        }
+                        // Messages kept only in history stay as sent, and get no reply.
+                        let limit = limit.filter(|_| triggers_agent(&msg));
+                        if let Some(reply) = limit.and_then(|l| apply_inbound_limit(&mut msg, &l)) {
+                            let _ = guard.outbound_tx.send(OutboundMessage::chat(
+                                msg.channel.clone(),
+                                msg.chat_id.clone(),
+                                reply,
+                                vec![],
+                                None,
+                            ));
+                            guard.finish_cron_run(&msg);
+                            continue;
+                        }
+                    }
+                    // Turns and /stop are tracked per conversation (see turn_key); workflows per chat.
+                    let sk = loop_ref.lock().await.turn_key(&msg).await;
+                    let wf_key = msg.session_key();
+
+                    // Channel commands from config come before the built-in slash commands, and
+                    // like a turn they answer only messages that trigger the agent.
+                    let channel_command = if triggers_agent(&msg) {
+                        loop_ref.lock().await.channel_command(&msg).await
+                    } else {
+                        None
+                    };
+                    let control = match channel_command {
+                        Some(ChannelCommandAction::Cancel) => Some(ControlCommand::Stop),
+                        Some(action) => {
+                            let guard = loop_ref.lock().await;
+                            let reply = match action {
+                                ChannelCommandAction::Reply { text } => text,
+                                _ => guard.health_summary(),
+                            };
+                            let _ = guard.outbound_tx.send(OutboundMessage::chat(
+                                msg.channel.clone(),
+                                msg.chat_id.clone(),
+                                reply,
+                                vec![],
+                                inbound_message_id(&msg),
+                            ));
+                            guard.finish_cron_run(&msg);
+                            continue;
+                        }
+                        None => parse_control_command(&msg.content),
+                    };
+                    if let Some(cmd) = control {
+                        // Control commands never start a turn of their own.
+                        loop_ref.lock().await.finish_cron_run(&msg);
                         match cmd {
                             ControlCommand::Stop => {
                                 let guard = loop_ref.lock().await;
                                 if let Some(token) = cancel_by_session.remove(&sk) {
                                     token.cancel();
+                                    let dropped = chat_queue.clear(&sk);
+                                    for queued in &dropped {
+                                        guard.finish_cron_run(queued);
+                                    }
+                                    let dropped = dropped.len();
+                                    let reply = if dropped > 0 {
+                                        format!(
+                                            "[Control] Cancelled. The current turn was stopped and {} queued message(s) were dropped.",
+                                            dropped
+                                        )
+                                    } else {
+                                        "[Control] Cancelled. The current turn was stopped.".to_string()
+                                    };
                                     let _ = guard.outbound_tx.send(OutboundMessage::chat(
                                         msg.channel.clone(),
                                         msg.chat_id.clone(),
-                                        "[Control] Stopped.".to_string(),
+                                        reply,
                                         vec![],
-                                        None,
+                                        inbound_message_id(&msg),
                                     ));
                                 } else {
                                     let _ = guard.outbound_tx.send(OutboundMessage::chat(
@@ -153,7 +513,7 @@ impl AgentLoop {
                                         msg.chat_id.clone(),
                                         "[Control] Nothing is currently running. Use /status to check.".to_string(),
                                         vec![],
-                                        None,
+                                        inbound_message_id(&msg),
                                     ));
                                 }
                                 continue;
@@ -172,7 +532,7 @@ impl AgentLoop {
                                     msg.chat_id.clone(),
                                     format!("[Commands]\n{}", slash_commands_help_text()),
                                     vec![],
-                                    None,
+                                    inbound_message_id(&msg),
                                 ));
                                 continue;
                             }
@@ -185,14 +545,17 @@ impl AgentLoop {
                                             .append_user_message_and_save(k, &msg.content)
                                             .await;
                                     }
-                                    loop_guard.handle_workflow_continue(&msg, &sk).await
+                                    loop_guard.handle_workflow_continue(&msg, &wf_key).await
                                 };
                                 match wf_result {
-                                    Ok(Some((handle, token, run_sk))) => {
-                                        cancel_by_session.insert(run_sk.clone(), token);
+                                    Ok(Some((handle, token, _))) => {
+                                        let active_turns = loop_ref.lock().await.active_turns.clone();
+                                        let turn_id = active_turns.start(&msg.channel, &msg.chat_id, token.clone());
+                                        cancel_by_session.insert(sk.clone(), token);
                                         join_set.spawn(async move {
                                             let _ = handle.await;
-                                            run_sk
+                                            active_turns.finish(&turn_id);
+                                            sk
                                         });
                                     }
                                     Ok(None) => {}
@@ -202,7 +565,7 @@ impl AgentLoop {
                                             msg.chat_id.clone(),
                                             format!("[Workflow] Resume failed: {}", e),
                                             vec![],
-                                            None,
+                                            inbound_message_id(&msg),
                                         ));
                                     }
                                 }
@@ -212,37 +575,89 @@ impl AgentLoop {
                     }
 
                     if cancel_by_session.contains_key(&sk) {
-                        let _ = loop_ref.lock().await.outbound_tx.send(OutboundMessage::chat(
-                            msg.channel.clone(),
-                            msg.chat_id.clone(),
-                            format!("[Control] Busy. {}", busy_hint_commands()),
-                            vec![],
-                            None,
-                        ));
+                        if !triggers_agent(&msg) {
+                            history_only.entry(sk).or_default().push(msg);
+                            continue;
+                        }
+                        let guard = loop_ref.lock().await;
+                        // A workflow waiting for user input is "busy" but wants this message now.
+                        if guard.pending_workflow_input.deliver(&wf_key, msg.content.clone()).await {
+                            guard.finish_cron_run(&msg);
+                            continue;
+                        }
+                        match chat_queue.push(&sk, msg) {
+                            Ok(position) => {
+                                info!(session_key = %sk, position, "Turn in progress, message queued");
+                            }
+                            Err(msg) => {
+                                let _ = guard.outbound_tx.send(OutboundMessage::chat(
+                                    msg.channel.clone(),
+                                    msg.chat_id.clone(),
+                                    format!("[Control] Busy, too many messages are queued for this chat. {}", busy_hint_commands()),
+                                    vec![],
+                                    inbound_message_id(&msg),
+                                ));
+                                guard.finish_cron_run(&msg);
+                            }
+                        }
                         continue;
                     }
 
-                    let result = {
-                        let mut guard = loop_ref.lock().await;
-                        guard.handle_message(msg, loop_ref.clone()).await
-                    };
-                    match result {
-                        Err(e) => error!("Error handling message: {e:#}"),
-                        Ok(Some((handle, token, run_sk))) => {
-                            cancel_by_session.insert(run_sk.clone(), token);
-                            join_set.spawn(async move {
-                                let _ = handle.await;
-                                run_sk
-                            });
-                        }
-                        Ok(None) => {}
-                    }
+                    Self::dispatch(&loop_ref, msg, &mut join_set, &mut cancel_by_session).await;
                 }
             }
         }
         Ok(())
     }
 
+    /// Start the turn for `msg` and track it in `join_set` / `cancel_by_session` under its
+    /// [`Self::turn_key`] so /stop can cancel it and the next queued message of the conversation
+    /// is dispatched when it ends.
+    async fn dispatch(
+        loop_ref: &Arc<Mutex<Self>>,
+        msg: InboundMessage,
+        join_set: &mut JoinSet<String>,
+        cancel_by_session: &mut HashMap<String, CancellationToken>,
+    ) {
+        let (channel, chat_id) = (msg.channel.clone(), msg.chat_id.clone());
+        let (result, active_turns, turn_key) = {
+            let mut guard = loop_ref.lock().await;
+            let turn_key = guard.turn_key(&msg).await;
+            // Config cron runs stay "in flight" until their turn ends (see CronRunTracker).
+            let cron_run = match (
+                msg.metadata.get(CRON_TASK_META).and_then(|v| v.as_str()),
+                guard.cron_runs.clone(),
+            ) {
+                (Some(key), Some(tracker)) => Some((tracker, key.to_string())),
+                _ => None,
+            };
+            let active_turns = guard.active_turns.clone();
+            ((guard.handle_message(msg, loop_ref.clone()).await, cron_run), active_turns, turn_key)
+        };
+        match result {
+            (Ok(Some((handle, token, _))), cron_run) => {
+                let turn_id = active_turns.start(&channel, &chat_id, token.clone());
+                cancel_by_session.insert(turn_key.clone(), token);
+                join_set.spawn(async move {
+                    let _ = handle.await;
+                    active_turns.finish(&turn_id);
+                    if let Some((tracker, key)) = cron_run {
+                        tracker.finish(&key);
+                    }
+                    turn_key
+                });
+            }
+            (other, cron_run) => {
+                if let Err(e) = other {
+                    error!("Error handling message: {e:#}");
+                }
+                if let Some((tracker, key)) = cron_run {
+                    tracker.finish(&key);
+                }
+            }
+        }
+    }
+
     /// Returns Ok(Some((handle, token, session_key))) when a workflow or agent run was started (so run() can track for /stop).
     async fn handle_message(
         &mut self,
@@ -257,12 +672,7 @@ impl AgentLoop {
         async {
             // When trigger_agent is false (e.g. not in allowlist, or group message not @bot),
             // still append the message to session and persist so the user can see history and update allowlist.
-            let trigger_agent = msg
-                .metadata
-                .get("trigger_agent")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(true);
-            if !trigger_agent {
+            if !triggers_agent(&msg) {
                 self.save_message_only(&msg).await?;
                 return Ok(None);
             }
@@ -387,22 +797,38 @@ impl AgentLoop {
                 h.dispatch(HookEvent::MessageReceived(msg.clone())).await;
             }
             info!(chat = %msg.chat_id, "Processing message");
+            if let Some(typing) = typing_for(&msg) {
+                let _ = self.outbound_tx.send(typing);
+            }
             let directives = DirectiveParser::parse(&msg.content);
             if directives.len() <= 1 {
                 // Spawn agent run so /stop or /cancel can cancel it; run() will track (handle, token, session_key).
                 let token = CancellationToken::new();
                 let token_for_spawn = token.clone();
                 let session_key = msg.session_key();
+                let session_key_for_spawn = session_key.clone();
                 let msg_clone = msg.clone();
                 let directives_clone = directives.clone();
                 let loop_ref_spawn = loop_ref.clone();
                 let handle = tokio::spawn(async move {
-                    let mut guard = loop_ref_spawn.lock().await;
-                    if let Err(e) = guard
-                        .process_directives_sequential(&msg_clone, &directives_clone, start, Some(token_for_spawn))
+                    // Run on a snapshot so the loop stays unlocked and other sessions can start turns.
+                    let runner = loop_ref_spawn.lock().await.clone();
+                    let _permit = tokio::select! {
+                        permit = runner.turn_limiter.acquire(&session_key_for_spawn) => permit,
+                        _ = token_for_spawn.cancelled() => {
+                            tracing::debug!("Agent run cancelled while waiting for a turn slot");
+                            return;
+                        }
+                    };
+                    if let Err(e) = runner
+                        .process_directives_sequential(&msg_clone, &directives_clone, start, Some(token_for_spawn.clone()))
                         .await
                     {
-                        tracing::debug!("Agent run ended (cancelled or error): {}", e);
+                        if token_for_spawn.is_cancelled() {
+                            tracing::debug!("Agent run ended (cancelled): {}", e);
+                        } else {
+                            report_run_error(&runner.outbound_tx, &msg_clone, &e);
+                        }
                     }
                 });
                 return Ok(Some((handle, token, session_key)));
@@ -527,24 +953,48 @@ impl AgentLoop {
         Ok(Some((handle, token, session_key.to_string())))
     }
 
-    /// Resolve the default agent id for this message (from channel's default_agent in metadata, or "main").
-    fn default_agent_for_msg(&self, msg: &InboundMessage) -> String {
-        let id = msg
-            .metadata
-            .get("default_agent")
-            .and_then(|v| v.as_str())
-            .unwrap_or("main")
-            .trim();
-        if id.is_empty() || !self.agent_registry.contains(id) {
-            "main".to_string()
+    /// Resolve the default agent id for this message: the agent running the `role` in metadata
+    /// (heartbeat / cron tasks), else the agent running the channel's `role` override, else the
+    /// channel's default_agent unless it is the implicit "main", else the agent mapped to the
+    /// message's language, else the agent running `mainAgent.defaultRole`, else "main"
+    /// (see [`AgentRegistry::resolve_agent`]). With language routing, a conversation that already
+    /// has a session keeps that session's agent, so only its first message is detected.
+    async fn default_agent_for_msg(&self, msg: &InboundMessage) -> String {
+        let task_role = msg.metadata.get("role").and_then(|v| v.as_str());
+        let channel_role = msg.metadata.get(CHANNEL_ROLE_META).and_then(|v| v.as_str());
+        let channel_agent = msg.metadata.get("default_agent").and_then(|v| v.as_str());
+        let session_agent = if self.agent_registry.routes_by_language() {
+            let sm = self.session_state.session_manager.read().await;
+            let conversation = sm.resolve_session("main", &msg.channel, &msg.chat_id, &msg.metadata);
+            sm.latest_session_agent(&conversation, |agent| self.agent_registry.is_language_routed(agent))
+                .map(str::to_string)
         } else {
-            id.to_string()
+            None
+        };
+        self.agent_registry
+            .resolve_conversation_agent(
+                task_role,
+                channel_role,
+                channel_agent,
+                session_agent.as_deref(),
+                Some(&msg.content),
+            )
+            .to_string()
+    }
+
+    /// Key that serializes turns and scopes /stop: the chat, or for DMs from a linked identity the
+    /// link, so a conversation shared across channels never runs two turns at once.
+    async fn turn_key(&self, msg: &InboundMessage) -> String {
+        let sm = self.session_state.session_manager.read().await;
+        match sm.identity_link(&msg.channel, &msg.chat_id, &msg.metadata) {
+            Some(link) => format!("{}:{}", LINKED_SESSION_CHANNEL, link),
+            None => msg.session_key(),
         }
     }
 
     /// Resolve session key used for conversation history (agent:channel:scope:id).
     async fn resolve_history_session_key(&self, msg: &InboundMessage) -> Option<String> {
-        let agent_id = self.default_agent_for_msg(msg);
+        let agent_id = self.default_agent_for_msg(msg).await;
         let session_id = {
             let sm = self.session_state.session_manager.write().await;
             sm.resolve_session(&agent_id, &msg.channel, &msg.chat_id, &msg.metadata)
@@ -562,7 +1012,7 @@ impl AgentLoop {
                     msg.chat_id.clone(),
                     "[Status] Could not resolve session.".to_string(),
                     vec![],
-                    None,
+                    inbound_message_id(msg),
                 ));
                 return Ok(());
             }
@@ -603,7 +1053,7 @@ impl AgentLoop {
             msg.chat_id.clone(),
             format!("[Status]\n{}", text),
             vec![],
-            None,
+            inbound_message_id(msg),
         ));
         Ok(())
     }
@@ -618,7 +1068,7 @@ impl AgentLoop {
                     msg.chat_id.clone(),
                     "[Clear] Could not resolve session.".to_string(),
                     vec![],
-                    None,
+                    inbound_message_id(msg),
                 ));
                 return Ok(());
             }
@@ -630,7 +1080,7 @@ impl AgentLoop {
                 msg.chat_id.clone(),
                 format!("[Clear] Failed: {}.", e),
                 vec![],
-                None,
+                inbound_message_id(msg),
             ));
             return Ok(());
         }
@@ -640,7 +1090,7 @@ impl AgentLoop {
             msg.chat_id.clone(),
             "[Clear] Session cleared. Conversation will continue as a fresh chat.".to_string(),
             vec![],
-            None,
+            inbound_message_id(msg),
         ));
         Ok(())
     }
@@ -687,7 +1137,7 @@ impl AgentLoop {
     /// Append the message to the channel's default agent session and persist to disk, without running completion.
     /// Used when the chat is not in allowlist or when it's a group message not directed at the bot.
     async fn save_message_only(&mut self, msg: &InboundMessage) -> Result<()> {
-        let agent_id = self.default_agent_for_msg(msg);
+        let agent_id = self.default_agent_for_msg(msg).await;
         let session_id = {
             let sm = self.session_state.session_manager.write().await;
             sm.resolve_session(&agent_id, &msg.channel, &msg.chat_id, &msg.metadata)
@@ -695,6 +1145,7 @@ impl AgentLoop {
         let session_key = session_id.format();
 
         let user_content = apply_sender_display_prefix(&msg.content, &msg.metadata);
+        let user_content = apply_reply_context(&user_content, &msg.metadata);
         let session_messages = self.session_state.get_or_create_session_messages(&session_key).await;
         {
             let mut history = session_messages.lock().await;
@@ -740,7 +1191,7 @@ impl AgentLoop {
     }
 
     async fn process_directives_sequential(
-        &mut self,
+        &self,
         msg: &InboundMessage,
         directives: &[crate::agent::directive::Directive],
         start: std::time::Instant,
@@ -748,7 +1199,7 @@ impl AgentLoop {
     ) -> Result<()> {
         for directive in directives {
             let agent_id = match &directive.target {
-                None => self.default_agent_for_msg(msg),
+                None => self.default_agent_for_msg(msg).await,
                 Some(name) => {
                     if !self.agent_registry.contains(name) {
                         self.send_unknown_agent_error(msg, name).await;
@@ -768,10 +1219,10 @@ impl AgentLoop {
             let session_key = session_id.format();
 
             let agent_workspace = agent_ctx.workspace_dir.clone();
-            let tool_ctx = ToolContext {
-                agent_id: agent_id.clone(),
-                workspace: agent_workspace,
-            };
+            let mut tool_ctx = ToolContext::new(agent_id.clone(), agent_workspace);
+            if let Some(ref token) = cancel {
+                tool_ctx = tool_ctx.with_cancel(token.clone());
+            }
 
             // When message is a response to a pending approval, prepend instruction so the agent calls submit_approval_response
             let base_content = if agent_id == "main" {
@@ -780,6 +1231,7 @@ impl AgentLoop {
                 format!("@@{} {}", agent_id, directive.content)
             };
             let base_content = apply_sender_display_prefix(&base_content, &msg.metadata);
+            let base_content = apply_reply_context(&base_content, &msg.metadata);
             let user_content = if let Some(rid) = msg.metadata.get("pending_approval_request_id").and_then(|v| v.as_str()) {
                 format!(
                     "[Context: The user is responding to a pending command approval request (request_id: {}). Interpret their message as approve or reject and call submit_approval_response with request_id \"{}\" and approved (true or false).]\n\nUser: {}",
@@ -790,11 +1242,21 @@ impl AgentLoop {
             };
 
             let cfg_snapshot = self.shared_config.read().await.clone();
+            let result_cap = ScheduledResultCap::for_message(msg, &cfg_snapshot, &agent_ctx.workspace_dir);
             #[cfg(feature = "memory-index")]
             if cfg_snapshot.memory.auto_index {
                 let _ = crate::agent::memory_index::reindex_if_changed_async(&agent_id, &cfg_snapshot)
                     .await;
             }
+            let tools_enabled =
+                agent_ctx.params.tools_enabled && cfg_snapshot.channels.tools_enabled(&msg.channel);
+            let turn_model =
+                self.channel_models.for_channel(&cfg_snapshot, &msg.channel, &agent_ctx.params.provider);
+            let tool_sandbox_workspace = cfg_snapshot
+                .tool_sandbox
+                .as_ref()
+                .map(config::tool_sandbox_workspace_dir)
+                .unwrap_or_else(|| crate::sandbox::types::DEFAULT_CONTAINER_WORKSPACE.to_string());
             let memory_cfg_arc = std::sync::Arc::new(cfg_snapshot);
             #[cfg(feature = "memory-index")]
             let system_prompt = {
@@ -804,7 +1266,8 @@ impl AgentLoop {
                     config::skills_dir().as_path(),
                     self.tool_sandbox_exec_kind,
                     Some(std::sync::Arc::clone(&memory_cfg_arc)),
-                );
+                )
+                .with_tool_sandbox_workspace(tool_sandbox_workspace);
                 context_builder.build_system_prompt_with_role_prompt(
                     &agent_ctx.system_prompt,
                     Some(user_content.as_str()),
@@ -817,11 +1280,11 @@ impl AgentLoop {
                     &agent_id,
                     config::skills_dir().as_path(),
                     self.tool_sandbox_exec_kind,
-                );
+                )
+                .with_tool_sandbox_workspace(tool_sandbox_workspace);
                 context_builder.build_system_prompt_with_role_prompt(&agent_ctx.system_prompt, None)
             };
-
-            let tool_defs = self.tools.rig_definitions();
+            let (system_prompt, tool_defs) = tools_for_turn(system_prompt, &self.tools, tools_enabled);
             let session_messages = self.session_state.get_or_create_session_messages(&session_key).await;
             {
                 let mut history = session_messages.lock().await;
@@ -856,12 +1319,14 @@ impl AgentLoop {
             let max_chat_history_messages = agent_ctx.params.max_chat_history_messages;
             let run_result = scope(tool_ctx, async {
                 run_completion_loop(
-                    &*self.model,
+                    &*turn_model,
                     &system_prompt,
                     agent_ctx.params.max_tokens,
                     agent_ctx.params.temperature,
+                    &agent_ctx.params.reasoning,
                     model_max_iterations,
                     agent_ctx.params.max_consecutive_tool_errors,
+                    agent_ctx.params.max_repeated_tool_calls,
                     max_chat_history_messages,
                     &agent_id,
                     &mut *history_guard,
@@ -870,12 +1335,16 @@ impl AgentLoop {
                     &msg.channel,
                     &msg.chat_id,
                     &msg.sender_id,
+                    inbound_message_id(msg).as_deref(),
                     &session_key,
                     &self.outbound_tx,
                     self.hooks.clone(),
                     self.tool_result_preview_chars,
+                    agent_ctx.params.tool_result_format,
                     cancel.as_ref(),
                     Some(memory_cfg_arc),
+                    result_cap.as_ref(),
+                    Some(self.active_turns.as_ref()),
                 )
                 .await
             })
@@ -946,7 +1415,7 @@ impl AgentLoop {
 
         for directive in directives {
             let agent_id = match &directive.target {
-                None => self.default_agent_for_msg(msg),
+                None => self.default_agent_for_msg(msg).await,
                 Some(name) => {
                     if !self.agent_registry.contains(name) {
                         self.send_unknown_agent_error(msg, name).await;
@@ -965,14 +1434,13 @@ impl AgentLoop {
             };
             let session_key = session_id.format();
 
-            let tool_defs = self.tools.rig_definitions();
-
             let base_content = if agent_id == "main" {
                 directive.content.clone()
             } else {
                 format!("@@{} {}", agent_id, directive.content)
             };
             let base_content = apply_sender_display_prefix(&base_content, &msg.metadata);
+            let base_content = apply_reply_context(&base_content, &msg.metadata);
             let user_content = if let Some(rid) = msg.metadata.get("pending_approval_request_id").and_then(|v| v.as_str()) {
                 format!(
                     "[Context: The user is responding to a pending command approval request (request_id: {}). Interpret their message as approve or reject and call submit_approval_response with request_id \"{}\" and approved (true or false).]\n\nUser: {}",
@@ -983,11 +1451,21 @@ impl AgentLoop {
             };
 
             let cfg_snapshot = self.shared_config.read().await.clone();
+            let result_cap = ScheduledResultCap::for_message(msg, &cfg_snapshot, &agent_ctx.workspace_dir);
             #[cfg(feature = "memory-index")]
             if cfg_snapshot.memory.auto_index {
                 let _ = crate::agent::memory_index::reindex_if_changed_async(&agent_id, &cfg_snapshot)
                     .await;
             }
+            let tools_enabled =
+                agent_ctx.params.tools_enabled && cfg_snapshot.channels.tools_enabled(&msg.channel);
+            let turn_model =
+                self.channel_models.for_channel(&cfg_snapshot, &msg.channel, &agent_ctx.params.provider);
+            let tool_sandbox_workspace = cfg_snapshot
+                .tool_sandbox
+                .as_ref()
+                .map(config::tool_sandbox_workspace_dir)
+                .unwrap_or_else(|| crate::sandbox::types::DEFAULT_CONTAINER_WORKSPACE.to_string());
             let memory_cfg_arc = std::sync::Arc::new(cfg_snapshot);
             #[cfg(feature = "memory-index")]
             let system_prompt = {
@@ -997,7 +1475,8 @@ impl AgentLoop {
                     config::skills_dir().as_path(),
                     self.tool_sandbox_exec_kind,
                     Some(std::sync::Arc::clone(&memory_cfg_arc)),
-                );
+                )
+                .with_tool_sandbox_workspace(tool_sandbox_workspace);
                 context_builder.build_system_prompt_with_role_prompt(
                     &agent_ctx.system_prompt,
                     Some(user_content.as_str()),
@@ -1010,9 +1489,11 @@ impl AgentLoop {
                     &agent_id,
                     config::skills_dir().as_path(),
                     self.tool_sandbox_exec_kind,
-                );
+                )
+                .with_tool_sandbox_workspace(tool_sandbox_workspace);
                 context_builder.build_system_prompt_with_role_prompt(&agent_ctx.system_prompt, None)
             };
+            let (system_prompt, tool_defs) = tools_for_turn(system_prompt, &self.tools, tools_enabled);
 
             // Push user message into session history before spawning
             let session_messages = self.session_state.get_or_create_session_messages(&session_key).await;
@@ -1032,7 +1513,7 @@ impl AgentLoop {
 
             let agent_workspace = agent_ctx.workspace_dir.clone();
 
-            let model = Arc::clone(&self.model);
+            let model = turn_model;
             let tools = Arc::clone(&self.tools);
             let session_state = self.session_state.clone();
             let outbound_tx = self.outbound_tx.clone();
@@ -1042,19 +1523,20 @@ impl AgentLoop {
             let chat_id = msg.chat_id.clone();
             let sender_id = msg.sender_id.clone();
             let sender_id_for_loop = msg.sender_id.clone();
+            let reply_to = inbound_message_id(msg);
             let sk = session_key.clone();
             let aid = agent_id.clone();
             let aid_for_meta = aid.clone();
             let sid = session_id.clone();
-            let tool_ctx = ToolContext {
-                agent_id: aid.clone(),
-                workspace: agent_workspace,
-            };
+            let tool_ctx = ToolContext::new(aid.clone(), agent_workspace);
             let tool_result_preview_chars = self.tool_result_preview_chars;
             let max_chat_history_messages = agent_ctx.params.max_chat_history_messages;
             let max_consecutive_tool_errors = agent_ctx.params.max_consecutive_tool_errors;
+            let max_repeated_tool_calls = agent_ctx.params.max_repeated_tool_calls;
             let max_tokens = agent_ctx.params.max_tokens;
             let temperature = agent_ctx.params.temperature;
+            let reasoning = agent_ctx.params.reasoning.clone();
+            let tool_result_format = agent_ctx.params.tool_result_format;
 
             let session_messages_clone = session_messages.clone();
             let memory_cfg_for_task = memory_cfg_arc.clone();
@@ -1075,8 +1557,10 @@ impl AgentLoop {
                         &system_prompt,
                         max_tokens,
                         temperature,
+                        &reasoning,
                         model_max_iterations,
                         max_consecutive_tool_errors,
+                        max_repeated_tool_calls,
                         max_chat_history_messages,
                         &aid,
                         &mut *history_guard,
@@ -1085,12 +1569,16 @@ impl AgentLoop {
                         &channel,
                         &chat_id,
                         &sender_id_for_loop,
+                        reply_to.as_deref(),
                         &session_id_str,
                         &outbound_tx,
                         hooks.clone(),
                         tool_result_preview_chars,
+                        tool_result_format,
                         None, // subagent tasks use timeout; no /stop cancel
                         Some(memory_cfg_for_task),
+                        result_cap.as_ref(),
+                        None,
                     )
                     .await?;
                     let messages = history_guard.clone();
@@ -1270,6 +1758,48 @@ fn truncate_debug(s: &str, max_len: usize) -> String {
     }
 }
 
+/// Await `fut` unless `cancel` fires first; returns `None` when cancelled (the future is dropped).
+async fn until_cancelled<F: std::future::Future>(
+    cancel: Option<&CancellationToken>,
+    fut: F,
+) -> Option<F::Output> {
+    match cancel {
+        Some(token) => tokio::select! {
+            out = fut => Some(out),
+            _ = token.cancelled() => None,
+        },
+        None => Some(fut.await),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tool-call loop detection
+// ---------------------------------------------------------------------------
+
+/// Tracks consecutive identical tool calls (same name and arguments) within one run. Arguments
+/// are compared as JSON values, so the same object with its keys in another order still matches.
+struct ToolCallLoopDetector {
+    last: Option<(String, serde_json::Value)>,
+    repeats: u32,
+}
+
+impl ToolCallLoopDetector {
+    fn new() -> Self {
+        Self { last: None, repeats: 0 }
+    }
+
+    /// Record a call and return how many times in a row this exact call has been seen (1 = new call).
+    fn record(&mut self, name: &str, args: &serde_json::Value) -> u32 {
+        if self.last.as_ref().is_some_and(|(n, a)| n == name && a == args) {
+            self.repeats += 1;
+        } else {
+            self.last = Some((name.to_string(), args.clone()));
+            self.repeats = 1;
+        }
+        self.repeats
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Chat history window helpers
 // ---------------------------------------------------------------------------
@@ -1325,13 +1855,29 @@ pub(crate) fn fix_window_start_for_tool_results(history: &[Message], mut start:
 // Standalone completion loop
 // ---------------------------------------------------------------------------
 
+/// System prompt and tool definitions for one turn. With tools disabled no definitions are sent
+/// and the prompt tells the model so.
+fn tools_for_turn(
+    system_prompt: String,
+    tools: &ToolRegistry,
+    tools_enabled: bool,
+) -> (String, Vec<rig::completion::ToolDefinition>) {
+    if tools_enabled {
+        (system_prompt, tools.rig_definitions())
+    } else {
+        (system_prompt + TOOLS_DISABLED_PROMPT, Vec::new())
+    }
+}
+
 async fn run_completion_loop(
     model: &dyn SynbotCompletionModel,
     system_prompt: &str,
     max_tokens: u32,
     temperature: f32,
+    reasoning: &ReasoningSettings,
     max_iterations: u32,
     max_consecutive_tool_errors: u32,
+    max_repeated_tool_calls: u32,
     max_chat_history_messages: u32,
     agent_id: &str,
     history: &mut Vec<Message>,
@@ -1340,13 +1886,18 @@ async fn run_completion_loop(
     channel: &str,
     chat_id: &str,
     sender_id: &str,
+    reply_to: Option<&str>,
     session_id: &str,
     outbound_tx: &broadcast::Sender<OutboundMessage>,
     hooks: Option<Arc<HookRegistry>>,
     tool_result_preview_chars: usize,
+    tool_result_format: ToolResultFormat,
     cancel: Option<&CancellationToken>,
     memory_cfg: Option<Arc<Config>>,
+    result_cap: Option<&ScheduledResultCap>,
+    active_turns: Option<&ActiveTurns>,
 ) -> Result<u32> {
+    let turn_key = format!("{}:{}", channel, chat_id);
     if let Some(ref c) = memory_cfg {
         if let Err(e) = crate::agent::session_compactor::maybe_compact_history(
             model,
@@ -1363,6 +1914,7 @@ async fn run_completion_loop(
     let message_ctx = Some((channel, chat_id, sender_id, session_id));
     let mut iterations = 0u32;
     let mut consecutive_tool_errors: u32 = 0;
+    let mut loop_detector = ToolCallLoopDetector::new();
     // Media paths from "message" tool calls in this run; sent with the final reply so channels (e.g. DingTalk) get one message with text + files.
     let mut pending_media = Vec::<String>::new();
 
@@ -1413,15 +1965,16 @@ async fn run_completion_loop(
             temperature: Some(temperature as f64),
             max_tokens: Some(max_tokens as u64),
             tool_choice: None,
-            additional_params: None,
+            additional_params: reasoning.request_params.clone(),
         };
 
-        tracing::debug!("Request prompt: {:?}", request);
-
-        let response = model
-            .completion(request)
-            .await
-            .map_err(|e| anyhow::anyhow!("completion failed (agent_id={}): {}", agent_id, e))?;
+        let response = match until_cancelled(cancel, model.completion(request)).await {
+            Some(r) => r.map_err(|e| anyhow::anyhow!("completion failed (agent_id={}): {}", agent_id, e))?,
+            None => {
+                info!(agent_id = %agent_id, "Provider call aborted by user (/stop or /cancel)");
+                return Err(anyhow::anyhow!("Cancelled by user (/stop or /cancel)"));
+            }
+        };
 
         let normalized_choice: Vec<AssistantContent> =
             crate::agent::embedded_tool_calls::normalize_embedded_tool_calls(
@@ -1482,7 +2035,36 @@ async fn run_completion_loop(
             tracing::debug!("Completion output (text): {}", trunc);
         }
 
+        // Reasoning stays in history (some providers require it back) but is only posted when asked.
+        let reasoning_text = normalized_choice
+            .iter()
+            .filter_map(|c| match c {
+                AssistantContent::Reasoning(r) => Some(r.reasoning.join("\n")),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !reasoning_text.trim().is_empty() {
+            info!(
+                agent_id = %agent_id,
+                chars = reasoning_text.chars().count(),
+                posted = reasoning.show_in_channel,
+                "Model reasoning received"
+            );
+            tracing::debug!(agent_id = %agent_id, reasoning = %reasoning_text.trim(), "Model reasoning text");
+            if reasoning.show_in_channel {
+                let _ = outbound_tx.send(OutboundMessage::chat(
+                    channel.to_string(),
+                    chat_id.to_string(),
+                    format!("[Reasoning]\n{}", reasoning_text.trim()),
+                    vec![],
+                    None,
+                ));
+            }
+        }
+
         let mut has_tool_calls = false;
+        let mut tool_loop_detected = false;
         let mut text_parts = Vec::new();
         let mut assistant_contents = Vec::new();
         let mut tool_results = Vec::new();
@@ -1500,6 +2082,20 @@ async fn run_completion_loop(
                 AssistantContent::ToolCall(tc) => {
                     has_tool_calls = true;
                     assistant_contents.push(content.clone());
+                    if tool_defs.is_empty() {
+                        // No tools were offered (pure chat): refuse instead of running what the model invented.
+                        warn!(agent_id = %agent_id, tool = %tc.function.name, "Tool call refused; tools are disabled for this turn");
+                        tool_results.push((
+                            tc.id.clone(),
+                            false,
+                            format_tool_result(
+                                tool_result_format,
+                                &tc.function.name,
+                                &Err(TOOLS_DISABLED_RESULT.to_string()),
+                            ),
+                        ));
+                        continue;
+                    }
                     if tc.function.name == "message" {
                         if let Some(arr) = tc.function.arguments.get("files").and_then(|a| a.as_array()) {
                             for v in arr {
@@ -1510,6 +2106,31 @@ async fn run_completion_loop(
                         }
                     }
                     let args = tc.function.arguments.clone();
+                    let repeats = loop_detector.record(&tc.function.name, &args);
+                    if max_repeated_tool_calls > 0 && repeats > max_repeated_tool_calls {
+                        // Already nudged once and the model kept going: stop the run.
+                        tool_loop_detected = true;
+                        break;
+                    }
+                    if max_repeated_tool_calls > 0 && repeats == max_repeated_tool_calls {
+                        warn!(
+                            agent_id = %agent_id,
+                            tool = %tc.function.name,
+                            repeats,
+                            "Identical tool call repeated; skipping execution and nudging the model"
+                        );
+                        let nudge = format!(
+                            "`{}` was called {} times in a row with identical arguments and was not run again. \
+                             Stop repeating this call: use the results you already have, try a different approach, or answer the user.",
+                            tc.function.name, repeats
+                        );
+                        tool_results.push((
+                            tc.id.clone(),
+                            false,
+                            format_tool_result(tool_result_format, &tc.function.name, &Err(nudge)),
+                        ));
+                        continue;
+                    }
                     let args_str = serde_json::to_string(&args).unwrap_or_else(|_| "{}".to_string());
                     let args_preview = if args_str.len() > 200 {
                         let mut end = 200;
@@ -1530,7 +2151,20 @@ async fn run_completion_loop(
                         })
                         .await;
                     }
-                    let result = tools.execute(&tc.function.name, args, message_ctx).await;
+                    if let Some(turns) = active_turns {
+                        turns.set_current_tool(&turn_key, Some(&tc.function.name));
+                    }
+                    let result = until_cancelled(cancel, tools.execute(&tc.function.name, args, message_ctx)).await;
+                    if let Some(turns) = active_turns {
+                        turns.set_current_tool(&turn_key, None);
+                    }
+                    let result = match result {
+                        Some(r) => r,
+                        None => {
+                            info!(agent_id = %agent_id, tool = %tc.function.name, "Tool execution aborted by user (/stop or /cancel)");
+                            return Err(anyhow::anyhow!("Cancelled by user (/stop or /cancel)"));
+                        }
+                    };
                     let result_str = match &result {
                         Ok(s) => s.clone(),
                         Err(e) => format!("Error: {e}"),
@@ -1567,13 +2201,39 @@ async fn run_completion_loop(
                         status.to_string(),
                         preview,
                     ));
-                    tool_results.push((tc.id.clone(), result_str));
+                    // Tools may also report failure as an "Error: ..." output.
+                    let succeeded = !result_str.starts_with("Error:");
+                    let outcome = result.as_ref().map(String::clone).map_err(|e| e.to_string());
+                    tool_results.push((
+                        tc.id.clone(),
+                        succeeded,
+                        format_tool_result(tool_result_format, &tc.function.name, &outcome),
+                    ));
                 }
             }
         }
 
+        if tool_loop_detected {
+            warn!(
+                "Repeated identical tool calls (limit {}) for agent '{}'",
+                max_repeated_tool_calls, agent_id
+            );
+            let msg = format!(
+                "[Agent '{}'] 检测到模型连续重复调用相同的 tool（相同参数超过 {} 次），已停止。请换一种方式描述请求。可调整配置 mainAgent.maxRepeatedToolCalls（当前默认 3，0 表示不检测）。",
+                agent_id, max_repeated_tool_calls
+            );
+            let _ = outbound_tx.send(OutboundMessage::chat(
+                channel.to_string(),
+                chat_id.to_string(),
+                msg,
+                vec![],
+                None,
+            ));
+            break;
+        }
+
         if has_tool_calls && !assistant_contents.is_empty() {
-            let any_tool_failed = tool_results.iter().any(|(_, s)| s.starts_with("Error:"));
+            let any_tool_failed = tool_results.iter().any(|(_, ok, _)| !ok);
             if any_tool_failed {
                 consecutive_tool_errors += 1;
             } else {
@@ -1608,7 +2268,7 @@ async fn run_completion_loop(
             if !tool_results.is_empty() {
                 let user_parts: Vec<UserContent> = tool_results
                     .into_iter()
-                    .map(|(id, result_str)| {
+                    .map(|(id, _, result_str)| {
                         UserContent::tool_result(
                             id,
                             OneOrMany::one(ToolResultContent::text(result_str)),
@@ -1631,12 +2291,21 @@ async fn run_completion_loop(
                 if !reply.is_empty() {
                     history.push(Message::assistant(&reply));
                 }
+                let mut media = pending_media.clone();
+                let content = match result_cap {
+                    Some(cap) => {
+                        let (capped, attachment) = cap.apply(&reply);
+                        media.extend(attachment);
+                        capped
+                    }
+                    None => reply,
+                };
                 let out_msg = OutboundMessage::chat(
                     channel.to_string(),
                     chat_id.to_string(),
-                    reply.clone(),
-                    pending_media.clone(),
-                    None,
+                    content,
+                    media,
+                    reply_to.map(String::from),
                 );
                 if let Some(ref h) = hooks {
                     h.dispatch(HookEvent::MessageSent(out_msg.clone())).await;
@@ -1700,3 +2369,719 @@ mod history_window_tests {
     }
 }
 
+
+#[cfg(test)]
+mod tool_loop_tests {
+    use super::*;
+    use crate::tools::DynTool;
+    use rig::completion::{CompletionError, CompletionResponse};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Fake LLM that always asks for the same tool call.
+    struct RepeatingModel;
+
+    impl SynbotCompletionModel for RepeatingModel {
+        fn completion(
+            &self,
+            _request: CompletionRequest,
+        ) -> Pin<
+            Box<dyn Future<Output = Result<CompletionResponse<()>, CompletionError>> + Send + '_>,
+        > {
+            Box::pin(async {
+                Ok(CompletionResponse {
+                    choice: OneOrMany::one(AssistantContent::tool_call(
+                        "call_1",
+                        "counter",
+                        serde_json::json!({"path": "same.txt"}),
+                    )),
+                    usage: rig::completion::Usage {
+                        input_tokens: 0,
+                        output_tokens: 0,
+                        total_tokens: 0,
+                        cached_input_tokens: 0,
+                    },
+                    raw_response: (),
+                })
+            })
+        }
+    }
+
+    struct CounterTool {
+        calls: Arc<AtomicU32>,
+    }
+
+    #[async_trait::async_trait]
+    impl DynTool for CounterTool {
+        fn name(&self) -> &str {
+            "counter"
+        }
+        fn description(&self) -> &str {
+            "Counts calls"
+        }
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {"path": {"type": "string"}}})
+        }
+        async fn call(&self, _args: serde_json::Value) -> Result<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok("same result".to_string())
+        }
+    }
+
+    #[test]
+    fn detector_counts_consecutive_identical_calls() {
+        let mut d = ToolCallLoopDetector::new();
+        let a = serde_json::json!({"path": "a"});
+        let b = serde_json::json!({"path": "b"});
+        assert_eq!(d.record("read_file", &a), 1);
+        assert_eq!(d.record("read_file", &a), 2);
+        assert_eq!(d.record("read_file", &b), 1);
+        assert_eq!(d.record("list_dir", &b), 1);
+        assert_eq!(d.record("list_dir", &b), 2);
+    }
+
+    #[test]
+    fn detector_ignores_argument_key_order() {
+        let mut d = ToolCallLoopDetector::new();
+        let first: serde_json::Value = serde_json::from_str(r#"{"path": "a", "limit": 10}"#).unwrap();
+        let reordered: serde_json::Value = serde_json::from_str(r#"{"limit": 10, "path": "a"}"#).unwrap();
+        assert_eq!(d.record("read_file", &first), 1);
+        assert_eq!(d.record("read_file", &reordered), 2);
+    }
+
+    #[tokio::test]
+    async fn repeated_tool_call_breaks_loop_before_iteration_cap() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let mut tools = ToolRegistry::new();
+        tools
+            .register(Arc::new(CounterTool { calls: Arc::clone(&calls) }))
+            .unwrap();
+        let (outbound_tx, mut outbound_rx) = broadcast::channel(64);
+        let mut history = vec![Message::user("loop please")];
+
+        let iterations = run_completion_loop(
+            &RepeatingModel,
+            "system",
+            1024,
+            0.0,
+            &ReasoningSettings::default(),
+            20,
+            100,
+            3,
+            50,
+            "main",
+            &mut history,
+            &tools.rig_definitions(),
+            &tools,
+            "test",
+            "chat-1",
+            "user-1",
+            None,
+            "test:chat-1",
+            &outbound_tx,
+            None,
+            200,
+            ToolResultFormat::Text,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Calls 1-2 run, call 3 is answered with a nudge, call 4 stops the run.
+        assert_eq!(iterations, 4);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        let mut stopped_notice = false;
+        while let Ok(msg) = outbound_rx.try_recv() {
+            if let crate::bus::OutboundMessageType::Chat { content, .. } = msg.message_type {
+                stopped_notice |= content.contains("maxRepeatedToolCalls");
+            }
+        }
+        assert!(stopped_notice, "user should be told the run was stopped");
+    }
+
+    /// Fake LLM that calls `counter` although no tools were offered, then answers in text.
+    /// Records whether each request carried tool definitions.
+    #[derive(Default)]
+    struct HallucinatingModel {
+        offered_tools: std::sync::Mutex<Vec<usize>>,
+    }
+
+    impl SynbotCompletionModel for HallucinatingModel {
+        fn completion(
+            &self,
+            request: CompletionRequest,
+        ) -> Pin<
+            Box<dyn Future<Output = Result<CompletionResponse<()>, CompletionError>> + Send + '_>,
+        > {
+            let call = {
+                let mut offered = self.offered_tools.lock().unwrap();
+                offered.push(request.tools.len());
+                offered.len()
+            };
+            Box::pin(async move {
+                let choice = if call == 1 {
+                    AssistantContent::tool_call("call_1", "counter", serde_json::json!({"path": "x"}))
+                } else {
+                    AssistantContent::text("plain answer")
+                };
+                Ok(CompletionResponse {
+                    choice: OneOrMany::one(choice),
+                    usage: rig::completion::Usage {
+                        input_tokens: 0,
+                        output_tokens: 0,
+                        total_tokens: 0,
+                        cached_input_tokens: 0,
+                    },
+                    raw_response: (),
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn tools_disabled_sends_no_definitions_and_refuses_tool_calls() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let mut tools = ToolRegistry::new();
+        tools
+            .register(Arc::new(CounterTool { calls: Arc::clone(&calls) }))
+            .unwrap();
+        let (system_prompt, tool_defs) = tools_for_turn("system".to_string(), &tools, false);
+        assert!(tool_defs.is_empty());
+        assert!(system_prompt.contains("Tools are disabled"));
+        let (_, enabled_defs) = tools_for_turn("system".to_string(), &tools, true);
+        assert_eq!(enabled_defs.len(), 1);
+
+        let model = HallucinatingModel::default();
+        let (outbound_tx, mut outbound_rx) = broadcast::channel(64);
+        let mut history = vec![Message::user("just chat")];
+        let iterations = run_completion_loop(
+            &model,
+            &system_prompt,
+            1024,
+            0.0,
+            &ReasoningSettings::default(),
+            10,
+            8,
+            3,
+            50,
+            "main",
+            &mut history,
+            &tool_defs,
+            &tools,
+            "test",
+            "chat-1",
+            "user-1",
+            None,
+            "test:chat-1",
+            &outbound_tx,
+            None,
+            200,
+            ToolResultFormat::Text,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(iterations, 2);
+        assert_eq!(*model.offered_tools.lock().unwrap(), vec![0, 0]);
+        assert_eq!(calls.load(Ordering::SeqCst), 0, "the invented call must not run");
+        assert!(format!("{:?}", history).contains(TOOLS_DISABLED_RESULT));
+        let mut answered = false;
+        while let Ok(msg) = outbound_rx.try_recv() {
+            if let crate::bus::OutboundMessageType::Chat { content, .. } = msg.message_type {
+                answered |= content.contains("plain answer");
+            }
+        }
+        assert!(answered, "the model's text answer still reaches the user");
+    }
+}
+
+#[cfg(test)]
+mod cancel_tests {
+    use super::*;
+    use rig::completion::{CompletionError, CompletionResponse};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::time::{Duration, Instant};
+
+    /// Fake LLM whose call takes far longer than the test is willing to wait.
+    struct SlowModel;
+
+    impl SynbotCompletionModel for SlowModel {
+        fn completion(
+            &self,
+            _request: CompletionRequest,
+        ) -> Pin<
+            Box<dyn Future<Output = Result<CompletionResponse<()>, CompletionError>> + Send + '_>,
+        > {
+            Box::pin(async {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+                Ok(CompletionResponse {
+                    choice: OneOrMany::one(AssistantContent::text("too late")),
+                    usage: rig::completion::Usage {
+                        input_tokens: 0,
+                        output_tokens: 0,
+                        total_tokens: 0,
+                        cached_input_tokens: 0,
+                    },
+                    raw_response: (),
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelling_long_turn_aborts_provider_call_promptly() {
+        let tools = ToolRegistry::new();
+        let (outbound_tx, mut outbound_rx) = broadcast::channel(16);
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            canceller.cancel();
+        });
+
+        let started = Instant::now();
+        let mut history = vec![Message::user("take your time")];
+        let result = run_completion_loop(
+            &SlowModel,
+            "system",
+            1024,
+            0.0,
+            &ReasoningSettings::default(),
+            10,
+            8,
+            3,
+            20,
+            "main",
+            &mut history,
+            &[],
+            &tools,
+            "test",
+            "chat-1",
+            "user-1",
+            None,
+            "test:chat-1",
+            &outbound_tx,
+            None,
+            200,
+            ToolResultFormat::Text,
+            Some(&token),
+            None,
+            None,
+            None,
+        )
+        .await;
+
+        let err = result.expect_err("cancelled turn must not succeed");
+        assert!(err.to_string().contains("Cancelled"));
+        assert!(started.elapsed() < Duration::from_secs(2), "cancel should abort promptly");
+        assert!(outbound_rx.try_recv().is_err(), "no reply is posted for a cancelled turn");
+    }
+
+    #[tokio::test]
+    async fn until_cancelled_passes_through_without_token() {
+        assert_eq!(until_cancelled(None, async { 7 }).await, Some(7));
+        let token = CancellationToken::new();
+        token.cancel();
+        assert_eq!(
+            until_cancelled(Some(&token), tokio::time::sleep(Duration::from_secs(5))).await,
+            None
+        );
+    }
+}
+
+#[cfg(test)]
+mod run_error_tests {
+    use super::*;
+
+    fn heartbeat_msg(on_error: Option<&str>) -> InboundMessage {
+        let mut metadata = serde_json::json!({ "source": "heartbeat" });
+        if let Some(mode) = on_error {
+            metadata["on_error"] = serde_json::json!(mode);
+        }
+        InboundMessage {
+            channel: "telegram".to_string(),
+            sender_id: "u1".to_string(),
+            chat_id: "c1".to_string(),
+            content: "check disk usage".to_string(),
+            timestamp: chrono::Utc::now(),
+            media: vec![],
+            metadata,
+        }
+    }
+
+    fn content(m: &OutboundMessage) -> &str {
+        match &m.message_type {
+            crate::bus::OutboundMessageType::Chat { content, .. } => content,
+            _ => "",
+        }
+    }
+
+    fn report(msg: &InboundMessage) -> Vec<OutboundMessage> {
+        let (tx, mut rx) = broadcast::channel(8);
+        report_run_error(&tx, msg, &anyhow::anyhow!("completion failed: provider down"));
+        let mut out = Vec::new();
+        while let Ok(m) = rx.try_recv() {
+            out.push(m);
+        }
+        out
+    }
+
+    #[test]
+    fn failing_heartbeat_task_posts_to_its_chat_by_default() {
+        for msg in [heartbeat_msg(None), heartbeat_msg(Some("post"))] {
+            let out = report(&msg);
+            assert_eq!(out.len(), 1);
+            assert_eq!(out[0].channel, "telegram");
+            assert_eq!(out[0].chat_id, "c1");
+            assert!(content(&out[0]).contains("provider down"));
+        }
+    }
+
+    #[test]
+    fn failing_heartbeat_task_can_be_silent() {
+        assert!(report(&heartbeat_msg(Some("silent"))).is_empty());
+    }
+
+    #[test]
+    fn failing_heartbeat_task_alerts_the_configured_admin_chat() {
+        let mut msg = heartbeat_msg(Some("system"));
+        msg.metadata["alert_channel"] = serde_json::json!("discord");
+        msg.metadata["alert_chat_id"] = serde_json::json!("ops");
+        let out = report(&msg);
+        assert_eq!(out.len(), 1);
+        assert_eq!((out[0].channel.as_str(), out[0].chat_id.as_str()), ("discord", "ops"));
+        assert!(content(&out[0]).contains("telegram:c1"));
+    }
+
+    #[test]
+    fn system_errors_without_an_alert_chat_are_only_logged() {
+        assert!(report(&heartbeat_msg(Some("system"))).is_empty());
+    }
+
+    #[test]
+    fn non_heartbeat_errors_are_not_posted() {
+        let mut msg = heartbeat_msg(None);
+        msg.metadata = serde_json::json!({});
+        assert!(report(&msg).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod scheduled_result_tests {
+    use super::*;
+
+    fn scheduled_msg(source: &str) -> InboundMessage {
+        InboundMessage {
+            channel: "telegram".to_string(),
+            sender_id: "u1".to_string(),
+            chat_id: "c1".to_string(),
+            content: "dump the logs".to_string(),
+            timestamp: chrono::Utc::now(),
+            media: vec![],
+            metadata: serde_json::json!({ "source": source }),
+        }
+    }
+
+    #[test]
+    fn only_cron_and_heartbeat_results_are_capped() {
+        let cfg = Config::default();
+        let workspace = Path::new("/tmp/ws");
+        assert!(ScheduledResultCap::for_message(&scheduled_msg("cron"), &cfg, workspace).is_some());
+        assert!(ScheduledResultCap::for_message(&scheduled_msg("heartbeat"), &cfg, workspace).is_some());
+        assert!(ScheduledResultCap::for_message(&scheduled_msg("telegram"), &cfg, workspace).is_none());
+        assert_eq!(cfg.cron.result.max_bytes, 8192);
+    }
+
+    #[test]
+    fn oversized_result_is_truncated_with_note() {
+        let cap = ScheduledResultCap::for_message(
+            &scheduled_msg("cron"),
+            &Config::default(),
+            Path::new("/tmp/ws"),
+        )
+        .unwrap();
+        let output = "line of output\n".repeat(100_000);
+
+        let (posted, media) = cap.apply(&output);
+        assert!(posted.len() <= 8192);
+        assert!(posted.starts_with("line of output"));
+        assert!(posted.ends_with(&format!("...truncated ({} bytes total)", output.len())));
+        assert!(media.is_empty());
+
+        let (small, media) = cap.apply("all good");
+        assert_eq!(small, "all good");
+        assert!(media.is_empty());
+    }
+
+    #[test]
+    fn full_output_is_attached_when_enabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = Config::default();
+        cfg.heartbeat.result = config::ScheduledResultConfig {
+            max_bytes: 256,
+            attach_full_output: true,
+        };
+        let cap = ScheduledResultCap::for_message(&scheduled_msg("heartbeat"), &cfg, dir.path()).unwrap();
+        let output = "x".repeat(10_000);
+
+        let (posted, media) = cap.apply(&output);
+        assert!(posted.len() <= 256);
+        assert!(posted.contains("full output attached"));
+        assert_eq!(media.len(), 1);
+        assert_eq!(std::fs::read_to_string(&media[0]).unwrap(), output);
+    }
+}
+
+#[cfg(test)]
+mod inbound_limit_tests {
+    use super::*;
+    use crate::config::{InboundLimitConfig, InboundOverflowPolicy};
+
+    fn msg(content: &str) -> InboundMessage {
+        InboundMessage {
+            channel: "telegram".to_string(),
+            sender_id: "u1".to_string(),
+            chat_id: "c1".to_string(),
+            content: content.to_string(),
+            timestamp: chrono::Utc::now(),
+            media: vec![],
+            metadata: serde_json::Value::Null,
+        }
+    }
+
+    fn limit(max_chars: usize, policy: InboundOverflowPolicy) -> InboundLimitConfig {
+        InboundLimitConfig { max_chars, policy }
+    }
+
+    #[test]
+    fn short_messages_pass_through() {
+        let mut m = msg("hello");
+        assert!(apply_inbound_limit(&mut m, &limit(5, InboundOverflowPolicy::Reject)).is_none());
+        assert_eq!(m.content, "hello");
+    }
+
+    #[test]
+    fn truncate_mode_keeps_prefix_and_adds_note() {
+        let mut m = msg("héllo wörld");
+        assert!(apply_inbound_limit(&mut m, &limit(5, InboundOverflowPolicy::Truncate)).is_none());
+        assert!(m.content.starts_with("héllo\n\n"));
+        assert!(m.content.contains("first 5 of 11 characters"));
+    }
+
+    #[test]
+    fn reject_mode_returns_friendly_reply() {
+        let mut m = msg("hello world");
+        let reply = apply_inbound_limit(&mut m, &limit(5, InboundOverflowPolicy::Reject)).unwrap();
+        assert!(reply.contains("too long"));
+        assert!(reply.contains("attach it as a file"));
+        assert_eq!(m.content, "hello world");
+    }
+
+    #[test]
+    fn scheduled_prompts_are_not_capped() {
+        let mut m = msg("hello world");
+        m.metadata = serde_json::json!({ "source": "cron" });
+        assert!(apply_inbound_limit(&mut m, &limit(5, InboundOverflowPolicy::Reject)).is_none());
+        assert_eq!(m.content, "hello world");
+    }
+
+    #[test]
+    fn limit_is_looked_up_by_channel_name() {
+        let channels: config::ChannelsConfig = serde_json::from_value(serde_json::json!({
+            "telegram": [
+                { "name": "capped", "inboundLimit": { "maxChars": 100, "policy": "reject" } },
+                { "name": "open" }
+            ]
+        }))
+        .unwrap();
+        let capped = channels.inbound_limit("capped").unwrap();
+        assert_eq!(capped.max_chars, 100);
+        assert_eq!(capped.policy, InboundOverflowPolicy::Reject);
+        assert!(channels.inbound_limit("open").is_none());
+        assert!(channels.inbound_limit("missing").is_none());
+    }
+}
+
+#[cfg(test)]
+mod reply_context_tests {
+    use super::*;
+
+    #[test]
+    fn quoted_reply_is_prepended_as_block() {
+        let meta = serde_json::json!({
+            "reply_to": { "message_id": "10", "sender_id": "alice", "content": "line one\nline two" }
+        });
+        assert_eq!(
+            apply_reply_context("summarize this", &meta),
+            "[Replying to a message from alice]\n> line one\n> line two\n\nsummarize this"
+        );
+    }
+
+    #[test]
+    fn missing_or_empty_quote_leaves_content_unchanged() {
+        assert_eq!(apply_reply_context("hi", &serde_json::Value::Null), "hi");
+        let meta = serde_json::json!({ "reply_to": { "content": "  " } });
+        assert_eq!(apply_reply_context("hi", &meta), "hi");
+    }
+
+    #[test]
+    fn long_quote_is_truncated() {
+        let meta = serde_json::json!({ "reply_to": { "content": "x".repeat(5000) } });
+        let out = apply_reply_context("go", &meta);
+        assert!(out.starts_with("[Replying to a message]\n> "));
+        assert!(out.chars().count() < REPLY_CONTEXT_MAX_CHARS + 100);
+        assert!(out.ends_with("\n\ngo"));
+    }
+}
+
+#[cfg(test)]
+mod typing_tests {
+    use super::*;
+    use crate::bus::OutboundMessageType;
+
+    fn msg(metadata: serde_json::Value) -> InboundMessage {
+        InboundMessage {
+            channel: "telegram".to_string(),
+            sender_id: "u1".to_string(),
+            chat_id: "42".to_string(),
+            content: "hello".to_string(),
+            timestamp: chrono::Utc::now(),
+            media: vec![],
+            metadata,
+        }
+    }
+
+    #[test]
+    fn user_message_starts_typing_in_its_chat() {
+        let typing = typing_for(&msg(serde_json::Value::Null)).unwrap();
+        assert_eq!((typing.channel.as_str(), typing.chat_id.as_str()), ("telegram", "42"));
+        assert!(matches!(typing.message_type, OutboundMessageType::Typing));
+        assert!(!typing.ends_typing());
+    }
+
+    #[test]
+    fn scheduled_prompts_do_not_type() {
+        assert!(typing_for(&msg(serde_json::json!({ "source": "cron" }))).is_none());
+        assert!(typing_for(&msg(serde_json::json!({ "source": "heartbeat" }))).is_none());
+    }
+}
+
+#[cfg(test)]
+mod reasoning_tests {
+    use super::*;
+    use rig::completion::{CompletionError, CompletionResponse};
+    use std::future::Future;
+    use std::pin::Pin;
+
+    /// Fake thinking model: returns reasoning plus an answer and records the request parameters.
+    struct ThinkingModel {
+        params: std::sync::Mutex<Vec<Option<serde_json::Value>>>,
+    }
+
+    impl SynbotCompletionModel for ThinkingModel {
+        fn completion(
+            &self,
+            request: CompletionRequest,
+        ) -> Pin<
+            Box<dyn Future<Output = Result<CompletionResponse<()>, CompletionError>> + Send + '_>,
+        > {
+            self.params.lock().unwrap().push(request.additional_params);
+            Box::pin(async {
+                Ok(CompletionResponse {
+                    choice: OneOrMany::many(vec![
+                        AssistantContent::reasoning("secret chain of thought"),
+                        AssistantContent::text("The answer is 42."),
+                    ])
+                    .unwrap(),
+                    usage: rig::completion::Usage {
+                        input_tokens: 0,
+                        output_tokens: 0,
+                        total_tokens: 0,
+                        cached_input_tokens: 0,
+                    },
+                    raw_response: (),
+                })
+            })
+        }
+    }
+
+    async fn run(reasoning: &ReasoningSettings) -> (ThinkingModel, Vec<String>) {
+        let model = ThinkingModel {
+            params: std::sync::Mutex::new(Vec::new()),
+        };
+        let tools = ToolRegistry::new();
+        let (outbound_tx, mut outbound_rx) = broadcast::channel(16);
+        let mut history = vec![Message::user("think hard")];
+        run_completion_loop(
+            &model,
+            "system",
+            8192,
+            1.0,
+            reasoning,
+            5,
+            8,
+            3,
+            20,
+            "main",
+            &mut history,
+            &[],
+            &tools,
+            "test",
+            "chat-1",
+            "user-1",
+            None,
+            "test:chat-1",
+            &outbound_tx,
+            None,
+            200,
+            ToolResultFormat::Text,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let mut posted = Vec::new();
+        while let Ok(msg) = outbound_rx.try_recv() {
+            if let crate::bus::OutboundMessageType::Chat { content, .. } = msg.message_type {
+                posted.push(content);
+            }
+        }
+        (model, posted)
+    }
+
+    #[tokio::test]
+    async fn reasoning_params_reach_the_provider_and_reasoning_is_not_posted() {
+        let settings = ReasoningSettings {
+            request_params: Some(serde_json::json!({ "thinking": { "type": "enabled", "budget_tokens": 2048 } })),
+            show_in_channel: false,
+        };
+        let (model, posted) = run(&settings).await;
+        assert_eq!(*model.params.lock().unwrap(), vec![settings.request_params.clone()]);
+        assert_eq!(posted, vec!["The answer is 42.".to_string()]);
+        assert!(posted.iter().all(|p| !p.contains("chain of thought")));
+    }
+
+    #[tokio::test]
+    async fn reasoning_is_posted_before_the_answer_when_enabled() {
+        let settings = ReasoningSettings {
+            request_params: None,
+            show_in_channel: true,
+        };
+        let (model, posted) = run(&settings).await;
+        assert_eq!(*model.params.lock().unwrap(), vec![None]);
+        assert_eq!(posted.len(), 2);
+        assert!(posted[0].starts_with("[Reasoning]") && posted[0].contains("secret chain of thought"));
+        assert_eq!(posted[1], "The answer is 42.");
+    }
+}
@@ -1,12 +1,19 @@
-//! Memory backend abstraction — trait and default file+SQLite implementation.
+//! Memory backend abstraction — trait, default file+SQLite implementation and the SQLite-only
+//! store selected by `memory.backend: "sqlite"`.
 
+use std::path::PathBuf;
 use std::sync::Arc;
 
-use chrono::NaiveDate;
+use chrono::{Local, NaiveDate};
+use rusqlite::Connection;
 
+use crate::agent::embeddings::{stub_embedding, try_embed_query_sync};
 use crate::agent::memory::MemoryStore;
-use crate::agent::memory_index::{hybrid_search_with_config, reindex_agent_blocking, IndexedChunk};
-use crate::config::{memory_dir, MemoryConfig, Config};
+use crate::agent::memory_index::{
+    create_tables_if_needed, embedding_as_bytes, hybrid_search_with_config, index_chunk_with_embedding,
+    open_database, reindex_agent_blocking, IndexedChunk,
+};
+use crate::config::{memory_dir, memory_root, MemoryConfig, Config};
 
 /// Options for building memory context (e.g. recent days, use search).
 #[derive(Debug, Clone)]
@@ -193,3 +200,277 @@ impl MemoryBackend for FileSqliteMemoryBackend {
         reindex_agent_blocking(agent_id, &self.config)
     }
 }
+
+/// `memory.backend` value that selects [`SqliteMemoryBackend`].
+pub const SQLITE_BACKEND: &str = "sqlite";
+
+/// True when `memory.backend` selects the SQLite store instead of the default files.
+pub fn is_sqlite_backend(config: &MemoryConfig) -> bool {
+    config.backend.trim().eq_ignore_ascii_case(SQLITE_BACKEND)
+}
+
+/// Backend selected by `memory.backend`: `sqlite`, or the file backend when empty / `file`.
+pub fn memory_backend_for(config: Arc<Config>) -> Box<dyn MemoryBackend> {
+    if is_sqlite_backend(&config.memory) {
+        Box::new(SqliteMemoryBackend::new(config))
+    } else {
+        Box::new(FileSqliteMemoryBackend::new(config))
+    }
+}
+
+/// Database file of [`SqliteMemoryBackend`] in the agent's memory dir.
+const SQLITE_STORE_FILE: &str = "store.sqlite";
+
+/// Source of long-term entries; daily notes use their date (`YYYY-MM-DD`).
+const LONG_TERM_SOURCE: &str = "long_term";
+
+/// `memory.backend: "sqlite"`: entries live only in `{memory dir}/{agentId}/store.sqlite`, in the
+/// same `chunks` / `memory_fts` / `vec_embeddings` tables the file index uses. Each write is
+/// embedded and indexed at once, so there is nothing to reindex from disk.
+pub struct SqliteMemoryBackend {
+    config: Arc<Config>,
+    root: PathBuf,
+}
+
+impl SqliteMemoryBackend {
+    pub fn new(config: Arc<Config>) -> Self {
+        Self::with_root(config, memory_root())
+    }
+
+    /// Store agent databases under `root` instead of the configured memory root.
+    pub fn with_root(config: Arc<Config>, root: PathBuf) -> Self {
+        Self { config, root }
+    }
+
+    fn db_path(&self, agent_id: &str) -> PathBuf {
+        let id = if agent_id.trim().is_empty() {
+            "main"
+        } else {
+            agent_id
+        };
+        self.root.join(id).join(SQLITE_STORE_FILE)
+    }
+
+    /// Open the agent's store. The vector width is kept in `user_version`; when
+    /// `embeddingDimensions` changes, the vectors are rebuilt from the stored text.
+    fn open(&self, agent_id: &str) -> anyhow::Result<Connection> {
+        let path = self.db_path(agent_id);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let conn = open_database(&path)?;
+        let dim = self.config.memory.embedding_dimensions;
+        create_tables_if_needed(&conn, dim)?;
+        let stored: u32 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+        if stored != dim {
+            if stored != 0 {
+                self.rebuild_vectors(&conn)?;
+            }
+            conn.pragma_update(None, "user_version", dim)?;
+        }
+        Ok(conn)
+    }
+
+    fn embed(&self, text: &str) -> Vec<f32> {
+        try_embed_query_sync(&self.config, text)
+            .unwrap_or_else(|| stub_embedding(self.config.memory.embedding_dimensions))
+    }
+
+    /// Recreate `vec_embeddings` at the configured width and embed every stored entry again.
+    fn rebuild_vectors(&self, conn: &Connection) -> anyhow::Result<usize> {
+        conn.execute_batch(&format!(
+            "DROP TABLE IF EXISTS vec_embeddings;
+             CREATE VIRTUAL TABLE vec_embeddings USING vec0(
+                 embedding float[{dim}],
+                 +chunk_id INTEGER,
+                 +content TEXT,
+                 +source TEXT
+             );",
+            dim = self.config.memory.embedding_dimensions
+        ))?;
+        let rows: Vec<(i64, String, String)> = conn
+            .prepare("SELECT id, source, content FROM chunks ORDER BY id")?
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?
+            .collect::<Result<_, _>>()?;
+        for (id, source, content) in &rows {
+            let embedding = self.embed(content);
+            conn.execute(
+                "INSERT INTO vec_embeddings (embedding, chunk_id, content, source) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![embedding_as_bytes(&embedding), id, content, source],
+            )?;
+        }
+        Ok(rows.len())
+    }
+
+    fn insert(&self, agent_id: &str, source: &str, content: &str) -> anyhow::Result<()> {
+        let content = content.trim();
+        if content.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.open(agent_id)?;
+        let embedding = self.embed(content);
+        index_chunk_with_embedding(&mut conn, source, content, &embedding)?;
+        Ok(())
+    }
+
+    fn read_source(
+        conn: &Connection,
+        sql: &str,
+        params: impl rusqlite::Params,
+    ) -> anyhow::Result<Vec<(String, String)>> {
+        Ok(conn
+            .prepare(sql)?
+            .query_map(params, |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect::<Result<_, _>>()?)
+    }
+}
+
+impl MemoryBackend for SqliteMemoryBackend {
+    fn get_memory_context(
+        &self,
+        agent_id: &str,
+        options: &MemoryContextOptions,
+    ) -> anyhow::Result<String> {
+        let conn = self.open(agent_id)?;
+        let mut parts = Vec::new();
+
+        let long_term = Self::read_source(
+            &conn,
+            "SELECT source, content FROM chunks WHERE source = ?1 ORDER BY id",
+            [LONG_TERM_SOURCE],
+        )?
+        .into_iter()
+        .map(|(_, content)| content)
+        .collect::<Vec<_>>()
+        .join("\n\n");
+        if !long_term.is_empty() {
+            let maxc = self.config.memory.long_term_max_chars;
+            let lt = if maxc > 0 {
+                truncate_long_term_text(&long_term, maxc)
+            } else {
+                long_term
+            };
+            parts.push(format!("## Long-term Memory\n\n{}", lt));
+        }
+
+        let days = options.recent_days.max(1);
+        let oldest = Local::now().date_naive() - chrono::Duration::days(days as i64 - 1);
+        let notes = Self::read_source(
+            &conn,
+            "SELECT source, content FROM chunks WHERE source <> ?1 AND source >= ?2 ORDER BY source DESC, id",
+            rusqlite::params![LONG_TERM_SOURCE, oldest.format("%Y-%m-%d").to_string()],
+        )?;
+        let mut by_day: Vec<(String, Vec<String>)> = Vec::new();
+        for (day, content) in notes {
+            match by_day.last_mut() {
+                Some((d, entries)) if *d == day => entries.push(content),
+                _ => by_day.push((day, vec![content])),
+            }
+        }
+        if !by_day.is_empty() {
+            let recent = by_day
+                .into_iter()
+                .map(|(_, entries)| entries.join("\n\n"))
+                .collect::<Vec<_>>()
+                .join("\n\n---\n\n");
+            parts.push(format!("## Recent Notes ({} days)\n\n{}", days, recent));
+        }
+
+        if let Some(q) = options.query_for_search.as_deref().map(str::trim) {
+            if !q.is_empty() {
+                let hits = hybrid_search_with_config(&conn, q, options.search_limit.min(10), &self.config)?;
+                if !hits.is_empty() {
+                    let search_block: String = hits
+                        .into_iter()
+                        .map(|c| format!("- [{}] {}", c.source, c.content))
+                        .collect::<Vec<_>>()
+                        .join("\n\n");
+                    parts.push(format!("## Relevant memory (search)\n\n{}", search_block));
+                }
+            }
+        }
+
+        Ok(parts.join("\n\n"))
+    }
+
+    fn search(
+        &self,
+        agent_id: &str,
+        query: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<IndexedChunk>> {
+        let conn = self.open(agent_id)?;
+        hybrid_search_with_config(&conn, query, limit, &self.config)
+    }
+
+    fn append_long_term(&self, agent_id: &str, content: &str) -> anyhow::Result<()> {
+        self.insert(agent_id, LONG_TERM_SOURCE, content)
+    }
+
+    fn append_daily_note(
+        &self,
+        agent_id: &str,
+        date: NaiveDate,
+        content: &str,
+    ) -> anyhow::Result<()> {
+        self.insert(agent_id, &date.format("%Y-%m-%d").to_string(), content)
+    }
+
+    /// Re-embed every entry (e.g. after switching `embeddingProvider`).
+    fn index_now(&self, agent_id: &str) -> anyhow::Result<usize> {
+        let conn = self.open(agent_id)?;
+        self.rebuild_vectors(&conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sqlite_backend(root: &std::path::Path) -> SqliteMemoryBackend {
+        let mut cfg = Config::default();
+        cfg.memory.backend = SQLITE_BACKEND.into();
+        cfg.memory.embedding_dimensions = 8;
+        SqliteMemoryBackend::with_root(Arc::new(cfg), root.to_path_buf())
+    }
+
+    #[test]
+    fn sqlite_backend_stores_and_searches_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = sqlite_backend(dir.path());
+        backend
+            .append_long_term("main", "The user prefers Rust for backend services.")
+            .unwrap();
+        backend
+            .append_daily_note("main", Local::now().date_naive(), "Planned the launch with Alice.")
+            .unwrap();
+        backend.append_long_term("helper", "Helper-only note about Rust.").unwrap();
+        assert!(dir.path().join("main").join(SQLITE_STORE_FILE).is_file());
+
+        let hits = backend.search("main", "Rust", 5).unwrap();
+        assert!(!hits.is_empty());
+        assert!(hits.iter().all(|c| !c.content.contains("Helper-only")));
+        let top = &hits[0];
+        assert_eq!(top.source, LONG_TERM_SOURCE);
+        assert!(top.content.contains("prefers Rust"));
+
+        let ctx = backend
+            .get_memory_context("main", &MemoryContextOptions::default())
+            .unwrap();
+        assert!(ctx.contains("## Long-term Memory\n\nThe user prefers Rust"));
+        assert!(ctx.contains("Planned the launch with Alice."));
+    }
+
+    #[test]
+    fn sqlite_backend_rebuilds_vectors_when_dimensions_change() {
+        let dir = tempfile::tempdir().unwrap();
+        sqlite_backend(dir.path()).append_long_term("main", "kept across widths").unwrap();
+
+        let mut cfg = Config::default();
+        cfg.memory.embedding_dimensions = 16;
+        let wider = SqliteMemoryBackend::with_root(Arc::new(cfg), dir.path().to_path_buf());
+        let hits = wider.search("main", "widths", 5).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(wider.index_now("main").unwrap(), 1);
+    }
+}
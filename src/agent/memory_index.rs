@@ -62,17 +62,21 @@ fn maybe_reset_sqlite_for_dim_change(agent_id: &str, dim: u32) -> Result<()> {
 
 /// Opens the index DB for an agent and ensures schema exists.
 pub fn open_index(agent_id: &str, embedding_dim: u32) -> Result<Connection> {
-    ensure_vec_extension();
     maybe_reset_sqlite_for_dim_change(agent_id, embedding_dim)?;
     let dir = config::memory_dir(agent_id);
     std::fs::create_dir_all(&dir).context("create memory dir")?;
-    let db_path = db_path(agent_id);
-    let conn = Connection::open(&db_path).context("open index db")?;
+    let conn = open_database(&db_path(agent_id))?;
     create_tables_if_needed(&conn, embedding_dim)?;
     Ok(conn)
 }
 
-fn create_tables_if_needed(conn: &Connection, embedding_dim: u32) -> Result<()> {
+/// Opens a SQLite file with the sqlite-vec extension available.
+pub(crate) fn open_database(path: &std::path::Path) -> Result<Connection> {
+    ensure_vec_extension();
+    Connection::open(path).context("open index db")
+}
+
+pub(crate) fn create_tables_if_needed(conn: &Connection, embedding_dim: u32) -> Result<()> {
     let vec_sql = format!(
         r#"
         CREATE TABLE IF NOT EXISTS chunks (
@@ -107,8 +111,105 @@ pub struct IndexedChunk {
     pub score: Option<f64>,
 }
 
+/// Characters of a chunk shown around the first match by [`snippet`].
+pub const SNIPPET_CHARS: usize = 240;
+
+/// Excerpt of a search hit around the query terms it contains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemorySnippet {
+    /// The excerpt, with `…` where the chunk was cut.
+    pub text: String,
+    /// Byte ranges of the query terms within `text`, in order.
+    pub matches: Vec<std::ops::Range<usize>>,
+}
+
+impl MemorySnippet {
+    /// `text` with each match wrapped in `**`.
+    pub fn highlighted(&self) -> String {
+        let mut out = String::with_capacity(self.text.len() + 4 * self.matches.len());
+        let mut pos = 0;
+        for m in &self.matches {
+            out.push_str(&self.text[pos..m.start]);
+            out.push_str("**");
+            out.push_str(&self.text[m.clone()]);
+            out.push_str("**");
+            pos = m.end;
+        }
+        out.push_str(&self.text[pos..]);
+        out
+    }
+}
+
+/// Byte ranges of the query terms in `content`, in order. Terms are the words of `query`,
+/// matched without regard to ASCII case.
+pub fn match_ranges(content: &str, query: &str) -> Vec<std::ops::Range<usize>> {
+    let mut terms: Vec<&str> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .collect();
+    // Longest first, so "memory" wins over "me" at the same position.
+    terms.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    terms.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+
+    let bytes = content.as_bytes();
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let term = content.is_char_boundary(i).then(|| {
+            terms.iter().find(|t| {
+                bytes.len() - i >= t.len() && bytes[i..i + t.len()].eq_ignore_ascii_case(t.as_bytes())
+            })
+        });
+        match term.flatten() {
+            Some(t) => {
+                found.push(i..i + t.len());
+                i += t.len();
+            }
+            None => i += 1,
+        }
+    }
+    found
+}
+
+/// Up to `max_chars` characters of `content` around the first occurrence of a query term, with
+/// the positions of every term it contains (see [`match_ranges`]). A hit found only by vector
+/// similarity gets the start of the chunk.
+pub fn snippet(content: &str, query: &str, max_chars: usize) -> MemorySnippet {
+    let content = content.trim();
+    let found = match_ranges(content, query);
+    let total = content.chars().count();
+    if total <= max_chars {
+        return MemorySnippet {
+            text: content.to_string(),
+            matches: found,
+        };
+    }
+    let byte_at = |n: usize| {
+        content
+            .char_indices()
+            .nth(n)
+            .map(|(b, _)| b)
+            .unwrap_or(content.len())
+    };
+    let anchor = found.first().map_or(0, |m| content[..m.start].chars().count());
+    let first_char = anchor.saturating_sub(max_chars / 3).min(total - max_chars);
+    let start = byte_at(first_char);
+    let end = byte_at(first_char + max_chars);
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < content.len() { "…" } else { "" };
+    let shift = |m: std::ops::Range<usize>| m.start - start + prefix.len()..m.end - start + prefix.len();
+    MemorySnippet {
+        text: format!("{}{}{}", prefix, &content[start..end], suffix),
+        matches: found
+            .into_iter()
+            .filter(|m| m.start >= start && m.end <= end)
+            .map(shift)
+            .collect(),
+    }
+}
+
 /// Cast f32 slice to u8 for sqlite-vec binary format.
-fn embedding_as_bytes(embedding: &[f32]) -> &[u8] {
+pub(crate) fn embedding_as_bytes(embedding: &[f32]) -> &[u8] {
     unsafe {
         std::slice::from_raw_parts(
             embedding.as_ptr() as *const u8,
@@ -489,4 +590,30 @@ mod tests {
         let c = chunk_text(&s, 1000);
         assert!(c.len() >= 5);
     }
+
+    #[test]
+    fn snippet_marks_query_terms_with_offsets() {
+        let s = snippet("The user prefers Rust; rust-analyzer is set up.", "rust setup", 240);
+        assert_eq!(s.text, "The user prefers Rust; rust-analyzer is set up.");
+        assert_eq!(s.matches, vec![17..21, 23..27]);
+        assert_eq!(&s.text[s.matches[0].clone()], "Rust");
+        assert_eq!(s.highlighted(), "The user prefers **Rust**; **rust**-analyzer is set up.");
+    }
+
+    #[test]
+    fn snippet_windows_long_chunks_around_the_first_match() {
+        let content = format!("{} deadline moved to Friday {}", "x".repeat(300), "y".repeat(300));
+        let s = snippet(&content, "Deadline", 60);
+        assert!(s.text.starts_with('…') && s.text.ends_with('…'));
+        assert_eq!(s.matches.len(), 1);
+        assert_eq!(&s.text[s.matches[0].clone()], "deadline");
+        assert!(s.text.contains("deadline moved to Friday"));
+
+        // Offsets into the whole chunk, not the excerpt.
+        assert_eq!(match_ranges(&content, "Deadline"), vec![301..309]);
+
+        let none = snippet(&content, "absent", 60);
+        assert!(none.matches.is_empty());
+        assert!(none.text.starts_with("xxx") && none.text.ends_with('…'));
+    }
 }
@@ -1,8 +1,12 @@
+pub mod active_turns;
 pub mod agent_registry;
+pub mod channel_models;
+pub mod chat_queue;
 pub mod control_commands;
 pub mod context;
 pub mod directive;
 pub mod embedded_tool_calls;
+pub mod language;
 pub mod memory;
 pub mod embeddings;
 #[cfg(feature = "memory-index")]
@@ -11,6 +15,7 @@ pub mod memory_backend;
 pub mod memory_index;
 pub mod session_compactor;
 pub mod r#loop;
+pub mod response_transforms;
 pub mod role_registry;
 pub mod session;
 pub mod session_id;
@@ -19,3 +24,4 @@ pub mod session_state;
 pub mod timestamp_serde;
 pub mod skills;
 pub mod subagent;
+pub mod turn_limiter;
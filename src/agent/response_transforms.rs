@@ -0,0 +1,165 @@
+//! Response transforms -- post-processing of outgoing chat messages before they are sent.
+//!
+//! Built from `responseTransforms` in config: the global list followed by the list for the
+//! message's channel, applied in order. Used for footers (disclaimers in regulated channels) and
+//! for stripping content that must not reach users. [`spawn_relay`] applies them to everything
+//! published on the outbound bus, so replies, reasoning posts and subagent results all pass
+//! through the same pipeline.
+
+use std::collections::HashMap;
+
+use regex::Regex;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::bus::{OutboundMessage, OutboundMessageType};
+use crate::config::{ResponseTransformConfig, ResponseTransformsConfig};
+
+enum Transform {
+    AppendFooter(String),
+    StripRegex { regex: Regex, replacement: String },
+}
+
+impl Transform {
+    fn apply(&self, text: &str) -> String {
+        match self {
+            Transform::AppendFooter(footer) if text.is_empty() => footer.clone(),
+            Transform::AppendFooter(footer) => format!("{}\n\n{}", text.trim_end(), footer),
+            Transform::StripRegex { regex, replacement } => {
+                regex.replace_all(text, replacement.as_str()).into_owned()
+            }
+        }
+    }
+}
+
+/// Ordered transform pipeline for one channel.
+pub struct ResponseTransforms {
+    steps: Vec<Transform>,
+}
+
+impl ResponseTransforms {
+    /// Pipeline for `channel`, or `None` when no transform applies. Invalid regexes are
+    /// rejected by config validation; any that slip through are logged and skipped.
+    pub fn for_channel(config: &ResponseTransformsConfig, channel: &str) -> Option<Self> {
+        let steps: Vec<Transform> = config
+            .for_channel(channel)
+            .filter_map(|t| match t {
+                ResponseTransformConfig::AppendFooter { text } => Some(Transform::AppendFooter(text.clone())),
+                ResponseTransformConfig::StripRegex { pattern, replacement } => match Regex::new(pattern) {
+                    Ok(regex) => Some(Transform::StripRegex {
+                        regex,
+                        replacement: replacement.clone(),
+                    }),
+                    Err(e) => {
+                        warn!(pattern = %pattern, error = %e, "Skipping invalid strip_regex response transform");
+                        None
+                    }
+                },
+            })
+            .collect();
+        (!steps.is_empty()).then_some(Self { steps })
+    }
+
+    /// Run every transform on `reply`, in order.
+    pub fn apply(&self, reply: &str) -> String {
+        self.steps
+            .iter()
+            .fold(reply.to_string(), |text, step| step.apply(&text))
+    }
+}
+
+/// Forward every message from `rx` to `tx`, transforming the content of chat messages. Each
+/// channel's pipeline is compiled once, on its first message; other message types pass through.
+pub fn spawn_relay(
+    config: ResponseTransformsConfig,
+    mut rx: broadcast::Receiver<OutboundMessage>,
+    tx: broadcast::Sender<OutboundMessage>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut pipelines: HashMap<String, Option<ResponseTransforms>> = HashMap::new();
+        loop {
+            let mut msg = match rx.recv().await {
+                Ok(msg) => msg,
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    warn!(skipped = n, "Response transforms relay lagged, messages dropped");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            if let OutboundMessageType::Chat { content, .. } = &mut msg.message_type {
+                let pipeline = pipelines
+                    .entry(msg.channel.clone())
+                    .or_insert_with(|| ResponseTransforms::for_channel(&config, &msg.channel));
+                if let Some(pipeline) = pipeline {
+                    *content = pipeline.apply(content);
+                }
+            }
+            let _ = tx.send(msg);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn footer(text: &str) -> ResponseTransformConfig {
+        ResponseTransformConfig::AppendFooter { text: text.into() }
+    }
+
+    fn strip(pattern: &str) -> ResponseTransformConfig {
+        ResponseTransformConfig::StripRegex {
+            pattern: pattern.into(),
+            replacement: String::new(),
+        }
+    }
+
+    #[test]
+    fn transforms_run_in_order_global_then_channel() {
+        let mut config = ResponseTransformsConfig {
+            global: vec![strip(r"\s*\[internal:[^\]]*\]")],
+            ..Default::default()
+        };
+        config
+            .channels
+            .insert("telegram".into(), vec![footer("Not investment advice."), strip("(?i)advice")]);
+
+        let telegram = ResponseTransforms::for_channel(&config, "telegram").unwrap();
+        // The strip after the footer also rewrites the footer.
+        assert_eq!(
+            telegram.apply("Buy low. [internal: ticket 42]\n"),
+            "Buy low.\n\nNot investment ."
+        );
+
+        let discord = ResponseTransforms::for_channel(&config, "discord").unwrap();
+        assert_eq!(discord.apply("Buy low. [internal: ticket 42]"), "Buy low.");
+    }
+
+    #[test]
+    fn strip_regex_uses_replacement_and_footer_appends() {
+        let config = ResponseTransformsConfig {
+            global: vec![
+                ResponseTransformConfig::StripRegex {
+                    pattern: r"\b\d{4}-\d{4}-\d{4}-(\d{4})\b".into(),
+                    replacement: "****-$1".into(),
+                },
+                footer("-- sent by synbot"),
+            ],
+            ..Default::default()
+        };
+        let pipeline = ResponseTransforms::for_channel(&config, "web").unwrap();
+        assert_eq!(
+            pipeline.apply("Card 1234-5678-9012-3456 is active."),
+            "Card ****-3456 is active.\n\n-- sent by synbot"
+        );
+        assert_eq!(pipeline.apply(""), "-- sent by synbot");
+    }
+
+    #[test]
+    fn no_pipeline_without_transforms() {
+        let mut config = ResponseTransformsConfig::default();
+        assert!(ResponseTransforms::for_channel(&config, "web").is_none());
+        config.global.push(strip("([unclosed"));
+        assert!(ResponseTransforms::for_channel(&config, "web").is_none());
+    }
+}
@@ -1,4 +1,6 @@
 //! Compress long chat history: LLM summary + optional append to MEMORY.md.
+//!
+//! The summary request is also used on demand by the `summarize_conversation` tool.
 
 use std::sync::Arc;
 
@@ -7,7 +9,7 @@ use rig::completion::CompletionRequest;
 use rig::message::{AssistantContent, Message};
 use rig::OneOrMany;
 
-use crate::agent::memory_backend::{should_compress, FileSqliteMemoryBackend, MemoryBackend};
+use crate::agent::memory_backend::{memory_backend_for, should_compress};
 use crate::agent::r#loop::fix_window_start_for_tool_results;
 use crate::config::Config;
 use crate::rig_provider::SynbotCompletionModel;
@@ -72,9 +74,32 @@ pub async fn maybe_compact_history(
     }
 
     let prefix: Vec<Message> = history.drain(..remove_n).collect();
-    let transcript = messages_to_text(&prefix);
-    if transcript.trim().is_empty() {
+    let Some(summary_text) = summarize_messages(model, &prefix).await? else {
         return Ok(());
+    };
+
+    let block = format!(
+        "[Conversation summary — earlier messages compressed]\n\n{}",
+        summary_text
+    );
+    history.insert(0, Message::user(&block));
+
+    if comp.summary_write_to_memory {
+        write_summary_to_memory(cfg, agent_id, "Auto summary", &summary_text);
+    }
+
+    Ok(())
+}
+
+/// Ask the model for a bullet-point summary of the user and assistant text in `msgs`.
+/// `None` when there is no text to summarize or the model returns an empty answer.
+pub async fn summarize_messages(
+    model: &dyn SynbotCompletionModel,
+    msgs: &[Message],
+) -> Result<Option<String>> {
+    let transcript = messages_to_text(msgs);
+    if transcript.trim().is_empty() {
+        return Ok(None);
     }
 
     let summarize_prompt = format!(
@@ -105,25 +130,17 @@ pub async fn maybe_compact_history(
         }
     }
     let summary_text = summary_text.trim().to_string();
-    if summary_text.is_empty() {
-        return Ok(());
-    }
+    Ok((!summary_text.is_empty()).then_some(summary_text))
+}
 
-    let block = format!(
-        "[Conversation summary — earlier messages compressed]\n\n{}",
-        summary_text
+/// Append `summary` to the agent's long-term memory under a dated `## {title}` heading.
+pub fn write_summary_to_memory(cfg: &Config, agent_id: &str, title: &str, summary: &str) {
+    let backend = memory_backend_for(Arc::new(cfg.clone()));
+    let note = format!(
+        "\n## {} ({})\n\n{}",
+        title,
+        chrono::Local::now().format("%Y-%m-%d %H:%M"),
+        summary
     );
-    history.insert(0, Message::user(&block));
-
-    if comp.summary_write_to_memory {
-        let backend = FileSqliteMemoryBackend::new(Arc::new(cfg.clone()));
-        let note = format!(
-            "\n## Auto summary ({})\n\n{}",
-            chrono::Local::now().format("%Y-%m-%d %H:%M"),
-            summary_text
-        );
-        let _ = backend.append_long_term(agent_id, &note);
-    }
-
-    Ok(())
+    let _ = backend.append_long_term(agent_id, &note);
 }
@@ -10,6 +10,18 @@ use std::collections::HashMap;
 use crate::agent::session::SessionMessage;
 use crate::agent::session_id::{SessionId, SessionScope};
 use crate::agent::timestamp_serde;
+use crate::config::IdentityLinkConfig;
+
+/// Channel component of the session ids of linked identities (see [`SessionManager::set_identity_links`]).
+pub const LINKED_SESSION_CHANNEL: &str = "linked";
+
+/// Whether message metadata marks a group chat (truthy `"group"` key).
+fn is_group_message(metadata: &serde_json::Value) -> bool {
+    metadata
+        .get("group")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
 
 // ---------------------------------------------------------------------------
 // Session metadata
@@ -38,6 +50,8 @@ pub struct SessionMeta {
 pub struct SessionManager {
     /// Active sessions keyed by `SessionId`.
     sessions: HashMap<SessionId, (SessionMeta, Vec<SessionMessage>)>,
+    /// `(channel, chat_id)` of linked identities -> name of their shared link.
+    identity_links: HashMap<(String, String), String>,
 }
 
 impl SessionManager {
@@ -45,9 +59,23 @@ impl SessionManager {
     pub fn new() -> Self {
         Self {
             sessions: HashMap::new(),
+            identity_links: HashMap::new(),
         }
     }
 
+    /// Replace the cross-channel identity links (config `identityLinks`). DMs from any member of
+    /// a link resolve to one session, `agent:<agentId>:linked:dm:<link name>`.
+    pub fn set_identity_links(&mut self, links: &[IdentityLinkConfig]) {
+        self.identity_links = links
+            .iter()
+            .flat_map(|link| {
+                link.parsed_members().map(|(channel, chat_id)| {
+                    ((channel.to_string(), chat_id.to_string()), link.name.clone())
+                })
+            })
+            .collect();
+    }
+
     // ── Session resolution ──────────────────────────────────────────
 
     /// Determine the [`SessionId`] for an incoming message based on the
@@ -55,7 +83,8 @@ impl SessionManager {
     ///
     /// If metadata contains a truthy `"group"` key (e.g. Telegram/Discord/Feishu, or
     /// DingTalk when `conversationType` is group), uses `Group` scope; otherwise `Dm`.
-    /// Identifier is always `chat_id` (for DingTalk, the `conversationId`).
+    /// Identifier is always `chat_id` (for DingTalk, the `conversationId`), except for DMs
+    /// from a linked identity, which share the link's session across channels.
     pub fn resolve_session(
         &self,
         agent_id: &str,
@@ -63,11 +92,10 @@ impl SessionManager {
         chat_id: &str,
         metadata: &serde_json::Value,
     ) -> SessionId {
-        let is_group = metadata
-            .get("group")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
-        let scope = if is_group {
+        if let Some(link) = self.identity_link(channel, chat_id, metadata) {
+            return SessionId::full(agent_id, LINKED_SESSION_CHANNEL, SessionScope::Dm, link);
+        }
+        let scope = if is_group_message(metadata) {
             SessionScope::Group
         } else {
             SessionScope::Dm
@@ -75,6 +103,17 @@ impl SessionManager {
         SessionId::full(agent_id, channel, scope, chat_id)
     }
 
+    /// Name of the identity link a message belongs to: set for DMs from a linked identity,
+    /// whose turns share one conversation across channels.
+    pub fn identity_link(&self, channel: &str, chat_id: &str, metadata: &serde_json::Value) -> Option<&str> {
+        if is_group_message(metadata) {
+            return None;
+        }
+        self.identity_links
+            .get(&(channel.to_string(), chat_id.to_string()))
+            .map(String::as_str)
+    }
+
     // ── Session CRUD ────────────────────────────────────────────────
 
     /// Get or create a session, returning a mutable reference to its message
@@ -164,6 +203,21 @@ impl SessionManager {
         self.sessions.get(id).map(|(meta, _)| meta)
     }
 
+    /// Agent of the most recently updated session in the same conversation as `id` (same channel,
+    /// scope and identifier, any agent) among the agents accepted by `candidate`.
+    pub fn latest_session_agent(&self, id: &SessionId, candidate: impl Fn(&str) -> bool) -> Option<&str> {
+        self.sessions
+            .iter()
+            .filter(|(sid, _)| {
+                sid.channel == id.channel
+                    && sid.scope == id.scope
+                    && sid.identifier == id.identifier
+                    && candidate(&sid.agent_id)
+            })
+            .max_by_key(|(_, (meta, _))| meta.updated_at)
+            .map(|(sid, _)| sid.agent_id.as_str())
+    }
+
     /// Get the number of active sessions.
     pub fn session_count(&self) -> usize {
         self.sessions.len()
@@ -229,6 +283,31 @@ mod tests {
         assert_eq!(sid.identifier, Some("user_1".into()));
     }
 
+    #[test]
+    fn linked_identities_share_one_session() {
+        let mut mgr = manager();
+        mgr.set_identity_links(&[IdentityLinkConfig {
+            name: "alice".into(),
+            members: vec!["telegram:111".into(), "discord:222".into()],
+        }]);
+        let dm = json!({});
+        let from_telegram = mgr.resolve_session("main", "telegram", "111", &dm);
+        let from_discord = mgr.resolve_session("main", "discord", "222", &dm);
+        assert_eq!(from_telegram, from_discord);
+        assert_eq!(from_telegram.to_string(), "agent:main:linked:dm:alice");
+
+        let unlinked = mgr.resolve_session("main", "discord", "333", &dm);
+        assert_ne!(unlinked, from_telegram);
+        assert_eq!(unlinked, SessionId::full("main", "discord", SessionScope::Dm, "333"));
+        // Same chat id on another channel is a different person.
+        assert_ne!(mgr.resolve_session("main", "feishu", "111", &dm), from_telegram);
+        // Group chats are never merged.
+        let group = mgr.resolve_session("main", "telegram", "111", &json!({ "group": true }));
+        assert_eq!(group.channel, "telegram");
+        // Agents keep their own linked session.
+        assert_ne!(mgr.resolve_session("ops", "discord", "222", &dm), from_discord);
+    }
+
     // ── get_or_create ───────────────────────────────────────────────
 
     #[test]
@@ -438,4 +517,27 @@ mod tests {
         let meta = mgr.get_meta(&sid).unwrap();
         assert_eq!(meta.id, sid);
     }
+
+    // ── latest_session_agent ────────────────────────────────────────
+
+    #[test]
+    fn latest_session_agent_picks_most_recent_candidate_in_conversation() {
+        let mut mgr = manager();
+        let conversation = SessionId::full("main", "telegram", SessionScope::Dm, "user_1");
+        assert_eq!(mgr.latest_session_agent(&conversation, |_| true), None);
+
+        let base = Utc::now();
+        for (i, agent) in ["xiaozhu", "helpdesk", "dev"].into_iter().enumerate() {
+            let sid = SessionId::full(agent, "telegram", SessionScope::Dm, "user_1");
+            mgr.get_or_create(&sid);
+            mgr.sessions.get_mut(&sid).unwrap().0.updated_at = base + chrono::Duration::seconds(i as i64);
+        }
+        // Another chat's newer session does not count.
+        let other = SessionId::full("main", "telegram", SessionScope::Dm, "user_2");
+        mgr.get_or_create(&other);
+        mgr.sessions.get_mut(&other).unwrap().0.updated_at = base + chrono::Duration::seconds(10);
+
+        assert_eq!(mgr.latest_session_agent(&conversation, |_| true), Some("dev"));
+        assert_eq!(mgr.latest_session_agent(&conversation, |a| a != "dev"), Some("helpdesk"));
+    }
 }
@@ -25,6 +25,8 @@ pub struct SharedSessionState {
     pub session_store: Arc<SessionStore>,
     /// Session keys currently being processed -> human-readable activity (e.g. "processing", "tool: exec").
     active_tasks: Arc<RwLock<HashMap<String, String>>>,
+    /// Tool sandboxes whose per-session sandbox is removed when a session is cleared.
+    tool_sandboxes: Option<Arc<crate::sandbox::SandboxManager>>,
 }
 
 impl SharedSessionState {
@@ -34,9 +36,16 @@ impl SharedSessionState {
             session_manager: Arc::new(RwLock::new(SessionManager::new())),
             session_store: Arc::new(session_store),
             active_tasks: Arc::new(RwLock::new(HashMap::new())),
+            tool_sandboxes: None,
         }
     }
 
+    /// Tear down a session's own tool sandbox (per-session isolation) when the session is cleared.
+    pub fn with_tool_sandboxes(mut self, manager: Arc<crate::sandbox::SandboxManager>) -> Self {
+        self.tool_sandboxes = Some(manager);
+        self
+    }
+
     /// Mark a session as currently running with the given activity description.
     pub async fn set_active(&self, session_key: &str, activity: &str) {
         self.active_tasks
@@ -195,15 +204,20 @@ impl SharedSessionState {
         Ok(())
     }
 
-    /// Clear a session: remove from in-memory map, from SessionManager, and delete
-    /// the persisted file. Used by the reset_session tool. No-op if the session
-    /// does not exist.
+    /// Clear a session: remove from in-memory map, from SessionManager, delete
+    /// the persisted file and remove the session's own tool sandbox. Used by the
+    /// reset_session tool. No-op if the session does not exist.
     pub async fn clear_session(&self, session_key: &str) -> Result<()> {
         self.sessions.write().await.remove(session_key);
         if let Ok(sid) = SessionId::parse(session_key) {
             self.session_manager.write().await.remove_session(&sid);
         }
         self.session_store.delete_session(session_key).await?;
+        if let Some(manager) = &self.tool_sandboxes {
+            if let Err(e) = manager.remove_session_tool_sandbox(session_key).await {
+                warn!(session_key = %session_key, error = %e, "Failed to remove session tool sandbox");
+            }
+        }
         debug!(session_key = %session_key, "session cleared");
         Ok(())
     }
@@ -234,10 +234,7 @@ async fn run_subagent_task(
     max_tokens: u32,
     temperature: f32,
 ) -> Result<String> {
-    let tool_ctx = ToolContext {
-        agent_id: agent_id.clone(),
-        workspace: workspace.clone(),
-    };
+    let tool_ctx = ToolContext::new(agent_id.clone(), workspace.clone());
 
     scope(tool_ctx, async move {
         #[cfg(feature = "memory-index")]
@@ -0,0 +1,129 @@
+//! Turn limiter -- bounds how many agent turns run at the same time.
+//!
+//! A global semaphore caps concurrent turns across all sessions (`mainAgent.maxConcurrentTurns`),
+//! and a per-session mutex on top keeps turns of one session strictly ordered. Callers beyond
+//! the limit wait for a free slot instead of failing.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::{Mutex, OwnedMutexGuard, OwnedSemaphorePermit, Semaphore};
+
+/// Held for the duration of one agent turn. Dropping it frees the session and the global slot.
+pub struct TurnPermit {
+    _global: OwnedSemaphorePermit,
+    _session: OwnedMutexGuard<()>,
+}
+
+pub struct TurnLimiter {
+    global: Arc<Semaphore>,
+    max_concurrent: usize,
+    sessions: std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>,
+}
+
+impl TurnLimiter {
+    /// Create a limiter allowing `max_concurrent` turns at once (0 is treated as 1).
+    pub fn new(max_concurrent: usize) -> Self {
+        let max_concurrent = max_concurrent.max(1);
+        Self {
+            global: Arc::new(Semaphore::new(max_concurrent)),
+            max_concurrent,
+            sessions: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
+
+    /// Number of global slots currently free.
+    pub fn available(&self) -> usize {
+        self.global.available_permits()
+    }
+
+    /// Wait until `session_key` has no other turn running and a global slot is free.
+    /// The session lock is taken first so a queued turn does not occupy a global slot.
+    pub async fn acquire(&self, session_key: &str) -> TurnPermit {
+        let session_lock = {
+            let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+            // Drop locks nobody holds or waits on so the map does not grow with every chat.
+            sessions.retain(|_, lock| Arc::strong_count(lock) > 1);
+            Arc::clone(sessions.entry(session_key.to_string()).or_default())
+        };
+        let session = session_lock.lock_owned().await;
+        let global = Arc::clone(&self.global)
+            .acquire_owned()
+            .await
+            .expect("turn limiter semaphore is never closed");
+        TurnPermit {
+            _global: global,
+            _session: session,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn zero_limit_is_clamped_to_one() {
+        let limiter = TurnLimiter::new(0);
+        assert_eq!(limiter.max_concurrent(), 1);
+        let _p = limiter.acquire("a").await;
+        assert_eq!(limiter.available(), 0);
+    }
+
+    #[tokio::test]
+    async fn global_limit_queues_extra_turns() {
+        let limiter = Arc::new(TurnLimiter::new(2));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let mut handles = Vec::new();
+        for i in 0..5 {
+            let limiter = Arc::clone(&limiter);
+            let in_flight = Arc::clone(&in_flight);
+            let peak = Arc::clone(&peak);
+            handles.push(tokio::spawn(async move {
+                let _permit = limiter.acquire(&format!("session-{i}")).await;
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(30)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+            }));
+        }
+        for h in handles {
+            h.await.unwrap();
+        }
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(limiter.available(), 2);
+    }
+
+    #[tokio::test]
+    async fn same_session_turns_run_one_at_a_time() {
+        let limiter = Arc::new(TurnLimiter::new(4));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let mut handles = Vec::new();
+        for _ in 0..3 {
+            let limiter = Arc::clone(&limiter);
+            let in_flight = Arc::clone(&in_flight);
+            let peak = Arc::clone(&peak);
+            handles.push(tokio::spawn(async move {
+                let _permit = limiter.acquire("telegram:chat-1").await;
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+            }));
+        }
+        for h in handles {
+            h.await.unwrap();
+        }
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+    }
+}
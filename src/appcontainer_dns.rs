@@ -224,3 +224,41 @@ pub fn build_reqwest_client_with_timeout(timeout: std::time::Duration) -> reqwes
             .unwrap_or_default()
     }
 }
+
+/// WebSocket connect when system DNS is unavailable (e.g. AppContainer): resolve via Google DNS.
+#[cfg(any(windows, unix))]
+pub async fn ws_connect_appcontainer(
+    conn_url: &str,
+) -> Result<
+    (
+        tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+        tokio_tungstenite::tungstenite::handshake::client::Response,
+    ),
+    String,
+> {
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+    use url::Url;
+
+    let parsed = Url::parse(conn_url).map_err(|e| e.to_string())?;
+    let host = parsed.host_str().ok_or("URL has no host")?;
+    let port = parsed.port_or_known_default().ok_or("URL has no port")?;
+
+    let ip = resolve_host(host).await?;
+
+    let tcp = tokio::net::TcpStream::connect((ip, port))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut request = conn_url
+        .into_client_request()
+        .map_err(|e| e.to_string())?;
+    request
+        .headers_mut()
+        .insert("host", host.try_into().map_err(|_| "invalid host")?);
+
+    tokio_tungstenite::client_async_tls_with_config(request, tcp, None, None)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -67,6 +67,26 @@ impl HeartbeatBackgroundService {
             inner: crate::heartbeat::HeartbeatService::new(config),
         }
     }
+
+    /// Heartbeat service driven by a control handle shared with the web API.
+    pub fn with_control(
+        config: Arc<RwLock<Config>>,
+        control: Arc<crate::heartbeat::HeartbeatControl>,
+    ) -> Self {
+        Self {
+            inner: crate::heartbeat::HeartbeatService::with_control(config, control),
+        }
+    }
+
+    /// Let `health_report` tasks post reports built from `sources` (see [crate::heartbeat::HealthSources]).
+    pub fn with_health_report(
+        mut self,
+        outbound_tx: tokio::sync::broadcast::Sender<crate::bus::OutboundMessage>,
+        sources: crate::heartbeat::HealthSources,
+    ) -> Self {
+        self.inner = self.inner.with_health_report(outbound_tx, sources);
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -91,6 +111,16 @@ impl CronBackgroundService {
             inner: crate::cron::config_runner::ConfigCronRunner::new(config),
         }
     }
+
+    /// Share the in-flight run tracker with the agent loop (overlap prevention).
+    pub fn with_run_tracker(
+        config: Arc<RwLock<Config>>,
+        runs: Arc<crate::cron::config_runner::CronRunTracker>,
+    ) -> Self {
+        Self {
+            inner: crate::cron::config_runner::ConfigCronRunner::new(config).with_run_tracker(runs),
+        }
+    }
 }
 
 #[async_trait::async_trait]
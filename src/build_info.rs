@@ -0,0 +1,58 @@
+//! Build information embedded at compile time by `build.rs`.
+//!
+//! Used by `synbot version` and `GET /api/version` to correlate bug reports with a build.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Version, git commit, build time, and enabled Cargo features of this binary.
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_sha: &'static str,
+    /// RFC 3339 build time (UTC), or "unknown".
+    pub build_timestamp: String,
+    pub features: Vec<&'static str>,
+    pub os: &'static str,
+    pub arch: &'static str,
+}
+
+/// Build info for the running binary.
+pub fn build_info() -> BuildInfo {
+    let build_timestamp = env!("SYNBOT_BUILD_EPOCH")
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0))
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_else(|| "unknown".to_string());
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_sha: env!("SYNBOT_GIT_SHA"),
+        build_timestamp,
+        features: env!("SYNBOT_FEATURES")
+            .split(',')
+            .filter(|f| !f.is_empty())
+            .collect(),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_info_has_version_and_timestamp() {
+        let info = build_info();
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert!(!info.git_sha.is_empty());
+        assert!(DateTime::parse_from_rfc3339(&info.build_timestamp).is_ok());
+    }
+
+    #[cfg(feature = "mcp")]
+    #[test]
+    fn build_info_lists_enabled_features() {
+        assert!(build_info().features.contains(&"mcp"));
+    }
+}
@@ -1,9 +1,13 @@
 //! Async message bus — decouples channels from the agent core.
 
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tokio::sync::{broadcast, mpsc};
 
+use crate::config::ResponseTransformsConfig;
 use crate::tools::approval::{ApprovalRequest, ApprovalResponse};
 
 // ---------------------------------------------------------------------------
@@ -58,6 +62,9 @@ pub enum OutboundMessageType {
         status: String,
         result_preview: String,
     },
+    /// The agent started working on a message for this chat; channels that support it show a
+    /// typing indicator until the next chat reply or approval request.
+    Typing,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -117,6 +124,172 @@ impl OutboundMessage {
             reply_to: None,
         }
     }
+
+    pub fn typing(channel: String, chat_id: String) -> Self {
+        Self {
+            channel,
+            chat_id,
+            message_type: OutboundMessageType::Typing,
+            reply_to: None,
+        }
+    }
+
+    /// True for messages that end a typing indicator (the reply or a prompt for the user).
+    pub fn ends_typing(&self) -> bool {
+        matches!(
+            self.message_type,
+            OutboundMessageType::Chat { .. } | OutboundMessageType::ApprovalRequest { .. }
+        )
+    }
+
+    /// True for messages worth delivering late: chat replies and approval requests. Typing
+    /// indicators and tool progress are stale once the channel is back.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.message_type,
+            OutboundMessageType::Chat { .. } | OutboundMessageType::ApprovalRequest { .. }
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Dead letters
+// ---------------------------------------------------------------------------
+
+/// Default number of undelivered messages kept; the oldest is dropped beyond it.
+pub const DEAD_LETTER_CAPACITY: usize = 500;
+
+/// An outbound message held back because its channel was down.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetter {
+    pub message: OutboundMessage,
+    /// Why the channel was unavailable (e.g. the error that put it into cooldown).
+    pub reason: String,
+    pub queued_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct DeadLetterState {
+    /// Channels currently down, with the reason.
+    down: HashMap<String, String>,
+    /// Channels that report their connection state (have called mark_up or mark_down).
+    reporting: HashSet<String>,
+    letters: VecDeque<DeadLetter>,
+}
+
+/// Outbound messages that could not be delivered because their channel was in cooldown or
+/// disconnected. Channels report outages with [`mark_down`](Self::mark_down); while a channel
+/// is down its retryable messages (see [`OutboundMessage::is_retryable`]) are kept here, and
+/// [`mark_up`](Self::mark_up) publishes them again once it reconnects. Only Telegram keeps dead
+/// letters so far; webhook channels report their state but drop what they fail to deliver.
+#[derive(Debug)]
+pub struct DeadLetterQueue {
+    capacity: usize,
+    outbound_tx: broadcast::Sender<OutboundMessage>,
+    state: Mutex<DeadLetterState>,
+}
+
+impl DeadLetterQueue {
+    /// Queue that republishes recovered messages on `outbound_tx`, keeping at most `capacity`.
+    pub fn new(outbound_tx: broadcast::Sender<OutboundMessage>, capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            outbound_tx,
+            state: Mutex::new(DeadLetterState::default()),
+        }
+    }
+
+    /// Record that `channel` cannot deliver messages until [`mark_up`](Self::mark_up).
+    pub fn mark_down(&self, channel: &str, reason: &str) {
+        let mut state = self.lock();
+        state.reporting.insert(channel.to_string());
+        state.down.insert(channel.to_string(), reason.to_string());
+    }
+
+    /// Record that `channel` is back and publish its dead letters again, oldest first.
+    /// Returns how many were retried (0 when the channel was not down).
+    pub fn mark_up(&self, channel: &str) -> usize {
+        let retry: Vec<OutboundMessage> = {
+            let mut state = self.lock();
+            if !state.reporting.contains(channel) {
+                state.reporting.insert(channel.to_string());
+            }
+            if state.down.remove(channel).is_none() {
+                return 0;
+            }
+            let (retry, keep): (VecDeque<DeadLetter>, VecDeque<DeadLetter>) =
+                std::mem::take(&mut state.letters)
+                    .into_iter()
+                    .partition(|l| l.message.channel == channel);
+            state.letters = keep;
+            retry.into_iter().map(|l| l.message).collect()
+        };
+        if !retry.is_empty() {
+            tracing::info!(channel = %channel, count = retry.len(), "Channel recovered, retrying dead letters");
+        }
+        let count = retry.len();
+        for msg in retry {
+            let _ = self.outbound_tx.send(msg);
+        }
+        count
+    }
+
+    pub fn is_down(&self, channel: &str) -> bool {
+        self.lock().down.contains_key(channel)
+    }
+
+    /// Called by a channel before delivering `msg`: when the channel is down, keeps the message
+    /// (if worth retrying) and returns true so the caller skips it.
+    pub fn divert(&self, msg: &OutboundMessage) -> bool {
+        let mut state = self.lock();
+        let Some(reason) = state.down.get(&msg.channel).cloned() else {
+            return false;
+        };
+        if !msg.is_retryable() {
+            return true;
+        }
+        if state.letters.len() >= self.capacity {
+            if let Some(dropped) = state.letters.pop_front() {
+                tracing::warn!(
+                    channel = %dropped.message.channel,
+                    chat_id = %dropped.message.chat_id,
+                    "Dead letter queue full, dropping oldest message"
+                );
+            }
+        }
+        state.letters.push_back(DeadLetter {
+            message: msg.clone(),
+            reason,
+            queued_at: Utc::now(),
+        });
+        true
+    }
+
+    /// Called by a channel when sending `msg` failed with an error worth retrying: marks the
+    /// channel down with `reason` and keeps the message until [`mark_up`](Self::mark_up).
+    pub fn capture(&self, msg: &OutboundMessage, reason: &str) {
+        self.mark_down(&msg.channel, reason);
+        self.divert(msg);
+    }
+
+    /// Undelivered messages, oldest first.
+    pub fn list(&self) -> Vec<DeadLetter> {
+        self.lock().letters.iter().cloned().collect()
+    }
+
+    /// Channels currently down, with the reason.
+    pub fn down_channels(&self) -> HashMap<String, String> {
+        self.lock().down.clone()
+    }
+
+    /// Whether `channel` reports its connection state here, so "not down" means up.
+    pub fn reports_state(&self, channel: &str) -> bool {
+        self.lock().reporting.contains(channel)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DeadLetterState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -131,17 +304,42 @@ pub struct MessageBus {
     inbound_tx: Option<mpsc::Sender<InboundMessage>>,
     inbound_rx: Option<mpsc::Receiver<InboundMessage>>,
     outbound_tx: broadcast::Sender<OutboundMessage>,
+    /// Sender the channels subscribe to: `outbound_tx` itself, or the output of the response
+    /// transforms relay.
+    delivery_tx: broadcast::Sender<OutboundMessage>,
+    dead_letters: Arc<DeadLetterQueue>,
 }
 
 impl MessageBus {
     pub fn new() -> Self {
         let (inbound_tx, inbound_rx) = mpsc::channel(BUS_CAPACITY);
         let (outbound_tx, _) = broadcast::channel(BUS_CAPACITY);
+        let dead_letters = Arc::new(DeadLetterQueue::new(outbound_tx.clone(), DEAD_LETTER_CAPACITY));
         Self {
             inbound_tx: Some(inbound_tx),
             inbound_rx: Some(inbound_rx),
+            delivery_tx: outbound_tx.clone(),
             outbound_tx,
+            dead_letters,
+        }
+    }
+
+    /// Run `responseTransforms` on every chat message between the publishers and the channels.
+    /// Call before any channel subscribes (needs a tokio runtime). Dead letters are republished
+    /// after the relay, so a retried message is not transformed twice.
+    pub fn with_response_transforms(mut self, config: &ResponseTransformsConfig) -> Self {
+        if config.global.is_empty() && config.channels.values().all(|t| t.is_empty()) {
+            return self;
         }
+        let (delivery_tx, _) = broadcast::channel(BUS_CAPACITY);
+        crate::agent::response_transforms::spawn_relay(
+            config.clone(),
+            self.outbound_tx.subscribe(),
+            delivery_tx.clone(),
+        );
+        self.dead_letters = Arc::new(DeadLetterQueue::new(delivery_tx.clone(), DEAD_LETTER_CAPACITY));
+        self.delivery_tx = delivery_tx;
+        self
     }
 
     /// Get a sender handle that channels use to push inbound messages.
@@ -167,13 +365,23 @@ impl MessageBus {
 
     /// Subscribe to outbound messages (each channel gets its own receiver).
     pub fn subscribe_outbound(&self) -> broadcast::Receiver<OutboundMessage> {
-        self.outbound_tx.subscribe()
+        self.delivery_tx.subscribe()
+    }
+
+    /// Clone the sender channels subscribe to (the web server subscribes per connection).
+    pub fn delivery_tx_clone(&self) -> broadcast::Sender<OutboundMessage> {
+        self.delivery_tx.clone()
     }
 
     /// Clone the outbound sender (needed by AgentLoop).
     pub fn outbound_tx_clone(&self) -> broadcast::Sender<OutboundMessage> {
         self.outbound_tx.clone()
     }
+
+    /// Dead-letter queue shared by the channels of this bus.
+    pub fn dead_letters(&self) -> Arc<DeadLetterQueue> {
+        Arc::clone(&self.dead_letters)
+    }
 }
 
 #[cfg(test)]
@@ -258,6 +466,7 @@ mod tests {
             timestamp: Utc::now(),
             timeout_secs: 300,
             display_message: None,
+            approver_id: None,
         };
 
         let msg = OutboundMessage::approval_request(
@@ -304,6 +513,7 @@ mod tests {
             timestamp: Utc::now(),
             timeout_secs: 600,
             display_message: None,
+            approver_id: None,
         };
 
         let msg = ApprovalRequestMessage {
@@ -393,10 +603,116 @@ mod tests {
             timestamp: Utc::now(),
             timeout_secs: 300,
             display_message: None,
+            approver_id: None,
         };
         let approval_type = OutboundMessageType::ApprovalRequest { request };
         let json = serde_json::to_string(&approval_type).unwrap();
         assert!(json.contains("\"type\":\"approval_request\""));
         assert!(json.contains("\"request\""));
     }
+
+    fn reply(channel: &str, text: &str) -> OutboundMessage {
+        OutboundMessage::chat(channel.to_string(), "chat-1".to_string(), text.to_string(), vec![], None)
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter_for_down_channel_is_retried_on_recovery() {
+        let (outbound_tx, mut outbound_rx) = broadcast::channel(16);
+        let dlq = DeadLetterQueue::new(outbound_tx, 10);
+
+        // Channel up: delivered normally.
+        assert!(!dlq.divert(&reply("telegram", "before")));
+
+        dlq.mark_down("telegram", "retries exhausted");
+        assert!(dlq.is_down("telegram"));
+        assert!(dlq.divert(&reply("telegram", "while down")));
+        // Typing indicators are not worth retrying, other channels are unaffected.
+        assert!(dlq.divert(&OutboundMessage::typing("telegram".into(), "chat-1".into())));
+        assert!(!dlq.divert(&reply("discord", "elsewhere")));
+
+        let letters = dlq.list();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].reason, "retries exhausted");
+        assert!(outbound_rx.try_recv().is_err());
+
+        assert_eq!(dlq.mark_up("telegram"), 1);
+        assert!(!dlq.is_down("telegram"));
+        assert!(dlq.list().is_empty());
+        let retried = outbound_rx.try_recv().expect("dead letter should be republished");
+        assert!(matches!(retried.message_type, OutboundMessageType::Chat { ref content, .. } if content == "while down"));
+        // The retried message now goes through.
+        assert!(!dlq.divert(&retried));
+        assert_eq!(dlq.mark_up("telegram"), 0);
+    }
+
+    #[test]
+    fn test_failed_send_is_captured_until_recovery() {
+        let (outbound_tx, mut outbound_rx) = broadcast::channel(16);
+        let dlq = DeadLetterQueue::new(outbound_tx, 10);
+        dlq.capture(&reply("telegram", "lost in transit"), "HTTP 502");
+        assert!(dlq.is_down("telegram"));
+        assert_eq!(dlq.list()[0].reason, "HTTP 502");
+        // Progress that fails is not worth keeping.
+        let progress = OutboundMessage::tool_progress("telegram".into(), "chat-1".into(), "exec".into(), "done".into(), String::new());
+        assert!(!progress.is_retryable());
+        dlq.capture(&progress, "HTTP 502");
+        assert_eq!(dlq.list().len(), 1);
+
+        assert_eq!(dlq.mark_up("telegram"), 1);
+        assert!(outbound_rx.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_dead_letter_queue_is_bounded() {
+        let (outbound_tx, _) = broadcast::channel(16);
+        let dlq = DeadLetterQueue::new(outbound_tx, 2);
+        dlq.mark_down("telegram", "down");
+        for text in ["a", "b", "c"] {
+            assert!(dlq.divert(&reply("telegram", text)));
+        }
+        let kept: Vec<String> = dlq
+            .list()
+            .into_iter()
+            .map(|l| match l.message.message_type {
+                OutboundMessageType::Chat { content, .. } => content,
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(kept, ["b", "c"]);
+    }
+
+    fn content(msg: &OutboundMessage) -> &str {
+        match &msg.message_type {
+            OutboundMessageType::Chat { content, .. } => content,
+            _ => panic!("Expected Chat message type"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_response_transforms_apply_to_all_chat_messages_once() {
+        let mut config = ResponseTransformsConfig::default();
+        config.channels.insert(
+            "telegram".into(),
+            vec![crate::config::ResponseTransformConfig::AppendFooter { text: "-- bot".into() }],
+        );
+        let bus = MessageBus::new().with_response_transforms(&config);
+        let mut rx = bus.subscribe_outbound();
+
+        // Any publisher (reasoning post, subagent result, final reply) goes through the relay.
+        bus.publish_outbound(reply("telegram", "thinking...")).await;
+        bus.publish_outbound(reply("discord", "untouched")).await;
+        bus.publish_outbound(OutboundMessage::typing("telegram".into(), "chat-1".into())).await;
+        assert_eq!(content(&rx.recv().await.unwrap()), "thinking...\n\n-- bot");
+        assert_eq!(content(&rx.recv().await.unwrap()), "untouched");
+        assert!(matches!(rx.recv().await.unwrap().message_type, OutboundMessageType::Typing));
+
+        // A dead letter is kept after the relay and republished without a second footer.
+        let dlq = bus.dead_letters();
+        dlq.mark_down("telegram", "down");
+        bus.publish_outbound(reply("telegram", "later")).await;
+        let transformed = rx.recv().await.unwrap();
+        assert!(dlq.divert(&transformed));
+        assert_eq!(dlq.mark_up("telegram"), 1);
+        assert_eq!(content(&rx.recv().await.unwrap()), "later\n\n-- bot");
+    }
 }
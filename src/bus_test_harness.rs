@@ -0,0 +1,183 @@
+//! In-memory message bus for integration tests (feature `test-harness`).
+//!
+//! [`BusTestHarness`] owns both ends of the bus an [`AgentLoop`](crate::agent::r#loop::AgentLoop)
+//! runs on: hand [`inbound_receiver`](BusTestHarness::inbound_receiver) and
+//! [`outbound_sender`](BusTestHarness::outbound_sender) to the code under test, inject messages
+//! with [`send_text`](BusTestHarness::send_text), then wait for what should come back with
+//! [`expect_chat_containing`](BusTestHarness::expect_chat_containing) and
+//! [`expect_tool_progress`](BusTestHarness::expect_tool_progress). An expectation that is not met
+//! in time panics with every outbound message seen so far.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use tokio::sync::{broadcast, mpsc};
+
+use crate::bus::{InboundMessage, OutboundMessage, OutboundMessageType};
+
+/// How long an expectation waits by default.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct BusTestHarness {
+    inbound_tx: mpsc::Sender<InboundMessage>,
+    inbound_rx: Option<mpsc::Receiver<InboundMessage>>,
+    outbound_tx: broadcast::Sender<OutboundMessage>,
+    outbound_rx: broadcast::Receiver<OutboundMessage>,
+    /// Received but not yet matched by an expectation, oldest first.
+    pending: VecDeque<OutboundMessage>,
+    /// Every outbound message received, for failure messages.
+    seen: Vec<OutboundMessage>,
+    timeout: Duration,
+}
+
+impl Default for BusTestHarness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BusTestHarness {
+    pub fn new() -> Self {
+        let (inbound_tx, inbound_rx) = mpsc::channel(32);
+        let (outbound_tx, outbound_rx) = broadcast::channel(256);
+        Self {
+            inbound_tx,
+            inbound_rx: Some(inbound_rx),
+            outbound_tx,
+            outbound_rx,
+            pending: VecDeque::new(),
+            seen: Vec::new(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Wait at most `timeout` in each expectation.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Receiving end of the inbound bus, for the agent loop. Panics when taken twice.
+    pub fn inbound_receiver(&mut self) -> mpsc::Receiver<InboundMessage> {
+        self.inbound_rx
+            .take()
+            .expect("BusTestHarness inbound receiver already taken")
+    }
+
+    /// Sender of the outbound bus, for the agent loop or a channel under test.
+    pub fn outbound_sender(&self) -> broadcast::Sender<OutboundMessage> {
+        self.outbound_tx.clone()
+    }
+
+    /// Inject an inbound message.
+    pub async fn send(&self, msg: InboundMessage) {
+        self.inbound_tx
+            .send(msg)
+            .await
+            .expect("inbound bus closed: is the agent loop running?");
+    }
+
+    /// Inject a user message that triggers the agent, as a channel would.
+    pub async fn send_text(&self, channel: &str, chat_id: &str, content: &str) {
+        self.send(InboundMessage {
+            channel: channel.to_string(),
+            sender_id: "test-user".to_string(),
+            chat_id: chat_id.to_string(),
+            content: content.to_string(),
+            timestamp: chrono::Utc::now(),
+            media: vec![],
+            metadata: serde_json::json!({ "trigger_agent": true }),
+        })
+        .await;
+    }
+
+    /// Wait for a chat reply in `chat_id` whose text contains `needle`.
+    pub async fn expect_chat_containing(&mut self, chat_id: &str, needle: &str) -> OutboundMessage {
+        self.expect(&format!("chat in {} containing {:?}", chat_id, needle), |m| {
+            m.chat_id == chat_id
+                && matches!(&m.message_type, OutboundMessageType::Chat { content, .. }
+                    if content.contains(needle))
+        })
+        .await
+    }
+
+    /// Wait for a progress message about `tool_name` in `chat_id`.
+    pub async fn expect_tool_progress(&mut self, chat_id: &str, tool_name: &str) -> OutboundMessage {
+        self.expect(&format!("tool progress for {} in {}", tool_name, chat_id), |m| {
+            m.chat_id == chat_id
+                && matches!(&m.message_type, OutboundMessageType::ToolProgress { tool_name: t, .. }
+                    if t == tool_name)
+        })
+        .await
+    }
+
+    /// Wait for the first outbound message matching `matches`, which is consumed; earlier
+    /// non-matching messages stay available to later expectations.
+    pub async fn expect(
+        &mut self,
+        what: &str,
+        matches: impl Fn(&OutboundMessage) -> bool,
+    ) -> OutboundMessage {
+        if let Some(pos) = self.pending.iter().position(&matches) {
+            return self.pending.remove(pos).expect("position is in range");
+        }
+        let deadline = tokio::time::Instant::now() + self.timeout;
+        loop {
+            match tokio::time::timeout_at(deadline, self.outbound_rx.recv()).await {
+                Ok(Ok(msg)) => {
+                    self.seen.push(msg.clone());
+                    if matches(&msg) {
+                        return msg;
+                    }
+                    self.pending.push_back(msg);
+                }
+                Ok(Err(broadcast::error::RecvError::Lagged(_))) => continue,
+                Ok(Err(broadcast::error::RecvError::Closed)) | Err(_) => panic!(
+                    "expected {} within {:?}; outbound messages seen: {:#?}",
+                    what, self.timeout, self.seen
+                ),
+            }
+        }
+    }
+
+    /// Every outbound message received so far, oldest first.
+    pub fn seen(&self) -> &[OutboundMessage] {
+        &self.seen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn expectations_match_out_of_order_and_keep_other_messages() {
+        let mut harness = BusTestHarness::new().with_timeout(Duration::from_millis(200));
+        let tx = harness.outbound_sender();
+        tx.send(OutboundMessage::tool_progress(
+            "telegram".into(),
+            "c1".into(),
+            "read_file".into(),
+            "ok".into(),
+            "contents".into(),
+        ))
+        .unwrap();
+        tx.send(OutboundMessage::chat("telegram".into(), "c1".into(), "all done".into(), vec![], None))
+            .unwrap();
+
+        harness.expect_chat_containing("c1", "done").await;
+        harness.expect_tool_progress("c1", "read_file").await;
+        assert_eq!(harness.seen().len(), 2);
+
+        let mut rx = harness.inbound_receiver();
+        harness.send_text("telegram", "c1", "hi").await;
+        assert_eq!(rx.recv().await.unwrap().content, "hi");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "expected chat in c1")]
+    async fn unmet_expectation_panics() {
+        let mut harness = BusTestHarness::new().with_timeout(Duration::from_millis(50));
+        harness.expect_chat_containing("c1", "never").await;
+    }
+}
@@ -58,6 +58,7 @@ mod tests {
             timestamp: Utc::now(),
             timeout_secs: 300,
             display_message: None,
+            approver_id: None,
         }
     }
 
@@ -17,7 +17,7 @@ use crate::bus::{InboundMessage, OutboundMessage, OutboundMessageType};
 use crate::channels::approval_formatter;
 use crate::channels::dingtalk_stream;
 use crate::channels::file_handler;
-use crate::channels::Channel;
+use crate::channels::{split_message, Channel, LengthUnit};
 use crate::config::{
     pairing_allows, pairing_message, pairings_from_config_file_cached, DingTalkConfig,
 };
@@ -713,16 +713,13 @@ async fn run_outbound_dingtalk(
                 if !show_tool_calls {
                     continue;
                 }
-                let prev = if result_preview.len() > tool_preview {
-                    format!("{}…", &result_preview[..tool_preview])
-                } else {
-                    result_preview.clone()
-                };
+                let prev = crate::channels::preview(result_preview, tool_preview);
                 (
                     format!("Tool `{}` — {}: {}", tool_name, status, prev),
                     vec![],
                 )
             }
+            OutboundMessageType::Typing => continue,
         };
         let map = sessions.read().await;
         let entry = match map.get(&msg.chat_id) {
@@ -743,7 +740,7 @@ async fn run_outbound_dingtalk(
         drop(map);
 
         const CHUNK: usize = 1800;
-        for chunk in split_chunks(&content, CHUNK) {
+        for chunk in split_message(&content, CHUNK, LengthUnit::Bytes) {
             if chunk.is_empty() {
                 continue;
             }
@@ -922,29 +919,3 @@ async fn post_session_json(
     }
     Ok(())
 }
-
-fn split_chunks(s: &str, max: usize) -> Vec<String> {
-    if s.is_empty() {
-        return vec![];
-    }
-    if s.len() <= max {
-        return vec![s.to_string()];
-    }
-    let mut out = Vec::new();
-    let mut rest = s;
-    while !rest.is_empty() {
-        if rest.len() <= max {
-            out.push(rest.to_string());
-            break;
-        }
-        let take = rest
-            .char_indices()
-            .take_while(|(i, _)| *i < max)
-            .last()
-            .map(|(i, c)| i + c.len_utf8())
-            .unwrap_or(max);
-        out.push(rest[..take].to_string());
-        rest = &rest[take..];
-    }
-    out
-}
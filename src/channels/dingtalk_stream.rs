@@ -141,7 +141,7 @@ async fn ws_connect(conn_url: &str) -> Result<
     String,
 > {
     if std::env::var_os("SYNBOT_IN_APP_SANDBOX").is_some() {
-        crate::channels::feishu_ws::ws_connect_appcontainer(conn_url).await
+        crate::appcontainer_dns::ws_connect_appcontainer(conn_url).await
     } else {
         tokio_tungstenite::connect_async(conn_url)
             .await
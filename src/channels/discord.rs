@@ -14,7 +14,7 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use async_trait::async_trait;
@@ -23,12 +23,17 @@ use tokio::sync::{broadcast, mpsc, RwLock};
 use tracing::{error, info, warn};
 
 use crate::bus::{InboundMessage, OutboundMessage};
+use crate::channels::discord_rate_limit::{messages_route, DiscordRateLimiter};
 use crate::channels::file_handler;
-use crate::channels::{approval_formatter, Channel, RetryPolicy, RetryState};
+use crate::channels::{
+    approval_formatter, split_message, strip_mention, Channel, LengthUnit, QuotedMessage,
+    RetryPolicy, RetryState, TypingIndicator,
+};
 use crate::config::{
     pairing_allows, pairing_message, pairings_from_config_file_cached, AllowlistEntry, DiscordConfig,
+    MentionMode,
 };
-use crate::tools::approval::ApprovalManager;
+use crate::tools::approval::{ApprovalManager, PendingApprovals};
 
 // ---------------------------------------------------------------------------
 // Constants
@@ -40,6 +45,8 @@ const GATEWAY_URL: &str = "wss://gateway.discord.gg/?v=10&encoding=json";
 const API_BASE: &str = "https://discord.com/api/v10";
 /// Maximum message length allowed by Discord.
 const DISCORD_MAX_MESSAGE_LEN: usize = 2000;
+/// A typing trigger lasts about 10 seconds.
+const DISCORD_TYPING_INTERVAL: Duration = Duration::from_secs(8);
 
 /// Gateway opcodes.
 mod opcode {
@@ -108,42 +115,17 @@ struct ResumeState {
 }
 
 // ---------------------------------------------------------------------------
-// Message splitting
+// Discord message conversion
 // ---------------------------------------------------------------------------
 
-/// Split a message into chunks of at most `max_len` characters.
-///
-/// Splits on the last newline before the limit when possible, otherwise
-/// splits at exactly `max_len` characters. The concatenation of all
-/// returned chunks equals the original string.
-pub fn split_message(content: &str, max_len: usize) -> Vec<String> {
-    if max_len == 0 {
-        return vec![content.to_string()];
-    }
-    if content.len() <= max_len {
-        return vec![content.to_string()];
-    }
-    let mut chunks = Vec::new();
-    let mut remaining = content;
-    while !remaining.is_empty() {
-        if remaining.len() <= max_len {
-            chunks.push(remaining.to_string());
-            break;
-        }
-        let split_at = remaining[..max_len]
-            .rfind('\n')
-            .map(|pos| pos + 1)
-            .unwrap_or(max_len);
-        chunks.push(remaining[..split_at].to_string());
-        remaining = &remaining[split_at..];
-    }
-    chunks
+/// Strip the bot mention (`<@id>` or `<@!id>`) from a group message; `None` if the bot is
+/// not mentioned where `mode` requires. Role routing like `@@dev` is left in place.
+fn discord_strip_group_mention(content: &str, my_name: &str, mode: MentionMode) -> Option<String> {
+    let nick = format!("<@!{}>", my_name);
+    let plain = format!("<@{}>", my_name);
+    strip_mention(content, &[&nick, &plain], mode)
 }
 
-// ---------------------------------------------------------------------------
-// Discord message conversion
-// ---------------------------------------------------------------------------
-
 /// Result of converting MESSAGE_CREATE: optional inbound message and list of (url, filename) to download.
 fn discord_event_to_inbound_with_attachments(
     data: &serde_json::Value,
@@ -228,6 +210,13 @@ fn discord_event_to_inbound_with_attachments(
         "Discord: Converting event to inbound message"
     );
 
+    let mut metadata = serde_json::json!({
+        "message_id": message_id,
+        "guild_id": guild_id,
+    });
+    if let Some(quoted) = discord_referenced_message(data) {
+        quoted.attach_to(&mut metadata);
+    }
     let msg = InboundMessage {
         channel: "discord".into(),
         sender_id,
@@ -235,19 +224,213 @@ fn discord_event_to_inbound_with_attachments(
         content,
         timestamp: chrono::Utc::now(),
         media: vec![],
-        metadata: serde_json::json!({
-            "message_id": message_id,
-            "guild_id": guild_id,
-        }),
+        metadata,
     };
     Some((msg, attachments))
 }
 
+/// The message this one replies to (`referenced_message`, null when deleted or not a reply).
+fn discord_referenced_message(data: &serde_json::Value) -> Option<QuotedMessage> {
+    let referenced = data.get("referenced_message").filter(|v| v.is_object())?;
+    let field = |v: Option<&serde_json::Value>| {
+        v.and_then(|v| v.as_str()).unwrap_or_default().to_string()
+    };
+    Some(QuotedMessage {
+        message_id: field(referenced.get("id")),
+        sender_id: field(referenced.get("author").and_then(|a| a.get("id"))),
+        content: field(referenced.get("content")),
+    })
+}
+
 /// Legacy helper: convert event to inbound only (no attachment download). Used when workspace not set.
 fn discord_event_to_inbound(data: &serde_json::Value) -> Option<InboundMessage> {
     discord_event_to_inbound_with_attachments(data).map(|(msg, _)| msg)
 }
 
+// ---------------------------------------------------------------------------
+// Slash commands (interactions)
+// ---------------------------------------------------------------------------
+
+/// Interaction type of a slash command invocation.
+const INTERACTION_APPLICATION_COMMAND: u64 = 2;
+/// Interaction callback: reply with a message right away.
+const CALLBACK_CHANNEL_MESSAGE: u64 = 4;
+/// Interaction callback: acknowledge now ("is thinking…") and fill in the response later.
+const CALLBACK_DEFERRED_CHANNEL_MESSAGE: u64 = 5;
+/// Message flag: only the invoking user sees the message.
+const MESSAGE_FLAG_EPHEMERAL: u64 = 1 << 6;
+/// Interaction tokens can be used for 15 minutes.
+const INTERACTION_TOKEN_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// A slash command acknowledged with a deferred response, waiting for the agent's reply.
+#[derive(Debug, Clone)]
+struct PendingInteraction {
+    id: String,
+    application_id: String,
+    token: String,
+    received: Instant,
+}
+
+/// Deferred slash commands by interaction ID (the `message_id` of their inbound message, which
+/// the agent's replies carry as `reply_to`).
+type PendingInteractions = Arc<RwLock<HashMap<String, PendingInteraction>>>;
+
+/// Commands registered when `slashCommands` is on. `/ask` sends its prompt to the agent; the
+/// others become the control command of the same name.
+fn slash_command_definitions() -> serde_json::Value {
+    let control = |name: &str, description: &str| {
+        serde_json::json!({ "name": name, "type": 1, "description": description })
+    };
+    serde_json::json!([
+        {
+            "name": "ask",
+            "type": 1,
+            "description": "Ask the agent",
+            "options": [
+                { "name": "prompt", "description": "Your message", "type": 3, "required": true },
+            ],
+        },
+        control("status", "Show the session status"),
+        control("stop", "Stop the running task"),
+        control("clear", "Clear the session"),
+        control("help", "List available commands"),
+    ])
+}
+
+/// Convert an INTERACTION_CREATE slash command into an inbound message (`/ask` becomes its
+/// prompt, any other command `/<name>`) and the interaction to answer. `None` for other
+/// interaction types.
+fn discord_interaction_to_inbound(
+    data: &serde_json::Value,
+) -> Option<(InboundMessage, PendingInteraction)> {
+    if data.get("type").and_then(|v| v.as_u64()) != Some(INTERACTION_APPLICATION_COMMAND) {
+        return None;
+    }
+    let field = |v: Option<&serde_json::Value>| v.and_then(|v| v.as_str()).map(String::from);
+    // Guild invocations carry the user under `member`, DMs directly under `user`.
+    let user = data
+        .get("member")
+        .and_then(|m| m.get("user"))
+        .or_else(|| data.get("user"))?;
+    let sender_id = field(user.get("id"))?;
+    let chat_id = field(data.get("channel_id"))?;
+    let command = data.get("data")?;
+    let name = command.get("name")?.as_str()?;
+    let content = if name == "ask" {
+        command
+            .get("options")?
+            .as_array()?
+            .iter()
+            .find(|o| o.get("name").and_then(|n| n.as_str()) == Some("prompt"))
+            .and_then(|o| o.get("value"))
+            .and_then(|v| v.as_str())?
+            .to_string()
+    } else {
+        format!("/{}", name)
+    };
+    let interaction = PendingInteraction {
+        id: field(data.get("id"))?,
+        application_id: field(data.get("application_id"))?,
+        token: field(data.get("token"))?,
+        received: Instant::now(),
+    };
+    let guild_id = field(data.get("guild_id")).unwrap_or_default();
+    let mut metadata = serde_json::json!({
+        "message_id": interaction.id,
+        "guild_id": guild_id,
+        "slash_command": name,
+    });
+    if !guild_id.is_empty() {
+        metadata["group"] = serde_json::json!(true);
+    }
+    let msg = InboundMessage {
+        channel: "discord".into(),
+        sender_id,
+        chat_id,
+        content,
+        timestamp: chrono::Utc::now(),
+        media: vec![],
+        metadata,
+    };
+    Some((msg, interaction))
+}
+
+/// Overwrite the application's global commands with [`slash_command_definitions`].
+async fn register_slash_commands(
+    client: &reqwest::Client,
+    token: &str,
+    application_id: &str,
+) -> Result<()> {
+    let url = format!("{}/applications/{}/commands", API_BASE, application_id);
+    let resp = client
+        .put(&url)
+        .header("Authorization", format!("Bot {}", token))
+        .json(&slash_command_definitions())
+        .send()
+        .await?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        anyhow::bail!("Discord slash command registration failed: {} {}", status, body);
+    }
+    Ok(())
+}
+
+/// Send the initial response to an interaction.
+async fn interaction_callback(
+    client: &reqwest::Client,
+    interaction: &PendingInteraction,
+    body: serde_json::Value,
+) -> Result<()> {
+    let url = format!(
+        "{}/interactions/{}/{}/callback",
+        API_BASE, interaction.id, interaction.token
+    );
+    let resp = client.post(&url).json(&body).send().await?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        anyhow::bail!("Discord interaction callback failed: {} {}", status, body);
+    }
+    Ok(())
+}
+
+/// Replace the deferred response of `interaction` with `content`.
+async fn edit_interaction_response(
+    client: &reqwest::Client,
+    interaction: &PendingInteraction,
+    content: &str,
+) -> Result<()> {
+    let url = format!(
+        "{}/webhooks/{}/{}/messages/@original",
+        API_BASE, interaction.application_id, interaction.token
+    );
+    let resp = client
+        .patch(&url)
+        .json(&serde_json::json!({ "content": content }))
+        .send()
+        .await?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        anyhow::bail!("Discord interaction response edit failed: {} {}", status, body);
+    }
+    Ok(())
+}
+
+/// Take the deferred slash command that `msg` replies to, if any and its token is still usable.
+/// Only chat replies complete a slash command.
+async fn take_pending_interaction(
+    pending: &PendingInteractions,
+    msg: &OutboundMessage,
+) -> Option<PendingInteraction> {
+    if !matches!(msg.message_type, crate::bus::OutboundMessageType::Chat { .. }) {
+        return None;
+    }
+    let interaction = pending.write().await.remove(msg.reply_to.as_deref()?)?;
+    (interaction.received.elapsed() < INTERACTION_TOKEN_TTL).then_some(interaction)
+}
+
 // ---------------------------------------------------------------------------
 // DiscordChannel
 // ---------------------------------------------------------------------------
@@ -259,9 +442,14 @@ pub struct DiscordChannel {
     inbound_tx: mpsc::Sender<InboundMessage>,
     outbound_rx: Option<broadcast::Receiver<OutboundMessage>>,
     client: reqwest::Client,
+    /// Per-route and global REST rate limits, shared by `send_message` and the outbound dispatcher.
+    rate_limiter: DiscordRateLimiter,
     running: bool,
     approval_manager: Option<Arc<ApprovalManager>>,
-    pending_approvals: Arc<RwLock<HashMap<String, (String, String)>>>,
+    /// Approval requests delivered by this channel and not answered yet
+    pending_approvals: Arc<RwLock<PendingApprovals>>,
+    /// Slash commands waiting for the agent's reply; the first reply to the chat answers them.
+    pending_interactions: PendingInteractions,
     /// Workspace directory for saving incoming files; when set, attachments are downloaded and paths added to InboundMessage.media.
     workspace_dir: Option<PathBuf>,
     config_path: Option<PathBuf>,
@@ -288,9 +476,11 @@ impl DiscordChannel {
             inbound_tx,
             outbound_rx: Some(outbound_rx),
             client,
+            rate_limiter: DiscordRateLimiter::new(),
             running: false,
             approval_manager: None,
-            pending_approvals: Arc::new(RwLock::new(HashMap::new())),
+            pending_approvals: Arc::new(RwLock::new(PendingApprovals::new())),
+            pending_interactions: Arc::new(RwLock::new(HashMap::new())),
             workspace_dir,
             config_path,
         }
@@ -302,25 +492,6 @@ impl DiscordChannel {
         self
     }
 
-    /// Register a user's pending approval request.
-    async fn register_pending_approval(&self, user_id: String, request_id: String, chat_id: String) {
-        let mut pending = self.pending_approvals.write().await;
-        pending.insert(user_id, (request_id, chat_id));
-    }
-
-    /// Get and remove the user's pending approval request.
-    async fn take_pending_approval(&self, user_id: &str) -> Option<(String, String)> {
-        let mut pending = self.pending_approvals.write().await;
-        pending.remove(user_id)
-    }
-
-    /// Check whether the user has a pending approval request.
-    #[allow(dead_code)]
-    async fn has_pending_approval(&self, user_id: &str) -> bool {
-        let pending = self.pending_approvals.read().await;
-        pending.contains_key(user_id)
-    }
-
     fn format_approval_request(request: &crate::tools::approval::ApprovalRequest) -> String {
         approval_formatter::format_approval_request(request)
     }
@@ -359,6 +530,26 @@ impl DiscordChannel {
         Ok(())
     }
 
+    /// POST /channels/{id}/typing, repeated until `typing.stop` is called for the channel.
+    fn start_typing(
+        typing: &TypingIndicator,
+        client: &reqwest::Client,
+        token: &str,
+        channel_id: &str,
+    ) {
+        let client = client.clone();
+        let url = format!("{}/channels/{}/typing", API_BASE, channel_id);
+        let auth = format!("Bot {}", token);
+        typing.start(channel_id, DISCORD_TYPING_INTERVAL, move || {
+            let request = client.post(&url).header("Authorization", &auth);
+            async move {
+                if let Err(e) = request.send().await {
+                    warn!("Discord typing trigger failed: {e}");
+                }
+            }
+        });
+    }
+
     /// Send a plain text message to a channel (used e.g. for allowlist denial reply).
     async fn send_text_to_channel(
         client: &reqwest::Client,
@@ -408,16 +599,16 @@ impl DiscordChannel {
     /// Send a text message to a Discord channel via the REST API.
     /// Automatically splits messages exceeding 2000 characters.
     async fn send_message(&self, channel_id: &str, content: &str) -> Result<()> {
-        let chunks = split_message(content, DISCORD_MAX_MESSAGE_LEN);
+        let chunks = split_message(content, DISCORD_MAX_MESSAGE_LEN, LengthUnit::Chars);
+        let route = messages_route(channel_id);
         for chunk in &chunks {
             let url = format!("{}/channels/{}/messages", API_BASE, channel_id);
-            let resp = self
+            let request = self
                 .client
                 .post(&url)
                 .header("Authorization", format!("Bot {}", self.config.token))
-                .json(&serde_json::json!({ "content": chunk }))
-                .send()
-                .await?;
+                .json(&serde_json::json!({ "content": chunk }));
+            let resp = self.rate_limiter.send(&route, request).await?;
             if !resp.status().is_success() {
                 let status = resp.status();
                 let body = resp.text().await.unwrap_or_default();
@@ -432,6 +623,67 @@ impl DiscordChannel {
         Ok(())
     }
 
+    /// Handle a slash command: check the allowlist, acknowledge it with a deferred response and
+    /// forward it to the agent. The agent's first reply to the chat completes the response.
+    async fn handle_slash_command(
+        data: &serde_json::Value,
+        client: &reqwest::Client,
+        inbound_tx: &mpsc::Sender<InboundMessage>,
+        allowlist: &[AllowlistEntry],
+        channel_name: &str,
+        channel_provider: &'static str,
+        config_path: Option<&PathBuf>,
+        default_agent: &str,
+        enable_allowlist: bool,
+        pending_interactions: &PendingInteractions,
+    ) {
+        let (mut inbound, interaction) = match discord_interaction_to_inbound(data) {
+            Some(v) => v,
+            None => return,
+        };
+        inbound.channel = channel_name.to_string();
+        inbound.metadata["default_agent"] = serde_json::json!(default_agent);
+        if enable_allowlist {
+            let pairings = config_path
+                .map(|p| pairings_from_config_file_cached(p.as_path()))
+                .unwrap_or_default();
+            let allowed = allowlist.iter().any(|e| e.chat_id == inbound.chat_id)
+                || pairing_allows(&inbound.chat_id, channel_provider, &pairings);
+            if !allowed {
+                warn!(chat_id = %inbound.chat_id, "Discord: slash command from chat not in allowlist");
+                let hint = pairing_message(channel_provider, &inbound.chat_id);
+                let body = serde_json::json!({
+                    "type": CALLBACK_CHANNEL_MESSAGE,
+                    "data": { "content": hint, "flags": MESSAGE_FLAG_EPHEMERAL },
+                });
+                if let Err(e) = interaction_callback(client, &interaction, body).await {
+                    warn!("{e:#}");
+                }
+                return;
+            }
+        }
+        let ack = serde_json::json!({ "type": CALLBACK_DEFERRED_CHANNEL_MESSAGE });
+        if let Err(e) = interaction_callback(client, &interaction, ack).await {
+            error!("Discord slash command acknowledgement failed: {e:#}");
+            return;
+        }
+        info!(
+            sender = %inbound.sender_id,
+            chat_id = %inbound.chat_id,
+            command = %inbound.metadata["slash_command"],
+            "Discord slash command received"
+        );
+        {
+            let mut pending = pending_interactions.write().await;
+            // Commands whose turn never replied (e.g. stopped) expire with their token.
+            pending.retain(|_, i| i.received.elapsed() < INTERACTION_TOKEN_TTL);
+            pending.insert(interaction.id.clone(), interaction);
+        }
+        if let Err(e) = inbound_tx.send(inbound).await {
+            error!("Failed to forward Discord slash command: {e}");
+        }
+    }
+
     /// Run a single Gateway session. Connects, identifies (or resumes),
     /// processes events, and returns when the connection drops or the
     /// server requests a reconnect.
@@ -445,11 +697,14 @@ impl DiscordChannel {
         default_agent: &str,
         enable_allowlist: bool,
         group_my_name: Option<&str>,
+        mention_mode: MentionMode,
         resume: &mut ResumeState,
         approval_manager: &Option<Arc<ApprovalManager>>,
-        pending_approvals: &Arc<RwLock<HashMap<String, (String, String)>>>,
+        pending_approvals: &Arc<RwLock<PendingApprovals>>,
         client: &reqwest::Client,
         workspace_dir: Option<&PathBuf>,
+        slash_commands: bool,
+        pending_interactions: &PendingInteractions,
     ) -> std::result::Result<(), DiscordGatewayError> {
         // Choose URL: use resume_gateway_url if we have one, else default.
         let ws_url = resume
@@ -622,6 +877,39 @@ impl DiscordChannel {
                                             session_id = ?resume.session_id,
                                             "Discord Gateway READY"
                                         );
+                                        let application_id = d
+                                            .get("application")
+                                            .and_then(|a| a.get("id"))
+                                            .and_then(|v| v.as_str())
+                                            .filter(|_| slash_commands);
+                                        if let Some(application_id) = application_id {
+                                            let client = client.clone();
+                                            let token = token.to_string();
+                                            let application_id = application_id.to_string();
+                                            tokio::spawn(async move {
+                                                match register_slash_commands(&client, &token, &application_id).await {
+                                                    Ok(()) => info!("Discord slash commands registered"),
+                                                    Err(e) => warn!("{e:#}"),
+                                                }
+                                            });
+                                        }
+                                    }
+                                }
+                                "INTERACTION_CREATE" if slash_commands => {
+                                    if let Some(d) = payload.get("d") {
+                                        Self::handle_slash_command(
+                                            d,
+                                            client,
+                                            inbound_tx,
+                                            allowlist,
+                                            channel_name,
+                                            channel_provider,
+                                            config_path.as_ref(),
+                                            default_agent,
+                                            enable_allowlist,
+                                            pending_interactions,
+                                        )
+                                        .await;
                                     }
                                 }
                                 "RESUMED" => {
@@ -688,32 +976,28 @@ impl DiscordChannel {
                                             let (trigger_agent, skip_send) = if !enable_allowlist {
                                                 if is_group {
                                                     if let Some(my_name) = group_my_name {
-                                                        let trimmed = inbound.content.trim_start();
-                                                        let mention_prefix = format!("<@!{}>", my_name);
-                                                        let mention_prefix_alt = format!("<@{}>", my_name);
-                                                        let starts = trimmed.starts_with(&mention_prefix)
-                                                            || trimmed.starts_with(&mention_prefix_alt);
-                                                        if !starts {
-                                                            info!(
-                                                                chat_id = %inbound.chat_id,
-                                                                "Discord: group message not @bot, saving to session only"
-                                                            );
-                                                            inbound.metadata["trigger_agent"] =
-                                                                serde_json::json!(false);
-                                                            inbound.metadata["group"] =
-                                                                serde_json::json!(true);
-                                                            (false, true)
-                                                        } else {
-                                                            // Strip only bot mention then 0+ spaces; do not strip @@role so agent loop can route @@dev etc.
-                                                            let stripped = trimmed
-                                                                .strip_prefix(&mention_prefix)
-                                                                .or_else(|| trimmed.strip_prefix(&mention_prefix_alt))
-                                                                .unwrap_or(trimmed)
-                                                                .trim_start();
-                                                            inbound.content = stripped.to_string();
-                                                            inbound.metadata["group"] =
-                                                                serde_json::json!(true);
-                                                            (true, false)
+                                                        match discord_strip_group_mention(
+                                                            &inbound.content,
+                                                            my_name,
+                                                            mention_mode,
+                                                        ) {
+                                                            None => {
+                                                                info!(
+                                                                    chat_id = %inbound.chat_id,
+                                                                    "Discord: group message not @bot, saving to session only"
+                                                                );
+                                                                inbound.metadata["trigger_agent"] =
+                                                                    serde_json::json!(false);
+                                                                inbound.metadata["group"] =
+                                                                    serde_json::json!(true);
+                                                                (false, true)
+                                                            }
+                                                            Some(stripped) => {
+                                                                inbound.content = stripped;
+                                                                inbound.metadata["group"] =
+                                                                    serde_json::json!(true);
+                                                                (true, false)
+                                                            }
                                                         }
                                                     } else {
                                                         inbound.metadata["group"] =
@@ -762,45 +1046,29 @@ impl DiscordChannel {
                                                     None => {
                                                         if is_group {
                                                             if let Some(my_name) = group_my_name {
-                                                                let trimmed =
-                                                                    inbound.content.trim_start();
-                                                                let mention_prefix =
-                                                                    format!("<@!{}>", my_name);
-                                                                let mention_prefix_alt =
-                                                                    format!("<@{}>", my_name);
-                                                                let starts = trimmed
-                                                                    .starts_with(&mention_prefix)
-                                                                    || trimmed.starts_with(
-                                                                        &mention_prefix_alt,
-                                                                    );
-                                                                if !starts {
-                                                                    info!(
-                                                                        chat_id = %inbound.chat_id,
-                                                                        "Discord: group message not @bot, saving to session only"
-                                                                    );
-                                                                    inbound.metadata
-                                                                        ["trigger_agent"] =
-                                                                        serde_json::json!(false);
-                                                                    inbound.metadata["group"] =
-                                                                        serde_json::json!(true);
-                                                                    (false, true)
-                                                                } else {
-                                                                    let stripped = trimmed
-                                                                        .strip_prefix(
-                                                                            &mention_prefix,
-                                                                        )
-                                                                        .or_else(|| {
-                                                                            trimmed.strip_prefix(
-                                                                                &mention_prefix_alt,
-                                                                            )
-                                                                        })
-                                                                        .unwrap_or(trimmed)
-                                                                        .trim_start();
-                                                                    inbound.content =
-                                                                        stripped.to_string();
-                                                                    inbound.metadata["group"] =
-                                                                        serde_json::json!(true);
-                                                                    (true, false)
+                                                                match discord_strip_group_mention(
+                                                                    &inbound.content,
+                                                                    my_name,
+                                                                    mention_mode,
+                                                                ) {
+                                                                    None => {
+                                                                        info!(
+                                                                            chat_id = %inbound.chat_id,
+                                                                            "Discord: group message not @bot, saving to session only"
+                                                                        );
+                                                                        inbound.metadata
+                                                                            ["trigger_agent"] =
+                                                                            serde_json::json!(false);
+                                                                        inbound.metadata["group"] =
+                                                                            serde_json::json!(true);
+                                                                        (false, true)
+                                                                    }
+                                                                    Some(stripped) => {
+                                                                        inbound.content = stripped;
+                                                                        inbound.metadata["group"] =
+                                                                            serde_json::json!(true);
+                                                                        (true, false)
+                                                                    }
                                                                 }
                                                             } else {
                                                                 inbound.metadata["group"] =
@@ -813,32 +1081,28 @@ impl DiscordChannel {
                                                     }
                                                     Some(e) => {
                                                         if let Some(ref my_name) = e.my_name {
-                                                            let trimmed = inbound.content.trim_start();
-                                                            let mention_prefix = format!("<@!{}>", my_name);
-                                                            let mention_prefix_alt = format!("<@{}>", my_name);
-                                                            let starts = trimmed.starts_with(&mention_prefix)
-                                                                || trimmed.starts_with(&mention_prefix_alt);
-                                                            if !starts {
-                                                                info!(
-                                                                    chat_id = %inbound.chat_id,
-                                                                    "Discord: group message not @bot, saving to session only"
-                                                                );
-                                                                inbound.metadata["trigger_agent"] =
-                                                                    serde_json::json!(false);
-                                                                inbound.metadata["group"] =
-                                                                    serde_json::json!(true);
-                                                                (false, true)
-                                                            } else {
-                                                                // Strip only bot mention then 0+ spaces; do not strip @@role so agent loop can route @@dev etc.
-                                                                let stripped = trimmed
-                                                                    .strip_prefix(&mention_prefix)
-                                                                    .or_else(|| trimmed.strip_prefix(&mention_prefix_alt))
-                                                                    .unwrap_or(trimmed)
-                                                                    .trim_start();
-                                                                inbound.content = stripped.to_string();
-                                                            inbound.metadata["group"] =
-                                                                serde_json::json!(true);
-                                                                (true, false)
+                                                            match discord_strip_group_mention(
+                                                                &inbound.content,
+                                                                my_name,
+                                                                mention_mode,
+                                                            ) {
+                                                                None => {
+                                                                    info!(
+                                                                        chat_id = %inbound.chat_id,
+                                                                        "Discord: group message not @bot, saving to session only"
+                                                                    );
+                                                                    inbound.metadata["trigger_agent"] =
+                                                                        serde_json::json!(false);
+                                                                    inbound.metadata["group"] =
+                                                                        serde_json::json!(true);
+                                                                    (false, true)
+                                                                }
+                                                                Some(stripped) => {
+                                                                    inbound.content = stripped;
+                                                                    inbound.metadata["group"] =
+                                                                        serde_json::json!(true);
+                                                                    (true, false)
+                                                                }
                                                             }
                                                         } else {
                                                             // Allowlist hit without per-entry my_name: still mark guild
@@ -858,9 +1122,11 @@ impl DiscordChannel {
                                                 continue;
                                             }
                                             // If user has pending approval, forward to agent with metadata for LLM to interpret
-                                            let mut pending = pending_approvals.write().await;
-                                            if let Some((request_id, _)) = pending.remove(&inbound.sender_id) {
-                                                drop(pending);
+                                            let taken = pending_approvals
+                                                .write()
+                                                .await
+                                                .take_reply(&inbound.sender_id, &inbound.chat_id);
+                                            if let Some(request_id) = taken {
                                                 let mut meta = inbound.metadata.clone();
                                                 if !meta.is_object() {
                                                     meta = serde_json::json!({});
@@ -877,7 +1143,6 @@ impl DiscordChannel {
                                                 }
                                                 continue;
                                             }
-                                            drop(pending);
 
                                             // Normal message
                                             info!(
@@ -952,17 +1217,24 @@ impl Channel for DiscordChannel {
         // --- Spawn outbound message dispatcher ---
         let mut outbound_rx = self.outbound_rx.take().unwrap();
         let outbound_client = self.client.clone();
+        let outbound_limiter = self.rate_limiter.clone();
         let outbound_token = self.config.token.clone();
         let outbound_channel_name = self.config.name.clone();
         let pending_approvals_clone = self.pending_approvals.clone();
+        let pending_interactions = self.pending_interactions.clone();
         let show_tool_calls = self.show_tool_calls;
         let tool_result_preview_chars = self.tool_result_preview_chars;
         let workspace_dir = self.workspace_dir.clone();
+        let typing_enabled = self.config.typing_indicator;
+        let typing = TypingIndicator::new();
         tokio::spawn(async move {
             while let Ok(msg) = outbound_rx.recv().await {
                 if msg.channel != outbound_channel_name {
                     continue;
                 }
+                if msg.ends_typing() {
+                    typing.stop(&msg.chat_id);
+                }
                 let (content, media_paths) = match &msg.message_type {
                     crate::bus::OutboundMessageType::Chat { content, media } => {
                         (content.clone(), media.clone())
@@ -975,13 +1247,7 @@ impl Channel for DiscordChannel {
                         if !show_tool_calls {
                             continue;
                         }
-                        let preview = if result_preview.is_empty() {
-                            String::new()
-                        } else if result_preview.len() > tool_result_preview_chars {
-                            format!("{}...", result_preview.chars().take(tool_result_preview_chars).collect::<String>())
-                        } else {
-                            result_preview.clone()
-                        };
+                        let preview = crate::channels::preview(result_preview, tool_result_preview_chars);
                         let content = if preview.is_empty() {
                             format!("🔧 {} — {}", tool_name, status)
                         } else {
@@ -991,11 +1257,7 @@ impl Channel for DiscordChannel {
                     }
                     crate::bus::OutboundMessageType::ApprovalRequest { request } => {
                         // Register the pending approval request
-                        let user_id = request.session_id.split(':').last().unwrap_or("").to_string();
-                        if !user_id.is_empty() {
-                            let mut pending = pending_approvals_clone.write().await;
-                            pending.insert(user_id, (request.id.clone(), msg.chat_id.clone()));
-                        }
+                        pending_approvals_clone.write().await.insert(request, &msg.chat_id);
                         let content = request
                             .display_message
                             .as_deref()
@@ -1004,8 +1266,37 @@ impl Channel for DiscordChannel {
                             .unwrap_or_else(|| Self::format_approval_request(request));
                         (content, vec![])
                     }
+                    crate::bus::OutboundMessageType::Typing => {
+                        if typing_enabled {
+                            Self::start_typing(
+                                &typing,
+                                &outbound_client,
+                                &outbound_token,
+                                &msg.chat_id,
+                            );
+                        }
+                        continue;
+                    }
                 };
+                // A slash command is answered with the first chunk of the reply to it; the rest,
+                // and any files, go to the channel as usual.
+                let mut content = content;
+                if !content.is_empty() {
+                    if let Some(interaction) =
+                        take_pending_interaction(&pending_interactions, &msg).await
+                    {
+                        let chunks = split_message(&content, DISCORD_MAX_MESSAGE_LEN, LengthUnit::Chars);
+                        match edit_interaction_response(&outbound_client, &interaction, &chunks[0]).await {
+                            Ok(()) => content = chunks[1..].concat(),
+                            Err(e) => warn!("{e:#}; sending the reply to the channel"),
+                        }
+                        if content.is_empty() && media_paths.is_empty() {
+                            continue;
+                        }
+                    }
+                }
                 let url = format!("{}/channels/{}/messages", API_BASE, msg.chat_id);
+                let route = messages_route(&msg.chat_id);
                 // If we have file paths, send as multipart (content + files). Discord supports multiple files in one message.
                 if !media_paths.is_empty() && workspace_dir.is_some() {
                     let ws = workspace_dir.as_ref().unwrap();
@@ -1034,12 +1325,11 @@ impl Channel for DiscordChannel {
                             let part = multipart::Part::bytes(bytes).file_name(name);
                             form = form.part(format!("files[{}]", idx), part);
                         }
-                        let resp = outbound_client
+                        let request = outbound_client
                             .post(&url)
                             .header("Authorization", format!("Bot {}", outbound_token))
-                            .multipart(form)
-                            .send()
-                            .await;
+                            .multipart(form);
+                        let resp = outbound_limiter.send(&route, request).await;
                         if let Err(e) = resp {
                             error!("Discord outbound send (with files) error: {e:#}");
                         } else if let Ok(r) = resp {
@@ -1053,17 +1343,16 @@ impl Channel for DiscordChannel {
                         continue;
                     }
                 }
-                let chunks = split_message(&content, DISCORD_MAX_MESSAGE_LEN);
+                let chunks = split_message(&content, DISCORD_MAX_MESSAGE_LEN, LengthUnit::Chars);
                 for chunk in &chunks {
-                    let resp = outbound_client
+                    let request = outbound_client
                         .post(&url)
                         .header(
                             "Authorization",
                             format!("Bot {}", outbound_token),
                         )
-                        .json(&serde_json::json!({ "content": chunk }))
-                        .send()
-                        .await;
+                        .json(&serde_json::json!({ "content": chunk }));
+                    let resp = outbound_limiter.send(&route, request).await;
                     if let Err(e) = resp {
                         error!("Discord outbound send error: {e:#}");
                     } else if let Ok(r) = resp {
@@ -1093,11 +1382,14 @@ impl Channel for DiscordChannel {
                 &self.config.default_agent,
                 self.config.enable_allowlist,
                 self.config.group_my_name.as_deref(),
+                self.config.mention_mode,
                 &mut resume,
                 &self.approval_manager,
                 &self.pending_approvals,
                 &self.client,
                 self.workspace_dir.as_ref(),
+                self.config.slash_commands,
+                &self.pending_interactions,
             )
             .await;
 
@@ -1202,13 +1494,7 @@ impl Channel for DiscordChannel {
                 if !self.show_tool_calls {
                     return Ok(());
                 }
-                let preview = if result_preview.is_empty() {
-                    String::new()
-                } else if result_preview.len() > self.tool_result_preview_chars {
-                    format!("{}...", result_preview.chars().take(self.tool_result_preview_chars).collect::<String>())
-                } else {
-                    result_preview.clone()
-                };
+                let preview = crate::channels::preview(result_preview, self.tool_result_preview_chars);
                 let content = if preview.is_empty() {
                     format!("🔧 {} — {}", tool_name, status)
                 } else {
@@ -1216,6 +1502,17 @@ impl Channel for DiscordChannel {
                 };
                 return self.send_message(&msg.chat_id, &content).await;
             }
+            crate::bus::OutboundMessageType::Typing => {
+                if self.config.typing_indicator {
+                    let url = format!("{}/channels/{}/typing", API_BASE, msg.chat_id);
+                    self.client
+                        .post(&url)
+                        .header("Authorization", format!("Bot {}", self.config.token))
+                        .send()
+                        .await?;
+                }
+                return Ok(());
+            }
         };
         if !media.is_empty() && self.workspace_dir.is_some() {
             let ws = self.workspace_dir.as_ref().unwrap();
@@ -1245,13 +1542,12 @@ impl Channel for DiscordChannel {
                     form = form.part(format!("files[{}]", idx), part);
                 }
                 let url = format!("{}/channels/{}/messages", API_BASE, msg.chat_id);
-                let resp = self
+                let request = self
                     .client
                     .post(&url)
                     .header("Authorization", format!("Bot {}", self.config.token))
-                    .multipart(form)
-                    .send()
-                    .await?;
+                    .multipart(form);
+                let resp = self.rate_limiter.send(&messages_route(&msg.chat_id), request).await?;
                 if !resp.status().is_success() {
                     let status = resp.status();
                     let body = resp.text().await.unwrap_or_default();
@@ -1276,14 +1572,14 @@ mod tests {
 
     #[test]
     fn split_message_short_returns_single_chunk() {
-        let result = split_message("hello", 2000);
+        let result = split_message("hello", 2000, LengthUnit::Chars);
         assert_eq!(result, vec!["hello"]);
     }
 
     #[test]
     fn split_message_exact_limit_returns_single_chunk() {
         let msg = "a".repeat(2000);
-        let result = split_message(&msg, 2000);
+        let result = split_message(&msg, 2000, LengthUnit::Chars);
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].len(), 2000);
     }
@@ -1291,7 +1587,7 @@ mod tests {
     #[test]
     fn split_message_over_limit_splits_correctly() {
         let msg = "a".repeat(4500);
-        let result = split_message(&msg, 2000);
+        let result = split_message(&msg, 2000, LengthUnit::Chars);
         assert_eq!(result.len(), 3);
         assert_eq!(result[0].len(), 2000);
         assert_eq!(result[1].len(), 2000);
@@ -1307,7 +1603,7 @@ mod tests {
         msg.push_str(&"a".repeat(1990));
         msg.push('\n');
         msg.push_str(&"b".repeat(100));
-        let result = split_message(&msg, 2000);
+        let result = split_message(&msg, 2000, LengthUnit::Chars);
         assert_eq!(result.len(), 2);
         // First chunk should end at the newline (1991 chars including \n)
         assert!(result[0].ends_with('\n'));
@@ -1317,33 +1613,87 @@ mod tests {
 
     #[test]
     fn split_message_empty_string() {
-        let result = split_message("", 2000);
+        let result = split_message("", 2000, LengthUnit::Chars);
         assert_eq!(result, vec![""]);
     }
 
     #[test]
     fn split_message_concatenation_equals_original() {
         let msg = "Hello\nWorld\nThis is a test\nwith newlines\n";
-        let result = split_message(msg, 10);
+        let result = split_message(msg, 10, LengthUnit::Chars);
         let joined: String = result.into_iter().collect();
         assert_eq!(joined, msg);
     }
 
     #[test]
     fn split_message_max_len_zero_returns_whole() {
-        let result = split_message("hello", 0);
+        let result = split_message("hello", 0, LengthUnit::Chars);
         assert_eq!(result, vec!["hello"]);
     }
 
     #[test]
     fn split_message_each_chunk_within_limit() {
         let msg = "x".repeat(5001);
-        let result = split_message(&msg, 2000);
+        let result = split_message(&msg, 2000, LengthUnit::Chars);
         for chunk in &result {
             assert!(chunk.len() <= 2000);
         }
     }
 
+    // ---- referenced_message tests ----
+
+    #[test]
+    fn reply_includes_referenced_message_in_metadata() {
+        let mut data = make_message_create("user-1", "chan-1", "summarize this", false);
+        data["referenced_message"] = serde_json::json!({
+            "id": "msg-100",
+            "content": "Quarterly numbers are up 12%.",
+            "author": { "id": "user-2", "username": "alice" },
+        });
+        let msg = discord_event_to_inbound(&data).unwrap();
+        assert_eq!(msg.metadata["reply_to"]["message_id"], "msg-100");
+        assert_eq!(msg.metadata["reply_to"]["sender_id"], "user-2");
+        assert_eq!(msg.metadata["reply_to"]["content"], "Quarterly numbers are up 12%.");
+    }
+
+    #[test]
+    fn deleted_or_missing_reference_adds_no_reply_to() {
+        let mut data = make_message_create("user-1", "chan-1", "hello", false);
+        assert!(discord_event_to_inbound(&data).unwrap().metadata.get("reply_to").is_none());
+        data["referenced_message"] = serde_json::Value::Null;
+        assert!(discord_event_to_inbound(&data).unwrap().metadata.get("reply_to").is_none());
+    }
+
+    // ---- group mention tests ----
+
+    #[test]
+    fn group_mention_prefix_mode_requires_leading_mention() {
+        assert_eq!(
+            discord_strip_group_mention("<@!42> @@dev run it", "42", MentionMode::Prefix),
+            Some("@@dev run it".to_string())
+        );
+        assert_eq!(
+            discord_strip_group_mention("hey <@42> do X", "42", MentionMode::Prefix),
+            None
+        );
+    }
+
+    #[test]
+    fn group_mention_anywhere_mode_strips_mid_message_mention() {
+        assert_eq!(
+            discord_strip_group_mention("hey <@42> do X", "42", MentionMode::Anywhere),
+            Some("hey do X".to_string())
+        );
+        assert_eq!(
+            discord_strip_group_mention("thanks <@!42>", "42", MentionMode::Anywhere),
+            Some("thanks".to_string())
+        );
+        assert_eq!(
+            discord_strip_group_mention("hey <@7> do X", "42", MentionMode::Anywhere),
+            None
+        );
+    }
+
     // ---- discord_event_to_inbound tests ----
 
     fn make_message_create(
@@ -1399,6 +1749,83 @@ mod tests {
         assert_eq!(msg.metadata["guild_id"], "guild-456");
     }
 
+    #[test]
+    fn slash_commands_become_inbound_messages() {
+        let ask = serde_json::json!({
+            "id": "int-1",
+            "application_id": "app-1",
+            "token": "tok-1",
+            "type": 2,
+            "channel_id": "chan-1",
+            "guild_id": "guild-1",
+            "member": { "user": { "id": "user-1", "username": "testuser" } },
+            "data": {
+                "name": "ask",
+                "type": 1,
+                "options": [{ "name": "prompt", "type": 3, "value": "what time is it?" }],
+            },
+        });
+        let (msg, interaction) = discord_interaction_to_inbound(&ask).unwrap();
+        assert_eq!(msg.sender_id, "user-1");
+        assert_eq!(msg.chat_id, "chan-1");
+        assert_eq!(msg.content, "what time is it?");
+        assert_eq!(msg.metadata["slash_command"], "ask");
+        assert_eq!(msg.metadata["group"], true);
+        assert_eq!(interaction.id, "int-1");
+        assert_eq!(interaction.application_id, "app-1");
+        assert_eq!(interaction.token, "tok-1");
+
+        // DMs carry the user directly; other commands map to control commands.
+        let status = serde_json::json!({
+            "id": "int-2",
+            "application_id": "app-1",
+            "token": "tok-2",
+            "type": 2,
+            "channel_id": "dm-1",
+            "user": { "id": "user-2" },
+            "data": { "name": "status", "type": 1 },
+        });
+        let (msg, _) = discord_interaction_to_inbound(&status).unwrap();
+        assert_eq!(msg.sender_id, "user-2");
+        assert_eq!(msg.content, "/status");
+        assert!(msg.metadata.get("group").is_none());
+
+        // Component clicks and other interaction types are not slash commands.
+        let button = serde_json::json!({ "type": 3, "channel_id": "chan-1", "user": { "id": "u" } });
+        assert!(discord_interaction_to_inbound(&button).is_none());
+    }
+
+    #[tokio::test]
+    async fn only_chat_replies_to_the_command_complete_it() {
+        let pending: PendingInteractions = Arc::new(RwLock::new(HashMap::new()));
+        for id in ["int-1", "int-2"] {
+            pending.write().await.insert(
+                id.to_string(),
+                PendingInteraction {
+                    id: id.to_string(),
+                    application_id: "app-1".into(),
+                    token: format!("tok-{}", id),
+                    received: Instant::now(),
+                },
+            );
+        }
+        let reply = |reply_to: Option<&str>| {
+            OutboundMessage::chat("discord".into(), "chan-1".into(), "hi".into(), vec![], reply_to.map(String::from))
+        };
+
+        // Other messages in the same chat leave both commands pending.
+        assert!(take_pending_interaction(&pending, &reply(None)).await.is_none());
+        assert!(take_pending_interaction(&pending, &reply(Some("msg-9"))).await.is_none());
+        let mut progress = OutboundMessage::tool_progress("discord".into(), "chan-1".into(), "exec".into(), "ok".into(), String::new());
+        progress.reply_to = Some("int-1".into());
+        assert!(take_pending_interaction(&pending, &progress).await.is_none());
+
+        let taken = take_pending_interaction(&pending, &reply(Some("int-2"))).await.unwrap();
+        assert_eq!(taken.token, "tok-int-2");
+        assert!(take_pending_interaction(&pending, &reply(Some("int-2"))).await.is_none(), "answered once");
+        assert!(take_pending_interaction(&pending, &reply(Some("int-1"))).await.is_some());
+    }
+
     // ---- classify_discord_error tests ----
 
     #[test]
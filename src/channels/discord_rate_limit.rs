@@ -0,0 +1,233 @@
+//! Discord REST rate limits: per-route buckets and the global limit.
+//!
+//! Discord reports the state of each route's bucket in `X-RateLimit-Remaining` and
+//! `X-RateLimit-Reset-After`, and answers 429 with `Retry-After` (plus `X-RateLimit-Global` when
+//! the whole bot is limited). [`DiscordRateLimiter::send`] waits before a request while its
+//! route's bucket is used up or a global limit is in effect, records the headers of every
+//! response, and retries a 429 once after the advised delay.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use tokio::time::Instant;
+use tracing::warn;
+
+/// Longest delay taken from a header, so a bogus value cannot stall the channel.
+const MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// Delay after a 429 without a usable `Retry-After`.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    remaining: u64,
+    reset_at: Instant,
+}
+
+#[derive(Default)]
+struct LimitState {
+    buckets: HashMap<String, Bucket>,
+    global_until: Option<Instant>,
+}
+
+/// Rate-limit state shared by all REST calls of one Discord channel. Routes are keyed like
+/// `POST /channels/{id}/messages`, so each Discord channel has its own message bucket.
+#[derive(Clone, Default)]
+pub struct DiscordRateLimiter {
+    state: Arc<Mutex<LimitState>>,
+}
+
+/// Route key for creating messages in `channel_id`.
+pub fn messages_route(channel_id: &str) -> String {
+    format!("POST /channels/{}/messages", channel_id)
+}
+
+fn header_secs(headers: &HeaderMap, name: &str) -> Option<Duration> {
+    let secs: f64 = headers.get(name)?.to_str().ok()?.trim().parse().ok()?;
+    (secs.is_finite() && secs >= 0.0).then(|| Duration::from_secs_f64(secs).min(MAX_WAIT))
+}
+
+fn header_is(headers: &HeaderMap, name: &str, value: &str) -> bool {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.trim().eq_ignore_ascii_case(value))
+}
+
+impl DiscordRateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LimitState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Time a request on `route` has to wait. When it may go now, one slot of the route's known
+    /// bucket is taken so concurrent senders do not overdraw it.
+    fn reserve(&self, route: &str) -> Option<Duration> {
+        let now = Instant::now();
+        let mut state = self.lock();
+        let global = state.global_until.filter(|until| *until > now);
+        let bucket = match state.buckets.get_mut(route) {
+            Some(b) if b.reset_at > now => {
+                if b.remaining == 0 {
+                    Some(b.reset_at)
+                } else {
+                    if global.is_none() {
+                        b.remaining -= 1;
+                    }
+                    None
+                }
+            }
+            Some(_) => {
+                // The bucket has reset: its size is unknown until the next response.
+                state.buckets.remove(route);
+                None
+            }
+            None => None,
+        };
+        global.max(bucket).map(|until| until - now)
+    }
+
+    /// Wait until a request on `route` may be sent.
+    pub async fn wait(&self, route: &str) {
+        while let Some(delay) = self.reserve(route) {
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Record a response's rate-limit headers. Returns the advised delay for a 429.
+    fn record(&self, route: &str, status: StatusCode, headers: &HeaderMap) -> Option<Duration> {
+        let now = Instant::now();
+        let mut state = self.lock();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = header_secs(headers, "retry-after").unwrap_or(DEFAULT_RETRY_AFTER);
+            if header_is(headers, "x-ratelimit-global", "true")
+                || header_is(headers, "x-ratelimit-scope", "global")
+            {
+                state.global_until = Some(now + retry_after);
+            } else {
+                state.buckets.insert(
+                    route.to_string(),
+                    Bucket {
+                        remaining: 0,
+                        reset_at: now + retry_after,
+                    },
+                );
+            }
+            return Some(retry_after);
+        }
+        let remaining = headers
+            .get("x-ratelimit-remaining")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok());
+        if let (Some(remaining), Some(reset_after)) =
+            (remaining, header_secs(headers, "x-ratelimit-reset-after"))
+        {
+            state.buckets.insert(
+                route.to_string(),
+                Bucket {
+                    remaining,
+                    reset_at: now + reset_after,
+                },
+            );
+        }
+        None
+    }
+
+    /// Send `request` on `route`, waiting for its bucket and the global limit first. A 429 is
+    /// retried once after `Retry-After` when the request can be cloned (multipart bodies cannot).
+    pub async fn send(&self, route: &str, request: RequestBuilder) -> reqwest::Result<Response> {
+        let retry = request.try_clone();
+        self.wait(route).await;
+        let resp = request.send().await?;
+        let Some(retry_after) = self.record(route, resp.status(), resp.headers()) else {
+            return Ok(resp);
+        };
+        let Some(retry) = retry else {
+            return Ok(resp);
+        };
+        warn!(
+            route = %route,
+            retry_after_ms = retry_after.as_millis() as u64,
+            "Discord rate limited (429); retrying once"
+        );
+        self.wait(route).await;
+        let resp = retry.send().await?;
+        self.record(route, resp.status(), resp.headers());
+        Ok(resp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Mock Discord API: answers every request with `head` (status line and headers) and records
+    /// when each request arrived.
+    async fn serve(head: &'static str) -> (String, Arc<Mutex<Vec<Instant>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let arrivals = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&arrivals);
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 16 * 1024];
+                let _ = socket.read(&mut buf).await;
+                seen.lock().unwrap().push(Instant::now());
+                let response = format!(
+                    "{}Content-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{{}}",
+                    head
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                socket.flush().await.unwrap();
+            }
+        });
+        (format!("http://{}", addr), arrivals)
+    }
+
+    #[tokio::test]
+    async fn exhausted_bucket_delays_the_next_request() {
+        let (base, arrivals) = serve(
+            "HTTP/1.1 200 OK\r\nX-RateLimit-Limit: 5\r\nX-RateLimit-Remaining: 0\r\nX-RateLimit-Reset-After: 0.4\r\n",
+        )
+        .await;
+        let client = reqwest::Client::new();
+        let limiter = DiscordRateLimiter::new();
+        let route = messages_route("123");
+        let url = format!("{}/channels/123/messages", base);
+        for _ in 0..2 {
+            let resp = limiter.send(&route, client.post(&url).body("{}")).await.unwrap();
+            assert!(resp.status().is_success());
+        }
+        let arrivals = arrivals.lock().unwrap();
+        assert_eq!(arrivals.len(), 2);
+        let gap = arrivals[1] - arrivals[0];
+        assert!(gap >= Duration::from_millis(350), "second request waited for the reset: {:?}", gap);
+
+        // Other routes have their own buckets.
+        assert!(limiter.reserve(&messages_route("456")).is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn global_429_holds_every_route() {
+        let limiter = DiscordRateLimiter::new();
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("2"));
+        headers.insert("x-ratelimit-global", HeaderValue::from_static("true"));
+        let advised = limiter.record("POST /channels/1/messages", StatusCode::TOO_MANY_REQUESTS, &headers);
+        assert_eq!(advised, Some(Duration::from_secs(2)));
+
+        let wait = limiter.reserve(&messages_route("2")).expect("global limit applies to all routes");
+        assert_eq!(wait, Duration::from_secs(2));
+        tokio::time::advance(Duration::from_secs(2)).await;
+        assert!(limiter.reserve(&messages_route("2")).is_none());
+    }
+}
@@ -1,8 +1,9 @@
-//! Email channel — poll IMAP for unread emails from a configured sender, reply via SMTP, mark read.
+//! Email channel — poll IMAP for unread emails from the configured senders, reply via SMTP, mark read.
 //!
-//! Processes only unread emails received after `start_time` and from `from_sender`, in chronological
-//! order (oldest first). For each email: send content to agent, wait for reply, send reply email,
-//! mark as read, then continue to the next.
+//! Processes only unread emails received after `start_time` and from `from_sender` (or one of
+//! `allowed_senders`), in chronological order (oldest first). For each email: save its attachments
+//! to the workspace, send content to agent, wait for reply, send the reply in the same thread
+//! (`In-Reply-To` / `References`), mark as read, then continue to the next.
 
 use anyhow::{Context, Result};
 use async_imap::Session;
@@ -11,10 +12,11 @@ use futures_util::StreamExt;
 use lettre::message::Mailbox;
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
-use mail_parser::MessageParser;
+use mail_parser::{HeaderValue, MessageParser, MimeHeaders};
 use std::collections::HashMap;
 use std::fmt;
 use std::io;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context as TaskContext, Poll};
@@ -26,12 +28,176 @@ use tokio_native_tls::TlsStream;
 use tracing::{debug, error, info, warn};
 
 use crate::bus::{InboundMessage, OutboundMessage, OutboundMessageType};
-use crate::channels::Channel;
+use crate::channels::{file_handler, Channel};
 use crate::config::EmailConfig;
 
 /// Chat id format: "from_addr:uid" so we can reply and mark the right message read.
 const CHAT_ID_SEP: char = ':';
 
+/// Attachment size cap when `maxAttachmentMb` is not set.
+const DEFAULT_MAX_ATTACHMENT_MB: u64 = 20;
+
+/// What a reply needs to land in the sender's thread.
+#[derive(Debug, Clone, Default)]
+struct EmailThread {
+    to_addr: String,
+    subject: String,
+    /// Message-ID of the email being answered, without angle brackets.
+    message_id: Option<String>,
+    /// References of the email being answered, oldest first.
+    references: Vec<String>,
+}
+
+/// A reply the agent has not sent yet, and the signal that lets polling move on.
+struct PendingReply {
+    thread: EmailThread,
+    reply_tx: oneshot::Sender<()>,
+}
+
+type PendingReplies = Arc<RwLock<HashMap<String, PendingReply>>>;
+
+/// The parts of a fetched email the channel uses.
+#[derive(Debug, Default)]
+struct ParsedEmail {
+    subject: String,
+    body: String,
+    message_id: Option<String>,
+    references: Vec<String>,
+    /// (file name, contents) of each attachment.
+    attachments: Vec<(String, Vec<u8>)>,
+}
+
+fn header_ids(value: &HeaderValue) -> Vec<String> {
+    match value {
+        HeaderValue::Text(id) => vec![id.to_string()],
+        HeaderValue::TextList(ids) => ids.iter().map(|id| id.to_string()).collect(),
+        _ => vec![],
+    }
+}
+
+/// Parse a raw RFC 5322 message; input that does not parse becomes the body as-is.
+fn parse_email(raw: &[u8]) -> ParsedEmail {
+    let Some(m) = MessageParser::default().parse(raw) else {
+        return ParsedEmail {
+            body: String::from_utf8_lossy(raw).trim().to_string(),
+            ..Default::default()
+        };
+    };
+    let body = m
+        .body_text(0)
+        .or_else(|| m.body_html(0))
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default();
+    let attachments = m
+        .attachments()
+        .enumerate()
+        .map(|(i, part)| {
+            let name = part
+                .attachment_name()
+                .map(String::from)
+                .unwrap_or_else(|| format!("attachment_{}", i + 1));
+            (name, part.contents().to_vec())
+        })
+        .collect();
+    ParsedEmail {
+        subject: m.subject().unwrap_or_default().to_string(),
+        body,
+        message_id: m.message_id().map(String::from),
+        references: header_ids(m.references()),
+        attachments,
+    }
+}
+
+/// Save `attachments` under `ws`, skipping those larger than `max_bytes`; returns the absolute
+/// paths of the saved files.
+fn save_attachments_to(ws: &Path, attachments: &[(String, Vec<u8>)], max_bytes: u64) -> Vec<String> {
+    attachments
+        .iter()
+        .filter_map(|(name, bytes)| {
+            if bytes.len() as u64 > max_bytes {
+                warn!(
+                    file = %name,
+                    size = bytes.len(),
+                    max_bytes,
+                    "Email channel: attachment over the size limit, dropped"
+                );
+                return None;
+            }
+            match file_handler::save_incoming_file(ws, name, bytes) {
+                Ok(path) => Some(path.to_string_lossy().into_owned()),
+                Err(e) => {
+                    warn!(file = %name, error = %e, "Email channel: failed to save attachment");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Whether mail from `addr` is processed: `fromSender` or one of `allowedSenders`, ignoring case.
+fn sender_allowed(config: &EmailConfig, addr: &str) -> bool {
+    std::iter::once(&config.from_sender)
+        .chain(&config.allowed_senders)
+        .map(|s| s.trim())
+        .any(|s| !s.is_empty() && s.eq_ignore_ascii_case(addr.trim()))
+}
+
+/// Inbound message for `email` from `from_addr`; `media` are its saved attachments.
+fn email_to_inbound(
+    config: &EmailConfig,
+    chat_id: &str,
+    from_addr: &str,
+    email: &ParsedEmail,
+    media: Vec<String>,
+) -> InboundMessage {
+    let content = if email.body.is_empty() { "(no body)" } else { email.body.as_str() };
+    InboundMessage {
+        channel: config.name.clone(),
+        sender_id: from_addr.to_string(),
+        chat_id: chat_id.to_string(),
+        content: content.to_string(),
+        timestamp: chrono::Utc::now(),
+        media,
+        metadata: serde_json::json!({
+            "default_agent": config.default_agent,
+            "email_subject": email.subject,
+        }),
+    }
+}
+
+/// Subject of a reply: a single `Re: ` however often the thread was answered.
+fn reply_subject(subject: &str) -> String {
+    let subject = subject.trim();
+    if subject.get(..3).is_some_and(|p| p.eq_ignore_ascii_case("re:")) {
+        subject.to_string()
+    } else if subject.is_empty() {
+        "Re: Reply".to_string()
+    } else {
+        format!("Re: {}", subject)
+    }
+}
+
+/// Reply email for `thread`, with `In-Reply-To` and `References` so mail clients keep it in the thread.
+fn build_reply(config: &EmailConfig, thread: &EmailThread, body: &str) -> Result<Message> {
+    let from_mailbox = Mailbox::new(None, config.smtp.username.parse().context("parse from")?);
+    let to_mailbox = Mailbox::new(None, thread.to_addr.parse().context("parse to")?);
+    let mut builder = Message::builder()
+        .from(from_mailbox)
+        .to(to_mailbox)
+        .subject(reply_subject(&thread.subject));
+    if let Some(id) = &thread.message_id {
+        let references = thread
+            .references
+            .iter()
+            .chain(std::iter::once(id))
+            .map(|r| format!("<{}>", r))
+            .collect::<Vec<_>>()
+            .join(" ");
+        builder = builder.in_reply_to(format!("<{}>", id)).references(references);
+    }
+    builder.body(body.to_string()).context("build email")
+}
+
 /// IMAP stream: either TLS-wrapped or plain TCP (async_imap requires Debug on the stream type).
 #[derive(Debug)]
 enum ImapStreamKind {
@@ -85,8 +251,10 @@ pub struct EmailChannel {
     tool_result_preview_chars: usize,
     inbound_tx: mpsc::Sender<InboundMessage>,
     outbound_rx: Option<broadcast::Receiver<OutboundMessage>>,
-    /// chat_id -> (from_addr, uid, reply_tx). When we get the outbound reply we send email, mark read, then signal.
-    pending: Arc<RwLock<HashMap<String, (String, u32, oneshot::Sender<()>)>>>,
+    /// chat_id -> pending reply. When we get the outbound reply we send email, mark read, then signal.
+    pending: PendingReplies,
+    /// Where attachments are saved; without it they are dropped.
+    workspace_dir: Option<PathBuf>,
 }
 
 impl EmailChannel {
@@ -96,6 +264,7 @@ impl EmailChannel {
         outbound_rx: broadcast::Receiver<OutboundMessage>,
         show_tool_calls: bool,
         tool_result_preview_chars: usize,
+        workspace_dir: Option<PathBuf>,
     ) -> Self {
         Self {
             config,
@@ -104,6 +273,7 @@ impl EmailChannel {
             inbound_tx,
             outbound_rx: Some(outbound_rx),
             pending: Arc::new(RwLock::new(HashMap::new())),
+            workspace_dir,
         }
     }
 
@@ -205,11 +375,11 @@ impl EmailChannel {
         }
     }
 
-    /// Fetch unread emails from the configured sender, after start_time, sorted old to new.
+    /// Fetch unread emails from the allowed senders, after start_time, sorted old to new.
     async fn fetch_unread(
         &self,
         session: &mut Session<ImapStreamKind>,
-    ) -> Result<Vec<(u32, String, String)>> {
+    ) -> Result<Vec<(u32, String, Vec<u8>)>> {
         let since = Self::parse_start_time(&self.config.start_time);
         if let Some(ref since_dt) = since {
             info!(
@@ -265,7 +435,7 @@ impl EmailChannel {
                     .internal_date()
                     .map(|d| d.with_timezone(&Utc))
                     .unwrap_or_else(Utc::now);
-                if from_addr != from_sender {
+                if !sender_allowed(&self.config, &from_addr) {
                     if first_skip.is_none() {
                         first_skip = Some((uid, from_addr.clone(), dt, "sender_mismatch"));
                     }
@@ -320,17 +490,17 @@ impl EmailChannel {
 
         let mut out = Vec::with_capacity(sorted.len());
         for (uid, _dt, from_addr) in sorted {
-            let body = self.fetch_body(session, uid).await?;
-            out.push((uid, from_addr, body));
+            let raw = self.fetch_raw(session, uid).await?;
+            out.push((uid, from_addr, raw));
         }
         Ok(out)
     }
 
-    async fn fetch_body(
+    async fn fetch_raw(
         &self,
         session: &mut Session<ImapStreamKind>,
         uid: u32,
-    ) -> Result<String> {
+    ) -> Result<Vec<u8>> {
         let uid_set = uid.to_string();
         let mut fetch = session
             .uid_fetch(&uid_set, "(BODY.PEEK[])")
@@ -339,18 +509,25 @@ impl EmailChannel {
         let msg = fetch.next().await.transpose().context("fetch body")?;
         let msg = match msg {
             Some(m) => m,
-            None => return Ok(String::new()),
+            None => return Ok(Vec::new()),
+        };
+        Ok(msg.body().unwrap_or_default().to_vec())
+    }
+
+    /// Save the email's attachments to the workspace; returns their absolute paths.
+    fn save_attachments(&self, email: &ParsedEmail) -> Vec<String> {
+        let Some(ws) = self.workspace_dir.as_deref() else {
+            if !email.attachments.is_empty() {
+                warn!(channel = %self.config.name, "Email channel: no workspace, attachments dropped");
+            }
+            return vec![];
         };
-        let raw = msg.body().unwrap_or_default();
-        let parsed = MessageParser::default().parse(raw);
-        let body = parsed
-            .and_then(|m| {
-                m.body_text(0)
-                    .map(|s| s.to_string())
-                    .or_else(|| m.body_html(0).map(|s| s.to_string()))
-            })
-            .unwrap_or_else(|| String::from_utf8_lossy(raw).trim().to_string());
-        Ok(body.trim().to_string())
+        let max_bytes = self
+            .config
+            .max_attachment_mb
+            .unwrap_or(DEFAULT_MAX_ATTACHMENT_MB)
+            .saturating_mul(1024 * 1024);
+        save_attachments_to(ws, &email.attachments, max_bytes)
     }
 
     async fn mark_read(
@@ -370,7 +547,7 @@ impl EmailChannel {
     async fn run_outbound_listener(
         channel_name: String,
         mut outbound_rx: broadcast::Receiver<OutboundMessage>,
-        pending: Arc<RwLock<HashMap<String, (String, u32, oneshot::Sender<()>)>>>,
+        pending: PendingReplies,
         config: EmailConfig,
         show_tool_calls: bool,
         tool_result_preview_chars: usize,
@@ -386,11 +563,7 @@ impl EmailChannel {
                     status,
                     result_preview,
                 } if show_tool_calls => {
-                    let preview = if result_preview.len() > tool_result_preview_chars {
-                        format!("{}...", result_preview.chars().take(tool_result_preview_chars).collect::<String>())
-                    } else {
-                        result_preview.clone()
-                    };
+                    let preview = crate::channels::preview(result_preview, tool_result_preview_chars);
                     (format!("🔧 {} — {}\n{}", tool_name, status, preview), false)
                 }
                 _ => continue,
@@ -398,10 +571,8 @@ impl EmailChannel {
             let chat_id = msg.chat_id.clone();
             if is_chat {
                 let entry = pending.write().await.remove(&chat_id);
-                if let Some((from_addr, _uid, reply_tx)) = entry {
-                    if let Err(e) =
-                        Self::send_reply_static(&config, &from_addr, "Reply", &content, None).await
-                    {
+                if let Some(PendingReply { thread, reply_tx }) = entry {
+                    if let Err(e) = Self::send_reply_static(&config, &thread, &content).await {
                         error!(error = %e, "Email channel: send reply failed");
                     }
                     let _ = reply_tx.send(());
@@ -410,13 +581,7 @@ impl EmailChannel {
         }
     }
 
-    async fn send_reply_static(
-        config: &EmailConfig,
-        to_addr: &str,
-        subject: &str,
-        body: &str,
-        in_reply_to: Option<&str>,
-    ) -> Result<()> {
+    async fn send_reply_static(config: &EmailConfig, thread: &EmailThread, body: &str) -> Result<()> {
         let cfg = &config.smtp;
         let port = if cfg.port != 0 { cfg.port } else if cfg.use_tls { 465 } else { 587 };
         let creds = Credentials::new(cfg.username.clone(), cfg.password.clone());
@@ -431,16 +596,7 @@ impl EmailChannel {
                 .credentials(creds)
                 .build()
         };
-        let from_mailbox = Mailbox::new(None, cfg.username.parse().context("parse from")?);
-        let to_mailbox = Mailbox::new(None, to_addr.parse().context("parse to")?);
-        let mut builder = Message::builder()
-            .from(from_mailbox)
-            .to(to_mailbox)
-            .subject(format!("Re: {}", subject));
-        if let Some(irt) = in_reply_to {
-            builder = builder.header(lettre::message::header::InReplyTo::from(irt.to_string()));
-        }
-        let email = builder.body(body.to_string()).context("build email")?;
+        let email = build_reply(config, thread, body)?;
         mailer.send(email).await.context("SMTP send")?;
         Ok(())
     }
@@ -489,34 +645,32 @@ impl EmailChannel {
             count = list.len(),
             "Email channel: processing messages"
         );
-        for (uid, from_addr, body) in list {
+        for (uid, from_addr, raw) in list {
+            let email = parse_email(&raw);
+            let media = self.save_attachments(&email);
             let chat_id = Self::chat_id(&from_addr, uid);
             let (tx, rx) = oneshot::channel();
             {
+                let thread = EmailThread {
+                    to_addr: from_addr.clone(),
+                    subject: email.subject.clone(),
+                    message_id: email.message_id.clone(),
+                    references: email.references.clone(),
+                };
                 let mut p = self.pending.write().await;
-                p.insert(chat_id.clone(), (from_addr.clone(), uid, tx));
+                p.insert(chat_id.clone(), PendingReply { thread, reply_tx: tx });
             }
-            let content = if body.is_empty() { "(no body)" } else { body.as_str() };
             info!(
                 channel = %self.config.name,
                 uid,
                 from = %from_addr,
                 chat_id = %chat_id,
+                attachments = media.len(),
                 "Email channel: sending to agent"
             );
             let _ = self
                 .inbound_tx
-                .send(InboundMessage {
-                    channel: self.config.name.clone(),
-                    sender_id: from_addr.clone(),
-                    chat_id: chat_id.clone(),
-                    content: content.to_string(),
-                    timestamp: chrono::Utc::now(),
-                    media: vec![],
-                    metadata: serde_json::json!({
-                        "default_agent": self.config.default_agent,
-                    }),
-                })
+                .send(email_to_inbound(&self.config, &chat_id, &from_addr, &email, media))
                 .await;
             match tokio::time::timeout(std::time::Duration::from_secs(600), rx).await {
                 Ok(Ok(())) => {}
@@ -588,11 +742,7 @@ impl Channel for EmailChannel {
                 status,
                 result_preview,
             } if self.show_tool_calls => {
-                let preview = if result_preview.len() > self.tool_result_preview_chars {
-                    format!("{}...", result_preview.chars().take(self.tool_result_preview_chars).collect::<String>())
-                } else {
-                    result_preview.clone()
-                };
+                let preview = crate::channels::preview(result_preview, self.tool_result_preview_chars);
                 (format!("🔧 {} — {}\n{}", tool_name, status, preview), false)
             }
             _ => return Ok(()),
@@ -601,10 +751,8 @@ impl Channel for EmailChannel {
             let mut guard = self.pending.write().await;
             guard.remove(&msg.chat_id)
         };
-        if let Some((from_addr, _uid, _)) = entry {
-            if let Err(e) =
-                Self::send_reply_static(&self.config, &from_addr, "Reply", &content, None).await
-            {
+        if let Some(PendingReply { thread, .. }) = entry {
+            if let Err(e) = Self::send_reply_static(&self.config, &thread, &content).await {
                 tracing::error!(error = %e, "Email channel: send failed");
                 return Err(e.into());
             }
@@ -612,3 +760,103 @@ impl Channel for EmailChannel {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RAW_EMAIL: &str = concat!(
+        "From: Alice <alice@example.com>\r\n",
+        "To: bot@example.com\r\n",
+        "Subject: Weekly report\r\n",
+        "Message-ID: <msg-2@example.com>\r\n",
+        "References: <msg-1@example.com>\r\n",
+        "MIME-Version: 1.0\r\n",
+        "Content-Type: multipart/mixed; boundary=\"b1\"\r\n",
+        "\r\n",
+        "--b1\r\n",
+        "Content-Type: text/plain; charset=utf-8\r\n",
+        "\r\n",
+        "Please summarize the attached numbers.\r\n",
+        "--b1\r\n",
+        "Content-Type: text/csv\r\n",
+        "Content-Disposition: attachment; filename=\"numbers.csv\"\r\n",
+        "Content-Transfer-Encoding: base64\r\n",
+        "\r\n",
+        "YSxiCjEsMgo=\r\n",
+        "--b1--\r\n",
+    );
+
+    fn config() -> EmailConfig {
+        let mut cfg = EmailConfig {
+            name: "mail".into(),
+            from_sender: "Alice@Example.com".into(),
+            allowed_senders: vec!["bob@example.com".into()],
+            default_agent: "main".into(),
+            ..Default::default()
+        };
+        cfg.smtp.username = "bot@example.com".into();
+        cfg
+    }
+
+    #[test]
+    fn email_becomes_inbound_with_attachments() {
+        let cfg = config();
+        assert!(sender_allowed(&cfg, "alice@example.com"));
+        assert!(sender_allowed(&cfg, "bob@example.com"));
+        assert!(!sender_allowed(&cfg, "mallory@example.com"));
+
+        let email = parse_email(RAW_EMAIL.as_bytes());
+        assert_eq!(email.subject, "Weekly report");
+        assert_eq!(email.body, "Please summarize the attached numbers.");
+        assert_eq!(email.message_id.as_deref(), Some("msg-2@example.com"));
+        assert_eq!(email.references, vec!["msg-1@example.com".to_string()]);
+        assert_eq!(email.attachments, vec![("numbers.csv".to_string(), b"a,b\n1,2\n".to_vec())]);
+
+        let chat_id = EmailChannel::chat_id("alice@example.com", 7);
+        let media = vec!["numbers.csv".to_string()];
+        let inbound = email_to_inbound(&cfg, &chat_id, "alice@example.com", &email, media);
+        assert_eq!(inbound.channel, "mail");
+        assert_eq!(inbound.chat_id, "alice@example.com:7");
+        assert_eq!(inbound.sender_id, "alice@example.com");
+        assert_eq!(inbound.content, "Please summarize the attached numbers.");
+        assert_eq!(inbound.media, vec!["numbers.csv".to_string()]);
+        assert_eq!(inbound.metadata["email_subject"], "Weekly report");
+    }
+
+    #[test]
+    fn attachments_are_saved_with_absolute_paths_up_to_the_limit() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let attachments = vec![
+            ("small.txt".to_string(), b"ok".to_vec()),
+            ("big.bin".to_string(), vec![0u8; 16]),
+        ];
+        let saved = save_attachments_to(tmp.path(), &attachments, 8);
+        assert_eq!(saved, vec![tmp.path().join("small.txt").to_string_lossy().into_owned()]);
+        assert!(Path::new(&saved[0]).is_absolute());
+        assert!(!tmp.path().join("big.bin").exists());
+    }
+
+    #[test]
+    fn reply_is_threaded_under_the_original() {
+        let email = parse_email(RAW_EMAIL.as_bytes());
+        let thread = EmailThread {
+            to_addr: "alice@example.com".into(),
+            subject: email.subject,
+            message_id: email.message_id,
+            references: email.references,
+        };
+        let reply = build_reply(&config(), &thread, "Total: 3").unwrap();
+        let headers = String::from_utf8(reply.formatted()).unwrap();
+        assert!(headers.contains("Subject: Re: Weekly report\r\n"), "{}", headers);
+        assert!(headers.contains("In-Reply-To: <msg-2@example.com>\r\n"), "{}", headers);
+        assert!(
+            headers.contains("References: <msg-1@example.com> <msg-2@example.com>\r\n"),
+            "{}",
+            headers
+        );
+
+        assert_eq!(reply_subject("RE: Weekly report"), "RE: Weekly report");
+        assert_eq!(reply_subject(""), "Re: Reply");
+    }
+}
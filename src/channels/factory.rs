@@ -5,18 +5,33 @@ use std::sync::Arc;
 use anyhow::Result;
 
 use crate::channels::{
-    dingtalk, discord, email, feishu, irc, matrix, slack, telegram, whatsapp, Channel, ChannelRegistry,
-    ChannelStartContext,
+    dingtalk, email, irc, matrix, slack, webhook, whatsapp, Channel, ChannelRegistry, ChannelStartContext,
 };
+#[cfg(feature = "discord")]
+use crate::channels::discord;
+#[cfg(feature = "feishu")]
+use crate::channels::feishu;
+#[cfg(feature = "telegram")]
+use crate::channels::telegram;
+#[cfg(feature = "discord")]
+use crate::config::DiscordConfig;
+#[cfg(feature = "feishu")]
+use crate::config::FeishuConfig;
+#[cfg(feature = "telegram")]
+use crate::config::TelegramConfig;
 use crate::config::{
-    DingTalkConfig, DiscordConfig, EmailConfig, FeishuConfig, IrcConfig, MatrixConfig, SlackConfig,
-    TelegramConfig, WhatsAppConfig,
+    DingTalkConfig, EmailConfig, IrcConfig, MatrixConfig, SlackConfig, WebhookConfig,
+    WhatsAppConfig,
 };
 
-/// Register all built-in channel factories (telegram, feishu, discord, slack, email, matrix, dingtalk, whatsapp, irc).
+/// Register all built-in channel factories (telegram, feishu, discord, slack, email, matrix, dingtalk, whatsapp, irc, webhook).
+/// Telegram, Feishu and Discord are only registered when their Cargo feature is enabled.
 pub fn register_builtin_channels(registry: &mut ChannelRegistry) {
+    #[cfg(feature = "telegram")]
     registry.register("telegram", Arc::new(TelegramChannelFactory));
+    #[cfg(feature = "feishu")]
     registry.register("feishu", Arc::new(FeishuChannelFactory));
+    #[cfg(feature = "discord")]
     registry.register("discord", Arc::new(DiscordChannelFactory));
     registry.register("slack", Arc::new(SlackChannelFactory));
     registry.register("email", Arc::new(EmailChannelFactory));
@@ -24,6 +39,7 @@ pub fn register_builtin_channels(registry: &mut ChannelRegistry) {
     registry.register("dingtalk", Arc::new(DingTalkChannelFactory));
     registry.register("whatsapp", Arc::new(WhatsAppChannelFactory));
     registry.register("irc", Arc::new(IrcChannelFactory));
+    registry.register("webhook", Arc::new(WebhookChannelFactory));
 }
 
 struct DingTalkChannelFactory;
@@ -48,8 +64,10 @@ impl crate::channels::ChannelFactory for DingTalkChannelFactory {
     }
 }
 
+#[cfg(feature = "telegram")]
 struct TelegramChannelFactory;
 
+#[cfg(feature = "telegram")]
 impl crate::channels::ChannelFactory for TelegramChannelFactory {
     fn create(
         &self,
@@ -57,7 +75,7 @@ impl crate::channels::ChannelFactory for TelegramChannelFactory {
         ctx: ChannelStartContext,
     ) -> Result<Box<dyn Channel>> {
         let cfg: TelegramConfig = serde_json::from_value(config)?;
-        let ch = telegram::TelegramChannel::new(
+        let mut ch = telegram::TelegramChannel::new(
             cfg,
             ctx.inbound_tx,
             ctx.outbound_rx,
@@ -65,12 +83,17 @@ impl crate::channels::ChannelFactory for TelegramChannelFactory {
             ctx.tool_result_preview_chars,
             ctx.config_path,
         );
+        if let Some(queue) = ctx.dead_letters {
+            ch = ch.with_dead_letters(queue);
+        }
         Ok(Box::new(ch))
     }
 }
 
+#[cfg(feature = "feishu")]
 struct FeishuChannelFactory;
 
+#[cfg(feature = "feishu")]
 impl crate::channels::ChannelFactory for FeishuChannelFactory {
     fn create(
         &self,
@@ -100,8 +123,10 @@ impl crate::channels::ChannelFactory for FeishuChannelFactory {
     }
 }
 
+#[cfg(feature = "discord")]
 struct DiscordChannelFactory;
 
+#[cfg(feature = "discord")]
 impl crate::channels::ChannelFactory for DiscordChannelFactory {
     fn create(
         &self,
@@ -159,6 +184,7 @@ impl crate::channels::ChannelFactory for EmailChannelFactory {
             ctx.outbound_rx,
             ctx.show_tool_calls,
             ctx.tool_result_preview_chars,
+            ctx.workspace,
         );
         Ok(Box::new(ch))
     }
@@ -214,10 +240,28 @@ impl crate::channels::ChannelFactory for IrcChannelFactory {
     }
 }
 
+struct WebhookChannelFactory;
+
+impl crate::channels::ChannelFactory for WebhookChannelFactory {
+    fn create(
+        &self,
+        config: serde_json::Value,
+        ctx: ChannelStartContext,
+    ) -> Result<Box<dyn Channel>> {
+        let cfg: WebhookConfig = serde_json::from_value(config)?;
+        let mut ch = webhook::WebhookChannel::new(cfg, ctx.outbound_rx);
+        if let Some(queue) = ctx.dead_letters {
+            ch = ch.with_dead_letters(queue);
+        }
+        Ok(Box::new(ch))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(all(feature = "telegram", feature = "feishu", feature = "discord"))]
     #[test]
     fn register_builtin_channels_populates_registry() {
         let mut registry = ChannelRegistry::new();
@@ -232,14 +276,30 @@ mod tests {
         assert!(names.contains(&"dingtalk".to_string()));
         assert!(names.contains(&"whatsapp".to_string()));
         assert!(names.contains(&"irc".to_string()));
-        assert_eq!(names.len(), 9);
+        assert!(names.contains(&"webhook".to_string()));
+        assert_eq!(names.len(), 10);
     }
 
     #[test]
     fn registry_get_returns_factory_after_register() {
         let mut registry = ChannelRegistry::new();
         register_builtin_channels(&mut registry);
+        #[cfg(feature = "telegram")]
         assert!(registry.get("telegram").is_some());
+        assert!(registry.get("slack").is_some());
         assert!(registry.get("nonexistent").is_none());
     }
+
+    /// Build check for `cargo test --no-default-features --features telegram`.
+    #[cfg(all(feature = "telegram", not(feature = "discord"), not(feature = "feishu")))]
+    #[test]
+    fn telegram_only_build_registers_telegram_without_discord_or_feishu() {
+        let mut registry = ChannelRegistry::new();
+        register_builtin_channels(&mut registry);
+        assert!(registry.get("telegram").is_some());
+        assert!(registry.get("discord").is_none());
+        assert!(registry.get("feishu").is_none());
+        assert!(registry.get("webhook").is_some());
+        assert_eq!(registry.type_names().len(), 8);
+    }
 }
@@ -4,7 +4,6 @@
 //! operations use the official Feishu Open API (feishu_api module). Config is
 //! unchanged (app_id, app_secret, allowlist, etc.).
 
-use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
@@ -21,12 +20,16 @@ use crate::channels::file_handler;
 use crate::channels::approval_classifier;
 use crate::channels::feishu_api::FeishuApiClient;
 use crate::channels::feishu_ws::{build_event_response_frame, get_ws_endpoint, run_ws_loop};
-use crate::channels::{approval_formatter, Channel, RetryPolicy, RetryState};
+use crate::channels::{
+    approval_formatter, split_message, strip_mention, Channel, LengthUnit, QuotedMessage,
+    RetryPolicy, RetryState,
+};
 use crate::config::{
     pairing_allows, pairing_message, pairings_from_config_file_cached, AllowlistEntry, FeishuConfig,
+    FeishuMessageFormat, MentionMode,
 };
 use crate::rig_provider::SynbotCompletionModel;
-use crate::tools::approval::{ApprovalManager, ApprovalResponse};
+use crate::tools::approval::{ApprovalManager, ApprovalResponse, PendingApprovals};
 
 /// Optional sender to notify the user when file upload fails (e.g. missing permission).
 type OutboundTx = Option<tokio::sync::broadcast::Sender<OutboundMessage>>;
@@ -34,7 +37,7 @@ type OutboundTx = Option<tokio::sync::broadcast::Sender<OutboundMessage>>;
 /// Per-channel state for processing events (pending approvals, classifier, workspace).
 #[derive(Clone)]
 struct FeishuChannelEventState {
-    pending_approvals: Arc<RwLock<HashMap<String, (String, String)>>>,
+    pending_approvals: Arc<RwLock<PendingApprovals>>,
     approval_classifier: Option<Arc<dyn SynbotCompletionModel>>,
     workspace_dir: Option<PathBuf>,
 }
@@ -64,7 +67,7 @@ pub struct FeishuChannel {
     running: bool,
     approval_manager: Option<Arc<ApprovalManager>>,
     approval_classifier: Option<Arc<dyn SynbotCompletionModel>>,
-    pending_approvals: Arc<RwLock<HashMap<String, (String, String)>>>,
+    pending_approvals: Arc<RwLock<PendingApprovals>>,
     workspace_dir: Option<PathBuf>,
     config_path: Option<PathBuf>,
 }
@@ -129,6 +132,9 @@ pub struct FeishuMessage {
     pub content: Option<String>,
     #[serde(default)]
     pub mentions: Option<Vec<FeishuMention>>,
+    /// Message this one replies to (set when the user replies to or quotes a message).
+    #[serde(default)]
+    pub parent_id: Option<String>,
 }
 
 /// True if this mention refers to the app bot (`open_id` from bot info) or matches configured display name.
@@ -242,11 +248,13 @@ fn strip_leading_feishu_placeholder_for_bot(
 }
 
 /// Resolves group @-bot: Feishu uses `@_user_1` + `mentions[]`, not literal `@显示名`.
+/// With `MentionMode::Anywhere`, a mention later in the text is also accepted and removed.
 fn feishu_resolve_group_mention(
     text: &str,
     expect_name: &str,
     bot_open_id: Option<&str>,
     mentions: Option<&[FeishuMention]>,
+    mode: MentionMode,
 ) -> Option<String> {
     let trimmed = text.trim_start();
     if let Some(b) = bot_open_id {
@@ -276,9 +284,40 @@ fn feishu_resolve_group_mention(
             return Some(s);
         }
     }
+    if mode == MentionMode::Anywhere {
+        let mut tokens: Vec<&str> = vec![at_name.as_str()];
+        tokens.extend(
+            mentions
+                .unwrap_or_default()
+                .iter()
+                .filter(|m| feishu_mention_is_bot(m, bot_open_id, expect_name))
+                .filter_map(|m| m.key.as_deref()),
+        );
+        return strip_mention(trimmed, &tokens, mode);
+    }
     None
 }
 
+/// Build the quoted message from a `GET /im/v1/messages/{id}` item. Only text bodies are
+/// quoted; other message types are described by their type (e.g. `[image]`).
+fn feishu_quoted_from_message_item(item: &serde_json::Value) -> Option<QuotedMessage> {
+    let message_id = item.get("message_id").and_then(|v| v.as_str())?.to_string();
+    let msg_type = item.get("msg_type").and_then(|v| v.as_str()).unwrap_or("");
+    let content = match msg_type {
+        "text" => {
+            let body = item.pointer("/body/content").and_then(|v| v.as_str()).unwrap_or("");
+            strip_feishu_leading_user_placeholders(&feishu_extract_text_message_body(body))
+        }
+        "" => String::new(),
+        other => format!("[{other}]"),
+    };
+    Some(QuotedMessage {
+        message_id,
+        sender_id: item.pointer("/sender/id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+        content,
+    })
+}
+
 /// Parse Feishu `message_type == "text"` `content` JSON: plain string or rich array (`tag: text|at`).
 fn feishu_extract_text_message_body(content_str: &str) -> String {
     let Ok(v) = serde_json::from_str::<serde_json::Value>(content_str) else {
@@ -320,13 +359,52 @@ fn feishu_extract_text_message_body(content_str: &str) -> String {
 // Helpers: token, upload, file type, approval keywords, error classification
 // ---------------------------------------------------------------------------
 
-/// Download file/image from a message using "get message resource" API.
+/// Attachment size cap when `maxMediaMb` is not set.
+const DEFAULT_MAX_MEDIA_MB: u64 = 20;
+
+/// Downloadable attachment of an image / file / audio / media message.
+#[derive(Debug, Clone, PartialEq)]
+struct FeishuMediaResource {
+    key: String,
+    /// `image` or `file`: the `type` parameter of the message resource API.
+    resource_type: &'static str,
+    file_name: String,
+}
+
+/// Resource referenced by the `content` JSON of an attachment message: `file_key` (files,
+/// audio, video) or `image_key` (images). `None` when the content names neither.
+fn feishu_media_resource(message_type: &str, content_str: &str) -> Option<FeishuMediaResource> {
+    let content = serde_json::from_str::<serde_json::Value>(content_str).ok()?;
+    let field = |name: &str| {
+        content
+            .get(name)
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+    };
+    let (key, resource_type) = match field("file_key") {
+        Some(file_key) => (file_key, "file"),
+        None => (field("image_key")?, "image"),
+    };
+    let file_name = field("file_name").unwrap_or_else(|| {
+        if message_type == "image" { "image.png" } else { "file" }.to_string()
+    });
+    Some(FeishuMediaResource {
+        key,
+        resource_type,
+        file_name,
+    })
+}
+
+/// Download file/image from a message using "get message resource" API. Stops reading and fails
+/// once the body passes `max_bytes`.
 async fn feishu_fetch_message_resource(
     app_id: &str,
     app_secret: &str,
     message_id: &str,
     file_key: &str,
     resource_type: &str,
+    max_bytes: u64,
 ) -> Result<Vec<u8>, String> {
     let client = reqwest::Client::builder()
         .build()
@@ -347,8 +425,9 @@ async fn feishu_fetch_message_resource(
         let body = resp.text().await.unwrap_or_default();
         return Err(format!("HTTP {}: {}", status, body));
     }
-    let bytes = resp.bytes().await.map_err(|e: reqwest::Error| e.to_string())?;
-    Ok(bytes.to_vec())
+    super::feishu_api::read_body_limited(resp, max_bytes)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 async fn feishu_upload_image(
@@ -480,6 +559,123 @@ fn classify_feishu_error(error_msg: &str) -> FeishuWsError {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Rich messages: post (rich text) and interactive approval cards
+// ---------------------------------------------------------------------------
+
+/// Key of the approval request ID in the value of an approval card's buttons.
+const CARD_APPROVAL_REQUEST_KEY: &str = "approval_request_id";
+
+/// `post` content with `text` as one Markdown paragraph.
+fn build_post_content(text: &str) -> serde_json::Value {
+    serde_json::json!({
+        "zh_cn": {
+            "title": "",
+            "content": [[{ "tag": "md", "text": text }]],
+        }
+    })
+}
+
+fn approval_card_button(label: &str, button_type: &str, request_id: &str, approved: bool) -> serde_json::Value {
+    serde_json::json!({
+        "tag": "button",
+        "type": button_type,
+        "text": { "tag": "plain_text", "content": label },
+        "value": { CARD_APPROVAL_REQUEST_KEY: request_id, "approved": approved },
+    })
+}
+
+/// Interactive card showing `text` with Approve / Deny buttons for `request_id`. A click comes
+/// back as a `card.action.trigger` callback (see [`parse_card_approval_action`]).
+fn build_approval_card(request_id: &str, text: &str) -> serde_json::Value {
+    serde_json::json!({
+        "config": { "wide_screen_mode": true },
+        "header": {
+            "template": "orange",
+            "title": { "tag": "plain_text", "content": "Approval required" },
+        },
+        "elements": [
+            { "tag": "markdown", "content": text },
+            {
+                "tag": "action",
+                "actions": [
+                    approval_card_button("Approve", "primary", request_id, true),
+                    approval_card_button("Deny", "danger", request_id, false),
+                ],
+            },
+        ],
+    })
+}
+
+/// A click on an approval card button.
+#[derive(Debug, Clone, PartialEq)]
+struct FeishuCardApproval {
+    request_id: String,
+    approved: bool,
+    operator_open_id: String,
+    chat_id: Option<String>,
+}
+
+/// Read an Approve / Deny click from the `event` body of a `card.action.trigger` callback.
+/// `None` for other card actions.
+fn parse_card_approval_action(event: &serde_json::Value) -> Option<FeishuCardApproval> {
+    let value = event.get("action")?.get("value")?;
+    let request_id = value.get(CARD_APPROVAL_REQUEST_KEY)?.as_str()?;
+    let approved = value.get("approved")?.as_bool()?;
+    let operator_open_id = event.get("operator")?.get("open_id")?.as_str()?;
+    let chat_id = event
+        .get("context")
+        .and_then(|c| c.get("open_chat_id"))
+        .and_then(|v| v.as_str())
+        .map(String::from);
+    Some(FeishuCardApproval {
+        request_id: request_id.to_string(),
+        approved,
+        operator_open_id: operator_open_id.to_string(),
+        chat_id,
+    })
+}
+
+/// Answer a pending approval from a card button click. Only the user who may answer the request
+/// (the routed approver, or the session's user) can click it, as with text replies.
+async fn process_card_action(
+    event: &serde_json::Value,
+    approval_manager: Option<&Arc<ApprovalManager>>,
+    pending_approvals: &Arc<RwLock<PendingApprovals>>,
+) {
+    let action = match parse_card_approval_action(event) {
+        Some(a) => a,
+        None => {
+            debug!("Feishu card action is not an approval button; ignoring");
+            return;
+        }
+    };
+    let taken = pending_approvals.write().await.take_request(
+        &action.request_id,
+        &action.operator_open_id,
+        action.chat_id.as_deref().unwrap_or_default(),
+    );
+    if !taken {
+        warn!(
+            request_id = %action.request_id,
+            operator = %action.operator_open_id,
+            "Feishu card approval is not pending for this user; ignoring"
+        );
+        return;
+    }
+    if let Some(mgr) = approval_manager {
+        let response = ApprovalResponse {
+            request_id: action.request_id,
+            approved: action.approved,
+            responder: action.operator_open_id,
+            timestamp: chrono::Utc::now(),
+        };
+        if let Err(e) = mgr.submit_response(response).await {
+            error!("Feishu failed to submit card approval response: {e:#}");
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // FeishuChannel
 // ---------------------------------------------------------------------------
@@ -504,7 +700,7 @@ impl FeishuChannel {
             running: false,
             approval_manager: None,
             approval_classifier: None,
-            pending_approvals: Arc::new(RwLock::new(HashMap::new())),
+            pending_approvals: Arc::new(RwLock::new(PendingApprovals::new())),
             workspace_dir,
             config_path,
         }
@@ -536,15 +732,7 @@ impl FeishuChannel {
     /// Send text message via Feishu IM v1 API (chunked if needed).
     async fn send_text(client: &FeishuApiClient, chat_id: &str, text: &str) -> Result<()> {
         const CHUNK_SIZE: usize = 30_000;
-        let chunks: Vec<&str> = if text.len() <= CHUNK_SIZE {
-            vec![text]
-        } else {
-            text.as_bytes()
-                .chunks(CHUNK_SIZE)
-                .map(|c| std::str::from_utf8(c).unwrap_or(""))
-                .collect()
-        };
-        for chunk in chunks {
+        for chunk in split_message(text, CHUNK_SIZE, LengthUnit::Bytes) {
             let content = serde_json::json!({ "text": chunk }).to_string();
             client
                 .send_message("chat_id", chat_id, "text", &content)
@@ -557,6 +745,48 @@ impl FeishuChannel {
         Ok(())
     }
 
+    /// Send `post` rich-text messages (chunked like [`Self::send_text`]).
+    async fn send_post(client: &FeishuApiClient, chat_id: &str, text: &str) -> Result<()> {
+        const CHUNK_SIZE: usize = 30_000;
+        for chunk in split_message(text, CHUNK_SIZE, LengthUnit::Bytes) {
+            let content = build_post_content(&chunk).to_string();
+            client
+                .send_message("chat_id", chat_id, "post", &content)
+                .await
+                .map_err(|e| {
+                    error!("Feishu send_post error: {e:#}");
+                    e
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Send a reply in the channel's `messageFormat`.
+    async fn send_reply(
+        client: &FeishuApiClient,
+        chat_id: &str,
+        text: &str,
+        format: FeishuMessageFormat,
+    ) -> Result<()> {
+        match format {
+            FeishuMessageFormat::Text => Self::send_text(client, chat_id, text).await,
+            FeishuMessageFormat::Post => Self::send_post(client, chat_id, text).await,
+        }
+    }
+
+    /// Send an approval request as an interactive card with Approve / Deny buttons.
+    async fn send_approval_card(
+        client: &FeishuApiClient,
+        chat_id: &str,
+        request_id: &str,
+        text: &str,
+    ) -> Result<()> {
+        let content = build_approval_card(request_id, text).to_string();
+        client
+            .send_message("chat_id", chat_id, "interactive", &content)
+            .await
+    }
+
     async fn notify_system_error(&self, error_msg: &str) {
         let notification = InboundMessage {
             channel: "system".into(),
@@ -582,7 +812,7 @@ struct WsEventPayload {
     #[serde(default)]
     header: WsEventHeader,
     #[serde(default)]
-    event: Option<FeishuImMessageEvent>,
+    event: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -603,13 +833,15 @@ async fn attempt_ws_connection(
     config_path: Option<PathBuf>,
     enable_allowlist: bool,
     group_my_name: Option<String>,
+    mention_mode: MentionMode,
+    max_media_mb: Option<u64>,
     bot_open_id: Option<String>,
     default_agent: String,
     app_id: String,
     app_secret: String,
     approval_manager: Option<Arc<ApprovalManager>>,
     approval_classifier: Option<Arc<dyn SynbotCompletionModel>>,
-    pending_approvals: Arc<RwLock<HashMap<String, (String, String)>>>,
+    pending_approvals: Arc<RwLock<PendingApprovals>>,
     workspace_dir: Option<PathBuf>,
 ) -> std::result::Result<(), FeishuWsError> {
     let http_client = if std::env::var_os("SYNBOT_IN_APP_SANDBOX").is_some() {
@@ -638,6 +870,16 @@ async fn attempt_ws_connection(
         group_my_name: group_my_name.clone(),
         show_tool_calls: true,
         default_agent: default_agent.clone(),
+        inbound_limit: None,
+        outbound_min_interval_ms: None,
+        commands: Vec::new(),
+        tools_enabled: true,
+        provider: None,
+        model: None,
+        role: None,
+        mention_mode,
+        message_format: Default::default(),
+        max_media_mb,
     };
 
     info!("Feishu WebSocket connecting...");
@@ -656,15 +898,26 @@ async fn attempt_ws_connection(
             let payload = frame.payload.as_ref().and_then(|p| {
                 serde_json::from_slice::<WsEventPayload>(p).ok()
             });
-            let event = match payload.as_ref() {
-                Some(p) if p.header.event_type.as_deref() == Some("im.message.receive_v1") => {
-                    p.event.clone()
-                }
+            let (event_type, event) = match payload {
+                Some(WsEventPayload { header, event: Some(event) }) => (header.event_type, event),
                 _ => return None,
             };
-            let event = match event {
-                Some(ev) => ev,
-                None => return None,
+            match event_type.as_deref() {
+                Some("im.message.receive_v1") => {}
+                Some("card.action.trigger") => {
+                    process_card_action(&event, approval_manager.as_ref(), &event_state.pending_approvals)
+                        .await;
+                    let elapsed = start.elapsed().as_millis();
+                    return Some(build_event_response_frame(&frame, elapsed));
+                }
+                _ => return None,
+            }
+            let event = match serde_json::from_value::<FeishuImMessageEvent>(event) {
+                Ok(ev) => ev,
+                Err(e) => {
+                    warn!("Feishu im.message.receive_v1 event did not parse: {e}");
+                    return None;
+                }
             };
             let client = FeishuApiClient::new(&config.app_id, &config.app_secret);
             process_im_message_receive(
@@ -732,109 +985,99 @@ async fn process_im_message_receive(
     let message_type = msg.message_type.as_deref().unwrap_or("").to_string();
     let content_str = msg.content.as_deref().unwrap_or("");
 
-    let is_file_like = message_type == "file" || message_type == "image" || message_type == "media";
+    let is_file_like = matches!(message_type.as_str(), "file" | "image" | "audio" | "media");
     let workspace_dir = event_state.and_then(|s| s.workspace_dir.as_ref());
 
     if is_file_like {
-        if let Some(ws) = workspace_dir {
-            if let Ok(content_json) = serde_json::from_str::<serde_json::Value>(content_str) {
-                let file_key = content_json.get("file_key").and_then(|v| v.as_str());
-                let image_key = content_json.get("image_key").and_then(|v| v.as_str());
-                let file_name = content_json
-                    .get("file_name")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or(if message_type == "image" { "image.png" } else { "file" });
-                let (key, use_image_api) = if let Some(fk) = file_key {
-                    (fk.to_string(), false)
-                } else if let Some(ik) = image_key {
-                    (ik.to_string(), true)
-                } else {
-                    (String::new(), false)
-                };
-                if !key.is_empty() {
-                    let data_result = if use_image_api {
-                        client.get_image(&key).await
+        let resource = feishu_media_resource(&message_type, content_str);
+        if let (Some(ws), Some(resource)) = (workspace_dir, resource) {
+            let file_name = resource.file_name.as_str();
+            let max_bytes = config
+                .max_media_mb
+                .unwrap_or(DEFAULT_MAX_MEDIA_MB)
+                .saturating_mul(1024 * 1024);
+            let use_image_api = resource.resource_type == "image";
+            let data_result = if use_image_api {
+                client.get_image(&resource.key, max_bytes).await
+            } else {
+                client.get_file(&resource.key, max_bytes).await
+            };
+            let data_result = match data_result {
+                Ok(d) => Ok(d),
+                Err(e) => {
+                    warn!("Feishu file/image get failed, trying message resource API: {e:#}");
+                    feishu_fetch_message_resource(
+                        &config.app_id,
+                        &config.app_secret,
+                        &message_id,
+                        &resource.key,
+                        resource.resource_type,
+                        max_bytes,
+                    )
+                    .await
+                    .map_err(anyhow::Error::msg)
+                }
+            };
+            match data_result {
+                Ok(data) => {
+                    if let Ok(path) = file_handler::save_incoming_file(ws, file_name, &data) {
+                        let media_path = path.to_string_lossy().into_owned();
+                        let _ = inbound_tx
+                            .send(InboundMessage {
+                                channel: channel_name.to_string(),
+                                sender_id: sender_open_id.clone(),
+                                chat_id: chat_id.clone(),
+                                content: format!("[File] {}", file_name),
+                                timestamp: chrono::Utc::now(),
+                                media: vec![media_path],
+                                metadata: serde_json::json!({
+                                    "message_id": message_id,
+                                    "message_type": message_type,
+                                    "chat_type": chat_type,
+                                    "default_agent": config.default_agent,
+                                }),
+                            })
+                            .await;
                     } else {
-                        client.get_file(&key).await
-                    };
-                    let data_result = match data_result {
-                        Ok(d) => Ok(d),
-                        Err(e) => {
-                            warn!("Feishu file/image get failed, trying message resource API: {e:#}");
-                            let resource_type = if use_image_api { "image" } else { "file" };
-                            feishu_fetch_message_resource(
-                                &config.app_id,
-                                &config.app_secret,
-                                &message_id,
-                                &key,
-                                resource_type,
-                            )
-                            .await
-                            .map_err(anyhow::Error::msg)
-                        }
-                    };
-                    match data_result {
-                        Ok(data) => {
-                            if let Ok(path) = file_handler::save_incoming_file(ws, file_name, &data) {
-                                let media_path = path.to_string_lossy().into_owned();
-                                let _ = inbound_tx
-                                    .send(InboundMessage {
-                                        channel: channel_name.to_string(),
-                                        sender_id: sender_open_id.clone(),
-                                        chat_id: chat_id.clone(),
-                                        content: format!("[File] {}", file_name),
-                                        timestamp: chrono::Utc::now(),
-                                        media: vec![media_path],
-                                        metadata: serde_json::json!({
-                                            "message_id": message_id,
-                                            "message_type": message_type,
-                                            "chat_type": chat_type,
-                                            "default_agent": config.default_agent,
-                                        }),
-                                    })
-                                    .await;
-                            } else {
-                                let _ = inbound_tx
-                                    .send(InboundMessage {
-                                        channel: channel_name.to_string(),
-                                        sender_id: sender_open_id.clone(),
-                                        chat_id: chat_id.clone(),
-                                        content: format!("[File] {} failed to save to workspace", file_name),
-                                        timestamp: chrono::Utc::now(),
-                                        media: vec![],
-                                        metadata: serde_json::json!({
-                                            "message_id": message_id,
-                                            "message_type": message_type,
-                                            "chat_type": chat_type,
-                                            "default_agent": config.default_agent,
-                                        }),
-                                    })
-                                    .await;
-                            }
-                        }
-                        Err(e) => {
-                            let _ = inbound_tx
-                                .send(InboundMessage {
-                                    channel: channel_name.to_string(),
-                                    sender_id: sender_open_id.clone(),
-                                    chat_id: chat_id.clone(),
-                                    content: format!("[File] {} download failed ({})", file_name, e),
-                                    timestamp: chrono::Utc::now(),
-                                    media: vec![],
-                                    metadata: serde_json::json!({
-                                        "message_id": message_id,
-                                        "message_type": message_type,
-                                        "chat_type": chat_type,
-                                        "download_error": e.to_string(),
-                                        "default_agent": config.default_agent,
-                                    }),
-                                })
-                                .await;
-                        }
-                    };
+                        let _ = inbound_tx
+                            .send(InboundMessage {
+                                channel: channel_name.to_string(),
+                                sender_id: sender_open_id.clone(),
+                                chat_id: chat_id.clone(),
+                                content: format!("[File] {} failed to save to workspace", file_name),
+                                timestamp: chrono::Utc::now(),
+                                media: vec![],
+                                metadata: serde_json::json!({
+                                    "message_id": message_id,
+                                    "message_type": message_type,
+                                    "chat_type": chat_type,
+                                    "default_agent": config.default_agent,
+                                }),
+                            })
+                            .await;
+                    }
                 }
-                return;
-            }
+                Err(e) => {
+                    let _ = inbound_tx
+                        .send(InboundMessage {
+                            channel: channel_name.to_string(),
+                            sender_id: sender_open_id.clone(),
+                            chat_id: chat_id.clone(),
+                            content: format!("[File] {} download failed ({})", file_name, e),
+                            timestamp: chrono::Utc::now(),
+                            media: vec![],
+                            metadata: serde_json::json!({
+                                "message_id": message_id,
+                                "message_type": message_type,
+                                "chat_type": chat_type,
+                                "download_error": e.to_string(),
+                                "default_agent": config.default_agent,
+                            }),
+                        })
+                        .await;
+                }
+            };
+            return;
         }
         warn!(
             "Feishu {} message skipped (no workspace or missing file_key/image_key)",
@@ -866,6 +1109,7 @@ async fn process_im_message_receive(
     let allowlist = &config.allowlist;
     let enable_allowlist = config.enable_allowlist;
     let group_my_name = &config.group_my_name;
+    let mention_mode = config.mention_mode;
 
     let mentions_slice = msg
         .mentions
@@ -897,9 +1141,13 @@ async fn process_im_message_receive(
     let (_trigger_agent, content, is_group_meta) = if !enable_allowlist {
         if is_group {
             if let Some(ref my_name) = group_my_name {
-                if let Some(stripped) =
-                    feishu_resolve_group_mention(&text, my_name, bot_open_id, mentions_slice)
-                {
+                if let Some(stripped) = feishu_resolve_group_mention(
+                    &text,
+                    my_name,
+                    bot_open_id,
+                    mentions_slice,
+                    mention_mode,
+                ) {
                     (true, stripped, true)
                 } else {
                     debug!(
@@ -970,9 +1218,13 @@ async fn process_im_message_receive(
             None => {
                 if is_group {
                     if let Some(ref my_name) = group_my_name {
-                        if let Some(stripped) =
-                            feishu_resolve_group_mention(&text, my_name, bot_open_id, mentions_slice)
-                        {
+                        if let Some(stripped) = feishu_resolve_group_mention(
+                            &text,
+                            my_name,
+                            bot_open_id,
+                            mentions_slice,
+                            mention_mode,
+                        ) {
                             (true, stripped, true)
                         } else {
                             debug!(
@@ -1008,9 +1260,13 @@ async fn process_im_message_receive(
             Some(e) => {
                 if let Some(ref my_name) = e.my_name {
                     if is_group {
-                        if let Some(stripped) =
-                            feishu_resolve_group_mention(&text, my_name, bot_open_id, mentions_slice)
-                        {
+                        if let Some(stripped) = feishu_resolve_group_mention(
+                            &text,
+                            my_name,
+                            bot_open_id,
+                            mentions_slice,
+                            mention_mode,
+                        ) {
                             (true, stripped, true)
                         } else {
                             debug!(
@@ -1059,11 +1315,8 @@ async fn process_im_message_receive(
     let approval_classifier = event_state.and_then(|s| s.approval_classifier.clone());
 
     if let Some(ref pending) = pending_approvals {
-        let removed = {
-            let mut guard = pending.write().await;
-            guard.remove(&chat_id)
-        };
-        if let Some((request_id, _)) = removed {
+        let removed = pending.write().await.take_reply(&sender_open_id, &chat_id);
+        if let Some(request_id) = removed {
             let mut approved_opt: Option<bool> = None;
             if let Some(ref model) = approval_classifier {
                 approved_opt = approval_classifier::classify_approval_response(model.as_ref(), &content).await;
@@ -1115,6 +1368,16 @@ async fn process_im_message_receive(
     if is_group_meta {
         meta["group"] = serde_json::json!(true);
     }
+    if let Some(parent_id) = msg.parent_id.as_deref().filter(|p| !p.is_empty()) {
+        match client.get_message(parent_id).await {
+            Ok(item) => {
+                if let Some(quoted) = feishu_quoted_from_message_item(&item) {
+                    quoted.attach_to(&mut meta);
+                }
+            }
+            Err(e) => warn!(parent_id = %parent_id, "Feishu: failed to fetch replied-to message: {e:#}"),
+        }
+    }
     let inbound = InboundMessage {
         channel: channel_name.to_string(),
         sender_id: sender_open_id,
@@ -1181,6 +1444,7 @@ impl Channel for FeishuChannel {
         let pending_approvals_clone = self.pending_approvals.clone();
         let show_tool_calls = self.show_tool_calls;
         let tool_result_preview_chars = self.tool_result_preview_chars;
+        let message_format = self.config.message_format;
         let workspace_dir = self.workspace_dir.clone();
         let outbound_tx_for_fail = self.outbound_tx.clone();
 
@@ -1201,16 +1465,7 @@ impl Channel for FeishuChannel {
                         if !show_tool_calls {
                             continue;
                         }
-                        let preview = if result_preview.is_empty() {
-                            String::new()
-                        } else if result_preview.len() > tool_result_preview_chars {
-                            format!(
-                                "{}...",
-                                result_preview.chars().take(tool_result_preview_chars).collect::<String>()
-                            )
-                        } else {
-                            result_preview.clone()
-                        };
+                        let preview = crate::channels::preview(&result_preview, tool_result_preview_chars);
                         let content = if preview.is_empty() {
                             format!("🔧 {} — {}", tool_name, status)
                         } else {
@@ -1219,22 +1474,29 @@ impl Channel for FeishuChannel {
                         (content, vec![])
                     }
                     crate::bus::OutboundMessageType::ApprovalRequest { request } => {
-                        let user_id = request.session_id.split(':').last().unwrap_or("").to_string();
-                        if !user_id.is_empty() {
-                            let mut pending = pending_approvals_clone.write().await;
-                            pending.insert(user_id, (request.id.clone(), msg.chat_id.clone()));
-                        }
+                        pending_approvals_clone.write().await.insert(&request, &msg.chat_id);
                         let content = request
                             .display_message
                             .as_deref()
                             .filter(|s| !s.is_empty())
                             .map(String::from)
                             .unwrap_or_else(|| FeishuChannel::format_approval_request(&request));
-                        (content, vec![])
+                        match FeishuChannel::send_approval_card(&outbound_client, &msg.chat_id, &request.id, &content)
+                            .await
+                        {
+                            Ok(()) => continue,
+                            Err(e) => {
+                                warn!("Feishu approval card send failed, sending as text: {e:#}");
+                                (content, vec![])
+                            }
+                        }
                     }
+                    // Feishu bots have no typing status API.
+                    crate::bus::OutboundMessageType::Typing => continue,
                 };
                 if !content.is_empty() {
-                    if let Err(e) = FeishuChannel::send_text(&outbound_client, &msg.chat_id, &content).await
+                    if let Err(e) =
+                        FeishuChannel::send_reply(&outbound_client, &msg.chat_id, &content, message_format).await
                     {
                         error!("Feishu outbound send error: {e:#}");
                     }
@@ -1329,6 +1591,8 @@ impl Channel for FeishuChannel {
                 self.config_path.clone(),
                 self.config.enable_allowlist,
                 self.config.group_my_name.clone(),
+                self.config.mention_mode,
+                self.config.max_media_mb,
                 feishu_bot_open_id.clone(),
                 self.config.default_agent.clone(),
                 self.config.app_id.clone(),
@@ -1404,15 +1668,21 @@ impl Channel for FeishuChannel {
             crate::bus::OutboundMessageType::Chat { content, media } => {
                 (content.clone(), media.clone())
             }
-            crate::bus::OutboundMessageType::ApprovalRequest { request } => (
-                request
+            crate::bus::OutboundMessageType::ApprovalRequest { request } => {
+                let content = request
                     .display_message
                     .as_deref()
                     .filter(|s| !s.is_empty())
                     .map(String::from)
-                    .unwrap_or_else(|| approval_formatter::format_approval_request(request)),
-                vec![],
-            ),
+                    .unwrap_or_else(|| approval_formatter::format_approval_request(request));
+                match FeishuChannel::send_approval_card(&client, &msg.chat_id, &request.id, &content).await {
+                    Ok(()) => return Ok(()),
+                    Err(e) => {
+                        warn!("Feishu approval card send failed, sending as text: {e:#}");
+                        (content, vec![])
+                    }
+                }
+            }
             crate::bus::OutboundMessageType::ToolProgress {
                 tool_name,
                 status,
@@ -1421,30 +1691,19 @@ impl Channel for FeishuChannel {
                 if !self.show_tool_calls {
                     return Ok(());
                 }
-                let preview = if result_preview.is_empty() {
-                    String::new()
-                } else if result_preview.len() > self.tool_result_preview_chars {
-                    format!(
-                        "{}...",
-                        result_preview
-                            .chars()
-                            .take(self.tool_result_preview_chars)
-                            .collect::<String>()
-                    )
-                } else {
-                    result_preview.clone()
-                };
+                let preview = crate::channels::preview(&result_preview, self.tool_result_preview_chars);
                 let content = if preview.is_empty() {
                     format!("🔧 {} — {}", tool_name, status)
                 } else {
                     format!("🔧 {} — {}\n{}", tool_name, status, preview)
                 };
-                FeishuChannel::send_text(&client, &msg.chat_id, &content).await?;
+                FeishuChannel::send_reply(&client, &msg.chat_id, &content, self.config.message_format).await?;
                 return Ok(());
             }
+            crate::bus::OutboundMessageType::Typing => return Ok(()),
         };
         if !content.is_empty() {
-            FeishuChannel::send_text(&client, &msg.chat_id, &content).await?;
+            FeishuChannel::send_reply(&client, &msg.chat_id, &content, self.config.message_format).await?;
         }
         if !media.is_empty() && self.workspace_dir.is_some() {
             let ws = self.workspace_dir.as_ref().unwrap();
@@ -1532,4 +1791,217 @@ mod feishu_parse_tests {
             "hello"
         );
     }
+
+    #[test]
+    fn parent_message_item_becomes_quote() {
+        let item = serde_json::json!({
+            "message_id": "om_parent",
+            "msg_type": "text",
+            "body": { "content": r#"{"text":"@_user_1 release notes draft"}"# },
+            "sender": { "id": "ou_alice", "id_type": "open_id" },
+        });
+        let q = feishu_quoted_from_message_item(&item).unwrap();
+        assert_eq!(q.message_id, "om_parent");
+        assert_eq!(q.sender_id, "ou_alice");
+        assert_eq!(q.content, "release notes draft");
+
+        let image = serde_json::json!({ "message_id": "om_img", "msg_type": "image" });
+        assert_eq!(feishu_quoted_from_message_item(&image).unwrap().content, "[image]");
+    }
+
+    #[test]
+    fn message_event_deserializes_parent_id() {
+        let j = r#"{"message_id":"om_2","chat_id":"oc_1","parent_id":"om_1","content":"{}"}"#;
+        let m: FeishuMessage = serde_json::from_str(j).unwrap();
+        assert_eq!(m.parent_id.as_deref(), Some("om_1"));
+    }
+
+    fn bot_mention(key: &str) -> FeishuMention {
+        let j = format!(r#"{{"key":"{key}","name":"SynBot","id":{{"open_id":"ou_bot"}}}}"#);
+        serde_json::from_str(&j).unwrap()
+    }
+
+    #[test]
+    fn group_mention_mid_message_needs_anywhere_mode() {
+        let mentions = [bot_mention("@_user_1")];
+        let text = "hey @_user_1 summarize this";
+        assert_eq!(
+            feishu_resolve_group_mention(
+                text,
+                "SynBot",
+                Some("ou_bot"),
+                Some(&mentions),
+                MentionMode::Prefix,
+            ),
+            None
+        );
+        assert_eq!(
+            feishu_resolve_group_mention(
+                text,
+                "SynBot",
+                Some("ou_bot"),
+                Some(&mentions),
+                MentionMode::Anywhere,
+            ),
+            Some("hey summarize this".to_string())
+        );
+    }
+
+    #[test]
+    fn group_mention_anywhere_matches_literal_name_and_keeps_other_placeholders() {
+        let mentions = [bot_mention("@_user_2")];
+        assert_eq!(
+            feishu_resolve_group_mention(
+                "ping @_user_1 and @_user_2 please",
+                "SynBot",
+                Some("ou_bot"),
+                Some(&mentions),
+                MentionMode::Anywhere,
+            ),
+            Some("ping @_user_1 and please".to_string())
+        );
+        assert_eq!(
+            feishu_resolve_group_mention(
+                "请 @SynBot 看一下",
+                "SynBot",
+                None,
+                None,
+                MentionMode::Anywhere,
+            ),
+            Some("请 看一下".to_string())
+        );
+    }
+
+    #[test]
+    fn attachment_messages_resolve_their_resource() {
+        assert_eq!(
+            feishu_media_resource("image", r#"{"image_key":"img_v3_abc"}"#),
+            Some(FeishuMediaResource {
+                key: "img_v3_abc".into(),
+                resource_type: "image",
+                file_name: "image.png".into(),
+            })
+        );
+        // Videos carry a thumbnail image_key as well; the file_key is the video itself.
+        assert_eq!(
+            feishu_media_resource(
+                "media",
+                r#"{"file_key":"file_v3_vid","image_key":"img_v3_thumb","file_name":"clip.mp4"}"#
+            ),
+            Some(FeishuMediaResource {
+                key: "file_v3_vid".into(),
+                resource_type: "file",
+                file_name: "clip.mp4".into(),
+            })
+        );
+        assert_eq!(feishu_media_resource("file", r#"{"file_name":"a.pdf"}"#), None);
+        assert_eq!(feishu_media_resource("image", "not json"), None);
+    }
+
+    #[test]
+    fn approval_card_has_approve_and_deny_buttons_for_the_request() {
+        let card = build_approval_card("req-1", "command: `rm -rf /tmp/x`");
+        assert_eq!(card["elements"][0]["content"], "command: `rm -rf /tmp/x`");
+        let actions = card["elements"][1]["actions"].as_array().unwrap();
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[0]["text"]["content"], "Approve");
+        assert_eq!(actions[0]["value"][CARD_APPROVAL_REQUEST_KEY], "req-1");
+        assert_eq!(actions[0]["value"]["approved"], true);
+        assert_eq!(actions[1]["text"]["content"], "Deny");
+        assert_eq!(actions[1]["value"]["approved"], false);
+
+        let post = build_post_content("**done**");
+        assert_eq!(post["zh_cn"]["content"][0][0]["tag"], "md");
+        assert_eq!(post["zh_cn"]["content"][0][0]["text"], "**done**");
+    }
+
+    fn card_action_event(request_id: &str, approved: bool, operator: &str) -> serde_json::Value {
+        serde_json::json!({
+            "operator": { "open_id": operator },
+            "action": {
+                "tag": "button",
+                "value": { "approval_request_id": request_id, "approved": approved },
+            },
+            "context": { "open_message_id": "om_1", "open_chat_id": "oc_chat" },
+        })
+    }
+
+    #[test]
+    fn card_action_callback_parses_into_approval() {
+        assert_eq!(
+            parse_card_approval_action(&card_action_event("req-1", false, "ou_user")),
+            Some(FeishuCardApproval {
+                request_id: "req-1".into(),
+                approved: false,
+                operator_open_id: "ou_user".into(),
+                chat_id: Some("oc_chat".into()),
+            })
+        );
+        let other_button = serde_json::json!({
+            "operator": { "open_id": "ou_user" },
+            "action": { "tag": "button", "value": { "page": 2 } },
+        });
+        assert_eq!(parse_card_approval_action(&other_button), None);
+    }
+
+    #[tokio::test]
+    async fn card_action_only_answers_approval_pending_for_that_user() {
+        let request = crate::tools::approval::ApprovalRequest {
+            id: "req-1".into(),
+            session_id: "main:main:feishu:group:ou_user".into(),
+            channel: "feishu".into(),
+            chat_id: "oc_chat".into(),
+            command: "ls".into(),
+            working_dir: "/".into(),
+            context: String::new(),
+            timestamp: chrono::Utc::now(),
+            timeout_secs: 10,
+            display_message: None,
+            approver_id: None,
+        };
+        let pending = Arc::new(RwLock::new(PendingApprovals::new()));
+        pending.write().await.insert(&request, "oc_chat");
+
+        process_card_action(&card_action_event("req-1", true, "ou_other"), None, &pending).await;
+        assert_eq!(pending.read().await.len(), 1, "other users cannot answer");
+
+        process_card_action(&card_action_event("req-1", true, "ou_user"), None, &pending).await;
+        assert!(pending.read().await.is_empty());
+    }
+
+    /// Serve one response whose body is sent in chunks without a Content-Length.
+    async fn chunked_response(chunks: usize, chunk_size: usize) -> reqwest::Response {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+            let _ = socket
+                .write_all(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n")
+                .await;
+            let chunk = format!("{:x}\r\n{}\r\n", chunk_size, "x".repeat(chunk_size));
+            for _ in 0..chunks {
+                if socket.write_all(chunk.as_bytes()).await.is_err() {
+                    return;
+                }
+            }
+            let _ = socket.write_all(b"0\r\n\r\n").await;
+        });
+        reqwest::get(format!("http://{}/", addr)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn downloads_stop_at_the_byte_limit_without_content_length() {
+        let within = super::super::feishu_api::read_body_limited(chunked_response(4, 100).await, 400)
+            .await
+            .unwrap();
+        assert_eq!(within.len(), 400);
+
+        let err = super::super::feishu_api::read_body_limited(chunked_response(1000, 100).await, 1000)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("1000 byte limit"), "{}", err);
+    }
 }
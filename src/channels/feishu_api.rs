@@ -147,8 +147,32 @@ impl FeishuApiClient {
         Ok(())
     }
 
-    /// GET /open-apis/im/v1/images/{image_key} — returns raw bytes.
-    pub async fn get_image(&self, image_key: &str) -> Result<Vec<u8>> {
+    /// GET /open-apis/im/v1/messages/{message_id} — returns the message item (`data.items[0]`).
+    pub async fn get_message(&self, message_id: &str) -> Result<serde_json::Value> {
+        let token = self.tenant_access_token().await?;
+        let url = format!("{}/im/v1/messages/{}", FEISHU_BASE, message_id);
+        let resp = self
+            .http_client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await?;
+        let status = resp.status();
+        let json: serde_json::Value = resp.json().await?;
+        if !status.is_success() {
+            anyhow::bail!("Feishu get message API error {}: {:?}", status, json);
+        }
+        let code = json.get("code").and_then(|v| v.as_i64()).unwrap_or(-1);
+        if code != 0 {
+            anyhow::bail!("Feishu get message code {}: {:?}", code, json);
+        }
+        json.pointer("/data/items/0")
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("missing data.items in get message response"))
+    }
+
+    /// GET /open-apis/im/v1/images/{image_key} — returns raw bytes; fails once the body passes `max_bytes`.
+    pub async fn get_image(&self, image_key: &str, max_bytes: u64) -> Result<Vec<u8>> {
         let token = self.tenant_access_token().await?;
         let url = format!("{}/im/v1/images/{}", FEISHU_BASE, image_key);
         let resp = self
@@ -162,12 +186,11 @@ impl FeishuApiClient {
             let body = resp.text().await.unwrap_or_default();
             anyhow::bail!("Feishu get image API error {}: {}", status, body);
         }
-        let bytes = resp.bytes().await?;
-        Ok(bytes.to_vec())
+        read_body_limited(resp, max_bytes).await
     }
 
-    /// GET /open-apis/im/v1/files/{file_key} — returns raw bytes.
-    pub async fn get_file(&self, file_key: &str) -> Result<Vec<u8>> {
+    /// GET /open-apis/im/v1/files/{file_key} — returns raw bytes; fails once the body passes `max_bytes`.
+    pub async fn get_file(&self, file_key: &str, max_bytes: u64) -> Result<Vec<u8>> {
         let token = self.tenant_access_token().await?;
         let url = format!("{}/im/v1/files/{}", FEISHU_BASE, file_key);
         let resp = self
@@ -181,9 +204,24 @@ impl FeishuApiClient {
             let body = resp.text().await.unwrap_or_default();
             anyhow::bail!("Feishu get file API error {}: {}", status, body);
         }
-        let bytes = resp.bytes().await?;
-        Ok(bytes.to_vec())
+        read_body_limited(resp, max_bytes).await
+    }
+}
+
+/// Read a download body chunk by chunk, failing as soon as it passes `max_bytes` (or up front
+/// when the response announces more), so an oversized attachment is never held in memory.
+pub async fn read_body_limited(mut resp: reqwest::Response, max_bytes: u64) -> Result<Vec<u8>> {
+    if let Some(len) = resp.content_length().filter(|len| *len > max_bytes) {
+        anyhow::bail!("{} bytes exceeds the {} byte limit", len, max_bytes);
+    }
+    let mut data = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        if (data.len() + chunk.len()) as u64 > max_bytes {
+            anyhow::bail!("download exceeds the {} byte limit", max_bytes);
+        }
+        data.extend_from_slice(&chunk);
     }
+    Ok(data)
 }
 
 #[derive(Debug, Default)]
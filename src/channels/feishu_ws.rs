@@ -142,7 +142,8 @@ fn build_ping_frame(service_id: i32) -> Frame {
     }
 }
 
-/// Run WebSocket loop: connect, ping, handle binary frames, call on_event for "event" data frames.
+/// Run WebSocket loop: connect, ping, handle binary frames, call on_event for "event" and "card"
+/// (card action callback) data frames.
 /// Returns when the connection closes or errors.
 pub async fn run_ws_loop<F, Fut>(
     ws_url: String,
@@ -200,7 +201,7 @@ where
                             }
                             1 => {
                                 let msg_type = get_header(&frame.headers, "type").unwrap_or_default();
-                                if msg_type == "event" {
+                                if msg_type == "event" || msg_type == "card" {
                                     if let Some(_payload) = &frame.payload {
                                         let response = on_event(frame).await;
                                         if let Some(resp_frame) = response {
@@ -243,48 +244,10 @@ async fn ws_connect_async(
     String,
 > {
     if std::env::var_os("SYNBOT_IN_APP_SANDBOX").is_some() {
-        ws_connect_appcontainer(conn_url).await
+        crate::appcontainer_dns::ws_connect_appcontainer(conn_url).await
     } else {
         tokio_tungstenite::connect_async(conn_url)
             .await
             .map_err(|e| e.to_string())
     }
 }
-
-/// WebSocket connect when system DNS is unavailable (e.g. AppContainer): resolve via Google DNS.
-#[cfg(any(windows, unix))]
-pub async fn ws_connect_appcontainer(
-    conn_url: &str,
-) -> Result<
-    (
-        tokio_tungstenite::WebSocketStream<
-            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
-        >,
-        tokio_tungstenite::tungstenite::handshake::client::Response,
-    ),
-    String,
-> {
-    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
-    use url::Url;
-
-    let parsed = Url::parse(conn_url).map_err(|e| e.to_string())?;
-    let host = parsed.host_str().ok_or("URL has no host")?;
-    let port = parsed.port_or_known_default().ok_or("URL has no port")?;
-
-    let ip = crate::appcontainer_dns::resolve_host(host).await?;
-
-    let tcp = tokio::net::TcpStream::connect((ip, port))
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let mut request = conn_url
-        .into_client_request()
-        .map_err(|e| e.to_string())?;
-    request
-        .headers_mut()
-        .insert("host", host.try_into().map_err(|_| "invalid host")?);
-
-    tokio_tungstenite::client_async_tls_with_config(request, tcp, None, None)
-        .await
-        .map_err(|e| e.to_string())
-}
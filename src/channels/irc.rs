@@ -14,11 +14,14 @@ use tokio::sync::{broadcast, mpsc};
 use tracing::{error, info, warn};
 
 use crate::bus::{InboundMessage, OutboundMessage};
-use crate::channels::{Channel, RetryPolicy, RetryState};
+use crate::channels::{split_message, Channel, LengthUnit, RetryPolicy, RetryState};
 use crate::config::{
     pairing_allows, pairing_message, pairings_from_config_file_cached, IrcConfig,
 };
 
+/// Payload budget per PRIVMSG; the 512-byte line limit also covers the prefix, command and target.
+const IRC_MAX_LINE_BYTES: usize = 400;
+
 // ---------------------------------------------------------------------------
 // IrcChannel
 // ---------------------------------------------------------------------------
@@ -174,7 +177,8 @@ impl Channel for IrcChannel {
                 }
                 let content = match &msg.message_type {
                     crate::bus::OutboundMessageType::Chat { content, .. } => content.clone(),
-                    crate::bus::OutboundMessageType::ToolProgress { .. } => continue,
+                    crate::bus::OutboundMessageType::ToolProgress { .. }
+                    | crate::bus::OutboundMessageType::Typing => continue,
                     crate::bus::OutboundMessageType::ApprovalRequest { request } => request
                         .display_message
                         .as_deref()
@@ -184,8 +188,11 @@ impl Channel for IrcChannel {
                 };
 
                 // IRC has a 512-byte line limit; split long messages
-                for line in content.lines() {
-                    if let Err(e) = sender_out.send_privmsg(&msg.chat_id, line) {
+                for line in content
+                    .lines()
+                    .flat_map(|l| split_message(l, IRC_MAX_LINE_BYTES, LengthUnit::Bytes))
+                {
+                    if let Err(e) = sender_out.send_privmsg(&msg.chat_id, &line) {
                         error!(
                             channel = %channel_name_out,
                             error = %e,
@@ -362,6 +369,13 @@ mod tests {
             allowlist: vec![],
             enable_allowlist: true,
             agent: "main".to_string(),
+            inbound_limit: None,
+            outbound_min_interval_ms: None,
+            commands: Vec::new(),
+            tools_enabled: true,
+            provider: None,
+            model: None,
+            role: None,
         }
     }
 
@@ -456,6 +470,13 @@ mod tests {
             allowlist: vec![],
             enable_allowlist: true,
             agent: "main".to_string(),
+            inbound_limit: None,
+            outbound_min_interval_ms: None,
+            commands: Vec::new(),
+            tools_enabled: true,
+            provider: None,
+            model: None,
+            role: None,
         };
         let ch = IrcChannel::new(cfg, inbound_tx, outbound_rx, None);
         let irc_cfg = ch.build_irc_config();
@@ -479,6 +500,7 @@ mod tests {
             completion_model: None,
             outbound_tx: Some(outbound_tx),
             config_path: None,
+            dead_letters: None,
         };
         let config = serde_json::json!({
             "enabled": true,
@@ -511,6 +533,7 @@ mod tests {
             completion_model: None,
             outbound_tx: Some(outbound_tx),
             config_path: None,
+            dead_letters: None,
         };
         let result = factory.create(serde_json::json!("not_an_object"), ctx);
         assert!(result.is_err());
@@ -24,7 +24,7 @@ use tokio::sync::{broadcast, broadcast::error::RecvError, mpsc};
 use tracing::{debug, error, info, warn};
 
 use crate::bus::{InboundMessage, OutboundMessage};
-use crate::channels::{approval_formatter, Channel};
+use crate::channels::{approval_formatter, split_message, Channel, LengthUnit};
 use crate::config::{
     pairing_allows, pairing_message, pairings_from_config_file_cached, sessions_root, MatrixConfig,
 };
@@ -119,30 +119,6 @@ fn matrix_sqlite_path(cfg: &MatrixConfig) -> PathBuf {
     }
 }
 
-fn split_message(content: &str, max_len: usize) -> Vec<String> {
-    if max_len == 0 {
-        return vec![content.to_string()];
-    }
-    if content.len() <= max_len {
-        return vec![content.to_string()];
-    }
-    let mut chunks = Vec::new();
-    let mut remaining = content;
-    while !remaining.is_empty() {
-        if remaining.len() <= max_len {
-            chunks.push(remaining.to_string());
-            break;
-        }
-        let split_at = remaining[..max_len]
-            .rfind('\n')
-            .map(|pos| pos + 1)
-            .unwrap_or(max_len);
-        chunks.push(remaining[..split_at].to_string());
-        remaining = &remaining[split_at..];
-    }
-    chunks
-}
-
 // ---------------------------------------------------------------------------
 // MatrixChannel
 // ---------------------------------------------------------------------------
@@ -310,7 +286,7 @@ impl MatrixChannel {
                 return Ok(());
             }
         };
-        let chunks = split_message(content, MATRIX_MAX_MESSAGE_LEN);
+        let chunks = split_message(content, MATRIX_MAX_MESSAGE_LEN, LengthUnit::Chars);
         for chunk in chunks {
             let msg = RoomMessageEventContent::text_plain(chunk);
             room.send(msg).await.context("Matrix send message")?;
@@ -540,13 +516,7 @@ impl Channel for MatrixChannel {
                         if !show_tool_calls {
                             continue;
                         }
-                        let preview = if result_preview.is_empty() {
-                            String::new()
-                        } else if result_preview.len() > tool_result_preview_chars {
-                            format!("{}...", result_preview.chars().take(tool_result_preview_chars).collect::<String>())
-                        } else {
-                            result_preview.clone()
-                        };
+                        let preview = crate::channels::preview(result_preview, tool_result_preview_chars);
                         let content = if preview.is_empty() {
                             format!("{} — {}", tool_name, status)
                         } else {
@@ -562,11 +532,13 @@ impl Channel for MatrixChannel {
                             .unwrap_or_else(|| approval_formatter::format_approval_request(request));
                         (content, vec![])
                     }
+                    crate::bus::OutboundMessageType::Typing => continue,
                 };
                 if !content.is_empty() {
                     match client_out.get_room(&room_id) {
                         Some(room) => {
-                            let chunks = split_message(&content, MATRIX_MAX_MESSAGE_LEN);
+                            let chunks =
+                                split_message(&content, MATRIX_MAX_MESSAGE_LEN, LengthUnit::Chars);
                             for chunk in chunks {
                                 if let Err(e) =
                                     room.send(RoomMessageEventContent::text_plain(&chunk)).await
@@ -681,13 +653,7 @@ impl Channel for MatrixChannel {
                 if !self.show_tool_calls {
                     return Ok(());
                 }
-                let preview = if result_preview.is_empty() {
-                    String::new()
-                } else if result_preview.len() > self.tool_result_preview_chars {
-                    format!("{}...", result_preview.chars().take(self.tool_result_preview_chars).collect::<String>())
-                } else {
-                    result_preview.clone()
-                };
+                let preview = crate::channels::preview(result_preview, self.tool_result_preview_chars);
                 let content = if preview.is_empty() {
                     format!("{} — {}", tool_name, status)
                 } else {
@@ -696,6 +662,7 @@ impl Channel for MatrixChannel {
                 self.send_to_room(&room_id, &content).await?;
                 return Ok(());
             }
+            crate::bus::OutboundMessageType::Typing => return Ok(()),
         };
 
         if !content.is_empty() {
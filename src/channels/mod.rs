@@ -1,19 +1,28 @@
 pub mod approval_classifier;
 pub mod approval_formatter;
 pub mod file_handler;
+#[cfg(feature = "discord")]
 pub mod discord;
+#[cfg(feature = "discord")]
+pub mod discord_rate_limit;
 pub mod email;
+#[cfg(feature = "feishu")]
 pub mod feishu;
+#[cfg(feature = "feishu")]
 pub mod feishu_api;
+#[cfg(feature = "feishu")]
 pub mod feishu_ws;
 pub mod dingtalk;
 pub mod dingtalk_stream;
 pub mod factory;
 pub mod matrix;
+pub mod outbound_throttle;
 pub mod slack;
+#[cfg(feature = "telegram")]
 pub mod telegram;
 pub mod whatsapp;
 pub mod irc;
+pub mod webhook;
 
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -26,6 +35,7 @@ use tokio::sync::{broadcast, mpsc};
 use tracing::{info, warn};
 
 use crate::bus::{InboundMessage, OutboundMessage};
+use crate::config::MentionMode;
 
 // ---------------------------------------------------------------------------
 // Retry policy & state
@@ -33,6 +43,9 @@ use crate::bus::{InboundMessage, OutboundMessage};
 
 /// Retry strategy configuration using exponential backoff.
 ///
+/// The default is 5 retries, 1 s doubling up to 60 s, with up to +20% jitter;
+/// [`RetryPolicy::new`] starts without jitter.
+///
 /// Each Channel implementation maintains a `RetryState` internally.
 /// Unrecoverable errors (e.g., invalid credentials) are sent to `MessageBus`
 /// via `InboundMessage` with `channel: "system"`.
@@ -42,10 +55,13 @@ pub struct RetryPolicy {
     pub initial_delay: Duration,
     pub max_delay: Duration,
     pub backoff_factor: f64,
+    /// Random extra delay as a fraction of the backoff (0.2 = up to +20%), so channels that
+    /// failed together do not all reconnect at the same instant. Seeded by `SYNBOT_SEED`.
+    pub jitter: f64,
 }
 
 impl RetryPolicy {
-    /// Create a new `RetryPolicy` with the given parameters.
+    /// Create a new `RetryPolicy` with the given parameters and no jitter.
     pub fn new(
         max_retries: u32,
         initial_delay: Duration,
@@ -57,9 +73,16 @@ impl RetryPolicy {
             initial_delay,
             max_delay,
             backoff_factor,
+            jitter: 0.0,
         }
     }
 
+    /// Set the jitter fraction (see [`RetryPolicy::jitter`]).
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.max(0.0);
+        self
+    }
+
     /// Compute the delay for the *n*-th retry attempt (0-indexed).
     ///
     /// Formula: `min(initial_delay * backoff_factor^attempt, max_delay)`
@@ -68,6 +91,16 @@ impl RetryPolicy {
             * self.backoff_factor.powi(attempt as i32);
         Duration::from_millis(delay_ms.min(self.max_delay.as_millis() as f64) as u64)
     }
+
+    /// [`delay_for_attempt`](Self::delay_for_attempt) plus up to `jitter` of it drawn from `rng`,
+    /// still capped at `max_delay`.
+    pub fn jittered_delay(&self, attempt: u32, rng: &mut crate::seed::SeededRng) -> Duration {
+        let base = self.delay_for_attempt(attempt);
+        if self.jitter <= 0.0 {
+            return base;
+        }
+        (base + base.mul_f64(self.jitter * rng.next_f64())).min(self.max_delay.max(base))
+    }
 }
 
 impl Default for RetryPolicy {
@@ -77,6 +110,7 @@ impl Default for RetryPolicy {
             initial_delay: Duration::from_secs(1),
             max_delay: Duration::from_secs(60),
             backoff_factor: 2.0,
+            jitter: 0.2,
         }
     }
 }
@@ -87,6 +121,7 @@ pub struct RetryState {
     pub attempts: u32,
     pub last_error: Option<String>,
     pub in_cooldown: bool,
+    rng: crate::seed::SeededRng,
 }
 
 impl RetryState {
@@ -96,6 +131,7 @@ impl RetryState {
             attempts: 0,
             last_error: None,
             in_cooldown: false,
+            rng: crate::seed::rng("channel-retry"),
         }
     }
 
@@ -144,9 +180,9 @@ impl RetryState {
     }
 
     /// Return the delay to wait before the next retry attempt based on the
-    /// current number of attempts.
-    pub fn next_delay(&self, policy: &RetryPolicy) -> Duration {
-        policy.delay_for_attempt(self.attempts)
+    /// current number of attempts, with the policy's jitter applied.
+    pub fn next_delay(&mut self, policy: &RetryPolicy) -> Duration {
+        policy.jittered_delay(self.attempts, &mut self.rng)
     }
 
     /// Whether the channel should attempt another retry.
@@ -201,6 +237,9 @@ pub struct ChannelStartContext {
     pub outbound_tx: Option<broadcast::Sender<OutboundMessage>>,
     /// Config JSON path for reloading `pairings` without daemon restart (mtime cache).
     pub config_path: Option<std::path::PathBuf>,
+    /// Where replies go while the channel is down. Only Telegram reports outages, recovery and
+    /// failed sends to it; other channels ignore it.
+    pub dead_letters: Option<Arc<crate::bus::DeadLetterQueue>>,
 }
 
 /// Factory that builds a [Channel] from config JSON and start context.
@@ -245,6 +284,209 @@ impl Default for ChannelRegistry {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Typing indicator
+// ---------------------------------------------------------------------------
+
+/// Longest a typing indicator is kept up without a reply, so a turn that ends silently
+/// does not leave the chat "typing" forever.
+const TYPING_MAX_DURATION: Duration = Duration::from_secs(300);
+
+/// Per-chat typing indicators. Platforms show "typing" for only a few seconds per API call,
+/// so [`start`](Self::start) repeats the action until [`stop`](Self::stop) is called for the
+/// chat, which channels do when the reply goes out.
+#[derive(Clone, Default)]
+pub struct TypingIndicator {
+    active: Arc<std::sync::Mutex<HashMap<String, tokio::task::JoinHandle<()>>>>,
+}
+
+impl TypingIndicator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `action` now and every `interval` for `chat_id`, replacing any indicator already
+    /// running for that chat.
+    pub fn start<F, Fut>(&self, chat_id: &str, interval: Duration, action: F)
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let handle = tokio::spawn(async move {
+            let deadline = tokio::time::Instant::now() + TYPING_MAX_DURATION;
+            while tokio::time::Instant::now() < deadline {
+                action().await;
+                tokio::time::sleep(interval).await;
+            }
+        });
+        let previous = self.active.lock().unwrap().insert(chat_id.to_string(), handle);
+        if let Some(previous) = previous {
+            previous.abort();
+        }
+    }
+
+    /// Stop the indicator for `chat_id`, if any.
+    pub fn stop(&self, chat_id: &str) {
+        if let Some(handle) = self.active.lock().unwrap().remove(chat_id) {
+            handle.abort();
+        }
+    }
+
+    pub fn is_active(&self, chat_id: &str) -> bool {
+        self.active
+            .lock()
+            .unwrap()
+            .get(chat_id)
+            .is_some_and(|h| !h.is_finished())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Reply / quote context
+// ---------------------------------------------------------------------------
+
+/// Metadata key for the message an inbound message replies to or quotes
+/// (`{ "message_id", "sender_id", "content" }`). The agent loop adds it to the user turn.
+pub const REPLY_TO_META: &str = "reply_to";
+
+/// The message a user replied to, as extracted from a platform's quote structure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotedMessage {
+    pub message_id: String,
+    pub sender_id: String,
+    pub content: String,
+}
+
+impl QuotedMessage {
+    /// Store this quote in `metadata` under [`REPLY_TO_META`]. Quotes without text are skipped.
+    pub fn attach_to(&self, metadata: &mut serde_json::Value) {
+        if self.content.trim().is_empty() {
+            return;
+        }
+        metadata[REPLY_TO_META] = serde_json::json!({
+            "message_id": self.message_id,
+            "sender_id": self.sender_id,
+            "content": self.content,
+        });
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Shared rendering helpers
+// ---------------------------------------------------------------------------
+
+/// Unit a platform counts its message length limit in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthUnit {
+    /// Unicode scalar values (Discord, Telegram, Slack, Matrix).
+    Chars,
+    /// UTF-8 bytes (Feishu request bodies, DingTalk webhooks, IRC lines).
+    Bytes,
+}
+
+/// Byte offset of the longest prefix of `s` that fits in `max_len` units, on a grapheme
+/// boundary when at least one whole grapheme fits (else on a char boundary). Returns
+/// `s.len()` when the whole string fits. Takes at least one char so splitting always
+/// makes progress.
+fn fitting_prefix_len(s: &str, max_len: usize, unit: LengthUnit) -> usize {
+    let end = match unit {
+        LengthUnit::Chars => s.char_indices().nth(max_len).map_or(s.len(), |(i, _)| i),
+        LengthUnit::Bytes if s.len() <= max_len => s.len(),
+        LengthUnit::Bytes => (0..=max_len).rev().find(|&i| s.is_char_boundary(i)).unwrap_or(0),
+    };
+    let end = match crate::tools::truncation::grapheme_prefix(s, end).len() {
+        0 => end,
+        whole_graphemes => whole_graphemes,
+    };
+    if end == 0 {
+        s.chars().next().map_or(0, char::len_utf8)
+    } else {
+        end
+    }
+}
+
+/// Split `content` into chunks of at most `max_len` units for platforms with a message size
+/// limit, preferring to break after a newline. Chunks never split a UTF-8 character, keep
+/// emoji sequences and combining marks with their grapheme unless it alone exceeds `max_len`,
+/// and concatenate back to `content`. A `max_len` of 0 disables splitting. Empty content gives
+/// a single empty chunk; callers whose platform rejects empty messages skip it.
+pub fn split_message(content: &str, max_len: usize, unit: LengthUnit) -> Vec<String> {
+    if max_len == 0 {
+        return vec![content.to_string()];
+    }
+    let mut chunks = Vec::new();
+    let mut remaining = content;
+    loop {
+        let end = fitting_prefix_len(remaining, max_len, unit);
+        if end == remaining.len() {
+            chunks.push(remaining.to_string());
+            break;
+        }
+        let split_at = remaining[..end]
+            .rfind('\n')
+            .map(|pos| pos + 1)
+            .unwrap_or(end);
+        chunks.push(remaining[..split_at].to_string());
+        remaining = &remaining[split_at..];
+    }
+    chunks
+}
+
+/// Find the bot mention in `text` according to `mode` and return the text with it removed.
+///
+/// `mentions` lists the accepted spellings of the mention (e.g. `<@123>` and `<@!123>`);
+/// longer spellings are tried first. In `Prefix` mode the text (after leading whitespace)
+/// must start with a mention. In `Anywhere` mode every occurrence is removed, but a match
+/// directly followed by an ASCII letter, digit or `_` is ignored so `@bot` does not match
+/// `@botany`. Returns `None` when the bot is not mentioned.
+pub fn strip_mention(text: &str, mentions: &[&str], mode: MentionMode) -> Option<String> {
+    let mut mentions: Vec<&str> = mentions.iter().copied().filter(|m| !m.is_empty()).collect();
+    mentions.sort_by_key(|m| std::cmp::Reverse(m.len()));
+    let trimmed = text.trim_start();
+    if mode == MentionMode::Prefix {
+        return mentions
+            .iter()
+            .find_map(|m| trimmed.strip_prefix(m))
+            .map(|rest| rest.trim_start().to_string());
+    }
+    let is_word_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let find_mention = |s: &str| -> Option<(usize, usize)> {
+        mentions
+            .iter()
+            .flat_map(|m| s.match_indices(m).map(|(i, _)| (i, i + m.len())))
+            .filter(|&(_, end)| !s[end..].starts_with(is_word_char))
+            .min_by_key(|&(start, end)| (start, std::cmp::Reverse(end)))
+    };
+    let mut out = trimmed.to_string();
+    let mut found = false;
+    while let Some((start, end)) = find_mention(&out) {
+        found = true;
+        let before = out[..start].trim_end();
+        let after = out[end..].trim_start();
+        out = match (before.is_empty(), after.is_empty()) {
+            (true, _) => after.to_string(),
+            (_, true) => before.to_string(),
+            _ => format!("{} {}", before, after),
+        };
+    }
+    found.then_some(out)
+}
+
+/// Shorten `text` to at most `max` grapheme clusters for tool-progress previews,
+/// appending `...` when anything was cut. Never separates an emoji sequence or a
+/// base character from its combining marks.
+pub fn preview(text: &str, max: usize) -> String {
+    use unicode_segmentation::UnicodeSegmentation;
+    match text.grapheme_indices(true).nth(max) {
+        None => text.to_string(),
+        Some((end, _)) => format!(
+            "{}{}",
+            &text[..end],
+            crate::tools::truncation::TruncationMarker::DOTS.marker
+        ),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -414,6 +656,30 @@ mod tests {
         assert_eq!(state.next_delay(&policy), Duration::from_millis(200));
     }
 
+    #[test]
+    fn jitter_is_reproducible_with_the_same_seed() {
+        let policy = RetryPolicy::default();
+        let delays = |seed: u64| -> Vec<Duration> {
+            let mut rng = crate::seed::SeededRng::for_stream(Some(seed), "channel-retry");
+            (0..6).map(|attempt| policy.jittered_delay(attempt, &mut rng)).collect()
+        };
+        assert_eq!(delays(42), delays(42));
+        assert_ne!(delays(42), delays(7));
+        for (attempt, delay) in delays(42).into_iter().enumerate() {
+            let base = policy.delay_for_attempt(attempt as u32);
+            assert!(delay >= base && delay <= base.mul_f64(1.2));
+        }
+    }
+
+    #[test]
+    fn jitter_never_exceeds_max_delay() {
+        let policy = RetryPolicy::default();
+        let mut rng = crate::seed::SeededRng::for_stream(Some(42), "channel-retry");
+        for attempt in 5..12 {
+            assert!(policy.jittered_delay(attempt, &mut rng) <= policy.max_delay);
+        }
+    }
+
     #[test]
     fn record_failure_with_max_retries_zero_enters_cooldown_immediately() {
         let policy = RetryPolicy::new(
@@ -456,4 +722,183 @@ mod tests {
         assert_eq!(policy.delay_for_attempt(1), Duration::from_millis(500));
         assert_eq!(policy.delay_for_attempt(5), Duration::from_millis(500));
     }
+
+    // ---- preview ----
+
+    #[test]
+    fn preview_keeps_short_text_unchanged() {
+        assert_eq!(preview("", 10), "");
+        assert_eq!(preview("done", 4), "done");
+        assert_eq!(preview("naïve", 5), "naïve");
+    }
+
+    #[test]
+    fn preview_respects_max_graphemes() {
+        use unicode_segmentation::UnicodeSegmentation;
+        let text = "héllo wörld, this is a long tool result";
+        for max in 0..12 {
+            let out = preview(text, max);
+            let kept = out.strip_suffix("...").unwrap();
+            assert_eq!(kept.graphemes(true).count(), max);
+            assert!(text.starts_with(kept));
+        }
+    }
+
+    #[test]
+    fn preview_never_splits_a_grapheme() {
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let accented = "e\u{301}";
+        let text = format!("{}{}{}x", family, accented, family);
+        assert_eq!(preview(&text, 1), format!("{}...", family));
+        assert_eq!(preview(&text, 2), format!("{}{}...", family, accented));
+        assert_eq!(preview(&text, 4), text);
+    }
+
+    // ---- TypingIndicator unit tests ----
+
+    #[tokio::test(start_paused = true)]
+    async fn typing_repeats_until_reply_stops_it() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let typing = TypingIndicator::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        typing.start("chat-1", Duration::from_secs(4), move || {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        tokio::time::sleep(Duration::from_secs(9)).await;
+        assert!(typing.is_active("chat-1"));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        typing.stop("chat-1");
+        assert!(!typing.is_active("chat-1"));
+        tokio::time::sleep(Duration::from_secs(20)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn typing_gives_up_after_max_duration() {
+        let typing = TypingIndicator::new();
+        typing.start("chat-1", Duration::from_secs(4), || async {});
+        tokio::time::sleep(TYPING_MAX_DURATION + Duration::from_secs(5)).await;
+        assert!(!typing.is_active("chat-1"));
+    }
+
+    #[test]
+    fn reply_and_approval_end_typing_but_progress_does_not() {
+        let chat = OutboundMessage::chat("tg".into(), "1".into(), "done".into(), vec![], None);
+        let progress = OutboundMessage::tool_progress(
+            "tg".into(),
+            "1".into(),
+            "exec".into(),
+            "ok".into(),
+            String::new(),
+        );
+        assert!(chat.ends_typing());
+        assert!(!progress.ends_typing());
+        assert!(!OutboundMessage::typing("tg".into(), "1".into()).ends_typing());
+    }
+
+    // ---- strip_mention unit tests ----
+
+    #[test]
+    fn strip_mention_prefix_mode() {
+        let m = ["@bot"];
+        assert_eq!(strip_mention("  @bot  hello", &m, MentionMode::Prefix), Some("hello".into()));
+        assert_eq!(strip_mention("hey @bot hello", &m, MentionMode::Prefix), None);
+    }
+
+    #[test]
+    fn strip_mention_anywhere_mode_removes_every_occurrence() {
+        let m = ["@bot"];
+        assert_eq!(
+            strip_mention("hey @bot do X", &m, MentionMode::Anywhere),
+            Some("hey do X".into())
+        );
+        assert_eq!(
+            strip_mention("@bot ping, also @bot", &m, MentionMode::Anywhere),
+            Some("ping, also".into())
+        );
+        assert_eq!(strip_mention("no mention here", &m, MentionMode::Anywhere), None);
+    }
+
+    #[test]
+    fn strip_mention_anywhere_respects_word_boundary_and_longest_spelling() {
+        assert_eq!(strip_mention("see @botany notes", &["@bot"], MentionMode::Anywhere), None);
+        assert_eq!(
+            strip_mention("ask @_user_10 and @_user_1 now", &["@_user_1"], MentionMode::Anywhere),
+            Some("ask @_user_10 and now".into())
+        );
+        assert_eq!(
+            strip_mention("hi <@!42> there", &["<@42>", "<@!42>"], MentionMode::Anywhere),
+            Some("hi there".into())
+        );
+    }
+
+    // ---- split_message unit tests ----
+
+    #[test]
+    fn split_message_char_limit_counts_characters() {
+        let msg = "é".repeat(25);
+        let chunks = split_message(&msg, 10, LengthUnit::Chars);
+        let lens: Vec<usize> = chunks.iter().map(|c| c.chars().count()).collect();
+        assert_eq!(lens, vec![10, 10, 5]);
+        assert_eq!(chunks.concat(), msg);
+    }
+
+    #[test]
+    fn split_message_byte_limit_stays_on_char_boundaries() {
+        let msg = "日本語のテキスト".repeat(5);
+        let chunks = split_message(&msg, 10, LengthUnit::Bytes);
+        for chunk in &chunks {
+            assert!(chunk.len() <= 10);
+            assert!(!chunk.is_empty());
+        }
+        // 3-byte characters: 10 bytes fit three of them.
+        assert_eq!(chunks[0], "日本語");
+        assert_eq!(chunks.concat(), msg);
+    }
+
+    #[test]
+    fn split_message_byte_limit_prefers_newline() {
+        let msg = "ab\ncdéfgh";
+        let chunks = split_message(msg, 6, LengthUnit::Bytes);
+        assert_eq!(chunks, vec!["ab\n", "cdéfg", "h"]);
+    }
+
+    #[test]
+    fn split_message_makes_progress_when_char_exceeds_byte_limit() {
+        let chunks = split_message("😀😀", 2, LengthUnit::Bytes);
+        assert_eq!(chunks, vec!["😀", "😀"]);
+    }
+
+    #[test]
+    fn split_message_keeps_graphemes_whole() {
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let msg = format!("ab{}", family);
+        assert_eq!(split_message(&msg, 4, LengthUnit::Chars)[0], "ab");
+        assert_eq!(split_message(&msg, 10, LengthUnit::Bytes)[0], "ab");
+        assert_eq!(split_message("cafe\u{301}", 4, LengthUnit::Chars), vec!["caf", "e\u{301}"]);
+        // A grapheme longer than the limit is still split on char boundaries.
+        let chunks = split_message(family, 2, LengthUnit::Chars);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.concat(), family);
+    }
+
+    #[test]
+    fn split_message_empty_content_is_one_empty_chunk() {
+        assert_eq!(split_message("", 10, LengthUnit::Chars), vec![""]);
+        assert_eq!(split_message("", 0, LengthUnit::Bytes), vec![""]);
+    }
+
+    #[test]
+    fn split_message_same_units_for_ascii() {
+        let msg = "line one\nline two\nline three";
+        assert_eq!(
+            split_message(msg, 12, LengthUnit::Chars),
+            split_message(msg, 12, LengthUnit::Bytes)
+        );
+    }
 }
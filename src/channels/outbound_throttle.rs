@@ -0,0 +1,164 @@
+//! Per-chat spacing of outbound messages (`outboundMinIntervalMs` on any channel entry).
+//!
+//! Platforms limit how fast a bot may post in one chat. While the agent streams tool progress
+//! and long replies split into several messages, a channel can exceed that and get its sends
+//! rejected; retry/backoff only helps after the fact. [`throttle_outbound`] sits between the bus
+//! and a channel: the channel's messages are queued per chat and released no faster than one per
+//! interval, while other chats are not held up.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+use crate::bus::OutboundMessage;
+
+/// Capacity of the throttled stream handed to the channel.
+const THROTTLED_CAPACITY: usize = 256;
+
+/// A chat's queue task exits after this long without messages (plus the interval).
+const CHAT_QUEUE_IDLE: Duration = Duration::from_secs(60);
+
+/// Queue of each chat with a running queue task. The relay only sends into a queue while holding
+/// the lock, and a task removes its entry under the lock once its queue is empty, so a message
+/// can never be left in the queue of a task that has exited.
+type ChatQueues = Arc<Mutex<HashMap<String, mpsc::UnboundedSender<OutboundMessage>>>>;
+
+/// Relay `rx` into a new receiver in which messages for `channel` are at least `interval` apart
+/// within each chat. Messages for other channels pass through unchanged.
+pub fn throttle_outbound(
+    mut rx: broadcast::Receiver<OutboundMessage>,
+    channel: String,
+    interval: Duration,
+) -> broadcast::Receiver<OutboundMessage> {
+    let (tx, out) = broadcast::channel(THROTTLED_CAPACITY);
+    tokio::spawn(async move {
+        let chats: ChatQueues = Arc::default();
+        loop {
+            let msg = match rx.recv().await {
+                Ok(msg) => msg,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(channel = %channel, skipped, "Outbound throttle fell behind; skipped messages");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            if msg.channel != channel {
+                let _ = tx.send(msg);
+                continue;
+            }
+            let mut queues = chats.lock().unwrap();
+            match queues.get(&msg.chat_id) {
+                Some(queue) => {
+                    let _ = queue.send(msg);
+                }
+                None => {
+                    let chat_id = msg.chat_id.clone();
+                    let queue = spawn_chat_queue(chat_id.clone(), Arc::clone(&chats), tx.clone(), interval);
+                    let _ = queue.send(msg);
+                    queues.insert(chat_id, queue);
+                }
+            }
+        }
+    });
+    out
+}
+
+/// Task that forwards one chat's messages in order, waiting `interval` after each. After
+/// `CHAT_QUEUE_IDLE` without messages it removes the chat from `chats` and exits.
+fn spawn_chat_queue(
+    chat_id: String,
+    chats: ChatQueues,
+    tx: broadcast::Sender<OutboundMessage>,
+    interval: Duration,
+) -> mpsc::UnboundedSender<OutboundMessage> {
+    let (queue_tx, mut queue_rx) = mpsc::unbounded_channel::<OutboundMessage>();
+    tokio::spawn(async move {
+        let mut next_send = Instant::now();
+        loop {
+            let msg = match tokio::time::timeout(interval + CHAT_QUEUE_IDLE, queue_rx.recv()).await {
+                Ok(Some(msg)) => msg,
+                Ok(None) => break,
+                Err(_) => {
+                    let mut queues = chats.lock().unwrap();
+                    match queue_rx.try_recv() {
+                        // Arrived while timing out: keep going.
+                        Ok(msg) => msg,
+                        Err(_) => {
+                            queues.remove(&chat_id);
+                            break;
+                        }
+                    }
+                }
+            };
+            tokio::time::sleep_until(next_send).await;
+            let _ = tx.send(msg);
+            next_send = Instant::now() + interval;
+        }
+    });
+    queue_tx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chat(channel: &str, chat_id: &str, text: &str) -> OutboundMessage {
+        OutboundMessage::chat(channel.into(), chat_id.into(), text.into(), vec![], None)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn bursty_sends_are_spaced_per_chat() {
+        let interval = Duration::from_millis(1000);
+        let (bus_tx, bus_rx) = broadcast::channel(16);
+        let mut rx = throttle_outbound(bus_rx, "tg".into(), interval);
+        let start = Instant::now();
+        for i in 0..3 {
+            bus_tx.send(chat("tg", "a", &format!("a{}", i))).unwrap();
+        }
+        bus_tx.send(chat("tg", "b", "b0")).unwrap();
+        bus_tx.send(chat("slack", "a", "other channel")).unwrap();
+
+        let mut received = Vec::new();
+        for _ in 0..5 {
+            let msg = rx.recv().await.unwrap();
+            received.push((msg.channel.clone(), msg.chat_id.clone(), Instant::now() - start));
+        }
+        let times = |channel: &str, chat_id: &str| -> Vec<Duration> {
+            received
+                .iter()
+                .filter(|(c, id, _)| c == channel && id == chat_id)
+                .map(|(_, _, t)| *t)
+                .collect()
+        };
+
+        let a = times("tg", "a");
+        assert_eq!(a.len(), 3);
+        assert!(a[0] < interval, "first message goes out at once");
+        for pair in a.windows(2) {
+            assert!(pair[1] - pair[0] >= interval, "sends in one chat are spaced: {:?}", a);
+        }
+        assert!(times("tg", "b")[0] < interval, "other chats are not held up");
+        assert!(times("slack", "a")[0] < interval, "other channels pass through");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn chat_queue_is_restarted_after_going_idle() {
+        let interval = Duration::from_millis(1000);
+        let (bus_tx, bus_rx) = broadcast::channel(16);
+        let mut rx = throttle_outbound(bus_rx, "tg".into(), interval);
+
+        bus_tx.send(chat("tg", "a", "first")).unwrap();
+        rx.recv().await.unwrap();
+        // Around the moment the idle queue task exits, and well after it.
+        for wait in [interval + CHAT_QUEUE_IDLE, Duration::from_secs(600)] {
+            tokio::time::sleep(wait).await;
+            bus_tx.send(chat("tg", "a", "later")).unwrap();
+            let msg = tokio::time::timeout(interval * 2, rx.recv()).await;
+            assert!(matches!(msg, Ok(Ok(ref m)) if m.chat_id == "a"), "message after idle is delivered");
+        }
+    }
+}
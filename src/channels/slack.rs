@@ -20,7 +20,7 @@ use tracing::{error, info, warn};
 
 use crate::bus::{InboundMessage, OutboundMessage};
 use crate::channels::file_handler;
-use crate::channels::{Channel, approval_formatter};
+use crate::channels::{Channel, LengthUnit, approval_formatter, split_message};
 use crate::config::{
     pairing_allows, pairing_message, pairings_from_config_file_cached, AllowlistEntry, SlackConfig,
 };
@@ -181,34 +181,6 @@ async fn slack_download_attachment(
     Ok(bytes.to_vec())
 }
 
-// ---------------------------------------------------------------------------
-// Message splitting (reuse pattern from Discord)
-// ---------------------------------------------------------------------------
-
-fn split_message(content: &str, max_len: usize) -> Vec<String> {
-    if max_len == 0 {
-        return vec![content.to_string()];
-    }
-    if content.len() <= max_len {
-        return vec![content.to_string()];
-    }
-    let mut chunks = Vec::new();
-    let mut remaining = content;
-    while !remaining.is_empty() {
-        if remaining.len() <= max_len {
-            chunks.push(remaining.to_string());
-            break;
-        }
-        let split_at = remaining[..max_len]
-            .rfind('\n')
-            .map(|pos| pos + 1)
-            .unwrap_or(max_len);
-        chunks.push(remaining[..split_at].to_string());
-        remaining = &remaining[split_at..];
-    }
-    chunks
-}
-
 // ---------------------------------------------------------------------------
 // State passed into Socket Mode callback via user_state
 // ---------------------------------------------------------------------------
@@ -298,7 +270,7 @@ impl SlackChannel {
         let raw_id = slack_channel_id_raw(channel_id);
         let client = SlackClient::new(SlackClientHyperConnector::new()?);
         let session = client.open_session(&self.bot_token);
-        let chunks = split_message(content, SLACK_MAX_MESSAGE_LEN);
+        let chunks = split_message(content, SLACK_MAX_MESSAGE_LEN, LengthUnit::Chars);
         for chunk in &chunks {
             let req = SlackApiChatPostMessageRequest::new(
                 raw_id.clone().into(),
@@ -714,13 +686,7 @@ impl Channel for SlackChannel {
                         if !show_tool_calls {
                             continue;
                         }
-                        let preview = if result_preview.is_empty() {
-                            String::new()
-                        } else if result_preview.len() > tool_result_preview_chars {
-                            format!("{}...", result_preview.chars().take(tool_result_preview_chars).collect::<String>())
-                        } else {
-                            result_preview.clone()
-                        };
+                        let preview = crate::channels::preview(result_preview, tool_result_preview_chars);
                         let content = if preview.is_empty() {
                             format!("🔧 {} — {}", tool_name, status)
                         } else {
@@ -736,6 +702,7 @@ impl Channel for SlackChannel {
                             .unwrap_or_else(|| approval_formatter::format_approval_request(request));
                         (content, vec![])
                     }
+                    crate::bus::OutboundMessageType::Typing => continue,
                 };
                 let client = match SlackClientHyperConnector::new() {
                     Ok(connector) => SlackClient::new(connector),
@@ -748,7 +715,7 @@ impl Channel for SlackChannel {
                 let raw_channel_id = slack_channel_id_raw(&msg.chat_id);
 
                 if !content.is_empty() {
-                    let chunks = split_message(&content, SLACK_MAX_MESSAGE_LEN);
+                    let chunks = split_message(&content, SLACK_MAX_MESSAGE_LEN, LengthUnit::Chars);
                     for chunk in &chunks {
                         let req = SlackApiChatPostMessageRequest::new(
                             raw_channel_id.clone().into(),
@@ -822,13 +789,7 @@ impl Channel for SlackChannel {
                 if !self.show_tool_calls {
                     return Ok(());
                 }
-                let preview = if result_preview.is_empty() {
-                    String::new()
-                } else if result_preview.len() > self.tool_result_preview_chars {
-                    format!("{}...", result_preview.chars().take(self.tool_result_preview_chars).collect::<String>())
-                } else {
-                    result_preview.clone()
-                };
+                let preview = crate::channels::preview(result_preview, self.tool_result_preview_chars);
                 let content = if preview.is_empty() {
                     format!("🔧 {} — {}", tool_name, status)
                 } else {
@@ -836,6 +797,7 @@ impl Channel for SlackChannel {
                 };
                 return self.send_message(&msg.chat_id, &content).await;
             }
+            crate::bus::OutboundMessageType::Typing => return Ok(()),
         };
         if !content.is_empty() {
             self.send_message(&msg.chat_id, &content).await?;
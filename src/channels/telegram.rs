@@ -3,25 +3,33 @@
 //! Uses the Telegram Bot API directly via reqwest (no heavy SDK dependency).
 //! Integrates `RetryPolicy` / `RetryState` for resilient polling with
 //! exponential backoff on transient errors and immediate abort + system
-//! notification on unrecoverable errors (e.g. 401/403).
+//! notification on unrecoverable errors (e.g. 401/403). Replies sent during a
+//! cooldown go to the dead-letter queue and are retried once polling recovers.
 
 use anyhow::Result;
 use async_trait::async_trait;
 use serde::Deserialize;
-use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{broadcast, mpsc, RwLock};
 use tracing::{error, info, warn};
 
-use crate::bus::{InboundMessage, OutboundMessage};
-use crate::channels::{approval_formatter, Channel, RetryPolicy, RetryState};
+use crate::bus::{DeadLetterQueue, InboundMessage, OutboundMessage};
+use crate::channels::{
+    approval_formatter, split_message, Channel, LengthUnit, QuotedMessage, RetryPolicy,
+    RetryState, TypingIndicator,
+};
 use crate::config::{
     pairing_allows, pairing_message, pairings_from_config_file_cached, TelegramConfig,
 };
-use crate::tools::approval::ApprovalManager;
+use crate::tools::approval::{ApprovalManager, PendingApprovals};
 
 const API_BASE: &str = "https://api.telegram.org/bot";
+/// Telegram limits messages to 4096 characters; leave headroom for HTML entities.
+const TELEGRAM_MAX_MESSAGE_LEN: usize = 4000;
+/// A `typing` chat action shows for about 5 seconds; refresh it a little sooner.
+const TELEGRAM_TYPING_INTERVAL: Duration = Duration::from_secs(4);
 
 pub struct TelegramChannel {
     config: TelegramConfig,
@@ -33,9 +41,10 @@ pub struct TelegramChannel {
     client: reqwest::Client,
     running: bool,
     approval_manager: Option<Arc<ApprovalManager>>,
-    /// Map of user's pending approval requests: user_id -> (request_id, chat_id)
-    pending_approvals: Arc<RwLock<HashMap<String, (String, String)>>>,
+    /// Approval requests delivered by this channel and not answered yet
+    pending_approvals: Arc<RwLock<PendingApprovals>>,
     config_path: Option<PathBuf>,
+    dead_letters: Option<Arc<DeadLetterQueue>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -58,6 +67,17 @@ struct TgMessage {
     from: Option<TgUser>,
     chat: TgChat,
     text: Option<String>,
+    /// Caption of a photo/document message.
+    caption: Option<String>,
+    /// The message this one replies to.
+    reply_to_message: Option<Box<TgMessage>>,
+    /// The part of the replied-to message the user selected when quoting.
+    quote: Option<TgTextQuote>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TgTextQuote {
+    text: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -73,12 +93,41 @@ struct TgChat {
     type_: Option<String>,
 }
 
+/// The replied-to message, preferring the user's selected quote over the full text or caption.
+fn telegram_quoted_message(m: &TgMessage) -> Option<QuotedMessage> {
+    let replied = m.reply_to_message.as_deref()?;
+    let content = m
+        .quote
+        .as_ref()
+        .map(|q| q.text.clone())
+        .or_else(|| replied.text.clone())
+        .or_else(|| replied.caption.clone())?;
+    Some(QuotedMessage {
+        message_id: replied.message_id.to_string(),
+        sender_id: replied.from.as_ref().map(|u| u.id.to_string()).unwrap_or_default(),
+        content,
+    })
+}
+
 /// Returns `true` if the HTTP status code indicates an unrecoverable error
 /// that should not be retried (e.g. invalid credentials).
 fn is_unrecoverable_status(status: reqwest::StatusCode) -> bool {
     matches!(status.as_u16(), 401 | 403)
 }
 
+/// Why a `sendMessage` call should be retried later: the request did not reach Telegram, or
+/// Telegram was rate limiting or failing (429, 5xx). `None` when it was sent, or rejected in a
+/// way a retry would not fix.
+fn send_failure_to_retry(sent: reqwest::Result<reqwest::Response>) -> Option<String> {
+    match sent {
+        Err(e) => Some(e.to_string()),
+        Ok(resp) if resp.status().as_u16() == 429 || resp.status().is_server_error() => {
+            Some(format!("HTTP {}", resp.status()))
+        }
+        Ok(_) => None,
+    }
+}
+
 impl TelegramChannel {
     pub fn new(
         config: TelegramConfig,
@@ -101,8 +150,9 @@ impl TelegramChannel {
             client,
             running: false,
             approval_manager: None,
-            pending_approvals: Arc::new(RwLock::new(HashMap::new())),
+            pending_approvals: Arc::new(RwLock::new(PendingApprovals::new())),
             config_path,
+            dead_letters: None,
         }
     }
 
@@ -112,22 +162,16 @@ impl TelegramChannel {
         self
     }
 
-    /// Register a user's pending approval request.
-    async fn register_pending_approval(&self, user_id: String, request_id: String, chat_id: String) {
-        let mut pending = self.pending_approvals.write().await;
-        pending.insert(user_id, (request_id, chat_id));
+    /// Hold replies in `queue` while polling is in cooldown.
+    pub fn with_dead_letters(mut self, queue: Arc<DeadLetterQueue>) -> Self {
+        self.dead_letters = Some(queue);
+        self
     }
 
-    /// Get and remove the user's pending approval request.
-    async fn take_pending_approval(&self, user_id: &str) -> Option<(String, String)> {
+    /// Take the most recent pending approval request that a message from `user_id` in `chat_id` answers.
+    async fn take_pending_approval(&self, user_id: &str, chat_id: &str) -> Option<String> {
         let mut pending = self.pending_approvals.write().await;
-        pending.remove(user_id)
-    }
-
-    /// Check whether the user has a pending approval request.
-    async fn has_pending_approval(&self, user_id: &str) -> bool {
-        let pending = self.pending_approvals.read().await;
-        pending.contains_key(user_id)
+        pending.take_reply(user_id, chat_id)
     }
 
     fn format_approval_request(request: &crate::tools::approval::ApprovalRequest) -> String {
@@ -187,15 +231,33 @@ impl TelegramChannel {
         Ok(resp.result.unwrap_or_default())
     }
 
+    /// Keep the "typing…" status up in `chat_id` until `typing.stop` is called for it.
+    fn start_typing(typing: &TypingIndicator, client: &reqwest::Client, token: &str, chat_id: i64) {
+        let client = client.clone();
+        let url = format!("{}{}/sendChatAction", API_BASE, token);
+        typing.start(&chat_id.to_string(), TELEGRAM_TYPING_INTERVAL, move || {
+            let request = client
+                .post(&url)
+                .json(&serde_json::json!({ "chat_id": chat_id, "action": "typing" }));
+            async move {
+                if let Err(e) = request.send().await {
+                    warn!("Telegram sendChatAction failed: {e}");
+                }
+            }
+        });
+    }
+
     async fn send_text(&self, chat_id: i64, text: &str) -> Result<()> {
-        // Telegram limits messages to 4096 chars; split if needed.
-        for chunk in text.as_bytes().chunks(4000) {
-            let chunk_str = String::from_utf8_lossy(chunk);
+        // Telegram rejects empty messages, so empty text sends nothing.
+        for chunk in split_message(text, TELEGRAM_MAX_MESSAGE_LEN, LengthUnit::Chars)
+            .into_iter()
+            .filter(|c| !c.is_empty())
+        {
             self.client
                 .post(self.api_url("sendMessage"))
                 .json(&serde_json::json!({
                     "chat_id": chat_id,
-                    "text": chunk_str,
+                    "text": chunk,
                     "parse_mode": "HTML"
                 }))
                 .send()
@@ -264,11 +326,21 @@ impl Channel for TelegramChannel {
         let pending_approvals = self.pending_approvals.clone();
         let show_tool_calls = self.show_tool_calls;
         let tool_result_preview_chars = self.tool_result_preview_chars;
+        let typing_enabled = self.config.typing_indicator;
+        let typing = TypingIndicator::new();
+        let dead_letters = self.dead_letters.clone();
         tokio::spawn(async move {
             while let Ok(msg) = outbound_rx.recv().await {
                 if msg.channel != channel_name {
                     continue;
                 }
+                // A diverted reply still ends the turn, so stop typing first.
+                if msg.ends_typing() {
+                    typing.stop(&msg.chat_id);
+                }
+                if dead_letters.as_ref().is_some_and(|d| d.divert(&msg)) {
+                    continue;
+                }
                 if let Ok(chat_id) = msg.chat_id.parse::<i64>() {
                     let url = format!("{}{}/sendMessage", API_BASE, token);
                     let content = match &msg.message_type {
@@ -281,13 +353,7 @@ impl Channel for TelegramChannel {
                             if !show_tool_calls {
                                 continue;
                             }
-                            let preview = if result_preview.is_empty() {
-                                String::new()
-                            } else if result_preview.len() > tool_result_preview_chars {
-                                format!("{}...", result_preview.chars().take(tool_result_preview_chars).collect::<String>())
-                            } else {
-                                result_preview.clone()
-                            };
+                            let preview = crate::channels::preview(result_preview, tool_result_preview_chars);
                             if preview.is_empty() {
                                 format!("🔧 {} — {}", tool_name, status)
                             } else {
@@ -296,12 +362,7 @@ impl Channel for TelegramChannel {
                         }
                         crate::bus::OutboundMessageType::ApprovalRequest { request } => {
                             // Register the pending approval request
-                            // Extract user_id from session_id (format: agent:role:channel:type:user_id)
-                            let user_id = request.session_id.split(':').last().unwrap_or("").to_string();
-                            if !user_id.is_empty() {
-                                let mut pending = pending_approvals.write().await;
-                                pending.insert(user_id, (request.id.clone(), msg.chat_id.clone()));
-                            }
+                            pending_approvals.write().await.insert(request, &msg.chat_id);
                             // Prefer Agent-generated display message for the user's language
                             request
                                 .display_message
@@ -310,18 +371,47 @@ impl Channel for TelegramChannel {
                                 .map(String::from)
                                 .unwrap_or_else(|| Self::format_approval_request(&request))
                         }
+                        crate::bus::OutboundMessageType::Typing => {
+                            if typing_enabled {
+                                Self::start_typing(&typing, &client, &token, chat_id);
+                            }
+                            continue;
+                        }
                     };
-                    for chunk in content.as_bytes().chunks(4000) {
-                        let chunk_str = String::from_utf8_lossy(chunk);
-                        let _ = client
+                    // Telegram rejects empty messages.
+                    if content.is_empty() {
+                        continue;
+                    }
+                    let chunks = split_message(&content, TELEGRAM_MAX_MESSAGE_LEN, LengthUnit::Chars);
+                    for (i, chunk) in chunks.iter().enumerate() {
+                        let sent = client
                             .post(&url)
                             .json(&serde_json::json!({
                                 "chat_id": chat_id,
-                                "text": chunk_str,
+                                "text": chunk,
                                 "parse_mode": "HTML"
                             }))
                             .send()
                             .await;
+                        let Some(reason) = send_failure_to_retry(sent) else {
+                            continue;
+                        };
+                        warn!(chat_id = %msg.chat_id, error = %reason, "Telegram send failed");
+                        if let Some(ref dead_letters) = dead_letters {
+                            // Keep what was not delivered for when the channel is back.
+                            let rest = match &msg.message_type {
+                                crate::bus::OutboundMessageType::Chat { media, .. } => OutboundMessage {
+                                    message_type: crate::bus::OutboundMessageType::Chat {
+                                        content: chunks[i..].concat(),
+                                        media: media.clone(),
+                                    },
+                                    ..msg.clone()
+                                },
+                                _ => msg.clone(),
+                            };
+                            dead_letters.capture(&rest, &reason);
+                            break;
+                        }
                     }
                 }
             }
@@ -339,10 +429,14 @@ impl Channel for TelegramChannel {
                         );
                         retry_state.reset();
                     }
+                    if let Some(ref dead_letters) = self.dead_letters {
+                        dead_letters.mark_up(&self.config.name);
+                    }
 
                     for u in updates {
                         offset = u.update_id + 1;
                         if let Some(m) = u.message {
+                            let quoted = telegram_quoted_message(&m);
                             let sender =
                                 m.from.map(|u| u.id.to_string()).unwrap_or_default();
                             if let Some(text) = m.text {
@@ -533,7 +627,7 @@ impl Channel for TelegramChannel {
                                     }
                                 };
                                 // If user has pending approval, forward message to agent with metadata for LLM to interpret
-                                if let Some((request_id, _chat_id_str)) = self.take_pending_approval(&sender).await {
+                                if let Some(request_id) = self.take_pending_approval(&sender, &m.chat.id.to_string()).await {
                                     let mut meta = serde_json::json!({
                                         "trigger_agent": true,
                                         "pending_approval_request_id": request_id,
@@ -542,6 +636,9 @@ impl Channel for TelegramChannel {
                                     if is_group_meta {
                                         meta["group"] = serde_json::json!(true);
                                     }
+                                    if let Some(ref q) = quoted {
+                                        q.attach_to(&mut meta);
+                                    }
                                     let _ = self.inbound_tx.send(InboundMessage {
                                         channel: self.config.name.clone(),
                                         sender_id: sender,
@@ -561,6 +658,9 @@ impl Channel for TelegramChannel {
                                 if is_group_meta {
                                     meta["group"] = serde_json::json!(true);
                                 }
+                                if let Some(ref q) = quoted {
+                                    q.attach_to(&mut meta);
+                                }
                                 let _ = self.inbound_tx.send(InboundMessage {
                                     channel: self.config.name.clone(),
                                     sender_id: sender,
@@ -607,6 +707,10 @@ impl Channel for TelegramChannel {
                             "Telegram retries exhausted, entering cooldown"
                         );
 
+                        if let Some(ref dead_letters) = self.dead_letters {
+                            dead_letters.mark_down(&self.config.name, &msg);
+                        }
+
                         // Cooldown period: wait max_delay then reset and try again
                         let cooldown = retry_policy.max_delay;
                         warn!(
@@ -648,20 +752,71 @@ impl Channel for TelegramChannel {
                 if !self.show_tool_calls {
                     return Ok(());
                 }
-                let preview = if result_preview.is_empty() {
-                    String::new()
-                } else if result_preview.len() > self.tool_result_preview_chars {
-                    format!("{}...", result_preview.chars().take(self.tool_result_preview_chars).collect::<String>())
-                } else {
-                    result_preview.clone()
-                };
+                let preview = crate::channels::preview(result_preview, self.tool_result_preview_chars);
                 if preview.is_empty() {
                     format!("🔧 {} — {}", tool_name, status)
                 } else {
                     format!("🔧 {} — {}\n{}", tool_name, status, preview)
                 }
             }
+            crate::bus::OutboundMessageType::Typing => {
+                if self.config.typing_indicator {
+                    self.client
+                        .post(self.api_url("sendChatAction"))
+                        .json(&serde_json::json!({ "chat_id": chat_id, "action": "typing" }))
+                        .send()
+                        .await?;
+                }
+                return Ok(());
+            }
         };
         self.send_text(chat_id, &content).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> TgMessage {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn reply_to_message_text_is_quoted() {
+        let m = parse(
+            r#"{"message_id": 11, "from": {"id": 1}, "chat": {"id": -5, "type": "group"},
+                "text": "summarize this",
+                "reply_to_message": {"message_id": 10, "from": {"id": 2},
+                    "chat": {"id": -5, "type": "group"}, "text": "Long report text"}}"#,
+        );
+        let q = telegram_quoted_message(&m).unwrap();
+        assert_eq!(q.message_id, "10");
+        assert_eq!(q.sender_id, "2");
+        assert_eq!(q.content, "Long report text");
+    }
+
+    #[test]
+    fn selected_quote_and_caption_take_effect() {
+        let m = parse(
+            r#"{"message_id": 11, "chat": {"id": 7}, "text": "what is this?",
+                "quote": {"text": "only this part"},
+                "reply_to_message": {"message_id": 10, "chat": {"id": 7}, "text": "all of it"}}"#,
+        );
+        assert_eq!(telegram_quoted_message(&m).unwrap().content, "only this part");
+
+        let m = parse(
+            r#"{"message_id": 11, "chat": {"id": 7}, "text": "describe",
+                "reply_to_message": {"message_id": 10, "chat": {"id": 7}, "caption": "photo caption"}}"#,
+        );
+        let q = telegram_quoted_message(&m).unwrap();
+        assert_eq!(q.content, "photo caption");
+        assert_eq!(q.sender_id, "");
+    }
+
+    #[test]
+    fn plain_message_has_no_quote() {
+        let m = parse(r#"{"message_id": 1, "chat": {"id": 7}, "text": "hi"}"#);
+        assert!(telegram_quoted_message(&m).is_none());
+    }
+}
@@ -0,0 +1,255 @@
+//! Generic webhook channel — connects the bot to any system that can send and receive HTTP.
+//!
+//! Inbound: the web server accepts `POST /webhook/<name>` (see `web::handlers::webhook`). The
+//! body must carry a valid [`SIGNATURE_HEADER`] for the channel's `secret`; [`map_inbound`]
+//! picks the message fields out of the JSON with the pointers in `mapping`.
+//!
+//! Outbound: chat replies, approval requests and (with `showToolCalls`) tool progress are POSTed
+//! to `outboundUrl` as the bus message JSON (`channel`, `chat_id`, `type`, ...), signed the same
+//! way so the receiver can check they came from this bot.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
+use tokio::sync::broadcast;
+use tracing::{error, info, warn};
+
+use crate::bus::{DeadLetterQueue, InboundMessage, OutboundMessage, OutboundMessageType};
+use crate::channels::Channel;
+use crate::config::WebhookConfig;
+
+/// Header with the hex HMAC-SHA256 of the raw body, as `sha256=<hex>`.
+pub const SIGNATURE_HEADER: &str = "X-Synbot-Signature";
+
+const SIGNATURE_PREFIX: &str = "sha256=";
+
+/// Timeout of one outbound POST.
+const OUTBOUND_TIMEOUT: Duration = Duration::from_secs(30);
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn mac_for(secret: &str, body: &[u8]) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    mac
+}
+
+/// Signature header value for `body`: `sha256=` and the hex HMAC-SHA256 keyed with `secret`.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    format!(
+        "{}{}",
+        SIGNATURE_PREFIX,
+        hex::encode(mac_for(secret, body).finalize().into_bytes())
+    )
+}
+
+/// Whether `signature` (a header value as produced by [`sign`]) matches `body`. Compared in
+/// constant time.
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(expected) = signature
+        .trim()
+        .strip_prefix(SIGNATURE_PREFIX)
+        .and_then(|h| hex::decode(h).ok())
+    else {
+        return false;
+    };
+    mac_for(secret, body).verify_slice(&expected).is_ok()
+}
+
+/// String (or number) at `pointer` in `body`; empty strings count as missing.
+fn string_at(body: &Value, pointer: &str) -> Option<String> {
+    let value = match body.pointer(pointer)? {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    (!value.is_empty()).then_some(value)
+}
+
+/// Build the inbound message for a webhook body using the channel's `mapping`.
+pub fn map_inbound(config: &WebhookConfig, body: &Value) -> Result<InboundMessage, String> {
+    let mapping = &config.mapping;
+    let content = string_at(body, &mapping.content)
+        .ok_or_else(|| format!("no message content at {}", mapping.content))?;
+    let chat_id = string_at(body, &mapping.chat_id)
+        .ok_or_else(|| format!("no chat id at {}", mapping.chat_id))?;
+    let sender_id = string_at(body, &mapping.sender_id).unwrap_or_else(|| chat_id.clone());
+    Ok(InboundMessage {
+        channel: config.name.clone(),
+        sender_id,
+        chat_id,
+        content,
+        timestamp: chrono::Utc::now(),
+        media: vec![],
+        metadata: serde_json::json!({
+            "trigger_agent": true,
+            "default_agent": config.default_agent,
+        }),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// WebhookChannel
+// ---------------------------------------------------------------------------
+
+pub struct WebhookChannel {
+    config: WebhookConfig,
+    outbound_rx: Option<broadcast::Receiver<OutboundMessage>>,
+    client: reqwest::Client,
+    dead_letters: Option<Arc<DeadLetterQueue>>,
+}
+
+impl WebhookChannel {
+    pub fn new(config: WebhookConfig, outbound_rx: broadcast::Receiver<OutboundMessage>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(OUTBOUND_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            config,
+            outbound_rx: Some(outbound_rx),
+            client,
+            dead_letters: None,
+        }
+    }
+
+    /// Report the outcome of each outbound POST to `queue` as this channel's connection state.
+    pub fn with_dead_letters(mut self, queue: Arc<DeadLetterQueue>) -> Self {
+        self.dead_letters = Some(queue);
+        self
+    }
+
+    /// Whether `msg` is POSTed at all: typing indicators never are, tool progress only with
+    /// `showToolCalls`.
+    fn forwards(&self, msg: &OutboundMessage) -> bool {
+        match msg.message_type {
+            OutboundMessageType::Chat { .. } | OutboundMessageType::ApprovalRequest { .. } => true,
+            OutboundMessageType::ToolProgress { .. } => self.config.show_tool_calls,
+            OutboundMessageType::Typing => false,
+        }
+    }
+}
+
+#[async_trait]
+impl Channel for WebhookChannel {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    async fn start(&mut self) -> Result<()> {
+        info!(
+            channel = %self.config.name,
+            inbound = %format!("/webhook/{}", self.config.name),
+            "Webhook channel starting"
+        );
+        let mut outbound_rx = self
+            .outbound_rx
+            .take()
+            .ok_or_else(|| anyhow!("webhook channel already started"))?;
+        loop {
+            let msg = match outbound_rx.recv().await {
+                Ok(msg) => msg,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(channel = %self.config.name, skipped, "Webhook channel fell behind; skipped outbound messages");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            if msg.channel != self.config.name || !self.forwards(&msg) {
+                continue;
+            }
+            match self.send(&msg).await {
+                Ok(()) => {
+                    if let Some(ref dead_letters) = self.dead_letters {
+                        dead_letters.mark_up(&self.config.name);
+                    }
+                }
+                Err(e) => {
+                    error!(channel = %self.config.name, chat_id = %msg.chat_id, error = %e, "Webhook delivery failed");
+                    if let Some(ref dead_letters) = self.dead_letters {
+                        dead_letters.mark_down(&self.config.name, &e.to_string());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        info!(channel = %self.config.name, "Webhook channel stopping");
+        Ok(())
+    }
+
+    async fn send(&self, msg: &OutboundMessage) -> Result<()> {
+        let body = serde_json::to_vec(msg)?;
+        let resp = self
+            .client
+            .post(&self.config.outbound_url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, sign(&self.config.secret, &body))
+            .body(body)
+            .send()
+            .await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("outbound webhook returned {}: {}", status, text));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn inbound_body_is_mapped_with_pointers() {
+        let mut config = WebhookConfig {
+            name: "ci".into(),
+            default_agent: "ops".into(),
+            ..Default::default()
+        };
+        let default_body = json!({ "content": "hello", "chatId": "room-1" });
+        let msg = map_inbound(&config, &default_body).unwrap();
+        assert_eq!((msg.channel.as_str(), msg.chat_id.as_str()), ("ci", "room-1"));
+        assert_eq!(msg.content, "hello");
+        assert_eq!(msg.sender_id, "room-1", "sender falls back to the chat id");
+        assert_eq!(msg.metadata["default_agent"], "ops");
+
+        config.mapping.content = "/event/text".into();
+        config.mapping.chat_id = "/event/thread".into();
+        config.mapping.sender_id = "/event/user/id".into();
+        let nested = json!({ "event": { "text": "build failed", "thread": 42, "user": { "id": "u7" } } });
+        let msg = map_inbound(&config, &nested).unwrap();
+        assert_eq!(msg.content, "build failed");
+        assert_eq!(msg.chat_id, "42");
+        assert_eq!(msg.sender_id, "u7");
+
+        let err = map_inbound(&config, &json!({ "event": { "thread": 42 } })).unwrap_err();
+        assert!(err.contains("/event/text"), "{}", err);
+    }
+
+    #[test]
+    fn outbound_signature_is_hmac_sha256_hex() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+
+        let body = br#"{"channel":"ci","chat_id":"room-1","type":"chat","content":"ok"}"#;
+        let signature = sign("s3cret", body);
+        assert!(verify_signature("s3cret", body, &signature));
+        assert!(!verify_signature("other", body, &signature));
+        assert!(!verify_signature("s3cret", b"tampered", &signature));
+        assert!(!verify_signature("s3cret", body, signature.trim_start_matches("sha256=")));
+    }
+}
@@ -11,6 +11,7 @@ pub async fn cmd_agent(message: Option<String>, provider: Option<String>, model:
     
     // Initialize logging with config
     logging::init_logging(&cfg, None)?;
+    crate::seed::init(cfg.seed);
     
     let ws = config::effective_workspace_path(&cfg);
 
@@ -21,15 +22,17 @@ pub async fn cmd_agent(message: Option<String>, provider: Option<String>, model:
     // Resolve API key for this provider (so model and key stay consistent when multiple providers are configured)
     let (api_key, api_base) = resolve_provider(&cfg, &provider_name);
     if api_key.is_empty() {
-        anyhow::bail!(
+        return Err(crate::rig_provider::ProviderError(format!(
             "No API key configured for provider '{}'. Run `synbot onboard` and set [providers.*] in {}",
             provider_name,
             config::config_path().display()
-        );
+        ))
+        .into());
     }
 
     // Build rig completion model via rig-core (no rig-dyn)
     let completion_model = build_rig_completion_model(
+        &cfg,
         &provider_name,
         &model_name,
         &api_key,
@@ -67,7 +70,7 @@ pub async fn cmd_agent(message: Option<String>, provider: Option<String>, model:
     }
 
     // Bus is needed for MessageTool's outbound_tx; create before building tools
-    let mut bus = crate::bus::MessageBus::new();
+    let mut bus = crate::bus::MessageBus::new().with_response_transforms(&cfg.response_transforms);
 
     // Same source of truth as AgentLoop (embeddingDimensions, etc.); tools must not use load_config(None) alone.
     let shared_config = std::sync::Arc::new(tokio::sync::RwLock::new(cfg.clone()));
@@ -103,10 +106,30 @@ pub async fn cmd_agent(message: Option<String>, provider: Option<String>, model:
     )
     .await;
 
+    if let Err(problems) = tool_reg.validate() {
+        for problem in &problems {
+            tracing::warn!(problem = %problem, "Tool registry check failed");
+        }
+    }
     if let Err(e) = tool_reg.register_list_tools_tool() {
         tracing::warn!(error = %e, "Failed to register list_tools tool");
     }
 
+    // Needs the completion model, so it is registered once the model exists. It shares the
+    // channel model overrides with the agent loop, so a summary uses the conversation's model.
+    let channel_models = std::sync::Arc::new(crate::agent::channel_models::ChannelModels::new(
+        std::sync::Arc::clone(&completion_model),
+    ));
+    if let Err(e) = tool_reg.register(std::sync::Arc::new(
+        crate::tools::session_tools::SummarizeConversationTool::new(
+            shared_session_state.clone(),
+            std::sync::Arc::clone(&channel_models),
+            std::sync::Arc::clone(&shared_config),
+        ),
+    )) {
+        tracing::warn!(error = %e, "Failed to register summarize_conversation tool");
+    }
+
     let tools = std::sync::Arc::new(tool_reg);
 
     // Wire spawn tool to run real subagents (model + tools) and to send completion to user
@@ -159,7 +182,8 @@ pub async fn cmd_agent(message: Option<String>, provider: Option<String>, model:
         Some(std::sync::Arc::new(hook_registry)),
         std::sync::Arc::clone(&shared_config),
     )
-    .await;
+    .await
+    .with_channel_models(channel_models);
     let loop_ref = std::sync::Arc::new(tokio::sync::Mutex::new(agent_loop));
 
     // If one-shot message, inject it and collect response
@@ -199,6 +223,7 @@ pub async fn cmd_agent(message: Option<String>, provider: Option<String>, model:
                     } => {
                         println!("[Tool: {}] {} — {}", tool_name, status, result_preview);
                     }
+                    crate::bus::OutboundMessageType::Typing => {}
                 }
             }
         });
@@ -230,6 +255,7 @@ pub async fn cmd_agent(message: Option<String>, provider: Option<String>, model:
                     } => {
                         println!("[Tool: {}] {} — {}", tool_name, status, result_preview);
                     }
+                    crate::bus::OutboundMessageType::Typing => {}
                 }
             }
         });
@@ -0,0 +1,39 @@
+//! `synbot completions <shell>`: print a shell completion script for the whole command tree.
+
+use std::io::Write;
+
+use anyhow::Result;
+use clap::CommandFactory;
+use clap_complete::Shell;
+
+use super::Cli;
+
+fn write_completions(shell: Shell, out: &mut dyn Write) {
+    let mut cmd = Cli::command();
+    let bin_name = cmd.get_name().to_string();
+    clap_complete::generate(shell, &mut cmd, bin_name, out);
+}
+
+/// Print the completion script for `shell` to stdout.
+pub fn cmd_completions(shell: Shell) -> Result<()> {
+    write_completions(shell, &mut std::io::stdout());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ValueEnum;
+
+    #[test]
+    fn generates_a_script_for_every_shell() {
+        for shell in Shell::value_variants() {
+            let mut out = Vec::new();
+            write_completions(*shell, &mut out);
+            let script = String::from_utf8(out).expect("utf-8 script");
+            for name in ["synbot", "onboard", "sandbox", "cron", "completions"] {
+                assert!(script.contains(name), "{} script lacks {}", shell, name);
+            }
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! `synbot diagnostics` — write a redacted bug-report bundle.
+//!
+//! Collects version, platform, redacted config, channel and sandbox health, and recent
+//! log entries into a single JSON file. Live data (channel status, log buffer) comes from
+//! the running daemon's web API when it is reachable. Every string in the bundle is passed
+//! through [`SecretMaskerLayer`] with the config's secrets loaded before it is written.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+
+use super::doctor::{ChannelCredentialCheck, CheckResult, CheckStatus, DoctorCheck, SandboxCheck};
+use super::helpers::daemon_api;
+use crate::config::{self, Config};
+use crate::sandbox::{PlatformDetector, PlatformInfo};
+use crate::security::SecretMaskerLayer;
+use crate::web::handlers::config_redact::config_to_redacted_value;
+
+/// Number of recent log entries requested from the daemon.
+const LOG_ENTRIES: usize = 100;
+
+/// Data fetched from the running daemon. Each field is `Err(reason)` when unavailable.
+pub struct DaemonSnapshot {
+    pub channels: std::result::Result<Value, String>,
+    pub logs: std::result::Result<Value, String>,
+}
+
+impl DaemonSnapshot {
+    async fn fetch(cfg: &Config) -> Self {
+        let channels = daemon_api(cfg, reqwest::Method::GET, "/api/channels")
+            .await
+            .map_err(|e| e.to_string());
+        let logs_path = format!("/api/logs?page=1&page_size={}", LOG_ENTRIES);
+        let logs = daemon_api(cfg, reqwest::Method::GET, &logs_path)
+            .await
+            .map(|page| page["items"].clone())
+            .map_err(|e| e.to_string());
+        Self { channels, logs }
+    }
+}
+
+fn check_to_json(result: &CheckResult) -> Value {
+    let (status, message) = match &result.status {
+        CheckStatus::Pass(m) => ("pass", m),
+        CheckStatus::Fail(m) => ("fail", m),
+        CheckStatus::Warn(m) => ("warn", m),
+        CheckStatus::Skip(m) => ("skip", m),
+    };
+    json!({ "name": result.name, "status": status, "message": message })
+}
+
+fn live_or_error(value: &std::result::Result<Value, String>) -> Value {
+    match value {
+        Ok(v) => v.clone(),
+        Err(e) => json!({ "unavailable": e }),
+    }
+}
+
+/// Assemble the bundle and redact it. The checks are the doctor results for channels and sandbox.
+pub fn build_bundle(
+    cfg: &Config,
+    platform: &PlatformInfo,
+    channel_check: &CheckResult,
+    sandbox_check: &CheckResult,
+    daemon: &DaemonSnapshot,
+) -> Result<Value> {
+    let config = config_to_redacted_value(cfg).context("serialize config")?;
+    let mut bundle = json!({
+        "generatedAt": chrono::Utc::now().to_rfc3339(),
+        "version": crate::build_info::build_info(),
+        "platform": {
+            "os": platform.os,
+            "version": platform.version,
+            "arch": platform.arch,
+            "supported": platform.supported,
+            "recommendedAppSandbox": platform.recommended_app_sandbox,
+            "recommendedToolSandbox": platform.recommended_tool_sandbox,
+        },
+        "config": config,
+        "channels": {
+            "check": check_to_json(channel_check),
+            "live": live_or_error(&daemon.channels),
+        },
+        "sandbox": {
+            "appSandboxConfigured": cfg.app_sandbox.is_some(),
+            "toolSandboxType": cfg.tool_sandbox.as_ref().and_then(|t| t.sandbox_type.clone()),
+            "check": check_to_json(sandbox_check),
+        },
+        "logs": live_or_error(&daemon.logs),
+    });
+    redact_strings(&mut bundle, cfg);
+    Ok(bundle)
+}
+
+/// Mask config secrets and well-known token patterns in every string of `value`.
+fn redact_strings(value: &mut Value, cfg: &Config) {
+    let masker = SecretMaskerLayer::new();
+    masker.load_config_secrets(cfg);
+    mask_value(value, &masker);
+}
+
+fn mask_value(value: &mut Value, masker: &SecretMaskerLayer) {
+    match value {
+        Value::String(s) => *s = masker.mask(s),
+        Value::Array(items) => items.iter_mut().for_each(|v| mask_value(v, masker)),
+        Value::Object(map) => map.values_mut().for_each(|v| mask_value(v, masker)),
+        _ => {}
+    }
+}
+
+/// Collect diagnostics and write them to `output` (default `synbot-diagnostics-<timestamp>.json`).
+pub async fn cmd_diagnostics(output: Option<PathBuf>) -> Result<()> {
+    let cfg = config::load_config(None)?;
+    let platform = PlatformDetector::detect();
+    let channel_check = ChannelCredentialCheck.run(&cfg).await;
+    let sandbox_check = SandboxCheck.run(&cfg).await;
+    let daemon = DaemonSnapshot::fetch(&cfg).await;
+    if let Err(e) = &daemon.channels {
+        eprintln!("⚠ Daemon not reachable, live channel status and logs omitted: {}", e);
+    }
+
+    let bundle = build_bundle(&cfg, &platform, &channel_check, &sandbox_check, &daemon)?;
+    let path = output.unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "synbot-diagnostics-{}.json",
+            chrono::Utc::now().format("%Y%m%d-%H%M%S")
+        ))
+    });
+    std::fs::write(&path, serde_json::to_string_pretty(&bundle)?)
+        .with_context(|| format!("write {}", path.display()))?;
+    println!("Diagnostics written to {}", path.display());
+    println!("Secrets from your config were redacted; review the file before attaching it.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ProviderEntry, TelegramConfig};
+
+    const TOKEN: &str = "123456:telegram-secret-token";
+    const API_KEY: &str = "sk-ant-diagnostics-secret";
+
+    fn config_with_secrets() -> Config {
+        let mut cfg = Config::default();
+        cfg.channels.telegram = vec![TelegramConfig {
+            enabled: true,
+            token: TOKEN.into(),
+            ..Default::default()
+        }];
+        cfg.providers.anthropic = ProviderEntry {
+            api_key: API_KEY.into(),
+            ..Default::default()
+        };
+        cfg
+    }
+
+    #[tokio::test]
+    async fn bundle_has_sections_and_no_secrets() {
+        let cfg = config_with_secrets();
+        let daemon = DaemonSnapshot {
+            channels: Ok(json!([{ "name": "telegram", "enabled": true, "status": "connected" }])),
+            logs: Ok(json!([
+                { "level": "ERROR", "message": format!("auth failed for key {}", API_KEY) },
+                { "level": "INFO", "message": format!("bot{} getUpdates", TOKEN) },
+            ])),
+        };
+        let channel_check = ChannelCredentialCheck.run(&cfg).await;
+        let sandbox_check = SandboxCheck.run(&cfg).await;
+        let bundle =
+            build_bundle(&cfg, &PlatformDetector::detect(), &channel_check, &sandbox_check, &daemon)
+                .unwrap();
+
+        for section in ["version", "platform", "config", "channels", "sandbox", "logs"] {
+            assert!(bundle.get(section).is_some(), "missing section {}", section);
+        }
+        assert_eq!(bundle["logs"].as_array().unwrap().len(), 2);
+
+        let text = serde_json::to_string(&bundle).unwrap();
+        assert!(!text.contains(TOKEN));
+        assert!(!text.contains(API_KEY));
+    }
+
+    #[tokio::test]
+    async fn unreachable_daemon_is_recorded() {
+        let cfg = Config::default();
+        let daemon = DaemonSnapshot {
+            channels: Err("connection refused".into()),
+            logs: Err("connection refused".into()),
+        };
+        let check = CheckResult {
+            name: "x".into(),
+            status: CheckStatus::Skip("n/a".into()),
+        };
+        let bundle =
+            build_bundle(&cfg, &PlatformDetector::detect(), &check, &check, &daemon).unwrap();
+        assert_eq!(bundle["logs"]["unavailable"], "connection refused");
+        assert_eq!(bundle["channels"]["check"]["status"], "skip");
+    }
+}
@@ -233,6 +233,14 @@ impl DoctorCheck for ChannelCredentialCheck {
                 }
             }
         }
+        for c in &config.channels.webhook {
+            if c.enabled {
+                checked += 1;
+                if c.outbound_url.is_empty() || c.secret.is_empty() {
+                    issues.push(format!("webhook/{}: outbound_url or secret is empty", c.name));
+                }
+            }
+        }
 
         if checked == 0 {
             return CheckResult::skip(self.name(), "no enabled channels configured");
@@ -427,6 +435,55 @@ impl DoctorCheck for McpServerCheck {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Check: Built-in tool metadata
+// ---------------------------------------------------------------------------
+
+pub struct ToolRegistryCheck;
+
+#[async_trait]
+impl DoctorCheck for ToolRegistryCheck {
+    fn name(&self) -> &str {
+        "Tool descriptions"
+    }
+
+    async fn run(&self, config: &Config) -> CheckResult {
+        let registry = built_in_tool_registry(config);
+        let count = registry.names().len();
+        match registry.validate() {
+            Ok(()) => CheckResult::pass(self.name(), format!("{} built-in tool(s) checked", count)),
+            Err(problems) => CheckResult::warn(self.name(), problems.join("; ")),
+        }
+    }
+}
+
+/// The default tool set as `synbot agent` builds it (no sandbox, heartbeat/cron, plugins or MCP).
+fn built_in_tool_registry(config: &Config) -> crate::tools::ToolRegistry {
+    let ws = config::effective_workspace_path(config);
+    let subagent_mgr = std::sync::Arc::new(tokio::sync::Mutex::new(
+        crate::agent::subagent::SubagentManager::new(
+            config.main_agent.max_concurrent_subagents,
+            Some(config.main_agent.subagent_task_timeout_secs),
+        ),
+    ));
+    let session_store =
+        crate::agent::session::SessionStore::new(crate::config::sessions_root().as_path());
+    let bus = crate::bus::MessageBus::new();
+    let (registry, _spawn_context) = super::helpers::build_default_tools(
+        config,
+        std::sync::Arc::new(tokio::sync::RwLock::new(config.clone())),
+        &ws,
+        subagent_mgr,
+        std::sync::Arc::new(crate::tools::approval::ApprovalManager::new()),
+        None,
+        None,
+        &None,
+        crate::agent::session_state::SharedSessionState::new(session_store),
+        bus.outbound_tx_clone(),
+    );
+    registry
+}
+
 /// Attempt a lightweight connection to an MCP server to verify it is reachable.
 async fn try_connect_mcp_server(server: &crate::config::McpServerConfig) -> Result<()> {
     use std::collections::HashMap;
@@ -519,6 +576,7 @@ pub async fn cmd_doctor() -> Result<()> {
         Box::new(SandboxCheck),
         Box::new(MemoryCheck),
         Box::new(McpServerCheck),
+        Box::new(ToolRegistryCheck),
     ];
 
     let mut report = DoctorReport::new();
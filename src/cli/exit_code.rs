@@ -0,0 +1,100 @@
+//! Process exit codes, so scripts wrapping synbot can tell failure types apart.
+//!
+//! | Code | Meaning |
+//! |------|---------|
+//! | 0 | Success |
+//! | 1 | Any other error |
+//! | 2 | Invalid command line (reported by clap) |
+//! | 3 | Config file unreadable, malformed, failing validation, or unusable for `synbot start` |
+//! | 4 | LLM provider could not be built or called |
+//! | 5 | App or tool sandbox failure |
+
+use std::process::ExitCode;
+
+use super::startup_check::StartupCheckFailed;
+use crate::config::{ConfigFileError, ConfigValidationError};
+use crate::rig_provider::ProviderError;
+
+/// Category of a failed command, as reported by the exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Failure {
+    Generic = 1,
+    Config = 3,
+    Provider = 4,
+    Sandbox = 5,
+}
+
+/// Context added to every error of `synbot sandbox`, whose failures are mostly plain messages.
+#[derive(Debug)]
+pub(crate) struct SandboxCommandFailed;
+
+impl std::fmt::Display for SandboxCommandFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("sandbox command failed")
+    }
+}
+
+impl std::error::Error for SandboxCommandFailed {}
+
+/// Whether `err` is, wraps, or carries as context an error of type `T`.
+fn caused_by<T: std::error::Error + Send + Sync + 'static>(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<T>().is_some() || err.chain().any(|cause| cause.is::<T>())
+}
+
+impl Failure {
+    /// Categorize a top-level error. Config problems win, since they also break provider and
+    /// sandbox setup.
+    pub fn of(err: &anyhow::Error) -> Self {
+        if caused_by::<ConfigValidationError>(err)
+            || caused_by::<ConfigFileError>(err)
+            || caused_by::<StartupCheckFailed>(err)
+        {
+            Failure::Config
+        } else if caused_by::<ProviderError>(err)
+            || caused_by::<rig::completion::CompletionError>(err)
+        {
+            Failure::Provider
+        } else if caused_by::<crate::sandbox::SandboxError>(err)
+            || caused_by::<crate::sandbox::ConfigError>(err)
+            || caused_by::<SandboxCommandFailed>(err)
+        {
+            Failure::Sandbox
+        } else {
+            Failure::Generic
+        }
+    }
+
+    pub fn exit_code(self) -> ExitCode {
+        ExitCode::from(self as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_validation_failure_maps_to_config_code() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, r#"{"mainAgent":{"maxTokens":0}}"#).unwrap();
+        let err = crate::config::load_config(Some(&path)).unwrap_err();
+        assert_eq!(Failure::of(&err), Failure::Config);
+        // Still recognized under the context callers add, e.g. `synbot sandbox`.
+        let wrapped = err.context(SandboxCommandFailed);
+        assert_eq!(Failure::of(&wrapped), Failure::Config);
+
+        std::fs::write(&path, "{ not json").unwrap();
+        let err = crate::config::load_config(Some(&path)).unwrap_err();
+        assert_eq!(Failure::of(&err), Failure::Config);
+    }
+
+    #[test]
+    fn other_categories_map_to_their_codes() {
+        let provider = anyhow::Error::new(ProviderError("Unknown provider: nope".into()));
+        assert_eq!(Failure::of(&provider), Failure::Provider);
+        let sandbox = anyhow::anyhow!("spawn failed").context(SandboxCommandFailed);
+        assert_eq!(Failure::of(&sandbox), Failure::Sandbox);
+        assert_eq!(Failure::of(&anyhow::anyhow!("disk full")), Failure::Generic);
+    }
+}
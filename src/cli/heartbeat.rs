@@ -0,0 +1,41 @@
+//! `synbot heartbeat` — pause, resume, or trigger the running daemon's heartbeat.
+
+use anyhow::Result;
+use clap::Subcommand;
+
+use super::helpers::daemon_api;
+use crate::config;
+
+#[derive(Subcommand)]
+pub enum HeartbeatAction {
+    /// Show whether heartbeat is enabled and paused.
+    Status,
+    /// Skip scheduled heartbeat runs until resumed (e.g. during maintenance).
+    Pause,
+    /// Resume scheduled heartbeat runs.
+    Resume,
+    /// Run all heartbeat tasks immediately.
+    RunNow,
+}
+
+pub async fn cmd_heartbeat(action: HeartbeatAction) -> Result<()> {
+    let cfg = config::load_config(None)?;
+    let (method, path) = match action {
+        HeartbeatAction::Status => (reqwest::Method::GET, "/api/heartbeat"),
+        HeartbeatAction::Pause => (reqwest::Method::POST, "/api/heartbeat/pause"),
+        HeartbeatAction::Resume => (reqwest::Method::POST, "/api/heartbeat/resume"),
+        HeartbeatAction::RunNow => (reqwest::Method::POST, "/api/heartbeat/run-now"),
+    };
+    let status = daemon_api(&cfg, method, path).await?;
+    if matches!(action, HeartbeatAction::RunNow) {
+        println!("Heartbeat run triggered.");
+    }
+    println!(
+        "Heartbeat: {} ({}), interval {}s, {} task(s)",
+        if status["enabled"].as_bool().unwrap_or(false) { "enabled" } else { "disabled" },
+        if status["paused"].as_bool().unwrap_or(false) { "paused" } else { "running" },
+        status["interval"].as_u64().unwrap_or(0),
+        status["task_count"].as_u64().unwrap_or(0),
+    );
+    Ok(())
+}
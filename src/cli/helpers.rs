@@ -10,17 +10,28 @@ pub fn resolve_provider(cfg: &config::Config, provider_name: &str) -> (String, O
 
 /// Build a rig completion model using rig-core (no rig-dyn). Returns Arc<dyn SynbotCompletionModel>.
 pub fn build_rig_completion_model(
+    cfg: &config::Config,
     provider_name: &str,
     model_name: &str,
     api_key: &str,
     api_base: Option<&str>,
 ) -> anyhow::Result<std::sync::Arc<dyn crate::rig_provider::SynbotCompletionModel>> {
-    crate::rig_provider::build_completion_model(
+    // Custom providers must be registered before the first model is built; agent mode builds
+    // its model before plugins load.
+    crate::rig_provider::register_extra_providers_from_config(cfg);
+    let model = crate::rig_provider::build_completion_model(
         provider_name,
         model_name,
         api_key,
         api_base,
-    )
+    )?;
+    let model = crate::rig_provider::with_streaming(model, provider_name, cfg);
+    let model = crate::rig_provider::with_debug_log(model, provider_name, cfg);
+    Ok(crate::rig_provider::with_seed(
+        model,
+        provider_name,
+        crate::seed::configured_seed(),
+    ))
 }
 
 /// Optional context for heartbeat/cron tools (shared config + path). When provided, list/add/delete heartbeat and cron tools are registered.
@@ -49,22 +60,39 @@ pub fn build_default_tools(
 ) -> (crate::tools::ToolRegistry, std::sync::Arc<tokio::sync::RwLock<Option<crate::tools::spawn::SpawnContext>>>) {
     use crate::tools::*;
     // Same flag as ExecTool: paths must stay under workspace when true (see filesystem::resolve_path).
-    // File tools run in the main process; tool sandbox only wraps exec — workspace scope still applies here.
+    // File tools run in the main process, except read_file/write_file/list_dir, which go through a
+    // Docker tool sandbox so they see what exec created there; workspace scope applies either way.
     let restrict = cfg.tools.exec.restrict_to_workspace;
     let ws = ws.to_path_buf();
 
     let spawn_context = std::sync::Arc::new(tokio::sync::RwLock::new(None));
     let mut reg = ToolRegistry::new();
-    reg.register(std::sync::Arc::new(filesystem::ReadFileTool { workspace: ws.clone(), restrict })).expect("register ReadFileTool");
-    reg.register(std::sync::Arc::new(filesystem::WriteFileTool { workspace: ws.clone(), restrict })).expect("register WriteFileTool");
+    reg.set_validate_args(cfg.tools.validate_args);
+    reg.set_max_description_chars(cfg.tools.max_description_chars);
+    reg.register(std::sync::Arc::new(filesystem::ReadFileTool {
+        workspace: ws.clone(),
+        restrict,
+        sandbox_context: sandbox_context.clone(),
+    })).expect("register ReadFileTool");
+    reg.register(std::sync::Arc::new(filesystem::WriteFileTool {
+        workspace: ws.clone(),
+        restrict,
+        sandbox_context: sandbox_context.clone(),
+    })).expect("register WriteFileTool");
     reg.register(std::sync::Arc::new(filesystem::EditFileTool { workspace: ws.clone(), restrict })).expect("register EditFileTool");
-    reg.register(std::sync::Arc::new(filesystem::ListDirTool { workspace: ws.clone(), restrict })).expect("register ListDirTool");
+    reg.register(std::sync::Arc::new(filesystem::ListDirTool {
+        workspace: ws.clone(),
+        restrict,
+        sandbox_context: sandbox_context.clone(),
+    })).expect("register ListDirTool");
     reg.register(std::sync::Arc::new(filesystem::ReadMultipleFilesTool { workspace: ws.clone(), restrict })).expect("register ReadMultipleFilesTool");
     reg.register(std::sync::Arc::new(filesystem::CreateDirTool { workspace: ws.clone(), restrict })).expect("register CreateDirTool");
     reg.register(std::sync::Arc::new(filesystem::MoveFileTool { workspace: ws.clone(), restrict })).expect("register MoveFileTool");
     reg.register(std::sync::Arc::new(filesystem::SearchFilesTool { workspace: ws.clone(), restrict })).expect("register SearchFilesTool");
     reg.register(std::sync::Arc::new(filesystem::SearchTextTool { workspace: ws.clone(), restrict })).expect("register SearchTextTool");
     reg.register(std::sync::Arc::new(filesystem::GetFileInfoTool { workspace: ws.clone(), restrict })).expect("register GetFileInfoTool");
+    reg.register(std::sync::Arc::new(filesystem::WatchFileTool { workspace: ws.clone(), restrict })).expect("register WatchFileTool");
+    reg.register(std::sync::Arc::new(filesystem::EditJsonTool { workspace: ws.clone(), restrict })).expect("register EditJsonTool");
     reg.register(std::sync::Arc::new(code_analyzer::CodeAnalyzerTool {
         workspace: ws.clone(),
         restrict,
@@ -85,9 +113,10 @@ pub fn build_default_tools(
         approval_timeout_secs: cfg.tools.exec.permissions.approval_timeout_secs,
         restrict_to_workspace: restrict,
         policy: shell::CommandPolicy::new(
-            cfg.tools.exec.deny_patterns.clone(),
-            cfg.tools.exec.allow_patterns.clone(),
-        ),
+            cfg.tools.exec.expanded_deny_patterns(),
+            cfg.tools.exec.expanded_allow_patterns(),
+        )
+        .with_case_sensitive(cfg.tools.exec.pattern_case_sensitive),
         permission_policy,
         approval_manager: Some(approval_manager),
         session_id: None,
@@ -97,7 +126,8 @@ pub fn build_default_tools(
     })).expect("register ExecTool");
     reg.register(std::sync::Arc::new(web::WebSearchTool::from_config(&cfg.tools.web)))
         .expect("register WebSearchTool");
-    reg.register(std::sync::Arc::new(web::WebFetchTool)).expect("register WebFetchTool");
+    reg.register(std::sync::Arc::new(web::WebFetchTool::from_config(&cfg.tools.web)))
+        .expect("register WebFetchTool");
     if cfg.tools.browser.enabled {
         reg.register(std::sync::Arc::new(browser::BrowserTool::from_config(&cfg.tools.browser)))
             .expect("register BrowserTool");
@@ -224,3 +254,45 @@ pub fn build_default_tools(
 
     (reg, spawn_context)
 }
+
+/// Call the running daemon's web API (e.g. `/api/heartbeat/pause`) using `web.host`/`web.port`
+/// and Basic auth from config. Returns the `data` field of the JSON response.
+pub async fn daemon_api(
+    cfg: &config::Config,
+    method: reqwest::Method,
+    path: &str,
+) -> anyhow::Result<serde_json::Value> {
+    use anyhow::Context;
+
+    if !cfg.web.enabled {
+        anyhow::bail!(
+            "web.enabled is false; this command talks to the running daemon through its web API"
+        );
+    }
+    let host = match cfg.web.host.as_str() {
+        "0.0.0.0" | "" => "127.0.0.1",
+        h => h,
+    };
+    let url = format!("http://{}:{}{}", host, cfg.web.port, path);
+    let mut req = reqwest::Client::new()
+        .request(method, &url)
+        .timeout(std::time::Duration::from_secs(10));
+    if let Some(auth) = &cfg.web.auth {
+        req = req.basic_auth(&auth.username, Some(&auth.password));
+    }
+    let resp = req
+        .send()
+        .await
+        .with_context(|| format!("Could not reach synbot daemon at {} (is `synbot start` running?)", url))?;
+    let status = resp.status();
+    let body: serde_json::Value = resp.json().await.unwrap_or(serde_json::Value::Null);
+    if !status.is_success() {
+        anyhow::bail!(
+            "{} {}: {}",
+            status,
+            path,
+            body["error"].as_str().unwrap_or("request failed")
+        );
+    }
+    Ok(body["data"].clone())
+}
@@ -4,13 +4,22 @@ mod onboard;
 mod agent;
 mod start;
 mod cron;
+mod heartbeat;
 mod sandbox_cmd;
+mod sandbox_logs;
+mod startup_check;
 #[cfg(target_os = "windows")]
 mod tool_sandbox_cmd;
 mod service;
 mod pairing;
-mod helpers;
+mod permission;
+mod turns;
+mod quarantine;
+pub(crate) mod helpers;
 pub mod doctor;
+mod diagnostics;
+mod completions;
+mod exit_code;
 
 use std::path::PathBuf;
 use anyhow::Result;
@@ -20,13 +29,19 @@ pub use onboard::cmd_onboard;
 pub use agent::cmd_agent;
 pub use start::cmd_start;
 pub use cron::{cmd_cron, CronAction};
+pub use heartbeat::{cmd_heartbeat, HeartbeatAction};
 pub use sandbox_cmd::cmd_sandbox;
 
 #[cfg(target_os = "windows")]
 use tool_sandbox_cmd::cmd_tool_sandbox_serve;
 pub use service::{cmd_service, ServiceAction};
 pub use doctor::cmd_doctor;
+pub use diagnostics::cmd_diagnostics;
+pub use completions::cmd_completions;
 pub use pairing::{cmd_pairing, PairingAction};
+pub use permission::{cmd_permission, PermissionAction};
+pub use turns::{cmd_turns, TurnsAction};
+pub use quarantine::{cmd_quarantine, QuarantineAction};
 
 #[derive(Parser)]
 #[command(name = "synbot", about = "synbot — Personal AI Assistant")]
@@ -36,10 +51,15 @@ struct Cli {
     #[arg(long, value_name = "DIR", global = true)]
     root_dir: Option<PathBuf>,
 
-    /// Print version, OS, architecture, and git tag.
-    #[arg(short = 'v', long = "version", global = true)]
+    /// Print version, git commit, build time, enabled features, OS and architecture.
+    #[arg(short = 'V', long = "version", global = true)]
     version: bool,
 
+    /// Raise the log level for this run above `log.level` (never lowers it): `-v` info, `-vv`
+    /// debug, `-vvv` trace.
+    #[arg(short = 'v', long = "verbose", action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -49,6 +69,9 @@ enum Commands {
     /// Initialize configuration and workspace.
     Onboard,
 
+    /// Print version, git commit, build time, enabled features, OS and architecture.
+    Version,
+
     /// Run the agent (one-shot or interactive).
     Agent {
         /// Single message to process (non-interactive).
@@ -65,11 +88,19 @@ enum Commands {
     },
 
     /// Start the full daemon (channels + heartbeat + cron).
-    Start,
+    Start {
+        /// Start even when the startup self-check finds no enabled channel or no provider key.
+        #[arg(long)]
+        force: bool,
+    },
 
     /// Run a subcommand inside the app sandbox. Starts the sandbox, then launches `synbot <args..>` as a child process in the container.
     /// Example: `synbot sandbox start` runs `synbot start` inside the sandbox.
+    /// `synbot sandbox logs [--follow]` prints the child's captured output instead.
     Sandbox {
+        /// Remove what a previous run left behind and create the sandbox fresh (sets `appSandbox.deleteOnStart` for this launch).
+        #[arg(long)]
+        recreate: bool,
         /// Subcommand and arguments passed to the child process (e.g. `start`, or `agent --message hello`).
         #[arg(trailing_var_arg = true)]
         child_args: Vec<String>,
@@ -81,6 +112,12 @@ enum Commands {
         action: CronAction,
     },
 
+    /// Pause, resume, or immediately run the daemon's heartbeat (via the web API).
+    Heartbeat {
+        #[command(subcommand)]
+        action: HeartbeatAction,
+    },
+
     /// Install, uninstall, start, stop, restart, or show status of the Synbot daemon as a system service (Linux: systemd user, macOS: launchd, Windows: scheduled task).
     Service {
         #[command(subcommand)]
@@ -90,12 +127,43 @@ enum Commands {
     /// Run diagnostics: check config, channel credentials, provider API keys, sandbox, memory, and MCP servers.
     Doctor,
 
+    /// Write a redacted diagnostics bundle (version, platform, config, channel/sandbox health, recent logs) for bug reports.
+    Diagnostics {
+        /// Output file. Default: synbot-diagnostics-<timestamp>.json in the current directory.
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
     /// List, approve, or remove channel pairings (supplement to allowlist).
     Pairing {
         #[command(subcommand)]
         action: PairingAction,
     },
 
+    /// Check exec permission rules against a command without running it.
+    Permission {
+        #[command(subcommand)]
+        action: PermissionAction,
+    },
+
+    /// List the daemon's running agent turns or cancel one (via the web API).
+    Turns {
+        #[command(subcommand)]
+        action: TurnsAction,
+    },
+
+    /// List sandboxes stopped for security violations or release one (via the web API).
+    Quarantine {
+        #[command(subcommand)]
+        action: QuarantineAction,
+    },
+
+    /// Print a shell completion script (bash, zsh, fish, powershell, elvish) to stdout.
+    Completions {
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+
     /// Internal: host-side tool sandbox IPC (used by `synbot sandbox` on Windows).
     #[cfg(target_os = "windows")]
     #[command(hide = true)]
@@ -118,9 +186,19 @@ enum ToolSandboxAction {
     },
 }
 
-pub async fn run() -> Result<()> {
-    let cli = Cli::parse();
+/// Parse the command line, run the command, and turn a failure into its exit code
+/// (see [`exit_code`]).
+pub async fn run() -> std::process::ExitCode {
+    match run_command(Cli::parse()).await {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {:?}", err);
+            exit_code::Failure::of(&err).exit_code()
+        }
+    }
+}
 
+async fn run_command(cli: Cli) -> Result<()> {
     if cli.version {
         return cmd_version();
     }
@@ -131,16 +209,28 @@ pub async fn run() -> Result<()> {
     };
 
     crate::config::set_root_dir(cli.root_dir.clone());
+    if let Some(level) = verbosity_level(cli.verbose) {
+        crate::logging::set_level_override(level);
+    }
 
     match command {
         Commands::Onboard => cmd_onboard().await,
+        Commands::Version => cmd_version(),
         Commands::Agent { message, provider, model } => cmd_agent(message, provider, model).await,
-        Commands::Start => cmd_start().await,
-        Commands::Sandbox { child_args } => cmd_sandbox(child_args).await,
+        Commands::Start { force } => cmd_start(force).await,
+        Commands::Sandbox { recreate, child_args } => cmd_sandbox(child_args, recreate)
+            .await
+            .map_err(|e| e.context(exit_code::SandboxCommandFailed)),
         Commands::Cron { action } => cmd_cron(action).await,
+        Commands::Heartbeat { action } => cmd_heartbeat(action).await,
         Commands::Service { action } => cmd_service(action).await,
         Commands::Doctor => cmd_doctor().await,
+        Commands::Diagnostics { output } => cmd_diagnostics(output).await,
         Commands::Pairing { action } => cmd_pairing(action).await,
+        Commands::Permission { action } => cmd_permission(action).await,
+        Commands::Turns { action } => cmd_turns(action).await,
+        Commands::Quarantine { action } => cmd_quarantine(action).await,
+        Commands::Completions { shell } => cmd_completions(shell),
         #[cfg(target_os = "windows")]
         Commands::ToolSandbox { action } => match action {
             ToolSandboxAction::Serve { pipe, auth } => cmd_tool_sandbox_serve(pipe, auth).await,
@@ -149,24 +239,43 @@ pub async fn run() -> Result<()> {
 }
 
 fn cmd_version() -> Result<()> {
-    let version = env!("CARGO_PKG_VERSION");
-    let os = std::env::consts::OS;
-    let arch = std::env::consts::ARCH;
-    let git_commit = std::process::Command::new("git")
-        .args(["rev-parse", "--short", "HEAD"])
-        .output()
-        .ok()
-        .and_then(|o| {
-            if o.status.success() {
-                String::from_utf8(o.stdout).ok().map(|s| s.trim().to_string())
-            } else {
-                None
-            }
-        })
-        .unwrap_or_else(|| "unknown".to_string());
-    println!("synbot {}", version);
-    println!("  os:    {}", os);
-    println!("  arch:  {}", arch);
-    println!("  git:   {}", git_commit);
+    let info = crate::build_info::build_info();
+    println!("synbot {}", info.version);
+    println!("  git:      {}", info.git_sha);
+    println!("  built:    {}", info.build_timestamp);
+    println!("  features: {}", if info.features.is_empty() { "(none)".to_string() } else { info.features.join(", ") });
+    println!("  os:       {}", info.os);
+    println!("  arch:     {}", info.arch);
     Ok(())
 }
+
+/// Log level for `count` repetitions of `-v`/`--verbose`; `None` keeps the configured level.
+fn verbosity_level(count: u8) -> Option<&'static str> {
+    match count {
+        0 => None,
+        1 => Some("info"),
+        2 => Some("debug"),
+        _ => Some("trace"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verbose_flag_count_maps_to_log_level() {
+        let count = |args: &[&str]| Cli::try_parse_from(args).unwrap().verbose;
+        assert_eq!(verbosity_level(count(&["synbot", "start"])), None);
+        assert_eq!(verbosity_level(count(&["synbot", "--verbose", "start"])), Some("info"));
+        assert_eq!(verbosity_level(count(&["synbot", "-vv", "start"])), Some("debug"));
+        assert_eq!(
+            verbosity_level(count(&["synbot", "--verbose", "--verbose", "start"])),
+            Some("debug")
+        );
+        assert_eq!(verbosity_level(count(&["synbot", "start", "-vvvv"])), Some("trace"));
+        // Version moved to -V, following clap's convention
+        assert!(Cli::try_parse_from(["synbot", "-V"]).unwrap().version);
+        assert!(!Cli::try_parse_from(["synbot", "-v"]).unwrap().version);
+    }
+}
@@ -0,0 +1,115 @@
+//! `synbot permission` — inspect exec permission rules without running anything.
+
+use anyhow::Result;
+use clap::Subcommand;
+
+use crate::config::{load_config, PermissionConfig};
+use crate::tools::permission::{CommandPermissionPolicy, PermissionLevel};
+
+#[derive(Subcommand)]
+pub enum PermissionAction {
+    /// Show the permission level the configured rules give a command and which rule matched.
+    /// The command is not executed.
+    Test {
+        /// Command line to evaluate (quote it, e.g. "git push origin main").
+        command: String,
+    },
+}
+
+fn level_name(level: PermissionLevel) -> &'static str {
+    match level {
+        PermissionLevel::Allow => "allow",
+        PermissionLevel::RequireApproval => "require_approval",
+        PermissionLevel::Deny => "deny",
+    }
+}
+
+/// Evaluate `command` against `permissions` and describe the result, one fact per line.
+pub fn describe_permission(permissions: &PermissionConfig, command: &str) -> String {
+    let policy = CommandPermissionPolicy::new(permissions.rules.clone(), permissions.default_level);
+    let decision = policy.evaluate(command);
+    let mut out = format!("Command: {}\nLevel:   {}\n", command, level_name(decision.level));
+    match decision.rule_index {
+        Some(i) => {
+            let rule = &policy.rules[i];
+            out.push_str(&format!("Rule:    rules[{}] pattern {:?}", i, rule.pattern));
+            if let Some(desc) = rule.description.as_deref().filter(|d| !d.is_empty()) {
+                out.push_str(&format!(" ({})", desc));
+            }
+            out.push('\n');
+        }
+        None => out.push_str("Rule:    none matched (defaultLevel)\n"),
+    }
+    if !permissions.enabled {
+        out.push_str("Note:    permissions are disabled (tools.exec.permissions.enabled is false), so exec does not apply this result.\n");
+    }
+    out
+}
+
+pub async fn cmd_permission(action: PermissionAction) -> Result<()> {
+    let cfg = load_config(None)?;
+    match action {
+        PermissionAction::Test { command } => {
+            print!("{}", describe_permission(&cfg.tools.exec.permissions, &command));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::permission::PermissionRule;
+
+    fn rule(pattern: &str, level: PermissionLevel) -> PermissionRule {
+        PermissionRule {
+            pattern: pattern.to_string(),
+            level,
+            description: None,
+        }
+    }
+
+    fn permissions() -> PermissionConfig {
+        PermissionConfig {
+            enabled: true,
+            default_level: PermissionLevel::RequireApproval,
+            rules: vec![
+                rule("git status*", PermissionLevel::Allow),
+                rule("*rm -rf*", PermissionLevel::Deny),
+                rule("git push*", PermissionLevel::RequireApproval),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn resolves_each_level_with_rule_index() {
+        let policy = CommandPermissionPolicy::new(permissions().rules, PermissionLevel::RequireApproval);
+        let allow = policy.evaluate("git status --short");
+        assert_eq!((allow.level, allow.rule_index), (PermissionLevel::Allow, Some(0)));
+        let deny = policy.evaluate("sudo RM -RF /tmp/x");
+        assert_eq!((deny.level, deny.rule_index), (PermissionLevel::Deny, Some(1)));
+        let prompt = policy.evaluate("git push origin main");
+        assert_eq!((prompt.level, prompt.rule_index), (PermissionLevel::RequireApproval, Some(2)));
+    }
+
+    #[test]
+    fn falls_through_to_default_level() {
+        let mut perms = permissions();
+        perms.default_level = PermissionLevel::Deny;
+        let out = describe_permission(&perms, "curl example.com");
+        assert!(out.contains("Level:   deny"));
+        assert!(out.contains("none matched (defaultLevel)"));
+        assert!(!out.contains("Note:"));
+    }
+
+    #[test]
+    fn describes_matching_rule_and_disabled_note() {
+        let mut perms = permissions();
+        perms.enabled = false;
+        let out = describe_permission(&perms, "git push origin main");
+        assert!(out.contains("Level:   require_approval"));
+        assert!(out.contains("rules[2] pattern \"git push*\""));
+        assert!(out.contains("permissions are disabled"));
+    }
+}
@@ -0,0 +1,39 @@
+//! `synbot quarantine` — list sandboxes stopped for security violations and release one.
+
+use anyhow::Result;
+use clap::Subcommand;
+
+use super::helpers::daemon_api;
+use crate::config;
+
+#[derive(Subcommand)]
+pub enum QuarantineAction {
+    /// List sandboxes whose executions are refused after repeated security violations.
+    List,
+    /// Allow executions in a quarantined sandbox again (it restarts on the next exec).
+    Release {
+        /// Sandbox id as shown by `synbot quarantine list`.
+        id: String,
+    },
+}
+
+pub async fn cmd_quarantine(action: QuarantineAction) -> Result<()> {
+    let cfg = config::load_config(None)?;
+    match action {
+        QuarantineAction::List => {
+            let ids = daemon_api(&cfg, reqwest::Method::GET, "/api/sandbox/quarantine").await?;
+            let ids = ids.as_array().map(Vec::as_slice).unwrap_or_default();
+            if ids.is_empty() {
+                println!("No sandboxes quarantined.");
+            }
+            for id in ids {
+                println!("{}", id.as_str().unwrap_or("?"));
+            }
+        }
+        QuarantineAction::Release { id } => {
+            daemon_api(&cfg, reqwest::Method::DELETE, &format!("/api/sandbox/quarantine/{}", id)).await?;
+            println!("Released {}.", id);
+        }
+    }
+    Ok(())
+}
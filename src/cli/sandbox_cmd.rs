@@ -5,6 +5,8 @@ use std::io::Write;
 use std::path::PathBuf;
 use tracing::info;
 
+use crate::config::SandboxRestartPolicy;
+
 /// `synbot sandbox …` uses a trailing var-arg for everything after `sandbox`, so `--root-dir`
 /// placed after `sandbox` is **not** parsed as the global `Cli.root_dir`. Extract it here and
 /// apply [`crate::config::set_root_dir`] before loading config, and strip it from argv forwarded
@@ -43,6 +45,47 @@ fn apply_root_dir_from_sandbox_child_args(child_args: Vec<String>) -> Vec<String
     filtered
 }
 
+/// App sandbox config for this launch; `recreate` forces `delete_on_start` on top of `appSandbox.deleteOnStart`.
+fn launch_sandbox_config(
+    cfg: &crate::config::Config,
+    app_cfg: &crate::config::AppSandboxConfig,
+    recreate: bool,
+) -> Result<crate::sandbox::SandboxConfig> {
+    let mut sandbox_config = crate::config::build_app_sandbox_config(app_cfg, cfg, &cfg.sandbox_monitoring)
+        .context("Build app sandbox config")?;
+    sandbox_config.delete_on_start |= recreate;
+    Ok(sandbox_config)
+}
+
+/// Restarts allowed by `appSandbox.restartPolicy` when `maxRestarts` is not set.
+const DEFAULT_MAX_RESTARTS: u32 = 5;
+
+/// Pause before starting the child again.
+const RESTART_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// How the sandbox child ended.
+#[cfg_attr(not(any(target_os = "windows", target_os = "linux", target_os = "macos")), allow(dead_code))]
+struct ChildExit {
+    /// Exit code, propagated as the exit code of `synbot sandbox` (128 + signal when killed).
+    code: i32,
+    /// The child was stopped on our request (Ctrl+C / SIGTERM): never restarted.
+    stopped: bool,
+}
+
+/// Whether `restartPolicy` starts the child again after it exited with `code`, having been
+/// restarted `restarts` times already.
+#[cfg_attr(not(any(target_os = "windows", target_os = "linux", target_os = "macos")), allow(dead_code))]
+fn should_restart(policy: SandboxRestartPolicy, code: i32, restarts: u32, max_restarts: u32) -> bool {
+    if restarts >= max_restarts {
+        return false;
+    }
+    match policy {
+        SandboxRestartPolicy::Never => false,
+        SandboxRestartPolicy::OnFailure => code != 0,
+        SandboxRestartPolicy::Always => true,
+    }
+}
+
 fn progress(msg: &str) {
     let _ = eprintln!("[synbot sandbox] {}", msg);
     let _ = std::io::stderr().flush();
@@ -71,34 +114,71 @@ fn child_argv(child_args: &[String]) -> Vec<String> {
 /// Run the given subcommand and args inside the app sandbox.
 /// Example: cmd_sandbox(vec!["start".into()]) → starts sandbox, then runs `synbot start` in it.
 /// If child_args is ["setup"], on Windows only: install firewall/WFP rules then exit (no daemon).
-pub async fn cmd_sandbox(child_args: Vec<String>) -> Result<()> {
+/// If child_args is ["logs", ..], print the captured output of the last sandbox child (see [`super::sandbox_logs`]).
+/// `recreate` (`--recreate`) removes what a previous run left behind before starting.
+pub async fn cmd_sandbox(child_args: Vec<String>, recreate: bool) -> Result<()> {
     let child_args = apply_root_dir_from_sandbox_child_args(child_args);
     progress("Loading config...");
     let cfg = crate::config::load_config(None).context("Load config for sandbox")?;
 
+    if child_args.first().map(|s| s.as_str()) == Some("logs") {
+        return super::sandbox_logs::cmd_sandbox_logs(&cfg, &child_args[1..]).await;
+    }
+
     let app_cfg = cfg
         .app_sandbox
         .as_ref()
         .ok_or_else(|| anyhow::anyhow!("app_sandbox is not configured; add it to config to use `synbot sandbox`"))?;
 
-    let monitoring = &cfg.sandbox_monitoring;
     progress("Building sandbox config...");
-    let sandbox_config =
-        crate::config::build_app_sandbox_config(app_cfg, &cfg, monitoring).context("Build app sandbox config")?;
+    let sandbox_config = launch_sandbox_config(&cfg, app_cfg, recreate)?;
 
     // Windows-only: setup adds firewall/WFP rules once (run as Administrator). After that, normal users can start the sandbox.
     if child_args.get(0).map(|s| s.as_str()) == Some("setup") {
         return run_sandbox_setup(&cfg, &sandbox_config).await;
     }
 
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
     {
-        run_sandbox_windows(&cfg, &sandbox_config, &child_args).await
-    }
-
-    #[cfg(any(target_os = "linux", target_os = "macos"))]
-    {
-        run_sandbox_nono(&sandbox_config, &child_args).await
+        let log_path = super::sandbox_logs::child_log_path(&cfg);
+        let capture = match super::sandbox_logs::OutputCapture::start(&log_path) {
+            Ok(capture) => Some(capture),
+            Err(e) => {
+                progress(&format!("Not capturing child output to {}: {}", log_path.display(), e));
+                None
+            }
+        };
+        let policy = app_cfg.restart_policy;
+        let max_restarts = app_cfg.max_restarts.unwrap_or(DEFAULT_MAX_RESTARTS);
+        let mut restarts = 0;
+        let code = loop {
+            #[cfg(target_os = "windows")]
+            let exit = run_sandbox_windows(&cfg, &sandbox_config, &child_args).await;
+            #[cfg(not(target_os = "windows"))]
+            let exit = run_sandbox_nono(&sandbox_config, &child_args).await;
+            let exit = match exit {
+                Ok(exit) => exit,
+                Err(e) => break Err(e),
+            };
+            progress(&format!("Child exited with code {}", exit.code));
+            if exit.stopped || !should_restart(policy, exit.code, restarts, max_restarts) {
+                break Ok(exit.code);
+            }
+            restarts += 1;
+            progress(&format!("Restarting child ({}/{})...", restarts, max_restarts));
+            // Ctrl-C during the back-off stops the launcher instead of starting another child.
+            tokio::select! {
+                _ = tokio::time::sleep(RESTART_DELAY) => {}
+                _ = tokio::signal::ctrl_c() => {
+                    progress("Interrupted; not restarting");
+                    break Ok(exit.code);
+                }
+            }
+        };
+        if let Some(capture) = capture {
+            capture.finish();
+        }
+        std::process::exit(code?);
     }
 
     #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
@@ -177,7 +257,7 @@ async fn run_sandbox_windows(
     cfg: &crate::config::Config,
     sandbox_config: &crate::sandbox::SandboxConfig,
     child_args: &[String],
-) -> Result<()> {
+) -> Result<ChildExit> {
     use crate::sandbox::sandbox_trait::Sandbox;
     use crate::sandbox::WindowsAppContainerSandbox;
     use std::io::{BufRead, BufReader};
@@ -250,19 +330,18 @@ async fn run_sandbox_windows(
     let code = sandbox.spawn_child_in_container(&exe, &args)?;
     if let Some(mut h) = helper_child {
         let _ = h.kill();
+        let _ = h.wait();
     }
-    progress(&format!("Child exited with code {}", code));
-    std::process::exit(code);
+    Ok(ChildExit { code, stopped: false })
 }
 
 #[cfg(any(target_os = "linux", target_os = "macos"))]
 async fn run_sandbox_nono(
     sandbox_config: &crate::sandbox::SandboxConfig,
     child_args: &[String],
-) -> Result<()> {
+) -> Result<ChildExit> {
     use crate::sandbox::nono::build_nono_capability_set;
     use nix::sys::signal::{self, Signal};
-    use nix::sys::wait::WaitStatus;
     use nix::unistd::{execv, fork, ForkResult};
     use std::ffi::CString;
     use tokio::signal::unix::{signal, SignalKind};
@@ -305,39 +384,44 @@ async fn run_sandbox_nono(
         Err(e) => anyhow::bail!("fork failed: {}", e),
     };
 
-    fn exit_code_from_wait(status: std::result::Result<WaitStatus, nix::errno::Errno>) -> i32 {
-        match status {
-            Ok(WaitStatus::Exited(_, code)) => code,
-            Ok(WaitStatus::Signaled(_, sig, _)) => 128 + sig as i32,
-            Ok(_) => 1,
-            Err(_) => 130,
-        }
-    }
-
     let mut join = tokio::task::spawn_blocking(move || nix::sys::wait::waitpid(pid, None));
 
     let mut sigterm = signal(SignalKind::terminate())
         .context("Register SIGTERM handler")?;
 
-    let code = tokio::select! {
-        res = &mut join => exit_code_from_wait(res.unwrap_or(Err(nix::errno::Errno::ECHILD))),
+    let (code, stopped) = tokio::select! {
+        res = &mut join => (exit_code_from_wait(res.unwrap_or(Err(nix::errno::Errno::ECHILD))), false),
         _ = tokio::signal::ctrl_c() => {
             let _ = signal::kill(pid, Signal::SIGINT);
-            exit_code_from_wait(join.await.unwrap_or(Err(nix::errno::Errno::ECHILD)))
+            (exit_code_from_wait(join.await.unwrap_or(Err(nix::errno::Errno::ECHILD))), true)
         }
         _ = sigterm.recv() => {
             let _ = signal::kill(pid, Signal::SIGTERM);
-            exit_code_from_wait(join.await.unwrap_or(Err(nix::errno::Errno::ECHILD)))
+            (exit_code_from_wait(join.await.unwrap_or(Err(nix::errno::Errno::ECHILD))), true)
         }
     };
 
-    progress(&format!("Child exited with code {}", code));
-    std::process::exit(code);
+    Ok(ChildExit { code, stopped })
+}
+
+/// Exit code of the nono child as a shell reports it: its own code, or 128 + signal number.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn exit_code_from_wait(
+    status: std::result::Result<nix::sys::wait::WaitStatus, nix::errno::Errno>,
+) -> i32 {
+    use nix::sys::wait::WaitStatus;
+    match status {
+        Ok(WaitStatus::Exited(_, code)) => code,
+        Ok(WaitStatus::Signaled(_, sig, _)) => 128 + sig as i32,
+        Ok(_) => 1,
+        Err(_) => 130,
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::strip_root_dir_from_child_args;
+    use super::{launch_sandbox_config, should_restart, strip_root_dir_from_child_args};
+    use crate::config::{AppSandboxConfig, Config, SandboxRestartPolicy};
     use std::path::Path;
 
     #[test]
@@ -358,4 +442,38 @@ mod tests {
         assert_eq!(root.as_ref().map(|p| p.as_path()), Some(Path::new(r"d:\inst")));
         assert_eq!(rest, vec!["start".to_string()]);
     }
+
+    #[test]
+    fn recreate_flag_sets_delete_on_start() {
+        let cfg = Config::default();
+        let mut app_cfg = AppSandboxConfig::default();
+        assert!(!launch_sandbox_config(&cfg, &app_cfg, false).unwrap().delete_on_start);
+        assert!(launch_sandbox_config(&cfg, &app_cfg, true).unwrap().delete_on_start);
+        app_cfg.delete_on_start = Some(true);
+        assert!(launch_sandbox_config(&cfg, &app_cfg, false).unwrap().delete_on_start);
+    }
+
+    #[test]
+    fn on_failure_restarts_only_non_zero_exits_up_to_max() {
+        let policy = SandboxRestartPolicy::OnFailure;
+        assert!(should_restart(policy, 1, 0, 3));
+        assert!(should_restart(policy, 137, 2, 3));
+        assert!(!should_restart(policy, 0, 0, 3), "clean exit is final");
+        assert!(!should_restart(policy, 1, 3, 3), "max restarts reached");
+        assert!(!should_restart(SandboxRestartPolicy::Never, 1, 0, 3));
+        assert!(should_restart(SandboxRestartPolicy::Always, 0, 0, 3));
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    #[test]
+    fn child_wait_status_becomes_exit_code() {
+        use super::exit_code_from_wait;
+        use nix::sys::signal::Signal;
+        use nix::sys::wait::WaitStatus;
+        let pid = nix::unistd::Pid::from_raw(1);
+        assert_eq!(exit_code_from_wait(Ok(WaitStatus::Exited(pid, 0))), 0);
+        assert_eq!(exit_code_from_wait(Ok(WaitStatus::Exited(pid, 3))), 3);
+        assert_eq!(exit_code_from_wait(Ok(WaitStatus::Signaled(pid, Signal::SIGTERM, false))), 143);
+        assert_eq!(exit_code_from_wait(Err(nix::errno::Errno::ECHILD)), 130);
+    }
 }
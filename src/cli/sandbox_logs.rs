@@ -0,0 +1,321 @@
+//! `synbot sandbox logs`: the app sandbox child's captured stdout/stderr.
+//!
+//! While `synbot sandbox <args..>` runs, everything written to its stdout and stderr (its own
+//! progress lines and, through the inherited handles, the child's output) is copied into
+//! `<log dir>/sandbox-child.log`, truncated at each launch, and still shown on the terminal.
+//! `synbot sandbox logs` prints the end of that file; `--follow` keeps printing what is
+//! appended, like `tail -f`.
+
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// File name of the captured child output, in the configured log directory.
+pub const CHILD_LOG_FILE: &str = "sandbox-child.log";
+
+/// Lines printed by `synbot sandbox logs` without `--lines`.
+const DEFAULT_TAIL_LINES: usize = 50;
+
+/// How often `--follow` checks the file for new output.
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub fn child_log_path(cfg: &crate::config::Config) -> PathBuf {
+    crate::config::log_dir_path(cfg).join(CHILD_LOG_FILE)
+}
+
+// ---------------------------------------------------------------------------
+// Tailing
+// ---------------------------------------------------------------------------
+
+/// Reads a log file from the last position read.
+pub(crate) struct LogTail {
+    path: PathBuf,
+    offset: u64,
+}
+
+impl LogTail {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            offset: 0,
+        }
+    }
+
+    /// The last `n` lines of the file (all of it when shorter). Later reads continue at the end.
+    pub fn last_lines(&mut self, n: usize) -> std::io::Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        File::open(&self.path)?.read_to_end(&mut buf)?;
+        self.offset = buf.len() as u64;
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+        let body = buf.strip_suffix(b"\n").unwrap_or(&buf);
+        let start = body
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, b)| **b == b'\n')
+            .nth(n - 1)
+            .map(|(i, _)| i + 1)
+            .unwrap_or(0);
+        Ok(buf[start..].to_vec())
+    }
+
+    /// Bytes appended since the last read; empty while the file does not exist. A file shorter
+    /// than the last position was truncated by a new launch, so reading restarts at its top.
+    pub fn read_new(&mut self) -> std::io::Result<Vec<u8>> {
+        let mut file = match File::open(&self.path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        if file.metadata()?.len() < self.offset {
+            self.offset = 0;
+        }
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+        self.offset += buf.len() as u64;
+        Ok(buf)
+    }
+}
+
+/// Options of `synbot sandbox logs`: `--follow` / `-f`, `--lines N` / `-n N`.
+fn parse_logs_args(args: &[String]) -> Result<(bool, usize)> {
+    let mut follow = false;
+    let mut lines = DEFAULT_TAIL_LINES;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value = match arg.as_str() {
+            "--follow" | "-f" => {
+                follow = true;
+                continue;
+            }
+            "--lines" | "-n" => iter.next().map(String::as_str),
+            other => match other.strip_prefix("--lines=") {
+                Some(v) => Some(v),
+                None => anyhow::bail!(
+                    "unknown option for `synbot sandbox logs`: {} (use --follow, --lines N)",
+                    other
+                ),
+            },
+        };
+        lines = value
+            .and_then(|v| v.parse().ok())
+            .context("--lines needs a number")?;
+    }
+    Ok((follow, lines))
+}
+
+/// `synbot sandbox logs [--follow] [--lines N]`.
+pub async fn cmd_sandbox_logs(cfg: &crate::config::Config, args: &[String]) -> Result<()> {
+    let (follow, lines) = parse_logs_args(args)?;
+    let path = child_log_path(cfg);
+    let mut tail = LogTail::new(&path);
+    let mut out = std::io::stdout();
+    match tail.last_lines(lines) {
+        Ok(text) => out.write_all(&text)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound && !follow => anyhow::bail!(
+            "No sandbox child log at {} yet; it is written by `synbot sandbox start`.",
+            path.display()
+        ),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e).with_context(|| format!("Read {}", path.display())),
+    }
+    out.flush()?;
+    if !follow {
+        return Ok(());
+    }
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(FOLLOW_POLL_INTERVAL) => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+        let chunk = tail
+            .read_new()
+            .with_context(|| format!("Read {}", path.display()))?;
+        if !chunk.is_empty() {
+            out.write_all(&chunk)?;
+            out.flush()?;
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Capture
+// ---------------------------------------------------------------------------
+
+#[cfg(unix)]
+mod std_handles {
+    use std::fs::File;
+    use std::io::PipeWriter;
+    use std::os::fd::{AsFd, AsRawFd, OwnedFd};
+
+    /// The original stdout / stderr.
+    pub struct Saved {
+        out: OwnedFd,
+        err: OwnedFd,
+    }
+
+    /// Point fds 1 and 2 at `writer`; children spawned afterwards inherit it.
+    pub fn redirect(writer: &PipeWriter) -> std::io::Result<Saved> {
+        let saved = Saved {
+            out: std::io::stdout().as_fd().try_clone_to_owned()?,
+            err: std::io::stderr().as_fd().try_clone_to_owned()?,
+        };
+        nix::unistd::dup2(writer.as_raw_fd(), 1)?;
+        nix::unistd::dup2(writer.as_raw_fd(), 2)?;
+        Ok(saved)
+    }
+
+    /// Writer for the original stdout.
+    pub fn terminal(saved: &Saved) -> std::io::Result<File> {
+        Ok(File::from(saved.out.try_clone()?))
+    }
+
+    pub fn restore(saved: Saved) {
+        let _ = nix::unistd::dup2(saved.out.as_raw_fd(), 1);
+        let _ = nix::unistd::dup2(saved.err.as_raw_fd(), 2);
+    }
+}
+
+#[cfg(windows)]
+mod std_handles {
+    use std::fs::File;
+    use std::io::PipeWriter;
+    use std::os::windows::io::{AsHandle, AsRawHandle};
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::Console::{
+        GetStdHandle, SetStdHandle, STD_ERROR_HANDLE, STD_OUTPUT_HANDLE,
+    };
+
+    /// The original std handles (not owned: the console keeps them).
+    pub struct Saved {
+        out: HANDLE,
+        err: HANDLE,
+    }
+
+    /// Make `writer` the process's stdout and stderr; the AppContainer child gets the current
+    /// std handles when it is created.
+    pub fn redirect(writer: &PipeWriter) -> std::io::Result<Saved> {
+        unsafe {
+            let saved = Saved {
+                out: GetStdHandle(STD_OUTPUT_HANDLE)?,
+                err: GetStdHandle(STD_ERROR_HANDLE)?,
+            };
+            let pipe = HANDLE(writer.as_raw_handle());
+            SetStdHandle(STD_OUTPUT_HANDLE, pipe)?;
+            SetStdHandle(STD_ERROR_HANDLE, pipe)?;
+            Ok(saved)
+        }
+    }
+
+    /// Writer for the original stdout (a duplicate, so closing it leaves the console alone).
+    pub fn terminal(saved: &Saved) -> std::io::Result<File> {
+        let borrowed = unsafe { std::os::windows::io::BorrowedHandle::borrow_raw(saved.out.0) };
+        Ok(File::from(borrowed.as_handle().try_clone_to_owned()?))
+    }
+
+    pub fn restore(saved: Saved) {
+        unsafe {
+            let _ = SetStdHandle(STD_OUTPUT_HANDLE, saved.out);
+            let _ = SetStdHandle(STD_ERROR_HANDLE, saved.err);
+        }
+    }
+}
+
+/// Copies this process's stdout and stderr, and so the sandbox child's, into the child log
+/// while still writing them to the terminal. Call [`finish`](Self::finish) before exiting.
+pub(crate) struct OutputCapture {
+    saved: std_handles::Saved,
+    writer: std::io::PipeWriter,
+    copier: std::thread::JoinHandle<()>,
+}
+
+impl OutputCapture {
+    /// Truncate `path` and start copying into it.
+    pub fn start(path: &Path) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut log = File::create(path)?;
+        let (mut reader, writer) = std::io::pipe()?;
+        let _ = std::io::stdout().flush();
+        let _ = std::io::stderr().flush();
+        let saved = std_handles::redirect(&writer)?;
+        let mut terminal = match std_handles::terminal(&saved) {
+            Ok(t) => t,
+            Err(e) => {
+                std_handles::restore(saved);
+                return Err(e);
+            }
+        };
+        let copier = std::thread::spawn(move || {
+            let mut buf = [0u8; 8192];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        let _ = terminal.write_all(&buf[..n]);
+                        let _ = log.write_all(&buf[..n]);
+                    }
+                }
+            }
+            let _ = log.flush();
+        });
+        Ok(Self {
+            saved,
+            writer,
+            copier,
+        })
+    }
+
+    /// Put stdout/stderr back and wait until everything written so far is in the log.
+    pub fn finish(self) {
+        let _ = std::io::stdout().flush();
+        let _ = std::io::stderr().flush();
+        std_handles::restore(self.saved);
+        drop(self.writer);
+        let _ = self.copier.join();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tail_prints_last_lines_then_follows_appends_and_truncation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CHILD_LOG_FILE);
+        let mut tail = LogTail::new(&path);
+        assert!(tail.read_new().unwrap().is_empty(), "missing file reads as empty");
+
+        std::fs::write(&path, "one\ntwo\nthree\n").unwrap();
+        assert_eq!(tail.last_lines(2).unwrap(), b"two\nthree\n");
+        assert_eq!(tail.last_lines(10).unwrap(), b"one\ntwo\nthree\n");
+        assert!(tail.read_new().unwrap().is_empty());
+
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"four\nfi").unwrap();
+        assert_eq!(tail.read_new().unwrap(), b"four\nfi");
+        file.write_all(b"ve\n").unwrap();
+        assert_eq!(tail.read_new().unwrap(), b"ve\n");
+
+        // A new launch truncates the log: start over from its first byte.
+        std::fs::write(&path, "restarted\n").unwrap();
+        assert_eq!(tail.read_new().unwrap(), b"restarted\n");
+    }
+
+    #[test]
+    fn logs_args_parse_follow_and_lines() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(parse_logs_args(&[]).unwrap(), (false, DEFAULT_TAIL_LINES));
+        assert_eq!(parse_logs_args(&args(&["-f", "-n", "5"])).unwrap(), (true, 5));
+        assert_eq!(parse_logs_args(&args(&["--lines=0", "--follow"])).unwrap(), (true, 0));
+        assert!(parse_logs_args(&args(&["--lines", "x"])).is_err());
+        assert!(parse_logs_args(&args(&["--tail"])).is_err());
+    }
+}
@@ -22,7 +22,7 @@ fn write_pid_file() -> Result<PidFileGuard> {
     Ok(PidFileGuard(pid_path))
 }
 
-pub async fn cmd_start() -> Result<()> {
+pub async fn cmd_start(force: bool) -> Result<()> {
     // Immediate stderr so sandbox parent sees child has started (before any logging init)
     let _ = writeln!(std::io::stderr(), "[synbot] daemon starting...");
     let _ = std::io::stderr().flush();
@@ -50,6 +50,10 @@ pub async fn cmd_start() -> Result<()> {
     
     // Initialize logging with config (and feed events to log buffer for web UI)
     logging::init_logging(&cfg, Some(std::sync::Arc::new(log_tx)))?;
+    super::startup_check::check_startup(&cfg, force)?;
+    if let Some(seed) = crate::seed::init(cfg.seed) {
+        info!(seed, "Seeded run: retry jitter is reproducible and model temperature is 0");
+    }
 
     // When running inside app sandbox (Windows AppContainer or macOS nono): one-shot network
     // diagnostic to capture underlying error (DNS, TLS, or connect).
@@ -120,21 +124,26 @@ pub async fn cmd_start() -> Result<()> {
     );
 
     // Message bus (create early so approval manager can broadcast to channels)
-    let mut bus = crate::bus::MessageBus::new();
+    let mut bus = crate::bus::MessageBus::new().with_response_transforms(&cfg.response_transforms);
     let inbound_tx = bus.inbound_sender();
     let inbound_rx = bus.take_inbound_receiver().unwrap();
 
     // Create approval manager with outbound sender so approval requests reach Web/other channels
     let approval_manager = std::sync::Arc::new(
-        crate::tools::approval::ApprovalManager::with_outbound(bus.outbound_tx_clone()),
+        crate::tools::approval::ApprovalManager::with_outbound(bus.outbound_tx_clone())
+            .with_route(cfg.tools.exec.permissions.approval_route.clone())
+            .with_deny_on_timeout(cfg.tools.exec.permissions.deny_on_timeout),
     );
 
     // Load permission policy if enabled
     let permission_policy = if cfg.tools.exec.permissions.enabled {
-        Some(std::sync::Arc::new(crate::tools::permission::CommandPermissionPolicy::new(
-            cfg.tools.exec.permissions.rules.clone(),
-            cfg.tools.exec.permissions.default_level,
-        )))
+        Some(std::sync::Arc::new(
+            crate::tools::permission::CommandPermissionPolicy::new(
+                cfg.tools.exec.permissions.rules.clone(),
+                cfg.tools.exec.permissions.default_level,
+            )
+            .with_audit_log(std::sync::Arc::new(crate::tools::audit::ToolAuditLog::default())),
+        ))
     } else {
         None
     };
@@ -143,7 +152,7 @@ pub async fn cmd_start() -> Result<()> {
         Some((std::sync::Arc::clone(&shared_config), Some(config::config_path())));
 
     // Optional sandbox: create and start app/tool sandboxes when configured.
-    let sandbox_context = init_sandbox_if_configured(&cfg).await;
+    let sandbox_context = init_sandbox_if_configured(&cfg, bus.outbound_tx_clone()).await;
     let tool_sandbox_delegate: crate::sandbox::SandboxContext =
         match &sandbox_context {
             None => None,
@@ -151,6 +160,11 @@ pub async fn cmd_start() -> Result<()> {
                 manager: std::sync::Arc::clone(mgr),
                 sandbox_id: id.clone(),
                 kind: *kind,
+                workspace_dir: cfg
+                    .tool_sandbox
+                    .as_ref()
+                    .map(config::tool_sandbox_workspace_dir)
+                    .unwrap_or_else(|| crate::sandbox::types::DEFAULT_CONTAINER_WORKSPACE.to_string()),
             }),
             Some((_, None, _)) => None,
         };
@@ -158,7 +172,15 @@ pub async fn cmd_start() -> Result<()> {
 
     // Shared session state (in-memory + persistence); load before agent loop and tools
     let session_store = crate::agent::session::SessionStore::new(crate::config::sessions_root().as_path());
-    let shared_session_state = crate::agent::session_state::SharedSessionState::new(session_store);
+    let mut shared_session_state = crate::agent::session_state::SharedSessionState::new(session_store);
+    if let Some((mgr, _, _)) = &sandbox_context {
+        shared_session_state = shared_session_state.with_tool_sandboxes(std::sync::Arc::clone(mgr));
+    }
+    shared_session_state
+        .session_manager
+        .write()
+        .await
+        .set_identity_links(&cfg.identity_links);
     if let Err(e) = shared_session_state.load_persisted_sessions().await {
         tracing::warn!(error = %e, "Failed to load persisted sessions");
     }
@@ -178,12 +200,40 @@ pub async fn cmd_start() -> Result<()> {
 
     let hook_registry = crate::hooks::HookRegistry::new();
     let mut background_registry = crate::background::BackgroundServiceRegistry::new();
+    let heartbeat_control = std::sync::Arc::new(crate::heartbeat::HeartbeatControl::new());
     background_registry.register(std::sync::Arc::new(
-        crate::background::HeartbeatBackgroundService::new(std::sync::Arc::clone(&shared_config)),
+        crate::background::HeartbeatBackgroundService::with_control(
+            std::sync::Arc::clone(&shared_config),
+            std::sync::Arc::clone(&heartbeat_control),
+        )
+        .with_health_report(
+            bus.outbound_tx_clone(),
+            crate::heartbeat::HealthSources {
+                dead_letters: Some(bus.dead_letters()),
+                approvals: Some(std::sync::Arc::clone(&approval_manager)),
+                sandboxes: sandbox_context.as_ref().map(|(mgr, _, _)| std::sync::Arc::clone(mgr)),
+                log_buffer: Some(std::sync::Arc::clone(&log_buffer)),
+            },
+        ),
     ));
+    let cron_runs = std::sync::Arc::new(crate::cron::config_runner::CronRunTracker::new());
     background_registry.register(std::sync::Arc::new(
-        crate::background::CronBackgroundService::new(std::sync::Arc::clone(&shared_config)),
+        crate::background::CronBackgroundService::with_run_tracker(
+            std::sync::Arc::clone(&shared_config),
+            std::sync::Arc::clone(&cron_runs),
+        ),
     ));
+    if cfg.telemetry.enabled {
+        let telemetry = std::sync::Arc::new(crate::telemetry::Telemetry::from_config(&cfg.telemetry));
+        hook_registry
+            .register(std::sync::Arc::new(crate::telemetry::TelemetryHook::new(
+                std::sync::Arc::clone(&telemetry),
+            )))
+            .await;
+        background_registry.register(std::sync::Arc::new(
+            crate::telemetry::TelemetryBackgroundService::new(telemetry),
+        ));
+    }
     let skills_dir = config::skills_dir();
     if let Err(e) = std::fs::create_dir_all(&skills_dir) {
         tracing::warn!(path = %skills_dir.display(), error = %e, "Could not create skills dir");
@@ -204,6 +254,11 @@ pub async fn cmd_start() -> Result<()> {
         crate::tools::mcp::load_mcp_tools(mcp, &mut tool_reg).await;
     }
 
+    if let Err(problems) = tool_reg.validate() {
+        for problem in &problems {
+            tracing::warn!(problem = %problem, "Tool registry check failed");
+        }
+    }
     if let Err(e) = tool_reg.register_list_tools_tool() {
         tracing::warn!(error = %e, "Failed to register list_tools tool");
     }
@@ -212,18 +267,35 @@ pub async fn cmd_start() -> Result<()> {
     let provider_name = cfg.main_agent.provider.clone();
     let (api_key, api_base) = resolve_provider(&cfg, &provider_name);
     if api_key.is_empty() {
-        anyhow::bail!(
+        return Err(crate::rig_provider::ProviderError(format!(
             "No API key configured for provider '{}'. Set the corresponding [providers] entry in config.",
             provider_name,
-        );
+        ))
+        .into());
     }
     let completion_model = build_rig_completion_model(
+        &cfg,
         &provider_name,
         &model,
         &api_key,
         api_base.as_deref(),
     )?;
 
+    // Needs the completion model, so it is registered once the model exists. It shares the
+    // channel model overrides with the agent loop, so a summary uses the conversation's model.
+    let channel_models = std::sync::Arc::new(crate::agent::channel_models::ChannelModels::new(
+        std::sync::Arc::clone(&completion_model),
+    ));
+    if let Err(e) = tool_reg.register(std::sync::Arc::new(
+        crate::tools::session_tools::SummarizeConversationTool::new(
+            shared_session_state.clone(),
+            std::sync::Arc::clone(&channel_models),
+            std::sync::Arc::clone(&shared_config),
+        ),
+    )) {
+        tracing::warn!(error = %e, "Failed to register summarize_conversation tool");
+    }
+
     let tools = std::sync::Arc::new(tool_reg);
 
     // Wire spawn tool to run real subagents (model + tools) and to send completion to user
@@ -267,14 +339,17 @@ pub async fn cmd_start() -> Result<()> {
         let _ = crate::agent::memory_index::open_index("main", cfg.memory.embedding_dimensions);
     }
 
+    // Skills and cron jobs are served by the web API
+    #[cfg(feature = "web")]
     let skills_loader = std::sync::Arc::new(skills_composite);
 
-    let cron_store_path = config::config_dir().join("cron").join("jobs.json");
+    #[cfg(feature = "web")]
     let cron_service = std::sync::Arc::new(tokio::sync::RwLock::new(
-        crate::cron::service::CronService::new(cron_store_path),
+        crate::cron::service::CronService::new(config::config_dir().join("cron").join("jobs.json")),
     ));
 
     // Start agent loop (Arc<Mutex<>> so /stop or /cancel can cancel a running agent task)
+    let active_turns = std::sync::Arc::new(crate::agent::active_turns::ActiveTurns::new());
     let agent_loop = crate::agent::r#loop::AgentLoop::new(
         std::sync::Arc::clone(&completion_model),
         ws.clone(),
@@ -288,7 +363,10 @@ pub async fn cmd_start() -> Result<()> {
         Some(std::sync::Arc::new(hook_registry)),
         std::sync::Arc::clone(&shared_config),
     )
-    .await;
+    .await
+    .with_channel_models(channel_models)
+    .with_cron_runs(cron_runs)
+    .with_active_turns(std::sync::Arc::clone(&active_turns));
     let loop_ref = std::sync::Arc::new(tokio::sync::Mutex::new(agent_loop));
     tokio::spawn(async move {
         if let Err(e) = crate::agent::r#loop::AgentLoop::run(loop_ref, inbound_rx).await {
@@ -314,10 +392,20 @@ pub async fn cmd_start() -> Result<()> {
     // Start channels via registry (built-in + any plugin-registered channel types)
     let mut channel_registry = crate::channels::ChannelRegistry::new();
     crate::channels::factory::register_builtin_channels(&mut channel_registry);
+    let enabled_channels = cfg.enabled_channels();
+    info!(
+        channels = ?enabled_channels.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+        "Starting enabled channels"
+    );
     for (type_name, configs) in cfg.channels.channel_entries() {
         let factory = match channel_registry.get(&type_name) {
             Some(f) => f,
-            None => continue,
+            None => {
+                if enabled_channels.iter().any(|(_, kind)| kind.as_str() == type_name) {
+                    warn!(channel = %type_name, "Channel is enabled in config but not available in this build (Cargo feature disabled); skipping");
+                }
+                continue;
+            }
         };
         for config_value in configs {
             let enabled = config_value
@@ -333,7 +421,17 @@ pub async fn cmd_start() -> Result<()> {
                 .unwrap_or(true);
             let show_tool_calls = cfg.show_tool_calls && channel_show_tool_calls;
             let inbound_tx_ch = inbound_tx.clone();
-            let outbound_rx = bus.subscribe_outbound();
+            let mut outbound_rx = bus.subscribe_outbound();
+            let settings: config::ChannelEntrySettings =
+                serde_json::from_value(config_value.clone()).unwrap_or_default();
+            if let Some(min_interval_ms) = settings.outbound_min_interval_ms.filter(|ms| *ms > 0) {
+                let name = if settings.name.is_empty() { type_name.clone() } else { settings.name };
+                outbound_rx = crate::channels::outbound_throttle::throttle_outbound(
+                    outbound_rx,
+                    name,
+                    std::time::Duration::from_millis(min_interval_ms),
+                );
+            }
             let ctx = crate::channels::ChannelStartContext {
                 inbound_tx: inbound_tx_ch,
                 outbound_rx,
@@ -344,6 +442,7 @@ pub async fn cmd_start() -> Result<()> {
                 completion_model: Some(std::sync::Arc::clone(&completion_model)),
                 outbound_tx: Some(bus.outbound_tx_clone()),
                 config_path: Some(config::config_path()),
+                dead_letters: Some(bus.dead_letters()),
             };
             let factory = std::sync::Arc::clone(&factory);
             let type_name = type_name.clone();
@@ -360,8 +459,18 @@ pub async fn cmd_start() -> Result<()> {
         }
     }
 
-    // Start web server if enabled
-    if cfg.web.enabled {
+    // Start web server if enabled (needs the `web` feature)
+    #[cfg(feature = "web")]
+    let run_web = cfg.web.enabled;
+    #[cfg(not(feature = "web"))]
+    let run_web = {
+        if cfg.web.enabled {
+            warn!("web.enabled is true but this build does not include the `web` feature; web UI and API are unavailable");
+        }
+        false
+    };
+    #[cfg(feature = "web")]
+    if run_web {
         let mut web_config = cfg.web.clone();
         // Inside AppContainer the process is network-isolated; bind to 0.0.0.0 so
         // LAN clients can reach the web UI (inbound firewall rule is added by the
@@ -371,7 +480,7 @@ pub async fn cmd_start() -> Result<()> {
             web_config.host = "0.0.0.0".to_string();
             info!("AppContainer: web server binding overridden to 0.0.0.0:{}", web_config.port);
         }
-        let web_state = crate::web::AppState::new(
+        let mut web_state = crate::web::AppState::new(
             std::sync::Arc::clone(&shared_config),
             config::config_path(),
             shared_session_state.session_manager.clone(),
@@ -383,7 +492,16 @@ pub async fn cmd_start() -> Result<()> {
             log_buffer,
             approval_manager,
             permission_policy,
-        );
+        )
+        .with_heartbeat_control(heartbeat_control)
+        .with_active_turns(active_turns)
+        .with_delivery(bus.delivery_tx_clone())
+        .with_dead_letters(bus.dead_letters());
+        if let Some((mgr, _, _)) = &sandbox_context {
+            web_state = web_state
+                .with_sandbox_monitoring(std::sync::Arc::clone(mgr.monitoring()))
+                .with_sandbox_manager(std::sync::Arc::clone(mgr));
+        }
 
         // Run web server in the main task (it will block until Ctrl+C)
         tokio::select! {
@@ -396,7 +514,8 @@ pub async fn cmd_start() -> Result<()> {
                 info!("Shutting down...");
             }
         }
-    } else {
+    }
+    if !run_web {
         info!("🐈 synbot daemon running. Press Ctrl+C to stop.");
         tokio::signal::ctrl_c().await?;
         info!("Shutting down...");
@@ -410,6 +529,7 @@ pub async fn cmd_start() -> Result<()> {
 /// When we are already inside the app sandbox (child of `synbot sandbox`), we skip creating/starting app sandbox.
 async fn init_sandbox_if_configured(
     cfg: &config::Config,
+    alert_tx: tokio::sync::broadcast::Sender<crate::bus::OutboundMessage>,
 ) -> Option<(
     std::sync::Arc<crate::sandbox::SandboxManager>,
     Option<String>,
@@ -422,7 +542,26 @@ async fn init_sandbox_if_configured(
         return None;
     }
 
-    let manager = std::sync::Arc::new(crate::sandbox::SandboxManager::with_defaults());
+    let idle_timeout = (cfg.tool_sandbox_idle_secs > 0)
+        .then(|| std::time::Duration::from_secs(cfg.tool_sandbox_idle_secs));
+    let pool_size = cfg.tool_sandbox.as_ref().and_then(|t| t.pool_size).unwrap_or(1);
+    let isolation = cfg.tool_sandbox.as_ref().map(|t| t.isolation).unwrap_or_default();
+    let mut manager = crate::sandbox::SandboxManager::with_defaults()
+        .with_tool_sandbox_idle_timeout(idle_timeout)
+        .with_tool_sandbox_pool_size(pool_size as usize)
+        .with_tool_sandbox_isolation(isolation)
+        .with_violation_threshold(config::sandbox_violation_threshold(&cfg.sandbox_monitoring));
+    if let Some((channel, chat_id)) = cfg
+        .sandbox_monitoring
+        .as_ref()
+        .and_then(|m| m.alert_channel.clone().zip(m.alert_chat_id.clone()))
+    {
+        manager = manager.with_alert_sender(alert_tx, channel, chat_id);
+    }
+    if let Some(ref limits) = cfg.sandbox_resource_limits {
+        manager = manager.with_resource_limits(limits.clone());
+    }
+    let manager = std::sync::Arc::new(manager);
     let monitoring = &cfg.sandbox_monitoring;
     let mut app_started = false;
 
@@ -443,12 +582,32 @@ async fn init_sandbox_if_configured(
     if let Some(ref tool_cfg) = cfg.tool_sandbox {
         match config::build_tool_sandbox_config(tool_cfg, monitoring, &workspace_path, &skills_dir) {
             Ok(sandbox_config) => {
+                manager.set_tool_sandbox_config(sandbox_config.clone()).await;
                 match manager.create_tool_sandbox(sandbox_config).await {
+                    Ok(id) if idle_timeout.is_some() => {
+                        // Started on the first tool exec and stopped again when idle.
+                        info!(
+                            sandbox_id = %id,
+                            idle_secs = cfg.tool_sandbox_idle_secs,
+                            "Tool sandbox created (starts on first exec, stops when idle)"
+                        );
+                        manager.spawn_tool_sandbox_idle_reaper();
+                        let kind = config::tool_sandbox_exec_kind(tool_cfg);
+                        return Some((manager, Some(id), kind));
+                    }
                     Ok(id) => {
                         if let Err(e) = manager.start_sandbox(&id).await {
                             warn!(sandbox_id = %id, error = %e, "Tool sandbox start failed (exec will run on host)");
                         } else {
                             info!(sandbox_id = %id, "Tool sandbox started (exec runs in sandbox)");
+                            if pool_size > 1 {
+                                match manager.start_tool_sandbox_pool().await {
+                                    Ok(ids) => info!(sandbox_ids = ?ids, "Tool sandbox pool started"),
+                                    Err(e) => warn!(error = %e, "Tool sandbox pool start failed (members start on demand)"),
+                                }
+                            }
+                            // Removes per-session sandboxes once idle; None in shared mode without an idle timeout.
+                            manager.spawn_tool_sandbox_idle_reaper();
                             let kind = config::tool_sandbox_exec_kind(tool_cfg);
                             return Some((manager, Some(id), kind));
                         }
@@ -0,0 +1,126 @@
+//! Startup self-check of `synbot start`: refuse to run a daemon that cannot do anything useful.
+//!
+//! Without an enabled channel nobody can reach the bot, and without an API key for the main
+//! agent's provider every turn fails. Both are reported before anything is started, with the
+//! setting to change; `synbot start --force` logs them as warnings and starts anyway.
+
+use anyhow::Result;
+use tracing::warn;
+
+use crate::config::{self, Config};
+
+/// Providers that run locally and need no API key.
+fn is_keyless_provider(cfg: &Config, provider: &str) -> bool {
+    provider.trim().to_lowercase().contains("ollama") || cfg.providers.extra.contains_key(provider.trim())
+}
+
+/// Configuration problems that leave the daemon with nothing useful to do; empty when it can run.
+pub(crate) fn startup_problems(cfg: &Config) -> Vec<String> {
+    let mut problems = Vec::new();
+    if cfg.enabled_channels().is_empty() && !cfg.web.enabled {
+        problems.push(
+            "no channel is enabled: set `enabled: true` on an entry under `channels` (e.g. channels.telegram) or enable the web dashboard with `web.enabled`".to_string(),
+        );
+    }
+    let provider = &cfg.main_agent.provider;
+    let (api_key, _) = config::resolve_provider(cfg, provider);
+    if api_key.trim().is_empty() && !is_keyless_provider(cfg, provider) {
+        problems.push(format!(
+            "the default provider '{}' (mainAgent.provider) has no API key: set `providers.{}.apiKey`, or pick a configured provider",
+            provider, provider
+        ));
+    }
+    problems
+}
+
+/// The self-check found no usable configuration; a config error for the exit code (see
+/// [`super::exit_code`]).
+#[derive(Debug)]
+pub(crate) struct StartupCheckFailed(pub Vec<String>);
+
+impl std::fmt::Display for StartupCheckFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list: Vec<String> = self.0.iter().map(|p| format!("  - {}", p)).collect();
+        write!(
+            f,
+            "Startup self-check failed; there is no usable configuration:\n{}\nFix the config (`synbot doctor` checks it) or run `synbot start --force` to start anyway.",
+            list.join("\n")
+        )
+    }
+}
+
+impl std::error::Error for StartupCheckFailed {}
+
+/// Fail with every problem found, unless `force` is set, in which case they are only logged.
+pub(crate) fn check_startup(cfg: &Config, force: bool) -> Result<()> {
+    let problems = startup_problems(cfg);
+    if problems.is_empty() {
+        return Ok(());
+    }
+    if force {
+        for problem in &problems {
+            warn!("Startup self-check (ignored with --force): {}", problem);
+        }
+        return Ok(());
+    }
+    Err(StartupCheckFailed(problems).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::exit_code::Failure;
+    use crate::config::TelegramConfig;
+
+    fn usable_config() -> Config {
+        let mut cfg = Config::default();
+        cfg.main_agent.provider = "anthropic".into();
+        cfg.providers.anthropic.api_key = "sk-ant-test".into();
+        cfg.channels.telegram.push(TelegramConfig {
+            name: "telegram".into(),
+            enabled: true,
+            token: "t".into(),
+            ..Default::default()
+        });
+        cfg
+    }
+
+    #[test]
+    fn no_usable_config_is_detected() {
+        assert!(startup_problems(&usable_config()).is_empty());
+
+        let mut cfg = usable_config();
+        cfg.channels.telegram[0].enabled = false;
+        cfg.web.enabled = false;
+        let problems = startup_problems(&cfg);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("no channel is enabled"), "{}", problems[0]);
+        cfg.web.enabled = true;
+        assert!(startup_problems(&cfg).is_empty(), "the web dashboard counts as a channel");
+
+        let mut cfg = usable_config();
+        cfg.providers.anthropic.api_key = String::new();
+        let problems = startup_problems(&cfg);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("providers.anthropic.apiKey"), "{}", problems[0]);
+        cfg.main_agent.provider = "ollama".into();
+        assert!(startup_problems(&cfg).is_empty(), "ollama runs without a key");
+
+        let mut cfg = Config::default();
+        cfg.web.enabled = false;
+        assert_eq!(startup_problems(&cfg).len(), 2);
+    }
+
+    #[test]
+    fn force_overrides_the_self_check() {
+        let mut cfg = Config::default();
+        cfg.web.enabled = false;
+        let err = check_startup(&cfg, false).unwrap_err();
+        assert_eq!(Failure::of(&err), Failure::Config, "a refused start is a config error");
+        let err = err.to_string();
+        assert!(err.contains("no channel is enabled"), "{}", err);
+        assert!(err.contains("--force"), "{}", err);
+        assert!(check_startup(&cfg, true).is_ok());
+        assert!(check_startup(&usable_config(), false).is_ok());
+    }
+}
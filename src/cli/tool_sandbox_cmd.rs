@@ -30,7 +30,11 @@ pub async fn cmd_tool_sandbox_serve(pipe: String, auth: String) -> anyhow::Resul
 
     let sandbox_id = sandbox_config.sandbox_id.clone();
 
-    let manager = std::sync::Arc::new(crate::sandbox::SandboxManager::with_defaults());
+    let mut manager = crate::sandbox::SandboxManager::with_defaults();
+    if let Some(ref limits) = cfg.sandbox_resource_limits {
+        manager = manager.with_resource_limits(limits.clone());
+    }
+    let manager = std::sync::Arc::new(manager);
     manager
         .create_tool_sandbox(sandbox_config)
         .await
@@ -0,0 +1,86 @@
+//! `synbot turns` — list the daemon's running agent turns and cancel a stuck one.
+
+use anyhow::Result;
+use clap::Subcommand;
+use serde_json::Value;
+
+use super::helpers::daemon_api;
+use crate::config;
+
+#[derive(Subcommand)]
+pub enum TurnsAction {
+    /// List running turns with their session, current tool, and elapsed time.
+    List,
+    /// Cancel a running turn (as if the user had sent /stop).
+    Kill {
+        /// Turn id as shown by `synbot turns list`.
+        id: String,
+    },
+}
+
+/// One line per turn from the `GET /api/turns` payload.
+pub fn format_turns(turns: &Value) -> String {
+    let items = turns.as_array().map(Vec::as_slice).unwrap_or_default();
+    if items.is_empty() {
+        return "No turns running.\n".to_string();
+    }
+    items
+        .iter()
+        .map(|t| {
+            format!(
+                "{}  {}  {}s  tool: {}  (started {})\n",
+                t["id"].as_str().unwrap_or("?"),
+                t["session_key"].as_str().unwrap_or("?"),
+                t["elapsed_secs"].as_u64().unwrap_or(0),
+                t["current_tool"].as_str().unwrap_or("-"),
+                t["started_at"].as_str().unwrap_or("?"),
+            )
+        })
+        .collect()
+}
+
+pub async fn cmd_turns(action: TurnsAction) -> Result<()> {
+    let cfg = config::load_config(None)?;
+    match action {
+        TurnsAction::List => {
+            let turns = daemon_api(&cfg, reqwest::Method::GET, "/api/turns").await?;
+            print!("{}", format_turns(&turns));
+        }
+        TurnsAction::Kill { id } => {
+            let turn = daemon_api(&cfg, reqwest::Method::DELETE, &format!("/api/turns/{}", id)).await?;
+            println!(
+                "Cancelled {} ({}).",
+                id,
+                turn["session_key"].as_str().unwrap_or("unknown session")
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn formats_running_turns() {
+        let turns = json!([{
+            "id": "turn-3",
+            "session_key": "telegram:42",
+            "elapsed_secs": 95,
+            "current_tool": "exec",
+            "started_at": "2026-01-01T00:00:00Z",
+        }, {
+            "id": "turn-4",
+            "session_key": "discord:7",
+            "elapsed_secs": 2,
+            "current_tool": null,
+            "started_at": "2026-01-01T00:01:33Z",
+        }]);
+        let out = format_turns(&turns);
+        assert!(out.contains("turn-3  telegram:42  95s  tool: exec"));
+        assert!(out.contains("turn-4  discord:7  2s  tool: -"));
+        assert_eq!(format_turns(&json!([])), "No turns running.\n");
+    }
+}
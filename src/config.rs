@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tracing::warn;
 use std::path::{Path, PathBuf};
-use std::sync::{Mutex, OnceLock, RwLock};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 use std::time::SystemTime;
 
 // ---------------------------------------------------------------------------
@@ -19,7 +19,8 @@ pub struct AllowlistEntry {
     pub chat_id: String,
     /// Human-readable alias for this chat (for logs and UI).
     pub chat_alias: String,
-    /// Bot's name in the group (optional). When set, only messages starting with @my_name are processed.
+    /// Bot's name in the group (optional). When set, only messages mentioning @my_name are processed
+    /// (at the start, or anywhere when the channel's `mentionMode` is `anywhere`).
     #[serde(default)]
     pub my_name: Option<String>,
 }
@@ -36,6 +37,28 @@ pub struct PairingEntry {
     pub pairing_code: String,
 }
 
+/// One person's identities on several channels, sharing a single DM conversation.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct IdentityLinkConfig {
+    /// Name of the shared conversation (letters, digits, `-`, `_`); becomes the session identifier.
+    pub name: String,
+    /// Linked identities as `<channel name>:<chatId>`, e.g. `telegram:12345`, `discord:67890`.
+    #[serde(default)]
+    pub members: Vec<String>,
+}
+
+impl IdentityLinkConfig {
+    /// `(channel, chat_id)` of each member in the `<channel>:<chatId>` form; others are skipped.
+    pub fn parsed_members(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.members.iter().filter_map(|m| {
+            m.split_once(':')
+                .filter(|(channel, chat_id)| !channel.is_empty() && !chat_id.is_empty())
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
@@ -60,9 +83,34 @@ pub struct TelegramConfig {
     /// When true (default), push tool execution progress to this channel.
     #[serde(default = "default_true")]
     pub show_tool_calls: bool,
+    /// When true (default), show a typing indicator while the agent works on a message.
+    #[serde(default = "default_true")]
+    pub typing_indicator: bool,
     /// Agent to use for this channel (e.g. "main", "dev"). Default "main".
     #[serde(default = "default_channel_agent")]
     pub default_agent: String,
+    /// Inbound message size cap for this channel. Unset means no cap.
+    #[serde(default)]
+    pub inbound_limit: Option<InboundLimitConfig>,
+    /// Minimum time between two messages sent to the same chat, in milliseconds. Unset or 0 sends without spacing.
+    #[serde(default)]
+    pub outbound_min_interval_ms: Option<u64>,
+    /// Prefix commands answered without the model (e.g. `!help`, `!status`).
+    #[serde(default)]
+    pub commands: Vec<ChannelCommandConfig>,
+    /// When false, messages on this channel get no tools: none are offered to the model and any
+    /// tool call it makes anyway is refused. Default true.
+    #[serde(default = "default_true")]
+    pub tools_enabled: bool,
+    /// Provider for this channel's turns instead of the agent's (requires `model`).
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model for this channel's turns instead of the agent's.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Route this channel's messages to the agent running this role (overrides the channel's agent).
+    #[serde(default)]
+    pub role: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -84,12 +132,44 @@ pub struct DiscordConfig {
     /// When enable_allowlist is false, bot name/id used for group @ check (optional).
     #[serde(default)]
     pub group_my_name: Option<String>,
+    /// Where the group @-mention (`groupMyName` / allowlist `myName`) may appear: `prefix` or `anywhere`.
+    #[serde(default)]
+    pub mention_mode: MentionMode,
     /// When true (default), push tool execution progress to this channel.
     #[serde(default = "default_true")]
     pub show_tool_calls: bool,
+    /// When true (default), show a typing indicator while the agent works on a message.
+    #[serde(default = "default_true")]
+    pub typing_indicator: bool,
     /// Agent to use for this channel (e.g. "main", "dev"). Default "main".
     #[serde(default = "default_channel_agent")]
     pub default_agent: String,
+    /// Inbound message size cap for this channel. Unset means no cap.
+    #[serde(default)]
+    pub inbound_limit: Option<InboundLimitConfig>,
+    /// Minimum time between two messages sent to the same chat, in milliseconds. Unset or 0 sends without spacing.
+    #[serde(default)]
+    pub outbound_min_interval_ms: Option<u64>,
+    /// Prefix commands answered without the model (e.g. `!help`, `!status`).
+    #[serde(default)]
+    pub commands: Vec<ChannelCommandConfig>,
+    /// When false, messages on this channel get no tools: none are offered to the model and any
+    /// tool call it makes anyway is refused. Default true.
+    #[serde(default = "default_true")]
+    pub tools_enabled: bool,
+    /// Provider for this channel's turns instead of the agent's (requires `model`).
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model for this channel's turns instead of the agent's.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Route this channel's messages to the agent running this role (overrides the channel's agent).
+    #[serde(default)]
+    pub role: Option<String>,
+    /// When true, register `/ask`, `/status`, `/stop`, `/clear` and `/help` as Discord slash
+    /// commands on startup and handle them. Default false.
+    #[serde(default)]
+    pub slash_commands: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -113,12 +193,56 @@ pub struct FeishuConfig {
     /// When enable_allowlist is false, bot name used for group @ check (optional).
     #[serde(default)]
     pub group_my_name: Option<String>,
+    /// Where the group @-mention (`groupMyName` / allowlist `myName`) may appear: `prefix` or `anywhere`.
+    #[serde(default)]
+    pub mention_mode: MentionMode,
     /// When true (default), push tool execution progress to this channel.
     #[serde(default = "default_true")]
     pub show_tool_calls: bool,
     /// Agent to use for this channel (e.g. "main", "dev"). Default "main".
     #[serde(default = "default_channel_agent")]
     pub default_agent: String,
+    /// Inbound message size cap for this channel. Unset means no cap.
+    #[serde(default)]
+    pub inbound_limit: Option<InboundLimitConfig>,
+    /// Minimum time between two messages sent to the same chat, in milliseconds. Unset or 0 sends without spacing.
+    #[serde(default)]
+    pub outbound_min_interval_ms: Option<u64>,
+    /// Prefix commands answered without the model (e.g. `!help`, `!status`).
+    #[serde(default)]
+    pub commands: Vec<ChannelCommandConfig>,
+    /// When false, messages on this channel get no tools: none are offered to the model and any
+    /// tool call it makes anyway is refused. Default true.
+    #[serde(default = "default_true")]
+    pub tools_enabled: bool,
+    /// Provider for this channel's turns instead of the agent's (requires `model`).
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model for this channel's turns instead of the agent's.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Route this channel's messages to the agent running this role (overrides the channel's agent).
+    #[serde(default)]
+    pub role: Option<String>,
+    /// Message type replies are sent as: `text` (default) or `post` (rich text, renders
+    /// Markdown). Approval requests are sent as interactive cards either way.
+    #[serde(default)]
+    pub message_format: FeishuMessageFormat,
+    /// Largest image / file attachment downloaded into the workspace, in MB. Default 20.
+    #[serde(default)]
+    pub max_media_mb: Option<u64>,
+}
+
+/// Feishu message type used for replies (`messageFormat` on a Feishu channel entry).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum FeishuMessageFormat {
+    /// Plain `text` messages (default).
+    #[default]
+    Text,
+    /// `post` rich text, so Markdown in replies (bold, lists, links, code) is rendered.
+    Post,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -150,6 +274,28 @@ pub struct SlackConfig {
     /// Agent to use for this channel (e.g. "main", "dev"). Default "main".
     #[serde(default = "default_channel_agent")]
     pub default_agent: String,
+    /// Inbound message size cap for this channel. Unset means no cap.
+    #[serde(default)]
+    pub inbound_limit: Option<InboundLimitConfig>,
+    /// Minimum time between two messages sent to the same chat, in milliseconds. Unset or 0 sends without spacing.
+    #[serde(default)]
+    pub outbound_min_interval_ms: Option<u64>,
+    /// Prefix commands answered without the model (e.g. `!help`, `!status`).
+    #[serde(default)]
+    pub commands: Vec<ChannelCommandConfig>,
+    /// When false, messages on this channel get no tools: none are offered to the model and any
+    /// tool call it makes anyway is refused. Default true.
+    #[serde(default = "default_true")]
+    pub tools_enabled: bool,
+    /// Provider for this channel's turns instead of the agent's (requires `model`).
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model for this channel's turns instead of the agent's.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Route this channel's messages to the agent running this role (overrides the channel's agent).
+    #[serde(default)]
+    pub role: Option<String>,
 }
 
 fn default_telegram_name() -> String {
@@ -209,6 +355,28 @@ pub struct MatrixConfig {
     /// Agent to use for this channel (e.g. "main", "dev"). Default "main".
     #[serde(default = "default_channel_agent")]
     pub default_agent: String,
+    /// Inbound message size cap for this channel. Unset means no cap.
+    #[serde(default)]
+    pub inbound_limit: Option<InboundLimitConfig>,
+    /// Minimum time between two messages sent to the same chat, in milliseconds. Unset or 0 sends without spacing.
+    #[serde(default)]
+    pub outbound_min_interval_ms: Option<u64>,
+    /// Prefix commands answered without the model (e.g. `!help`, `!status`).
+    #[serde(default)]
+    pub commands: Vec<ChannelCommandConfig>,
+    /// When false, messages on this channel get no tools: none are offered to the model and any
+    /// tool call it makes anyway is refused. Default true.
+    #[serde(default = "default_true")]
+    pub tools_enabled: bool,
+    /// Provider for this channel's turns instead of the agent's (requires `model`).
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model for this channel's turns instead of the agent's.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Route this channel's messages to the agent running this role (overrides the channel's agent).
+    #[serde(default)]
+    pub role: Option<String>,
 }
 
 fn default_matrix_name() -> String {
@@ -250,6 +418,28 @@ pub struct DingTalkConfig {
     /// Robot code from open platform (机器人 → robotCode). Required for file/image download API if callback omits it.
     #[serde(default)]
     pub robot_code: String,
+    /// Inbound message size cap for this channel. Unset means no cap.
+    #[serde(default)]
+    pub inbound_limit: Option<InboundLimitConfig>,
+    /// Minimum time between two messages sent to the same chat, in milliseconds. Unset or 0 sends without spacing.
+    #[serde(default)]
+    pub outbound_min_interval_ms: Option<u64>,
+    /// Prefix commands answered without the model (e.g. `!help`, `!status`).
+    #[serde(default)]
+    pub commands: Vec<ChannelCommandConfig>,
+    /// When false, messages on this channel get no tools: none are offered to the model and any
+    /// tool call it makes anyway is refused. Default true.
+    #[serde(default = "default_true")]
+    pub tools_enabled: bool,
+    /// Provider for this channel's turns instead of the agent's (requires `model`).
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model for this channel's turns instead of the agent's.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Route this channel's messages to the agent running this role (overrides the channel's agent).
+    #[serde(default)]
+    pub role: Option<String>,
 }
 
 fn default_dingtalk_name() -> String {
@@ -292,6 +482,9 @@ pub struct EmailConfig {
     /// Only treat emails FROM this address as chat (e.g. "user@example.com").
     #[serde(default)]
     pub from_sender: String,
+    /// Further sender addresses accepted besides `fromSender`.
+    #[serde(default)]
+    pub allowed_senders: Vec<String>,
     /// Only process emails received after this time (RFC3339 or date-only "YYYY-MM-DD").
     #[serde(default)]
     pub start_time: String,
@@ -301,9 +494,34 @@ pub struct EmailConfig {
     /// When true (default), push tool execution progress to this channel.
     #[serde(default = "default_true")]
     pub show_tool_calls: bool,
+    /// Largest attachment saved into the workspace, in MB. Default 20.
+    #[serde(default)]
+    pub max_attachment_mb: Option<u64>,
     /// Agent to use for this channel (e.g. "main", "dev"). Default "main".
     #[serde(default = "default_channel_agent")]
     pub default_agent: String,
+    /// Inbound message size cap for this channel. Unset means no cap.
+    #[serde(default)]
+    pub inbound_limit: Option<InboundLimitConfig>,
+    /// Minimum time between two messages sent to the same chat, in milliseconds. Unset or 0 sends without spacing.
+    #[serde(default)]
+    pub outbound_min_interval_ms: Option<u64>,
+    /// Prefix commands answered without the model (e.g. `!help`, `!status`).
+    #[serde(default)]
+    pub commands: Vec<ChannelCommandConfig>,
+    /// When false, messages on this channel get no tools: none are offered to the model and any
+    /// tool call it makes anyway is refused. Default true.
+    #[serde(default = "default_true")]
+    pub tools_enabled: bool,
+    /// Provider for this channel's turns instead of the agent's (requires `model`).
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model for this channel's turns instead of the agent's.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Route this channel's messages to the agent running this role (overrides the channel's agent).
+    #[serde(default)]
+    pub role: Option<String>,
 }
 
 fn default_email_name() -> String {
@@ -338,6 +556,28 @@ pub struct WhatsAppConfig {
     /// Agent to use for this channel (e.g. "main", "dev"). Default "main".
     #[serde(default = "default_channel_agent")]
     pub agent: String,
+    /// Inbound message size cap for this channel. Unset means no cap.
+    #[serde(default)]
+    pub inbound_limit: Option<InboundLimitConfig>,
+    /// Minimum time between two messages sent to the same chat, in milliseconds. Unset or 0 sends without spacing.
+    #[serde(default)]
+    pub outbound_min_interval_ms: Option<u64>,
+    /// Prefix commands answered without the model (e.g. `!help`, `!status`).
+    #[serde(default)]
+    pub commands: Vec<ChannelCommandConfig>,
+    /// When false, messages on this channel get no tools: none are offered to the model and any
+    /// tool call it makes anyway is refused. Default true.
+    #[serde(default = "default_true")]
+    pub tools_enabled: bool,
+    /// Provider for this channel's turns instead of the agent's (requires `model`).
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model for this channel's turns instead of the agent's.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Route this channel's messages to the agent running this role (overrides the channel's agent).
+    #[serde(default)]
+    pub role: Option<String>,
 }
 
 fn default_whatsapp_name() -> String {
@@ -380,6 +620,28 @@ pub struct IrcConfig {
     /// Agent to use for this channel (e.g. "main", "dev"). Default "main".
     #[serde(default = "default_channel_agent")]
     pub agent: String,
+    /// Inbound message size cap for this channel. Unset means no cap.
+    #[serde(default)]
+    pub inbound_limit: Option<InboundLimitConfig>,
+    /// Minimum time between two messages sent to the same chat, in milliseconds. Unset or 0 sends without spacing.
+    #[serde(default)]
+    pub outbound_min_interval_ms: Option<u64>,
+    /// Prefix commands answered without the model (e.g. `!help`, `!status`).
+    #[serde(default)]
+    pub commands: Vec<ChannelCommandConfig>,
+    /// When false, messages on this channel get no tools: none are offered to the model and any
+    /// tool call it makes anyway is refused. Default true.
+    #[serde(default = "default_true")]
+    pub tools_enabled: bool,
+    /// Provider for this channel's turns instead of the agent's (requires `model`).
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model for this channel's turns instead of the agent's.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Route this channel's messages to the agent running this role (overrides the channel's agent).
+    #[serde(default)]
+    pub role: Option<String>,
 }
 
 fn default_irc_name() -> String {
@@ -390,6 +652,190 @@ fn default_irc_port() -> u16 {
     6697
 }
 
+/// Generic webhook channel: other systems POST messages to `/webhook/<name>` on the web server
+/// and receive the bot's replies as signed POSTs to `outboundUrl`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookConfig {
+    /// Whether this webhook channel is enabled.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Unique channel name (default "webhook"); also the path segment of the inbound route.
+    #[serde(default = "default_webhook_name")]
+    pub name: String,
+    /// URL that replies are POSTed to as JSON.
+    #[serde(default)]
+    pub outbound_url: String,
+    /// Shared secret for the HMAC-SHA256 signature in `X-Synbot-Signature`: checked on inbound
+    /// requests and set on outbound ones.
+    #[serde(default)]
+    pub secret: String,
+    /// Where the inbound JSON body keeps the message fields.
+    #[serde(default)]
+    pub mapping: WebhookMappingConfig,
+    /// Agent to use for this channel (e.g. "main", "dev"). Default "main".
+    #[serde(default = "default_channel_agent")]
+    pub default_agent: String,
+    /// Whether to POST tool progress messages too. Default false.
+    #[serde(default)]
+    pub show_tool_calls: bool,
+    /// Inbound message size cap for this channel. Unset means no cap.
+    #[serde(default)]
+    pub inbound_limit: Option<InboundLimitConfig>,
+    /// Minimum time between two messages sent to the same chat, in milliseconds. Unset or 0 sends without spacing.
+    #[serde(default)]
+    pub outbound_min_interval_ms: Option<u64>,
+    /// Prefix commands answered without the model (e.g. `!help`, `!status`).
+    #[serde(default)]
+    pub commands: Vec<ChannelCommandConfig>,
+    /// When false, messages on this channel get no tools: none are offered to the model and any
+    /// tool call it makes anyway is refused. Default true.
+    #[serde(default = "default_true")]
+    pub tools_enabled: bool,
+    /// Provider for this channel's turns instead of the agent's (requires `model`).
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model for this channel's turns instead of the agent's.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Route this channel's messages to the agent running this role (overrides the channel's agent).
+    #[serde(default)]
+    pub role: Option<String>,
+}
+
+fn default_webhook_name() -> String {
+    "webhook".into()
+}
+
+/// JSON pointers (RFC 6901) into an inbound webhook body. `content` and `chatId` must resolve;
+/// a missing `senderId` falls back to the chat id.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookMappingConfig {
+    #[serde(default = "default_webhook_content_pointer")]
+    pub content: String,
+    #[serde(default = "default_webhook_chat_id_pointer")]
+    pub chat_id: String,
+    #[serde(default = "default_webhook_sender_id_pointer")]
+    pub sender_id: String,
+}
+
+impl Default for WebhookMappingConfig {
+    fn default() -> Self {
+        Self {
+            content: default_webhook_content_pointer(),
+            chat_id: default_webhook_chat_id_pointer(),
+            sender_id: default_webhook_sender_id_pointer(),
+        }
+    }
+}
+
+fn default_webhook_content_pointer() -> String {
+    "/content".into()
+}
+
+fn default_webhook_chat_id_pointer() -> String {
+    "/chatId".into()
+}
+
+fn default_webhook_sender_id_pointer() -> String {
+    "/senderId".into()
+}
+
+/// What to do with an inbound message longer than [`InboundLimitConfig::max_chars`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum InboundOverflowPolicy {
+    /// Keep the first `maxChars` characters and append a note saying the rest was dropped.
+    #[default]
+    Truncate,
+    /// Drop the message and reply asking the user to trim it or attach a file.
+    Reject,
+}
+
+/// Action of a channel command.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ChannelCommandAction {
+    /// Reply with fixed `text`.
+    Reply { text: String },
+    /// Reply with a short health summary (version, running turns).
+    Health,
+    /// Cancel the chat's running turn, like `/stop`.
+    Cancel,
+}
+
+/// Prefix command handled before the model (`commands` on any channel entry). A message matches
+/// when it starts with `prefix` (case-insensitive) followed by whitespace or nothing.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelCommandConfig {
+    pub prefix: String,
+    #[serde(flatten)]
+    pub action: ChannelCommandAction,
+}
+
+impl ChannelCommandConfig {
+    /// True when `content` invokes this command.
+    pub fn matches(&self, content: &str) -> bool {
+        let prefix = self.prefix.trim();
+        let content = content.trim_start();
+        if prefix.is_empty() {
+            return false;
+        }
+        match content.get(..prefix.len()) {
+            Some(head) if head.eq_ignore_ascii_case(prefix) => {
+                let rest = &content[prefix.len()..];
+                rest.is_empty() || rest.starts_with(char::is_whitespace)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Where a group message must mention the bot for it to trigger the agent.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum MentionMode {
+    /// The message must start with the mention (default).
+    #[default]
+    Prefix,
+    /// The mention may appear anywhere, e.g. "hey @bot do X".
+    Anywhere,
+}
+
+/// Per-channel cap on inbound message content (`inboundLimit` on any channel entry).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InboundLimitConfig {
+    /// Maximum number of characters accepted per message. Default 16000.
+    #[serde(default = "default_inbound_max_chars")]
+    pub max_chars: usize,
+    /// `truncate` (default) or `reject`.
+    #[serde(default)]
+    pub policy: InboundOverflowPolicy,
+}
+
+fn default_inbound_max_chars() -> usize {
+    16_000
+}
+
+impl Default for InboundLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_chars: default_inbound_max_chars(),
+            policy: InboundOverflowPolicy::default(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
@@ -415,6 +861,87 @@ pub struct ChannelsConfig {
     /// IRC channels.
     #[serde(default)]
     pub irc: Option<Vec<IrcConfig>>,
+    /// Generic webhook channels.
+    #[serde(default)]
+    pub webhook: Vec<WebhookConfig>,
+    #[serde(skip)]
+    pub settings_index: ChannelSettingsIndex,
+}
+
+/// Fields every channel entry has, read from the entry's serialized config (see
+/// [`ChannelsConfig::channel_entries`]).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelEntrySettings {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub outbound_min_interval_ms: Option<u64>,
+    #[serde(default)]
+    pub inbound_limit: Option<InboundLimitConfig>,
+    #[serde(default = "default_true")]
+    pub tools_enabled: bool,
+    #[serde(default)]
+    pub commands: Vec<ChannelCommandConfig>,
+    #[serde(flatten)]
+    pub binding: ChannelModelBinding,
+}
+
+/// [`ChannelEntrySettings`] of every channel entry by `name`, built on the first lookup and
+/// carried along when the config is cloned. Not part of the config file; a config whose
+/// channels change is loaded or assigned as a whole, which starts with an empty index.
+#[derive(Clone, Default)]
+pub struct ChannelSettingsIndex(OnceLock<Arc<HashMap<String, ChannelEntrySettings>>>);
+
+impl std::fmt::Debug for ChannelSettingsIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ChannelSettingsIndex")
+    }
+}
+
+/// The `provider`, `model` and `role` fields of a channel entry: which agent and model handle
+/// its turns instead of the defaults.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ChannelModelBinding {
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub role: Option<String>,
+}
+
+/// Built-in channel types under `channels`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelKind {
+    Telegram,
+    Discord,
+    Feishu,
+    Slack,
+    Email,
+    Matrix,
+    DingTalk,
+    WhatsApp,
+    Irc,
+    Webhook,
+}
+
+impl ChannelKind {
+    /// Config key and channel registry type name (as in [`ChannelsConfig::channel_entries`]).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChannelKind::Telegram => "telegram",
+            ChannelKind::Discord => "discord",
+            ChannelKind::Feishu => "feishu",
+            ChannelKind::Slack => "slack",
+            ChannelKind::Email => "email",
+            ChannelKind::Matrix => "matrix",
+            ChannelKind::DingTalk => "dingtalk",
+            ChannelKind::WhatsApp => "whatsapp",
+            ChannelKind::Irc => "irc",
+            ChannelKind::Webhook => "webhook",
+        }
+    }
 }
 
 impl ChannelsConfig {
@@ -496,8 +1023,52 @@ impl ChannelsConfig {
                 out.push(("irc".to_string(), irc));
             }
         }
+        let webhook: Vec<serde_json::Value> = self
+            .webhook
+            .iter()
+            .map(|c| serde_json::to_value(c).unwrap_or_default())
+            .collect();
+        if !webhook.is_empty() {
+            out.push(("webhook".to_string(), webhook));
+        }
         out
     }
+
+    /// Settings of the channel instance whose `name` is `channel` (the first entry with that name).
+    pub fn settings(&self, channel: &str) -> Option<&ChannelEntrySettings> {
+        let index = self.settings_index.0.get_or_init(|| {
+            let mut index = HashMap::new();
+            for entry in self.channel_entries().into_iter().flat_map(|(_, entries)| entries) {
+                let settings: ChannelEntrySettings = serde_json::from_value(entry).unwrap_or_default();
+                if !settings.name.is_empty() {
+                    index.entry(settings.name.clone()).or_insert(settings);
+                }
+            }
+            Arc::new(index)
+        });
+        index.get(channel)
+    }
+
+    /// Inbound size cap for the channel instance whose `name` is `channel`, if one is configured.
+    pub fn inbound_limit(&self, channel: &str) -> Option<InboundLimitConfig> {
+        self.settings(channel).and_then(|s| s.inbound_limit.clone())
+    }
+
+    /// Whether the channel instance whose `name` is `channel` allows tools (true when unknown).
+    pub fn tools_enabled(&self, channel: &str) -> bool {
+        self.settings(channel).is_none_or(|s| s.tools_enabled)
+    }
+
+    /// Provider / model / role overrides of the channel instance whose `name` is `channel`
+    /// (all unset when unknown).
+    pub fn model_binding(&self, channel: &str) -> ChannelModelBinding {
+        self.settings(channel).map(|s| s.binding.clone()).unwrap_or_default()
+    }
+
+    /// Prefix commands of the channel instance whose `name` is `channel`, in config order.
+    pub fn commands(&self, channel: &str) -> Vec<ChannelCommandConfig> {
+        self.settings(channel).map(|s| s.commands.clone()).unwrap_or_default()
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -511,8 +1082,10 @@ impl ChannelsConfig {
 pub enum ProviderApiStyle {
     /// OpenAI Chat Completions–compatible (`/v1/chat/completions` style).
     #[default]
+    #[serde(alias = "openai_compatible")]
     Openai,
     /// Anthropic Messages API–compatible (same paths/headers as api.anthropic.com).
+    #[serde(alias = "anthropic_compatible")]
     Anthropic,
 }
 
@@ -525,12 +1098,17 @@ pub struct ProviderEntry {
     pub api_base: Option<String>,
     /// For `providers.extra` entries: choose OpenAI-compatible vs Anthropic-compatible HTTP API.
     /// Ignored for built-in provider keys (`anthropic`, `openai`, etc.); those are fixed by name.
-    #[serde(default)]
+    /// Also accepted as `kind` (`openai_compatible` / `anthropic_compatible`).
+    #[serde(default, alias = "kind")]
     pub api_style: ProviderApiStyle,
     /// When set, caps per-request completion `max_tokens` for this provider (after `mainAgent.maxTokens` / agent override).
     /// Use for Anthropic-compatible gateways that enforce a lower output limit (e.g. MiniMax ≤ 196608).
     #[serde(default)]
     pub max_tokens_cap: Option<u32>,
+    /// How tool results are passed back to this provider's models (`text`, `json` or `typed`).
+    /// Unset = `text`. An agent's `toolResultFormat` takes precedence.
+    #[serde(default)]
+    pub tool_result_format: Option<ToolResultFormat>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -554,9 +1132,43 @@ pub struct ProvidersConfig {
     pub kimi_code: ProviderEntry,
     #[serde(default)]
     pub ollama: ProviderEntry,
-    /// Extra provider entries for plugin-registered providers. Key = provider name as used in config (e.g. mainAgent.provider).
+    /// Custom providers (also accepted as `custom`, see [`deserialize_providers`]) and entries
+    /// for plugin-registered providers. Key = provider name as used in config (e.g. mainAgent.provider).
     #[serde(default)]
     pub extra: std::collections::HashMap<String, ProviderEntry>,
+    /// Log every provider request and response at debug level, with secrets masked.
+    /// Off by default: prompts and replies contain user conversations.
+    #[serde(default)]
+    pub debug_log: bool,
+    /// Cap each logged request/response to this many characters (0 = log in full).
+    #[serde(default)]
+    pub debug_log_max_chars: usize,
+    /// Stream responses from the DeepSeek, Kimi Code and OpenRouter providers. If the connection
+    /// drops mid-answer, the text received so far is kept and marked as truncated.
+    #[serde(default)]
+    pub stream: bool,
+}
+
+/// Deserialize `providers`, merging the entries under `custom` into `extra` (an `extra` entry
+/// wins when both define a name). A serde alias would reject configs that set both keys.
+fn deserialize_providers<'de, D>(deserializer: D) -> std::result::Result<ProvidersConfig, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let mut value = serde_json::Value::deserialize(deserializer)?;
+    if let Some(obj) = value.as_object_mut() {
+        if let Some(serde_json::Value::Object(custom)) = obj.remove("custom") {
+            let extra = obj
+                .entry("extra")
+                .or_insert_with(|| serde_json::Value::Object(Default::default()));
+            if let Some(extra) = extra.as_object_mut() {
+                for (name, entry) in custom {
+                    extra.entry(name).or_insert(entry);
+                }
+            }
+        }
+    }
+    serde_json::from_value(value).map_err(serde::de::Error::custom)
 }
 
 /// Resolve API key and base URL for the given provider name (same as chat/completion).
@@ -631,9 +1243,8 @@ pub fn resolve_provider(cfg: &Config, provider_name: &str) -> (String, Option<St
     (key, base)
 }
 
-/// Optional per-provider ceiling for completion `max_tokens` (see [`ProviderEntry::max_tokens_cap`]).
-/// Aligns provider name resolution with [`resolve_provider`] (extra entry first, then built-in blocks).
-pub fn resolve_provider_max_tokens_cap(providers: &ProvidersConfig, provider_name: &str) -> Option<u32> {
+/// Config entry for a provider name, resolved like [`resolve_provider`] (extra entry first, then built-in blocks).
+fn provider_entry_for<'a>(providers: &'a ProvidersConfig, provider_name: &str) -> Option<&'a ProviderEntry> {
     let trimmed = provider_name.trim();
     let lower = trimmed.to_lowercase();
 
@@ -642,30 +1253,43 @@ pub fn resolve_provider_max_tokens_cap(providers: &ProvidersConfig, provider_nam
         .get(trimmed)
         .or_else(|| providers.extra.get(&lower))
     {
-        return e.max_tokens_cap;
+        return Some(e);
     }
 
     if lower.contains("openrouter") {
-        providers.openrouter.max_tokens_cap
+        Some(&providers.openrouter)
     } else if lower.contains("anthropic") || lower.contains("claude") {
-        providers.anthropic.max_tokens_cap
+        Some(&providers.anthropic)
     } else if lower.contains("openai") {
-        providers.openai.max_tokens_cap
+        Some(&providers.openai)
     } else if lower.contains("gemini") {
-        providers.gemini.max_tokens_cap
+        Some(&providers.gemini)
     } else if lower.contains("deepseek") {
-        providers.deepseek.max_tokens_cap
+        Some(&providers.deepseek)
     } else if lower.contains("moonshot") {
-        providers.moonshot.max_tokens_cap
+        Some(&providers.moonshot)
     } else if lower.contains("kimi") {
-        providers.kimi_code.max_tokens_cap
+        Some(&providers.kimi_code)
     } else if lower.contains("ollama") {
-        providers.ollama.max_tokens_cap
+        Some(&providers.ollama)
     } else {
         None
     }
 }
 
+/// Optional per-provider ceiling for completion `max_tokens` (see [`ProviderEntry::max_tokens_cap`]).
+pub fn resolve_provider_max_tokens_cap(providers: &ProvidersConfig, provider_name: &str) -> Option<u32> {
+    provider_entry_for(providers, provider_name).and_then(|e| e.max_tokens_cap)
+}
+
+/// Tool result shape configured for a provider (see [`ProviderEntry::tool_result_format`]).
+pub fn resolve_provider_tool_result_format(
+    providers: &ProvidersConfig,
+    provider_name: &str,
+) -> Option<ToolResultFormat> {
+    provider_entry_for(providers, provider_name).and_then(|e| e.tool_result_format)
+}
+
 // ---------------------------------------------------------------------------
 // Agent config (runtime entity that references one role)
 // ---------------------------------------------------------------------------
@@ -685,10 +1309,56 @@ pub struct AgentConfig {
     pub max_tokens: Option<u32>,
     pub temperature: Option<f32>,
     pub max_iterations: Option<u32>,
+    /// Overrides `mainAgent.reasoningEffort` for this agent.
+    #[serde(default)]
+    pub reasoning_effort: Option<ReasoningEffort>,
+    /// Overrides `mainAgent.thinkingBudget` for this agent.
+    #[serde(default)]
+    pub thinking_budget: Option<u32>,
     #[serde(default)]
     pub skills: Vec<String>,
     #[serde(default)]
     pub tools: Vec<String>,
+    /// Overrides the provider's `toolResultFormat` for this agent's model.
+    #[serde(default)]
+    pub tool_result_format: Option<ToolResultFormat>,
+    /// When false, this agent runs as pure chat: no tools are offered and tool calls are refused.
+    #[serde(default = "default_true")]
+    pub tools_enabled: bool,
+}
+
+/// Shape of a tool result in the tool-result message sent back to the model.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ToolResultFormat {
+    /// The tool's output as is; failures as `Error: <message>`.
+    #[default]
+    Text,
+    /// `{"result": "<output>"}`, or `{"error": "<message>"}` on failure.
+    Json,
+    /// `{"tool": "<name>", "ok": true, "output": "<output>"}`, or `"ok": false` with `"error"`.
+    Typed,
+}
+
+/// How hard a model with extended thinking should reason before answering.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    Low,
+    Medium,
+    High,
+}
+
+impl ReasoningEffort {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReasoningEffort::Low => "low",
+            ReasoningEffort::Medium => "medium",
+            ReasoningEffort::High => "high",
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -705,8 +1375,12 @@ fn default_agents() -> Vec<AgentConfig> {
         max_tokens: None,
         temperature: None,
         max_iterations: None,
+        reasoning_effort: None,
+        thinking_budget: None,
         skills: Vec::new(),
         tools: Vec::new(),
+        tool_result_format: None,
+        tools_enabled: true,
     }]
 }
 
@@ -732,11 +1406,38 @@ pub struct MainAgent {
     /// Maximum number of chat history messages to send to the model (most recent N). Default 20.
     #[serde(default = "default_max_chat_history_messages")]
     pub max_chat_history_messages: u32,
+    /// Stop a run when the model issues the same tool call (same name and arguments) this many times in a row.
+    /// The first repeat over the limit is answered with a nudge instead of running the tool. 0 disables. Default 3.
+    #[serde(default = "default_max_repeated_tool_calls")]
+    pub max_repeated_tool_calls: u32,
     #[serde(default = "default_max_concurrent_subagents")]
     pub max_concurrent_subagents: usize,
     /// Timeout in seconds for each subagent/directive task. When exceeded, the task is marked failed and the slot is freed. Default 600 (10 min).
     #[serde(default = "default_subagent_task_timeout_secs")]
     pub subagent_task_timeout_secs: u64,
+    /// Maximum number of agent turns (provider conversations) running at once across all sessions.
+    /// Turns beyond the limit wait for a free slot; turns within one session always run one at a time. Default 4.
+    #[serde(default = "default_max_concurrent_turns")]
+    pub max_concurrent_turns: usize,
+    /// Reasoning effort for models with extended thinking (`low`, `medium`, `high`); unset = provider default.
+    /// Sent to Anthropic, OpenAI and OpenRouter; ignored for other providers.
+    #[serde(default)]
+    pub reasoning_effort: Option<ReasoningEffort>,
+    /// Token budget for extended thinking (Anthropic `budget_tokens`, OpenRouter `reasoning.max_tokens`).
+    /// Takes precedence over `reasoningEffort` where the provider accepts a budget.
+    #[serde(default)]
+    pub thinking_budget: Option<u32>,
+    /// Post the model's reasoning to the channel before its answer. Default false: reasoning is only logged.
+    #[serde(default)]
+    pub show_reasoning: bool,
+    /// Role whose agent answers messages from channels without a `defaultAgent` (or naming an
+    /// unknown one). Must be "main" or the role of an agent in `agents`; unset = the main agent.
+    #[serde(default)]
+    pub default_role: Option<String>,
+    /// Role per detected message language, keyed by ISO 639-3 code (e.g. `cmn`, `spa`). Used when
+    /// the channel names no agent; languages not listed fall back to `defaultRole`.
+    #[serde(default)]
+    pub language_roles: HashMap<String, String>,
     #[serde(default = "default_agents")]
     pub agents: Vec<AgentConfig>,
 }
@@ -767,12 +1468,18 @@ fn default_max_consecutive_tool_errors() -> u32 {
 fn default_max_chat_history_messages() -> u32 {
     20
 }
+fn default_max_repeated_tool_calls() -> u32 {
+    3
+}
 fn default_max_concurrent_subagents() -> usize {
     5
 }
 fn default_subagent_task_timeout_secs() -> u64 {
     600
 }
+fn default_max_concurrent_turns() -> usize {
+    4
+}
 
 impl Default for MainAgent {
     fn default() -> Self {
@@ -785,8 +1492,15 @@ impl Default for MainAgent {
             max_tool_iterations: default_max_iterations(),
             max_consecutive_tool_errors: default_max_consecutive_tool_errors(),
             max_chat_history_messages: default_max_chat_history_messages(),
+            max_repeated_tool_calls: default_max_repeated_tool_calls(),
             max_concurrent_subagents: default_max_concurrent_subagents(),
             subagent_task_timeout_secs: default_subagent_task_timeout_secs(),
+            max_concurrent_turns: default_max_concurrent_turns(),
+            reasoning_effort: None,
+            thinking_budget: None,
+            show_reasoning: false,
+            default_role: None,
+            language_roles: HashMap::new(),
             agents: default_agents(),
         }
     }
@@ -815,6 +1529,27 @@ pub struct PermissionConfig {
     /// List of permission rules (matched in order).
     #[serde(default)]
     pub rules: Vec<PermissionRule>,
+    /// When set, approval requests are posted to this chat instead of the chat that ran the command.
+    #[serde(default)]
+    pub approval_route: Option<ApprovalRoute>,
+    /// When true (default), an approval that times out is denied; when false, it is approved.
+    #[serde(default = "default_true")]
+    pub deny_on_timeout: bool,
+}
+
+/// Approver destination for [`PermissionConfig::approval_route`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalRoute {
+    /// Channel name (e.g. "telegram", "admin-feishu").
+    pub channel: String,
+    /// Chat ID on that channel (e.g. the approver's direct-message chat or an admin group).
+    pub chat_id: String,
+    /// User ID of the only person who may answer routed requests. Defaults to `chatId`, which is
+    /// the user's ID for a Telegram direct chat; set it for Discord, Feishu and group chats.
+    #[serde(default)]
+    pub user_id: Option<String>,
 }
 
 fn default_permission_level() -> PermissionLevel {
@@ -832,6 +1567,8 @@ impl Default for PermissionConfig {
             default_level: default_permission_level(),
             approval_timeout_secs: default_approval_timeout(),
             rules: Vec::new(),
+            approval_route: None,
+            deny_on_timeout: true,
         }
     }
 }
@@ -852,6 +1589,10 @@ pub struct ExecToolConfig {
     pub deny_patterns: Vec<String>,
     #[serde(default)]
     pub allow_patterns: Option<Vec<String>>,
+    /// When true, deny/allow patterns match case-sensitively. Default false. Whitespace is
+    /// collapsed either way; prefix a pattern with `exact:` to match it verbatim.
+    #[serde(default)]
+    pub pattern_case_sensitive: bool,
     #[serde(default)]
     pub permissions: PermissionConfig,
 }
@@ -884,11 +1625,120 @@ impl Default for ExecToolConfig {
             restrict_to_workspace: false,
             deny_patterns: default_deny_patterns(),
             allow_patterns: None,
+            pattern_case_sensitive: false,
             permissions: PermissionConfig::default(),
         }
     }
 }
 
+impl ExecToolConfig {
+    /// `deny_patterns` with `preset:` entries expanded. Unknown presets are rejected by
+    /// [`validate_config`] at load; if one slips through it is kept as a literal pattern and
+    /// the other entries are still expanded.
+    pub fn expanded_deny_patterns(&self) -> Vec<String> {
+        expand_known_pattern_presets(&self.deny_patterns)
+    }
+
+    /// `allow_patterns` with `preset:` entries expanded (see [`Self::expanded_deny_patterns`]).
+    pub fn expanded_allow_patterns(&self) -> Option<Vec<String>> {
+        self.allow_patterns.as_deref().map(expand_known_pattern_presets)
+    }
+}
+
+/// [`expand_pattern_presets`] entry by entry, keeping an unknown preset as a literal pattern.
+fn expand_known_pattern_presets(patterns: &[String]) -> Vec<String> {
+    patterns
+        .iter()
+        .flat_map(|p| {
+            expand_pattern_presets(std::slice::from_ref(p)).unwrap_or_else(|_| vec![p.clone()])
+        })
+        .collect()
+}
+
+/// Prefix for named pattern sets in `denyPatterns` / `allowPatterns`, e.g. `"preset:destructive"`.
+pub const PATTERN_PRESET_PREFIX: &str = "preset:";
+
+/// Names accepted after [`PATTERN_PRESET_PREFIX`].
+pub const PATTERN_PRESETS: &[&str] = &["destructive", "network", "privilege"];
+
+/// Curated patterns for an exec pattern preset, or `None` for an unknown name.
+pub fn exec_pattern_preset(name: &str) -> Option<&'static [&'static str]> {
+    match name {
+        "destructive" => Some(&[
+            "rm -rf /",
+            "rm -rf ~",
+            "rm -rf *",
+            "rm -fr /",
+            "mkfs",
+            "dd if=",
+            "cmd:shred",
+            "cmd:wipefs",
+            "> /dev/sd",
+            "format c:",
+            "del /s /q",
+            "rd /s /q",
+            "cmd:shutdown",
+            "cmd:reboot",
+            "cmd:poweroff",
+            "cmd:halt",
+            ":(){",
+        ]),
+        "network" => Some(&[
+            "cmd:curl",
+            "cmd:wget",
+            "cmd:nc",
+            "cmd:ncat",
+            "cmd:netcat",
+            "cmd:socat",
+            "cmd:telnet",
+            "cmd:ssh",
+            "cmd:scp",
+            "cmd:sftp",
+            "cmd:ftp",
+            "cmd:rsync",
+            "invoke-webrequest",
+            "invoke-restmethod",
+        ]),
+        "privilege" => Some(&[
+            "cmd:sudo",
+            "cmd:su",
+            "cmd:doas",
+            "cmd:pkexec",
+            "cmd:runas",
+            "cmd:visudo",
+            "chmod 777",
+            "chmod +s",
+            "chmod u+s",
+            "chown root",
+            "cmd:setcap",
+            "cmd:passwd",
+        ]),
+        _ => None,
+    }
+}
+
+/// Replace every `preset:<name>` entry with that preset's patterns, keeping other entries
+/// in place. Errors on the first unknown preset.
+pub fn expand_pattern_presets(patterns: &[String]) -> std::result::Result<Vec<String>, String> {
+    let mut out = Vec::with_capacity(patterns.len());
+    for pattern in patterns {
+        match pattern.strip_prefix(PATTERN_PRESET_PREFIX) {
+            Some(name) => {
+                let preset = exec_pattern_preset(name.trim()).ok_or_else(|| {
+                    format!(
+                        "unknown preset {:?}; expected one of: {}",
+                        name,
+                        PATTERN_PRESETS.join(", ")
+                    )
+                })?;
+                out.extend(preset.iter().map(|p| p.to_string()));
+            }
+            None => out.push(pattern.clone()),
+        }
+    }
+    Ok(out)
+}
+
 // ---------------------------------------------------------------------------
 // Web tool config
 // ---------------------------------------------------------------------------
@@ -911,7 +1761,7 @@ pub enum WebSearchBackend {
     Firecrawl,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct WebToolConfig {
@@ -938,6 +1788,31 @@ pub struct WebToolConfig {
     /// Maximum results to return (default 5).
     #[serde(default = "default_search_count")]
     pub search_count: u32,
+
+    /// When the DuckDuckGo HTML endpoint fails or returns markup we cannot parse, retry once
+    /// against the DuckDuckGo lite endpoint. Default true.
+    #[serde(default = "default_true")]
+    pub duck_duck_go_lite_fallback: bool,
+
+    /// Hosts `web_fetch` may fetch, redirects included: domains (subdomains match too), IPs or
+    /// `[ipv6]`. IPv4-mapped IPv6 addresses match their IPv4 entry. Empty (default) allows any host.
+    #[serde(default)]
+    pub fetch_allowed_hosts: Vec<String>,
+}
+
+impl Default for WebToolConfig {
+    fn default() -> Self {
+        Self {
+            brave_api_key: String::new(),
+            tavily_api_key: String::new(),
+            search_backend: WebSearchBackend::default(),
+            searxng_url: String::new(),
+            firecrawl_api_key: String::new(),
+            search_count: default_search_count(),
+            duck_duck_go_lite_fallback: true,
+            fetch_allowed_hosts: Vec::new(),
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -1061,6 +1936,40 @@ impl Default for LogConfig {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Telemetry config
+// ---------------------------------------------------------------------------
+
+/// Opt-in usage telemetry. Only anonymous event counters are ever emitted (see `crate::telemetry`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryConfig {
+    /// Enable telemetry. Default false; nothing is counted or sent unless this is true.
+    #[serde(default)]
+    pub enabled: bool,
+    /// HTTP endpoint that receives counter snapshots as JSON. Empty: write snapshots to the local log only.
+    #[serde(default)]
+    pub endpoint: String,
+    /// Seconds between snapshots (minimum 60). Default 3600.
+    #[serde(default = "default_telemetry_interval_secs")]
+    pub interval_secs: u64,
+}
+
+fn default_telemetry_interval_secs() -> u64 {
+    3600
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: String::new(),
+            interval_secs: default_telemetry_interval_secs(),
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Web server config
 // ---------------------------------------------------------------------------
@@ -1154,6 +2063,8 @@ impl Default for MemoryCompressionConfig {
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct MemoryConfig {
+    /// Where memory is stored: empty or `file` = `MEMORY.md` + daily notes with a SQLite index;
+    /// `sqlite` = entries only in `store.sqlite` (see `agent::memory_backend::SqliteMemoryBackend`).
     #[serde(default)]
     pub backend: String,
     /// Provider **name** for embeddings only (independent of chat `mainAgent.provider`). Same keys as elsewhere: `ollama`, `openai`, `deepseek`, or `providers.extra` — credentials via [`resolve_provider`]. `none` or empty: stub vectors (FTS5 still works).
@@ -1418,7 +2329,7 @@ pub struct McpConfig {
     pub servers: Vec<McpServerConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ToolsConfig {
@@ -1434,6 +2345,31 @@ pub struct ToolsConfig {
     /// MCP servers to connect; their tools are registered as synbot tools.
     #[serde(default)]
     pub mcp: Option<McpConfig>,
+    /// Validate tool-call arguments against each tool's parameters schema before running it. Default true.
+    #[serde(default = "default_true")]
+    pub validate_args: bool,
+    /// Tool descriptions longer than this (in characters) are reported at startup and by
+    /// `synbot doctor`; every description is sent with each request. 0 turns the check off. Default 1024.
+    #[serde(default = "default_max_tool_description_chars")]
+    pub max_description_chars: usize,
+}
+
+fn default_max_tool_description_chars() -> usize {
+    crate::tools::DEFAULT_MAX_TOOL_DESCRIPTION_CHARS
+}
+
+impl Default for ToolsConfig {
+    fn default() -> Self {
+        Self {
+            exec: ExecToolConfig::default(),
+            web: WebToolConfig::default(),
+            browser: BrowserToolConfig::default(),
+            generation: GenerationConfig::default(),
+            mcp: None,
+            validate_args: true,
+            max_description_chars: default_max_tool_description_chars(),
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -1450,9 +2386,49 @@ pub struct HeartbeatTask {
     /// Conversation id (group id or user id for DM) where to send the result.
     pub chat_id: String,
     /// User id of the task creator (for display / reply_to).
+    #[serde(default)]
     pub user_id: String,
-    /// Task content to execute (e.g. "list files in current directory").
+    /// Task content to execute (e.g. "list files in current directory"); unused by `health_report`.
+    #[serde(default)]
     pub target: String,
+    /// What the task does: run `target` through the agent (default) or post a health report.
+    #[serde(default, rename = "type")]
+    pub kind: HeartbeatTaskKind,
+    /// What to do when the task's run fails: "post" (default) the error to this chat,
+    /// "silent" (log only), or "system" (log at error level and alert `heartbeat.alertChatId`).
+    #[serde(default)]
+    pub on_error: HeartbeatOnError,
+    /// Run the task as a turn of the agent with this role (its prompt, tools and model settings)
+    /// instead of the chat's default agent. Must be "main" or the role of an agent in `mainAgent.agents`.
+    #[serde(default)]
+    pub role: Option<String>,
+}
+
+/// Kind of a heartbeat task.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum HeartbeatTaskKind {
+    /// Send `target` to the agent and post its answer.
+    #[default]
+    Agent,
+    /// Post a status summary (channels, pending approvals, sandboxes, recent errors) without
+    /// involving the agent. See [`crate::heartbeat::HealthReport`].
+    HealthReport,
+}
+
+/// Failure behavior for a heartbeat task.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum HeartbeatOnError {
+    /// Post the error to the task's channel/chat.
+    #[default]
+    Post,
+    /// Log the error only.
+    Silent,
+    /// Log the error at error level and send it to the heartbeat's alert chat, if configured.
+    System,
 }
 
 /// Heartbeat: periodic execution of tasks from config, results sent to configured channel/user.
@@ -1467,6 +2443,15 @@ pub struct HeartbeatConfig {
     pub interval: u64,
     #[serde(default)]
     pub tasks: Vec<HeartbeatTask>,
+    /// Size cap for task results posted to the channel.
+    #[serde(default)]
+    pub result: ScheduledResultConfig,
+    /// Channel of the admin chat told about failed tasks with `onError: "system"`; with `alertChatId`.
+    #[serde(default)]
+    pub alert_channel: Option<String>,
+    /// Chat id on `alertChannel` for those failures. Without both, they are only logged.
+    #[serde(default)]
+    pub alert_chat_id: Option<String>,
 }
 
 fn default_heartbeat_interval() -> u64 {
@@ -1479,6 +2464,36 @@ impl Default for HeartbeatConfig {
             enabled: true,
             interval: default_heartbeat_interval(),
             tasks: Vec::new(),
+            result: ScheduledResultConfig::default(),
+            alert_channel: None,
+            alert_chat_id: None,
+        }
+    }
+}
+
+/// Size cap for cron / heartbeat results, applied before the result is posted to a channel.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledResultConfig {
+    /// Maximum result size in bytes (default 8192). Longer results are truncated with a note.
+    #[serde(default = "default_scheduled_result_max_bytes")]
+    pub max_bytes: usize,
+    /// When a result is truncated, also attach the full output as a file on channels that
+    /// support attachments (default false).
+    #[serde(default)]
+    pub attach_full_output: bool,
+}
+
+fn default_scheduled_result_max_bytes() -> usize {
+    8 * 1024
+}
+
+impl Default for ScheduledResultConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: default_scheduled_result_max_bytes(),
+            attach_full_output: false,
         }
     }
 }
@@ -1509,6 +2524,44 @@ pub struct CronTaskConfig {
     /// Conversation id where to send result (defaults to user_id for DM).
     #[serde(default)]
     pub chat_id: Option<String>,
+    /// What to do on startup about runs missed while the daemon was down: "skip" (default),
+    /// "run_once" (one catch-up run), or "run_all" (one run per missed occurrence).
+    #[serde(default)]
+    pub misfire_policy: MisfirePolicy,
+    /// What to do when the task fires while its previous run is still in flight: "skip" (default)
+    /// drops the new fire, "queue" runs it after the current run finishes.
+    #[serde(default)]
+    pub overlap_policy: CronOverlapPolicy,
+    /// Run the task as a turn of the agent with this role instead of the chat's default agent.
+    /// Must be "main" or the role of an agent in `mainAgent.agents`.
+    #[serde(default)]
+    pub role: Option<String>,
+}
+
+/// Handling of a cron fire that arrives while the task's previous run is still in flight.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CronOverlapPolicy {
+    /// Drop the new fire and log it.
+    #[default]
+    Skip,
+    /// Run the new fire once the current run finishes.
+    Queue,
+}
+
+/// Catch-up behavior for cron runs missed while the daemon was not running.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum MisfirePolicy {
+    /// Drop missed runs; continue with the next scheduled time.
+    #[default]
+    Skip,
+    /// Run once on startup if at least one run was missed.
+    RunOnce,
+    /// Run once on startup for every missed occurrence (capped).
+    RunAll,
 }
 
 /// Cron config: array of cron tasks (schedule, command, channel, userId).
@@ -1518,6 +2571,9 @@ pub struct CronTaskConfig {
 pub struct CronConfig {
     #[serde(default)]
     pub tasks: Vec<CronTaskConfig>,
+    /// Size cap for task results posted to the channel.
+    #[serde(default)]
+    pub result: ScheduledResultConfig,
 }
 
 /// TurboWorkflow config: persistent resumable workflows.
@@ -1575,6 +2631,9 @@ pub struct SandboxNetworkConfig {
     pub allowed_hosts: Vec<String>,
     #[serde(default)]
     pub allowed_ports: Vec<u16>,
+    /// Share the host network (Docker backends). Rejected unless `allowInsecure` is set on the sandbox.
+    #[serde(default)]
+    pub host_network: bool,
 }
 
 /// Resource limits; max_memory and max_disk can be "2G", "512M", or bytes number.
@@ -1667,6 +2726,37 @@ pub struct AppSandboxConfig {
     /// config root directory (`~/.synbot`, or `--root-dir`), not the whole home folder.
     #[serde(default)]
     pub work_dir: Option<String>,
+    /// Accept settings that let the sandbox reach the host (writable `/`, `/dev` mounts, host network).
+    #[serde(default)]
+    pub allow_insecure: bool,
+    /// Block privilege gain via setuid binaries inside the sandbox (prctl no_new_privs on nono,
+    /// `--security-opt no-new-privileges` on Docker). Default true.
+    #[serde(default)]
+    pub no_new_privileges: Option<bool>,
+    /// When true, remove what a previous run left behind (the Windows AppContainer profile) and
+    /// create the sandbox fresh on each start. `synbot sandbox --recreate` sets it for one launch.
+    #[serde(default)]
+    pub delete_on_start: Option<bool>,
+    /// What `synbot sandbox` does when its child exits: "never" (default), "on-failure" or "always".
+    #[serde(default)]
+    pub restart_policy: SandboxRestartPolicy,
+    /// Restarts allowed by `restartPolicy` before `synbot sandbox` exits with the child's code. Default 5.
+    #[serde(default)]
+    pub max_restarts: Option<u32>,
+}
+
+/// Restart behavior of the app sandbox child (`appSandbox.restartPolicy`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum SandboxRestartPolicy {
+    /// Exit with the child's exit code.
+    #[default]
+    Never,
+    /// Start the child again after a non-zero exit.
+    OnFailure,
+    /// Start the child again after any exit.
+    Always,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -1692,6 +2782,39 @@ pub struct ToolSandboxConfig {
     pub resources: Option<SandboxResourceConfig>,
     #[serde(default)]
     pub process: Option<SandboxProcessConfig>,
+    /// Accept settings that let the sandbox reach the host (writable `/`, `/dev` mounts, host network).
+    #[serde(default)]
+    pub allow_insecure: bool,
+    /// Block privilege gain via setuid binaries inside the sandbox (prctl no_new_privs on nono,
+    /// `--security-opt no-new-privileges` on Docker). Default true.
+    #[serde(default)]
+    pub no_new_privileges: Option<bool>,
+    /// Run the container with a read-only root filesystem plus a tmpfs at `/tmp`; writable paths
+    /// and the workspace stay mounted rw. Docker backends only. Default true.
+    #[serde(default)]
+    pub readonly_rootfs: Option<bool>,
+    /// Linux capabilities to add back after `--cap-drop ALL` (e.g. "NET_BIND_SERVICE"). Names are
+    /// validated at sandbox creation; SYS_ADMIN and similar require allowInsecure. Docker backends only.
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    /// Number of tool sandbox containers that execs are spread across. The first one is named
+    /// `sandboxName`, the others `<sandboxName>-1`, `<sandboxName>-2`, ...; extra ones are created
+    /// on first use. Default 1.
+    #[serde(default)]
+    pub pool_size: Option<u32>,
+    /// "shared" (default): all sessions use the same tool sandbox. "per_session": each chat session
+    /// gets its own tool sandbox, created on its first exec and removed after `toolSandboxIdleSecs`
+    /// without executions (30 minutes when that is 0). `poolSize` applies to shared mode only.
+    #[serde(default)]
+    pub isolation: crate::sandbox::types::ToolSandboxIsolation,
+    /// Container path where the workspace is bind-mounted and exec runs (Docker backends).
+    /// Default "/workspace".
+    #[serde(default)]
+    pub workspace_mount_path: Option<String>,
+    /// Run the container as the host user's uid:gid so files created in the workspace mount are
+    /// owned by that user on the host (Unix hosts, Docker backends). Default true.
+    #[serde(default)]
+    pub map_host_user: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -1716,6 +2839,61 @@ pub struct SandboxMonitoringConfig {
     pub log_level: Option<String>,
     #[serde(default)]
     pub log_output: Vec<SandboxLogOutputConfig>,
+    /// Stop a sandbox after this many security violations within `violationWindowSecs`
+    /// (default 0 = never stop; violations are only recorded).
+    #[serde(default)]
+    pub violation_threshold: Option<u32>,
+    /// Window in seconds for `violationThreshold` (default 60).
+    #[serde(default)]
+    pub violation_window_secs: Option<u64>,
+    /// Channel of the chat told when a sandbox is stopped for violations; with `alertChatId`.
+    #[serde(default)]
+    pub alert_channel: Option<String>,
+    /// Chat id on `alertChannel` for violation alerts. Without both, stops are only logged.
+    #[serde(default)]
+    pub alert_chat_id: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Response transforms
+// ---------------------------------------------------------------------------
+
+/// One step of the reply post-processing pipeline, selected by `type`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseTransformConfig {
+    /// Append `text` on its own line after the reply (e.g. a disclaimer).
+    AppendFooter { text: String },
+    /// Replace every match of the regex `pattern` with `replacement` (default: remove it).
+    /// `replacement` may use `$1` / `${name}` capture references.
+    StripRegex {
+        pattern: String,
+        #[serde(default)]
+        replacement: String,
+    },
+}
+
+/// Transforms applied to every outbound chat message before it reaches the channel: `global`
+/// first, then the list under the channel's name in `channels`. History keeps the raw text.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseTransformsConfig {
+    #[serde(default)]
+    pub global: Vec<ResponseTransformConfig>,
+    /// Channel name (e.g. "telegram", "web") to its transforms.
+    #[serde(default)]
+    pub channels: std::collections::HashMap<String, Vec<ResponseTransformConfig>>,
+}
+
+impl ResponseTransformsConfig {
+    /// Transforms for `channel`, in the order they run.
+    pub fn for_channel<'a>(&'a self, channel: &str) -> impl Iterator<Item = &'a ResponseTransformConfig> {
+        self.global
+            .iter()
+            .chain(self.channels.get(channel).into_iter().flatten())
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -1742,7 +2920,7 @@ pub struct Config {
     pub tool_result_preview_chars: u32,
     #[serde(default)]
     pub channels: ChannelsConfig,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "deserialize_providers")]
     pub providers: ProvidersConfig,
     #[serde(default, rename = "mainAgent")]
     pub main_agent: MainAgent,
@@ -1754,6 +2932,9 @@ pub struct Config {
     pub web: WebConfig,
     #[serde(default)]
     pub log: LogConfig,
+    /// Opt-in anonymous usage counters (disabled by default).
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
     #[serde(default)]
     pub main_channel: String,
     /// Heartbeat: periodic tasks (enabled, interval, tasks with channel/userId/target).
@@ -1771,6 +2952,14 @@ pub struct Config {
     /// Optional tool sandbox (exec and other tools run inside this).
     #[serde(default)]
     pub tool_sandbox: Option<ToolSandboxConfig>,
+    /// Stop the tool sandbox after this many seconds without executions; it is started on the first
+    /// tool call and restarted transparently on the next one. 0 (default) keeps it running.
+    #[serde(default)]
+    pub tool_sandbox_idle_secs: u64,
+    /// Upper bounds that app/tool sandbox configs are validated against (memory, CPU, disk, processes).
+    /// When omitted, built-in defaults apply.
+    #[serde(default)]
+    pub sandbox_resource_limits: Option<crate::sandbox::ResourceLimits>,
     /// Optional sandbox monitoring (log_level, log_output for sandbox audit).
     #[serde(default)]
     pub sandbox_monitoring: Option<SandboxMonitoringConfig>,
@@ -1780,9 +2969,43 @@ pub struct Config {
     /// Pairing approvals: supplement allowlist; matched by channel provider + MD5(chat_id) prefix.
     #[serde(default)]
     pub pairings: Vec<PairingEntry>,
+    /// Cross-channel identities: DMs from any member of a link continue one shared session.
+    #[serde(default)]
+    pub identity_links: Vec<IdentityLinkConfig>,
     /// Config file format version. Used by ConfigMigrator to apply incremental migrations.
     #[serde(default = "default_config_version")]
     pub config_version: u32,
+    /// Seed for reproducible runs (retry jitter, temperature 0, provider `seed`). `SYNBOT_SEED` overrides it.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Post-processing of final agent replies (footers, redaction), globally or per channel.
+    #[serde(default)]
+    pub response_transforms: ResponseTransformsConfig,
+}
+
+impl Config {
+    /// Names and kinds of the enabled channel instances with a non-empty name, in config order
+    /// (telegram, discord, feishu, slack, email, matrix, dingtalk, whatsapp, irc, webhook).
+    pub fn enabled_channels(&self) -> Vec<(&str, ChannelKind)> {
+        let ch = &self.channels;
+        let entries = ch
+            .telegram
+            .iter()
+            .map(|c| (c.enabled, c.name.as_str(), ChannelKind::Telegram))
+            .chain(ch.discord.iter().map(|c| (c.enabled, c.name.as_str(), ChannelKind::Discord)))
+            .chain(ch.feishu.iter().map(|c| (c.enabled, c.name.as_str(), ChannelKind::Feishu)))
+            .chain(ch.slack.iter().map(|c| (c.enabled, c.name.as_str(), ChannelKind::Slack)))
+            .chain(ch.email.iter().map(|c| (c.enabled, c.name.as_str(), ChannelKind::Email)))
+            .chain(ch.matrix.iter().map(|c| (c.enabled, c.name.as_str(), ChannelKind::Matrix)))
+            .chain(ch.dingtalk.iter().map(|c| (c.enabled, c.name.as_str(), ChannelKind::DingTalk)))
+            .chain(ch.whatsapp.iter().flatten().map(|c| (c.enabled, c.name.as_str(), ChannelKind::WhatsApp)))
+            .chain(ch.irc.iter().flatten().map(|c| (c.enabled, c.name.as_str(), ChannelKind::Irc)))
+            .chain(ch.webhook.iter().map(|c| (c.enabled, c.name.as_str(), ChannelKind::Webhook)));
+        entries
+            .filter(|(enabled, name, _)| *enabled && !name.is_empty())
+            .map(|(_, name, kind)| (name, kind))
+            .collect()
+    }
 }
 
 fn default_config_version() -> u32 {
@@ -1902,6 +3125,23 @@ fn build_sandbox_monitoring(mon: &Option<SandboxMonitoringConfig>) -> crate::san
     }
 }
 
+/// Violation threshold from `sandboxMonitoring`, falling back to the defaults.
+pub fn sandbox_violation_threshold(
+    mon: &Option<SandboxMonitoringConfig>,
+) -> crate::sandbox::ViolationThreshold {
+    let default = crate::sandbox::ViolationThreshold::default();
+    let Some(m) = mon else {
+        return default;
+    };
+    crate::sandbox::ViolationThreshold {
+        max_violations: m.violation_threshold.unwrap_or(default.max_violations),
+        window: m
+            .violation_window_secs
+            .map(std::time::Duration::from_secs)
+            .unwrap_or(default.window),
+    }
+}
+
 /// Merge dirs the main synbot process always needs (config, workspace, logs, workflows).
 /// Same idea as [`build_tool_sandbox_config`] for host-native backends: keeps `appSandbox.filesystem`
 /// optional while AppContainer setup / nono still get correct path grants.
@@ -1990,6 +3230,7 @@ pub fn build_app_sandbox_config(
             enabled: net.enabled,
             allowed_hosts: net.allowed_hosts,
             allowed_ports: net.allowed_ports,
+            host_network: net.host_network,
         },
         resources: crate::sandbox::types::ResourceConfig {
             max_memory,
@@ -2002,14 +3243,42 @@ pub fn build_app_sandbox_config(
         },
         child_work_dir,
         monitoring: build_sandbox_monitoring(monitoring),
-        delete_on_start: false,
+        delete_on_start: cfg.delete_on_start.unwrap_or(false),
         requested_tool_sandbox_type: None,
         image: None,
+        hardening: crate::sandbox::types::HardeningConfig {
+            allow_insecure: cfg.allow_insecure,
+            no_new_privileges: cfg.no_new_privileges.unwrap_or(true),
+            ..Default::default()
+        },
     })
 }
 
+/// Container path of the workspace in a Docker tool sandbox (`workspaceMountPath`, default `/workspace`).
+pub fn tool_sandbox_workspace_dir(cfg: &ToolSandboxConfig) -> String {
+    cfg.workspace_mount_path
+        .as_deref()
+        .map(|p| p.trim_end_matches('/'))
+        .filter(|p| !p.is_empty())
+        .unwrap_or(crate::sandbox::types::DEFAULT_CONTAINER_WORKSPACE)
+        .to_string()
+}
+
+/// `uid:gid` of this process, for running tool containers as the host user.
+#[cfg(unix)]
+fn host_user_spec() -> Option<String> {
+    Some(format!("{}:{}", nix::unistd::getuid(), nix::unistd::getgid()))
+}
+
+/// Docker Desktop maps file ownership itself on Windows.
+#[cfg(not(unix))]
+fn host_user_spec() -> Option<String> {
+    None
+}
+
 /// Build SandboxConfig for tool sandbox from Config.
-/// For Docker backends: `workspace_path` is bind-mounted at `/workspace` (exec cwd); skills at `/skills` when enabled.
+/// For Docker backends: `workspace_path` is bind-mounted at `workspaceMountPath` (default `/workspace`, the exec cwd)
+/// and the container runs as the host user unless `mapHostUser` is false; skills at `/skills` when enabled.
 /// For host-native backends (`appcontainer`, `nono`, `seatbelt`): workspace and skills are merged into `writable_paths` / `readonly_paths` on the host.
 pub fn build_tool_sandbox_config(
     cfg: &ToolSandboxConfig,
@@ -2081,7 +3350,7 @@ pub fn build_tool_sandbox_config(
 
     let (workspace_mount, skills_mount) = if is_docker {
         (
-            Some((workspace_host, "/workspace".to_string())),
+            Some((workspace_host, tool_sandbox_workspace_dir(cfg))),
             if mount_skills {
                 Some((
                     skills_dir.to_string_lossy().to_string(),
@@ -2110,11 +3379,15 @@ pub fn build_tool_sandbox_config(
             hidden_paths: rewrite_legacy_synbot_paths_for_root_override(expand_sandbox_paths(&fs.hidden_paths)),
             workspace_mount,
             skills_mount,
+            workspace_owner: (is_docker && cfg.map_host_user != Some(false))
+                .then(host_user_spec)
+                .flatten(),
         },
         network: crate::sandbox::types::NetworkConfig {
             enabled: net.enabled,
             allowed_hosts: net.allowed_hosts,
             allowed_ports: net.allowed_ports,
+            host_network: net.host_network,
         },
         resources: crate::sandbox::types::ResourceConfig {
             max_memory,
@@ -2130,6 +3403,12 @@ pub fn build_tool_sandbox_config(
         delete_on_start: cfg.delete_on_start.unwrap_or(false),
         requested_tool_sandbox_type: Some(tool_type.to_string()),
         image: cfg.image.clone(),
+        hardening: crate::sandbox::types::HardeningConfig {
+            allow_insecure: cfg.allow_insecure,
+            no_new_privileges: cfg.no_new_privileges.unwrap_or(true),
+            readonly_rootfs: cfg.readonly_rootfs.unwrap_or(true),
+            capabilities: cfg.capabilities.clone(),
+        },
     })
 }
 
@@ -2156,6 +3435,40 @@ impl std::fmt::Display for ValidationError {
     }
 }
 
+/// [`load_config`] found a config that fails [`validate_config`].
+#[derive(Debug)]
+pub struct ConfigValidationError(pub Vec<ValidationError>);
+
+impl std::fmt::Display for ConfigValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = self
+            .0
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>()
+            .join("; ");
+        write!(f, "config validation failed: {}", msg)
+    }
+}
+
+impl std::error::Error for ConfigValidationError {}
+
+/// Context on [`load_config`] errors while reading or parsing the file, e.g.
+/// "parsing config from ~/.synbot/config.json".
+#[derive(Debug)]
+pub struct ConfigFileError {
+    pub action: &'static str,
+    pub path: PathBuf,
+}
+
+impl std::fmt::Display for ConfigFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} config from {}", self.action, self.path.display())
+    }
+}
+
+impl std::error::Error for ConfigFileError {}
+
 /// Validate all fields of a [`Config`] against defined constraints.
 ///
 /// Collects **all** validation errors and returns them at once so the user
@@ -2167,6 +3480,8 @@ impl std::fmt::Display for ValidationError {
 /// - `mainAgent.max_tool_iterations > 0`
 /// - `tools.exec.timeout_secs > 0`
 /// - Enabled channels must have non-empty credentials
+/// - A channel's allowlist must not list the same `chatId` twice
+/// - `main_channel`, heartbeat / cron task channels and the approval route name exactly one enabled channel
 pub fn validate_config(config: &Config) -> Result<(), Vec<ValidationError>> {
     let mut errors = Vec::new();
 
@@ -2195,6 +3510,40 @@ pub fn validate_config(config: &Config) -> Result<(), Vec<ValidationError>> {
         });
     }
 
+    // --- Provider endpoints ---
+    let p = &config.providers;
+    let mut providers: Vec<(String, &ProviderEntry)> = [
+        ("anthropic", &p.anthropic),
+        ("openai", &p.openai),
+        ("gemini", &p.gemini),
+        ("openrouter", &p.openrouter),
+        ("deepseek", &p.deepseek),
+        ("moonshot", &p.moonshot),
+        ("kimiCode", &p.kimi_code),
+        ("ollama", &p.ollama),
+    ]
+    .into_iter()
+    .map(|(name, entry)| (name.to_string(), entry))
+    .collect();
+    let mut extra: Vec<_> = p.extra.iter().collect();
+    extra.sort_by(|a, b| a.0.cmp(b.0));
+    providers.extend(extra.into_iter().map(|(name, entry)| (format!("extra.{}", name), entry)));
+    for (name, entry) in providers {
+        let Some(base) = entry.api_base.as_deref().map(str::trim).filter(|b| !b.is_empty()) else {
+            continue;
+        };
+        let valid = url::Url::parse(base)
+            .map(|u| matches!(u.scheme(), "http" | "https") && u.host_str().is_some())
+            .unwrap_or(false);
+        if !valid {
+            errors.push(ValidationError {
+                field: format!("providers.{}.apiBase", name),
+                value: base.to_string(),
+                constraint: "must be an absolute http(s) URL (e.g. https://api.example.com/v1)".into(),
+            });
+        }
+    }
+
     // --- Memory ---
     if config.memory.vector_weight < 0.0 || config.memory.vector_weight > 1.0 {
         errors.push(ValidationError {
@@ -2210,6 +3559,13 @@ pub fn validate_config(config: &Config) -> Result<(), Vec<ValidationError>> {
             constraint: "must be between 0.0 and 1.0".into(),
         });
     }
+    if !["", "file", "sqlite"].contains(&config.memory.backend.trim().to_lowercase().as_str()) {
+        errors.push(ValidationError {
+            field: "memory.backend".into(),
+            value: config.memory.backend.clone(),
+            constraint: "must be empty, \"file\" or \"sqlite\"".into(),
+        });
+    }
 
     // --- Tools ---
     if config.tools.exec.timeout_secs == 0 {
@@ -2219,6 +3575,48 @@ pub fn validate_config(config: &Config) -> Result<(), Vec<ValidationError>> {
             constraint: "must be greater than 0".into(),
         });
     }
+    for (i, host) in config.tools.web.fetch_allowed_hosts.iter().enumerate() {
+        if crate::url_utils::UrlHost::parse(host).is_none() {
+            errors.push(ValidationError {
+                field: format!("tools.web.fetchAllowedHosts[{}]", i),
+                value: host.clone(),
+                constraint: "must be a host name or IP address".into(),
+            });
+        }
+    }
+
+    // --- Response transforms ---
+    let transform_lists = std::iter::once(("global".to_string(), &config.response_transforms.global)).chain(
+        config
+            .response_transforms
+            .channels
+            .iter()
+            .map(|(name, list)| (format!("channels.{}", name), list)),
+    );
+    for (list_name, list) in transform_lists {
+        for (i, transform) in list.iter().enumerate() {
+            if let ResponseTransformConfig::StripRegex { pattern, .. } = transform {
+                if let Err(e) = regex::Regex::new(pattern) {
+                    errors.push(ValidationError {
+                        field: format!("responseTransforms.{}[{}].pattern", list_name, i),
+                        value: pattern.clone(),
+                        constraint: format!("must be a valid regex ({})", e),
+                    });
+                }
+            }
+        }
+    }
+
+    // --- Tool sandbox ---
+    if let Some(path) = config.tool_sandbox.as_ref().and_then(|t| t.workspace_mount_path.as_ref()) {
+        if !path.starts_with('/') || path.trim_end_matches('/').is_empty() {
+            errors.push(ValidationError {
+                field: "toolSandbox.workspaceMountPath".into(),
+                value: path.clone(),
+                constraint: "must be an absolute container path other than /".into(),
+            });
+        }
+    }
 
     // --- Log config validation ---
     let valid_log_levels = ["trace", "debug", "info", "warn", "error"];
@@ -2266,6 +3664,23 @@ pub fn validate_config(config: &Config) -> Result<(), Vec<ValidationError>> {
         }
     }
 
+    // --- Exec pattern presets ---
+    let pattern_lists = [
+        ("tools.exec.denyPatterns", Some(&config.tools.exec.deny_patterns)),
+        ("tools.exec.allowPatterns", config.tools.exec.allow_patterns.as_ref()),
+    ];
+    for (field, patterns) in pattern_lists {
+        for (i, pattern) in patterns.into_iter().flatten().enumerate() {
+            if let Err(e) = expand_pattern_presets(std::slice::from_ref(pattern)) {
+                errors.push(ValidationError {
+                    field: format!("{}[{}]", field, i),
+                    value: pattern.clone(),
+                    constraint: e,
+                });
+            }
+        }
+    }
+
     // --- Permission config validation ---
     if config.tools.exec.permissions.enabled {
         if config.tools.exec.permissions.approval_timeout_secs == 0 {
@@ -2524,61 +3939,71 @@ pub fn validate_config(config: &Config) -> Result<(), Vec<ValidationError>> {
             }
         }
     }
-
-    // --- Collect enabled channel names for main_channel validation ---
-    let enabled_channels: Vec<String> = {
-        let mut ch = Vec::new();
-        for c in &config.channels.telegram {
-            if c.enabled && !c.name.is_empty() {
-                ch.push(c.name.clone());
-            }
-        }
-        for c in &config.channels.discord {
-            if c.enabled && !c.name.is_empty() {
-                ch.push(c.name.clone());
-            }
-        }
-        for c in &config.channels.feishu {
-            if c.enabled && !c.name.is_empty() {
-                ch.push(c.name.clone());
-            }
-        }
-        for c in &config.channels.slack {
-            if c.enabled && !c.name.is_empty() {
-                ch.push(c.name.clone());
-            }
-        }
-        for c in &config.channels.email {
-            if c.enabled && !c.name.is_empty() {
-                ch.push(c.name.clone());
+    for (i, c) in config.channels.webhook.iter().enumerate() {
+        if c.enabled {
+            if c.outbound_url.trim().is_empty() {
+                errors.push(ValidationError {
+                    field: format!("channels.webhook[{}].outboundUrl", i),
+                    value: String::new(),
+                    constraint: "must be non-empty when enabled".into(),
+                });
             }
-        }
-        for c in &config.channels.matrix {
-            if c.enabled && !c.name.is_empty() {
-                ch.push(c.name.clone());
+            if c.secret.is_empty() {
+                errors.push(ValidationError {
+                    field: format!("channels.webhook[{}].secret", i),
+                    value: String::new(),
+                    constraint: "must be non-empty when enabled (used to sign and verify requests)".into(),
+                });
             }
         }
-        for c in &config.channels.dingtalk {
-            if c.enabled && !c.name.is_empty() {
-                ch.push(c.name.clone());
-            }
+        if !c.name.is_empty() && !all_channel_names.insert(c.name.clone()) {
+            errors.push(ValidationError {
+                field: format!("channels.webhook[{}].name", i),
+                value: c.name.clone(),
+                constraint: "channel name must be globally unique".into(),
+            });
         }
-        if let Some(list) = &config.channels.whatsapp {
-            for c in list {
-                if c.enabled && !c.name.is_empty() {
-                    ch.push(c.name.clone());
-                }
+    }
+
+    // --- Duplicate allowlist chat ids (per channel instance) ---
+    let ch = &config.channels;
+    let allowlists = ch
+        .telegram
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (format!("channels.telegram[{}]", i), &c.allowlist))
+        .chain(ch.discord.iter().enumerate().map(|(i, c)| (format!("channels.discord[{}]", i), &c.allowlist)))
+        .chain(ch.feishu.iter().enumerate().map(|(i, c)| (format!("channels.feishu[{}]", i), &c.allowlist)))
+        .chain(ch.slack.iter().enumerate().map(|(i, c)| (format!("channels.slack[{}]", i), &c.allowlist)))
+        .chain(ch.matrix.iter().enumerate().map(|(i, c)| (format!("channels.matrix[{}]", i), &c.allowlist)))
+        .chain(ch.dingtalk.iter().enumerate().map(|(i, c)| (format!("channels.dingtalk[{}]", i), &c.allowlist)))
+        .chain(ch.whatsapp.iter().flatten().enumerate().map(|(i, c)| (format!("channels.whatsapp[{}]", i), &c.allowlist)))
+        .chain(ch.irc.iter().flatten().enumerate().map(|(i, c)| (format!("channels.irc[{}]", i), &c.allowlist)));
+    for (prefix, allowlist) in allowlists {
+        let mut first_index: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+        for (j, entry) in allowlist.iter().enumerate() {
+            let chat_id = entry.chat_id.trim();
+            if chat_id.is_empty() {
+                continue;
             }
-        }
-        if let Some(list) = &config.channels.irc {
-            for c in list {
-                if c.enabled && !c.name.is_empty() {
-                    ch.push(c.name.clone());
-                }
+            if let Some(first) = first_index.get(chat_id) {
+                errors.push(ValidationError {
+                    field: format!("{}.allowlist[{}].chatId", prefix, j),
+                    value: entry.chat_id.clone(),
+                    constraint: format!("duplicate chatId in this channel's allowlist (also allowlist[{}])", first),
+                });
+            } else {
+                first_index.insert(chat_id, j);
             }
         }
-        ch
-    };
+    }
+
+    // --- Collect enabled channel names for main_channel validation ---
+    let enabled_channels: Vec<&str> = config
+        .enabled_channels()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
 
     // --- main_channel validation ---
     let has_multi_agent_features = !config.main_agent.agents.is_empty();
@@ -2590,14 +4015,54 @@ pub fn validate_config(config: &Config) -> Result<(), Vec<ValidationError>> {
             constraint: "must be non-empty when multiple agents are configured".into(),
         });
     }
-    if !config.main_channel.is_empty() && !enabled_channels.contains(&config.main_channel) {
-        errors.push(ValidationError {
-            field: "main_channel".into(),
-            value: config.main_channel.clone(),
-            constraint: format!(
-                "must reference an enabled channel (available: {})",
-                enabled_channels.join(", ")
+
+    // --- Channel references (main_channel, heartbeat / cron tasks, approval route) ---
+    // Each must name exactly one enabled channel; "web" is the web chat when the web UI is on.
+    let mut reachable = enabled_channels.clone();
+    if config.web.enabled {
+        reachable.push("web");
+    }
+    let mut channel_refs: Vec<(String, &str)> = Vec::new();
+    if !config.main_channel.is_empty() {
+        channel_refs.push(("main_channel".into(), &config.main_channel));
+    }
+    if config.heartbeat.enabled {
+        for (i, t) in config.heartbeat.tasks.iter().enumerate() {
+            channel_refs.push((format!("heartbeat.tasks[{}].channel", i), &t.channel));
+        }
+    }
+    for (i, t) in config.cron.tasks.iter().enumerate() {
+        if t.enabled {
+            channel_refs.push((format!("cron.tasks[{}].channel", i), &t.channel));
+        }
+    }
+    if config.tools.exec.permissions.enabled {
+        if let Some(route) = &config.tools.exec.permissions.approval_route {
+            channel_refs.push(("tools.exec.permissions.approvalRoute.channel".into(), &route.channel));
+        }
+    }
+    let available = if reachable.is_empty() {
+        "none".to_string()
+    } else {
+        reachable.join(", ")
+    };
+    for (field, name) in channel_refs {
+        let constraint = match reachable.iter().filter(|n| **n == name).count() {
+            1 => continue,
+            0 if all_channel_names.contains(name) => format!(
+                "channel '{}' is disabled; must reference an enabled channel (available: {})",
+                name, available
+            ),
+            0 => format!("must reference an enabled channel (available: {})", available),
+            n => format!(
+                "ambiguous: {} enabled channels are named '{}'; channel names must be unique (available: {})",
+                n, name, available
             ),
+        };
+        errors.push(ValidationError {
+            field,
+            value: name.to_string(),
+            constraint,
         });
     }
 
@@ -2665,6 +4130,143 @@ pub fn validate_config(config: &Config) -> Result<(), Vec<ValidationError>> {
         }
     }
 
+    // --- Cron task schedules ---
+    for (i, t) in config.cron.tasks.iter().enumerate() {
+        if let Err(e) = crate::cron::config_runner::validate_cron_expr(&t.schedule) {
+            errors.push(ValidationError {
+                field: format!("cron.tasks[{}].schedule", i),
+                value: t.schedule.clone(),
+                constraint: format!(
+                    "must be a cron expression (min hour day month weekday, e.g. \"0 9 * * *\"): {}",
+                    e
+                ),
+            });
+        }
+    }
+
+    // --- Heartbeat agent tasks need something to send to the agent ---
+    for (i, t) in config.heartbeat.tasks.iter().enumerate() {
+        if t.kind == HeartbeatTaskKind::Agent && t.target.trim().is_empty() {
+            errors.push(ValidationError {
+                field: format!("heartbeat.tasks[{}].target", i),
+                value: t.target.clone(),
+                constraint: "must be non-empty for \"type\": \"agent\" tasks".into(),
+            });
+        }
+    }
+
+    // --- Default role, language roles and heartbeat / cron task roles (must be run by some agent) ---
+    let role_known = |role: &str| {
+        role == "main" || config.main_agent.agents.iter().any(|a| a.role == role)
+    };
+    let task_roles = std::iter::once((
+        "mainAgent.defaultRole".to_string(),
+        config.main_agent.default_role.as_ref(),
+    ))
+    .chain(
+        config
+            .main_agent
+            .language_roles
+            .iter()
+            .map(|(lang, role)| (format!("mainAgent.languageRoles.{}", lang), Some(role))),
+    )
+    .chain(
+        config
+            .heartbeat
+            .tasks
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (format!("heartbeat.tasks[{}].role", i), t.role.as_ref())),
+    )
+    .chain(
+        config
+            .cron
+            .tasks
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (format!("cron.tasks[{}].role", i), t.role.as_ref())),
+    );
+    for (field, role) in task_roles {
+        if let Some(role) = role {
+            if !role_known(role) {
+                errors.push(ValidationError {
+                    field,
+                    value: role.clone(),
+                    constraint: "role must be \"main\" or the role of an agent in mainAgent.agents".into(),
+                });
+            }
+        }
+    }
+
+    // --- Per-channel provider / model / role overrides ---
+    for (kind, entries) in config.channels.channel_entries() {
+        for (i, entry) in entries.into_iter().enumerate() {
+            let binding: ChannelModelBinding = serde_json::from_value(entry).unwrap_or_default();
+            if let Some(role) = &binding.role {
+                if !role_known(role) {
+                    errors.push(ValidationError {
+                        field: format!("channels.{}[{}].role", kind, i),
+                        value: role.clone(),
+                        constraint: "role must be \"main\" or the role of an agent in mainAgent.agents".into(),
+                    });
+                }
+            }
+            let model_set = binding.model.as_deref().is_some_and(|m| !m.trim().is_empty());
+            if binding.model.is_some() && !model_set {
+                errors.push(ValidationError {
+                    field: format!("channels.{}[{}].model", kind, i),
+                    value: String::new(),
+                    constraint: "must be non-empty when set".into(),
+                });
+            }
+            if let Some(provider) = &binding.provider {
+                if provider.trim().is_empty() || !model_set {
+                    errors.push(ValidationError {
+                        field: format!("channels.{}[{}].provider", kind, i),
+                        value: provider.clone(),
+                        constraint: "must be a non-empty provider name, with model set".into(),
+                    });
+                }
+            }
+        }
+    }
+
+    // --- Identity links ---
+    let mut linked_members = std::collections::HashSet::new();
+    let mut link_names = std::collections::HashSet::new();
+    for (i, link) in config.identity_links.iter().enumerate() {
+        let name_ok = !link.name.is_empty()
+            && link
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !name_ok || !link_names.insert(link.name.as_str()) {
+            errors.push(ValidationError {
+                field: format!("identityLinks[{}].name", i),
+                value: link.name.clone(),
+                constraint: "must be a unique, non-empty name of letters, digits, '-' or '_'".into(),
+            });
+        }
+        for (j, member) in link.members.iter().enumerate() {
+            let well_formed = member
+                .split_once(':')
+                .is_some_and(|(channel, chat_id)| !channel.is_empty() && !chat_id.is_empty());
+            if !well_formed {
+                errors.push(ValidationError {
+                    field: format!("identityLinks[{}].members[{}]", i, j),
+                    value: member.clone(),
+                    constraint: "must be \"<channel name>:<chatId>\"".into(),
+                });
+            } else if !linked_members.insert(member.as_str()) {
+                errors.push(ValidationError {
+                    field: format!("identityLinks[{}].members[{}]", i, j),
+                    value: member.clone(),
+                    constraint: "identity is already in another link".into(),
+                });
+            }
+        }
+    }
+
     if errors.is_empty() {
         Ok(())
     } else {
@@ -3274,34 +4876,29 @@ pub fn load_config(path: Option<&Path>) -> Result<Config> {
         .map(PathBuf::from)
         .unwrap_or_else(config_path);
 
+    let file_error = |action| ConfigFileError {
+        action,
+        path: p.clone(),
+    };
     let cfg = if p.exists() {
-        let text = std::fs::read_to_string(&p)
-            .with_context(|| format!("reading config from {}", p.display()))?;
+        let text = std::fs::read_to_string(&p).with_context(|| file_error("reading"))?;
         let text = EnvSubstitutor::substitute(&text)
-            .with_context(|| format!("substituting environment variables in config from {}", p.display()))?;
+            .with_context(|| file_error("substituting environment variables in"))?;
 
         // Run config migration if needed
-        let mut value: serde_json::Value = serde_json::from_str(&text)
-            .with_context(|| format!("parsing config from {}", p.display()))?;
+        let mut value: serde_json::Value =
+            serde_json::from_str(&text).with_context(|| file_error("parsing"))?;
 
         let migrator = build_config_migrator();
         value = migrator.migrate(&p, value)?;
 
-        serde_json::from_value(value)
-            .with_context(|| format!("deserializing config from {}", p.display()))?
+        serde_json::from_value(value).with_context(|| file_error("deserializing"))?
     } else {
         Config::default()
     };
 
     // Validate after successful parsing
-    if let Err(errors) = validate_config(&cfg) {
-        let msg = errors
-            .iter()
-            .map(|e| e.to_string())
-            .collect::<Vec<_>>()
-            .join("; ");
-        anyhow::bail!("config validation failed: {}", msg);
-    }
+    validate_config(&cfg).map_err(ConfigValidationError)?;
 
     Ok(cfg)
 }
@@ -3648,6 +5245,35 @@ mod tests {
         assert!(find_error(&errors, "mainAgent.max_tool_iterations").is_some());
     }
 
+    // --- providers.*.apiBase ---
+
+    #[test]
+    fn malformed_provider_api_base_is_rejected_with_provider_name() {
+        let mut cfg = valid_config();
+        cfg.providers.openai.api_base = Some("api.openai.com/v1".into());
+        cfg.providers.extra.insert(
+            "mygw".into(),
+            ProviderEntry {
+                api_base: Some("ftp://gw.example.com".into()),
+                ..Default::default()
+            },
+        );
+        let errors = validate_config(&cfg).unwrap_err();
+        let err = find_error(&errors, "providers.openai.apiBase").expect("openai error");
+        assert_eq!(err.value, "api.openai.com/v1");
+        assert!(err.constraint.contains("absolute http(s) URL"));
+        assert!(find_error(&errors, "providers.extra.mygw.apiBase").is_some());
+    }
+
+    #[test]
+    fn valid_or_unset_provider_api_base_passes() {
+        let mut cfg = valid_config();
+        cfg.providers.openai.api_base = Some("https://api.openai.com/v1".into());
+        cfg.providers.ollama.api_base = Some("http://localhost:11434".into());
+        cfg.providers.deepseek.api_base = Some("  ".into());
+        assert!(validate_config(&cfg).is_ok());
+    }
+
     // --- tools.exec.timeout_secs ---
 
     #[test]
@@ -3658,6 +5284,141 @@ mod tests {
         assert!(find_error(&errors, "tools.exec.timeout_secs").is_some());
     }
 
+    // --- tools.exec pattern presets ---
+
+    #[test]
+    fn pattern_presets_expand_in_place() {
+        let mut cfg = valid_config();
+        cfg.tools.exec.deny_patterns = vec!["custom-bad".into(), "preset:destructive".into()];
+        cfg.tools.exec.allow_patterns = Some(vec!["preset:network".into()]);
+        assert!(validate_config(&cfg).is_ok());
+
+        let deny = cfg.tools.exec.expanded_deny_patterns();
+        assert_eq!(deny[0], "custom-bad");
+        assert!(deny.contains(&"rm -rf /".to_string()));
+        assert!(deny.contains(&"mkfs".to_string()));
+        assert!(!deny.iter().any(|p| p.starts_with(PATTERN_PRESET_PREFIX)));
+        let allow = cfg.tools.exec.expanded_allow_patterns().unwrap();
+        assert!(allow.contains(&"cmd:curl".to_string()));
+        for name in PATTERN_PRESETS {
+            assert!(exec_pattern_preset(name).is_some(), "preset {} has no patterns", name);
+        }
+    }
+
+    #[test]
+    fn unknown_pattern_preset_is_rejected() {
+        let mut cfg = valid_config();
+        cfg.tools.exec.deny_patterns = vec!["mkfs".into(), "preset:everything".into()];
+        let errors = validate_config(&cfg).unwrap_err();
+        let err = find_error(&errors, "tools.exec.denyPatterns[1]").unwrap();
+        assert!(err.constraint.contains("unknown preset"));
+        assert!(expand_pattern_presets(&["preset:everything".to_string()]).is_err());
+
+        // Past validation, the unknown preset stays literal and the others still expand
+        cfg.tools.exec.deny_patterns.push("preset:destructive".into());
+        let deny = cfg.tools.exec.expanded_deny_patterns();
+        assert_eq!(deny[..2], ["mkfs".to_string(), "preset:everything".to_string()]);
+        assert!(deny.contains(&"rm -rf /".to_string()));
+    }
+
+    // --- channel commands ---
+
+    #[test]
+    fn channel_commands_match_whole_prefix_and_are_found_by_channel_name() {
+        let mut cfg = valid_config();
+        cfg.channels.discord = vec![serde_json::from_value(serde_json::json!({
+            "name": "ops",
+            "commands": [{ "prefix": "!status", "action": "health" }]
+        }))
+        .unwrap()];
+        let commands = cfg.channels.commands("ops");
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].action, ChannelCommandAction::Health);
+        assert!(commands[0].matches("!status"));
+        assert!(commands[0].matches("  !STATUS please"));
+        assert!(!commands[0].matches("!statuses"));
+        assert!(!commands[0].matches("what is !status"));
+        assert!(cfg.channels.commands("discord").is_empty());
+    }
+
+    #[test]
+    fn channel_tools_enabled_defaults_to_true() {
+        let mut cfg = valid_config();
+        cfg.channels.discord = vec![
+            serde_json::from_value(serde_json::json!({ "name": "lobby", "toolsEnabled": false })).unwrap(),
+            serde_json::from_value(serde_json::json!({ "name": "ops" })).unwrap(),
+        ];
+        assert!(!cfg.channels.tools_enabled("lobby"));
+        assert!(cfg.channels.tools_enabled("ops"));
+        assert!(cfg.channels.tools_enabled("unknown"));
+
+        let snapshot = cfg.clone();
+        assert!(snapshot.channels.settings_index.0.get().is_some(), "clones reuse the built index");
+        assert!(!snapshot.channels.tools_enabled("lobby"));
+    }
+
+    // --- responseTransforms ---
+
+    #[test]
+    fn response_transforms_parse_and_reject_bad_regex() {
+        let mut cfg: Config = serde_json::from_value(serde_json::json!({
+            "responseTransforms": {
+                "global": [{ "type": "strip_regex", "pattern": "(?i)internal" }],
+                "channels": {
+                    "telegram": [{ "type": "append_footer", "text": "Not financial advice." }]
+                }
+            }
+        }))
+        .unwrap();
+        assert!(validate_config(&cfg).is_ok());
+        assert_eq!(cfg.response_transforms.for_channel("telegram").count(), 2);
+        assert_eq!(cfg.response_transforms.for_channel("discord").count(), 1);
+
+        cfg.response_transforms
+            .channels
+            .get_mut("telegram")
+            .unwrap()
+            .push(ResponseTransformConfig::StripRegex {
+                pattern: "([unclosed".into(),
+                replacement: String::new(),
+            });
+        let errors = validate_config(&cfg).unwrap_err();
+        assert!(find_error(&errors, "responseTransforms.channels.telegram[1].pattern").is_some());
+    }
+
+    // --- toolSandbox ---
+
+    #[test]
+    fn tool_sandbox_workspace_mount_path_is_configurable() {
+        let mut cfg = valid_config();
+        let mut tool = ToolSandboxConfig {
+            sandbox_type: Some("plain-docker".into()),
+            workspace_mount_path: Some("/work/".into()),
+            ..Default::default()
+        };
+        cfg.tool_sandbox = Some(tool.clone());
+        assert!(validate_config(&cfg).is_ok());
+        assert_eq!(tool_sandbox_workspace_dir(&tool), "/work");
+
+        let ws = std::path::Path::new("/home/me/.synbot/workspace");
+        let built = build_tool_sandbox_config(&tool, &None, ws, std::path::Path::new("/nonexistent")).unwrap();
+        assert_eq!(
+            built.filesystem.workspace_mount,
+            Some(("/home/me/.synbot/workspace".to_string(), "/work".to_string()))
+        );
+        #[cfg(unix)]
+        assert!(built.filesystem.workspace_owner.is_some());
+
+        tool.map_host_user = Some(false);
+        let built = build_tool_sandbox_config(&tool, &None, ws, std::path::Path::new("/nonexistent")).unwrap();
+        assert_eq!(built.filesystem.workspace_owner, None);
+
+        tool.workspace_mount_path = Some("relative".into());
+        cfg.tool_sandbox = Some(tool);
+        let errors = validate_config(&cfg).unwrap_err();
+        assert!(find_error(&errors, "toolSandbox.workspaceMountPath").is_some());
+    }
+
     // --- Channel credential validation ---
 
     #[test]
@@ -3833,8 +5594,12 @@ mod tests {
             max_tokens: None,
             temperature: None,
             max_iterations: None,
+            reasoning_effort: None,
+            thinking_budget: None,
             skills: Vec::new(),
             tools: Vec::new(),
+            tool_result_format: None,
+            tools_enabled: true,
         }
     }
 
@@ -3874,6 +5639,117 @@ mod tests {
         assert!(validate_config(&cfg).is_ok());
     }
 
+    #[test]
+    fn duplicate_allowlist_chat_id_in_one_channel_is_rejected() {
+        let entry = |chat_id: &str, alias: &str| AllowlistEntry {
+            chat_id: chat_id.into(),
+            chat_alias: alias.into(),
+            my_name: None,
+        };
+        let mut cfg = config_with_telegram();
+        cfg.channels.telegram[0].allowlist = vec![
+            entry("1001", "me"),
+            entry("-200", "team group"),
+            entry(" 1001", "me again"),
+        ];
+        let errors = validate_config(&cfg).unwrap_err();
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(errors[0].field, "channels.telegram[0].allowlist[2].chatId");
+        assert!(errors[0].constraint.contains("also allowlist[0]"));
+
+        // The same chat may be allowlisted on two different channels.
+        cfg.channels.telegram[0].allowlist.pop();
+        cfg.channels.telegram.push(TelegramConfig {
+            name: "telegram_2".into(),
+            enabled: true,
+            token: "bot456:def".into(),
+            allowlist: vec![entry("1001", "me")],
+            ..Default::default()
+        });
+        assert!(validate_config(&cfg).is_ok());
+    }
+
+    #[test]
+    fn custom_openai_compatible_provider_deserializes_and_resolves() {
+        let cfg: Config = serde_json::from_value(serde_json::json!({
+            "mainAgent": { "provider": "mygw", "model": "gw-large" },
+            "providers": {
+                "custom": {
+                    "mygw": {
+                        "apiKey": "gw-key",
+                        "apiBase": "https://gw.example.com/v1",
+                        "kind": "openai_compatible"
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let entry = &cfg.providers.extra["mygw"];
+        assert_eq!(entry.api_style, ProviderApiStyle::Openai);
+
+        let (key, base) = resolve_provider(&cfg, &cfg.main_agent.provider);
+        assert_eq!(key, "gw-key");
+        assert_eq!(base.as_deref(), Some("https://gw.example.com/v1"));
+
+        let anthropic: ProviderEntry =
+            serde_json::from_value(serde_json::json!({ "kind": "anthropic_compatible" })).unwrap();
+        assert_eq!(anthropic.api_style, ProviderApiStyle::Anthropic);
+    }
+
+    #[test]
+    fn custom_and_extra_providers_can_be_set_together() {
+        let cfg: Config = serde_json::from_value(serde_json::json!({
+            "providers": {
+                "extra": { "a": { "apiKey": "extra-a" } },
+                "custom": { "a": { "apiKey": "custom-a" }, "b": { "apiKey": "custom-b" } }
+            }
+        }))
+        .unwrap();
+        assert_eq!(cfg.providers.extra["a"].api_key, "extra-a");
+        assert_eq!(cfg.providers.extra["b"].api_key, "custom-b");
+    }
+
+    #[test]
+    fn enabled_channels_lists_enabled_named_entries_with_kinds() {
+        let mut cfg = config_with_telegram();
+        cfg.channels.telegram.push(TelegramConfig {
+            name: "tg_off".into(),
+            enabled: false,
+            ..Default::default()
+        });
+        cfg.channels.discord = vec![DiscordConfig {
+            name: "discord".into(),
+            enabled: true,
+            ..Default::default()
+        }];
+        cfg.channels.slack = vec![SlackConfig {
+            name: String::new(),
+            enabled: true,
+            ..Default::default()
+        }];
+        cfg.channels.whatsapp = Some(vec![WhatsAppConfig {
+            name: "wa_off".into(),
+            enabled: false,
+            ..Default::default()
+        }]);
+        cfg.channels.irc = Some(vec![IrcConfig {
+            name: "irc_ops".into(),
+            enabled: true,
+            ..Default::default()
+        }]);
+
+        assert_eq!(
+            cfg.enabled_channels(),
+            vec![
+                ("telegram", ChannelKind::Telegram),
+                ("discord", ChannelKind::Discord),
+                ("irc_ops", ChannelKind::Irc),
+            ]
+        );
+        assert!(Config::default().enabled_channels().is_empty());
+        assert_eq!(ChannelKind::DingTalk.as_str(), "dingtalk");
+    }
+
     // --- Agent validation (main is implicit; must not define agent named "main") ---
 
     #[test]
@@ -3903,12 +5779,188 @@ mod tests {
     fn agent_role_empty_is_rejected() {
         let mut cfg = config_with_telegram();
         cfg.main_agent.agents = vec![
-            AgentConfig { name: "helper".into(), role: String::new(), provider: None, model: None, max_tokens: None, temperature: None, max_iterations: None, skills: Vec::new(), tools: Vec::new() },
+            AgentConfig { name: "helper".into(), role: String::new(), provider: None, model: None, max_tokens: None, temperature: None, max_iterations: None, reasoning_effort: None, thinking_budget: None, skills: Vec::new(), tools: Vec::new(), tool_result_format: None, tools_enabled: true },
         ];
         let errors = validate_config(&cfg).unwrap_err();
         assert!(errors.iter().any(|e| e.constraint.contains("role must be non-empty")));
     }
 
+    #[test]
+    fn heartbeat_and_cron_roles_must_belong_to_an_agent() {
+        let mut cfg = config_with_telegram();
+        cfg.main_agent.agents = vec![make_agent("ops_bot", "ops")];
+        cfg.heartbeat.tasks = vec![HeartbeatTask {
+            channel: "telegram".into(),
+            chat_id: "c1".into(),
+            user_id: "u1".into(),
+            target: "check disk usage".into(),
+            kind: Default::default(),
+            on_error: HeartbeatOnError::Post,
+            role: Some("ops".into()),
+        }];
+        cfg.cron.tasks = vec![CronTaskConfig {
+            schedule: "0 9 * * *".into(),
+            description: String::new(),
+            enabled: true,
+            command: "daily report".into(),
+            channel: "telegram".into(),
+            user_id: "u1".into(),
+            chat_id: None,
+            misfire_policy: MisfirePolicy::Skip,
+            overlap_policy: CronOverlapPolicy::Skip,
+            role: Some("main".into()),
+        }];
+        assert!(validate_config(&cfg).is_ok());
+
+        cfg.cron.tasks[0].role = Some("finance".into());
+        let errors = validate_config(&cfg).unwrap_err();
+        let err = find_error(&errors, "cron.tasks[0].role").expect("unknown cron role");
+        assert_eq!(err.value, "finance");
+        assert!(find_error(&errors, "heartbeat.tasks[0].role").is_none());
+    }
+
+    #[test]
+    fn heartbeat_agent_tasks_need_a_target() {
+        let mut cfg = config_with_telegram();
+        let task: HeartbeatTask = serde_json::from_value(serde_json::json!({
+            "channel": "telegram",
+            "chatId": "c1"
+        }))
+        .unwrap();
+        cfg.heartbeat.tasks = vec![task];
+        let errors = validate_config(&cfg).unwrap_err();
+        assert!(find_error(&errors, "heartbeat.tasks[0].target").is_some());
+
+        cfg.heartbeat.tasks[0].kind = HeartbeatTaskKind::HealthReport;
+        assert!(validate_config(&cfg).is_ok());
+    }
+
+    #[test]
+    fn default_role_must_belong_to_an_agent() {
+        let mut cfg = config_with_telegram();
+        cfg.main_agent.agents = vec![make_agent("ops_bot", "ops")];
+        cfg.main_agent.default_role = Some("ops".into());
+        assert!(validate_config(&cfg).is_ok());
+
+        cfg.main_agent.default_role = Some("support".into());
+        let errors = validate_config(&cfg).unwrap_err();
+        let err = find_error(&errors, "mainAgent.defaultRole").expect("unknown default role");
+        assert_eq!(err.value, "support");
+    }
+
+    #[test]
+    fn language_roles_must_belong_to_an_agent() {
+        let mut cfg = config_with_telegram();
+        cfg.main_agent.agents = vec![make_agent("ops_bot", "ops")];
+        cfg.main_agent.language_roles = HashMap::from([("cmn".into(), "ops".into()), ("eng".into(), "main".into())]);
+        assert!(validate_config(&cfg).is_ok());
+
+        cfg.main_agent.language_roles.insert("spa".into(), "ventas".into());
+        let errors = validate_config(&cfg).unwrap_err();
+        let err = find_error(&errors, "mainAgent.languageRoles.spa").expect("unknown language role");
+        assert_eq!(err.value, "ventas");
+    }
+
+    #[test]
+    fn channel_model_binding_is_looked_up_and_validated() {
+        let mut cfg = config_with_telegram();
+        cfg.main_agent.agents = vec![make_agent("ops_bot", "ops")];
+        cfg.channels.telegram.push(TelegramConfig {
+            name: "admin".into(),
+            token: "bot456:def".into(),
+            provider: Some("anthropic".into()),
+            model: Some("claude-opus".into()),
+            role: Some("ops".into()),
+            ..Default::default()
+        });
+        assert!(validate_config(&cfg).is_ok());
+        let admin = cfg.channels.model_binding("admin");
+        assert_eq!(admin.provider.as_deref(), Some("anthropic"));
+        assert_eq!(admin.model.as_deref(), Some("claude-opus"));
+        assert_eq!(admin.role.as_deref(), Some("ops"));
+        assert_eq!(cfg.channels.model_binding("telegram"), ChannelModelBinding::default());
+        assert_eq!(cfg.channels.model_binding("missing"), ChannelModelBinding::default());
+
+        cfg.channels.telegram[1].model = None;
+        cfg.channels.telegram[1].role = Some("finance".into());
+        let errors = validate_config(&cfg).unwrap_err();
+        assert!(find_error(&errors, "channels.telegram[1].provider").is_some(), "provider without model");
+        let err = find_error(&errors, "channels.telegram[1].role").expect("unknown channel role");
+        assert_eq!(err.value, "finance");
+    }
+
+    fn cron_task(channel: &str) -> CronTaskConfig {
+        CronTaskConfig {
+            schedule: "0 9 * * *".into(),
+            description: String::new(),
+            enabled: true,
+            command: "daily report".into(),
+            channel: channel.into(),
+            user_id: "u1".into(),
+            chat_id: None,
+            misfire_policy: MisfirePolicy::Skip,
+            overlap_policy: CronOverlapPolicy::Skip,
+            role: None,
+        }
+    }
+
+    #[test]
+    fn invalid_cron_task_schedule_is_rejected() {
+        let mut cfg = config_with_telegram();
+        cfg.cron.tasks = vec![cron_task("telegram"), cron_task("telegram")];
+        cfg.cron.tasks[1].schedule = "every morning".into();
+        let errors = validate_config(&cfg).unwrap_err();
+        assert!(find_error(&errors, "cron.tasks[0].schedule").is_none());
+        let err = find_error(&errors, "cron.tasks[1].schedule").expect("bad schedule");
+        assert_eq!(err.value, "every morning");
+
+        cfg.cron.tasks[1].schedule = "0 0 9 * * Mon-Fri *".into();
+        assert!(validate_config(&cfg).is_ok());
+    }
+
+    #[test]
+    fn cron_task_channel_must_resolve_to_one_enabled_channel() {
+        let mut cfg = config_with_telegram();
+        cfg.channels.discord = vec![DiscordConfig {
+            name: "discord".into(),
+            enabled: false,
+            ..Default::default()
+        }];
+        cfg.cron.tasks = vec![cron_task("telegram"), cron_task("discord"), cron_task("telgram")];
+        let errors = validate_config(&cfg).unwrap_err();
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+
+        let disabled = find_error(&errors, "cron.tasks[1].channel").expect("disabled channel");
+        assert_eq!(disabled.value, "discord");
+        assert!(disabled.constraint.contains("'discord' is disabled"));
+        assert!(disabled.constraint.contains("(available: telegram)"));
+
+        let dangling = find_error(&errors, "cron.tasks[2].channel").expect("unknown channel");
+        assert_eq!(dangling.constraint, "must reference an enabled channel (available: telegram)");
+
+        // Disabled tasks are not checked; the web chat counts when the web UI is on.
+        cfg.cron.tasks[1].enabled = false;
+        cfg.cron.tasks[2].channel = "web".into();
+        cfg.web.enabled = true;
+        assert!(validate_config(&cfg).is_ok());
+    }
+
+    #[test]
+    fn cron_task_channel_with_duplicate_name_is_ambiguous() {
+        let mut cfg = config_with_telegram();
+        cfg.channels.slack = vec![SlackConfig {
+            name: "telegram".into(),
+            enabled: true,
+            token: "xoxb-1".into(),
+            app_token: "xapp-1".into(),
+            ..Default::default()
+        }];
+        cfg.cron.tasks = vec![cron_task("telegram")];
+        let errors = validate_config(&cfg).unwrap_err();
+        let err = find_error(&errors, "cron.tasks[0].channel").expect("ambiguous reference");
+        assert!(err.constraint.starts_with("ambiguous: 2 enabled channels are named 'telegram'"));
+    }
+
     // --- ValidationError Display ---
 
     #[test]
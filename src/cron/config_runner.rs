@@ -1,7 +1,8 @@
 //! Config-based cron runner: reads config.cron.tasks and fires due jobs, sending InboundMessage to the bus.
 
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -11,7 +12,7 @@ use tracing::{info, warn};
 use chrono::{DateTime, Local, Utc};
 
 use crate::bus::InboundMessage;
-use crate::config::{Config, CronTaskConfig};
+use crate::config::{self, Config, CronOverlapPolicy, CronTaskConfig, MisfirePolicy};
 
 /// When a task has never fired, use this lookback for the `after` instant when computing the next
 /// schedule match. Otherwise `after = now` can fall a few seconds *past* the scheduled minute (the
@@ -19,6 +20,10 @@ use crate::config::{Config, CronTaskConfig};
 /// today's run is skipped entirely.
 const NEVER_FIRED_AFTER_LOOKBACK_MS: i64 = 180_000;
 
+/// Upper bound on catch-up runs per task for [`MisfirePolicy::RunAll`], so a long outage with a
+/// minutely schedule does not flood the bus.
+const MAX_CATCH_UP_RUNS: usize = 24;
+
 /// Tracks last run time per task key to avoid duplicate fires.
 fn task_key(task: &CronTaskConfig) -> String {
     format!(
@@ -37,6 +42,14 @@ fn normalize_cron_expr(expr: &str) -> String {
     }
 }
 
+/// Checks that `expr` is a schedule the runner can use (5-field or 7-field); the error is the
+/// parser's message.
+pub fn validate_cron_expr(expr: &str) -> Result<(), String> {
+    cron::Schedule::from_str(&normalize_cron_expr(expr))
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
 /// Returns the next run time (epoch ms) for a cron expression after the given timestamp, or None if invalid.
 ///
 /// Uses the system local timezone for the schedule fields (hour/minute). The `cron` crate interprets
@@ -52,87 +65,324 @@ fn next_run_ms_after(expr: &str, after_ms: i64) -> Option<i64> {
     Some(next.timestamp_millis())
 }
 
+/// Scheduled occurrences (epoch ms) strictly after `last_ms` and at or before `now_ms`, at most `cap`.
+fn missed_runs(expr: &str, last_ms: i64, now_ms: i64, cap: usize) -> Vec<i64> {
+    let mut runs = Vec::new();
+    let mut after = last_ms;
+    while runs.len() < cap {
+        match next_run_ms_after(expr, after) {
+            Some(next) if next <= now_ms && next > after => {
+                runs.push(next);
+                after = next;
+            }
+            _ => break,
+        }
+    }
+    runs
+}
+
+/// Default location of the persisted last-run state for config tasks.
+pub fn default_state_path() -> PathBuf {
+    config::config_dir()
+        .join("cron")
+        .join("config_tasks_state.json")
+}
+
+fn load_state(path: &Path) -> Result<HashMap<String, i64>> {
+    if path.exists() {
+        let text = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&text)?)
+    } else {
+        Ok(HashMap::new())
+    }
+}
+
+fn save_state(path: &Path, state: &HashMap<String, i64>) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(state)?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+/// Inbound metadata key carrying the task key of a config cron run, so the agent loop can report
+/// the run as finished via [`CronRunTracker::finish`].
+pub const CRON_TASK_META: &str = "cron_task";
+
+fn task_message(task: &CronTaskConfig, key: &str, misfire: bool) -> InboundMessage {
+    let chat_id = task
+        .chat_id
+        .as_deref()
+        .unwrap_or(&task.user_id)
+        .to_string();
+    let mut metadata = serde_json::json!({
+        "source": "cron",
+        "description": task.description,
+        CRON_TASK_META: key,
+    });
+    if misfire {
+        metadata["misfire"] = serde_json::Value::Bool(true);
+    }
+    if let Some(ref role) = task.role {
+        metadata["role"] = serde_json::Value::String(role.clone());
+    }
+    InboundMessage {
+        channel: task.channel.clone(),
+        sender_id: task.user_id.clone(),
+        chat_id,
+        content: task.command.clone(),
+        timestamp: Utc::now(),
+        media: vec![],
+        metadata,
+    }
+}
+
+/// Tracks which config cron tasks have a run in flight. Shared between the runner (which marks a
+/// run started when it dispatches it) and the agent loop (which marks it finished when the turn
+/// ends).
+#[derive(Default)]
+pub struct CronRunTracker {
+    running: std::sync::Mutex<HashSet<String>>,
+}
+
+impl CronRunTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mark a run of `key` as started. Returns false if one is already in flight.
+    pub fn try_begin(&self, key: &str) -> bool {
+        self.running.lock().unwrap().insert(key.to_string())
+    }
+
+    /// Mark the in-flight run of `key` as finished.
+    pub fn finish(&self, key: &str) {
+        self.running.lock().unwrap().remove(key);
+    }
+
+    pub fn is_running(&self, key: &str) -> bool {
+        self.running.lock().unwrap().contains(key)
+    }
+}
+
 pub struct ConfigCronRunner {
     config: Arc<RwLock<Config>>,
     /// task_key -> last_fired_at_ms
     last_fired: RwLock<HashMap<String, i64>>,
+    /// Where `last_fired` is persisted so missed runs can be detected after a restart.
+    state_path: PathBuf,
+    runs: Arc<CronRunTracker>,
+    /// task_key -> fires waiting for the in-flight run to finish (queue overlap policy, run_all catch-up).
+    pending: RwLock<HashMap<String, usize>>,
 }
 
 impl ConfigCronRunner {
     pub fn new(config: Arc<RwLock<Config>>) -> Self {
+        Self::with_state_path(config, default_state_path())
+    }
+
+    pub fn with_state_path(config: Arc<RwLock<Config>>, state_path: PathBuf) -> Self {
+        let last_fired = load_state(&state_path).unwrap_or_else(|e| {
+            warn!(path = %state_path.display(), "Config cron: failed to load state: {e}");
+            HashMap::new()
+        });
         Self {
             config,
-            last_fired: RwLock::new(HashMap::new()),
+            last_fired: RwLock::new(last_fired),
+            state_path,
+            runs: Arc::new(CronRunTracker::new()),
+            pending: RwLock::new(HashMap::new()),
         }
     }
 
-    /// Run loop: every 60s read config.cron.tasks, for each enabled task check if due, then send InboundMessage.
-    pub async fn run(&self, inbound_tx: mpsc::Sender<InboundMessage>) -> Result<()> {
-        info!("Config cron runner started");
-        let mut interval = tokio::time::interval(Duration::from_secs(60));
-        interval.tick().await; // first tick fires immediately, skip it
+    /// Share the in-flight tracker with the agent loop so finished runs are released.
+    pub fn with_run_tracker(mut self, runs: Arc<CronRunTracker>) -> Self {
+        self.runs = runs;
+        self
+    }
 
-        loop {
-            interval.tick().await;
+    fn persist(&self, last_fired: &HashMap<String, i64>) {
+        if let Err(e) = save_state(&self.state_path, last_fired) {
+            warn!(path = %self.state_path.display(), "Config cron: failed to save state: {e}");
+        }
+    }
+
+    /// Start a run of `task` unless one is already in flight. Returns true if the message was sent.
+    async fn dispatch(
+        &self,
+        inbound_tx: &mpsc::Sender<InboundMessage>,
+        task: &CronTaskConfig,
+        key: &str,
+        misfire: bool,
+    ) -> bool {
+        if !self.runs.try_begin(key) {
+            return false;
+        }
+        if let Err(e) = inbound_tx.send(task_message(task, key, misfire)).await {
+            warn!("Config cron failed to send task: {e}");
+            self.runs.finish(key);
+            return false;
+        }
+        info!(
+            schedule = %task.schedule,
+            channel = %task.channel,
+            "Config cron task sent"
+        );
+        true
+    }
 
-            let tasks = {
-                let cfg = self.config.read().await;
-                cfg.cron.tasks.clone()
+    /// Apply each task's [`MisfirePolicy`] to runs missed since its persisted last-run time.
+    /// Tasks with no stored last run are left to the normal schedule. Returns the number of
+    /// catch-up runs sent or queued; runs beyond the first wait for the previous one to finish.
+    pub async fn catch_up(&self, inbound_tx: &mpsc::Sender<InboundMessage>, now_ms: i64) -> usize {
+        let tasks = {
+            let cfg = self.config.read().await;
+            cfg.cron.tasks.clone()
+        };
+        let mut last_fired = self.last_fired.write().await;
+        let mut pending = self.pending.write().await;
+        let mut scheduled = 0;
+
+        for task in tasks {
+            if !task.enabled || task.command.is_empty() {
+                continue;
+            }
+            let key = task_key(&task);
+            let Some(last_ms) = last_fired.get(&key).copied() else {
+                continue;
+            };
+            let missed = missed_runs(&task.schedule, last_ms, now_ms, MAX_CATCH_UP_RUNS);
+            if missed.is_empty() {
+                continue;
+            }
+            let runs = match task.misfire_policy {
+                MisfirePolicy::Skip => 0,
+                MisfirePolicy::RunOnce => 1,
+                MisfirePolicy::RunAll => missed.len(),
+            };
+            info!(
+                schedule = %task.schedule,
+                missed = missed.len(),
+                policy = ?task.misfire_policy,
+                "Config cron: missed runs while not running"
+            );
+            last_fired.insert(key.clone(), now_ms);
+            if runs == 0 {
+                continue;
+            }
+            let queued = if self.dispatch(inbound_tx, &task, &key, true).await {
+                runs - 1
+            } else {
+                runs
             };
+            if queued > 0 {
+                pending.insert(key, queued);
+            }
+            scheduled += runs;
+        }
 
-            // Local wall clock: cron fields match machine local time (see `next_run_ms_after`).
-            let now_ms = Local::now().timestamp_millis();
-            let mut last_fired = self.last_fired.write().await;
+        self.persist(&last_fired);
+        scheduled
+    }
 
-            for task in tasks {
-                if !task.enabled || task.command.is_empty() {
-                    continue;
+    /// Fire every enabled task that is due at `now_ms`, applying its [`CronOverlapPolicy`] when the
+    /// previous run is still in flight, and start queued runs whose predecessor has finished.
+    /// Returns the number of runs sent.
+    pub async fn tick(&self, inbound_tx: &mpsc::Sender<InboundMessage>, now_ms: i64) -> usize {
+        let tasks = {
+            let cfg = self.config.read().await;
+            cfg.cron.tasks.clone()
+        };
+        let mut last_fired = self.last_fired.write().await;
+        let mut pending = self.pending.write().await;
+        let mut fired_any = false;
+        let mut sent = 0;
+
+        for task in tasks {
+            if !task.enabled || task.command.is_empty() {
+                continue;
+            }
+            let key = task_key(&task);
+
+            // A queued run goes first once the previous run has finished.
+            if pending.get(&key).copied().unwrap_or(0) > 0
+                && self.dispatch(inbound_tx, &task, &key, false).await
+            {
+                sent += 1;
+                if let Some(n) = pending.get_mut(&key) {
+                    *n -= 1;
                 }
-                let key = task_key(&task);
-                let after_ms = match last_fired.get(&key).copied() {
-                    Some(ts) => ts,
-                    None => now_ms.saturating_sub(NEVER_FIRED_AFTER_LOOKBACK_MS),
-                };
-                let next_ms = match next_run_ms_after(&task.schedule, after_ms) {
-                    Some(ms) => ms,
-                    None => {
-                        warn!(
-                            schedule = %task.schedule,
-                            "Config cron: invalid schedule, skipping"
-                        );
-                        continue;
-                    }
-                };
-                if now_ms < next_ms {
+            }
+
+            let after_ms = match last_fired.get(&key).copied() {
+                Some(ts) => ts,
+                None => now_ms.saturating_sub(NEVER_FIRED_AFTER_LOOKBACK_MS),
+            };
+            let next_ms = match next_run_ms_after(&task.schedule, after_ms) {
+                Some(ms) => ms,
+                None => {
+                    warn!(
+                        schedule = %task.schedule,
+                        "Config cron: invalid schedule, skipping"
+                    );
                     continue;
                 }
+            };
+            if now_ms < next_ms {
+                continue;
+            }
+            last_fired.insert(key.clone(), now_ms);
+            fired_any = true;
 
-                let chat_id = task
-                    .chat_id
-                    .as_deref()
-                    .unwrap_or(&task.user_id)
-                    .to_string();
-                let msg = InboundMessage {
-                    channel: task.channel.clone(),
-                    sender_id: task.user_id.clone(),
-                    chat_id,
-                    content: task.command.clone(),
-                    timestamp: Utc::now(),
-                    media: vec![],
-                    metadata: serde_json::json!({ "source": "cron", "description": task.description }),
-                };
-                if let Err(e) = inbound_tx.send(msg).await {
-                    warn!("Config cron failed to send task: {e}");
-                } else {
+            if self.dispatch(inbound_tx, &task, &key, false).await {
+                sent += 1;
+                continue;
+            }
+            if !self.runs.is_running(&key) {
+                // Send failed; already logged.
+                continue;
+            }
+            match task.overlap_policy {
+                CronOverlapPolicy::Skip => {
+                    info!(
+                        schedule = %task.schedule,
+                        channel = %task.channel,
+                        "Config cron: previous run still in flight, skipping this fire"
+                    );
+                }
+                CronOverlapPolicy::Queue => {
                     info!(
                         schedule = %task.schedule,
                         channel = %task.channel,
-                        "Config cron task sent"
+                        "Config cron: previous run still in flight, queueing this fire"
                     );
+                    // Coalesce: at most one overlapping fire waits, so a slow task cannot pile up.
+                    pending.entry(key).or_insert(1);
                 }
-                last_fired.insert(key, now_ms);
             }
         }
+
+        pending.retain(|_, n| *n > 0);
+        if fired_any {
+            self.persist(&last_fired);
+        }
+        sent
+    }
+
+    /// Run loop: every 60s read config.cron.tasks, for each enabled task check if due, then send InboundMessage.
+    pub async fn run(&self, inbound_tx: mpsc::Sender<InboundMessage>) -> Result<()> {
+        info!("Config cron runner started");
+        self.catch_up(&inbound_tx, Local::now().timestamp_millis())
+            .await;
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        interval.tick().await; // first tick fires immediately, skip it
+
+        loop {
+            interval.tick().await;
+            // Local wall clock: cron fields match machine local time (see `next_run_ms_after`).
+            self.tick(&inbound_tx, Local::now().timestamp_millis()).await;
+        }
     }
 }
 
@@ -169,4 +419,148 @@ mod tests {
         assert_eq!((next_ok_dt.hour(), next_ok_dt.minute()), (22, 35));
         assert!(next_ok <= during, "today's 22:35:00 should be <= now for firing");
     }
+
+    fn hourly_task(policy: MisfirePolicy) -> CronTaskConfig {
+        CronTaskConfig {
+            schedule: "0 * * * *".to_string(),
+            description: "hourly report".to_string(),
+            enabled: true,
+            command: "report".to_string(),
+            channel: "telegram".to_string(),
+            user_id: "u1".to_string(),
+            chat_id: None,
+            misfire_policy: policy,
+            overlap_policy: CronOverlapPolicy::Skip,
+            role: None,
+        }
+    }
+
+    /// Runner with one hourly task whose stored last run is 10:00, "now" being 13:30 (3 missed runs).
+    fn runner_with_missed_runs(
+        policy: MisfirePolicy,
+        dir: &tempfile::TempDir,
+    ) -> (ConfigCronRunner, i64, PathBuf) {
+        let task = hourly_task(policy);
+        let last = Local
+            .with_ymd_and_hms(2030, 6, 15, 10, 0, 0)
+            .unwrap()
+            .timestamp_millis();
+        let now = Local
+            .with_ymd_and_hms(2030, 6, 15, 13, 30, 0)
+            .unwrap()
+            .timestamp_millis();
+        let state_path = dir.path().join("state.json");
+        save_state(&state_path, &HashMap::from([(task_key(&task), last)])).unwrap();
+
+        let mut config = Config::default();
+        config.cron.tasks.push(task);
+        let runner =
+            ConfigCronRunner::with_state_path(Arc::new(RwLock::new(config)), state_path.clone());
+        (runner, now, state_path)
+    }
+
+    #[test]
+    fn missed_runs_lists_occurrences_between_last_and_now() {
+        let last = Local
+            .with_ymd_and_hms(2030, 6, 15, 10, 0, 0)
+            .unwrap()
+            .timestamp_millis();
+        let now = Local
+            .with_ymd_and_hms(2030, 6, 15, 13, 30, 0)
+            .unwrap()
+            .timestamp_millis();
+        let runs = missed_runs("0 * * * *", last, now, 10);
+        let hours: Vec<u32> = runs
+            .iter()
+            .map(|ms| {
+                DateTime::from_timestamp_millis(*ms)
+                    .unwrap()
+                    .with_timezone(&Local)
+                    .hour()
+            })
+            .collect();
+        assert_eq!(hours, vec![11, 12, 13]);
+        assert_eq!(missed_runs("0 * * * *", last, now, 2).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn catch_up_skip_sends_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let (runner, now, state_path) = runner_with_missed_runs(MisfirePolicy::Skip, &dir);
+        let (tx, mut rx) = mpsc::channel(16);
+
+        assert_eq!(runner.catch_up(&tx, now).await, 0);
+        assert!(rx.try_recv().is_err());
+        let stored = load_state(&state_path).unwrap();
+        assert_eq!(stored.values().copied().collect::<Vec<_>>(), vec![now]);
+    }
+
+    #[tokio::test]
+    async fn catch_up_run_once_sends_single_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let (runner, now, _) = runner_with_missed_runs(MisfirePolicy::RunOnce, &dir);
+        let (tx, mut rx) = mpsc::channel(16);
+
+        assert_eq!(runner.catch_up(&tx, now).await, 1);
+        let msg = rx.try_recv().expect("catch-up message");
+        assert_eq!(msg.content, "report");
+        assert_eq!(msg.metadata["misfire"], true);
+        assert!(rx.try_recv().is_err());
+
+        // Catching up again at the same instant finds nothing missed.
+        assert_eq!(runner.catch_up(&tx, now).await, 0);
+    }
+
+    #[tokio::test]
+    async fn catch_up_run_all_sends_one_per_missed_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let (runner, now, _) = runner_with_missed_runs(MisfirePolicy::RunAll, &dir);
+        let (tx, _rx) = mpsc::channel(16);
+
+        assert_eq!(runner.catch_up(&tx, now).await, 3);
+    }
+
+    /// Every-minute task whose runs take 2.5 minutes: under the skip policy a new run only starts
+    /// after the previous one finished.
+    #[tokio::test]
+    async fn slow_task_runs_never_overlap_under_skip_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut task = hourly_task(MisfirePolicy::Skip);
+        task.schedule = "* * * * *".to_string();
+        let key = task_key(&task);
+        let mut config = Config::default();
+        config.cron.tasks.push(task);
+        let runs = Arc::new(CronRunTracker::new());
+        let runner = ConfigCronRunner::with_state_path(
+            Arc::new(RwLock::new(config)),
+            dir.path().join("state.json"),
+        )
+        .with_run_tracker(Arc::clone(&runs));
+        let (tx, mut rx) = mpsc::channel(16);
+
+        let start = Local
+            .with_ymd_and_hms(2030, 6, 15, 10, 0, 5)
+            .unwrap()
+            .timestamp_millis();
+        let mut in_flight_until: Option<i64> = None;
+        let mut started = Vec::new();
+        for minute in 0..10 {
+            let now = start + minute * 60_000;
+            if in_flight_until.is_some_and(|end| now >= end) {
+                runs.finish(&key);
+                in_flight_until = None;
+            }
+            runner.tick(&tx, now).await;
+            while let Ok(msg) = rx.try_recv() {
+                assert_eq!(msg.metadata[CRON_TASK_META], key.as_str());
+                assert!(
+                    in_flight_until.is_none(),
+                    "run started at minute {minute} while the previous one was in flight"
+                );
+                in_flight_until = Some(now + 150_000);
+                started.push(minute);
+            }
+        }
+        assert_eq!(started, vec![0, 3, 6, 9]);
+    }
 }
@@ -1,27 +1,265 @@
 //! Heartbeat service — periodic execution of config.heartbeat tasks; results sent to channel/userId.
+//!
+//! Tasks of type `health_report` skip the agent: the service gathers a [`HealthReport`] from
+//! [`HealthSources`] and posts it to the task's chat directly.
 
 use anyhow::Result;
-use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{mpsc, RwLock};
-use tracing::info;
+use tokio::sync::{broadcast, mpsc, Notify, RwLock};
+use tracing::{info, warn};
 
-use crate::bus::InboundMessage;
-use crate::config::Config;
+use crate::bus::{DeadLetterQueue, InboundMessage, OutboundMessage};
+use crate::config::{Config, HeartbeatOnError, HeartbeatTask, HeartbeatTaskKind};
+use crate::sandbox::SandboxManager;
+use crate::tools::approval::ApprovalManager;
+use crate::web::log_buffer::SharedLogBuffer;
+
+/// Error log lines included in a health report.
+const HEALTH_REPORT_ERRORS: usize = 3;
+
+/// Longest error message (chars) quoted in a health report.
+const HEALTH_REPORT_ERROR_CHARS: usize = 160;
+
+/// Runtime control shared between the heartbeat loop and the web API / CLI: pause, resume,
+/// and trigger an immediate run.
+#[derive(Default)]
+pub struct HeartbeatControl {
+    paused: AtomicBool,
+    run_now: Notify,
+}
+
+impl HeartbeatControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skip scheduled runs until [resume](Self::resume) is called.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Run all tasks now instead of waiting for the next interval. Works while paused; if the
+    /// loop is not currently waiting, the next wait returns immediately.
+    pub fn run_now(&self) {
+        self.run_now.notify_one();
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Health report
+// ---------------------------------------------------------------------------
+
+/// Subsystems read by `health_report` tasks. Sources left unset are reported as empty.
+#[derive(Clone, Default)]
+pub struct HealthSources {
+    pub dead_letters: Option<Arc<DeadLetterQueue>>,
+    pub approvals: Option<Arc<ApprovalManager>>,
+    pub sandboxes: Option<Arc<SandboxManager>>,
+    pub log_buffer: Option<SharedLogBuffer>,
+}
+
+/// Compact system status posted by `health_report` heartbeat tasks.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HealthReport {
+    /// Enabled channels that report their connection state and are not down.
+    pub channels_up: Vec<String>,
+    /// Channels in cooldown or disconnected, with the reason.
+    pub channels_down: Vec<(String, String)>,
+    /// Enabled channels that do not report their connection state (see [`DeadLetterQueue`]).
+    pub channels_unknown: Vec<String>,
+    pub pending_approvals: usize,
+    /// Age of the oldest pending approval.
+    pub oldest_pending_approval: Option<Duration>,
+    /// `(sandbox id, healthy, message)` of each active sandbox.
+    pub sandboxes: Vec<(String, bool, String)>,
+    /// Latest error log lines, newest first.
+    pub recent_errors: Vec<String>,
+}
+
+impl HealthSources {
+    /// Gather the current status; `enabled_channels` are the configured channel names.
+    pub async fn report(&self, enabled_channels: &[String]) -> HealthReport {
+        let down = self
+            .dead_letters
+            .as_ref()
+            .map(|d| d.down_channels())
+            .unwrap_or_default();
+        let (channels_up, channels_unknown): (Vec<String>, Vec<String>) = enabled_channels
+            .iter()
+            .filter(|name| !down.contains_key(*name))
+            .cloned()
+            .partition(|name| self.dead_letters.as_ref().is_some_and(|d| d.reports_state(name)));
+        let mut channels_down: Vec<_> = down.into_iter().collect();
+        channels_down.sort();
+
+        let (pending_approvals, oldest_pending_approval) = match &self.approvals {
+            Some(approvals) => {
+                let stats = approvals.stats().await;
+                (stats.pending, stats.oldest_pending_age)
+            }
+            None => (0, None),
+        };
+
+        let sandboxes = match &self.sandboxes {
+            Some(manager) => manager
+                .health_checks()
+                .await
+                .into_iter()
+                .map(|(id, health)| (id, health.healthy, health.message))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let recent_errors = match &self.log_buffer {
+            Some(buffer) => buffer
+                .read()
+                .await
+                .get_filtered(Some("ERROR"), None)
+                .into_iter()
+                .take(HEALTH_REPORT_ERRORS)
+                .map(|e| {
+                    let mut message: String =
+                        e.message.chars().take(HEALTH_REPORT_ERROR_CHARS).collect();
+                    if e.message.chars().count() > HEALTH_REPORT_ERROR_CHARS {
+                        message.push('…');
+                    }
+                    format!(
+                        "{} {}",
+                        e.timestamp.with_timezone(&chrono::Local).format("%H:%M:%S"),
+                        message
+                    )
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        HealthReport {
+            channels_up,
+            channels_down,
+            channels_unknown,
+            pending_approvals,
+            oldest_pending_approval,
+            sandboxes,
+            recent_errors,
+        }
+    }
+}
+
+/// `90s` -> `1m`, `7200s` -> `2h`: the largest whole unit.
+fn short_age(age: Duration) -> String {
+    match age.as_secs() {
+        s if s >= 3600 => format!("{}h", s / 3600),
+        s if s >= 60 => format!("{}m", s / 60),
+        s => format!("{}s", s),
+    }
+}
+
+impl HealthReport {
+    /// Chat text of the report, one line per subsystem.
+    pub fn render(&self) -> String {
+        let mut lines = vec!["Health report".to_string()];
+
+        let mut channels = format!("Channels: {} up", self.channels_up.len());
+        if !self.channels_up.is_empty() {
+            channels.push_str(&format!(" ({})", self.channels_up.join(", ")));
+        }
+        if !self.channels_down.is_empty() {
+            let down: Vec<String> = self
+                .channels_down
+                .iter()
+                .map(|(name, reason)| format!("{} ({})", name, reason))
+                .collect();
+            channels.push_str(&format!("; down: {}", down.join(", ")));
+        }
+        if !self.channels_unknown.is_empty() {
+            channels.push_str(&format!("; state not reported: {}", self.channels_unknown.join(", ")));
+        }
+        lines.push(channels);
+
+        let mut approvals = format!("Approvals: {} pending", self.pending_approvals);
+        if let Some(age) = self.oldest_pending_approval {
+            approvals.push_str(&format!(" (oldest {})", short_age(age)));
+        }
+        lines.push(approvals);
+
+        let unhealthy: Vec<String> = self
+            .sandboxes
+            .iter()
+            .filter(|(_, healthy, _)| !healthy)
+            .map(|(id, _, message)| format!("{} ({})", id, message))
+            .collect();
+        let mut sandboxes = format!("Sandboxes: {} healthy", self.sandboxes.len() - unhealthy.len());
+        if !unhealthy.is_empty() {
+            sandboxes.push_str(&format!("; unhealthy: {}", unhealthy.join(", ")));
+        }
+        lines.push(sandboxes);
+
+        if self.recent_errors.is_empty() {
+            lines.push("Recent errors: none".to_string());
+        } else {
+            lines.push("Recent errors:".to_string());
+            lines.extend(self.recent_errors.iter().map(|e| format!("- {}", e)));
+        }
+        lines.join("\n")
+    }
+}
+
+// ---------------------------------------------------------------------------
+// HeartbeatService
+// ---------------------------------------------------------------------------
 
 pub struct HeartbeatService {
     config: Arc<RwLock<Config>>,
+    control: Arc<HeartbeatControl>,
+    /// Where `health_report` tasks post; without it those tasks are skipped.
+    outbound_tx: Option<broadcast::Sender<OutboundMessage>>,
+    health: HealthSources,
 }
 
 impl HeartbeatService {
     /// Create a heartbeat service that reads `config.heartbeat` each interval.
     pub fn new(config: Arc<RwLock<Config>>) -> Self {
-        Self { config }
+        Self::with_control(config, Arc::new(HeartbeatControl::new()))
+    }
+
+    /// Like [new](Self::new), with a control handle shared with the web API.
+    pub fn with_control(config: Arc<RwLock<Config>>, control: Arc<HeartbeatControl>) -> Self {
+        Self {
+            config,
+            control,
+            outbound_tx: None,
+            health: HealthSources::default(),
+        }
+    }
+
+    /// Enable `health_report` tasks: reports are built from `sources` and posted on `outbound_tx`.
+    pub fn with_health_report(
+        mut self,
+        outbound_tx: broadcast::Sender<OutboundMessage>,
+        sources: HealthSources,
+    ) -> Self {
+        self.outbound_tx = Some(outbound_tx);
+        self.health = sources;
+        self
+    }
+
+    pub fn control(&self) -> &Arc<HeartbeatControl> {
+        &self.control
     }
 
     /// Run the heartbeat loop: every `heartbeat.interval` seconds, read tasks from config
     /// and send each task as an InboundMessage so the agent runs it and replies to the task's channel/chat_id.
+    /// Scheduled runs are skipped while paused; [HeartbeatControl::run_now] runs immediately.
     pub async fn run(&self, inbound_tx: mpsc::Sender<InboundMessage>) -> Result<()> {
         loop {
             let (enabled, interval_secs, tasks) = {
@@ -47,17 +285,46 @@ impl HeartbeatService {
                 tasks.len()
             );
 
-            tokio::time::sleep(interval).await;
+            let forced = tokio::select! {
+                _ = tokio::time::sleep(interval) => false,
+                _ = self.control.run_now.notified() => true,
+            };
+            if forced {
+                info!("Heartbeat run triggered manually");
+            } else if self.control.is_paused() {
+                info!("Heartbeat paused; skipping scheduled run");
+                continue;
+            }
 
-            let (enabled2, tasks2) = {
+            let (enabled2, tasks2, alert) = {
                 let cfg = self.config.read().await;
-                (cfg.heartbeat.enabled, cfg.heartbeat.tasks.clone())
+                let alert = cfg
+                    .heartbeat
+                    .alert_channel
+                    .clone()
+                    .zip(cfg.heartbeat.alert_chat_id.clone());
+                (cfg.heartbeat.enabled, cfg.heartbeat.tasks.clone(), alert)
             };
             if !enabled2 || tasks2.is_empty() {
                 continue;
             }
 
             for task in &tasks2 {
+                if task.kind == HeartbeatTaskKind::HealthReport {
+                    self.post_health_report(task).await;
+                    continue;
+                }
+                let mut metadata = serde_json::json!({
+                    "source": "heartbeat",
+                    "on_error": task.on_error,
+                });
+                if let Some(ref role) = task.role {
+                    metadata["role"] = serde_json::Value::String(role.clone());
+                }
+                if let (HeartbeatOnError::System, Some((channel, chat_id))) = (task.on_error, &alert) {
+                    metadata["alert_channel"] = serde_json::Value::String(channel.clone());
+                    metadata["alert_chat_id"] = serde_json::Value::String(chat_id.clone());
+                }
                 let msg = InboundMessage {
                     channel: task.channel.clone(),
                     sender_id: task.user_id.clone(),
@@ -65,7 +332,7 @@ impl HeartbeatService {
                     content: task.target.clone(),
                     timestamp: chrono::Utc::now(),
                     media: vec![],
-                    metadata: serde_json::json!({ "source": "heartbeat" }),
+                    metadata,
                 };
                 if let Err(e) = inbound_tx.send(msg).await {
                     tracing::error!("Heartbeat failed to send task to bus: {e}");
@@ -80,4 +347,189 @@ impl HeartbeatService {
             }
         }
     }
+
+    /// Build a health report and post it to the task's chat.
+    async fn post_health_report(&self, task: &HeartbeatTask) {
+        let Some(outbound_tx) = &self.outbound_tx else {
+            warn!(channel = %task.channel, "Heartbeat health_report task skipped: no outbound sender");
+            return;
+        };
+        let enabled_channels: Vec<String> = {
+            let cfg = self.config.read().await;
+            cfg.enabled_channels()
+                .into_iter()
+                .map(|(name, _)| name.to_string())
+                .collect()
+        };
+        let report = self.health.report(&enabled_channels).await;
+        let msg = OutboundMessage::chat(
+            task.channel.clone(),
+            task.chat_id.clone(),
+            report.render(),
+            vec![],
+            None,
+        );
+        if outbound_tx.send(msg).is_err() {
+            tracing::error!("Heartbeat failed to post health report: no outbound subscribers");
+        } else {
+            info!(channel = %task.channel, chat_id = %task.chat_id, "Heartbeat health report sent");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HeartbeatTask;
+
+    fn config_with_task(interval: u64) -> Arc<RwLock<Config>> {
+        let mut cfg = Config::default();
+        cfg.heartbeat.interval = interval;
+        cfg.heartbeat.tasks = vec![HeartbeatTask {
+            channel: "telegram".to_string(),
+            chat_id: "c1".to_string(),
+            user_id: "u1".to_string(),
+            target: "check disk usage".to_string(),
+            kind: HeartbeatTaskKind::Agent,
+            on_error: crate::config::HeartbeatOnError::Silent,
+            role: None,
+        }];
+        Arc::new(RwLock::new(cfg))
+    }
+
+    fn spawn_service(service: HeartbeatService) -> mpsc::Receiver<InboundMessage> {
+        let (tx, rx) = mpsc::channel(16);
+        tokio::spawn(async move { service.run(tx).await });
+        rx
+    }
+
+    #[tokio::test]
+    async fn paused_heartbeat_does_not_fire() {
+        let service = HeartbeatService::new(config_with_task(1));
+        let control = Arc::clone(service.control());
+        control.pause();
+        let mut rx = spawn_service(service);
+
+        let got = tokio::time::timeout(Duration::from_millis(2500), rx.recv()).await;
+        assert!(got.is_err(), "paused heartbeat must not send tasks");
+
+        control.resume();
+        let msg = tokio::time::timeout(Duration::from_secs(3), rx.recv())
+            .await
+            .expect("resumed heartbeat fires")
+            .unwrap();
+        assert_eq!(msg.content, "check disk usage");
+    }
+
+    #[tokio::test]
+    async fn run_now_triggers_immediately() {
+        let service = HeartbeatService::new(config_with_task(3600));
+        let control = Arc::clone(service.control());
+        control.pause();
+        let mut rx = spawn_service(service);
+
+        control.run_now();
+        let msg = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .expect("run-now fires without waiting for the interval")
+            .unwrap();
+        assert_eq!(msg.chat_id, "c1");
+        assert_eq!(msg.metadata["source"], "heartbeat");
+        assert_eq!(msg.metadata["on_error"], "silent");
+        assert!(msg.metadata.get("alert_chat_id").is_none());
+    }
+
+    #[tokio::test]
+    async fn system_on_error_carries_the_alert_chat() {
+        let config = config_with_task(3600);
+        {
+            let mut cfg = config.write().await;
+            cfg.heartbeat.tasks[0].on_error = HeartbeatOnError::System;
+            cfg.heartbeat.alert_channel = Some("discord".to_string());
+            cfg.heartbeat.alert_chat_id = Some("ops".to_string());
+        }
+        let service = HeartbeatService::new(config);
+        let control = Arc::clone(service.control());
+        control.pause();
+        let mut rx = spawn_service(service);
+
+        control.run_now();
+        let msg = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .expect("run-now fires")
+            .unwrap();
+        assert_eq!(msg.metadata["on_error"], "system");
+        assert_eq!(msg.metadata["alert_channel"], "discord");
+        assert_eq!(msg.metadata["alert_chat_id"], "ops");
+    }
+
+    #[tokio::test]
+    async fn health_report_aggregates_subsystems() {
+        use crate::web::log_buffer::{create_log_buffer, LogEntry};
+        use tracing::Level;
+
+        let (outbound_tx, _outbound_rx) = broadcast::channel(16);
+        let dead_letters = Arc::new(DeadLetterQueue::new(outbound_tx, 10));
+        dead_letters.mark_down("irc", "connection refused");
+        dead_letters.mark_up("telegram");
+        let approvals = Arc::new(ApprovalManager::new());
+        let pending = Arc::clone(&approvals);
+        tokio::spawn(async move {
+            pending
+                .request_approval(
+                    "s1".into(),
+                    "telegram".into(),
+                    "c1".into(),
+                    "rm -rf build".into(),
+                    "/tmp".into(),
+                    String::new(),
+                    60,
+                    None,
+                )
+                .await
+        });
+        let log_buffer = create_log_buffer(10);
+        {
+            let mut logs = log_buffer.write().await;
+            logs.push(LogEntry::new(Level::ERROR, "synbot".into(), "first failure".into()));
+            logs.push(LogEntry::new(Level::WARN, "synbot".into(), "just a warning".into()));
+            logs.push(LogEntry::new(Level::ERROR, "synbot".into(), "second failure".into()));
+        }
+        let sources = HealthSources {
+            dead_letters: Some(dead_letters),
+            approvals: Some(Arc::clone(&approvals)),
+            sandboxes: Some(Arc::new(SandboxManager::with_defaults())),
+            log_buffer: Some(log_buffer),
+        };
+        while approvals.stats().await.pending == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let report = sources
+            .report(&["telegram".to_string(), "irc".to_string(), "slack".to_string()])
+            .await;
+        assert_eq!(report.channels_up, vec!["telegram".to_string()]);
+        assert_eq!(
+            report.channels_down,
+            vec![("irc".to_string(), "connection refused".to_string())]
+        );
+        assert_eq!(report.channels_unknown, vec!["slack".to_string()], "slack never reported");
+        assert_eq!(report.pending_approvals, 1);
+        assert!(report.oldest_pending_approval.is_some());
+        assert!(report.sandboxes.is_empty());
+        assert_eq!(report.recent_errors.len(), 2, "warnings are left out");
+        assert!(report.recent_errors[0].ends_with("second failure"), "newest first");
+
+        let text = report.render();
+        assert!(
+            text.contains("Channels: 1 up (telegram); down: irc (connection refused); state not reported: slack"),
+            "{}",
+            text
+        );
+        assert!(text.contains("Approvals: 1 pending"), "{}", text);
+        assert!(text.contains("Sandboxes: 0 healthy"), "{}", text);
+        assert!(text.contains("first failure"), "{}", text);
+
+        assert_eq!(HealthSources::default().report(&[]).await, HealthReport::default());
+    }
 }
@@ -112,6 +112,7 @@
 pub mod agent;
 pub mod appcontainer_dns;
 pub mod background;
+pub mod build_info;
 pub mod bus;
 pub mod workflow;
 pub mod channels;
@@ -121,10 +122,16 @@ pub mod config;
 pub mod cron;
 pub mod heartbeat;
 pub mod logging;
+#[cfg(feature = "mock-provider")]
+pub mod mock_provider;
+#[cfg(feature = "test-harness")]
+pub mod bus_test_harness;
 pub mod plugin;
 pub mod rig_provider;
 pub mod sandbox;
 pub mod security;
+pub mod seed;
+pub mod telemetry;
 pub mod tools;
 pub mod url_utils;
 pub mod web;
@@ -1,10 +1,11 @@
 //! Logging initialization and configuration.
 
 use anyhow::Result;
+use std::collections::BTreeMap;
 use std::io::{self, Write};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock};
 use tracing::field::Visit;
-use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use tracing_subscriber::{fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Registry};
 use tracing_subscriber::fmt::time::{ChronoLocal, ChronoUtc, FormatTime};
 use tracing_subscriber::Layer;
 
@@ -15,31 +16,76 @@ use crate::security::SecretMaskerLayer;
 /// When provided, a layer is added that forwards each event to the buffer.
 pub type LogBufferTx = Option<Arc<tokio::sync::mpsc::Sender<crate::web::log_buffer::LogEntry>>>;
 
-/// Visitor to capture the "message" field from a tracing event.
-struct MessageVisitor(String);
+/// Visitor to capture the "message" field and the other fields of a tracing event as JSON values.
+#[derive(Default)]
+struct EventVisitor {
+    message: String,
+    fields: BTreeMap<String, serde_json::Value>,
+}
 
-impl Visit for MessageVisitor {
-    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+impl EventVisitor {
+    fn record_value(&mut self, field: &tracing::field::Field, value: serde_json::Value) {
         if field.name() == "message" {
-            self.0 = value.to_string();
+            self.message = match value {
+                serde_json::Value::String(s) => s,
+                other => other.to_string(),
+            };
+        } else {
+            self.fields.insert(field.name().to_string(), value);
         }
     }
+}
+
+impl Visit for EventVisitor {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.record_value(field, value.into());
+    }
+
+    fn record_i64(&mut self, field: &tracing::field::Field, value: i64) {
+        self.record_value(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+        self.record_value(field, value.into());
+    }
+
+    fn record_f64(&mut self, field: &tracing::field::Field, value: f64) {
+        self.record_value(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &tracing::field::Field, value: bool) {
+        self.record_value(field, value.into());
+    }
+
+    fn record_error(&mut self, field: &tracing::field::Field, value: &(dyn std::error::Error + 'static)) {
+        self.record_value(field, value.to_string().into());
+    }
 
     fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
-        if field.name() == "message" {
-            self.0 = format!("{:?}", value);
-        }
+        self.record_value(field, format!("{:?}", value).into());
     }
 }
 
-/// Layer that forwards tracing events to the in-memory log buffer for the web UI.
+/// Layer that forwards tracing events to the in-memory log buffer for the web UI. The message
+/// and every field value go through `masker`, as the file and stdout writers do.
 struct LogBufferLayer {
     tx: LogBufferTx,
+    masker: Arc<SecretMaskerLayer>,
 }
 
 impl LogBufferLayer {
-    fn new(tx: LogBufferTx) -> Self {
-        Self { tx }
+    fn new(tx: LogBufferTx, masker: Arc<SecretMaskerLayer>) -> Self {
+        Self { tx, masker }
+    }
+}
+
+/// Mask every string in a buffered field value.
+fn mask_field(value: &mut serde_json::Value, masker: &SecretMaskerLayer) {
+    match value {
+        serde_json::Value::String(s) => *s = masker.mask(s),
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|v| mask_field(v, masker)),
+        serde_json::Value::Object(map) => map.values_mut().for_each(|v| mask_field(v, masker)),
+        _ => {}
     }
 }
 
@@ -56,13 +102,16 @@ where
             Some(t) => t,
             None => return,
         };
-        let mut visitor = MessageVisitor(String::new());
+        let mut visitor = EventVisitor::default();
         event.record(&mut visitor);
-        let message = if visitor.0.is_empty() {
+        let message = if visitor.message.is_empty() {
             event.metadata().name().to_string()
         } else {
-            visitor.0
+            self.masker.mask(&visitor.message)
         };
+        for value in visitor.fields.values_mut() {
+            mask_field(value, &self.masker);
+        }
         let level = *event.metadata().level();
         let target = event.metadata().target().to_string();
         let entry = crate::web::log_buffer::LogEntry {
@@ -70,11 +119,90 @@ where
             level: level.to_string(),
             target,
             message,
+            fields: visitor.fields,
         };
         let _ = tx.try_send(entry);
     }
 }
 
+/// The filter layer of the installed subscriber; it can be swapped while running.
+type FilterLayer = reload::Layer<EnvFilter, Registry>;
+
+/// Levels the log filter is built from: `synbot` / `open_lark` at `base`, plus per-module levels.
+#[derive(Clone)]
+struct LogLevels {
+    base: &'static str,
+    modules: BTreeMap<String, &'static str>,
+}
+
+impl LogLevels {
+    fn directives(&self) -> String {
+        let mut filter = format!("synbot={},open_lark={}", self.base, self.base);
+        for (module, level) in &self.modules {
+            filter.push_str(&format!(",{}={}", module, level));
+        }
+        filter
+    }
+}
+
+/// Changes the log filter installed by [`init_logging`] while running (`POST /api/log/level`).
+/// Changes are not written to the config and last until restart.
+pub struct LogLevelControl {
+    handle: reload::Handle<EnvFilter, Registry>,
+    levels: Mutex<LogLevels>,
+}
+
+impl LogLevelControl {
+    fn new(handle: reload::Handle<EnvFilter, Registry>, levels: LogLevels) -> Self {
+        Self {
+            handle,
+            levels: Mutex::new(levels),
+        }
+    }
+
+    /// Set `level` for `module` (a target such as `synbot::channels`), or for synbot's own logs
+    /// when `module` is `None`. Returns the filter now in effect.
+    pub fn set_level(&self, level: &str, module: Option<&str>) -> Result<String> {
+        let level = parse_log_level(level)?;
+        let mut levels = self.levels.lock().unwrap_or_else(|e| e.into_inner());
+        let mut updated = levels.clone();
+        match module.map(str::trim).filter(|m| !m.is_empty()) {
+            Some(module) => {
+                if !module
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | ':'))
+                {
+                    anyhow::bail!("Invalid module: {} (expected a target like synbot::channels)", module);
+                }
+                updated.modules.insert(module.to_string(), level);
+            }
+            None => updated.base = level,
+        }
+        let directives = updated.directives();
+        self.handle
+            .reload(EnvFilter::new(&directives))
+            .map_err(|e| anyhow::anyhow!("Failed to reload log filter: {}", e))?;
+        *levels = updated;
+        Ok(directives)
+    }
+}
+
+static LOG_LEVEL_CONTROL: OnceLock<LogLevelControl> = OnceLock::new();
+
+/// Runtime log level control; `None` until [`init_logging`] has run.
+pub fn log_level_control() -> Option<&'static LogLevelControl> {
+    LOG_LEVEL_CONTROL.get()
+}
+
+/// Base level set on the command line (`-v`/`--verbose`); raises `log.level` for this run.
+static LEVEL_OVERRIDE: OnceLock<&'static str> = OnceLock::new();
+
+/// Use `level` instead of the configured `log.level` when it is more verbose, when
+/// [`init_logging`] runs. Call before it.
+pub fn set_level_override(level: &'static str) {
+    let _ = LEVEL_OVERRIDE.set(level);
+}
+
 /// A writer wrapper that applies `SecretMaskerLayer::mask()` to every write
 /// before forwarding to the inner writer.  Used to sanitise file and stdout
 /// log output at the byte level.
@@ -126,27 +254,33 @@ pub fn init_logging(cfg: &Config, buffer_tx: LogBufferTx) -> Result<()> {
     let masker = Arc::new(SecretMaskerLayer::new());
     masker.load_config_secrets(cfg);
 
-    // Parse log level
-    let level = parse_log_level(&cfg.log.level)?;
+    // Parse log level (a `-v` override only ever raises it)
+    let configured = parse_log_level(&cfg.log.level)?;
+    let level = match LEVEL_OVERRIDE.get() {
+        Some(level) => more_verbose(level, configured),
+        None => configured,
+    };
     
     // Create log directory
     let log_dir = log_dir_path(cfg);
     std::fs::create_dir_all(&log_dir)?;
     
     // Build env filter with module-specific levels
+    let levels = LogLevels {
+        base: level,
+        modules: cfg
+            .log
+            .module_levels
+            .iter()
+            .filter_map(|(module, module_level)| {
+                parse_log_level(module_level).ok().map(|l| (module.clone(), l))
+            })
+            .collect(),
+    };
     let env_filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| {
-            let mut filter_str = format!("synbot={},open_lark={}", level, level);
-            
-            // Add module-specific levels
-            for (module, module_level) in &cfg.log.module_levels {
-                if let Ok(parsed_level) = parse_log_level(module_level) {
-                    filter_str.push_str(&format!(",{}={}", module, parsed_level));
-                }
-            }
-            
-            EnvFilter::new(filter_str)
-        });
+        .unwrap_or_else(|_| EnvFilter::new(levels.directives()));
+    let (env_filter, filter_handle) = reload::Layer::new(env_filter);
+    let _ = LOG_LEVEL_CONTROL.set(LogLevelControl::new(filter_handle, levels));
     
     // File name: synbot.yyyy-MM-dd.log or synbot.yyyy-MM-dd.json (date in the middle, suffix at the end)
     let log_file_suffix = match cfg.log.format.to_lowercase().as_str() {
@@ -209,12 +343,13 @@ fn create_timer(timestamp_format: &str, custom_format: &Option<String>) -> Resul
 
 fn init_json_logging(
     cfg: &Config,
-    env_filter: EnvFilter,
+    env_filter: FilterLayer,
     non_blocking: tracing_appender::non_blocking::NonBlocking,
     timestamp_format: &str,
     buffer_tx: LogBufferTx,
     masker: Arc<SecretMaskerLayer>,
 ) -> Result<()> {
+    let buffer_layer = LogBufferLayer::new(buffer_tx, masker.clone());
     match timestamp_format {
         "rfc3339" => {
             let masker2 = masker.clone();
@@ -242,7 +377,7 @@ fn init_json_logging(
             
             tracing_subscriber::registry()
                 .with(env_filter)
-                .with(LogBufferLayer::new(buffer_tx))
+                .with(buffer_layer)
                 .with(file_layer)
                 .with(stdout_layer)
                 .init();
@@ -273,7 +408,7 @@ fn init_json_logging(
             
             tracing_subscriber::registry()
                 .with(env_filter)
-                .with(LogBufferLayer::new(buffer_tx.clone()))
+                .with(buffer_layer)
                 .with(file_layer)
                 .with(stdout_layer)
                 .init();
@@ -309,7 +444,7 @@ fn init_json_logging(
             
             tracing_subscriber::registry()
                 .with(env_filter)
-                .with(LogBufferLayer::new(buffer_tx.clone()))
+                .with(buffer_layer)
                 .with(file_layer)
                 .with(stdout_layer)
                 .init();
@@ -340,7 +475,7 @@ fn init_json_logging(
             
             tracing_subscriber::registry()
                 .with(env_filter)
-                .with(LogBufferLayer::new(buffer_tx.clone()))
+                .with(buffer_layer)
                 .with(file_layer)
                 .with(stdout_layer)
                 .init();
@@ -352,12 +487,13 @@ fn init_json_logging(
 
 fn init_compact_logging(
     cfg: &Config,
-    env_filter: EnvFilter,
+    env_filter: FilterLayer,
     non_blocking: tracing_appender::non_blocking::NonBlocking,
     timestamp_format: &str,
     buffer_tx: LogBufferTx,
     masker: Arc<SecretMaskerLayer>,
 ) -> Result<()> {
+    let buffer_layer = LogBufferLayer::new(buffer_tx, masker.clone());
     match timestamp_format {
         "rfc3339" => {
             let masker2 = masker.clone();
@@ -386,7 +522,7 @@ fn init_compact_logging(
             
             tracing_subscriber::registry()
                 .with(env_filter)
-                .with(LogBufferLayer::new(buffer_tx.clone()))
+                .with(buffer_layer)
                 .with(file_layer)
                 .with(stdout_layer)
                 .init();
@@ -418,7 +554,7 @@ fn init_compact_logging(
             
             tracing_subscriber::registry()
                 .with(env_filter)
-                .with(LogBufferLayer::new(buffer_tx.clone()))
+                .with(buffer_layer)
                 .with(file_layer)
                 .with(stdout_layer)
                 .init();
@@ -455,7 +591,7 @@ fn init_compact_logging(
             
             tracing_subscriber::registry()
                 .with(env_filter)
-                .with(LogBufferLayer::new(buffer_tx.clone()))
+                .with(buffer_layer)
                 .with(file_layer)
                 .with(stdout_layer)
                 .init();
@@ -487,7 +623,7 @@ fn init_compact_logging(
             
             tracing_subscriber::registry()
                 .with(env_filter)
-                .with(LogBufferLayer::new(buffer_tx.clone()))
+                .with(buffer_layer)
                 .with(file_layer)
                 .with(stdout_layer)
                 .init();
@@ -499,12 +635,13 @@ fn init_compact_logging(
 
 fn init_pretty_logging(
     cfg: &Config,
-    env_filter: EnvFilter,
+    env_filter: FilterLayer,
     non_blocking: tracing_appender::non_blocking::NonBlocking,
     timestamp_format: &str,
     buffer_tx: LogBufferTx,
     masker: Arc<SecretMaskerLayer>,
 ) -> Result<()> {
+    let buffer_layer = LogBufferLayer::new(buffer_tx, masker.clone());
     match timestamp_format {
         "rfc3339" => {
             let masker2 = masker.clone();
@@ -532,7 +669,7 @@ fn init_pretty_logging(
             
             tracing_subscriber::registry()
                 .with(env_filter)
-                .with(LogBufferLayer::new(buffer_tx.clone()))
+                .with(buffer_layer)
                 .with(file_layer)
                 .with(stdout_layer)
                 .init();
@@ -563,7 +700,7 @@ fn init_pretty_logging(
             
             tracing_subscriber::registry()
                 .with(env_filter)
-                .with(LogBufferLayer::new(buffer_tx.clone()))
+                .with(buffer_layer)
                 .with(file_layer)
                 .with(stdout_layer)
                 .init();
@@ -599,7 +736,7 @@ fn init_pretty_logging(
             
             tracing_subscriber::registry()
                 .with(env_filter)
-                .with(LogBufferLayer::new(buffer_tx.clone()))
+                .with(buffer_layer)
                 .with(file_layer)
                 .with(stdout_layer)
                 .init();
@@ -630,7 +767,7 @@ fn init_pretty_logging(
             
             tracing_subscriber::registry()
                 .with(env_filter)
-                .with(LogBufferLayer::new(buffer_tx.clone()))
+                .with(buffer_layer)
                 .with(file_layer)
                 .with(stdout_layer)
                 .init();
@@ -642,12 +779,13 @@ fn init_pretty_logging(
 
 fn init_text_logging(
     cfg: &Config,
-    env_filter: EnvFilter,
+    env_filter: FilterLayer,
     non_blocking: tracing_appender::non_blocking::NonBlocking,
     timestamp_format: &str,
     buffer_tx: LogBufferTx,
     masker: Arc<SecretMaskerLayer>,
 ) -> Result<()> {
+    let buffer_layer = LogBufferLayer::new(buffer_tx, masker.clone());
     match timestamp_format {
         "rfc3339" => {
             let masker2 = masker.clone();
@@ -674,7 +812,7 @@ fn init_text_logging(
             
             tracing_subscriber::registry()
                 .with(env_filter)
-                .with(LogBufferLayer::new(buffer_tx.clone()))
+                .with(buffer_layer)
                 .with(file_layer)
                 .with(stdout_layer)
                 .init();
@@ -704,7 +842,7 @@ fn init_text_logging(
             
             tracing_subscriber::registry()
                 .with(env_filter)
-                .with(LogBufferLayer::new(buffer_tx.clone()))
+                .with(buffer_layer)
                 .with(file_layer)
                 .with(stdout_layer)
                 .init();
@@ -739,7 +877,7 @@ fn init_text_logging(
             
             tracing_subscriber::registry()
                 .with(env_filter)
-                .with(LogBufferLayer::new(buffer_tx.clone()))
+                .with(buffer_layer)
                 .with(file_layer)
                 .with(stdout_layer)
                 .init();
@@ -769,7 +907,7 @@ fn init_text_logging(
             
             tracing_subscriber::registry()
                 .with(env_filter)
-                .with(LogBufferLayer::new(buffer_tx.clone()))
+                .with(buffer_layer)
                 .with(file_layer)
                 .with(stdout_layer)
                 .init();
@@ -791,6 +929,17 @@ fn parse_log_level(level_str: &str) -> Result<&'static str> {
     }
 }
 
+/// The more verbose of two levels returned by [`parse_log_level`].
+fn more_verbose(a: &'static str, b: &'static str) -> &'static str {
+    const ORDER: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+    let rank = |level: &str| ORDER.iter().position(|l| *l == level).unwrap_or(0);
+    if rank(a) >= rank(b) {
+        a
+    } else {
+        b
+    }
+}
+
 /// Initialize simple logging for commands that don't load config.
 pub fn init_simple_logging() {
     tracing_subscriber::fmt()
@@ -800,3 +949,105 @@ pub fn init_simple_logging() {
         )
         .init();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffered_entry_keeps_event_fields_structured() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+        let subscriber = tracing_subscriber::registry()
+            .with(LogBufferLayer::new(Some(Arc::new(tx)), Arc::new(SecretMaskerLayer::new())));
+        tracing::subscriber::with_default(subscriber, || {
+            let delay = std::time::Duration::from_secs(2);
+            tracing::warn!(chat_id = "c1", attempts = 3, retry = true, delay = ?delay, "send failed");
+        });
+
+        let entry = rx.try_recv().unwrap();
+        assert_eq!(entry.level, "WARN");
+        assert_eq!(entry.message, "send failed");
+        assert_eq!(entry.fields["chat_id"], "c1");
+        assert_eq!(entry.fields["attempts"], 3);
+        assert_eq!(entry.fields["retry"], true);
+        assert_eq!(entry.fields["delay"], "2s");
+        assert!(!entry.fields.contains_key("message"));
+
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["message"], "send failed");
+        assert_eq!(json["fields"]["attempts"], 3);
+    }
+
+    #[test]
+    fn buffered_entry_masks_message_and_field_values() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+        let subscriber = tracing_subscriber::registry()
+            .with(LogBufferLayer::new(Some(Arc::new(tx)), Arc::new(SecretMaskerLayer::new())));
+        tracing::subscriber::with_default(subscriber, || {
+            let args = serde_json::json!({ "command": "curl -H 'Authorization: sk-abcdef123456' api" });
+            tracing::debug!(args = ?args, token = "ghp_0123456789abcdef", "using key sk-abcdef123456");
+        });
+
+        let entry = rx.try_recv().unwrap();
+        for text in [
+            entry.message.as_str(),
+            entry.fields["args"].as_str().unwrap(),
+            entry.fields["token"].as_str().unwrap(),
+        ] {
+            assert!(text.contains("[REDACTED:"), "not masked: {}", text);
+            assert!(!text.contains("abcdef123456") && !text.contains("0123456789abcdef"), "{}", text);
+        }
+    }
+
+    #[test]
+    fn verbose_override_only_raises_the_level() {
+        assert_eq!(more_verbose("info", "warn"), "info");
+        assert_eq!(more_verbose("info", "debug"), "debug");
+        assert_eq!(more_verbose("trace", "trace"), "trace");
+    }
+
+    #[test]
+    fn changing_the_level_at_runtime_filters_buffered_events() {
+        let levels = LogLevels {
+            base: "info",
+            modules: BTreeMap::new(),
+        };
+        let (filter, handle) = reload::Layer::new(EnvFilter::new(levels.directives()));
+        let control = LogLevelControl::new(handle, levels);
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let subscriber = tracing_subscriber::registry()
+            .with(filter)
+            .with(LogBufferLayer::new(Some(Arc::new(tx)), Arc::new(SecretMaskerLayer::new())));
+        let mut received = || {
+            let mut messages = Vec::new();
+            while let Ok(entry) = rx.try_recv() {
+                messages.push(entry.message);
+            }
+            messages
+        };
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!("hidden");
+            tracing::info!("shown");
+            assert_eq!(received(), vec!["shown"]);
+
+            assert_eq!(
+                control.set_level("DEBUG", None).unwrap(),
+                "synbot=debug,open_lark=debug"
+            );
+            tracing::debug!("now shown");
+            assert_eq!(received(), vec!["now shown"]);
+
+            let filter = control.set_level("warn", Some("synbot::logging")).unwrap();
+            assert_eq!(filter, "synbot=debug,open_lark=debug,synbot::logging=warn");
+            tracing::info!("quiet module");
+            tracing::warn!("still shown");
+            assert_eq!(received(), vec!["still shown"]);
+
+            assert!(control.set_level("verbose", None).is_err());
+            assert!(control.set_level("info", Some("bad module!")).is_err());
+            tracing::debug!(target: "synbot::other", "unchanged by rejected requests");
+            assert_eq!(received(), vec!["unchanged by rejected requests"]);
+        });
+    }
+}
@@ -2,11 +2,10 @@
 #![recursion_limit = "512"]
 #![type_length_limit = "16777216"]
 
-use anyhow::Result;
-
 mod agent;
 mod appcontainer_dns;
 mod background;
+mod build_info;
 mod bus;
 mod channels;
 mod cli;
@@ -19,12 +18,14 @@ mod plugin;
 mod rig_provider;
 mod sandbox;
 mod security;
+mod seed;
+mod telemetry;
 mod tools;
 mod url_utils;
 mod web;
 mod workflow;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> std::process::ExitCode {
     cli::run().await
 }
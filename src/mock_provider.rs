@@ -0,0 +1,204 @@
+//! Scripted completion model for end-to-end tests (feature `mock-provider`).
+//!
+//! [`MockProvider`] answers each completion call with the next entry of its script: plain text,
+//! one or more tool calls, or an error. It records every request it receives, so a test can check
+//! what the agent loop sent back after running a tool. Register it under a provider name with
+//! [`register_mock_provider`] and point `mainAgent.provider` at that name to drive the full agent
+//! loop without a real LLM.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use rig::completion::request::{CompletionError, CompletionRequest, CompletionResponse};
+use rig::message::AssistantContent;
+use rig::OneOrMany;
+use serde_json::Value;
+
+use crate::rig_provider::{default_registry, ProviderFactory, SynbotCompletionModel};
+
+/// One scripted model turn.
+#[derive(Debug, Clone)]
+pub enum MockReply {
+    /// Final answer text.
+    Text(String),
+    /// Ask the agent to run these tools (name, arguments); the loop calls the model again afterwards.
+    ToolCalls(Vec<(String, Value)>),
+    /// Fail the completion with this provider error.
+    Error(String),
+}
+
+#[derive(Default)]
+pub struct MockProvider {
+    script: Mutex<VecDeque<MockReply>>,
+    requests: Mutex<Vec<CompletionRequest>>,
+}
+
+impl MockProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a final text answer to the script.
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.push(MockReply::Text(text.into()))
+    }
+
+    /// Append a turn that requests a single tool call.
+    pub fn tool_call(self, name: impl Into<String>, args: Value) -> Self {
+        self.push(MockReply::ToolCalls(vec![(name.into(), args)]))
+    }
+
+    /// Append a turn that fails with a provider error.
+    pub fn error(self, message: impl Into<String>) -> Self {
+        self.push(MockReply::Error(message.into()))
+    }
+
+    /// Append any scripted reply.
+    pub fn push(self, reply: MockReply) -> Self {
+        self.lock_script().push_back(reply);
+        self
+    }
+
+    /// Requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<CompletionRequest> {
+        self.requests.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Scripted replies not consumed yet.
+    pub fn remaining(&self) -> usize {
+        self.lock_script().len()
+    }
+
+    fn lock_script(&self) -> std::sync::MutexGuard<'_, VecDeque<MockReply>> {
+        self.script.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn next_response(&self, request: CompletionRequest) -> Result<CompletionResponse<()>, CompletionError> {
+        let call_index = {
+            let mut requests = self.requests.lock().unwrap_or_else(|e| e.into_inner());
+            requests.push(request);
+            requests.len()
+        };
+        let reply = self.lock_script().pop_front().ok_or_else(|| {
+            CompletionError::ProviderError(format!(
+                "MockProvider script exhausted at call {}",
+                call_index
+            ))
+        })?;
+        let contents: Vec<AssistantContent> = match reply {
+            MockReply::Text(text) => vec![AssistantContent::text(text)],
+            MockReply::ToolCalls(calls) => calls
+                .into_iter()
+                .enumerate()
+                .map(|(i, (name, args))| {
+                    AssistantContent::tool_call(format!("mock_call_{}_{}", call_index, i), name, args)
+                })
+                .collect(),
+            MockReply::Error(message) => return Err(CompletionError::ProviderError(message)),
+        };
+        let choice = OneOrMany::many(contents)
+            .map_err(|_| CompletionError::ProviderError("MockProvider reply has no content".into()))?;
+        Ok(CompletionResponse {
+            choice,
+            usage: rig::completion::Usage {
+                input_tokens: 0,
+                output_tokens: 0,
+                total_tokens: 0,
+                cached_input_tokens: 0,
+            },
+            raw_response: (),
+        })
+    }
+}
+
+impl SynbotCompletionModel for MockProvider {
+    fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<CompletionResponse<()>, CompletionError>> + Send + '_>> {
+        let response = self.next_response(request);
+        Box::pin(async move { response })
+    }
+}
+
+struct MockProviderFactory(Arc<MockProvider>);
+
+impl ProviderFactory for MockProviderFactory {
+    fn build(
+        &self,
+        _provider_name: &str,
+        _model_name: &str,
+        _api_key: &str,
+        _api_base: Option<&str>,
+    ) -> Result<Arc<dyn SynbotCompletionModel>> {
+        Ok(Arc::clone(&self.0) as Arc<dyn SynbotCompletionModel>)
+    }
+}
+
+/// Register `provider` in the default provider registry under `name`, so building a model for
+/// that provider name returns it (shared, so the test keeps access to its recorded requests).
+pub fn register_mock_provider(name: &str, provider: Arc<MockProvider>) {
+    default_registry()
+        .write()
+        .expect("provider registry lock")
+        .register(name, Arc::new(MockProviderFactory(provider)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rig::message::Message;
+    use serde_json::json;
+
+    fn request() -> CompletionRequest {
+        CompletionRequest {
+            preamble: None,
+            chat_history: OneOrMany::one(Message::user("hi")),
+            tools: vec![],
+            documents: vec![],
+            temperature: None,
+            max_tokens: None,
+            tool_choice: None,
+            additional_params: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn replays_script_in_order_and_records_requests() {
+        let mock = MockProvider::new()
+            .tool_call("read_file", json!({ "path": "a.txt" }))
+            .text("done")
+            .error("rate limited");
+
+        let first = mock.completion(request()).await.unwrap();
+        match first.choice.first() {
+            AssistantContent::ToolCall(tc) => {
+                assert_eq!(tc.function.name, "read_file");
+                assert_eq!(tc.function.arguments["path"], "a.txt");
+            }
+            other => panic!("expected tool call, got {:?}", other),
+        }
+        let second = mock.completion(request()).await.unwrap();
+        assert!(matches!(second.choice.first(), AssistantContent::Text(t) if t.text == "done"));
+        let third = mock.completion(request()).await.unwrap_err();
+        assert!(third.to_string().contains("rate limited"));
+        let exhausted = mock.completion(request()).await.unwrap_err();
+        assert!(exhausted.to_string().contains("script exhausted"));
+
+        assert_eq!(mock.requests().len(), 4);
+        assert_eq!(mock.remaining(), 0);
+    }
+
+    #[tokio::test]
+    async fn registered_mock_is_built_by_provider_name() {
+        let mock = Arc::new(MockProvider::new().text("from registry"));
+        register_mock_provider("mock-unit-test", Arc::clone(&mock));
+        let model =
+            crate::rig_provider::build_completion_model("mock-unit-test", "any", "", None).unwrap();
+        model.completion(request()).await.unwrap();
+        assert_eq!(mock.requests().len(), 1);
+    }
+}
@@ -36,6 +36,19 @@ pub trait SynbotCompletionModel: Send + Sync {
     >;
 }
 
+/// A completion model could not be built for a provider (unknown provider, missing API key,
+/// bad endpoint). The CLI exits with its provider-error code on it.
+#[derive(Debug)]
+pub struct ProviderError(pub String);
+
+impl std::fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ProviderError {}
+
 // ---------------------------------------------------------------------------
 // Provider factory and registry (for plugins)
 // ---------------------------------------------------------------------------
@@ -262,6 +275,7 @@ pub fn build_completion_model(
         .read()
         .map_err(|e| anyhow!("provider registry lock: {}", e))?
         .build(provider_name, model_name, api_key, api_base)
+        .map_err(|e| ProviderError(format!("{:#}", e)).into())
 }
 
 /// Internal: built-in provider dispatch (used by BuiltinProviderFactory).
@@ -382,8 +396,28 @@ fn build_completion_model_builtin(
     Ok(model)
 }
 
+/// Chat Completions takes the reasoning effort as top-level `reasoning_effort`, while
+/// [`reasoning_params`] gives OpenAI the Responses API's `reasoning: { effort }`.
+fn chat_completions_reasoning(mut request: CompletionRequest) -> CompletionRequest {
+    if let Some(Value::Object(params)) = request.additional_params.as_mut() {
+        let effort = params
+            .get("reasoning")
+            .and_then(|r| r.get("effort"))
+            .cloned();
+        if let Some(effort) = effort {
+            params.remove("reasoning");
+            params.insert("reasoning_effort".to_string(), effort);
+        }
+    }
+    request
+}
+
+/// Wraps a rig completion model; `$prepare` adapts the request to the client's API first.
 macro_rules! impl_model {
     ($name:ident, $client:ty) => {
+        impl_model!($name, $client, std::convert::identity);
+    };
+    ($name:ident, $client:ty, $prepare:expr) => {
         struct $name(
             $client,
             <$client as CompletionClient>::CompletionModel,
@@ -400,6 +434,7 @@ macro_rules! impl_model {
                 >,
             > {
                 let m = &self.1;
+                let request = $prepare(request);
                 let fut = async move {
                     let r = m.completion(request).await?;
                     Ok(CompletionResponse {
@@ -415,12 +450,425 @@ macro_rules! impl_model {
 }
 
 impl_model!(OpenAiModel, rig::providers::openai::Client);
-impl_model!(OpenAiCompletionsModel, rig::providers::openai::CompletionsClient);
+impl_model!(OpenAiCompletionsModel, rig::providers::openai::CompletionsClient, chat_completions_reasoning);
 impl_model!(AnthropicModel, rig::providers::anthropic::Client);
 impl_model!(GeminiModel, rig::providers::gemini::Client);
 impl_model!(MoonshotModel, rig::providers::moonshot::Client);
 impl_model!(OllamaModel, rig::providers::ollama::Client);
 
+// ---------------------------------------------------------------------------
+// Debug logging (providers.debugLog): wraps any model and logs the request it
+// receives and the response it returns at debug level, secrets masked.
+// ---------------------------------------------------------------------------
+
+struct DebugLogModel {
+    inner: Arc<dyn SynbotCompletionModel>,
+    provider: String,
+    max_chars: usize,
+    masker: crate::security::SecretMaskerLayer,
+}
+
+impl DebugLogModel {
+    fn render(&self, value: &Value) -> String {
+        let text = self.masker.mask(&value.to_string());
+        if self.max_chars == 0 {
+            return text;
+        }
+        crate::tools::truncation::truncate_chars_with(
+            &text,
+            self.max_chars,
+            &crate::tools::truncation::TruncationMarker::LOG,
+        )
+    }
+}
+
+fn request_debug_value(request: &CompletionRequest) -> Value {
+    json!({
+        "preamble": request.preamble,
+        "chat_history": serde_json::to_value(&request.chat_history).unwrap_or(Value::Null),
+        "tools": request.tools.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+        "temperature": request.temperature,
+        "max_tokens": request.max_tokens,
+        "additional_params": request.additional_params,
+    })
+}
+
+fn response_debug_value(response: &CompletionResponse<()>) -> Value {
+    json!({
+        "choice": serde_json::to_value(&response.choice).unwrap_or(Value::Null),
+        "usage": {
+            "input_tokens": response.usage.input_tokens,
+            "output_tokens": response.usage.output_tokens,
+        },
+    })
+}
+
+impl SynbotCompletionModel for DebugLogModel {
+    fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<CompletionResponse<()>, CompletionError>> + Send + '_>> {
+        Box::pin(async move {
+            tracing::debug!(
+                provider = %self.provider,
+                request = %self.render(&request_debug_value(&request)),
+                "Provider request"
+            );
+            let result = self.inner.completion(request).await;
+            match &result {
+                Ok(response) => tracing::debug!(
+                    provider = %self.provider,
+                    response = %self.render(&response_debug_value(response)),
+                    "Provider response"
+                ),
+                Err(e) => tracing::debug!(
+                    provider = %self.provider,
+                    error = %self.masker.mask(&e.to_string()),
+                    "Provider error"
+                ),
+            }
+            result
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Seeded runs (SYNBOT_SEED): temperature 0 and a fixed sampling seed where the
+// provider's API accepts one.
+// ---------------------------------------------------------------------------
+
+struct SeededModel {
+    inner: Arc<dyn SynbotCompletionModel>,
+    seed: Option<u64>,
+}
+
+/// Providers whose chat API takes a top-level `seed` parameter.
+fn provider_accepts_seed(provider_name: &str) -> bool {
+    provider_name.to_lowercase().contains("openrouter")
+}
+
+fn seeded_request(mut request: CompletionRequest, seed: Option<u64>) -> CompletionRequest {
+    request.temperature = Some(0.0);
+    if let Some(seed) = seed {
+        let mut params = request.additional_params.take().unwrap_or_else(|| json!({}));
+        if let Some(obj) = params.as_object_mut() {
+            obj.insert("seed".to_string(), json!(seed));
+        }
+        request.additional_params = Some(params);
+    }
+    request
+}
+
+impl SynbotCompletionModel for SeededModel {
+    fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<CompletionResponse<()>, CompletionError>> + Send + '_>> {
+        self.inner.completion(seeded_request(request, self.seed))
+    }
+}
+
+/// Wrap `model` for deterministic sampling when a seed is configured (see [`crate::seed`]);
+/// returns `model` unchanged otherwise.
+pub fn with_seed(
+    model: Arc<dyn SynbotCompletionModel>,
+    provider_name: &str,
+    seed: Option<u64>,
+) -> Arc<dyn SynbotCompletionModel> {
+    match seed {
+        Some(seed) => Arc::new(SeededModel {
+            inner: model,
+            seed: provider_accepts_seed(provider_name).then_some(seed),
+        }),
+        None => model,
+    }
+}
+
+/// Wrap `model` so requests and responses are logged when `providers.debugLog` is on;
+/// returns `model` unchanged otherwise.
+pub fn with_debug_log(
+    model: Arc<dyn SynbotCompletionModel>,
+    provider_name: &str,
+    cfg: &crate::config::Config,
+) -> Arc<dyn SynbotCompletionModel> {
+    if !cfg.providers.debug_log {
+        return model;
+    }
+    let masker = crate::security::SecretMaskerLayer::new();
+    masker.load_config_secrets(cfg);
+    Arc::new(DebugLogModel {
+        inner: model,
+        provider: provider_name.to_string(),
+        max_chars: cfg.providers.debug_log_max_chars,
+        masker,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Extended thinking: map `reasoningEffort` / `thinkingBudget` onto each
+// provider's request parameters.
+// ---------------------------------------------------------------------------
+
+/// Smallest thinking budget Anthropic accepts.
+const ANTHROPIC_MIN_THINKING_BUDGET: u32 = 1024;
+
+fn effort_budget(effort: crate::config::ReasoningEffort) -> u32 {
+    match effort {
+        crate::config::ReasoningEffort::Low => 1024,
+        crate::config::ReasoningEffort::Medium => 4096,
+        crate::config::ReasoningEffort::High => 16384,
+    }
+}
+
+/// Request parameters that enable extended thinking on `provider_name`, or `None` when neither
+/// option is set or the provider has no reasoning controls. Anthropic's budget is kept below
+/// `max_tokens`, as the API requires.
+pub fn reasoning_params(
+    provider_name: &str,
+    effort: Option<crate::config::ReasoningEffort>,
+    budget: Option<u32>,
+    max_tokens: u32,
+) -> Option<Value> {
+    if effort.is_none() && budget.is_none() {
+        return None;
+    }
+    let lower = provider_name.to_lowercase();
+    if lower.contains("anthropic") || lower.contains("claude") {
+        let budget = budget
+            .or(effort.map(effort_budget))?
+            .max(ANTHROPIC_MIN_THINKING_BUDGET)
+            .min(max_tokens.saturating_sub(1));
+        if budget < ANTHROPIC_MIN_THINKING_BUDGET {
+            return None;
+        }
+        // Anthropic rejects thinking requests with a temperature other than 1.
+        Some(json!({
+            "thinking": { "type": "enabled", "budget_tokens": budget },
+            "temperature": 1.0
+        }))
+    } else if lower.contains("openrouter") {
+        match budget {
+            Some(budget) => Some(json!({ "reasoning": { "max_tokens": budget } })),
+            None => effort.map(|e| json!({ "reasoning": { "effort": e.as_str() } })),
+        }
+    } else if lower.contains("openai") {
+        effort.map(|e| json!({ "reasoning": { "effort": e.as_str() } }))
+    } else {
+        None
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Streamed responses (`providers.stream`): the direct HTTP models below read
+// OpenAI-style server-sent events. When the connection drops before the
+// provider finishes, the text received so far is returned with a note instead
+// of failing the turn.
+// ---------------------------------------------------------------------------
+
+/// Appended to an answer whose stream broke off before the provider finished it.
+pub const STREAM_TRUNCATED_NOTE: &str = "[Response truncated due to connection loss]";
+
+/// Built-in providers served by the direct HTTP models, which can read streamed responses.
+fn provider_supports_streaming(provider_name: &str) -> bool {
+    matches!(
+        provider_name.trim().to_lowercase().as_str(),
+        "deepseek" | "kimi" | "kimi_code" | "openrouter"
+    )
+}
+
+struct StreamingModel {
+    inner: Arc<dyn SynbotCompletionModel>,
+}
+
+fn streaming_request(mut request: CompletionRequest) -> CompletionRequest {
+    let mut params = request.additional_params.take().unwrap_or_else(|| json!({}));
+    if let Some(obj) = params.as_object_mut() {
+        obj.insert("stream".to_string(), json!(true));
+        obj.insert("stream_options".to_string(), json!({ "include_usage": true }));
+    }
+    request.additional_params = Some(params);
+    request
+}
+
+impl SynbotCompletionModel for StreamingModel {
+    fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<CompletionResponse<()>, CompletionError>> + Send + '_>> {
+        self.inner.completion(streaming_request(request))
+    }
+}
+
+/// Wrap `model` so responses are streamed when `providers.stream` is on and the provider
+/// supports it; returns `model` unchanged otherwise.
+pub fn with_streaming(
+    model: Arc<dyn SynbotCompletionModel>,
+    provider_name: &str,
+    cfg: &crate::config::Config,
+) -> Arc<dyn SynbotCompletionModel> {
+    if !cfg.providers.stream || !provider_supports_streaming(provider_name) {
+        return model;
+    }
+    Arc::new(StreamingModel { inner: model })
+}
+
+#[derive(Default)]
+struct StreamedToolCall {
+    id: String,
+    name: String,
+    arguments: String,
+}
+
+/// Accumulates `data:` events of a chat completions stream into one response.
+#[derive(Default)]
+struct StreamAccumulator {
+    /// Bytes of the current, not yet terminated line.
+    pending: Vec<u8>,
+    text: String,
+    reasoning: String,
+    tool_calls: Vec<StreamedToolCall>,
+    usage: Value,
+    /// A `finish_reason` or `[DONE]` was received.
+    finished: bool,
+}
+
+impl StreamAccumulator {
+    fn feed(&mut self, chunk: &[u8]) {
+        self.pending.extend_from_slice(chunk);
+        while let Some(pos) = self.pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=pos).collect();
+            self.handle_line(String::from_utf8_lossy(&line).trim());
+        }
+    }
+
+    fn handle_line(&mut self, line: &str) {
+        let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+            return;
+        };
+        if data == "[DONE]" {
+            self.finished = true;
+            return;
+        }
+        let Ok(event) = serde_json::from_str::<Value>(data) else {
+            return;
+        };
+        if event["usage"].is_object() {
+            self.usage = event["usage"].clone();
+        }
+        let Some(choice) = event["choices"].as_array().and_then(|a| a.first()) else {
+            return;
+        };
+        let delta = &choice["delta"];
+        if let Some(s) = delta["content"].as_str() {
+            self.text.push_str(s);
+        }
+        if let Some(s) = delta["reasoning_content"].as_str() {
+            self.reasoning.push_str(s);
+        }
+        for tc in delta["tool_calls"].as_array().into_iter().flatten() {
+            let index = tc["index"].as_u64().unwrap_or(0) as usize;
+            if self.tool_calls.len() <= index {
+                self.tool_calls.resize_with(index + 1, StreamedToolCall::default);
+            }
+            let slot = &mut self.tool_calls[index];
+            if let Some(id) = tc["id"].as_str().filter(|s| !s.is_empty()) {
+                slot.id = id.to_string();
+            }
+            if let Some(name) = tc["function"]["name"].as_str().filter(|s| !s.is_empty()) {
+                slot.name = name.to_string();
+            }
+            if let Some(args) = tc["function"]["arguments"].as_str() {
+                slot.arguments.push_str(args);
+            }
+        }
+        if choice["finish_reason"].as_str().is_some() {
+            self.finished = true;
+        }
+    }
+
+    /// Build the response. `disconnect` is the transport error when the body ended with one.
+    /// A stream that ends unfinished keeps its text (with [`STREAM_TRUNCATED_NOTE`]) and drops
+    /// tool calls, whose arguments may be cut off; with no text at all it is an error.
+    fn finish(
+        mut self,
+        disconnect: Option<String>,
+        url: &str,
+        model: &str,
+    ) -> Result<CompletionResponse<()>, CompletionError> {
+        if !self.pending.is_empty() {
+            let line = std::mem::take(&mut self.pending);
+            self.handle_line(String::from_utf8_lossy(&line).trim());
+        }
+        if !self.finished {
+            let reason = disconnect.unwrap_or_else(|| "stream closed before the response finished".to_string());
+            if self.text.trim().is_empty() {
+                return Err(CompletionError::ProviderError(format!(
+                    "Stream broke off before any answer text (url={}, model={}): {}",
+                    url, model, reason
+                )));
+            }
+            tracing::warn!(
+                url = %url,
+                model = %model,
+                received_chars = self.text.chars().count(),
+                dropped_tool_calls = self.tool_calls.len(),
+                error = %reason,
+                "Provider stream broke off mid-response; returning the partial answer"
+            );
+            self.text = format!("{}\n\n{}", self.text.trim_end(), STREAM_TRUNCATED_NOTE);
+            self.tool_calls.clear();
+        }
+
+        let mut contents: Vec<AssistantContent> = Vec::new();
+        if !self.reasoning.is_empty() {
+            contents.push(AssistantContent::reasoning(&self.reasoning));
+        }
+        for tc in &self.tool_calls {
+            let args: Value = serde_json::from_str(&tc.arguments).unwrap_or(json!({}));
+            contents.push(AssistantContent::tool_call(&tc.id, &tc.name, args));
+        }
+        if !self.text.is_empty() {
+            contents.push(AssistantContent::text(&self.text));
+        }
+        if contents.is_empty() {
+            contents.push(AssistantContent::text(""));
+        }
+        let choice = rig::OneOrMany::many(contents)
+            .unwrap_or_else(|_| rig::OneOrMany::one(AssistantContent::text("")));
+        let usage = rig::completion::Usage {
+            input_tokens: self.usage["prompt_tokens"].as_u64().unwrap_or(0),
+            output_tokens: self.usage["completion_tokens"].as_u64().unwrap_or(0),
+            total_tokens: self.usage["total_tokens"].as_u64().unwrap_or(0),
+            cached_input_tokens: self.usage["prompt_tokens_details"]["cached_tokens"]
+                .as_u64()
+                .unwrap_or(0),
+        };
+        Ok(CompletionResponse {
+            choice,
+            usage,
+            raw_response: (),
+        })
+    }
+}
+
+/// Read a streamed (SSE) chat completions body; see [`StreamAccumulator::finish`] for how a
+/// connection lost mid-stream is handled.
+async fn read_streamed_response(
+    mut resp: reqwest::Response,
+    url: &str,
+    model: &str,
+) -> Result<CompletionResponse<()>, CompletionError> {
+    let mut acc = StreamAccumulator::default();
+    let disconnect = loop {
+        match resp.chunk().await {
+            Ok(Some(chunk)) => acc.feed(&chunk),
+            Ok(None) => break None,
+            Err(e) => break Some(e.to_string()),
+        }
+    };
+    acc.finish(disconnect, url, model)
+}
+
 // ---------------------------------------------------------------------------
 // DeepSeek: custom implementation that correctly handles reasoning_content
 // in multi-turn tool-call conversations.
@@ -660,6 +1108,7 @@ impl SynbotCompletionModel for DeepSeekDirectModel {
         let model_name = self.model.clone();
         let http = self.http.clone();
         let api_key = self.api_key.clone();
+        let streamed = body["stream"].as_bool() == Some(true);
 
         Box::pin(async move {
             let resp = http
@@ -676,6 +1125,9 @@ impl SynbotCompletionModel for DeepSeekDirectModel {
                 )))?;
 
             let status = resp.status();
+            if streamed && status.is_success() {
+                return read_streamed_response(resp, &url, &model_name).await;
+            }
             let bytes = resp
                 .bytes()
                 .await
@@ -889,6 +1341,7 @@ impl SynbotCompletionModel for KimiCodeDirectModel {
         let model_name = self.model.clone();
         let http = self.http.clone();
         let api_key = self.api_key.clone();
+        let streamed = body["stream"].as_bool() == Some(true);
 
         Box::pin(async move {
             let resp = http
@@ -902,6 +1355,9 @@ impl SynbotCompletionModel for KimiCodeDirectModel {
                     url, model_name, e
                 )))?;
             let status = resp.status();
+            if streamed && status.is_success() {
+                return read_streamed_response(resp, &url, &model_name).await;
+            }
             let bytes = resp
                 .bytes()
                 .await
@@ -1094,6 +1550,7 @@ impl SynbotCompletionModel for OpenRouterDirectModel {
         let model_name = self.model.clone();
         let http = self.http.clone();
         let api_key = self.api_key.clone();
+        let streamed = body["stream"].as_bool() == Some(true);
 
         Box::pin(async move {
             let resp = http
@@ -1107,6 +1564,9 @@ impl SynbotCompletionModel for OpenRouterDirectModel {
                     url, model_name, e
                 )))?;
             let status = resp.status();
+            if streamed && status.is_success() {
+                return read_streamed_response(resp, &url, &model_name).await;
+            }
             let bytes = resp
                 .bytes()
                 .await
@@ -1165,3 +1625,292 @@ impl SynbotCompletionModel for OpenRouterDirectModel {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rig::message::Message;
+    use rig::OneOrMany;
+    use std::sync::Mutex;
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+
+    const API_KEY: &str = "sk-debug-log-test-secret-0123456789";
+
+    /// Fake provider that echoes a fixed answer.
+    struct CannedModel;
+
+    impl SynbotCompletionModel for CannedModel {
+        fn completion(
+            &self,
+            _request: CompletionRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<CompletionResponse<()>, CompletionError>> + Send + '_>>
+        {
+            Box::pin(async {
+                Ok(CompletionResponse {
+                    choice: OneOrMany::one(AssistantContent::text("canned answer")),
+                    usage: rig::completion::Usage {
+                        input_tokens: 3,
+                        output_tokens: 2,
+                        total_tokens: 5,
+                        cached_input_tokens: 0,
+                    },
+                    raw_response: (),
+                })
+            })
+        }
+    }
+
+    /// Records the fields of every event as `name=value` text.
+    struct Capture(Arc<Mutex<Vec<String>>>);
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for Capture {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            let mut fields = String::new();
+            event.record(&mut |field: &tracing::field::Field, value: &dyn std::fmt::Debug| {
+                fields.push_str(&format!("{}={:?} ", field.name(), value));
+            });
+            self.0.lock().unwrap().push(fields);
+        }
+    }
+
+    fn request(text: &str) -> CompletionRequest {
+        CompletionRequest {
+            preamble: Some("system".to_string()),
+            chat_history: OneOrMany::one(Message::user(text)),
+            tools: vec![],
+            documents: vec![],
+            temperature: Some(0.0),
+            max_tokens: Some(64),
+            tool_choice: None,
+            additional_params: None,
+        }
+    }
+
+    fn config(debug_log: bool) -> crate::config::Config {
+        let mut cfg = crate::config::Config::default();
+        cfg.providers.openai.api_key = API_KEY.to_string();
+        cfg.providers.debug_log = debug_log;
+        cfg
+    }
+
+    async fn logged_events(cfg: &crate::config::Config, prompt: &str) -> Vec<String> {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::registry().with(Capture(Arc::clone(&events)));
+        let _guard = tracing::subscriber::set_default(subscriber);
+        let model = with_debug_log(Arc::new(CannedModel), "openai", cfg);
+        model.completion(request(prompt)).await.unwrap();
+        let out = events.lock().unwrap().clone();
+        out
+    }
+
+    #[tokio::test]
+    async fn debug_log_records_request_and_response_with_secrets_masked() {
+        let prompt = format!("my key is {}", API_KEY);
+        let events = logged_events(&config(true), &prompt).await;
+
+        let request = events.iter().find(|e| e.contains("Provider request")).expect("request event");
+        assert!(request.contains("my key is"));
+        assert!(!request.contains(API_KEY));
+        let response = events.iter().find(|e| e.contains("Provider response")).expect("response event");
+        assert!(response.contains("canned answer"));
+    }
+
+    #[tokio::test]
+    async fn debug_log_is_off_by_default() {
+        assert!(!crate::config::Config::default().providers.debug_log);
+        let events = logged_events(&config(false), "hello").await;
+        assert!(events.iter().all(|e| !e.contains("Provider request")));
+    }
+
+    #[test]
+    fn seeded_requests_use_temperature_zero_and_seed_where_supported() {
+        let mut req = request("hi");
+        req.temperature = Some(0.7);
+        let seeded = seeded_request(req, Some(42));
+        assert_eq!(seeded.temperature, Some(0.0));
+        assert_eq!(seeded.additional_params, Some(json!({ "seed": 42 })));
+
+        let plain = seeded_request(request("hi"), None);
+        assert_eq!(plain.temperature, Some(0.0));
+        assert!(plain.additional_params.is_none());
+
+        assert!(provider_accepts_seed("OpenRouter"));
+        assert!(!provider_accepts_seed("anthropic"));
+    }
+
+    #[tokio::test]
+    async fn debug_log_truncates_when_capped() {
+        let mut cfg = config(true);
+        cfg.providers.debug_log_max_chars = 40;
+        let events = logged_events(&cfg, &"x".repeat(500)).await;
+        let request = events.iter().find(|e| e.contains("Provider request")).unwrap();
+        assert!(request.contains("... ("));
+        assert!(!request.contains(&"x".repeat(100)));
+    }
+
+    fn sse(delta: Value) -> String {
+        format!("data: {}\n\n", json!({ "choices": [{ "index": 0, "delta": delta }] }))
+    }
+
+    /// Mock streaming provider: promises a long body, sends `events`, then drops the connection.
+    async fn serve_cut_off_stream(events: String) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 64 * 1024];
+            let _ = socket.read(&mut buf).await;
+            let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nContent-Length: 100000\r\n\r\n";
+            socket.write_all(head.as_bytes()).await.unwrap();
+            socket.write_all(events.as_bytes()).await.unwrap();
+            socket.flush().await.unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn stream_cut_off_mid_response_returns_partial_text_with_note() {
+        let events = format!(
+            "{}{}",
+            sse(json!({ "role": "assistant", "content": "The capital of France " })),
+            sse(json!({ "content": "is Par" }))
+        );
+        let base = serve_cut_off_stream(events).await;
+        let model = DeepSeekDirectModel::new(
+            reqwest::Client::new(),
+            "sk-test".to_string(),
+            "deepseek-chat".to_string(),
+            base,
+        );
+        let response = model
+            .completion(streaming_request(request("capital of France?")))
+            .await
+            .expect("partial stream should not be a hard error");
+        let text: String = response
+            .choice
+            .iter()
+            .filter_map(|c| match c {
+                AssistantContent::Text(t) => Some(t.text.clone()),
+                _ => None,
+            })
+            .collect();
+        assert!(text.starts_with("The capital of France is Par"));
+        assert!(text.ends_with(STREAM_TRUNCATED_NOTE));
+    }
+
+    #[tokio::test]
+    async fn stream_cut_off_before_any_text_is_an_error() {
+        let base = serve_cut_off_stream(sse(json!({ "role": "assistant" }))).await;
+        let model = DeepSeekDirectModel::new(
+            reqwest::Client::new(),
+            "sk-test".to_string(),
+            "deepseek-chat".to_string(),
+            base,
+        );
+        let err = model
+            .completion(streaming_request(request("hi")))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Stream broke off"));
+    }
+
+    #[test]
+    fn complete_stream_assembles_tool_call_fragments() {
+        let mut acc = StreamAccumulator::default();
+        let stream = format!(
+            "{}{}{}data: {}\n\ndata: [DONE]\n\n",
+            sse(json!({ "tool_calls": [{ "index": 0, "id": "call_1", "function": { "name": "read_file", "arguments": "{\"pa" } }] })),
+            sse(json!({ "tool_calls": [{ "index": 0, "function": { "arguments": "th\":\"a.txt\"}" } }] })),
+            sse(json!({ "content": "Reading it." })),
+            json!({ "choices": [], "usage": { "prompt_tokens": 12, "completion_tokens": 5, "total_tokens": 17 } })
+        );
+        // Split mid-line to exercise buffering across chunks.
+        let (a, b) = stream.as_bytes().split_at(37);
+        acc.feed(a);
+        acc.feed(b);
+        let response = acc.finish(None, "http://test", "m").unwrap();
+        let contents: Vec<AssistantContent> = response.choice.into_iter().collect();
+        match &contents[0] {
+            AssistantContent::ToolCall(tc) => {
+                assert_eq!(tc.id, "call_1");
+                assert_eq!(tc.function.name, "read_file");
+                assert_eq!(tc.function.arguments, json!({ "path": "a.txt" }));
+            }
+            other => panic!("expected tool call, got {:?}", other),
+        }
+        assert!(matches!(&contents[1], AssistantContent::Text(t) if t.text == "Reading it."));
+        assert_eq!(response.usage.total_tokens, 17);
+    }
+
+    #[test]
+    fn reasoning_options_map_to_provider_params() {
+        use crate::config::ReasoningEffort;
+        assert_eq!(reasoning_params("anthropic", None, None, 8192), None);
+        assert_eq!(
+            reasoning_params("anthropic", Some(ReasoningEffort::Medium), None, 8192),
+            Some(json!({ "thinking": { "type": "enabled", "budget_tokens": 4096 }, "temperature": 1.0 }))
+        );
+        // Budget wins over effort and stays below max_tokens.
+        assert_eq!(
+            reasoning_params("claude", Some(ReasoningEffort::Low), Some(50_000), 8192).unwrap()["thinking"]["budget_tokens"],
+            json!(8191)
+        );
+        assert_eq!(reasoning_params("anthropic", Some(ReasoningEffort::High), None, 1000), None);
+        assert_eq!(
+            reasoning_params("openrouter", Some(ReasoningEffort::High), None, 8192),
+            Some(json!({ "reasoning": { "effort": "high" } }))
+        );
+        assert_eq!(
+            reasoning_params("openrouter", None, Some(2000), 8192),
+            Some(json!({ "reasoning": { "max_tokens": 2000 } }))
+        );
+        assert_eq!(
+            reasoning_params("openai", Some(ReasoningEffort::Low), None, 8192),
+            Some(json!({ "reasoning": { "effort": "low" } }))
+        );
+        // Unsupported providers ignore the options.
+        assert_eq!(reasoning_params("ollama", Some(ReasoningEffort::High), Some(2000), 8192), None);
+        assert_eq!(reasoning_params("deepseek", Some(ReasoningEffort::High), None, 8192), None);
+    }
+
+    #[test]
+    fn chat_completions_get_top_level_reasoning_effort() {
+        let mut low = request("hi");
+        low.additional_params = Some(json!({ "reasoning": { "effort": "low" }, "top_p": 0.9 }));
+        let low = chat_completions_reasoning(low);
+        assert_eq!(
+            low.additional_params,
+            Some(json!({ "top_p": 0.9, "reasoning_effort": "low" }))
+        );
+
+        let untouched = chat_completions_reasoning(request("hi"));
+        assert_eq!(untouched.additional_params, None);
+    }
+
+    #[test]
+    fn streaming_is_limited_to_direct_providers() {
+        assert!(provider_supports_streaming("DeepSeek"));
+        assert!(provider_supports_streaming("openrouter"));
+        assert!(!provider_supports_streaming("anthropic"));
+        let params = streaming_request(request("hi")).additional_params.unwrap();
+        assert_eq!(params["stream"], json!(true));
+    }
+
+    #[test]
+    fn custom_provider_is_selected_by_name_after_registration() {
+        let mut cfg = crate::config::Config::default();
+        cfg.providers.extra.insert(
+            "custom-gw-test".into(),
+            crate::config::ProviderEntry {
+                api_key: "gw-key".into(),
+                api_base: Some("https://gw.example.com/v1".into()),
+                ..Default::default()
+            },
+        );
+        assert!(build_completion_model("custom-gw-test", "m", "gw-key", None).is_err());
+        register_extra_providers_from_config(&cfg);
+        build_completion_model("custom-gw-test", "m", "gw-key", Some("https://gw.example.com/v1"))
+            .unwrap();
+    }
+}
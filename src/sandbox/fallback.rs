@@ -171,7 +171,7 @@ impl FallbackManager {
         &self,
         config: SandboxConfig,
     ) -> Result<(Box<dyn Sandbox>, FallbackResult)> {
-        #[cfg(target_os = "windows")]
+        #[cfg(all(target_os = "windows", feature = "sandbox-docker"))]
         {
             use crate::sandbox::Wsl2GVisorSandbox;
             
@@ -216,7 +216,7 @@ impl FallbackManager {
             }
         }
         
-        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        #[cfg(all(any(target_os = "linux", target_os = "macos"), feature = "sandbox-docker"))]
         {
             use crate::sandbox::plain_docker::PlainDockerSandbox;
             use crate::sandbox::GVisorDockerSandbox;
@@ -271,13 +271,22 @@ impl FallbackManager {
             }
         }
         
-        #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+        #[cfg(not(feature = "sandbox-docker"))]
+        {
+            Err(SandboxError::CreationFailed(format!(
+                "Tool sandbox '{}': Docker backends need the `sandbox-docker` feature, which this build does not include",
+                config.sandbox_id
+            )))
+        }
+        
+        #[cfg(all(feature = "sandbox-docker", not(any(target_os = "windows", target_os = "linux", target_os = "macos"))))]
         {
             Err(SandboxError::UnsupportedPlatform)
         }
     }
     
     /// Record a fallback event
+    #[cfg_attr(not(feature = "sandbox-docker"), allow(dead_code))]
     async fn record_fallback_event(
         &self,
         sandbox_id: &str,
@@ -373,6 +382,7 @@ mod tests {
                 enabled: false,
                 allowed_hosts: vec![],
                 allowed_ports: vec![],
+                host_network: false,
             },
             resources: ResourceConfig {
                 max_memory: 1024 * 1024 * 1024,
@@ -388,6 +398,7 @@ mod tests {
             delete_on_start: false,
             requested_tool_sandbox_type: None,
             image: None,
+            hardening: HardeningConfig::default(),
         }
     }
     
@@ -5,13 +5,12 @@ use super::sandbox_trait::Sandbox;
 use super::types::{
     ExecutionResult, HealthStatus, SandboxConfig, SandboxInfo, SandboxState, SandboxStatus,
 };
-use super::plain_docker::connect_docker;
+use super::plain_docker::{apply_egress_filter, connect_docker, docker_container_config};
 use bollard::container::{
-    Config, CreateContainerOptions, RemoveContainerOptions, StartContainerOptions,
+    CreateContainerOptions, RemoveContainerOptions, StartContainerOptions,
     StopContainerOptions,
 };
 use bollard::exec::{CreateExecOptions, StartExecResults};
-use bollard::models::HostConfig;
 use bollard::Docker;
 use chrono::Utc;
 use std::collections::HashMap;
@@ -66,40 +65,6 @@ impl GVisorDockerSandbox {
             status,
         })
     }
-    
-    /// Get network mode based on configuration
-    fn get_network_mode(&self) -> String {
-        if self.config.network.enabled {
-            "bridge".to_string()
-        } else {
-            "none".to_string()
-        }
-    }
-    
-    /// Get volume bindings from configuration (writable_paths as host:host, plus workspace at /workspace if set)
-    fn get_volumes(&self) -> Vec<String> {
-        let mut binds: Vec<String> = self
-            .config
-            .filesystem
-            .writable_paths
-            .iter()
-            .map(|p| format!("{}:{}", p, p))
-            .collect();
-        if let Some((ref host, ref guest)) = self.config.filesystem.workspace_mount {
-            binds.push(format!("{}:{}", host, guest));
-        }
-        if let Some((ref host, ref guest)) = self.config.filesystem.skills_mount {
-            binds.push(format!("{}:{}:ro", host, guest));
-        }
-        binds
-    }
-    
-    /// Get required capabilities (minimal set for security)
-    fn get_required_capabilities(&self) -> Vec<String> {
-        // Return minimal capability set
-        // Most capabilities are dropped for security
-        vec![]
-    }
 }
 
 impl Sandbox for GVisorDockerSandbox {
@@ -109,17 +74,8 @@ impl Sandbox for GVisorDockerSandbox {
         let docker = self.docker.clone();
         let sandbox_id = self.config.sandbox_id.clone();
         let delete_on_start = self.config.delete_on_start;
-        let network_mode = self.get_network_mode();
-        let binds = self.get_volumes();
-        let memory = self.config.resources.max_memory as i64;
-        let nano_cpus = (self.config.resources.max_cpu * 1_000_000_000.0) as i64;
-        let cap_add = self.get_required_capabilities();
-        let image = self
-            .config
-            .image
-            .as_deref()
-            .unwrap_or("ubuntu:22.04")
-            .to_string();
+        let config = docker_container_config(&self.config, Some("runsc"));
+        let network = self.config.network.clone();
 
         // Run Docker async work in a blocking thread to avoid "runtime within runtime" when
         // start() is called from an async context (e.g. init_sandbox_if_configured).
@@ -162,6 +118,7 @@ impl Sandbox for GVisorDockerSandbox {
                                 ))
                             })?;
                     }
+                    apply_egress_filter(&docker, &id, &network).await?;
                     return Ok::<String, SandboxError>(id);
                 }
 
@@ -170,25 +127,6 @@ impl Sandbox for GVisorDockerSandbox {
                     platform: None,
                 };
 
-                let host_config = HostConfig {
-                    runtime: Some("runsc".to_string()),
-                    network_mode: Some(network_mode),
-                    binds: Some(binds),
-                    memory: Some(memory),
-                    nano_cpus: Some(nano_cpus),
-                    security_opt: Some(vec!["no-new-privileges".to_string()]),
-                    cap_drop: Some(vec!["ALL".to_string()]),
-                    cap_add: Some(cap_add),
-                    ..Default::default()
-                };
-
-                let config = Config {
-                    image: Some(image),
-                    host_config: Some(host_config),
-                    cmd: Some(vec!["sleep".to_string(), "infinity".to_string()]),
-                    ..Default::default()
-                };
-
                 let container = docker
                     .create_container(Some(options), config)
                     .await
@@ -199,6 +137,7 @@ impl Sandbox for GVisorDockerSandbox {
                     .start_container(&cid, None::<StartContainerOptions<String>>)
                     .await
                     .map_err(|e| SandboxError::CreationFailed(format!("Failed to start container: {}", e)))?;
+                apply_egress_filter(&docker, &cid, &network).await?;
 
                 Ok(cid)
             })
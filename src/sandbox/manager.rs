@@ -2,14 +2,126 @@
 
 use super::error::{Result, SandboxError};
 use super::sandbox_trait::Sandbox;
-use super::types::{SandboxConfig, SandboxInfo, ExecutionResult};
-use std::time::Duration;
+use super::types::{SandboxConfig, SandboxInfo, SandboxState, ExecutionResult, HealthStatus, ToolSandboxIsolation};
+use std::time::{Duration, Instant};
 use super::config::ConfigurationManager;
-use super::monitoring::MonitoringModule;
-use super::security::{SecurityValidator, EscapePrevention, PrivilegeEscalationPrevention, ResourceExhaustionPrevention};
-use std::collections::HashMap;
+use super::monitoring::{MonitoringModule, SecurityViolation, ViolationKind, ViolationThreshold};
+use super::security::{SecurityValidator, EscapePrevention, PrivilegeEscalationPrevention, ResourceExhaustionPrevention, ResourceLimits};
+use crate::bus::OutboundMessage;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, Mutex, RwLock};
+
+/// Builds a tool sandbox instance from its configuration (see [`SandboxManager::with_tool_sandbox_factory`]).
+pub type ToolSandboxFactory = Box<dyn Fn(SandboxConfig) -> Result<Box<dyn Sandbox>> + Send + Sync>;
+
+/// Running execs per tool sandbox pool member. An exec takes the member with the fewest running
+/// execs (the lowest index on ties), so idle members are used before any member is shared.
+struct ToolSandboxSlots {
+    running: std::sync::Mutex<Vec<usize>>,
+}
+
+impl ToolSandboxSlots {
+    fn new(pool_size: usize) -> Self {
+        Self {
+            running: std::sync::Mutex::new(vec![0; pool_size.max(1)]),
+        }
+    }
+
+    /// Take the least busy member until the returned slot is dropped.
+    fn acquire(&self) -> ToolSandboxSlot<'_> {
+        let mut running = self.running.lock().unwrap();
+        let index = (0..running.len()).min_by_key(|&i| running[i]).unwrap_or(0);
+        running[index] += 1;
+        ToolSandboxSlot { slots: self, index }
+    }
+}
+
+/// Pool member held by one exec until dropped (also when the exec is cancelled).
+struct ToolSandboxSlot<'a> {
+    slots: &'a ToolSandboxSlots,
+    index: usize,
+}
+
+impl Drop for ToolSandboxSlot<'_> {
+    fn drop(&mut self) {
+        let mut running = self.slots.running.lock().unwrap();
+        running[self.index] = running[self.index].saturating_sub(1);
+    }
+}
+
+/// How long an unused per-session tool sandbox is kept when no tool sandbox idle timeout is configured.
+pub const DEFAULT_SESSION_TOOL_SANDBOX_IDLE: Duration = Duration::from_secs(30 * 60);
+
+/// Directory under the host workspace that holds each session's workspace under per-session isolation.
+const SESSION_WORKSPACES_DIR: &str = "sessions";
+
+/// Name of the tool sandbox and workspace directory of `session_id` under per-session isolation:
+/// the session ID with characters that container names do not allow replaced by `-` (shortened),
+/// then a hash of the full ID, so sessions that differ only in those characters never share one.
+fn session_tool_sandbox_suffix(session_id: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let readable: String = session_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '.' { c } else { '-' })
+        .take(48)
+        .collect();
+    let digest = hex::encode(Sha256::digest(session_id.as_bytes()));
+    format!("{}-{}", readable, &digest[..12])
+}
+
+/// ID of the tool sandbox of `session_id` under per-session isolation: `<id>-<session>-<hash>`.
+fn session_tool_sandbox_id(sandbox_id: &str, session_id: &str) -> String {
+    format!("{}-{}", sandbox_id, session_tool_sandbox_suffix(session_id))
+}
+
+/// A per-session tool sandbox and what the idle reaper needs to know about it.
+struct SessionToolSandbox {
+    /// Time of the last execution start or end
+    last_used: Instant,
+    /// Executions running now; the reaper never removes a sandbox that has any
+    in_flight: usize,
+    /// Host directory mounted as the session's workspace (Docker backends)
+    workspace: Option<PathBuf>,
+}
+
+/// Marks an exec in a per-session tool sandbox as running until dropped (also when the exec is cancelled).
+struct SessionExecGuard<'a> {
+    sandboxes: &'a std::sync::Mutex<HashMap<String, SessionToolSandbox>>,
+    sandbox_id: String,
+}
+
+/// Marks an exec in the shared tool sandbox as running until dropped (also when the exec is cancelled).
+struct ToolExecGuard<'a> {
+    manager: &'a SandboxManager,
+}
+
+impl Drop for ToolExecGuard<'_> {
+    fn drop(&mut self) {
+        self.manager.tool_sandbox_in_flight.fetch_sub(1, Ordering::SeqCst);
+        self.manager.touch_tool_sandbox();
+    }
+}
+
+impl Drop for SessionExecGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(entry) = self.sandboxes.lock().unwrap().get_mut(&self.sandbox_id) {
+            entry.in_flight = entry.in_flight.saturating_sub(1);
+            entry.last_used = Instant::now();
+        }
+    }
+}
+
+/// ID of tool sandbox pool member `index`: the configured ID for the first one, `<id>-<index>` for the others.
+fn tool_sandbox_member_id(sandbox_id: &str, index: usize) -> String {
+    if index == 0 {
+        sandbox_id.to_string()
+    } else {
+        format!("{}-{}", sandbox_id, index)
+    }
+}
 
 /// Sandbox Manager
 /// 
@@ -31,6 +143,56 @@ pub struct SandboxManager {
     
     /// Security validator for configuration and runtime checks
     security_validator: Arc<SecurityValidator>,
+    
+    /// Configured tool sandbox, created and started on first use by `exec_in_tool_sandbox`
+    tool_sandbox_config: RwLock<Option<SandboxConfig>>,
+    
+    /// Builds tool sandbox instances (platform factory by default)
+    tool_sandbox_factory: ToolSandboxFactory,
+    
+    /// Serializes lazy creation/start and idle shutdown of the tool sandbox
+    tool_sandbox_init: Mutex<()>,
+    
+    /// Number of tool sandbox instances execs are spread across
+    tool_sandbox_pool_size: usize,
+    
+    /// Running execs per pool member
+    tool_sandbox_slots: ToolSandboxSlots,
+    
+    /// Shared tool sandbox or one per session
+    tool_sandbox_isolation: ToolSandboxIsolation,
+    
+    /// Per-session tool sandboxes by ID
+    session_tool_sandboxes: std::sync::Mutex<HashMap<String, SessionToolSandbox>>,
+    
+    /// Stop the tool sandbox after this long without executions (None = keep running)
+    tool_sandbox_idle: Option<Duration>,
+    
+    /// Time of the last tool sandbox execution start or end
+    tool_sandbox_last_used: std::sync::Mutex<Option<Instant>>,
+    
+    /// Executions running in the shared tool sandbox now; it is never stopped as idle while any run
+    tool_sandbox_in_flight: AtomicUsize,
+    
+    /// Violations within a window that stop the offending sandbox
+    violation_threshold: ViolationThreshold,
+    
+    /// Recent violation times per sandbox, pruned to the threshold window
+    recent_violations: std::sync::Mutex<HashMap<String, VecDeque<Instant>>>,
+    
+    /// Sandboxes stopped for exceeding the violation threshold; executions are refused until
+    /// [`Self::release_quarantine`]
+    quarantined: std::sync::Mutex<HashSet<String>>,
+    
+    /// Where the alert is sent when a sandbox is stopped
+    alert: Option<ViolationAlert>,
+}
+
+/// Chat that is told when a sandbox is stopped for violations
+struct ViolationAlert {
+    tx: broadcast::Sender<OutboundMessage>,
+    channel: String,
+    chat_id: String,
 }
 
 impl SandboxManager {
@@ -49,6 +211,20 @@ impl SandboxManager {
             config_manager: Arc::new(RwLock::new(config_manager)),
             monitoring: Arc::new(monitoring),
             security_validator: Arc::new(SecurityValidator::new()),
+            tool_sandbox_config: RwLock::new(None),
+            tool_sandbox_factory: Box::new(super::platform::SandboxFactory::create_tool_sandbox),
+            tool_sandbox_init: Mutex::new(()),
+            tool_sandbox_pool_size: 1,
+            tool_sandbox_slots: ToolSandboxSlots::new(1),
+            tool_sandbox_isolation: ToolSandboxIsolation::Shared,
+            session_tool_sandboxes: std::sync::Mutex::new(HashMap::new()),
+            tool_sandbox_idle: None,
+            tool_sandbox_last_used: std::sync::Mutex::new(None),
+            tool_sandbox_in_flight: AtomicUsize::new(0),
+            violation_threshold: ViolationThreshold::default(),
+            recent_violations: std::sync::Mutex::new(HashMap::new()),
+            quarantined: std::sync::Mutex::new(HashSet::new()),
+            alert: None,
         }
     }
     
@@ -69,6 +245,20 @@ impl SandboxManager {
             config_manager: Arc::new(RwLock::new(config_manager)),
             monitoring: Arc::new(monitoring),
             security_validator: Arc::new(security_validator),
+            tool_sandbox_config: RwLock::new(None),
+            tool_sandbox_factory: Box::new(super::platform::SandboxFactory::create_tool_sandbox),
+            tool_sandbox_init: Mutex::new(()),
+            tool_sandbox_pool_size: 1,
+            tool_sandbox_slots: ToolSandboxSlots::new(1),
+            tool_sandbox_isolation: ToolSandboxIsolation::Shared,
+            session_tool_sandboxes: std::sync::Mutex::new(HashMap::new()),
+            tool_sandbox_idle: None,
+            tool_sandbox_last_used: std::sync::Mutex::new(None),
+            tool_sandbox_in_flight: AtomicUsize::new(0),
+            violation_threshold: ViolationThreshold::default(),
+            recent_violations: std::sync::Mutex::new(HashMap::new()),
+            quarantined: std::sync::Mutex::new(HashSet::new()),
+            alert: None,
         }
     }
     
@@ -81,6 +271,81 @@ impl SandboxManager {
         Self::new(config_manager, monitoring)
     }
     
+    /// Validate and enforce sandboxes against `limits` instead of the default resource limits
+    pub fn with_resource_limits(mut self, limits: ResourceLimits) -> Self {
+        self.security_validator = Arc::new(SecurityValidator::with_limits(limits));
+        self
+    }
+    
+    /// Replace the factory used to build tool sandboxes (e.g. with a fake in tests)
+    pub fn with_tool_sandbox_factory(mut self, factory: ToolSandboxFactory) -> Self {
+        self.tool_sandbox_factory = factory;
+        self
+    }
+    
+    /// Spread tool sandbox execs across `pool_size` instances (see [`Self::exec_in_tool_sandbox`])
+    pub fn with_tool_sandbox_pool_size(mut self, pool_size: usize) -> Self {
+        let pool_size = pool_size.max(1);
+        self.tool_sandbox_pool_size = pool_size;
+        self.tool_sandbox_slots = ToolSandboxSlots::new(pool_size);
+        self
+    }
+    
+    /// Give each session its own tool sandbox (see [`Self::exec_in_session_tool_sandbox`])
+    pub fn with_tool_sandbox_isolation(mut self, isolation: ToolSandboxIsolation) -> Self {
+        self.tool_sandbox_isolation = isolation;
+        self
+    }
+    
+    /// Stop the tool sandbox after `idle` without executions (see [`Self::stop_tool_sandbox_if_idle`])
+    pub fn with_tool_sandbox_idle_timeout(mut self, idle: Option<Duration>) -> Self {
+        self.tool_sandbox_idle = idle.filter(|d| !d.is_zero());
+        self
+    }
+    
+    /// Stop sandboxes that exceed `threshold` (see [`Self::report_violation`])
+    pub fn with_violation_threshold(mut self, threshold: ViolationThreshold) -> Self {
+        self.violation_threshold = threshold;
+        self
+    }
+    
+    /// Send an alert to `chat_id` on `channel` through `tx` when a sandbox is stopped for violations
+    pub fn with_alert_sender(
+        mut self,
+        tx: broadcast::Sender<OutboundMessage>,
+        channel: String,
+        chat_id: String,
+    ) -> Self {
+        self.alert = Some(ViolationAlert { tx, channel, chat_id });
+        self
+    }
+    
+    /// Set the tool sandbox used by [`Self::exec_in_tool_sandbox`]
+    /// 
+    /// The sandbox is not created here; it is created and started on first use.
+    pub async fn set_tool_sandbox_config(&self, config: SandboxConfig) {
+        *self.tool_sandbox_config.write().await = Some(config);
+    }
+    
+    /// ID of the configured tool sandbox, if any
+    pub async fn tool_sandbox_id(&self) -> Option<String> {
+        self.tool_sandbox_config
+            .read()
+            .await
+            .as_ref()
+            .map(|c| c.sandbox_id.clone())
+    }
+    
+    /// IDs of all tool sandbox pool members (the first is [`Self::tool_sandbox_id`]); empty when none is configured
+    pub async fn tool_sandbox_ids(&self) -> Vec<String> {
+        match self.tool_sandbox_id().await {
+            Some(id) => (0..self.tool_sandbox_pool_size)
+                .map(|i| tool_sandbox_member_id(&id, i))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+    
     /// Create an application sandbox instance
     /// 
     /// Creates a sandbox for running applications based on the platform:
@@ -183,7 +448,7 @@ impl SandboxManager {
         let sandbox_id = config.sandbox_id.clone();
 
         // Create only the requested sandbox type (no fallback); if environment doesn't match, fail and user can change config.
-        let sandbox = super::platform::SandboxFactory::create_tool_sandbox(config)?;
+        let sandbox = (self.tool_sandbox_factory)(config)?;
 
         // Store sandbox instance
         {
@@ -223,6 +488,17 @@ impl SandboxManager {
             .collect()
     }
     
+    /// Health check of every active sandbox, keyed by sandbox id
+    pub async fn health_checks(&self) -> Vec<(String, HealthStatus)> {
+        let sandboxes = self.sandboxes.read().await;
+        let mut checks: Vec<_> = sandboxes
+            .iter()
+            .map(|(id, s)| (id.clone(), s.health_check()))
+            .collect();
+        checks.sort_by(|a, b| a.0.cmp(&b.0));
+        checks
+    }
+    
     /// Destroy a sandbox
     /// 
     /// Stops and removes a sandbox instance, cleaning up all resources.
@@ -324,7 +600,8 @@ impl SandboxManager {
     /// 
     /// # Errors
     /// 
-    /// Returns an error if the sandbox is not found or execution fails.
+    /// Returns an error if the sandbox is not found, was stopped for security violations,
+    /// or execution fails.
     pub async fn execute_in_sandbox(
         &self,
         sandbox_id: &str,
@@ -333,11 +610,435 @@ impl SandboxManager {
         timeout: Duration,
         working_dir: Option<&str>,
     ) -> Result<ExecutionResult> {
+        self.ensure_not_quarantined(sandbox_id)?;
+        if let Some(program) = PrivilegeEscalationPrevention::detect_escalation_attempt(command, args) {
+            let detail = format!("command invokes {}", program);
+            self.report_violation(SecurityViolation::new(sandbox_id, ViolationKind::PrivilegeEscalation, detail))
+                .await;
+            self.ensure_not_quarantined(sandbox_id)?;
+        }
+        
+        let started = Instant::now();
+        let result = {
+            let sandboxes = self.sandboxes.read().await;
+            let sandbox = sandboxes
+                .get(sandbox_id)
+                .ok_or(SandboxError::NotFound)?;
+            sandbox.execute(command, args, timeout, working_dir)
+        };
+        let success = matches!(&result, Ok(r) if r.exit_code == 0 && r.error.is_none());
+        self.monitoring.record_tool_execution(started.elapsed(), success);
+        
+        if let Ok(output) = &result {
+            if EscapePrevention::blocked_syscall(output) {
+                let detail = format!("`{}` was killed by the seccomp filter", command);
+                self.report_violation(SecurityViolation::new(sandbox_id, ViolationKind::BlockedSyscall, detail))
+                    .await;
+            }
+        }
+        result
+    }
+    
+    /// Record a security violation and stop the sandbox if it exceeds the violation threshold
+    /// 
+    /// When the threshold is reached the sandbox is stopped, further executions in it are
+    /// refused until [`Self::release_quarantine`], and the alert chat (if any) is told.
+    /// 
+    /// # Returns
+    /// 
+    /// `true` if this violation stopped the sandbox
+    pub async fn report_violation(&self, violation: SecurityViolation) -> bool {
+        tracing::warn!(
+            sandbox_id = %violation.sandbox_id,
+            kind = %violation.kind,
+            detail = %violation.detail,
+            "Sandbox security violation"
+        );
+        self.monitoring.record_violation(&violation).await;
+        if !self.violation_threshold_reached(&violation.sandbox_id, Instant::now()) {
+            return false;
+        }
+        
+        let sandbox_id = violation.sandbox_id.as_str();
+        self.quarantined.lock().unwrap().insert(sandbox_id.to_string());
+        if let Err(e) = self.stop_sandbox(sandbox_id).await {
+            tracing::warn!(sandbox_id = %sandbox_id, error = %e, "Failed to stop sandbox after security violations");
+        }
+        let message = format!(
+            "[Sandbox] Stopped {} after {} security violations within {}s (last: {}: {})",
+            sandbox_id,
+            self.violation_threshold.max_violations,
+            self.violation_threshold.window.as_secs(),
+            violation.kind,
+            violation.detail
+        );
+        tracing::error!("{}", message);
+        if let Some(alert) = &self.alert {
+            let _ = alert.tx.send(OutboundMessage::chat(
+                alert.channel.clone(),
+                alert.chat_id.clone(),
+                message,
+                vec![],
+                None,
+            ));
+        }
+        true
+    }
+    
+    /// Record a blocked outbound connection as an audited network access and a violation
+    pub async fn report_denied_network_host(&self, sandbox_id: &str, host: &str, port: u16) -> bool {
+        self.monitoring.log_network_access(sandbox_id, host, port, false).await;
+        let detail = format!("connection to {}:{} is not in the network allowlist", host, port);
+        self.report_violation(SecurityViolation::new(sandbox_id, ViolationKind::DeniedNetworkHost, detail))
+            .await
+    }
+    
+    /// Add a violation at `now` and report whether the sandbox reached the threshold
+    fn violation_threshold_reached(&self, sandbox_id: &str, now: Instant) -> bool {
+        let threshold = self.violation_threshold;
+        if threshold.max_violations == 0 {
+            return false;
+        }
+        let mut recent = self.recent_violations.lock().unwrap();
+        let times = recent.entry(sandbox_id.to_string()).or_default();
+        times.push_back(now);
+        while times.front().is_some_and(|t| now.duration_since(*t) > threshold.window) {
+            times.pop_front();
+        }
+        if times.len() >= threshold.max_violations as usize {
+            times.clear();
+            true
+        } else {
+            false
+        }
+    }
+    
+    /// Sandboxes stopped for violations whose executions are refused, sorted by id
+    pub fn quarantined_sandboxes(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.quarantined.lock().unwrap().iter().cloned().collect();
+        ids.sort();
+        ids
+    }
+    
+    /// Allow executions in a sandbox stopped for violations again; the next execution
+    /// restarts it. Its violation count starts over.
+    /// 
+    /// # Returns
+    /// 
+    /// `false` if the sandbox was not quarantined
+    pub fn release_quarantine(&self, sandbox_id: &str) -> bool {
+        if !self.quarantined.lock().unwrap().remove(sandbox_id) {
+            return false;
+        }
+        self.recent_violations.lock().unwrap().remove(sandbox_id);
+        tracing::info!(sandbox_id = %sandbox_id, "Sandbox quarantine released");
+        true
+    }
+    
+    fn ensure_not_quarantined(&self, sandbox_id: &str) -> Result<()> {
+        if self.quarantined.lock().unwrap().contains(sandbox_id) {
+            return Err(SandboxError::SecurityViolation(format!(
+                "Sandbox {} was stopped after repeated security violations; release it with `synbot quarantine release {}`",
+                sandbox_id, sandbox_id
+            )));
+        }
+        Ok(())
+    }
+    
+    /// Execute a command in the configured tool sandbox.
+    /// 
+    /// Creates and starts the tool sandbox on first use (and restarts it if it is no longer
+    /// running), then runs the command in it. With a pool size above 1, concurrent execs run
+    /// in different pool members; a member is created the first time it is needed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `command` - The command to run (e.g. "sh" or "cmd")
+    /// * `args` - Command arguments (e.g. ["-c", "echo hello"])
+    /// * `timeout` - Maximum execution time
+    /// * `cwd` - Optional working directory inside the sandbox (e.g. `/workspace`)
+    /// 
+    /// # Errors
+    /// 
+    /// Returns an error if no tool sandbox is configured, it cannot be created or started,
+    /// or execution fails.
+    pub async fn exec_in_tool_sandbox(
+        &self,
+        command: &str,
+        args: &[String],
+        timeout: Duration,
+        cwd: Option<&str>,
+    ) -> Result<ExecutionResult> {
+        self.exec_in_session_tool_sandbox(None, command, args, timeout, cwd).await
+    }
+    
+    /// Execute a command in the tool sandbox of `session_id`.
+    /// 
+    /// With per-session isolation every session runs in its own tool sandbox, created and started
+    /// on the session's first exec and removed once idle (see
+    /// [`Self::remove_idle_session_tool_sandboxes`]). Otherwise, or without a session, this is
+    /// [`Self::exec_in_tool_sandbox`].
+    pub async fn exec_in_session_tool_sandbox(
+        &self,
+        session_id: Option<&str>,
+        command: &str,
+        args: &[String],
+        timeout: Duration,
+        cwd: Option<&str>,
+    ) -> Result<ExecutionResult> {
+        if let (ToolSandboxIsolation::PerSession, Some(session_id)) = (self.tool_sandbox_isolation, session_id) {
+            let (sandbox_id, _running) = self.ensure_session_tool_sandbox(session_id).await?;
+            return self.execute_in_sandbox(&sandbox_id, command, args, timeout, cwd).await;
+        }
+        
+        let _running = self.begin_tool_exec();
+        let slot = self.tool_sandbox_slots.acquire();
+        let sandbox_id = self.ensure_tool_sandbox(slot.index).await?;
+        self.execute_in_sandbox(&sandbox_id, command, args, timeout, cwd).await
+    }
+    
+    fn touch_tool_sandbox(&self) {
+        *self.tool_sandbox_last_used.lock().unwrap() = Some(Instant::now());
+    }
+    
+    /// Count an exec in the shared tool sandbox as running until the guard is dropped.
+    fn begin_tool_exec(&self) -> ToolExecGuard<'_> {
+        self.tool_sandbox_in_flight.fetch_add(1, Ordering::SeqCst);
+        self.touch_tool_sandbox();
+        ToolExecGuard { manager: self }
+    }
+    
+    /// Count an exec in the session tool sandbox `sandbox_id` as running until the guard is dropped.
+    fn begin_session_exec(&self, sandbox_id: &str, workspace: Option<PathBuf>) -> SessionExecGuard<'_> {
+        let mut sandboxes = self.session_tool_sandboxes.lock().unwrap();
+        let entry = sandboxes.entry(sandbox_id.to_string()).or_insert(SessionToolSandbox {
+            last_used: Instant::now(),
+            in_flight: 0,
+            workspace,
+        });
+        entry.in_flight += 1;
+        entry.last_used = Instant::now();
+        SessionExecGuard {
+            sandboxes: &self.session_tool_sandboxes,
+            sandbox_id: sandbox_id.to_string(),
+        }
+    }
+    
+    /// IDs of the per-session tool sandboxes that exist now
+    pub fn session_tool_sandbox_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.session_tool_sandboxes.lock().unwrap().keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+    
+    /// Destroy the tool sandbox of `session_id` and delete its workspace directory (the session
+    /// was reset, e.g. `/clear` or the `reset_session` tool).
+    /// 
+    /// Returns true if the session had one.
+    pub async fn remove_session_tool_sandbox(&self, session_id: &str) -> Result<bool> {
+        if self.tool_sandbox_isolation != ToolSandboxIsolation::PerSession {
+            return Ok(false);
+        }
+        let Some(base_id) = self.tool_sandbox_id().await else {
+            return Ok(false);
+        };
+        let sandbox_id = session_tool_sandbox_id(&base_id, session_id);
+        let _init = self.tool_sandbox_init.lock().await;
+        let Some(removed) = self.session_tool_sandboxes.lock().unwrap().remove(&sandbox_id) else {
+            return Ok(false);
+        };
+        self.destroy_session_tool_sandbox(&sandbox_id).await?;
+        if let Some(dir) = removed.workspace {
+            if let Err(e) = std::fs::remove_dir_all(&dir) {
+                tracing::warn!(dir = %dir.display(), error = %e, "Failed to delete session tool sandbox workspace");
+            }
+        }
+        tracing::info!(sandbox_id = %sandbox_id, "Removed tool sandbox of reset session");
+        Ok(true)
+    }
+    
+    /// Destroy per-session tool sandboxes that have not been used for the idle timeout as of `now`
+    /// (the tool sandbox idle timeout, or [`DEFAULT_SESSION_TOOL_SANDBOX_IDLE`] when none is set)
+    /// and run no exec. Their workspace directories are kept.
+    /// 
+    /// Returns how many were removed. The session's next exec creates a fresh one.
+    pub async fn remove_idle_session_tool_sandboxes(&self, now: Instant) -> Result<usize> {
+        let idle = self.tool_sandbox_idle.unwrap_or(DEFAULT_SESSION_TOOL_SANDBOX_IDLE);
+        let _init = self.tool_sandbox_init.lock().await;
+        let expired: Vec<String> = {
+            let mut sandboxes = self.session_tool_sandboxes.lock().unwrap();
+            let expired: Vec<String> = sandboxes
+                .iter()
+                .filter(|(_, s)| s.in_flight == 0 && now.saturating_duration_since(s.last_used) >= idle)
+                .map(|(id, _)| id.clone())
+                .collect();
+            for id in &expired {
+                sandboxes.remove(id);
+            }
+            expired
+        };
+        for sandbox_id in &expired {
+            self.destroy_session_tool_sandbox(sandbox_id).await?;
+            tracing::info!(sandbox_id = %sandbox_id, idle_secs = idle.as_secs(), "Removed idle session tool sandbox");
+        }
+        Ok(expired.len())
+    }
+    
+    async fn destroy_session_tool_sandbox(&self, sandbox_id: &str) -> Result<()> {
+        match self.destroy_sandbox(sandbox_id).await {
+            Ok(()) | Err(SandboxError::NotFound) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+    
+    /// Time of the last tool sandbox execution, if any
+    pub fn tool_sandbox_last_used(&self) -> Option<Instant> {
+        *self.tool_sandbox_last_used.lock().unwrap()
+    }
+    
+    /// Stop the tool sandbox if it is running, runs no exec and has not been used for the idle
+    /// timeout as of `now`.
+    /// 
+    /// Stops every running pool member. Returns true if any sandbox was stopped. The next
+    /// [`Self::exec_in_tool_sandbox`] starts it again.
+    pub async fn stop_tool_sandbox_if_idle(&self, now: Instant) -> Result<bool> {
+        let Some(idle) = self.tool_sandbox_idle else {
+            return Ok(false);
+        };
+        let sandbox_ids = self.tool_sandbox_ids().await;
+        if sandbox_ids.is_empty() {
+            return Ok(false);
+        }
+        let _init = self.tool_sandbox_init.lock().await;
+        if self.tool_sandbox_in_flight.load(Ordering::SeqCst) > 0 {
+            return Ok(false);
+        }
+        let idle_for = match self.tool_sandbox_last_used() {
+            Some(last) => now.saturating_duration_since(last),
+            None => return Ok(false),
+        };
+        if idle_for < idle {
+            return Ok(false);
+        }
+        let mut stopped = false;
+        for sandbox_id in sandbox_ids {
+            if !self.tool_sandbox_running(&sandbox_id).await {
+                continue;
+            }
+            self.stop_sandbox(&sandbox_id).await?;
+            tracing::info!(sandbox_id = %sandbox_id, idle_secs = idle_for.as_secs(), "Stopped idle tool sandbox");
+            stopped = true;
+        }
+        Ok(stopped)
+    }
+    
+    /// Spawn a task that stops the tool sandbox once it has been idle for the configured timeout
+    /// and removes idle per-session tool sandboxes.
+    /// 
+    /// Returns None when no idle timeout is configured and sessions share the tool sandbox.
+    /// The task exits when the manager is dropped.
+    pub fn spawn_tool_sandbox_idle_reaper(self: &Arc<Self>) -> Option<tokio::task::JoinHandle<()>> {
+        let per_session = self.tool_sandbox_isolation == ToolSandboxIsolation::PerSession;
+        let idle = self
+            .tool_sandbox_idle
+            .or(per_session.then_some(DEFAULT_SESSION_TOOL_SANDBOX_IDLE))?;
+        let manager = Arc::downgrade(self);
+        let period = (idle / 2).clamp(Duration::from_secs(1), Duration::from_secs(30));
+        Some(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(period).await;
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                if let Err(e) = manager.stop_tool_sandbox_if_idle(Instant::now()).await {
+                    tracing::warn!(error = %e, "Failed to stop idle tool sandbox");
+                }
+                if per_session {
+                    if let Err(e) = manager.remove_idle_session_tool_sandboxes(Instant::now()).await {
+                        tracing::warn!(error = %e, "Failed to remove idle session tool sandboxes");
+                    }
+                }
+            }
+        }))
+    }
+    
+    async fn tool_sandbox_running(&self, sandbox_id: &str) -> bool {
         let sandboxes = self.sandboxes.read().await;
-        let sandbox = sandboxes
+        sandboxes
             .get(sandbox_id)
-            .ok_or(SandboxError::NotFound)?;
-        sandbox.execute(command, args, timeout, working_dir)
+            .map(|s| s.get_status().state == SandboxState::Running)
+            .unwrap_or(false)
+    }
+    
+    /// Create and start every tool sandbox pool member that is not running yet; returns their IDs.
+    pub async fn start_tool_sandbox_pool(&self) -> Result<Vec<String>> {
+        let mut sandbox_ids = Vec::with_capacity(self.tool_sandbox_pool_size);
+        for index in 0..self.tool_sandbox_pool_size {
+            sandbox_ids.push(self.ensure_tool_sandbox(index).await?);
+        }
+        // Started but unused: the idle timeout counts from now.
+        self.touch_tool_sandbox();
+        Ok(sandbox_ids)
+    }
+    
+    /// Create and start tool sandbox pool member `index` if needed; returns its ID.
+    async fn ensure_tool_sandbox(&self, index: usize) -> Result<String> {
+        let mut config = self
+            .tool_sandbox_config
+            .read()
+            .await
+            .clone()
+            .ok_or_else(|| SandboxError::Configuration("No tool sandbox configured".to_string()))?;
+        config.sandbox_id = tool_sandbox_member_id(&config.sandbox_id, index);
+        self.ensure_tool_sandbox_started(config).await
+    }
+    
+    /// Create and start the tool sandbox of `session_id` if needed, with its own workspace
+    /// directory (`<workspace>/sessions/<session>-<hash>`) mounted as the workspace. Returns its ID
+    /// and a guard that counts an exec as running in it until dropped.
+    async fn ensure_session_tool_sandbox(&self, session_id: &str) -> Result<(String, SessionExecGuard<'_>)> {
+        let mut config = self
+            .tool_sandbox_config
+            .read()
+            .await
+            .clone()
+            .ok_or_else(|| SandboxError::Configuration("No tool sandbox configured".to_string()))?;
+        let suffix = session_tool_sandbox_suffix(session_id);
+        config.sandbox_id = format!("{}-{}", config.sandbox_id, suffix);
+        let workspace = match config.filesystem.workspace_mount.as_mut() {
+            Some((host, _)) => {
+                let dir = PathBuf::from(&*host).join(SESSION_WORKSPACES_DIR).join(&suffix);
+                std::fs::create_dir_all(&dir).map_err(|e| {
+                    SandboxError::CreationFailed(format!(
+                        "Cannot create session workspace {}: {}",
+                        dir.display(),
+                        e
+                    ))
+                })?;
+                *host = dir.to_string_lossy().into_owned();
+                Some(dir)
+            }
+            None => None,
+        };
+        // Count the exec first so the idle reaper does not remove the sandbox while it is being started.
+        let guard = self.begin_session_exec(&config.sandbox_id, workspace);
+        let sandbox_id = self.ensure_tool_sandbox_started(config).await?;
+        Ok((sandbox_id, guard))
+    }
+    
+    /// Create and start the tool sandbox described by `config` unless it is already running.
+    async fn ensure_tool_sandbox_started(&self, config: SandboxConfig) -> Result<String> {
+        let sandbox_id = config.sandbox_id.clone();
+        self.ensure_not_quarantined(&sandbox_id)?;
+        
+        let _init = self.tool_sandbox_init.lock().await;
+        if !self.sandbox_exists(&sandbox_id).await {
+            self.create_tool_sandbox(config).await?;
+        }
+        if !self.tool_sandbox_running(&sandbox_id).await {
+            self.start_sandbox(&sandbox_id).await?;
+        }
+        Ok(sandbox_id)
     }
     
     /// Get reference to the monitoring module
@@ -388,13 +1089,14 @@ impl SandboxManager {
             return Err(SandboxError::NotFound);
         }
         
-        // Verify isolation integrity
-        EscapePrevention::verify_isolation(sandbox_id)?;
-        
-        // Check for escape attempts
-        EscapePrevention::check_escape_attempts()?;
-        
-        Ok(())
+        // Verify isolation integrity, then check for escape attempts
+        let check = EscapePrevention::verify_isolation(sandbox_id)
+            .and_then(|_| EscapePrevention::check_escape_attempts());
+        if let Err(SandboxError::SecurityViolation(detail)) = &check {
+            self.report_violation(SecurityViolation::new(sandbox_id, ViolationKind::EscapeAttempt, detail.clone()))
+                .await;
+        }
+        check
     }
     
     /// Enforce resource limits for a sandbox
@@ -414,7 +1116,9 @@ impl SandboxManager {
         if let Some(_sandbox) = sandboxes.get(sandbox_id) {
             // In a real implementation, we would fetch the actual config for this sandbox
             // For now, we perform basic resource checks without config
-            ResourceExhaustionPrevention::check_file_descriptors()?;
+            ResourceExhaustionPrevention::check_file_descriptors_with(
+                self.security_validator.resource_limits().fd_warn_threshold,
+            )?;
             
             Ok(())
         } else {
@@ -504,6 +1208,8 @@ impl SandboxManager {
 mod tests {
     use super::*;
     use crate::sandbox::types::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::path::Path;
     use std::time::Duration;
     
     fn create_test_config(sandbox_id: &str, platform: &str) -> SandboxConfig {
@@ -520,6 +1226,7 @@ mod tests {
                 enabled: false,
                 allowed_hosts: vec![],
                 allowed_ports: vec![],
+                host_network: false,
             },
             resources: ResourceConfig {
                 max_memory: 1024 * 1024 * 1024,
@@ -535,6 +1242,7 @@ mod tests {
             delete_on_start: false,
             requested_tool_sandbox_type: None,
             image: None,
+            hardening: HardeningConfig::default(),
         }
     }
     
@@ -640,4 +1348,503 @@ mod tests {
             SandboxError::SecurityViolation(_)
         ));
     }
+    
+    /// Fake tool sandbox that counts how often it is created, started and asked to execute.
+    struct FakeSandbox {
+        sandbox_id: String,
+        state: SandboxState,
+        starts: Arc<AtomicUsize>,
+        execs: Arc<AtomicUsize>,
+        exit_code: i32,
+    }
+    
+    impl Sandbox for FakeSandbox {
+        fn start(&mut self) -> Result<()> {
+            self.starts.fetch_add(1, Ordering::SeqCst);
+            self.state = SandboxState::Running;
+            Ok(())
+        }
+        
+        fn stop(&mut self) -> Result<()> {
+            self.state = SandboxState::Stopped;
+            Ok(())
+        }
+        
+        fn execute(
+            &self,
+            command: &str,
+            args: &[String],
+            _timeout: Duration,
+            _working_dir: Option<&str>,
+        ) -> Result<ExecutionResult> {
+            self.execs.fetch_add(1, Ordering::SeqCst);
+            Ok(ExecutionResult {
+                exit_code: self.exit_code,
+                stdout: format!("{} {}", command, args.join(" ")).into_bytes(),
+                stderr: vec![],
+                duration: Duration::from_millis(1),
+                error: None,
+            })
+        }
+        
+        fn get_status(&self) -> SandboxStatus {
+            SandboxStatus {
+                sandbox_id: self.sandbox_id.clone(),
+                state: self.state.clone(),
+                created_at: chrono::Utc::now(),
+                started_at: None,
+                stopped_at: None,
+                error: None,
+            }
+        }
+        
+        fn health_check(&self) -> HealthStatus {
+            HealthStatus {
+                healthy: true,
+                checks: HashMap::new(),
+                message: "Healthy".to_string(),
+            }
+        }
+        
+        fn get_info(&self) -> SandboxInfo {
+            SandboxInfo {
+                sandbox_id: self.sandbox_id.clone(),
+                platform: "test".to_string(),
+                sandbox_type: "fake".to_string(),
+            }
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_exec_in_tool_sandbox_without_config() {
+        let manager = SandboxManager::with_defaults();
+        let result = manager
+            .exec_in_tool_sandbox("sh", &[], Duration::from_secs(1), None)
+            .await;
+        assert!(matches!(result.unwrap_err(), SandboxError::Configuration(_)));
+    }
+    
+    #[tokio::test]
+    async fn test_exec_in_tool_sandbox_starts_lazily_and_reuses() {
+        let creates = Arc::new(AtomicUsize::new(0));
+        let starts = Arc::new(AtomicUsize::new(0));
+        let execs = Arc::new(AtomicUsize::new(0));
+        let (c, s, e) = (creates.clone(), starts.clone(), execs.clone());
+        let manager = SandboxManager::with_defaults().with_tool_sandbox_factory(Box::new(
+            move |config: SandboxConfig| {
+                c.fetch_add(1, Ordering::SeqCst);
+                Ok(Box::new(FakeSandbox {
+                    sandbox_id: config.sandbox_id,
+                    state: SandboxState::Created,
+                    starts: s.clone(),
+                    execs: e.clone(),
+                    exit_code: 0,
+                }) as Box<dyn Sandbox>)
+            },
+        ));
+        manager
+            .set_tool_sandbox_config(create_test_config("synbot-tool", "linux"))
+            .await;
+        if refused_as_root(&manager).await {
+            return;
+        }
+        assert!(!manager.sandbox_exists("synbot-tool").await);
+        
+        let args = vec!["-c".to_string(), "echo hi".to_string()];
+        let first = manager
+            .exec_in_tool_sandbox("sh", &args, Duration::from_secs(5), Some("/workspace"))
+            .await
+            .unwrap();
+        assert_eq!(first.stdout, b"sh -c echo hi");
+        assert!(manager.sandbox_exists("synbot-tool").await);
+        
+        manager
+            .exec_in_tool_sandbox("sh", &args, Duration::from_secs(5), None)
+            .await
+            .unwrap();
+        assert_eq!(creates.load(Ordering::SeqCst), 1);
+        assert_eq!(starts.load(Ordering::SeqCst), 1);
+        assert_eq!(execs.load(Ordering::SeqCst), 2);
+    }
+    
+    #[tokio::test]
+    async fn test_tool_sandbox_stops_after_idle_and_restarts_on_demand() {
+        let starts = Arc::new(AtomicUsize::new(0));
+        let s = starts.clone();
+        let manager = SandboxManager::with_defaults()
+            .with_tool_sandbox_factory(Box::new(move |config: SandboxConfig| {
+                Ok(Box::new(FakeSandbox {
+                    sandbox_id: config.sandbox_id,
+                    state: SandboxState::Created,
+                    starts: s.clone(),
+                    execs: Arc::new(AtomicUsize::new(0)),
+                    exit_code: 0,
+                }) as Box<dyn Sandbox>)
+            }))
+            .with_tool_sandbox_idle_timeout(Some(Duration::from_secs(60)));
+        manager
+            .set_tool_sandbox_config(create_test_config("synbot-tool", "linux"))
+            .await;
+        if refused_as_root(&manager).await {
+            return;
+        }
+        
+        // Never used: nothing to stop.
+        assert!(!manager.stop_tool_sandbox_if_idle(Instant::now()).await.unwrap());
+        
+        manager
+            .exec_in_tool_sandbox("true", &[], Duration::from_secs(5), None)
+            .await
+            .unwrap();
+        let last = manager.tool_sandbox_last_used().expect("last use tracked");
+        assert!(manager.tool_sandbox_running("synbot-tool").await);
+        
+        // Not idle long enough yet.
+        assert!(!manager
+            .stop_tool_sandbox_if_idle(last + Duration::from_secs(30))
+            .await
+            .unwrap());
+        assert!(manager.tool_sandbox_running("synbot-tool").await);
+        
+        // A running exec keeps it however long ago it started.
+        let running = manager.begin_tool_exec();
+        assert!(!manager
+            .stop_tool_sandbox_if_idle(Instant::now() + Duration::from_secs(600))
+            .await
+            .unwrap());
+        assert!(manager.tool_sandbox_running("synbot-tool").await);
+        drop(running);
+        let last = manager.tool_sandbox_last_used().expect("last use tracked");
+        
+        assert!(manager
+            .stop_tool_sandbox_if_idle(last + Duration::from_secs(61))
+            .await
+            .unwrap());
+        assert!(!manager.tool_sandbox_running("synbot-tool").await);
+        assert_eq!(starts.load(Ordering::SeqCst), 1);
+        
+        manager
+            .exec_in_tool_sandbox("true", &[], Duration::from_secs(5), None)
+            .await
+            .unwrap();
+        assert!(manager.tool_sandbox_running("synbot-tool").await);
+        assert_eq!(starts.load(Ordering::SeqCst), 2);
+    }
+    
+    #[test]
+    fn test_tool_sandbox_slots_use_free_members_before_sharing() {
+        let slots = ToolSandboxSlots::new(3);
+        let first = slots.acquire();
+        let second = slots.acquire();
+        assert_eq!((first.index, second.index), (0, 1));
+        drop(first);
+        
+        // Member 0 is free again: it and member 2 are taken before any member is shared.
+        let third = slots.acquire();
+        let fourth = slots.acquire();
+        let mut held = vec![second.index, third.index, fourth.index];
+        held.sort_unstable();
+        assert_eq!(held, vec![0, 1, 2]);
+        
+        // Every member busy: the next exec shares the least busy one.
+        let fifth = slots.acquire();
+        assert_eq!(fifth.index, 0);
+        drop(second);
+        assert_eq!(slots.acquire().index, 1);
+        drop((third, fourth, fifth));
+        assert_eq!(*slots.running.lock().unwrap(), vec![0, 0, 0]);
+    }
+    
+    #[tokio::test]
+    async fn test_tool_sandbox_pool_spreads_concurrent_execs() {
+        const POOL_SIZE: usize = 3;
+        let manager = SandboxManager::with_defaults().with_tool_sandbox_pool_size(POOL_SIZE);
+        manager
+            .set_tool_sandbox_config(create_test_config("synbot-tool", "linux"))
+            .await;
+        // Running members registered directly, as tool sandbox creation refuses to run as root.
+        let ids: Vec<String> = (0..POOL_SIZE)
+            .map(|index| tool_sandbox_member_id("synbot-tool", index))
+            .collect();
+        assert_eq!(ids, vec!["synbot-tool", "synbot-tool-1", "synbot-tool-2"]);
+        let mut execs = Vec::new();
+        for id in &ids {
+            let counter = Arc::new(AtomicUsize::new(0));
+            manager.sandboxes.write().await.insert(
+                id.clone(),
+                Box::new(FakeSandbox {
+                    sandbox_id: id.clone(),
+                    state: SandboxState::Running,
+                    starts: Arc::new(AtomicUsize::new(0)),
+                    execs: counter.clone(),
+                    exit_code: 0,
+                }),
+            );
+            execs.push(counter);
+        }
+        assert_eq!(manager.tool_sandbox_ids().await, ids);
+        let counts = || execs.iter().map(|c| c.load(Ordering::SeqCst)).collect::<Vec<_>>();
+        
+        // Two execs still running in members 0 and 1: the next one runs in member 2.
+        let running_0 = manager.tool_sandbox_slots.acquire();
+        let running_1 = manager.tool_sandbox_slots.acquire();
+        manager
+            .exec_in_tool_sandbox("true", &[], Duration::from_secs(5), None)
+            .await
+            .unwrap();
+        assert_eq!(counts(), vec![0, 0, 1]);
+        
+        // Member 0 finished: it is reused while member 1 is still busy.
+        drop(running_0);
+        manager
+            .exec_in_tool_sandbox("true", &[], Duration::from_secs(5), None)
+            .await
+            .unwrap();
+        assert_eq!(counts(), vec![1, 0, 1]);
+        
+        // Every member busy: the exec shares the least busy one.
+        let running_0 = manager.tool_sandbox_slots.acquire();
+        let running_2 = manager.tool_sandbox_slots.acquire();
+        let _extra = manager.tool_sandbox_slots.acquire();
+        manager
+            .exec_in_tool_sandbox("true", &[], Duration::from_secs(5), None)
+            .await
+            .unwrap();
+        assert_eq!(counts(), vec![1, 1, 1]);
+        drop((running_0, running_1, running_2));
+    }
+    
+    #[tokio::test]
+    async fn test_per_session_isolation_gives_sessions_their_own_sandbox() {
+        let created = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let c = created.clone();
+        let manager = SandboxManager::with_defaults()
+            .with_tool_sandbox_factory(Box::new(move |config: SandboxConfig| {
+                c.lock().unwrap().push(config.sandbox_id.clone());
+                Ok(Box::new(FakeSandbox {
+                    sandbox_id: config.sandbox_id,
+                    state: SandboxState::Created,
+                    starts: Arc::new(AtomicUsize::new(0)),
+                    execs: Arc::new(AtomicUsize::new(0)),
+                    exit_code: 0,
+                }) as Box<dyn Sandbox>)
+            }))
+            .with_tool_sandbox_isolation(ToolSandboxIsolation::PerSession)
+            .with_tool_sandbox_idle_timeout(Some(Duration::from_secs(60)));
+        manager
+            .set_tool_sandbox_config(create_test_config("synbot-tool", "linux"))
+            .await;
+        if refused_as_root(&manager).await {
+            return;
+        }
+        
+        for session in ["telegram:1", "telegram:2", "telegram:1"] {
+            manager
+                .exec_in_session_tool_sandbox(Some(session), "true", &[], Duration::from_secs(5), None)
+                .await
+                .unwrap();
+        }
+        let (first, second) = (
+            session_tool_sandbox_id("synbot-tool", "telegram:1"),
+            session_tool_sandbox_id("synbot-tool", "telegram:2"),
+        );
+        assert!(first.starts_with("synbot-tool-telegram-1-"), "{}", first);
+        let mut ids = vec![first.clone(), second.clone()];
+        ids.sort();
+        assert_eq!(manager.session_tool_sandbox_ids(), ids);
+        assert_eq!(*created.lock().unwrap(), vec![first.clone(), second.clone()], "one sandbox per session, reused by later execs");
+        assert!(!manager.sandbox_exists("synbot-tool").await);
+        
+        // Session end tears its sandbox down; the other one goes once idle.
+        assert!(manager.remove_session_tool_sandbox("telegram:1").await.unwrap());
+        assert!(!manager.sandbox_exists(&first).await);
+        assert_eq!(manager.remove_idle_session_tool_sandboxes(Instant::now()).await.unwrap(), 0);
+        let later = Instant::now() + Duration::from_secs(61);
+        // A running exec keeps the sandbox however long ago it started.
+        let running = manager.begin_session_exec(&second, None);
+        assert_eq!(manager.remove_idle_session_tool_sandboxes(later + Duration::from_secs(61)).await.unwrap(), 0);
+        drop(running);
+        let later = Instant::now() + Duration::from_secs(61);
+        assert_eq!(manager.remove_idle_session_tool_sandboxes(later).await.unwrap(), 1);
+        assert!(!manager.sandbox_exists(&second).await);
+        assert!(manager.session_tool_sandbox_ids().is_empty());
+    }
+    
+    #[test]
+    fn test_session_tool_sandbox_names_do_not_collide() {
+        let a = session_tool_sandbox_id("synbot-tool", "main:telegram:dm:1");
+        let b = session_tool_sandbox_id("synbot-tool", "main:telegram:dm-1");
+        assert_ne!(a, b, "sessions that sanitize to the same text still get their own sandbox");
+        assert_eq!(a, session_tool_sandbox_id("synbot-tool", "main:telegram:dm:1"));
+        assert!(a.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c)), "{}", a);
+    }
+    
+    #[tokio::test]
+    async fn test_per_session_workspaces_are_separate_directories() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mounts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let m = mounts.clone();
+        let manager = SandboxManager::with_defaults()
+            .with_tool_sandbox_factory(Box::new(move |config: SandboxConfig| {
+                m.lock().unwrap().push(config.filesystem.workspace_mount.clone().unwrap().0);
+                Ok(Box::new(FakeSandbox {
+                    sandbox_id: config.sandbox_id,
+                    state: SandboxState::Created,
+                    starts: Arc::new(AtomicUsize::new(0)),
+                    execs: Arc::new(AtomicUsize::new(0)),
+                    exit_code: 0,
+                }) as Box<dyn Sandbox>)
+            }))
+            .with_tool_sandbox_isolation(ToolSandboxIsolation::PerSession);
+        let mut config = create_test_config("synbot-tool", "linux");
+        config.filesystem.workspace_mount =
+            Some((tmp.path().to_string_lossy().into_owned(), "/workspace".to_string()));
+        manager.set_tool_sandbox_config(config).await;
+        if refused_as_root(&manager).await {
+            return;
+        }
+        
+        for session in ["telegram:1", "telegram:2"] {
+            manager
+                .exec_in_session_tool_sandbox(Some(session), "true", &[], Duration::from_secs(5), None)
+                .await
+                .unwrap();
+        }
+        let mounts = mounts.lock().unwrap().clone();
+        assert_eq!(mounts.len(), 2);
+        assert_ne!(mounts[0], mounts[1]);
+        for mount in &mounts {
+            assert!(Path::new(mount).starts_with(tmp.path().join(SESSION_WORKSPACES_DIR)), "{}", mount);
+            assert!(Path::new(mount).is_dir());
+        }
+        
+        // Resetting the session deletes its workspace; the other session's stays.
+        assert!(manager.remove_session_tool_sandbox("telegram:1").await.unwrap());
+        assert!(!Path::new(&mounts[0]).exists());
+        assert!(Path::new(&mounts[1]).is_dir());
+    }
+    
+    /// Tool sandbox creation refuses to run as root on Linux. Returns true, after checking that
+    /// `manager` (with a tool sandbox config set) refuses, when the test cannot go on as root.
+    #[cfg(target_os = "linux")]
+    async fn refused_as_root(manager: &SandboxManager) -> bool {
+        if !nix::unistd::Uid::effective().is_root() {
+            return false;
+        }
+        let err = manager
+            .exec_in_tool_sandbox("true", &[], Duration::from_secs(5), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SandboxError::SecurityViolation(_)), "{:?}", err);
+        true
+    }
+    
+    #[cfg(not(target_os = "linux"))]
+    async fn refused_as_root(_manager: &SandboxManager) -> bool {
+        false
+    }
+    
+    fn manager_with_exit_code(exit_code: i32) -> SandboxManager {
+        SandboxManager::with_defaults().with_tool_sandbox_factory(Box::new(move |config: SandboxConfig| {
+            Ok(Box::new(FakeSandbox {
+                sandbox_id: config.sandbox_id,
+                state: SandboxState::Created,
+                starts: Arc::new(AtomicUsize::new(0)),
+                execs: Arc::new(AtomicUsize::new(0)),
+                exit_code,
+            }) as Box<dyn Sandbox>)
+        }))
+    }
+    
+    #[tokio::test]
+    async fn test_execution_emits_security_violations() {
+        let manager = manager_with_exit_code(159);
+        manager
+            .set_tool_sandbox_config(create_test_config("synbot-tool", "linux"))
+            .await;
+        if refused_as_root(&manager).await {
+            return;
+        }
+        let args = vec!["-c".to_string(), "doas id".to_string()];
+        manager
+            .exec_in_tool_sandbox("sh", &args, Duration::from_secs(5), None)
+            .await
+            .unwrap();
+        
+        let violations = manager.monitoring().snapshot().counters.violations;
+        assert_eq!(violations.get("privilege_escalation"), Some(&1));
+        assert_eq!(violations.get("blocked_syscall"), Some(&1));
+        // Stopping is opt-in: without a threshold the sandbox keeps running
+        assert!(manager.tool_sandbox_running("synbot-tool").await);
+    }
+    
+    #[tokio::test]
+    async fn test_violation_threshold_stops_sandbox_and_alerts() {
+        let (alert_tx, mut alert_rx) = broadcast::channel(8);
+        let manager = manager_with_exit_code(159)
+            .with_violation_threshold(ViolationThreshold {
+                max_violations: 2,
+                window: Duration::from_secs(60),
+            })
+            .with_alert_sender(alert_tx, "telegram".to_string(), "ops".to_string());
+        manager
+            .set_tool_sandbox_config(create_test_config("synbot-tool", "linux"))
+            .await;
+        if refused_as_root(&manager).await {
+            return;
+        }
+        
+        manager
+            .exec_in_tool_sandbox("true", &[], Duration::from_secs(5), None)
+            .await
+            .unwrap();
+        assert!(manager.tool_sandbox_running("synbot-tool").await);
+        assert!(alert_rx.try_recv().is_err());
+        
+        manager
+            .exec_in_tool_sandbox("true", &[], Duration::from_secs(5), None)
+            .await
+            .unwrap();
+        assert!(!manager.tool_sandbox_running("synbot-tool").await);
+        
+        let alert = alert_rx.try_recv().expect("alert sent");
+        assert_eq!((alert.channel.as_str(), alert.chat_id.as_str()), ("telegram", "ops"));
+        match alert.message_type {
+            crate::bus::OutboundMessageType::Chat { content, .. } => {
+                assert!(content.contains("synbot-tool"));
+                assert!(content.contains("blocked_syscall"));
+            }
+            other => panic!("unexpected alert {:?}", other),
+        }
+        
+        // Stopped sandboxes are not restarted on the next exec
+        let result = manager
+            .exec_in_tool_sandbox("true", &[], Duration::from_secs(5), None)
+            .await;
+        assert!(matches!(result.unwrap_err(), SandboxError::SecurityViolation(_)));
+        assert!(!manager.tool_sandbox_running("synbot-tool").await);
+        assert_eq!(manager.quarantined_sandboxes(), ["synbot-tool"]);
+        
+        // Releasing the quarantine lets the next exec restart it
+        assert!(manager.release_quarantine("synbot-tool"));
+        assert!(!manager.release_quarantine("synbot-tool"));
+        manager
+            .exec_in_tool_sandbox("true", &[], Duration::from_secs(5), None)
+            .await
+            .unwrap();
+        assert!(manager.tool_sandbox_running("synbot-tool").await);
+    }
+    
+    #[test]
+    fn test_violation_window_expires() {
+        let manager = SandboxManager::with_defaults().with_violation_threshold(ViolationThreshold {
+            max_violations: 2,
+            window: Duration::from_secs(10),
+        });
+        let start = Instant::now();
+        assert!(!manager.violation_threshold_reached("sb", start));
+        // The first violation has left the window
+        assert!(!manager.violation_threshold_reached("sb", start + Duration::from_secs(11)));
+        assert!(manager.violation_threshold_reached("sb", start + Duration::from_secs(12)));
+    }
 }
@@ -9,8 +9,10 @@ pub mod config;
 pub mod monitoring;
 pub mod sandbox_trait;
 pub mod manager;
+#[cfg(feature = "sandbox-docker")]
 pub mod gvisor_docker;
 //#[cfg(any(target_os = "linux", target_os = "macos"))]
+#[cfg(feature = "sandbox-docker")]
 pub mod plain_docker;
 pub mod isolation;
 pub mod recovery;
@@ -33,7 +35,7 @@ pub mod windows_appcontainer;
 #[cfg(target_os = "macos")]
 pub mod macos_sandbox_exec;
 
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", feature = "sandbox-docker"))]
 pub mod wsl2;
 
 // Re-export commonly used types
@@ -44,6 +46,7 @@ pub use config::*;
 pub use monitoring::*;
 pub use sandbox_trait::Sandbox;
 pub use manager::SandboxManager;
+#[cfg(feature = "sandbox-docker")]
 pub use gvisor_docker::GVisorDockerSandbox;
 pub use isolation::{
     IsolationVerifier, IsolationVerification, IsolationCheck,
@@ -57,9 +60,11 @@ pub use security::{
     ResourceExhaustionPrevention, ResourceLimits,
 };
 pub use performance::{
-    DockerConnectionPool, LazyInit, ConfigCache, BatchExecutor,
+    LazyInit, ConfigCache, BatchExecutor,
     ResourcePool, PoolStats, StringInterner, parallel_init,
 };
+#[cfg(feature = "sandbox-docker")]
+pub use performance::DockerConnectionPool;
 
 #[cfg(any(target_os = "linux", target_os = "macos"))]
 pub use nono::NonoSandbox;
@@ -67,5 +72,5 @@ pub use nono::NonoSandbox;
 #[cfg(target_os = "windows")]
 pub use windows_appcontainer::WindowsAppContainerSandbox;
 
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", feature = "sandbox-docker"))]
 pub use wsl2::{Wsl2Integration, Wsl2GVisorSandbox};
@@ -1,11 +1,17 @@
 // Monitoring and auditing module for the sandbox security solution
 
 use super::types::{AuditEvent, MonitoringConfig, SandboxMetrics};
-use chrono::Utc;
-use std::collections::HashMap;
-use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::sync::RwLock;
 
+/// Upper bounds (seconds) of the tool execution duration histogram buckets
+pub const TOOL_DURATION_BUCKETS: [f64; 9] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
 /// Logger trait for different log outputs
 pub trait Logger: Send + Sync {
     fn log(&self, event: &AuditEvent);
@@ -62,12 +68,264 @@ impl Logger for SyslogLogger {
     }
 }
 
+/// Kind of security violation detected in a sandbox
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationKind {
+    /// Isolation check failed or the sandbox tried to reach the host
+    EscapeAttempt,
+    /// Outbound connection to a host outside the network allowlist
+    DeniedNetworkHost,
+    /// Process killed by the seccomp filter (SIGSYS)
+    BlockedSyscall,
+    /// Attempt to gain privileges (sudo, su, setuid helpers, ...)
+    PrivilegeEscalation,
+}
+
+impl ViolationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ViolationKind::EscapeAttempt => "escape_attempt",
+            ViolationKind::DeniedNetworkHost => "denied_network_host",
+            ViolationKind::BlockedSyscall => "blocked_syscall",
+            ViolationKind::PrivilegeEscalation => "privilege_escalation",
+        }
+    }
+}
+
+impl std::fmt::Display for ViolationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Security violation event emitted by the security and monitoring layer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityViolation {
+    pub sandbox_id: String,
+    pub kind: ViolationKind,
+    pub detail: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl SecurityViolation {
+    pub fn new(sandbox_id: &str, kind: ViolationKind, detail: impl Into<String>) -> Self {
+        Self {
+            sandbox_id: sandbox_id.to_string(),
+            kind,
+            detail: detail.into(),
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// Stop a sandbox once it reports `max_violations` violations within `window`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ViolationThreshold {
+    /// Violations that trigger shutdown (0 = never stop, the default)
+    pub max_violations: u32,
+    pub window: Duration,
+}
+
+impl Default for ViolationThreshold {
+    fn default() -> Self {
+        Self {
+            max_violations: 0,
+            window: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Cumulative histogram (Prometheus semantics: each bucket counts observations <= its bound)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramSnapshot {
+    /// `(upper_bound, cumulative_count)` pairs in ascending bound order
+    pub buckets: Vec<(f64, u64)>,
+    pub count: u64,
+    pub sum: f64,
+}
+
+impl HistogramSnapshot {
+    fn with_bounds(bounds: &[f64]) -> Self {
+        Self {
+            buckets: bounds.iter().map(|b| (*b, 0)).collect(),
+            count: 0,
+            sum: 0.0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        for (bound, count) in &mut self.buckets {
+            if value <= *bound {
+                *count += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+}
+
+/// Monotonic counters recorded by [`MonitoringModule`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsCounters {
+    pub sandboxes_created: u64,
+    pub tool_executions: u64,
+    pub tool_failures: u64,
+    pub file_access_allowed: u64,
+    pub file_access_denied: u64,
+    pub network_access_allowed: u64,
+    pub network_access_denied: u64,
+    pub process_creations: u64,
+    /// Violations keyed by violation type
+    pub violations: BTreeMap<String, u64>,
+}
+
+/// Point-in-time values recorded by [`MonitoringModule`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsGauges {
+    /// Events currently held in the in-memory audit log
+    pub audit_log_entries: u64,
+}
+
+/// Histograms recorded by [`MonitoringModule`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsHistograms {
+    pub tool_duration_seconds: HistogramSnapshot,
+}
+
+impl Default for MetricsHistograms {
+    fn default() -> Self {
+        Self {
+            tool_duration_seconds: HistogramSnapshot::with_bounds(&TOOL_DURATION_BUCKETS),
+        }
+    }
+}
+
+/// Structured copy of every metric, served as JSON by `/api/metrics` and as
+/// Prometheus text by `/metrics`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsSnapshot {
+    pub counters: MetricsCounters,
+    pub gauges: MetricsGauges,
+    pub histograms: MetricsHistograms,
+}
+
+impl MetricsSnapshot {
+    /// Render in the Prometheus text exposition format (version 0.0.4)
+    pub fn to_prometheus(&self) -> String {
+        let c = &self.counters;
+        let mut out = String::new();
+        write_metric(
+            &mut out,
+            "synbot_sandboxes_created_total",
+            "counter",
+            "Sandboxes created",
+            &[("", c.sandboxes_created as f64)],
+        );
+        write_metric(
+            &mut out,
+            "synbot_tool_executions_total",
+            "counter",
+            "Commands executed in sandboxes",
+            &[("", c.tool_executions as f64)],
+        );
+        write_metric(
+            &mut out,
+            "synbot_tool_failures_total",
+            "counter",
+            "Sandbox command executions that failed or exited non-zero",
+            &[("", c.tool_failures as f64)],
+        );
+        write_metric(
+            &mut out,
+            "synbot_file_access_total",
+            "counter",
+            "Audited file accesses",
+            &[
+                ("allowed=\"true\"", c.file_access_allowed as f64),
+                ("allowed=\"false\"", c.file_access_denied as f64),
+            ],
+        );
+        write_metric(
+            &mut out,
+            "synbot_network_access_total",
+            "counter",
+            "Audited network accesses",
+            &[
+                ("allowed=\"true\"", c.network_access_allowed as f64),
+                ("allowed=\"false\"", c.network_access_denied as f64),
+            ],
+        );
+        write_metric(
+            &mut out,
+            "synbot_process_creations_total",
+            "counter",
+            "Audited process creations",
+            &[("", c.process_creations as f64)],
+        );
+        let violations: Vec<(String, f64)> = c
+            .violations
+            .iter()
+            .map(|(kind, n)| (format!("type=\"{}\"", escape_label(kind)), *n as f64))
+            .collect();
+        let violations: Vec<(&str, f64)> = violations.iter().map(|(l, n)| (l.as_str(), *n)).collect();
+        write_metric(
+            &mut out,
+            "synbot_sandbox_violations_total",
+            "counter",
+            "Security violations by type",
+            &violations,
+        );
+        write_metric(
+            &mut out,
+            "synbot_audit_log_entries",
+            "gauge",
+            "Events held in the in-memory audit log",
+            &[("", self.gauges.audit_log_entries as f64)],
+        );
+
+        let h = &self.histograms.tool_duration_seconds;
+        let name = "synbot_tool_duration_seconds";
+        let _ = writeln!(out, "# HELP {} Sandbox command execution time", name);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for (bound, count) in &h.buckets {
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, count);
+        }
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, h.count);
+        let _ = writeln!(out, "{}_sum {}", name, h.sum);
+        let _ = writeln!(out, "{}_count {}", name, h.count);
+        out
+    }
+}
+
+fn write_metric(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(&str, f64)]) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    for (labels, value) in samples {
+        if labels.is_empty() {
+            let _ = writeln!(out, "{} {}", name, value);
+        } else {
+            let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+        }
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
 /// Monitoring module
 pub struct MonitoringModule {
     config: MonitoringConfig,
     loggers: Vec<Arc<dyn Logger>>,
     metrics_collector: MetricsCollector,
     audit_log: Arc<RwLock<Vec<AuditEvent>>>,
+    metrics: Mutex<MetricsSnapshot>,
 }
 
 impl MonitoringModule {
@@ -81,9 +339,30 @@ impl MonitoringModule {
             loggers,
             metrics_collector,
             audit_log: Arc::new(RwLock::new(Vec::new())),
+            metrics: Mutex::new(MetricsSnapshot::default()),
         }
     }
     
+    fn record(&self, update: impl FnOnce(&mut MetricsSnapshot)) {
+        update(&mut self.metrics.lock().unwrap());
+    }
+    
+    /// Current value of every counter, gauge and histogram
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        self.metrics.lock().unwrap().clone()
+    }
+    
+    /// Record a command executed in a sandbox
+    pub fn record_tool_execution(&self, duration: Duration, success: bool) {
+        self.record(|m| {
+            m.counters.tool_executions += 1;
+            if !success {
+                m.counters.tool_failures += 1;
+            }
+            m.histograms.tool_duration_seconds.observe(duration.as_secs_f64());
+        });
+    }
+    
     /// Initialize loggers based on configuration
     fn init_loggers(config: &MonitoringConfig) -> Vec<Arc<dyn Logger>> {
         let mut loggers: Vec<Arc<dyn Logger>> = Vec::new();
@@ -101,6 +380,7 @@ impl MonitoringModule {
     
     /// Log sandbox creation
     pub fn log_sandbox_created(&self, sandbox_id: &str, sandbox_type: &str) {
+        self.record(|m| m.counters.sandboxes_created += 1);
         let event = AuditEvent {
             timestamp: Utc::now(),
             sandbox_id: sandbox_id.to_string(),
@@ -118,6 +398,10 @@ impl MonitoringModule {
     
     /// Log file access
     pub async fn log_file_access(&self, sandbox_id: &str, path: &str, operation: &str, allowed: bool) {
+        self.record(|m| match allowed {
+            true => m.counters.file_access_allowed += 1,
+            false => m.counters.file_access_denied += 1,
+        });
         if !self.config.audit.file_access {
             return;
         }
@@ -138,6 +422,10 @@ impl MonitoringModule {
     
     /// Log network access
     pub async fn log_network_access(&self, sandbox_id: &str, host: &str, port: u16, allowed: bool) {
+        self.record(|m| match allowed {
+            true => m.counters.network_access_allowed += 1,
+            false => m.counters.network_access_denied += 1,
+        });
         if !self.config.audit.network_access {
             return;
         }
@@ -158,6 +446,7 @@ impl MonitoringModule {
     
     /// Log process creation
     pub async fn log_process_creation(&self, sandbox_id: &str, command: &str, args: &[String]) {
+        self.record(|m| m.counters.process_creations += 1);
         if !self.config.audit.process_creation {
             return;
         }
@@ -177,6 +466,7 @@ impl MonitoringModule {
     
     /// Log violation
     pub async fn log_violation(&self, sandbox_id: &str, violation_type: &str, details: serde_json::Value) {
+        self.record(|m| *m.counters.violations.entry(violation_type.to_string()).or_default() += 1);
         if !self.config.audit.violations {
             return;
         }
@@ -199,11 +489,22 @@ impl MonitoringModule {
         self.send_alert(event);
     }
     
+    /// Record a [`SecurityViolation`] (counted, audited and alerted like [`Self::log_violation`])
+    pub async fn record_violation(&self, violation: &SecurityViolation) {
+        let details = serde_json::json!({
+            "detail": violation.detail,
+            "detected_at": violation.timestamp.to_rfc3339(),
+        });
+        self.log_violation(&violation.sandbox_id, violation.kind.as_str(), details).await;
+    }
+    
     /// Write audit log to all configured loggers
     async fn write_audit_log(&self, event: AuditEvent) {
         // Store in memory for querying
         let mut log = self.audit_log.write().await;
         log.push(event.clone());
+        let entries = log.len() as u64;
+        self.record(|m| m.gauges.audit_log_entries = entries);
         
         // Write to all loggers
         for logger in &self.loggers {
@@ -648,6 +949,69 @@ mod tests {
         assert_eq!(history.len(), 0);
     }
     
+    #[tokio::test]
+    async fn test_snapshot_reflects_recorded_events() {
+        let monitoring = MonitoringModule::new(create_test_monitoring_config());
+        assert_eq!(monitoring.snapshot(), MetricsSnapshot::default());
+        
+        monitoring.log_sandbox_created("test-sandbox", "tool");
+        monitoring.log_file_access("test-sandbox", "/etc/passwd", "read", false).await;
+        monitoring.log_network_access("test-sandbox", "example.com", 443, true).await;
+        monitoring.log_violation("test-sandbox", "escape_attempt", serde_json::json!({})).await;
+        monitoring.log_violation("test-sandbox", "escape_attempt", serde_json::json!({})).await;
+        monitoring.record_tool_execution(Duration::from_millis(200), true);
+        monitoring.record_tool_execution(Duration::from_secs(3), false);
+        
+        let snapshot = monitoring.snapshot();
+        assert_eq!(snapshot.counters.sandboxes_created, 1);
+        assert_eq!(snapshot.counters.file_access_denied, 1);
+        assert_eq!(snapshot.counters.network_access_allowed, 1);
+        assert_eq!(snapshot.counters.violations.get("escape_attempt"), Some(&2));
+        assert_eq!(snapshot.counters.tool_executions, 2);
+        assert_eq!(snapshot.counters.tool_failures, 1);
+        assert_eq!(snapshot.gauges.audit_log_entries, 4);
+        
+        let durations = &snapshot.histograms.tool_duration_seconds;
+        assert_eq!(durations.count, 2);
+        assert!((durations.sum - 3.2).abs() < 1e-9);
+        let bucket = |le: f64| durations.buckets.iter().find(|(b, _)| *b == le).unwrap().1;
+        assert_eq!(bucket(0.1), 0);
+        assert_eq!(bucket(0.25), 1);
+        assert_eq!(bucket(5.0), 2);
+        
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(json["counters"]["toolExecutions"], 2);
+        assert_eq!(json["histograms"]["toolDurationSeconds"]["count"], 2);
+    }
+    
+    #[tokio::test]
+    async fn test_record_security_violation() {
+        let monitoring = MonitoringModule::new(create_test_monitoring_config());
+        let violation = SecurityViolation::new("test-sandbox", ViolationKind::BlockedSyscall, "ptrace");
+        
+        monitoring.record_violation(&violation).await;
+        
+        let log = monitoring.audit_log.read().await;
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].event_type, "violation");
+        assert_eq!(log[0].details["violation_type"], "blocked_syscall");
+        assert_eq!(log[0].details["detail"], "ptrace");
+        assert_eq!(monitoring.snapshot().counters.violations.get("blocked_syscall"), Some(&1));
+    }
+    
+    #[test]
+    fn test_snapshot_prometheus_text() {
+        let monitoring = MonitoringModule::new(create_test_monitoring_config());
+        monitoring.record_tool_execution(Duration::from_millis(20), true);
+        
+        let text = monitoring.snapshot().to_prometheus();
+        assert!(text.contains("# TYPE synbot_tool_executions_total counter"));
+        assert!(text.contains("synbot_tool_executions_total 1\n"));
+        assert!(text.contains("synbot_tool_duration_seconds_bucket{le=\"0.05\"} 1\n"));
+        assert!(text.contains("synbot_tool_duration_seconds_bucket{le=\"+Inf\"} 1\n"));
+        assert!(text.contains("synbot_file_access_total{allowed=\"false\"} 0\n"));
+    }
+    
     #[test]
     fn test_monitoring_module_collect_metrics() {
         let config = create_test_monitoring_config();
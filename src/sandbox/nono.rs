@@ -6,6 +6,7 @@
 
 use super::error::{Result, SandboxError};
 use super::sandbox_trait::Sandbox;
+use super::security::PrivilegeEscalationPrevention;
 use super::types::*;
 use chrono::Utc;
 use std::path::Path;
@@ -158,6 +159,8 @@ pub struct NonoSandbox {
     nono_config: NonoConfig,
     process: Option<Child>,
     status: SandboxStatus,
+    /// Applies no_new_privs to spawned commands; swapped out in tests.
+    no_new_privs_hook: fn(&mut Command),
 }
 
 impl NonoSandbox {
@@ -187,8 +190,16 @@ impl NonoSandbox {
             nono_config,
             process: None,
             status,
+            no_new_privs_hook: PrivilegeEscalationPrevention::no_new_privs_pre_exec,
         })
     }
+
+    /// Apply process hardening from `config.hardening` to a `nono` command before it is spawned.
+    fn harden_command(&self, cmd: &mut Command) {
+        if self.config.hardening.no_new_privileges {
+            (self.no_new_privs_hook)(cmd);
+        }
+    }
     
     /// Build nono.sh specific configuration from sandbox config
     fn build_nono_config(config: &SandboxConfig) -> NonoConfig {
@@ -438,12 +449,14 @@ impl NonoSandbox {
         nono_args.push(exe.to_string_lossy().into_owned());
         nono_args.extend_from_slice(args);
 
-        let child = Command::new("nono")
-            .args(&nono_args)
+        let mut cmd = Command::new("nono");
+        cmd.args(&nono_args)
             .env("SYNBOT_IN_APP_SANDBOX", "1")
             .stdin(Stdio::inherit())
             .stdout(Stdio::inherit())
-            .stderr(Stdio::inherit())
+            .stderr(Stdio::inherit());
+        self.harden_command(&mut cmd);
+        let child = cmd
             .spawn()
             .map_err(|e| SandboxError::CreationFailed(format!("Failed to spawn child in nono sandbox: {}", e)))?;
 
@@ -514,6 +527,7 @@ impl Sandbox for NonoSandbox {
         if let Some(wd) = working_dir.filter(|s| !s.is_empty()) {
             cmd.current_dir(wd);
         }
+        self.harden_command(&mut cmd);
         let output = cmd
             .output()
             .map_err(|e| SandboxError::ExecutionFailed(format!("Failed to execute command: {}", e)))?;
@@ -604,6 +618,7 @@ mod tests {
                 enabled: false,
                 allowed_hosts: vec![],
                 allowed_ports: vec![],
+                host_network: false,
             },
             resources: ResourceConfig {
                 max_memory: 1024 * 1024 * 1024, // 1GB
@@ -619,6 +634,7 @@ mod tests {
             delete_on_start: false,
             requested_tool_sandbox_type: None,
             image: None,
+            hardening: HardeningConfig::default(),
         }
     }
     
@@ -661,6 +677,29 @@ mod tests {
         assert!(sandbox.status.stopped_at.is_some());
     }
     
+    static HOOK_CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+    fn counting_hook(_cmd: &mut Command) {
+        HOOK_CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_harden_command_applies_no_new_privs_only_when_enabled() {
+        use std::sync::atomic::Ordering;
+
+        let mut sandbox = NonoSandbox::new(create_test_config()).unwrap();
+        assert!(sandbox.config.hardening.no_new_privileges);
+        sandbox.no_new_privs_hook = counting_hook;
+
+        let before = HOOK_CALLS.load(Ordering::SeqCst);
+        sandbox.harden_command(&mut Command::new("nono"));
+        assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), before + 1);
+
+        sandbox.config.hardening.no_new_privileges = false;
+        sandbox.harden_command(&mut Command::new("nono"));
+        assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), before + 1);
+    }
+
     #[test]
     fn test_build_nono_args() {
         let config = create_test_config();
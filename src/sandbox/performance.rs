@@ -13,11 +13,13 @@ use std::time::{Duration, Instant};
 /// Connection pool for Docker clients to reduce connection overhead
 /// 
 /// Reusing Docker connections significantly reduces tool execution latency
+#[cfg(feature = "sandbox-docker")]
 pub struct DockerConnectionPool {
     connections: Arc<RwLock<Vec<bollard::Docker>>>,
     max_size: usize,
 }
 
+#[cfg(feature = "sandbox-docker")]
 impl DockerConnectionPool {
     /// Create a new connection pool
     pub fn new(max_size: usize) -> Self {
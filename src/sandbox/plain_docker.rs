@@ -3,8 +3,10 @@
 
 use super::error::{Result, SandboxError};
 use super::sandbox_trait::Sandbox;
+use super::security::PrivilegeEscalationPrevention;
 use super::types::{
-    ExecutionResult, HealthStatus, SandboxConfig, SandboxInfo, SandboxState, SandboxStatus,
+    ExecutionResult, HealthStatus, NetworkConfig, SandboxConfig, SandboxInfo, SandboxState,
+    SandboxStatus,
 };
 use bollard::container::{
     Config, CreateContainerOptions, RemoveContainerOptions, StartContainerOptions,
@@ -103,30 +105,210 @@ impl PlainDockerSandbox {
         })
     }
 
-    fn get_network_mode(&self) -> String {
-        if self.config.network.enabled {
-            "bridge".to_string()
-        } else {
-            "none".to_string()
+}
+
+/// Docker network mode for the config: "none" when disabled, "host" only when escape prevention
+/// accepted it (allowInsecure), otherwise "bridge".
+fn docker_network_mode(config: &SandboxConfig) -> String {
+    if config.network.enabled && config.network.host_network {
+        "host".to_string()
+    } else if config.network.enabled {
+        "bridge".to_string()
+    } else {
+        "none".to_string()
+    }
+}
+
+/// Volume bindings: writable_paths as host:host, plus workspace (rw) and skills (ro) mounts if set.
+fn docker_binds(config: &SandboxConfig) -> Vec<String> {
+    let mut binds: Vec<String> = config
+        .filesystem
+        .writable_paths
+        .iter()
+        .map(|p| format!("{}:{}", p, p))
+        .collect();
+    if let Some((ref host, ref guest)) = config.filesystem.workspace_mount {
+        binds.push(format!("{}:{}", host, guest));
+    }
+    if let Some((ref host, ref guest)) = config.filesystem.skills_mount {
+        binds.push(format!("{}:{}:ro", host, guest));
+    }
+    binds
+}
+
+/// Tmpfs mounts for a read-only root: `/tmp` unless a bind already targets it.
+fn docker_tmpfs(binds: &[String]) -> HashMap<String, String> {
+    let mut tmpfs = HashMap::new();
+    let tmp_bound = binds.iter().any(|b| b.split(':').nth(1) == Some("/tmp"));
+    if !tmp_bound {
+        tmpfs.insert("/tmp".to_string(), "rw,nosuid,nodev".to_string());
+    }
+    tmpfs
+}
+
+/// Container `HostConfig` for a tool sandbox. Shared by PlainDockerSandbox (`runtime` None) and
+/// GVisorDockerSandbox (`runtime` "runsc").
+pub(crate) fn docker_host_config(config: &SandboxConfig, runtime: Option<&str>) -> HostConfig {
+    let binds = docker_binds(config);
+    let readonly_rootfs = config.hardening.readonly_rootfs;
+    HostConfig {
+        runtime: runtime.map(str::to_string),
+        network_mode: Some(docker_network_mode(config)),
+        readonly_rootfs: Some(readonly_rootfs),
+        tmpfs: readonly_rootfs.then(|| docker_tmpfs(&binds)),
+        binds: Some(binds),
+        memory: Some(config.resources.max_memory as i64),
+        nano_cpus: Some((config.resources.max_cpu * 1_000_000_000.0) as i64),
+        security_opt: Some(PrivilegeEscalationPrevention::docker_security_opts(
+            config.hardening.no_new_privileges,
+        )),
+        cap_drop: Some(vec!["ALL".to_string()]),
+        // Names were validated by SecurityValidator at creation; skip anything unknown regardless
+        cap_add: Some(
+            config
+                .hardening
+                .capabilities
+                .iter()
+                .filter_map(|c| PrivilegeEscalationPrevention::normalize_capability(c).ok())
+                .collect(),
+        ),
+        ..Default::default()
+    }
+}
+
+/// Container `Config` for a tool sandbox: image, host config (see [`docker_host_config`]), the
+/// workspace mount as working directory, and the host user when the workspace owner is mapped.
+pub(crate) fn docker_container_config(config: &SandboxConfig, runtime: Option<&str>) -> Config<String> {
+    Config {
+        image: Some(config.image.as_deref().unwrap_or("ubuntu:22.04").to_string()),
+        host_config: Some(docker_host_config(config, runtime)),
+        cmd: Some(vec!["sleep".to_string(), "infinity".to_string()]),
+        user: config.filesystem.workspace_owner.clone(),
+        working_dir: config.filesystem.workspace_mount.as_ref().map(|(_, guest)| guest.clone()),
+        ..Default::default()
+    }
+}
+
+/// Quote `s` as one `sh` word.
+fn sh_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Shell script that restricts a container's outbound traffic to the network allowlist:
+/// OUTPUT policy DROP, then loopback, the container's DNS servers and each allowed host/port
+/// (TCP; every port of a host when no ports are listed, every host for a port when no hosts are).
+/// IPv6 egress is dropped, and the script fails when the container has IPv6 but no `ip6tables`.
+/// Hostnames are resolved once, when the rules are installed at container start; later DNS
+/// changes are not followed. `None` when there is nothing to enforce: network disabled (no
+/// interface), host network (the rules would land in the host's firewall), or no allowlist
+/// (empty `allowed_hosts` and `allowed_ports` keep unrestricted egress and need no `iptables`).
+pub(crate) fn egress_filter_script(network: &NetworkConfig) -> Option<String> {
+    if !network.enabled
+        || network.host_network
+        || (network.allowed_hosts.is_empty() && network.allowed_ports.is_empty())
+    {
+        return None;
+    }
+    let mut lines = vec![
+        "set -e".to_string(),
+        "iptables -F OUTPUT".to_string(),
+        "iptables -P OUTPUT DROP".to_string(),
+        "iptables -A OUTPUT -o lo -j ACCEPT".to_string(),
+        // Hostnames in the allowlist (and in tools) resolve through the container's resolvers.
+        "for ns in $(awk '/^nameserver [0-9.]+$/ {print $2}' /etc/resolv.conf); do \
+         iptables -A OUTPUT -p udp -d \"$ns\" --dport 53 -j ACCEPT; \
+         iptables -A OUTPUT -p tcp -d \"$ns\" --dport 53 -j ACCEPT; done"
+            .to_string(),
+    ];
+    let hosts: Vec<Option<&str>> = if network.allowed_hosts.is_empty() {
+        vec![None]
+    } else {
+        network.allowed_hosts.iter().map(|h| Some(h.as_str())).collect()
+    };
+    for host in hosts {
+        let dest = host.map(|h| format!(" -d {}", sh_quote(h))).unwrap_or_default();
+        if network.allowed_ports.is_empty() {
+            lines.push(format!("iptables -A OUTPUT{} -j ACCEPT", dest));
+        }
+        for port in &network.allowed_ports {
+            lines.push(format!("iptables -A OUTPUT -p tcp{} --dport {} -j ACCEPT", dest, port));
         }
     }
+    // Allowed hosts resolve to IPv4 rules only; shut IPv6 egress when the container has it.
+    lines.push(
+        "if [ -e /proc/net/if_inet6 ]; then \
+         command -v ip6tables >/dev/null || { echo 'ip6tables not found; cannot restrict IPv6 egress' >&2; exit 1; }; \
+         ip6tables -F OUTPUT; ip6tables -P OUTPUT DROP; ip6tables -A OUTPUT -o lo -j ACCEPT; fi"
+            .to_string(),
+    );
+    Some(lines.join("\n"))
+}
 
-    fn get_volumes(&self) -> Vec<String> {
-        let mut binds: Vec<String> = self
-            .config
-            .filesystem
-            .writable_paths
-            .iter()
-            .map(|p| format!("{}:{}", p, p))
-            .collect();
-        if let Some((ref host, ref guest)) = self.config.filesystem.workspace_mount {
-            binds.push(format!("{}:{}", host, guest));
+/// Install the network allowlist (see [`egress_filter_script`]) in a started container. Runs as a
+/// privileged root exec, so the container itself keeps no capability to change the rules. When
+/// the rules cannot be installed (e.g. the image has no `iptables`) the container is removed and
+/// start fails, rather than leaving a sandbox with unrestricted egress.
+pub(crate) async fn apply_egress_filter(
+    docker: &Docker,
+    container_id: &str,
+    network: &NetworkConfig,
+) -> Result<()> {
+    let Some(script) = egress_filter_script(network) else {
+        return Ok(());
+    };
+    let outcome = async {
+        let exec = docker
+            .create_exec(
+                container_id,
+                CreateExecOptions {
+                    cmd: Some(vec!["sh", "-c", script.as_str()]),
+                    user: Some("0:0"),
+                    privileged: Some(true),
+                    attach_stdout: Some(true),
+                    attach_stderr: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut stderr = Vec::new();
+        if let StartExecResults::Attached { mut output, .. } =
+            docker.start_exec(&exec.id, None).await.map_err(|e| e.to_string())?
+        {
+            use futures_util::stream::StreamExt;
+            while let Some(chunk) = output.next().await {
+                if let Ok(bollard::container::LogOutput::StdErr { message }) = chunk {
+                    stderr.extend_from_slice(&message);
+                }
+            }
         }
-        if let Some((ref host, ref guest)) = self.config.filesystem.skills_mount {
-            binds.push(format!("{}:{}:ro", host, guest));
+        let inspect = docker.inspect_exec(&exec.id).await.map_err(|e| e.to_string())?;
+        match inspect.exit_code {
+            Some(0) => Ok(()),
+            code => Err(format!(
+                "exit code {:?}: {}",
+                code,
+                String::from_utf8_lossy(&stderr).trim()
+            )),
         }
-        binds
     }
+    .await;
+    if let Err(e) = outcome {
+        let _ = docker
+            .remove_container(
+                container_id,
+                Some(RemoveContainerOptions {
+                    force: true,
+                    ..Default::default()
+                }),
+            )
+            .await;
+        return Err(SandboxError::CreationFailed(format!(
+            "Failed to install the network allowlist (the image needs iptables): {}",
+            e
+        )));
+    }
+    Ok(())
 }
 
 impl Sandbox for PlainDockerSandbox {
@@ -170,6 +352,7 @@ impl Sandbox for PlainDockerSandbox {
                                 .await
                                 .map_err(|e| SandboxError::CreationFailed(format!("Failed to start existing container: {}", e)))?;
                         }
+                        apply_egress_filter(&self.docker, id, &self.config.network).await?;
                         self.container_id = Some(id.to_string());
                         self.status.state = SandboxState::Running;
                         self.status.started_at = Some(Utc::now());
@@ -182,30 +365,7 @@ impl Sandbox for PlainDockerSandbox {
                     platform: None,
                 };
 
-                let host_config = HostConfig {
-                    runtime: None,
-                    network_mode: Some(self.get_network_mode()),
-                    binds: Some(self.get_volumes()),
-                    memory: Some(self.config.resources.max_memory as i64),
-                    nano_cpus: Some((self.config.resources.max_cpu * 1_000_000_000.0) as i64),
-                    security_opt: Some(vec!["no-new-privileges".to_string()]),
-                    cap_drop: Some(vec!["ALL".to_string()]),
-                    cap_add: Some(vec![]),
-                    ..Default::default()
-                };
-
-                let image = self
-                    .config
-                    .image
-                    .as_deref()
-                    .unwrap_or("ubuntu:22.04")
-                    .to_string();
-                let config = Config {
-                    image: Some(image),
-                    host_config: Some(host_config),
-                    cmd: Some(vec!["sleep".to_string(), "infinity".to_string()]),
-                    ..Default::default()
-                };
+                let config = docker_container_config(&self.config, None);
 
                 let container = self.docker
                     .create_container(Some(options), config)
@@ -219,6 +379,11 @@ impl Sandbox for PlainDockerSandbox {
                     .await
                     .map_err(|e| SandboxError::CreationFailed(format!("Failed to start container: {}", e)))?;
 
+                if let Err(e) = apply_egress_filter(&self.docker, &container.id, &self.config.network).await {
+                    self.container_id = None;
+                    return Err(e);
+                }
+
                 self.status.state = SandboxState::Running;
                 self.status.started_at = Some(Utc::now());
 
@@ -392,3 +557,159 @@ impl Sandbox for PlainDockerSandbox {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sandbox::types::{
+        FilesystemConfig, HardeningConfig, MonitoringConfig, NetworkConfig, ProcessConfig,
+        ResourceConfig,
+    };
+
+    fn create_test_config() -> SandboxConfig {
+        SandboxConfig {
+            sandbox_id: "test-docker-001".to_string(),
+            platform: "linux".to_string(),
+            filesystem: FilesystemConfig {
+                writable_paths: vec!["/tmp/work".to_string()],
+                ..Default::default()
+            },
+            network: NetworkConfig {
+                enabled: false,
+                allowed_hosts: vec![],
+                allowed_ports: vec![],
+                host_network: false,
+            },
+            resources: ResourceConfig {
+                max_memory: 512 * 1024 * 1024,
+                max_cpu: 1.0,
+                max_disk: 1024 * 1024 * 1024,
+            },
+            process: ProcessConfig {
+                allow_fork: false,
+                max_processes: 10,
+            },
+            child_work_dir: None,
+            monitoring: MonitoringConfig::default(),
+            delete_on_start: false,
+            requested_tool_sandbox_type: None,
+            image: None,
+            hardening: HardeningConfig::default(),
+        }
+    }
+
+    #[test]
+    fn test_host_config_sets_no_new_privileges_by_default() {
+        let host = docker_host_config(&create_test_config(), None);
+        assert_eq!(host.security_opt, Some(vec!["no-new-privileges".to_string()]));
+        assert_eq!(host.cap_drop, Some(vec!["ALL".to_string()]));
+        assert_eq!(host.network_mode.as_deref(), Some("none"));
+        assert_eq!(host.runtime, None);
+    }
+
+    #[test]
+    fn test_host_config_omits_no_new_privileges_when_disabled() {
+        let mut config = create_test_config();
+        config.hardening.no_new_privileges = false;
+        let host = docker_host_config(&config, Some("runsc"));
+        assert_eq!(host.security_opt, Some(vec![]));
+        assert_eq!(host.runtime.as_deref(), Some("runsc"));
+    }
+
+    #[test]
+    fn test_host_config_drops_all_and_adds_only_allowlist() {
+        let host = docker_host_config(&create_test_config(), None);
+        assert_eq!(host.cap_drop, Some(vec!["ALL".to_string()]));
+        assert_eq!(host.cap_add, Some(vec![]));
+
+        let mut config = create_test_config();
+        config.hardening.capabilities = vec!["cap_net_bind_service".to_string(), "CHOWN".to_string()];
+        let host = docker_host_config(&config, Some("runsc"));
+        assert_eq!(host.cap_drop, Some(vec!["ALL".to_string()]));
+        assert_eq!(
+            host.cap_add,
+            Some(vec!["NET_BIND_SERVICE".to_string(), "CHOWN".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_host_config_readonly_rootfs_keeps_writable_paths_rw() {
+        let host = docker_host_config(&create_test_config(), None);
+        assert_eq!(host.readonly_rootfs, Some(true));
+        let tmpfs = host.tmpfs.expect("tmpfs for /tmp");
+        assert!(tmpfs.contains_key("/tmp"));
+        let binds = host.binds.unwrap();
+        assert!(binds.contains(&"/tmp/work:/tmp/work".to_string()));
+        assert!(binds.iter().all(|b| !b.ends_with(":ro")));
+    }
+
+    #[test]
+    fn test_host_config_readonly_rootfs_disabled() {
+        let mut config = create_test_config();
+        config.hardening.readonly_rootfs = false;
+        let host = docker_host_config(&config, None);
+        assert_eq!(host.readonly_rootfs, Some(false));
+        assert!(host.tmpfs.is_none());
+    }
+
+    #[test]
+    fn test_container_config_mounts_workspace_as_host_user() {
+        let tool = crate::config::ToolSandboxConfig {
+            sandbox_type: Some("plain-docker".to_string()),
+            workspace_mount_path: Some("/work".to_string()),
+            ..Default::default()
+        };
+        let config = crate::config::build_tool_sandbox_config(
+            &tool,
+            &None,
+            std::path::Path::new("/home/me/.synbot/workspace"),
+            std::path::Path::new("/nonexistent-skills"),
+        )
+        .unwrap();
+        let container = docker_container_config(&config, None);
+        let binds = container.host_config.unwrap().binds.unwrap();
+        assert!(binds.contains(&"/home/me/.synbot/workspace:/work".to_string()), "{:?}", binds);
+        assert_eq!(container.working_dir.as_deref(), Some("/work"));
+        #[cfg(unix)]
+        assert_eq!(
+            container.user,
+            Some(format!("{}:{}", nix::unistd::getuid(), nix::unistd::getgid()))
+        );
+    }
+
+    #[test]
+    fn test_egress_filter_restricts_to_allowlist() {
+        let mut config = create_test_config();
+        assert!(egress_filter_script(&config.network).is_none(), "no network, nothing to filter");
+
+        config.network.enabled = true;
+        assert!(
+            egress_filter_script(&config.network).is_none(),
+            "no allowlist keeps unrestricted egress and needs no iptables in the image"
+        );
+        assert_eq!(docker_host_config(&config, None).network_mode.as_deref(), Some("bridge"));
+
+        config.network.allowed_hosts = vec!["api.example.com".to_string()];
+        config.network.allowed_ports = vec![443];
+        let script = egress_filter_script(&config.network).unwrap();
+        let rules: Vec<&str> = script.lines().filter(|l| l.starts_with("iptables -A OUTPUT")).collect();
+        assert!(script.contains("iptables -P OUTPUT DROP"), "{}", script);
+        assert!(rules.contains(&"iptables -A OUTPUT -o lo -j ACCEPT"));
+        assert!(rules.contains(&"iptables -A OUTPUT -p tcp -d 'api.example.com' --dport 443 -j ACCEPT"));
+        assert!(script.contains("--dport 53"), "allowlisted hostnames must resolve");
+        assert_eq!(rules.len(), 2, "only loopback and the allowlisted host:port: {:?}", rules);
+        assert!(script.contains("ip6tables -P OUTPUT DROP"));
+        assert!(script.contains("ip6tables not found"), "IPv6 must not fail open");
+
+        config.network.host_network = true;
+        assert!(egress_filter_script(&config.network).is_none(), "never touch the host firewall");
+    }
+
+    #[test]
+    fn test_tmpfs_skipped_when_tmp_is_bind_mounted() {
+        let mut config = create_test_config();
+        config.filesystem.writable_paths.push("/tmp".to_string());
+        let host = docker_host_config(&config, None);
+        assert!(host.tmpfs.unwrap().is_empty());
+    }
+}
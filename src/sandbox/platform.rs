@@ -154,6 +154,15 @@ impl PlatformDetector {
     }
 }
 
+/// Error for a Docker-based tool sandbox type in a build without the `sandbox-docker` feature
+#[cfg(all(not(feature = "sandbox-docker"), any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn docker_backend_disabled(requested: &str) -> SandboxError {
+    SandboxError::CreationFailed(format!(
+        "Tool sandbox type '{}' needs the `sandbox-docker` feature, which this build does not include. Rebuild with it or pick a host-native type.",
+        requested
+    ))
+}
+
 /// Sandbox factory for creating platform-appropriate sandbox instances
 pub struct SandboxFactory;
 
@@ -244,12 +253,20 @@ impl SandboxFactory {
 
         #[cfg(target_os = "windows")]
         {
+            #[cfg(feature = "sandbox-docker")]
             use crate::sandbox::plain_docker::PlainDockerSandbox;
-            use crate::sandbox::{GVisorDockerSandbox, Wsl2GVisorSandbox, WindowsAppContainerSandbox};
+            #[cfg(feature = "sandbox-docker")]
+            use crate::sandbox::{GVisorDockerSandbox, Wsl2GVisorSandbox};
+            use crate::sandbox::WindowsAppContainerSandbox;
             match requested {
+                #[cfg(feature = "sandbox-docker")]
                 "wsl2-gvisor" => Ok(Box::new(Wsl2GVisorSandbox::new(config)?)),
+                #[cfg(feature = "sandbox-docker")]
                 "gvisor-docker" => Ok(Box::new(GVisorDockerSandbox::new(config)?)),
+                #[cfg(feature = "sandbox-docker")]
                 "plain-docker" => Ok(Box::new(PlainDockerSandbox::new(config)?)),
+                #[cfg(not(feature = "sandbox-docker"))]
+                "wsl2-gvisor" | "gvisor-docker" | "plain-docker" => Err(docker_backend_disabled(requested)),
                 "appcontainer" => Ok(Box::new(WindowsAppContainerSandbox::new(config)?)),
                 "nono" | "seatbelt" => Err(SandboxError::CreationFailed(format!(
                     "Tool sandbox type '{}' is not supported on Windows. Use 'appcontainer', 'wsl2-gvisor', 'gvisor-docker', or 'plain-docker'.",
@@ -264,14 +281,20 @@ impl SandboxFactory {
 
         #[cfg(any(target_os = "linux", target_os = "macos"))]
         {
+            #[cfg(feature = "sandbox-docker")]
             use crate::sandbox::plain_docker::PlainDockerSandbox;
+            #[cfg(feature = "sandbox-docker")]
             use crate::sandbox::GVisorDockerSandbox;
             use crate::sandbox::NonoSandbox;
             #[cfg(target_os = "macos")]
             use crate::sandbox::macos_sandbox_exec::MacosSandboxExecToolSandbox;
             match requested {
+                #[cfg(feature = "sandbox-docker")]
                 "gvisor-docker" => Ok(Box::new(GVisorDockerSandbox::new(config)?)),
+                #[cfg(feature = "sandbox-docker")]
                 "plain-docker" => Ok(Box::new(PlainDockerSandbox::new(config)?)),
+                #[cfg(not(feature = "sandbox-docker"))]
+                "gvisor-docker" | "plain-docker" => Err(docker_backend_disabled(requested)),
                 "nono" => Ok(Box::new(NonoSandbox::new(config)?)),
                 #[cfg(target_os = "macos")]
                 "seatbelt" => Ok(Box::new(MacosSandboxExecToolSandbox::new(config)?)),
@@ -4,7 +4,8 @@
 // protection, and resource exhaustion defense mechanisms for all sandbox implementations.
 
 use super::error::{Result, SandboxError};
-use super::types::SandboxConfig;
+use super::types::{ExecutionResult, SandboxConfig};
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
 /// Security validator for sandbox configurations and runtime behavior
@@ -18,25 +19,88 @@ pub struct SecurityValidator {
 }
 
 /// Maximum resource limits to prevent exhaustion
-#[derive(Debug, Clone)]
+/// 
+/// Deserializes from camelCase JSON; omitted fields keep their defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(default, rename_all = "camelCase")]
 pub struct ResourceLimits {
+    /// Largest memory limit (bytes) a sandbox may be configured with
     pub max_memory: u64,
+    /// Smallest memory limit (bytes) a sandbox may be configured with
+    pub min_memory: u64,
+    /// Largest CPU limit (cores) a sandbox may be configured with
     pub max_cpu: f64,
+    /// Largest disk limit (bytes) a sandbox may be configured with
     pub max_disk: u64,
+    /// Largest process limit a sandbox may be configured with
     pub max_processes: u32,
+    /// Open file descriptor count above which a warning is logged
+    pub fd_warn_threshold: usize,
 }
 
 impl Default for ResourceLimits {
     fn default() -> Self {
         Self {
             max_memory: 16 * 1024 * 1024 * 1024, // 16GB
+            min_memory: 64 * 1024 * 1024,         // 64MB
             max_cpu: num_cpus::get() as f64,      // All available CPUs
             max_disk: 100 * 1024 * 1024 * 1024,   // 100GB
             max_processes: 1000,
+            fd_warn_threshold: 900,
         }
     }
 }
 
+impl ResourceLimits {
+    /// Start building limits from the defaults
+    pub fn builder() -> ResourceLimitsBuilder {
+        ResourceLimitsBuilder::default()
+    }
+}
+
+/// Builder for [`ResourceLimits`]; unset fields keep their defaults
+#[derive(Debug, Clone, Default)]
+pub struct ResourceLimitsBuilder {
+    limits: ResourceLimits,
+}
+
+impl ResourceLimitsBuilder {
+    pub fn max_memory(mut self, bytes: u64) -> Self {
+        self.limits.max_memory = bytes;
+        self
+    }
+    
+    pub fn min_memory(mut self, bytes: u64) -> Self {
+        self.limits.min_memory = bytes;
+        self
+    }
+    
+    pub fn max_cpu(mut self, cores: f64) -> Self {
+        self.limits.max_cpu = cores;
+        self
+    }
+    
+    pub fn max_disk(mut self, bytes: u64) -> Self {
+        self.limits.max_disk = bytes;
+        self
+    }
+    
+    pub fn max_processes(mut self, count: u32) -> Self {
+        self.limits.max_processes = count;
+        self
+    }
+    
+    pub fn fd_warn_threshold(mut self, count: usize) -> Self {
+        self.limits.fd_warn_threshold = count;
+        self
+    }
+    
+    pub fn build(self) -> ResourceLimits {
+        self.limits
+    }
+}
+
 impl SecurityValidator {
     /// Create a new security validator with default settings
     pub fn new() -> Self {
@@ -56,6 +120,11 @@ impl SecurityValidator {
         }
     }
     
+    /// Resource limits enforced by this validator
+    pub fn resource_limits(&self) -> &ResourceLimits {
+        &self.max_resource_limits
+    }
+    
     /// Initialize list of dangerous paths that should never be accessible
     fn init_dangerous_paths() -> HashSet<String> {
         let mut paths = HashSet::new();
@@ -115,6 +184,12 @@ impl SecurityValidator {
     /// 
     /// Returns `Ok(())` if configuration is secure, or an error describing the issue
     pub fn validate_config(&self, config: &SandboxConfig) -> Result<()> {
+        // Reject host root / device mounts and host networking
+        EscapePrevention::validate_config(config)?;
+        
+        // Validate capability allowlist names
+        PrivilegeEscalationPrevention::validate_capabilities(&config.hardening.capabilities)?;
+        
         // Validate filesystem access
         self.validate_filesystem_access(config)?;
         
@@ -189,7 +264,7 @@ impl SecurityValidator {
         }
         
         // Enforce minimum memory limit to prevent DoS
-        let min_memory = 64 * 1024 * 1024; // 64MB minimum
+        let min_memory = self.max_resource_limits.min_memory;
         if config.resources.max_memory < min_memory {
             return Err(SandboxError::SecurityViolation(
                 format!("Memory limit {} is below minimum {}", config.resources.max_memory, min_memory)
@@ -379,6 +454,85 @@ impl Default for SecurityValidator {
 pub struct EscapePrevention;
 
 impl EscapePrevention {
+    /// Reject configurations that would hand the sandbox a path back to the host
+    /// 
+    /// Checked before a sandbox is created:
+    /// - Writable mount of the host root (`/`, `C:\`)
+    /// - Device passthrough (any mount of `/dev` or a path under it)
+    /// - Sharing the host network namespace
+    /// - Adding back capabilities that bypass the container boundary (e.g. `SYS_ADMIN`)
+    /// 
+    /// `hardening.allow_insecure` accepts such configs with a warning instead.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` if the config is safe, or `SecurityViolation` naming the offending setting
+    pub fn validate_config(config: &SandboxConfig) -> Result<()> {
+        let Some(problem) = Self::find_escape_risk(config) else {
+            return Ok(());
+        };
+        if config.hardening.allow_insecure {
+            log::warn!(
+                "Sandbox '{}': {} (accepted because allowInsecure is set)",
+                config.sandbox_id, problem
+            );
+            return Ok(());
+        }
+        Err(SandboxError::SecurityViolation(format!(
+            "Sandbox '{}': {}. Remove it from the sandbox config, or set allowInsecure to accept the risk",
+            config.sandbox_id, problem
+        )))
+    }
+    
+    fn find_escape_risk(config: &SandboxConfig) -> Option<String> {
+        let fs = &config.filesystem;
+        let mut writable: Vec<&str> = fs.writable_paths.iter().map(String::as_str).collect();
+        if let Some((ref host, _)) = fs.workspace_mount {
+            writable.push(host);
+        }
+        if let Some(root) = writable.iter().find(|p| Self::is_host_root(p)) {
+            return Some(format!("writable mount of the host root filesystem ({})", root));
+        }
+        
+        let mut all_paths = writable;
+        all_paths.extend(fs.readonly_paths.iter().map(String::as_str));
+        if let Some((ref host, _)) = fs.skills_mount {
+            all_paths.push(host);
+        }
+        if let Some(dev) = all_paths.iter().find(|p| Self::is_device_path(p)) {
+            return Some(format!("device passthrough via mount of {}", dev));
+        }
+        
+        if config.network.host_network {
+            return Some("sharing the host network namespace".to_string());
+        }
+        
+        let escape_cap = config
+            .hardening
+            .capabilities
+            .iter()
+            .filter_map(|c| PrivilegeEscalationPrevention::normalize_capability(c).ok())
+            .find(|c| ESCAPE_CAPABILITIES.contains(&c.as_str()));
+        if let Some(cap) = escape_cap {
+            return Some(format!("adding capability CAP_{}", cap));
+        }
+        None
+    }
+    
+    fn is_host_root(path: &str) -> bool {
+        let trimmed = path.trim().trim_end_matches(['/', '\\']);
+        if trimmed.is_empty() {
+            return !path.trim().is_empty();
+        }
+        // Windows drive root, e.g. "C:" from "C:\\"
+        trimmed.len() == 2 && trimmed.ends_with(':') && trimmed.as_bytes()[0].is_ascii_alphabetic()
+    }
+    
+    fn is_device_path(path: &str) -> bool {
+        let trimmed = path.trim().trim_end_matches('/');
+        trimmed == "/dev" || trimmed.starts_with("/dev/")
+    }
+    
     /// Verify sandbox isolation integrity
     /// 
     /// This performs runtime checks to ensure the sandbox hasn't been compromised:
@@ -449,8 +603,39 @@ impl EscapePrevention {
         // For now, this is a placeholder for future implementation
         Ok(())
     }
+    
+    /// Whether the command was killed by the seccomp filter
+    /// 
+    /// Seccomp kills offending processes with SIGSYS; container runtimes report that as exit
+    /// code 159 (128 + 31) and shells print "Bad system call".
+    pub fn blocked_syscall(result: &ExecutionResult) -> bool {
+        const SIGSYS_EXIT_CODE: i32 = 128 + 31;
+        result.exit_code == SIGSYS_EXIT_CODE
+            || String::from_utf8_lossy(&result.stderr).contains("Bad system call")
+    }
 }
 
+/// Programs whose only purpose is to switch to another (usually more privileged) user.
+/// `sudo` and `su` are left out: models try them routinely, and counting them would quarantine
+/// sandboxes for harmless mistakes (they fail anyway under no-new-privileges).
+const ESCALATION_COMMANDS: &[&str] = &["doas", "pkexec", "runuser", "setpriv"];
+
+/// Linux capability names (without the `CAP_` prefix) accepted in `hardening.capabilities`
+const LINUX_CAPABILITIES: &[&str] = &[
+    "AUDIT_CONTROL", "AUDIT_READ", "AUDIT_WRITE", "BLOCK_SUSPEND", "BPF", "CHECKPOINT_RESTORE",
+    "CHOWN", "DAC_OVERRIDE", "DAC_READ_SEARCH", "FOWNER", "FSETID", "IPC_LOCK", "IPC_OWNER",
+    "KILL", "LEASE", "LINUX_IMMUTABLE", "MAC_ADMIN", "MAC_OVERRIDE", "MKNOD", "NET_ADMIN",
+    "NET_BIND_SERVICE", "NET_BROADCAST", "NET_RAW", "PERFMON", "SETFCAP", "SETGID", "SETPCAP",
+    "SETUID", "SYS_ADMIN", "SYS_BOOT", "SYS_CHROOT", "SYS_MODULE", "SYS_NICE", "SYS_PACCT",
+    "SYS_PTRACE", "SYS_RAWIO", "SYS_RESOURCE", "SYS_TIME", "SYS_TTY_CONFIG", "SYSLOG",
+    "WAKE_ALARM",
+];
+
+/// Capabilities that let a container reach the host; rejected by [`EscapePrevention`]
+const ESCAPE_CAPABILITIES: &[&str] = &[
+    "SYS_ADMIN", "SYS_MODULE", "SYS_RAWIO", "SYS_PTRACE", "DAC_READ_SEARCH",
+];
+
 /// Privilege escalation prevention
 pub struct PrivilegeEscalationPrevention;
 
@@ -535,6 +720,99 @@ impl PrivilegeEscalationPrevention {
         // Platform-specific implementation would go here
         Ok(())
     }
+
+    /// Docker `--security-opt` values for a container
+    ///
+    /// Returns `["no-new-privileges"]` when enabled, otherwise an empty list.
+    pub fn docker_security_opts(no_new_privileges: bool) -> Vec<String> {
+        if no_new_privileges {
+            vec!["no-new-privileges".to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Normalize a capability name to Docker's form
+    /// 
+    /// Accepts any case and an optional `CAP_` prefix ("cap_net_raw" -> "NET_RAW").
+    /// 
+    /// # Returns
+    /// 
+    /// The normalized name, or a `Configuration` error for an unknown capability
+    pub fn normalize_capability(name: &str) -> Result<String> {
+        let upper = name.trim().to_ascii_uppercase();
+        let bare = upper.strip_prefix("CAP_").unwrap_or(&upper);
+        if LINUX_CAPABILITIES.contains(&bare) {
+            Ok(bare.to_string())
+        } else {
+            Err(SandboxError::Configuration(format!(
+                "Unknown Linux capability '{}'",
+                name
+            )))
+        }
+    }
+    
+    /// Validate and normalize a capability allowlist
+    /// 
+    /// # Returns
+    /// 
+    /// Normalized, de-duplicated names in config order, or an error naming the first unknown one
+    pub fn validate_capabilities(capabilities: &[String]) -> Result<Vec<String>> {
+        let mut normalized: Vec<String> = Vec::with_capacity(capabilities.len());
+        for name in capabilities {
+            let cap = Self::normalize_capability(name)?;
+            if !normalized.contains(&cap) {
+                normalized.push(cap);
+            }
+        }
+        Ok(normalized)
+    }
+    
+    /// Set `PR_SET_NO_NEW_PRIVS` in the child before exec
+    ///
+    /// The flag is inherited across fork/exec and cannot be cleared, so everything the sandboxed
+    /// command runs is barred from gaining privileges via setuid binaries or file capabilities.
+    #[cfg(target_os = "linux")]
+    pub fn no_new_privs_pre_exec(cmd: &mut std::process::Command) {
+        use std::os::unix::process::CommandExt;
+
+        // SAFETY: the closure runs between fork and exec and only issues a single prctl syscall;
+        // it does not allocate or take locks.
+        unsafe {
+            cmd.pre_exec(|| nix::sys::prctl::set_no_new_privs().map_err(std::io::Error::from));
+        }
+    }
+
+    #[cfg(all(unix, not(target_os = "linux")))]
+    pub fn no_new_privs_pre_exec(_cmd: &mut std::process::Command) {
+        // No equivalent of PR_SET_NO_NEW_PRIVS; nono relies on Seatbelt on macOS
+    }
+    
+    /// Find a privilege escalation program invoked by `command`, including in a `sh -c` script
+    /// 
+    /// Returns the offending program name. Only command positions are checked, so
+    /// `grep doas /var/log/auth.log` is not reported. Detection is for reporting only: inside
+    /// the sandbox these programs already fail because of no-new-privileges.
+    pub fn detect_escalation_attempt(command: &str, args: &[String]) -> Option<String> {
+        fn program_name(word: &str) -> &str {
+            word.rsplit(['/', '\\']).next().unwrap_or(word)
+        }
+        let escalation = |word: &str| {
+            let name = program_name(word);
+            ESCALATION_COMMANDS.contains(&name).then(|| name.to_string())
+        };
+        if let Some(found) = escalation(command) {
+            return Some(found);
+        }
+        if !matches!(program_name(command), "sh" | "bash" | "dash" | "ash" | "zsh" | "ksh") {
+            return None;
+        }
+        // First word of every pipeline segment in the script, skipping `VAR=value` prefixes
+        args.iter()
+            .flat_map(|script| script.split([';', '|', '&', '(', ')', '`', '\n']))
+            .filter_map(|segment| segment.split_whitespace().find(|w| !w.contains('=')))
+            .find_map(escalation)
+    }
 }
 
 /// Resource exhaustion prevention
@@ -558,14 +836,20 @@ impl ResourceExhaustionPrevention {
     /// 
     /// Returns `Ok(())` if resources are within limits, or an error if exceeded
     pub fn enforce_resource_limits(config: &SandboxConfig) -> Result<()> {
+        Self::enforce_resource_limits_with(config, &ResourceLimits::default())
+    }
+    
+    /// Like [`Self::enforce_resource_limits`], additionally capping the sandbox's own limits
+    /// by the configured `limits`
+    pub fn enforce_resource_limits_with(config: &SandboxConfig, limits: &ResourceLimits) -> Result<()> {
         // Check memory usage
-        Self::check_memory_usage(config.resources.max_memory)?;
+        Self::check_memory_usage(config.resources.max_memory.min(limits.max_memory))?;
         
         // Check process count
-        Self::check_process_count(config.process.max_processes)?;
+        Self::check_process_count(config.process.max_processes.min(limits.max_processes))?;
         
         // Check file descriptors
-        Self::check_file_descriptors()?;
+        Self::check_file_descriptors_with(limits.fd_warn_threshold)?;
         
         Ok(())
     }
@@ -614,6 +898,11 @@ impl ResourceExhaustionPrevention {
     
     /// Check file descriptor usage
     pub fn check_file_descriptors() -> Result<()> {
+        Self::check_file_descriptors_with(ResourceLimits::default().fd_warn_threshold)
+    }
+    
+    /// Check file descriptor usage, warning above `warn_threshold` open descriptors
+    pub fn check_file_descriptors_with(warn_threshold: usize) -> Result<()> {
         #[cfg(target_os = "linux")]
         {
             use std::fs;
@@ -623,11 +912,13 @@ impl ResourceExhaustionPrevention {
                 let fd_count = entries.count();
                 
                 // Warn if approaching limit (typically 1024 or 4096)
-                if fd_count > 900 {
+                if fd_count > warn_threshold {
                     log::warn!("High file descriptor usage: {}", fd_count);
                 }
             }
         }
+        #[cfg(not(target_os = "linux"))]
+        let _ = warn_threshold;
         
         Ok(())
     }
@@ -673,6 +964,7 @@ mod tests {
                 enabled: true,
                 allowed_hosts: vec!["api.example.com".to_string()],
                 allowed_ports: vec![443],
+                host_network: false,
             },
             resources: ResourceConfig {
                 max_memory: 1024 * 1024 * 1024, // 1GB
@@ -688,6 +980,7 @@ mod tests {
             delete_on_start: false,
             requested_tool_sandbox_type: None,
             image: None,
+            hardening: HardeningConfig::default(),
         }
     }
     
@@ -781,4 +1074,189 @@ mod tests {
         assert!(!validator.is_dangerous_path("/tmp"));
         assert!(!validator.is_dangerous_path("/home/user"));
     }
+    
+    #[test]
+    fn test_escape_prevention_rejects_root_writable_mount() {
+        let mut config = create_test_config();
+        config.filesystem.writable_paths.push("/".to_string());
+        
+        let result = EscapePrevention::validate_config(&config);
+        assert!(matches!(result.unwrap_err(), SandboxError::SecurityViolation(msg) if msg.contains("host root")));
+        
+        // Also rejected through the validator used at sandbox creation
+        let result = SecurityValidator::new().validate_config(&config);
+        assert!(matches!(result.unwrap_err(), SandboxError::SecurityViolation(_)));
+        
+        let mut config = create_test_config();
+        config.filesystem.workspace_mount = Some(("C:\\".to_string(), "/workspace".to_string()));
+        assert!(EscapePrevention::validate_config(&config).is_err());
+    }
+    
+    #[test]
+    fn test_escape_prevention_rejects_device_passthrough() {
+        let mut config = create_test_config();
+        config.filesystem.readonly_paths.push("/dev".to_string());
+        let result = EscapePrevention::validate_config(&config);
+        assert!(matches!(result.unwrap_err(), SandboxError::SecurityViolation(msg) if msg.contains("/dev")));
+        
+        // Paths that merely start with "dev" are fine
+        let mut config = create_test_config();
+        config.filesystem.readonly_paths.push("/devtools".to_string());
+        assert!(EscapePrevention::validate_config(&config).is_ok());
+    }
+    
+    #[test]
+    fn test_escape_prevention_rejects_host_network() {
+        let mut config = create_test_config();
+        config.network.host_network = true;
+        
+        let result = EscapePrevention::validate_config(&config);
+        assert!(matches!(result.unwrap_err(), SandboxError::SecurityViolation(msg) if msg.contains("host network")));
+    }
+    
+    #[test]
+    fn test_escape_prevention_allow_insecure_override() {
+        let mut config = create_test_config();
+        config.network.host_network = true;
+        config.filesystem.writable_paths.push("/".to_string());
+        config.hardening.allow_insecure = true;
+        
+        assert!(EscapePrevention::validate_config(&config).is_ok());
+    }
+    
+    #[test]
+    fn test_validate_capabilities_normalizes_names() {
+        let caps = vec![
+            "net_bind_service".to_string(),
+            "CAP_CHOWN".to_string(),
+            "NET_BIND_SERVICE".to_string(),
+        ];
+        let normalized = PrivilegeEscalationPrevention::validate_capabilities(&caps).unwrap();
+        assert_eq!(normalized, vec!["NET_BIND_SERVICE".to_string(), "CHOWN".to_string()]);
+    }
+    
+    #[test]
+    fn test_validate_capabilities_rejects_unknown() {
+        let caps = vec!["NET_RAW".to_string(), "MAKE_COFFEE".to_string()];
+        let result = PrivilegeEscalationPrevention::validate_capabilities(&caps);
+        assert!(matches!(result.unwrap_err(), SandboxError::Configuration(msg) if msg.contains("MAKE_COFFEE")));
+        
+        let mut config = create_test_config();
+        config.hardening.capabilities = caps;
+        assert!(SecurityValidator::new().validate_config(&config).is_err());
+    }
+    
+    #[test]
+    fn test_detect_escalation_attempt() {
+        let sh = |script: &str| vec!["-c".to_string(), script.to_string()];
+        assert_eq!(
+            PrivilegeEscalationPrevention::detect_escalation_attempt("/usr/bin/pkexec", &["id".to_string()]),
+            Some("pkexec".to_string())
+        );
+        assert_eq!(
+            PrivilegeEscalationPrevention::detect_escalation_attempt("sh", &sh("ls && runuser -u root id")),
+            Some("runuser".to_string())
+        );
+        // sudo and su are common model mistakes, not reported
+        assert_eq!(
+            PrivilegeEscalationPrevention::detect_escalation_attempt("sh", &sh("sudo apt install jq; su -c id")),
+            None
+        );
+        assert_eq!(
+            PrivilegeEscalationPrevention::detect_escalation_attempt("bash", &sh("FOO=1 doas id")),
+            Some("doas".to_string())
+        );
+        // Arguments that merely mention the programs are not attempts
+        assert_eq!(
+            PrivilegeEscalationPrevention::detect_escalation_attempt("sh", &sh("grep doas /var/log/auth.log")),
+            None
+        );
+        assert_eq!(
+            PrivilegeEscalationPrevention::detect_escalation_attempt("echo", &["doas".to_string()]),
+            None
+        );
+    }
+    
+    #[test]
+    fn test_blocked_syscall_detection() {
+        let result = |exit_code: i32, stderr: &str| ExecutionResult {
+            exit_code,
+            stdout: vec![],
+            stderr: stderr.as_bytes().to_vec(),
+            duration: std::time::Duration::from_millis(1),
+            error: None,
+        };
+        assert!(EscapePrevention::blocked_syscall(&result(159, "")));
+        assert!(EscapePrevention::blocked_syscall(&result(1, "sh: line 1: 12 Bad system call")));
+        assert!(!EscapePrevention::blocked_syscall(&result(1, "No such file or directory")));
+    }
+    
+    #[test]
+    fn test_escape_prevention_rejects_sys_admin() {
+        let mut config = create_test_config();
+        config.hardening.capabilities = vec!["cap_sys_admin".to_string()];
+        let result = EscapePrevention::validate_config(&config);
+        assert!(matches!(result.unwrap_err(), SandboxError::SecurityViolation(msg) if msg.contains("CAP_SYS_ADMIN")));
+    }
+    
+    #[test]
+    fn test_resource_limits_deserialize_partial() {
+        let limits: ResourceLimits =
+            serde_json::from_str(r#"{"maxMemory": 2147483648, "maxProcesses": 50}"#).unwrap();
+        assert_eq!(limits.max_memory, 2 * 1024 * 1024 * 1024);
+        assert_eq!(limits.max_processes, 50);
+        let defaults = ResourceLimits::default();
+        assert_eq!(limits.min_memory, defaults.min_memory);
+        assert_eq!(limits.max_disk, defaults.max_disk);
+        assert_eq!(limits.fd_warn_threshold, defaults.fd_warn_threshold);
+        
+        let round_trip: ResourceLimits =
+            serde_json::from_str(&serde_json::to_string(&limits).unwrap()).unwrap();
+        assert_eq!(round_trip, limits);
+    }
+    
+    #[test]
+    fn test_resource_limits_builder() {
+        let limits = ResourceLimits::builder()
+            .max_memory(512 * 1024 * 1024)
+            .max_cpu(2.0)
+            .max_processes(20)
+            .build();
+        assert_eq!(limits.max_memory, 512 * 1024 * 1024);
+        assert_eq!(limits.max_cpu, 2.0);
+        assert_eq!(limits.max_processes, 20);
+        assert_eq!(limits.max_disk, ResourceLimits::default().max_disk);
+    }
+    
+    #[test]
+    fn test_security_validator_enforces_configured_limits() {
+        let limits: ResourceLimits =
+            serde_json::from_str(r#"{"maxMemory": 536870912, "maxProcesses": 5}"#).unwrap();
+        let validator = SecurityValidator::with_limits(limits);
+        
+        // 1GB memory exceeds the configured 512MB maximum
+        let config = create_test_config();
+        let result = validator.validate_config(&config);
+        assert!(matches!(result.unwrap_err(), SandboxError::SecurityViolation(msg) if msg.contains("Memory")));
+        
+        let mut config = create_test_config();
+        config.resources.max_memory = 256 * 1024 * 1024;
+        let result = validator.validate_config(&config);
+        assert!(matches!(result.unwrap_err(), SandboxError::SecurityViolation(msg) if msg.contains("Process")));
+        
+        config.process.max_processes = 5;
+        assert!(validator.validate_config(&config).is_ok());
+        
+        // The default validator accepts the original config
+        assert!(SecurityValidator::new().validate_config(&create_test_config()).is_ok());
+    }
+    
+    #[test]
+    fn test_security_validator_configured_min_memory() {
+        let validator = SecurityValidator::with_limits(
+            ResourceLimits::builder().min_memory(2 * 1024 * 1024 * 1024).build(),
+        );
+        let result = validator.validate_config(&create_test_config());
+        assert!(matches!(result.unwrap_err(), SandboxError::SecurityViolation(msg) if msg.contains("below minimum")));
+    }
 }
@@ -1,9 +1,11 @@
 //! How tool sandbox execution is delegated: in-process [`SandboxManager`] or (Windows) remote helper.
 
 use std::sync::Arc;
+use std::time::Duration;
 
+use super::error::Result;
 use super::manager::SandboxManager;
-use super::types::ToolSandboxExecKind;
+use super::types::{ExecutionResult, ToolSandboxExecKind};
 
 #[cfg(windows)]
 use super::tool_sandbox_ipc::ToolSandboxIpcClient;
@@ -16,6 +18,8 @@ pub enum ToolSandboxDelegate {
         manager: Arc<SandboxManager>,
         sandbox_id: String,
         kind: ToolSandboxExecKind,
+        /// Container path the host workspace is mounted at (Docker kinds).
+        workspace_dir: String,
     },
     /// Windows: tool AppContainer runs in `synbot tool-sandbox serve` on the host; daemon uses IPC.
     #[cfg(windows)]
@@ -42,6 +46,38 @@ impl ToolSandboxDelegate {
             ToolSandboxDelegate::Remote { sandbox_id, .. } => sandbox_id.as_str(),
         }
     }
+
+    /// Container path of the workspace: the configured mount for Docker tool sandboxes.
+    pub fn workspace_dir(&self) -> &str {
+        match self {
+            ToolSandboxDelegate::Local { workspace_dir, .. } => workspace_dir.as_str(),
+            #[cfg(windows)]
+            ToolSandboxDelegate::Remote { .. } => super::types::DEFAULT_CONTAINER_WORKSPACE,
+        }
+    }
+
+    /// Run `command` in the tool sandbox; `session_id` selects the session's own sandbox under
+    /// per-session isolation (local manager only).
+    pub async fn exec(
+        &self,
+        session_id: Option<&str>,
+        command: &str,
+        args: &[String],
+        timeout: Duration,
+        cwd: Option<&str>,
+    ) -> Result<ExecutionResult> {
+        match self {
+            ToolSandboxDelegate::Local { manager, .. } => {
+                manager
+                    .exec_in_session_tool_sandbox(session_id, command, args, timeout, cwd)
+                    .await
+            }
+            #[cfg(windows)]
+            ToolSandboxDelegate::Remote { client, sandbox_id, .. } => {
+                client.execute(sandbox_id, command, args, timeout, cwd).await
+            }
+        }
+    }
 }
 
 /// Optional tool sandbox context for CLI / tools. Replaces the old `(Manager, Option<id>, kind)` tuple.
@@ -5,6 +5,9 @@ use chrono::{DateTime, Utc};
 use std::time::Duration;
 use std::collections::HashMap;
 
+/// Container path where Docker tool sandboxes mount the workspace unless `toolSandbox.workspaceMountPath` is set.
+pub const DEFAULT_CONTAINER_WORKSPACE: &str = "/workspace";
+
 /// How the exec tool should invoke the shell when `toolSandbox` is enabled.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ToolSandboxExecKind {
@@ -15,6 +18,18 @@ pub enum ToolSandboxExecKind {
     HostNative,
 }
 
+/// Whether chat sessions share the tool sandbox or each get their own (`toolSandbox.isolation`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ToolSandboxIsolation {
+    /// One tool sandbox (or pool, see `poolSize`) runs the execs of every session.
+    #[default]
+    Shared,
+    /// Each session gets its own tool sandbox, created on its first exec and removed when idle.
+    PerSession,
+}
+
 /// Sandbox configuration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SandboxConfig {
@@ -28,7 +43,7 @@ pub struct SandboxConfig {
     /// Optional working directory for the child process (app sandbox only). When set, used as cwd so config_dir() resolves correctly.
     #[serde(default)]
     pub child_work_dir: Option<String>,
-    /// When true (tool sandbox only), remove existing container and create fresh on start. When false, reuse existing container if found.
+    /// When true, remove the existing container (tool sandbox) or AppContainer profile (app sandbox) and create fresh on start. When false, reuse existing container if found.
     #[serde(default)]
     pub delete_on_start: bool,
     /// Requested tool sandbox backend: "gvisor-docker", "plain-docker", "wsl2-gvisor" (Windows), "appcontainer" (Windows), "nono" (Linux/macOS), "seatbelt" (macOS only). Set at build from config; no fallback when this is set.
@@ -37,6 +52,44 @@ pub struct SandboxConfig {
     /// Docker image for tool sandbox (e.g. "ubuntu:22.04"). When None, Docker backends use default "ubuntu:22.04". Set at build from config.
     #[serde(skip, default)]
     pub image: Option<String>,
+    /// Escape/privilege hardening options.
+    #[serde(default)]
+    pub hardening: HardeningConfig,
+}
+
+/// Hardening options checked by [`super::security::EscapePrevention`] and applied by the backends.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HardeningConfig {
+    /// Accept configs that escape prevention would otherwise reject (host root or `/dev` mounts,
+    /// host network). Only for trusted setups.
+    #[serde(default)]
+    pub allow_insecure: bool,
+    /// Block privilege gain via setuid/file capabilities: `no-new-privileges` on Docker backends,
+    /// `PR_SET_NO_NEW_PRIVS` on Linux nono. Default true.
+    #[serde(default = "default_true")]
+    pub no_new_privileges: bool,
+    /// Mount the container root filesystem read-only (Docker `--read-only`) with a tmpfs at `/tmp`;
+    /// only the configured writable paths stay writable. Default true.
+    #[serde(default = "default_true")]
+    pub readonly_rootfs: bool,
+    /// Linux capabilities added back after dropping all (e.g. "NET_BIND_SERVICE"). Docker backends only.
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+impl Default for HardeningConfig {
+    fn default() -> Self {
+        Self {
+            allow_insecure: false,
+            no_new_privileges: true,
+            readonly_rootfs: true,
+            capabilities: Vec::new(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
 }
 
 /// Filesystem configuration
@@ -51,6 +104,9 @@ pub struct FilesystemConfig {
     /// When set, bind-mount host skills dir at container path (host, container). Used by tool sandbox so exec can read skills at e.g. /skills. Read-only. Not from config; set at build time.
     #[serde(skip, default)]
     pub skills_mount: Option<(String, String)>,
+    /// When set, the container runs as this `uid:gid` so files created in the workspace mount belong to the host user. Not from config; set at build time.
+    #[serde(skip, default)]
+    pub workspace_owner: Option<String>,
 }
 
 /// Network configuration
@@ -59,6 +115,9 @@ pub struct NetworkConfig {
     pub enabled: bool,
     pub allowed_hosts: Vec<String>,
     pub allowed_ports: Vec<u16>,
+    /// Share the host network namespace (Docker `--network host`). Rejected unless `hardening.allow_insecure`.
+    #[serde(default)]
+    pub host_network: bool,
 }
 
 /// Resource limits configuration
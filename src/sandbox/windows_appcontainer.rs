@@ -471,6 +471,49 @@ pub fn log_process_token_appcontainer_diagnostic() {
     }
 }
 
+/// Returns true if a Windows Firewall rule with this name exists. Reading rules does not need
+/// Administrator. When the firewall cannot be queried, assumes the rule is there so start()
+/// keeps its previous behavior.
+fn firewall_rule_exists(rule_name: &str) -> bool {
+    use windows::core::BSTR;
+    use windows::Win32::NetworkManagement::WindowsFirewall::{INetFwPolicy2, NetFwPolicy2};
+    use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED};
+
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        let policy: INetFwPolicy2 = match CoCreateInstance(&NetFwPolicy2, None, CLSCTX_INPROC_SERVER) {
+            Ok(p) => p,
+            Err(_) => return true,
+        };
+        let rules = match policy.Rules() {
+            Ok(r) => r,
+            Err(_) => return true,
+        };
+        rules.Item(&BSTR::from(rule_name)).is_ok()
+    }
+}
+
+/// What start() does about firewall/WFP rules for an AppContainer with network enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NetworkRulesPlan {
+    /// Running as Administrator: add the rules now.
+    Install,
+    /// Normal user; rules from `synbot sandbox setup` are in place.
+    UseExisting,
+    /// Normal user and no rules: outbound traffic would be blocked, so start without network.
+    DisableNetwork,
+}
+
+fn plan_network_rules(elevated: bool, rules_installed: bool) -> NetworkRulesPlan {
+    if elevated {
+        NetworkRulesPlan::Install
+    } else if rules_installed {
+        NetworkRulesPlan::UseExisting
+    } else {
+        NetworkRulesPlan::DisableNetwork
+    }
+}
+
 /// Add a Windows Firewall outbound allow rule for the given AppContainer SID so that
 /// the container can make outbound TCP/UDP (e.g. HTTPS) connections.
 fn add_firewall_outbound_rule_for_appcontainer(sid_string: &str, rule_name: &str) -> Result<()> {
@@ -2364,10 +2407,25 @@ impl WindowsAppContainerSandbox {
 }
 
 impl WindowsAppContainerSandbox {
+    /// Deletes an existing profile with this sandbox's name (e.g. left by a crashed run).
+    /// Returns true when one was removed.
+    fn delete_leftover_profile(&self) -> bool {
+        let name_wide = to_wide_null(&self.profile_name);
+        unsafe { DeleteAppContainerProfile(PCWSTR::from_raw(name_wide.as_ptr())).is_ok() }
+    }
+
     /// Creates the AppContainer profile and adds firewall/WFP/loopback rules.
     /// Used by both start() and install_windows_sandbox_network_rules().
     /// Does not set sandbox state to Running.
     fn create_profile_and_add_network_rules(&mut self) -> Result<()> {
+        if self.config.delete_on_start && self.delete_leftover_profile() {
+            let _ = writeln!(
+                std::io::stderr(),
+                "[synbot sandbox] Removed leftover AppContainer profile {} (deleteOnStart)",
+                self.profile_name
+            );
+            let _ = std::io::stderr().flush();
+        }
         let name_wide = to_wide_null(&self.profile_name);
         let display_wide = to_wide_null(&format!("SynBot Sandbox {}", self.config.sandbox_id));
         let desc_wide = to_wide_null("Sandbox for SynBot agent process");
@@ -2596,6 +2654,22 @@ pub fn install_windows_sandbox_network_rules(
 impl Sandbox for WindowsAppContainerSandbox {
     fn start(&mut self) -> Result<()> {
         self.status.state = SandboxState::Starting;
+        if self.config.network.enabled {
+            let rule_name = format!("SynBot Sandbox - {}", self.config.sandbox_id);
+            let plan = plan_network_rules(is_process_elevated(), firewall_rule_exists(&rule_name));
+            if plan == NetworkRulesPlan::DisableNetwork {
+                let _ = writeln!(std::io::stderr(), "[synbot sandbox] WARNING: network is enabled but the firewall/WFP rules for this sandbox are missing and this process is not elevated.");
+                let _ = writeln!(std::io::stderr(), "[synbot sandbox] Starting WITHOUT network access. To enable it, run once as Administrator: synbot sandbox setup");
+                let _ = std::io::stderr().flush();
+                log::warn!(
+                    "AppContainer {}: firewall rule '{}' not found and not elevated; network disabled (run as Administrator: synbot sandbox setup)",
+                    self.config.sandbox_id,
+                    rule_name
+                );
+                self.config.network.enabled = false;
+                self.capabilities = Self::build_capabilities(&self.config)?;
+            }
+        }
         self.create_profile_and_add_network_rules()?;
 
         log::info!(
@@ -2738,6 +2812,7 @@ mod tests {
                 enabled: true,
                 allowed_hosts: vec!["api.example.com".to_string()],
                 allowed_ports: vec![80, 443],
+                host_network: false,
             },
             resources: ResourceConfig {
                 max_memory: 1024 * 1024 * 1024, // 1GB
@@ -2753,6 +2828,7 @@ mod tests {
             delete_on_start: false,
             requested_tool_sandbox_type: None,
             image: None,
+            hardening: HardeningConfig::default(),
         }
     }
     
@@ -2778,6 +2854,45 @@ mod tests {
         assert!(capabilities.iter().any(|c| c.name == "internetClientServer"));
     }
     
+    #[test]
+    fn test_plan_network_rules_falls_back_without_elevation_or_setup() {
+        assert_eq!(plan_network_rules(true, false), NetworkRulesPlan::Install);
+        assert_eq!(plan_network_rules(true, true), NetworkRulesPlan::Install);
+        assert_eq!(plan_network_rules(false, true), NetworkRulesPlan::UseExisting);
+        assert_eq!(plan_network_rules(false, false), NetworkRulesPlan::DisableNetwork);
+    }
+    
+    #[test]
+    fn test_delete_on_start_removes_leftover_profile() {
+        let mut config = create_test_config();
+        config.sandbox_id = "test-windows-recreate".to_string();
+        config.network.enabled = false;
+        config.delete_on_start = true;
+        let sandbox = WindowsAppContainerSandbox::new(config).unwrap();
+        let name_wide = to_wide_null(&sandbox.profile_name);
+        let create = || unsafe {
+            CreateAppContainerProfile(
+                PCWSTR::from_raw(name_wide.as_ptr()),
+                PCWSTR::from_raw(name_wide.as_ptr()),
+                PCWSTR::from_raw(name_wide.as_ptr()),
+                None,
+            )
+        };
+        
+        // Leftover from a previous run
+        let leftover = create().or_else(|_| {
+            sandbox.delete_leftover_profile();
+            create()
+        });
+        let leftover = leftover.expect("create leftover profile");
+        unsafe { FreeSid(leftover) };
+        
+        assert!(sandbox.delete_leftover_profile());
+        let fresh = create().expect("profile name is free again");
+        unsafe { FreeSid(fresh) };
+        assert!(sandbox.delete_leftover_profile());
+    }
+    
     #[test]
     fn test_build_capabilities_without_network() {
         let mut config = create_test_config();
@@ -439,6 +439,7 @@ mod wsl2_sandbox_tests {
                 enabled: false,
                 allowed_hosts: vec![],
                 allowed_ports: vec![],
+                host_network: false,
             },
             resources: ResourceConfig {
                 max_memory: 512 * 1024 * 1024, // 512MB
@@ -454,6 +455,7 @@ mod wsl2_sandbox_tests {
             delete_on_start: false,
             requested_tool_sandbox_type: None,
             image: None,
+            hardening: HardeningConfig::default(),
         }
     }
     
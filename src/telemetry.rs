@@ -0,0 +1,346 @@
+//! Opt-in usage telemetry — anonymous counters only.
+//!
+//! Disabled by default (`telemetry.enabled = false`). When enabled, a [TelemetryHook] counts
+//! lifecycle events and [TelemetryBackgroundService] periodically emits a [TelemetrySnapshot].
+//! Snapshots hold event counts and the synbot version, nothing else: no message content, chat or
+//! user ids, tool arguments, or secrets. [ensure_counters_only] enforces that on every payload
+//! before it leaves the process.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::background::{BackgroundContext, BackgroundService};
+use crate::config::TelemetryConfig;
+use crate::hooks::{Hook, HookEvent};
+
+/// Keys allowed in an emitted payload. Everything except `version` must be a number.
+const ALLOWED_FIELDS: &[&str] = &[
+    "version",
+    "messagesReceived",
+    "messagesSent",
+    "agentRuns",
+    "toolRuns",
+    "toolFailures",
+    "approvalsRequested",
+];
+
+// ---------------------------------------------------------------------------
+// Counters and snapshot
+// ---------------------------------------------------------------------------
+
+#[derive(Default)]
+struct Counters {
+    messages_received: AtomicU64,
+    messages_sent: AtomicU64,
+    agent_runs: AtomicU64,
+    tool_runs: AtomicU64,
+    tool_failures: AtomicU64,
+    approvals_requested: AtomicU64,
+}
+
+/// What gets emitted. Deliberately has no string fields other than the build version.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetrySnapshot {
+    pub version: &'static str,
+    pub messages_received: u64,
+    pub messages_sent: u64,
+    pub agent_runs: u64,
+    pub tool_runs: u64,
+    pub tool_failures: u64,
+    pub approvals_requested: u64,
+}
+
+/// Reject any payload that is not a flat object of allowlisted counter fields. Called on every
+/// emission, so a future field carrying content fails loudly instead of leaking.
+pub fn ensure_counters_only(payload: &Value) -> Result<()> {
+    let obj = payload
+        .as_object()
+        .ok_or_else(|| anyhow::anyhow!("telemetry payload must be a JSON object"))?;
+    for (key, value) in obj {
+        if !ALLOWED_FIELDS.contains(&key.as_str()) {
+            anyhow::bail!("telemetry field '{}' is not an allowed counter", key);
+        }
+        let ok = if key == "version" {
+            value.is_string()
+        } else {
+            value.is_u64()
+        };
+        if !ok {
+            anyhow::bail!("telemetry field '{}' must be a counter", key);
+        }
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Sinks
+// ---------------------------------------------------------------------------
+
+/// Destination for telemetry payloads.
+#[async_trait]
+pub trait TelemetrySink: Send + Sync {
+    async fn send(&self, payload: &Value) -> Result<()>;
+}
+
+/// POSTs the payload as JSON to `telemetry.endpoint`.
+pub struct HttpSink {
+    endpoint: String,
+    client: reqwest::Client,
+}
+
+impl HttpSink {
+    pub fn new(endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl TelemetrySink for HttpSink {
+    async fn send(&self, payload: &Value) -> Result<()> {
+        self.client
+            .post(&self.endpoint)
+            .json(payload)
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Writes the payload to the local log only (used when no endpoint is configured).
+pub struct LogSink;
+
+#[async_trait]
+impl TelemetrySink for LogSink {
+    async fn send(&self, payload: &Value) -> Result<()> {
+        tracing::info!(target: "synbot::telemetry", payload = %payload, "Telemetry snapshot");
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Telemetry
+// ---------------------------------------------------------------------------
+
+/// Counters plus the sink they are flushed to. When disabled, counting and emission are no-ops.
+pub struct Telemetry {
+    enabled: bool,
+    interval: Duration,
+    counters: Counters,
+    sink: Arc<dyn TelemetrySink>,
+}
+
+impl Telemetry {
+    pub fn new(cfg: &TelemetryConfig, sink: Arc<dyn TelemetrySink>) -> Self {
+        Self {
+            enabled: cfg.enabled,
+            interval: Duration::from_secs(cfg.interval_secs.max(60)),
+            counters: Counters::default(),
+            sink,
+        }
+    }
+
+    /// Build from config: HTTP sink when an endpoint is set, local log otherwise.
+    pub fn from_config(cfg: &TelemetryConfig) -> Self {
+        let sink: Arc<dyn TelemetrySink> = if cfg.endpoint.trim().is_empty() {
+            Arc::new(LogSink)
+        } else {
+            Arc::new(HttpSink::new(cfg.endpoint.trim()))
+        };
+        Self::new(cfg, sink)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Count one event. Only the event kind is looked at; payload fields are never read.
+    pub fn record(&self, event: &HookEvent) {
+        if !self.enabled {
+            return;
+        }
+        let c = &self.counters;
+        match event {
+            HookEvent::MessageReceived(_) => c.messages_received.fetch_add(1, Ordering::Relaxed),
+            HookEvent::MessageSent(_) => c.messages_sent.fetch_add(1, Ordering::Relaxed),
+            HookEvent::AgentRunStart { .. } => c.agent_runs.fetch_add(1, Ordering::Relaxed),
+            HookEvent::ToolRunStart { .. } => c.tool_runs.fetch_add(1, Ordering::Relaxed),
+            HookEvent::ToolRunEnd { success: false, .. } => {
+                c.tool_failures.fetch_add(1, Ordering::Relaxed)
+            }
+            HookEvent::ApprovalRequested(_) => c.approvals_requested.fetch_add(1, Ordering::Relaxed),
+            _ => 0,
+        };
+    }
+
+    /// Read and reset all counters.
+    pub fn take_snapshot(&self) -> TelemetrySnapshot {
+        let c = &self.counters;
+        TelemetrySnapshot {
+            version: env!("CARGO_PKG_VERSION"),
+            messages_received: c.messages_received.swap(0, Ordering::Relaxed),
+            messages_sent: c.messages_sent.swap(0, Ordering::Relaxed),
+            agent_runs: c.agent_runs.swap(0, Ordering::Relaxed),
+            tool_runs: c.tool_runs.swap(0, Ordering::Relaxed),
+            tool_failures: c.tool_failures.swap(0, Ordering::Relaxed),
+            approvals_requested: c.approvals_requested.swap(0, Ordering::Relaxed),
+        }
+    }
+
+    /// Emit the current counters. Returns Ok(false) without touching the sink when disabled.
+    pub async fn flush(&self) -> Result<bool> {
+        if !self.enabled {
+            return Ok(false);
+        }
+        let payload = serde_json::to_value(self.take_snapshot())?;
+        ensure_counters_only(&payload)?;
+        self.sink.send(&payload).await?;
+        Ok(true)
+    }
+}
+
+/// [Hook] that feeds lifecycle events into [Telemetry] counters.
+pub struct TelemetryHook {
+    telemetry: Arc<Telemetry>,
+}
+
+impl TelemetryHook {
+    pub fn new(telemetry: Arc<Telemetry>) -> Self {
+        Self { telemetry }
+    }
+}
+
+#[async_trait]
+impl Hook for TelemetryHook {
+    async fn on_event(&self, event: HookEvent) {
+        self.telemetry.record(&event);
+    }
+}
+
+/// Flushes [Telemetry] every `telemetry.intervalSecs`.
+pub struct TelemetryBackgroundService {
+    telemetry: Arc<Telemetry>,
+}
+
+impl TelemetryBackgroundService {
+    pub fn new(telemetry: Arc<Telemetry>) -> Self {
+        Self { telemetry }
+    }
+}
+
+#[async_trait]
+impl BackgroundService for TelemetryBackgroundService {
+    fn name(&self) -> &str {
+        "telemetry"
+    }
+
+    async fn run(&self, _ctx: BackgroundContext) -> Result<()> {
+        if !self.telemetry.is_enabled() {
+            return Ok(());
+        }
+        let mut ticker = tokio::time::interval(self.telemetry.interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.telemetry.flush().await {
+                tracing::debug!(error = %e, "Telemetry flush failed");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::InboundMessage;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct CaptureSink {
+        sent: Mutex<Vec<Value>>,
+    }
+
+    #[async_trait]
+    impl TelemetrySink for CaptureSink {
+        async fn send(&self, payload: &Value) -> Result<()> {
+            self.sent.lock().unwrap().push(payload.clone());
+            Ok(())
+        }
+    }
+
+    fn telemetry(enabled: bool) -> (Telemetry, Arc<CaptureSink>) {
+        let sink = Arc::new(CaptureSink::default());
+        let cfg = TelemetryConfig {
+            enabled,
+            ..TelemetryConfig::default()
+        };
+        (Telemetry::new(&cfg, sink.clone()), sink)
+    }
+
+    fn secret_message() -> HookEvent {
+        HookEvent::MessageReceived(InboundMessage {
+            channel: "telegram".to_string(),
+            sender_id: "user-42".to_string(),
+            chat_id: "chat-42".to_string(),
+            content: "my api key is sk-SECRET".to_string(),
+            timestamp: chrono::Utc::now(),
+            media: vec![],
+            metadata: Value::Null,
+        })
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        assert!(!TelemetryConfig::default().enabled);
+    }
+
+    #[tokio::test]
+    async fn disabled_telemetry_emits_nothing() {
+        let (t, sink) = telemetry(false);
+        t.record(&secret_message());
+        assert!(!t.flush().await.unwrap());
+        assert!(sink.sent.lock().unwrap().is_empty());
+        assert_eq!(t.take_snapshot().messages_received, 0);
+    }
+
+    #[tokio::test]
+    async fn snapshot_contains_counters_but_no_content() {
+        let (t, sink) = telemetry(true);
+        t.record(&secret_message());
+        t.record(&HookEvent::ToolRunEnd {
+            tool_name: "exec".to_string(),
+            result_preview: "password=hunter2".to_string(),
+            success: false,
+        });
+        assert!(t.flush().await.unwrap());
+
+        let sent = sink.sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0]["messagesReceived"], 1);
+        assert_eq!(sent[0]["toolFailures"], 1);
+        let text = sent[0].to_string();
+        for leaked in ["sk-SECRET", "hunter2", "user-42", "chat-42", "telegram", "exec"] {
+            assert!(!text.contains(leaked), "telemetry leaked {leaked}: {text}");
+        }
+        ensure_counters_only(&sent[0]).unwrap();
+    }
+
+    #[test]
+    fn guard_rejects_content_fields() {
+        assert!(ensure_counters_only(&serde_json::json!({"toolRuns": 1})).is_ok());
+        assert!(ensure_counters_only(&serde_json::json!({"content": "hello"})).is_err());
+        assert!(ensure_counters_only(&serde_json::json!({"toolRuns": "exec"})).is_err());
+        assert!(ensure_counters_only(&serde_json::json!([1, 2])).is_err());
+    }
+}
@@ -24,6 +24,10 @@ pub struct ApprovalRequest {
     /// Display message in user's language; when None/empty, channels use a neutral fallback
     #[serde(skip_serializing_if = "Option::is_none")]
     pub display_message: Option<String>,
+    /// User who must answer a request routed to an approver chat (see
+    /// `PermissionConfig::approval_route`); None when the request went to its originating chat.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approver_id: Option<String>,
 }
 
 /// Approval response (user → agent, submitted via submit_approval_response tool)
@@ -40,7 +44,8 @@ pub enum ApprovalStatus {
     Pending,
     Approved(ApprovalResponse),
     Rejected(ApprovalResponse),
-    Timeout,
+    /// Nobody answered in time; `approved` is the action taken (see `denyOnTimeout`).
+    Timeout { approved: bool },
 }
 
 /// Result of waiting for approval: caller can distinguish user rejection vs timeout.
@@ -97,12 +102,132 @@ impl ApprovalMetrics {
     }
 }
 
+/// Point-in-time view of pending approvals and lifetime outcome totals (see [`ApprovalManager::stats`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalStats {
+    pub pending: usize,
+    pub approved_total: u64,
+    pub denied_total: u64,
+    pub expired_total: u64,
+    /// Age of the oldest request still waiting for a response; `None` when nothing is pending.
+    pub oldest_pending_age: Option<Duration>,
+}
+
+/// ID of whoever may answer a delivered approval request. Routed requests (see
+/// `PermissionConfig::approval_route`) are answered by their `approver_id`; others by the user
+/// in the session (agent:role:channel:type:user_id).
+pub fn approval_responder_key(request: &ApprovalRequest) -> String {
+    match &request.approver_id {
+        Some(approver) => approver.clone(),
+        None => request.session_id.split(':').next_back().unwrap_or("").to_string(),
+    }
+}
+
+/// An approval request a channel delivered and is waiting on.
+#[derive(Debug, Clone)]
+struct PendingApproval {
+    /// See [`approval_responder_key`].
+    responder: String,
+    /// Chat the request was delivered to; replies count only there.
+    chat_id: String,
+    routed: bool,
+    delivered_at: DateTime<Utc>,
+    /// When the [`ApprovalManager`] stops waiting on the request (its timestamp plus timeout).
+    expires_at: DateTime<Utc>,
+}
+
+impl PendingApproval {
+    /// Whether `sender_id` writing in `chat_id` answers this request. Routed requests are
+    /// answered only by the approver; others also by anyone in the session's chat when the
+    /// session is keyed by chat (e.g. groups), as before.
+    fn answered_by(&self, sender_id: &str, chat_id: &str) -> bool {
+        self.chat_id == chat_id
+            && (self.responder == sender_id || (!self.routed && self.responder == chat_id))
+    }
+}
+
+/// Approval requests a channel has delivered, keyed by request ID so that concurrent requests
+/// to the same chat (e.g. several routed to one approver) do not replace each other. Requests
+/// past their timeout are dropped, so a late reply never resolves a request nobody waits on.
+#[derive(Debug, Default)]
+pub struct PendingApprovals {
+    by_request: HashMap<String, PendingApproval>,
+}
+
+impl PendingApprovals {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track `request` after delivering it to `delivered_chat_id`.
+    pub fn insert(&mut self, request: &ApprovalRequest, delivered_chat_id: &str) {
+        let responder = approval_responder_key(request);
+        if responder.is_empty() {
+            return;
+        }
+        self.by_request.insert(
+            request.id.clone(),
+            PendingApproval {
+                responder,
+                chat_id: delivered_chat_id.to_string(),
+                routed: request.approver_id.is_some(),
+                delivered_at: Utc::now(),
+                expires_at: request.timestamp
+                    + chrono::Duration::seconds(request.timeout_secs.min(i64::MAX as u64) as i64),
+            },
+        );
+    }
+
+    /// Drop requests whose timeout has passed.
+    fn remove_expired(&mut self) {
+        let now = Utc::now();
+        self.by_request.retain(|_, p| p.expires_at > now);
+    }
+
+    /// Take the most recently delivered request a text reply from `sender_id` in `chat_id`
+    /// answers; returns its ID.
+    pub fn take_reply(&mut self, sender_id: &str, chat_id: &str) -> Option<String> {
+        self.remove_expired();
+        let request_id = self
+            .by_request
+            .iter()
+            .filter(|(_, p)| p.answered_by(sender_id, chat_id))
+            .max_by_key(|(_, p)| p.delivered_at)
+            .map(|(id, _)| id.clone())?;
+        self.by_request.remove(&request_id);
+        Some(request_id)
+    }
+
+    /// Take `request_id` when `sender_id` in `chat_id` may answer it (e.g. a button click).
+    pub fn take_request(&mut self, request_id: &str, sender_id: &str, chat_id: &str) -> bool {
+        self.remove_expired();
+        let allowed = self
+            .by_request
+            .get(request_id)
+            .is_some_and(|p| p.answered_by(sender_id, chat_id));
+        if allowed {
+            self.by_request.remove(request_id);
+        }
+        allowed
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_request.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_request.is_empty()
+    }
+}
+
 pub struct ApprovalManager {
     pending: Arc<RwLock<HashMap<String, (ApprovalRequest, mpsc::Sender<ApprovalResponse>)>>>,
     history: Arc<RwLock<Vec<(ApprovalRequest, ApprovalStatus)>>>,
     history_capacity: usize,
     outbound_tx: Option<tokio::sync::broadcast::Sender<crate::bus::OutboundMessage>>,
     metrics: Arc<ApprovalMetrics>,
+    route: Option<crate::config::ApprovalRoute>,
+    deny_on_timeout: bool,
 }
 
 impl ApprovalManager {
@@ -117,6 +242,8 @@ impl ApprovalManager {
             history_capacity: capacity,
             outbound_tx: None,
             metrics: Arc::new(ApprovalMetrics::default()),
+            route: None,
+            deny_on_timeout: true,
         }
     }
 
@@ -129,6 +256,8 @@ impl ApprovalManager {
             history_capacity: 1000,
             outbound_tx: Some(outbound_tx),
             metrics: Arc::new(ApprovalMetrics::default()),
+            route: None,
+            deny_on_timeout: true,
         }
     }
     
@@ -142,9 +271,25 @@ impl ApprovalManager {
             history_capacity: capacity,
             outbound_tx: Some(outbound_tx),
             metrics: Arc::new(ApprovalMetrics::default()),
+            route: None,
+            deny_on_timeout: true,
         }
     }
     
+    /// Post approval requests to `route` instead of the originating chat. Responses still
+    /// resolve the original request by its ID.
+    pub fn with_route(mut self, route: Option<crate::config::ApprovalRoute>) -> Self {
+        self.route = route;
+        self
+    }
+
+    /// Outcome for requests nobody answered in time: denied when `deny` is true (default),
+    /// otherwise approved. History records them as [`ApprovalStatus::Timeout`] with the action taken.
+    pub fn with_deny_on_timeout(mut self, deny: bool) -> Self {
+        self.deny_on_timeout = deny;
+        self
+    }
+
     async fn add_to_history(&self, request: ApprovalRequest, status: ApprovalStatus) {
         let mut history = self.history.write().await;
         if history.len() >= self.history_capacity {
@@ -187,6 +332,10 @@ impl ApprovalManager {
             timestamp: Utc::now(),
             timeout_secs,
             display_message: display_message.filter(|s| !s.is_empty()),
+            approver_id: self
+                .route
+                .as_ref()
+                .map(|route| route.user_id.clone().unwrap_or_else(|| route.chat_id.clone())),
         };
 
         info!(
@@ -207,11 +356,15 @@ impl ApprovalManager {
             pending.insert(request_id.clone(), (request.clone(), tx));
         }
 
-        // Broadcast approval request via message bus
+        // Broadcast approval request via message bus (to the approver route when configured)
         if let Some(outbound_tx) = &self.outbound_tx {
+            let (target_channel, target_chat_id) = match &self.route {
+                Some(route) => (route.channel.clone(), route.chat_id.clone()),
+                None => (channel.clone(), chat_id.clone()),
+            };
             let approval_msg = crate::bus::OutboundMessage::approval_request(
-                channel.clone(),
-                chat_id.clone(),
+                target_channel.clone(),
+                target_chat_id,
                 request.clone(),
                 None,
             );
@@ -219,7 +372,7 @@ impl ApprovalManager {
             
             info!(
                 request_id = %request_id,
-                channel = %channel,
+                channel = %target_channel,
                 "Approval request sent to message bus"
             );
         }
@@ -276,7 +429,7 @@ impl ApprovalManager {
                     "Approval request timeout - no response received"
                 );
                 self.metrics.timeout_count.fetch_add(1, Ordering::Relaxed);
-                ApprovalStatus::Timeout
+                ApprovalStatus::Timeout { approved: !self.deny_on_timeout }
             }
             Err(_) => {
                 warn!(
@@ -286,13 +439,19 @@ impl ApprovalManager {
                     "Approval request timeout - exceeded time limit"
                 );
                 self.metrics.timeout_count.fetch_add(1, Ordering::Relaxed);
-                ApprovalStatus::Timeout
+                ApprovalStatus::Timeout { approved: !self.deny_on_timeout }
             }
         };
 
         self.add_to_history(request, status).await;
 
-        Ok(ApprovalOutcome::Timeout)
+        if self.deny_on_timeout {
+            info!(request_id = %request_id, command = %command, decision = "deny", "Approval timed out; denying");
+            Ok(ApprovalOutcome::Timeout)
+        } else {
+            warn!(request_id = %request_id, command = %command, decision = "approve", "Approval timed out; approving (denyOnTimeout is false)");
+            Ok(ApprovalOutcome::Approved)
+        }
     }
 
     pub async fn submit_response(&self, response: ApprovalResponse) -> anyhow::Result<()> {
@@ -318,6 +477,24 @@ impl ApprovalManager {
     pub fn metrics(&self) -> Arc<ApprovalMetrics> {
         Arc::clone(&self.metrics)
     }
+
+    /// Pending count, oldest pending age, and approve/deny/timeout totals since start (or last reset).
+    pub async fn stats(&self) -> ApprovalStats {
+        let pending = self.pending.read().await;
+        let now = Utc::now();
+        let oldest_pending_age = pending
+            .values()
+            .map(|(req, _)| req.timestamp)
+            .min()
+            .map(|oldest| (now - oldest).to_std().unwrap_or_default());
+        ApprovalStats {
+            pending: pending.len(),
+            approved_total: self.metrics.approved_count.load(Ordering::Relaxed),
+            denied_total: self.metrics.rejected_count.load(Ordering::Relaxed),
+            expired_total: self.metrics.timeout_count.load(Ordering::Relaxed),
+            oldest_pending_age,
+        }
+    }
     
     pub fn reset_metrics(&self) {
         self.metrics.reset();
@@ -371,7 +548,7 @@ mod tests {
         // Check history
         let history = manager.get_history().await;
         assert_eq!(history.len(), 1);
-        assert!(matches!(history[0].1, ApprovalStatus::Timeout));
+        assert!(matches!(history[0].1, ApprovalStatus::Timeout { approved: false }));
     }
 
     #[tokio::test]
@@ -500,7 +677,7 @@ mod tests {
         // Check history
         let history = manager.get_history().await;
         assert_eq!(history.len(), 1);
-        assert!(matches!(history[0].1, ApprovalStatus::Timeout));
+        assert!(matches!(history[0].1, ApprovalStatus::Timeout { approved: false }));
 
         // Check pending request was cleaned up
         let pending = manager.pending.read().await;
@@ -609,6 +786,8 @@ mod tests {
                 history_capacity: self.history_capacity,
                 outbound_tx: self.outbound_tx.clone(),
                 metrics: Arc::clone(&self.metrics),
+                route: self.route.clone(),
+                deny_on_timeout: self.deny_on_timeout,
             }
         }
     }
@@ -676,6 +855,164 @@ mod tests {
         assert_eq!(result.unwrap(), ApprovalOutcome::Approved);
     }
 
+    #[tokio::test]
+    async fn test_routed_request_goes_to_approver_and_resolves_original() {
+        use tokio::sync::broadcast;
+
+        let (outbound_tx, mut outbound_rx) = broadcast::channel(10);
+        let manager = ApprovalManager::with_outbound(outbound_tx).with_route(Some(
+            crate::config::ApprovalRoute {
+                channel: "admin-telegram".to_string(),
+                chat_id: "admin-chat".to_string(),
+                user_id: Some("admin".to_string()),
+            },
+        ));
+
+        let manager_clone = manager.clone_for_test();
+        let handle = tokio::spawn(async move {
+            manager_clone
+                .request_approval(
+                    "main:dev:telegram:dm:user42".to_string(),
+                    "telegram".to_string(),
+                    "user42".to_string(),
+                    "git push --force".to_string(),
+                    "/home/user".to_string(),
+                    "Test route".to_string(),
+                    10,
+                    None,
+                )
+                .await
+        });
+
+        let msg = tokio::time::timeout(Duration::from_secs(1), outbound_rx.recv())
+            .await
+            .expect("Should receive message within timeout")
+            .expect("Should receive message");
+        assert_eq!(msg.channel, "admin-telegram");
+        assert_eq!(msg.chat_id, "admin-chat");
+
+        let request = match msg.message_type {
+            crate::bus::OutboundMessageType::ApprovalRequest { request } => request,
+            _ => panic!("Expected ApprovalRequest message type"),
+        };
+        // The request still describes where the command came from
+        assert_eq!(request.channel, "telegram");
+        assert_eq!(request.chat_id, "user42");
+        assert_eq!(request.approver_id.as_deref(), Some("admin"));
+        assert_eq!(approval_responder_key(&request), "admin");
+
+        manager
+            .submit_response(ApprovalResponse {
+                request_id: request.id.clone(),
+                approved: true,
+                responder: "admin".to_string(),
+                timestamp: Utc::now(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(handle.await.unwrap().unwrap(), ApprovalOutcome::Approved);
+
+        let history = manager.get_history().await;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].0.id, request.id);
+        assert_eq!(history[0].0.command, "git push --force");
+        assert!(matches!(history[0].1, ApprovalStatus::Approved(_)));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_outcome_follows_deny_on_timeout() {
+        for (deny, expected) in [(true, ApprovalOutcome::Timeout), (false, ApprovalOutcome::Approved)] {
+            let manager = ApprovalManager::new().with_deny_on_timeout(deny);
+            let outcome = manager
+                .request_approval(
+                    "session1".to_string(),
+                    "web".to_string(),
+                    "chat1".to_string(),
+                    "make deploy".to_string(),
+                    "/home/user".to_string(),
+                    "Test timeout".to_string(),
+                    1,
+                    None,
+                )
+                .await
+                .unwrap();
+            assert_eq!(outcome, expected);
+
+            // Either way the audit trail shows nobody answered, and what was done about it
+            let history = manager.get_history().await;
+            assert_eq!(history.len(), 1);
+            assert!(
+                matches!(history[0].1, ApprovalStatus::Timeout { approved } if approved == !deny),
+                "{:?}",
+                history[0].1
+            );
+            assert_eq!(manager.stats().await.expired_total, 1);
+        }
+    }
+
+    fn pending_request(id: &str, approver_id: Option<&str>) -> ApprovalRequest {
+        ApprovalRequest {
+            id: id.to_string(),
+            session_id: "main:dev:telegram:dm:user42".to_string(),
+            channel: "telegram".to_string(),
+            chat_id: "user42".to_string(),
+            command: "ls".to_string(),
+            working_dir: "/".to_string(),
+            context: String::new(),
+            timestamp: Utc::now(),
+            timeout_secs: 10,
+            display_message: None,
+            approver_id: approver_id.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_responder_key_uses_session_user_when_not_routed() {
+        let request = pending_request("r1", None);
+        assert_eq!(approval_responder_key(&request), "user42");
+
+        let mut pending = PendingApprovals::new();
+        pending.insert(&request, "user42");
+        assert_eq!(pending.take_reply("someone-else", "other-chat"), None);
+        assert_eq!(pending.take_reply("user42", "user42").as_deref(), Some("r1"));
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_routed_requests_are_tracked_separately_and_only_the_approver_answers() {
+        let mut pending = PendingApprovals::new();
+        let first = pending_request("r1", Some("admin"));
+        let second = pending_request("r2", Some("admin"));
+        pending.insert(&first, "admin-group");
+        std::thread::sleep(Duration::from_millis(2));
+        pending.insert(&second, "admin-group");
+        assert_eq!(pending.len(), 2, "a second routed request must not replace the first");
+
+        // Another member of the approver chat cannot answer, even by clicking a button.
+        assert_eq!(pending.take_reply("member", "admin-group"), None);
+        assert!(!pending.take_request("r2", "member", "admin-group"));
+        // Nor can the approver from a different chat.
+        assert_eq!(pending.take_reply("admin", "elsewhere"), None);
+
+        assert_eq!(pending.take_reply("admin", "admin-group").as_deref(), Some("r2"));
+        assert!(pending.take_request("r1", "admin", "admin-group"));
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_reply_skips_timed_out_requests() {
+        let mut pending = PendingApprovals::new();
+        let mut stale = pending_request("stale", None);
+        stale.timestamp = Utc::now() - chrono::Duration::seconds(60);
+        pending.insert(&stale, "user42");
+        pending.insert(&pending_request("live", None), "user42");
+
+        assert_eq!(pending.take_reply("user42", "user42").as_deref(), Some("live"));
+        assert!(pending.is_empty(), "the timed-out request is dropped, not kept for the next reply");
+        pending.insert(&stale, "user42");
+        assert!(!pending.take_request("stale", "user42", "user42"));
+    }
+
     #[tokio::test]
     async fn test_approval_request_without_broadcast() {
         // Create approval manager without message bus
@@ -793,6 +1130,65 @@ mod tests {
         assert_eq!(size, 5);
         assert_eq!(capacity, 5);
     }
+
+    #[tokio::test]
+    async fn test_stats_track_pending_and_resolved_requests() {
+        let manager = ApprovalManager::new();
+        let empty = manager.stats().await;
+        assert_eq!(empty.pending, 0);
+        assert_eq!(empty.oldest_pending_age, None);
+
+        let spawn_request = |command: &str| {
+            let manager_clone = manager.clone_for_test();
+            let command = command.to_string();
+            tokio::spawn(async move {
+                manager_clone
+                    .request_approval(
+                        "session1".to_string(),
+                        "web".to_string(),
+                        "chat1".to_string(),
+                        command,
+                        "/home/user".to_string(),
+                        "Test context".to_string(),
+                        10,
+                        None,
+                    )
+                    .await
+            })
+        };
+        let first = spawn_request("git push");
+        let second = spawn_request("rm -rf build");
+        sleep(Duration::from_millis(100)).await;
+
+        let stats = manager.stats().await;
+        assert_eq!(stats.pending, 2);
+        assert!(stats.oldest_pending_age.unwrap() >= Duration::from_millis(50));
+
+        let ids: Vec<(String, String)> = {
+            let pending = manager.pending.read().await;
+            pending.iter().map(|(id, (req, _))| (id.clone(), req.command.clone())).collect()
+        };
+        for (request_id, command) in ids {
+            manager
+                .submit_response(ApprovalResponse {
+                    request_id,
+                    approved: command == "git push",
+                    responder: "user1".to_string(),
+                    timestamp: Utc::now(),
+                })
+                .await
+                .unwrap();
+        }
+        assert_eq!(first.await.unwrap().unwrap(), ApprovalOutcome::Approved);
+        assert_eq!(second.await.unwrap().unwrap(), ApprovalOutcome::Rejected);
+
+        let stats = manager.stats().await;
+        assert_eq!(stats.pending, 0);
+        assert_eq!(stats.approved_total, 1);
+        assert_eq!(stats.denied_total, 1);
+        assert_eq!(stats.expired_total, 0);
+        assert_eq!(stats.oldest_pending_age, None);
+    }
 }
 
     #[tokio::test]
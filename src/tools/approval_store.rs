@@ -22,7 +22,11 @@ pub enum ApprovalStatusRecord {
     Pending,
     Approved { responder: String, timestamp: DateTime<Utc> },
     Rejected { responder: String, timestamp: DateTime<Utc> },
-    Timeout,
+    /// `approved` is the action taken on timeout; records written before it was kept read as denied.
+    Timeout {
+        #[serde(default)]
+        approved: bool,
+    },
 }
 
 impl From<&ApprovalStatus> for ApprovalStatusRecord {
@@ -37,7 +41,7 @@ impl From<&ApprovalStatus> for ApprovalStatusRecord {
                 responder: response.responder.clone(),
                 timestamp: response.timestamp,
             },
-            ApprovalStatus::Timeout => ApprovalStatusRecord::Timeout,
+            ApprovalStatus::Timeout { approved } => ApprovalStatusRecord::Timeout { approved: *approved },
         }
     }
 }
@@ -171,7 +175,7 @@ fn matches_status(status: &ApprovalStatusRecord, filter: &ApprovalStatusRecord)
         (ApprovalStatusRecord::Pending, ApprovalStatusRecord::Pending) => true,
         (ApprovalStatusRecord::Approved { .. }, ApprovalStatusRecord::Approved { .. }) => true,
         (ApprovalStatusRecord::Rejected { .. }, ApprovalStatusRecord::Rejected { .. }) => true,
-        (ApprovalStatusRecord::Timeout, ApprovalStatusRecord::Timeout) => true,
+        (ApprovalStatusRecord::Timeout { .. }, ApprovalStatusRecord::Timeout { .. }) => true,
         _ => false,
     }
 }
@@ -193,6 +197,7 @@ mod tests {
             timestamp: Utc::now(),
             timeout_secs: 300,
             display_message: None,
+            approver_id: None,
         }
     }
 
@@ -0,0 +1,67 @@
+//! In-memory audit trail of tool security decisions (bounded; oldest entries dropped first).
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use crate::tools::permission::PermissionLevel;
+
+/// A security-relevant decision made while running a tool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ToolAuditEvent {
+    /// An exec command was checked against the permission rules.
+    PermissionDecision {
+        command: String,
+        level: PermissionLevel,
+        /// Index into the configured rules; `None` when the default level applied.
+        rule_index: Option<usize>,
+        pattern: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolAuditEntry {
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: ToolAuditEvent,
+}
+
+/// Bounded log of [`ToolAuditEvent`]s shared by the tools that make security decisions.
+#[derive(Debug)]
+pub struct ToolAuditLog {
+    entries: Mutex<VecDeque<ToolAuditEntry>>,
+    capacity: usize,
+}
+
+impl ToolAuditLog {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(capacity.min(1024))),
+            capacity,
+        }
+    }
+
+    pub fn record(&self, event: ToolAuditEvent) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(ToolAuditEntry {
+            timestamp: Utc::now(),
+            event,
+        });
+    }
+
+    /// All retained entries, oldest first.
+    pub fn entries(&self) -> Vec<ToolAuditEntry> {
+        self.entries.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+impl Default for ToolAuditLog {
+    fn default() -> Self {
+        Self::with_capacity(1000)
+    }
+}
@@ -7,6 +7,10 @@
 
 use std::path::PathBuf;
 
+use tokio_util::sync::CancellationToken;
+
+use crate::tools::ToolError;
+
 tokio::task_local! {
     /// Current agent's tool context: agent_id and allowed workspace.
     /// Set by the agent loop before running the completion loop for main or a role.
@@ -18,9 +22,25 @@ tokio::task_local! {
 pub struct ToolContext {
     pub agent_id: String,
     pub workspace: PathBuf,
+    /// Cancelled when the turn running these tools is aborted (/stop, /cancel, cancel API).
+    pub cancel: CancellationToken,
 }
 
 impl ToolContext {
+    /// Context with a cancellation token that never fires; use [`ToolContext::with_cancel`] to tie it to a turn.
+    pub fn new(agent_id: impl Into<String>, workspace: PathBuf) -> Self {
+        Self {
+            agent_id: agent_id.into(),
+            workspace,
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    pub fn with_cancel(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
     /// Run a future with this context set. Tools executed inside will see this agent's scope.
     pub async fn scope<F, R>(self, f: F) -> R
     where
@@ -48,3 +68,74 @@ pub fn current_agent_id() -> Option<String> {
 pub fn current_allowed_roots() -> Option<PathBuf> {
     TOOL_CONTEXT.try_with(|c| c.workspace.clone()).ok()
 }
+
+/// Cancellation token of the current scope. Returns None if not in context.
+pub fn current_cancel_token() -> Option<CancellationToken> {
+    TOOL_CONTEXT.try_with(|c| c.cancel.clone()).ok()
+}
+
+/// True if the current scope has been cancelled. Long-running tools can poll this between steps.
+pub fn is_cancelled() -> bool {
+    TOOL_CONTEXT.try_with(|c| c.cancel.is_cancelled()).unwrap_or(false)
+}
+
+/// Await `fut` unless the current scope is cancelled first, in which case `fut` is dropped and
+/// [`ToolError::Cancelled`] is returned. Outside a tool context `fut` simply runs to completion.
+pub async fn cancellable<F>(fut: F) -> Result<F::Output, ToolError>
+where
+    F: std::future::Future,
+{
+    match current_cancel_token() {
+        Some(token) => tokio::select! {
+            out = fut => Ok(out),
+            _ = token.cancelled() => Err(ToolError::Cancelled),
+        },
+        None => Ok(fut.await),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Fake long-running tool body that cooperates with cancellation.
+    async fn slow_tool_call() -> anyhow::Result<String> {
+        cancellable(tokio::time::sleep(Duration::from_secs(30))).await?;
+        Ok("finished".to_string())
+    }
+
+    #[tokio::test]
+    async fn cancelling_scope_aborts_cooperating_tool() {
+        let token = CancellationToken::new();
+        let ctx = ToolContext::new("main", PathBuf::from("/tmp")).with_cancel(token.clone());
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            canceller.cancel();
+        });
+
+        let started = std::time::Instant::now();
+        let err = scope(ctx, slow_tool_call()).await.unwrap_err();
+        assert_eq!(err.downcast_ref::<ToolError>(), Some(&ToolError::Cancelled));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn uncancelled_scope_runs_to_completion() {
+        let ctx = ToolContext::new("main", PathBuf::from("/tmp"));
+        let out = scope(ctx, async {
+            assert!(!is_cancelled());
+            cancellable(async { 5 }).await
+        })
+        .await;
+        assert_eq!(out, Ok(5));
+    }
+
+    #[tokio::test]
+    async fn outside_scope_is_never_cancelled() {
+        assert!(current_cancel_token().is_none());
+        assert!(!is_cancelled());
+        assert_eq!(cancellable(async { "ok" }).await, Ok("ok"));
+    }
+}
@@ -0,0 +1,16 @@
+//! Typed errors returned by [`crate::tools::ToolRegistry::execute`].
+//!
+//! `execute` still returns `anyhow::Result`; callers that need to tell these cases apart can
+//! `downcast_ref::<ToolError>()` on the error.
+
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ToolError {
+    /// The arguments supplied by the model do not match the tool's `parameters_schema`.
+    #[error("Invalid arguments for tool '{tool}': {message}")]
+    InvalidArgs { tool: String, message: String },
+    /// The turn was cancelled while the tool was running.
+    #[error("Tool execution cancelled")]
+    Cancelled,
+}
@@ -1,12 +1,20 @@
 //! File-system tools: read_file, write_file, edit_file, list_dir,
-//! read_multiple_files, create_dir, move_file, search_files, search_text, get_file_info.
+//! read_multiple_files, create_dir, move_file, search_files, search_text, get_file_info,
+//! watch_file, edit_json.
 //!
 //! When an agent runs, paths are restricted to that agent's workspace
 //! via the tool execution context. Memory is accessed only via remember/list_memory tools.
+//!
+//! With a Docker tool sandbox, read_file, write_file and list_dir run inside the container
+//! (through the same exec path as the exec tool), so they see the files exec created there.
 
+use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
 use serde_json::{json, Value};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tracing::{info, warn};
+use crate::sandbox::types::ToolSandboxExecKind;
+use crate::sandbox::{SandboxContext, ToolSandboxDelegate};
 use crate::tools::context;
 use crate::tools::DynTool;
 
@@ -70,6 +78,74 @@ fn resolve_path(path: &str, workspace: &Path, restrict: bool) -> anyhow::Result<
     Ok(p)
 }
 
+/// Timeout of each file operation run inside the tool sandbox.
+const SANDBOX_FS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Base64 bytes passed per write_file exec; stays below Linux's 128 KiB limit for one argument.
+const SANDBOX_WRITE_CHUNK: usize = 64 * 1024;
+
+/// The tool sandbox when file tools must go through it. Only Docker backends have a filesystem
+/// of their own; host-native sandboxes work on the host files directly.
+fn container_sandbox(ctx: &SandboxContext) -> Option<&ToolSandboxDelegate> {
+    ctx.as_ref().filter(|d| d.exec_kind() == ToolSandboxExecKind::Docker)
+}
+
+/// Resolve `path` for a file tool running in the tool sandbox and return the path inside the container.
+/// Container paths under the workspace mount (`/workspace` by default) are checked as the matching
+/// host workspace paths; workspace paths map to the mount, other absolute paths are used as they are.
+fn resolve_sandbox_path(
+    sandbox: &ToolSandboxDelegate,
+    path: &str,
+    workspace: &Path,
+    restrict: bool,
+) -> anyhow::Result<String> {
+    let mount = sandbox.workspace_dir();
+    let effective_workspace: PathBuf = context::current_allowed_roots()
+        .unwrap_or_else(|| workspace.to_path_buf());
+    let host_path = match path.strip_prefix(mount) {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => effective_workspace
+            .join(rest.trim_start_matches('/'))
+            .to_string_lossy()
+            .into_owned(),
+        _ => path.to_string(),
+    };
+    let resolved = resolve_path(&host_path, workspace, restrict)?;
+    let ws_canon = effective_workspace.canonicalize().unwrap_or_else(|_| effective_workspace.clone());
+    let relative = resolved.strip_prefix(&effective_workspace).ok().map(Path::to_path_buf).or_else(|| {
+        let canonical = resolved.canonicalize().ok()?;
+        canonical.strip_prefix(&ws_canon).ok().map(Path::to_path_buf)
+    });
+    Ok(match relative {
+        Some(rel) => std::iter::once(mount.to_string())
+            .chain(rel.components().map(|c| c.as_os_str().to_string_lossy().into_owned()))
+            .collect::<Vec<_>>()
+            .join("/"),
+        None => resolved.to_string_lossy().into_owned(),
+    })
+}
+
+/// Run the shell `script` in the tool sandbox with `args` as `$1`, `$2`, ...; returns stdout.
+/// A non-zero exit becomes an error carrying stderr (e.g. "No such file or directory").
+async fn sandbox_sh(
+    sandbox: &ToolSandboxDelegate,
+    args: &Value,
+    script: &str,
+    script_args: &[&str],
+) -> anyhow::Result<Vec<u8>> {
+    let mut argv = vec!["-c".to_string(), script.to_string(), "sh".to_string()];
+    argv.extend(script_args.iter().map(|a| a.to_string()));
+    let session_id = args["_session_id"].as_str();
+    let result = sandbox
+        .exec(session_id, "sh", &argv, SANDBOX_FS_TIMEOUT, Some(sandbox.workspace_dir()))
+        .await
+        .map_err(|e| anyhow::anyhow!("Tool sandbox: {}", e))?;
+    if result.exit_code != 0 {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        anyhow::bail!("{} (exit code {})", stderr.trim(), result.exit_code);
+    }
+    Ok(result.stdout)
+}
+
 /// Extensions that are typically binary; read_file returns file info instead of UTF-8 content.
 fn is_likely_binary_extension(ext: &str) -> bool {
     matches!(
@@ -87,6 +163,8 @@ fn is_likely_binary_extension(ext: &str) -> bool {
 pub struct ReadFileTool {
     pub workspace: PathBuf,
     pub restrict: bool,
+    /// Read inside this tool sandbox when it is a Docker backend
+    pub sandbox_context: SandboxContext,
 }
 
 #[async_trait::async_trait]
@@ -97,6 +175,26 @@ impl DynTool for ReadFileTool {
         json!({"type":"object","properties":{"path":{"type":"string"}},"required":["path"]})
     }
     async fn call(&self, args: Value) -> anyhow::Result<String> {
+        if let Some(sandbox) = container_sandbox(&self.sandbox_context) {
+            let path = resolve_sandbox_path(
+                sandbox,
+                args["path"].as_str().unwrap_or(""),
+                &self.workspace,
+                self.restrict,
+            )?;
+            info!(path = %path, "read_file (tool sandbox)");
+            let ext = Path::new(&path)
+                .extension()
+                .map(|e| e.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if is_likely_binary_extension(&ext) {
+                let out = sandbox_sh(sandbox, &args, r#"wc -c < "$1""#, &[&path]).await?;
+                let size: u64 = String::from_utf8_lossy(&out).trim().parse().unwrap_or(0);
+                return Ok(binary_file_summary(&ext, size));
+            }
+            let out = sandbox_sh(sandbox, &args, r#"cat -- "$1""#, &[&path]).await?;
+            return String::from_utf8(out).map_err(|_| anyhow::anyhow!("{} is not valid UTF-8", path));
+        }
         let path = resolve_path(args["path"].as_str().unwrap_or(""), &self.workspace, self.restrict)?;
         info!(path = %path.display(), "read_file");
         let ext = path
@@ -105,29 +203,35 @@ impl DynTool for ReadFileTool {
             .unwrap_or_default();
         if is_likely_binary_extension(&ext) {
             let meta = tokio::fs::metadata(&path).await?;
-            let size = meta.len();
-            let mime_hint = match ext.to_lowercase().as_str() {
-                "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" | "ico" => "image",
-                "pdf" => "document (PDF)",
-                "mp3" | "wav" | "ogg" => "audio",
-                "mp4" | "webm" | "avi" | "mov" => "video",
-                "zip" | "rar" | "7z" | "tar" | "gz" => "archive",
-                _ => "binary",
-            };
-            return Ok(format!(
-                "Binary file ({}), size: {} bytes. Content is not text; use get_file_info for metadata.",
-                mime_hint, size
-            ));
+            return Ok(binary_file_summary(&ext, meta.len()));
         }
         Ok(tokio::fs::read_to_string(&path).await?)
     }
 }
 
+/// read_file's answer for a binary file of `size` bytes with extension `ext`.
+fn binary_file_summary(ext: &str, size: u64) -> String {
+    let mime_hint = match ext.to_lowercase().as_str() {
+        "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" | "ico" => "image",
+        "pdf" => "document (PDF)",
+        "mp3" | "wav" | "ogg" => "audio",
+        "mp4" | "webm" | "avi" | "mov" => "video",
+        "zip" | "rar" | "7z" | "tar" | "gz" => "archive",
+        _ => "binary",
+    };
+    format!(
+        "Binary file ({}), size: {} bytes. Content is not text; use get_file_info for metadata.",
+        mime_hint, size
+    )
+}
+
 // ---- WriteFile ----
 
 pub struct WriteFileTool {
     pub workspace: PathBuf,
     pub restrict: bool,
+    /// Write inside this tool sandbox when it is a Docker backend
+    pub sandbox_context: SandboxContext,
 }
 
 #[async_trait::async_trait]
@@ -138,6 +242,24 @@ impl DynTool for WriteFileTool {
         json!({"type":"object","properties":{"path":{"type":"string"},"content":{"type":"string"}},"required":["path","content"]})
     }
     async fn call(&self, args: Value) -> anyhow::Result<String> {
+        if let Some(sandbox) = container_sandbox(&self.sandbox_context) {
+            let path = resolve_sandbox_path(
+                sandbox,
+                args["path"].as_str().unwrap_or(""),
+                &self.workspace,
+                self.restrict,
+            )?;
+            let content = args["content"].as_str().unwrap_or("");
+            info!(path = %path, len = content.len(), "write_file (tool sandbox)");
+            sandbox_sh(sandbox, &args, r#"mkdir -p "$(dirname "$1")" && : > "$1""#, &[&path]).await?;
+            // Chunk lengths are multiples of 4, so every chunk decodes on its own.
+            let encoded = B64.encode(content);
+            for chunk in encoded.as_bytes().chunks(SANDBOX_WRITE_CHUNK) {
+                let chunk = std::str::from_utf8(chunk)?;
+                sandbox_sh(sandbox, &args, r#"printf %s "$2" | base64 -d >> "$1""#, &[&path, chunk]).await?;
+            }
+            return Ok(format!("Wrote {} bytes to {}", content.len(), path));
+        }
         let path = resolve_path(args["path"].as_str().unwrap_or(""), &self.workspace, self.restrict)?;
         let content = args["content"].as_str().unwrap_or("");
         info!(path = %path.display(), len = content.len(), "write_file");
@@ -156,11 +278,152 @@ pub struct EditFileTool {
     pub restrict: bool,
 }
 
+/// One replacement. With `before` / `after` context (or from a diff hunk) the match must be
+/// unique; a plain `old` replaces its first occurrence. Diff hunks also match whole lines only.
+struct TextEdit {
+    before: String,
+    old: String,
+    new: String,
+    after: String,
+    verified: bool,
+    whole_lines: bool,
+}
+
+impl TextEdit {
+    fn from_args(e: &Value) -> Self {
+        let field = |k: &str| e[k].as_str().unwrap_or("").to_string();
+        let (before, after) = (field("before"), field("after"));
+        Self {
+            verified: !before.is_empty() || !after.is_empty(),
+            whole_lines: false,
+            before,
+            old: field("old_text"),
+            new: field("new_text"),
+            after,
+        }
+    }
+}
+
+/// Byte offset of the only occurrence of `needle` in `haystack`. With `whole_lines`, only
+/// occurrences that start at a line start and end at a line end count.
+fn unique_match(haystack: &str, needle: &str, whole_lines: bool) -> Result<usize, String> {
+    let mut found = haystack.match_indices(needle).map(|(i, _)| i).filter(|&i| {
+        !whole_lines
+            || ((i == 0 || haystack[..i].ends_with('\n'))
+                && matches!(haystack[i + needle.len()..].chars().next(), None | Some('\n' | '\r')))
+    });
+    match (found.next(), found.next()) {
+        (Some(i), None) => Ok(i),
+        (None, _) => Err("context not found".to_string()),
+        (Some(_), Some(_)) => Err(format!(
+            "context is ambiguous: it matches {} places; include more surrounding lines",
+            2 + found.count()
+        )),
+    }
+}
+
+/// Whether `lines[i]` starts a file's header: `diff --git`, `Index:`, or a `---` / `+++` pair
+/// followed by a hunk (so removed and added lines that happen to start that way are not taken).
+fn is_file_header(lines: &[&str], i: usize) -> bool {
+    let line = lines[i];
+    line.starts_with("diff --git ")
+        || line.starts_with("Index: ")
+        || (line.starts_with("--- ")
+            && lines.get(i + 1).is_some_and(|l| l.starts_with("+++ "))
+            && lines.get(i + 2).is_none_or(|l| l.starts_with("@@")))
+}
+
+/// Turn unified-diff hunks into edits: context and `-` lines must match whole lines, context and
+/// `+` lines replace them. File headers before the first hunk are skipped; a diff that goes on
+/// to another file is rejected, so its hunks never land in this one.
+fn parse_unified_diff(diff: &str, line_ending: &str) -> anyhow::Result<Vec<TextEdit>> {
+    let lines: Vec<&str> = diff
+        .lines()
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+    let mut hunks: Vec<(Vec<&str>, Vec<&str>)> = Vec::new();
+    for (i, &line) in lines.iter().enumerate() {
+        if is_file_header(&lines, i) {
+            if !hunks.is_empty() {
+                anyhow::bail!(
+                    "diff continues with another file at line {}; send one file's hunks per edit",
+                    i + 1
+                );
+            }
+            continue;
+        }
+        if line.starts_with("@@") {
+            hunks.push((Vec::new(), Vec::new()));
+            continue;
+        }
+        let Some((old, new)) = hunks.last_mut() else {
+            continue;
+        };
+        if let Some(rest) = line.strip_prefix('-') {
+            old.push(rest);
+        } else if let Some(rest) = line.strip_prefix('+') {
+            new.push(rest);
+        } else if line.starts_with('\\') {
+            // "\ No newline at end of file"
+        } else {
+            let rest = line.strip_prefix(' ').unwrap_or(line);
+            old.push(rest);
+            new.push(rest);
+        }
+    }
+    if hunks.is_empty() {
+        anyhow::bail!("diff has no hunks (expected lines starting with @@)");
+    }
+    hunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, (old, new))| {
+            if old.is_empty() {
+                anyhow::bail!("hunk #{} has no context or removed lines to locate it", i + 1);
+            }
+            Ok(TextEdit {
+                before: String::new(),
+                old: old.join(line_ending),
+                new: new.join(line_ending),
+                after: String::new(),
+                verified: true,
+                whole_lines: true,
+            })
+        })
+        .collect()
+}
+
+/// Apply `edits` in order to `content`; errors name the failing edit (or hunk).
+fn apply_text_edits(content: String, edits: &[TextEdit], label: &str, path: &Path) -> anyhow::Result<String> {
+    let mut result = content;
+    for (i, edit) in edits.iter().enumerate() {
+        if edit.verified {
+            let needle = format!("{}{}{}", edit.before, edit.old, edit.after);
+            let start = unique_match(&result, &needle, edit.whole_lines)
+                .map_err(|e| anyhow::anyhow!("{} #{}: {} in {}", label, i + 1, e, path.display()))?
+                + edit.before.len();
+            result.replace_range(start..start + edit.old.len(), &edit.new);
+            continue;
+        }
+        if !result.contains(edit.old.as_str()) {
+            anyhow::bail!(
+                "{} #{}: old_text not found in {} (after {} prior edit(s))",
+                label,
+                i + 1,
+                path.display(),
+                i
+            );
+        }
+        result = result.replacen(edit.old.as_str(), edit.new.as_str(), 1);
+    }
+    Ok(result)
+}
+
 #[async_trait::async_trait]
 impl DynTool for EditFileTool {
     fn name(&self) -> &str { "edit_file" }
     fn description(&self) -> &str {
-        "Edit a file by replacing specific text. Supports single edit (old_text/new_text) or batch edits (edits array). Batch edits are applied sequentially and are atomic — if any edit fails, all changes are rolled back."
+        "Edit a file by replacing specific text. Supports single edit (old_text/new_text), batch edits (edits array) or a unified diff (diff). Give before/after context around old_text, or use diff, to make sure the right occurrence is changed: the context must match exactly once or the edit is rejected. Edits are applied sequentially and are atomic — if any edit fails, all changes are rolled back."
     }
     fn parameters_schema(&self) -> Value {
         json!({
@@ -169,18 +432,23 @@ impl DynTool for EditFileTool {
                 "path": { "type": "string", "description": "File path to edit" },
                 "old_text": { "type": "string", "description": "Text to find (single edit mode)" },
                 "new_text": { "type": "string", "description": "Replacement text (single edit mode)" },
+                "before": { "type": "string", "description": "Text that must directly precede old_text (single edit mode)" },
+                "after": { "type": "string", "description": "Text that must directly follow old_text (single edit mode)" },
                 "edits": {
                     "type": "array",
-                    "description": "Batch edits: array of {old_text, new_text} pairs applied sequentially",
+                    "description": "Batch edits: array of {old_text, new_text, before?, after?} applied sequentially",
                     "items": {
                         "type": "object",
                         "properties": {
                             "old_text": { "type": "string" },
-                            "new_text": { "type": "string" }
+                            "new_text": { "type": "string" },
+                            "before": { "type": "string" },
+                            "after": { "type": "string" }
                         },
                         "required": ["old_text", "new_text"]
                     }
-                }
+                },
+                "diff": { "type": "string", "description": "Unified diff hunks (@@ headers, ' ' context, '-' removed, '+' added lines); each hunk must match exactly one place" }
             },
             "required": ["path"]
         })
@@ -190,19 +458,14 @@ impl DynTool for EditFileTool {
         info!(path = %path.display(), "edit_file");
         let content = tokio::fs::read_to_string(&path).await?;
 
-        // Build the list of edits: either from `edits` array or single old_text/new_text
-        let edits: Vec<(String, String)> = if let Some(arr) = args["edits"].as_array() {
-            arr.iter()
-                .map(|e| {
-                    let old = e["old_text"].as_str().unwrap_or("").to_string();
-                    let new = e["new_text"].as_str().unwrap_or("").to_string();
-                    (old, new)
-                })
-                .collect()
+        // Build the list of edits: from `diff` hunks, the `edits` array or single old_text/new_text
+        let (edits, label) = if let Some(diff) = args["diff"].as_str() {
+            let line_ending = if content.contains("\r\n") { "\r\n" } else { "\n" };
+            (parse_unified_diff(diff, line_ending)?, "hunk")
+        } else if let Some(arr) = args["edits"].as_array() {
+            (arr.iter().map(TextEdit::from_args).collect(), "edit")
         } else {
-            let old = args["old_text"].as_str().unwrap_or("").to_string();
-            let new = args["new_text"].as_str().unwrap_or("").to_string();
-            vec![(old, new)]
+            (vec![TextEdit::from_args(&args)], "edit")
         };
 
         if edits.is_empty() {
@@ -210,18 +473,7 @@ impl DynTool for EditFileTool {
         }
 
         // Apply edits sequentially; bail on first failure (atomic: original file untouched)
-        let mut result = content;
-        for (i, (old, new)) in edits.iter().enumerate() {
-            if !result.contains(old.as_str()) {
-                anyhow::bail!(
-                    "edit #{}: old_text not found in {} (after {} prior edit(s))",
-                    i + 1,
-                    path.display(),
-                    i
-                );
-            }
-            result = result.replacen(old.as_str(), new.as_str(), 1);
-        }
+        let result = apply_text_edits(content, &edits, label, &path)?;
 
         tokio::fs::write(&path, &result).await?;
         let count = edits.len();
@@ -233,11 +485,210 @@ impl DynTool for EditFileTool {
     }
 }
 
+// ---- EditJson ----
+
+pub struct EditJsonTool {
+    pub workspace: PathBuf,
+    pub restrict: bool,
+}
+
+const JSON_PATCH_OPS: &[&str] = &["add", "remove", "replace", "move", "copy", "test"];
+
+/// Check that `patch` is an RFC 6902 operation list before anything is applied.
+fn validate_json_patch(patch: &Value) -> anyhow::Result<&Vec<Value>> {
+    let ops = patch
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("patch must be an array of operations"))?;
+    if ops.is_empty() {
+        anyhow::bail!("patch has no operations");
+    }
+    for (i, op) in ops.iter().enumerate() {
+        let name = op["op"].as_str().unwrap_or("");
+        if !JSON_PATCH_OPS.contains(&name) {
+            anyhow::bail!("operation #{}: op must be one of {}", i + 1, JSON_PATCH_OPS.join(", "));
+        }
+        let pointers: &[&str] = match name {
+            "move" | "copy" => &["path", "from"],
+            _ => &["path"],
+        };
+        for field in pointers {
+            match op[*field].as_str() {
+                Some(p) if p.is_empty() || p.starts_with('/') => {}
+                Some(p) => anyhow::bail!("operation #{}: {} must be a JSON Pointer starting with '/': {}", i + 1, field, p),
+                None => anyhow::bail!("operation #{} ({}): missing {}", i + 1, name, field),
+            }
+        }
+        if matches!(name, "add" | "replace" | "test") && op.get("value").is_none() {
+            anyhow::bail!("operation #{} ({}): missing value", i + 1, name);
+        }
+    }
+    Ok(ops)
+}
+
+/// Parent pointer and unescaped last reference token of a non-empty JSON Pointer.
+fn split_json_pointer(pointer: &str) -> (&str, String) {
+    let slash = pointer.rfind('/').unwrap_or(0);
+    let token = pointer[slash + 1..].replace("~1", "/").replace("~0", "~");
+    (&pointer[..slash], token)
+}
+
+/// Array index token: digits without leading zeros, below `bound`.
+fn json_array_index(token: &str, bound: usize, pointer: &str) -> anyhow::Result<usize> {
+    let valid = !token.is_empty()
+        && token.bytes().all(|b| b.is_ascii_digit())
+        && (token == "0" || !token.starts_with('0'));
+    match token.parse::<usize>() {
+        Ok(i) if valid && i < bound => Ok(i),
+        _ => anyhow::bail!("{}: array index out of range or invalid", pointer),
+    }
+}
+
+fn json_patch_add(doc: &mut Value, pointer: &str, value: Value) -> anyhow::Result<()> {
+    if pointer.is_empty() {
+        *doc = value;
+        return Ok(());
+    }
+    let (parent, token) = split_json_pointer(pointer);
+    match doc.pointer_mut(parent) {
+        Some(Value::Object(map)) => {
+            map.insert(token, value);
+        }
+        Some(Value::Array(arr)) => {
+            let i = if token == "-" { arr.len() } else { json_array_index(&token, arr.len() + 1, pointer)? };
+            arr.insert(i, value);
+        }
+        Some(_) => anyhow::bail!("{}: parent is not an object or array", pointer),
+        None => anyhow::bail!("{}: parent does not exist", pointer),
+    }
+    Ok(())
+}
+
+fn json_patch_remove(doc: &mut Value, pointer: &str) -> anyhow::Result<Value> {
+    if pointer.is_empty() {
+        anyhow::bail!("cannot remove the whole document");
+    }
+    let (parent, token) = split_json_pointer(pointer);
+    match doc.pointer_mut(parent) {
+        Some(Value::Object(map)) => map
+            .shift_remove(&token)
+            .ok_or_else(|| anyhow::anyhow!("{}: no such member", pointer)),
+        Some(Value::Array(arr)) => {
+            let i = json_array_index(&token, arr.len(), pointer)?;
+            Ok(arr.remove(i))
+        }
+        _ => anyhow::bail!("{}: path does not exist", pointer),
+    }
+}
+
+/// Apply validated RFC 6902 operations to `doc` in order.
+fn apply_json_patch(doc: &mut Value, ops: &[Value]) -> anyhow::Result<()> {
+    for (i, op) in ops.iter().enumerate() {
+        let path = op["path"].as_str().unwrap_or("");
+        let from = op["from"].as_str().unwrap_or("");
+        let result = match op["op"].as_str().unwrap_or("") {
+            "add" => json_patch_add(doc, path, op["value"].clone()),
+            "remove" => json_patch_remove(doc, path).map(|_| ()),
+            "replace" => match doc.pointer_mut(path) {
+                Some(target) => {
+                    *target = op["value"].clone();
+                    Ok(())
+                }
+                None => Err(anyhow::anyhow!("{}: path does not exist", path)),
+            },
+            "move" if path.starts_with(&format!("{}/", from)) => {
+                Err(anyhow::anyhow!("cannot move {} into its own child {}", from, path))
+            }
+            "move" => json_patch_remove(doc, from).and_then(|v| json_patch_add(doc, path, v)),
+            "copy" => match doc.pointer(from).cloned() {
+                Some(v) => json_patch_add(doc, path, v),
+                None => Err(anyhow::anyhow!("{}: path does not exist", from)),
+            },
+            "test" if doc.pointer(path) == Some(&op["value"]) => Ok(()),
+            "test" => Err(anyhow::anyhow!("test failed: {} does not equal {}", path, op["value"])),
+            other => Err(anyhow::anyhow!("unsupported op {}", other)),
+        };
+        result.map_err(|e| anyhow::anyhow!("operation #{}: {}", i + 1, e))?;
+    }
+    Ok(())
+}
+
+/// Serialize `doc` in the layout of `original`: compact or indented with the same unit,
+/// same line endings, trailing newline kept. Object keys keep their original order.
+fn format_json_like(doc: &Value, original: &str) -> anyhow::Result<String> {
+    let indent = original
+        .lines()
+        .skip(1)
+        .find(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start().len()]);
+    let mut out = match indent {
+        Some(unit) if !unit.is_empty() => {
+            let mut buf = Vec::new();
+            let formatter = serde_json::ser::PrettyFormatter::with_indent(unit.as_bytes());
+            let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
+            serde::Serialize::serialize(doc, &mut ser)?;
+            String::from_utf8(buf)?
+        }
+        _ if original.trim().contains('\n') => serde_json::to_string_pretty(doc)?,
+        _ => serde_json::to_string(doc)?,
+    };
+    if original.ends_with('\n') {
+        out.push('\n');
+    }
+    if original.contains("\r\n") {
+        out = out.replace('\n', "\r\n");
+    }
+    Ok(out)
+}
+
+#[async_trait::async_trait]
+impl DynTool for EditJsonTool {
+    fn name(&self) -> &str { "edit_json" }
+    fn description(&self) -> &str {
+        "Edit a JSON file with an RFC 6902 JSON Patch: operations add, remove, replace, move, copy and test, addressed by JSON Pointer (e.g. /dependencies/serde, /items/0, /items/- to append). The whole patch is validated and applied in memory first; if any operation fails the file is left untouched. Indentation, line endings and key order are kept."
+    }
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string", "description": "JSON file to edit" },
+                "patch": {
+                    "type": "array",
+                    "description": "JSON Patch operations applied in order",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "op": { "type": "string", "enum": JSON_PATCH_OPS },
+                            "path": { "type": "string", "description": "JSON Pointer of the target" },
+                            "from": { "type": "string", "description": "JSON Pointer of the source (move, copy)" },
+                            "value": { "description": "Value for add, replace and test" }
+                        },
+                        "required": ["op", "path"]
+                    }
+                }
+            },
+            "required": ["path", "patch"]
+        })
+    }
+    async fn call(&self, args: Value) -> anyhow::Result<String> {
+        let path = resolve_path(args["path"].as_str().unwrap_or(""), &self.workspace, self.restrict)?;
+        info!(path = %path.display(), "edit_json");
+        let ops = validate_json_patch(&args["patch"])?;
+        let original = tokio::fs::read_to_string(&path).await?;
+        let mut doc: Value = serde_json::from_str(&original)
+            .map_err(|e| anyhow::anyhow!("{} is not valid JSON: {}", path.display(), e))?;
+        apply_json_patch(&mut doc, ops)?;
+        tokio::fs::write(&path, format_json_like(&doc, &original)?).await?;
+        Ok(format!("Applied {} JSON Patch operation(s) to {}", ops.len(), path.display()))
+    }
+}
+
 // ---- ListDir ----
 
 pub struct ListDirTool {
     pub workspace: PathBuf,
     pub restrict: bool,
+    /// List inside this tool sandbox when it is a Docker backend
+    pub sandbox_context: SandboxContext,
 }
 
 #[async_trait::async_trait]
@@ -250,6 +701,26 @@ impl DynTool for ListDirTool {
         json!({"type":"object","properties":{"path":{"type":"string","description":"Directory path (default '.' for current workspace)"}},"required":["path"]})
     }
     async fn call(&self, args: Value) -> anyhow::Result<String> {
+        if let Some(sandbox) = container_sandbox(&self.sandbox_context) {
+            let path = resolve_sandbox_path(
+                sandbox,
+                args["path"].as_str().unwrap_or("."),
+                &self.workspace,
+                self.restrict,
+            )?;
+            info!(path = %path, "list_dir (tool sandbox)");
+            // -p marks directories with a trailing slash.
+            let out = sandbox_sh(sandbox, &args, r#"ls -1Ap -- "$1""#, &[&path]).await?;
+            let mut dirs = Vec::new();
+            let mut files = Vec::new();
+            for line in String::from_utf8_lossy(&out).lines().filter(|l| !l.is_empty()) {
+                match line.strip_suffix('/') {
+                    Some(dir) => dirs.push(dir.to_string()),
+                    None => files.push(line.to_string()),
+                }
+            }
+            return Ok(format_dir_listing(dirs, files));
+        }
         let path = resolve_path(args["path"].as_str().unwrap_or("."), &self.workspace, self.restrict)?;
         info!(path = %path.display(), "list_dir");
         let mut entries = tokio::fs::read_dir(&path).await?;
@@ -264,26 +735,31 @@ impl DynTool for ListDirTool {
                 files.push(name);
             }
         }
-        dirs.sort();
-        files.sort();
-        let mut out = Vec::new();
-        if !dirs.is_empty() {
-            out.push("Directories:".to_string());
-            for d in &dirs {
-                out.push(format!("  {} (dir)", d));
-            }
-        }
-        if !files.is_empty() {
-            out.push("Files:".to_string());
-            for f in &files {
-                out.push(format!("  {}", f));
-            }
+        Ok(format_dir_listing(dirs, files))
+    }
+}
+
+/// list_dir's answer: directories, then files, each sorted.
+fn format_dir_listing(mut dirs: Vec<String>, mut files: Vec<String>) -> String {
+    dirs.sort();
+    files.sort();
+    let mut out = Vec::new();
+    if !dirs.is_empty() {
+        out.push("Directories:".to_string());
+        for d in &dirs {
+            out.push(format!("  {} (dir)", d));
         }
-        if out.is_empty() {
-            out.push("(empty directory)".to_string());
+    }
+    if !files.is_empty() {
+        out.push("Files:".to_string());
+        for f in &files {
+            out.push(format!("  {}", f));
         }
-        Ok(out.join("\n"))
     }
+    if out.is_empty() {
+        out.push("(empty directory)".to_string());
+    }
+    out.join("\n")
 }
 
 // ---- ReadMultipleFiles ----
@@ -653,3 +1129,304 @@ impl DynTool for GetFileInfoTool {
         ))
     }
 }
+
+// ---- WatchFile ----
+
+/// Watch length used when `timeout_secs` is not given.
+const WATCH_FILE_DEFAULT_SECS: u64 = 30;
+/// Longest watch one call may request.
+const WATCH_FILE_MAX_SECS: u64 = 300;
+const WATCH_FILE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Characters of new output returned at most (the newest are kept).
+const WATCH_FILE_MAX_CHARS: usize = 20_000;
+
+pub struct WatchFileTool {
+    pub workspace: PathBuf,
+    pub restrict: bool,
+}
+
+async fn file_len(path: &Path) -> u64 {
+    tokio::fs::metadata(path).await.map(|m| m.len()).unwrap_or(0)
+}
+
+async fn read_from(path: &Path, offset: u64) -> anyhow::Result<Vec<u8>> {
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+    let mut file = tokio::fs::File::open(path).await?;
+    file.seek(std::io::SeekFrom::Start(offset)).await?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf).await?;
+    Ok(buf)
+}
+
+#[async_trait::async_trait]
+impl DynTool for WatchFileTool {
+    fn name(&self) -> &str { "watch_file" }
+    fn description(&self) -> &str {
+        "Watch a file like `tail -f` and return the lines appended to it. Returns as soon as new lines appear, or after timeout_secs (default 30, max 300) if nothing changed. A file that does not exist yet is watched until it is created. Path must be within workspace."
+    }
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string", "description": "Path to the file to watch" },
+                "timeout_secs": {
+                    "type": "integer",
+                    "description": "Longest time to wait for new lines, in seconds (default 30, max 300)"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+    async fn call(&self, args: Value) -> anyhow::Result<String> {
+        let path = resolve_path(args["path"].as_str().unwrap_or(""), &self.workspace, self.restrict)?;
+        let secs = args["timeout_secs"]
+            .as_u64()
+            .unwrap_or(WATCH_FILE_DEFAULT_SECS)
+            .clamp(1, WATCH_FILE_MAX_SECS);
+        info!(path = %path.display(), secs, "watch_file");
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(secs);
+        let mut offset = file_len(&path).await;
+        let mut new_bytes: Vec<u8> = Vec::new();
+        let mut truncated = false;
+        while !new_bytes.contains(&b'\n') {
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                break;
+            }
+            context::cancellable(tokio::time::sleep(WATCH_FILE_POLL_INTERVAL.min(deadline - now))).await?;
+            let len = file_len(&path).await;
+            if len < offset {
+                // Rotated or truncated: what is there now is all new.
+                offset = 0;
+                new_bytes.clear();
+                truncated = true;
+            }
+            if len > offset {
+                let chunk = read_from(&path, offset).await?;
+                offset += chunk.len() as u64;
+                new_bytes.extend_from_slice(&chunk);
+            }
+        }
+
+        if new_bytes.is_empty() {
+            return Ok(format!("No new lines in {} after {}s.", path.display(), secs));
+        }
+        let text = String::from_utf8_lossy(&new_bytes);
+        let char_count = text.chars().count();
+        let mut out = String::new();
+        if truncated {
+            out.push_str("(file was truncated or replaced; showing it from the start)\n");
+        }
+        if char_count > WATCH_FILE_MAX_CHARS {
+            out.push_str("...[earlier output omitted]\n");
+            out.extend(text.chars().skip(char_count - WATCH_FILE_MAX_CHARS));
+        } else {
+            out.push_str(&text);
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[tokio::test]
+    async fn watch_file_returns_appended_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "old line\n").unwrap();
+        let tool = WatchFileTool { workspace: dir.path().to_path_buf(), restrict: true };
+
+        let append_to = path.clone();
+        let writer = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(400)).await;
+            let mut file = std::fs::OpenOptions::new().append(true).open(&append_to).unwrap();
+            file.write_all(b"new line 1\nnew line 2\n").unwrap();
+        });
+        let out = tool
+            .call(json!({ "path": path.to_string_lossy(), "timeout_secs": 10 }))
+            .await
+            .unwrap();
+        writer.await.unwrap();
+        assert_eq!(out, "new line 1\nnew line 2\n");
+    }
+
+    #[tokio::test]
+    async fn watch_file_stops_when_turn_is_cancelled() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("quiet.log");
+        std::fs::write(&path, "").unwrap();
+        let tool = WatchFileTool { workspace: dir.path().to_path_buf(), restrict: true };
+        let token = tokio_util::sync::CancellationToken::new();
+        let ctx = context::ToolContext::new("main", dir.path().to_path_buf()).with_cancel(token.clone());
+
+        let cancel = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            token.cancel();
+        });
+        let started = std::time::Instant::now();
+        let err = ctx
+            .scope(tool.call(json!({ "path": "quiet.log", "timeout_secs": 60 })))
+            .await
+            .unwrap_err();
+        cancel.await.unwrap();
+        assert!(matches!(err.downcast_ref::<crate::tools::ToolError>(), Some(crate::tools::ToolError::Cancelled)));
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    fn json_file(dir: &Path, content: &str) -> (EditJsonTool, String) {
+        let path = dir.join("config.json");
+        std::fs::write(&path, content).unwrap();
+        let tool = EditJsonTool { workspace: dir.to_path_buf(), restrict: true };
+        (tool, path.to_string_lossy().into_owned())
+    }
+
+    #[tokio::test]
+    async fn edit_json_applies_add_replace_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = "{\n    \"name\": \"app\",\n    \"tags\": [\"a\", \"c\"],\n    \"debug\": true\n}\n";
+        let (tool, path) = json_file(dir.path(), original);
+        let patch = json!([
+            { "op": "replace", "path": "/name", "value": "synbot" },
+            { "op": "add", "path": "/tags/1", "value": "b" },
+            { "op": "add", "path": "/tags/-", "value": "d" },
+            { "op": "add", "path": "/a~1b", "value": 1 },
+            { "op": "remove", "path": "/debug" }
+        ]);
+        tool.call(json!({ "path": path, "patch": patch })).await.unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        let doc: Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(doc, json!({ "name": "synbot", "tags": ["a", "b", "c", "d"], "a/b": 1 }));
+        assert!(written.contains("\n    \"name\""), "indent kept: {}", written);
+        assert!(written.ends_with("}\n"));
+    }
+
+    #[tokio::test]
+    async fn edit_json_keeps_key_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = "{\"zeta\":1,\"alpha\":2,\"mid\":3,\"beta\":4}";
+        let (tool, path) = json_file(dir.path(), original);
+        let patch = json!([
+            { "op": "replace", "path": "/alpha", "value": 20 },
+            { "op": "remove", "path": "/zeta" },
+            { "op": "add", "path": "/aaa", "value": 5 }
+        ]);
+        tool.call(json!({ "path": path, "patch": patch })).await.unwrap();
+        // Existing keys stay where they were, removals do not reorder, new keys go last.
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "{\"alpha\":20,\"mid\":3,\"beta\":4,\"aaa\":5}"
+        );
+    }
+
+    #[tokio::test]
+    async fn failing_json_patch_leaves_file_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = "{\"name\":\"app\",\"tags\":[\"a\"]}";
+        let (tool, path) = json_file(dir.path(), original);
+        let patch = json!([
+            { "op": "replace", "path": "/name", "value": "changed" },
+            { "op": "remove", "path": "/missing" }
+        ]);
+        let err = tool.call(json!({ "path": path, "patch": patch })).await.unwrap_err();
+        assert!(err.to_string().contains("operation #2"), "{}", err);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+
+        for bad in [json!({ "op": "add" }), json!([{ "op": "frobnicate", "path": "/x" }]), json!([{ "op": "add", "path": "/x" }])] {
+            assert!(tool.call(json!({ "path": path, "patch": bad })).await.is_err());
+        }
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+    }
+
+    fn edit_tool(dir: &Path) -> EditFileTool {
+        EditFileTool { workspace: dir.to_path_buf(), restrict: true }
+    }
+
+    const REPEATED: &str = "fn a() {\n    let x = 1;\n}\n\nfn b() {\n    let x = 1;\n}\n";
+
+    #[tokio::test]
+    async fn contextual_edit_changes_only_the_matching_occurrence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lib.rs");
+        std::fs::write(&path, REPEATED).unwrap();
+        let tool = edit_tool(dir.path());
+
+        tool.call(json!({
+            "path": "lib.rs",
+            "before": "fn b() {\n",
+            "old_text": "    let x = 1;",
+            "new_text": "    let x = 2;"
+        }))
+        .await
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "fn a() {\n    let x = 1;\n}\n\nfn b() {\n    let x = 2;\n}\n"
+        );
+
+        let diff = "--- a/lib.rs\n+++ b/lib.rs\n@@ -1,3 +1,3 @@\n fn a() {\n-    let x = 1;\n+    let x = 3;\n }\n";
+        tool.call(json!({ "path": "lib.rs", "diff": diff })).await.unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().starts_with("fn a() {\n    let x = 3;\n}"));
+    }
+
+    #[tokio::test]
+    async fn contextual_edit_rejects_ambiguous_and_missing_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lib.rs");
+        std::fs::write(&path, REPEATED).unwrap();
+        let tool = edit_tool(dir.path());
+
+        let err = tool
+            .call(json!({ "path": "lib.rs", "after": "\n}", "old_text": "    let x = 1;", "new_text": "" }))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("ambiguous"), "{}", err);
+
+        let diff = "@@ -1,3 +1,3 @@\n fn c() {\n-    let x = 1;\n+    let x = 2;\n";
+        let err = tool.call(json!({ "path": "lib.rs", "diff": diff })).await.unwrap_err();
+        assert!(err.to_string().contains("hunk #1: context not found"), "{}", err);
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), REPEATED);
+    }
+
+    #[tokio::test]
+    async fn diff_hunks_match_whole_lines_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lib.rs");
+        std::fs::write(&path, "let max = 10;\nlet x = 1;\n").unwrap();
+        let tool = edit_tool(dir.path());
+
+        // "x = 1;" is inside the second line, not a line of its own
+        let diff = "@@ -2 +2 @@\n-x = 1;\n+x = 2;\n";
+        let err = tool.call(json!({ "path": "lib.rs", "diff": diff })).await.unwrap_err();
+        assert!(err.to_string().contains("context not found"), "{}", err);
+
+        let diff = "@@ -2 +2 @@\n-let x = 1;\n+let x = 2;\n";
+        tool.call(json!({ "path": "lib.rs", "diff": diff })).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "let max = 10;\nlet x = 2;\n");
+    }
+
+    #[tokio::test]
+    async fn multi_file_diff_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lib.rs");
+        std::fs::write(&path, REPEATED).unwrap();
+        let tool = edit_tool(dir.path());
+
+        let diff = "--- a/lib.rs\n+++ b/lib.rs\n@@ -1,3 +1,3 @@\n fn a() {\n-    let x = 1;\n+    let x = 3;\n }\n\
+                    diff --git a/main.rs b/main.rs\n--- a/main.rs\n+++ b/main.rs\n@@ -1 +1 @@\n-fn main() {}\n+fn main() { run() }\n";
+        let err = tool.call(json!({ "path": "lib.rs", "diff": diff })).await.unwrap_err();
+        assert!(err.to_string().contains("another file"), "{}", err);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), REPEATED);
+
+        // A removed line that starts with "-- " is not taken for a header
+        std::fs::write(&path, "-- note\nbody\n").unwrap();
+        let diff = "@@ -1,2 +1,2 @@\n--- note\n+++ remark\n body\n";
+        tool.call(json!({ "path": "lib.rs", "diff": diff })).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "++ remark\nbody\n");
+    }
+}
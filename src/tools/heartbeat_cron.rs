@@ -152,6 +152,9 @@ impl DynTool for AddHeartbeatTaskTool {
             chat_id,
             user_id,
             target: target.clone(),
+            kind: Default::default(),
+            on_error: Default::default(),
+            role: None,
         };
         let mut cfg = config.write().await;
         cfg.heartbeat.tasks.push(task);
@@ -340,6 +343,9 @@ impl DynTool for AddCronTaskTool {
             channel,
             user_id,
             chat_id: Some(chat_id),
+            misfire_policy: Default::default(),
+            overlap_policy: Default::default(),
+            role: None,
         };
         let mut cfg = config.write().await;
         cfg.cron.tasks.push(task);
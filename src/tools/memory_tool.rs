@@ -99,6 +99,22 @@ impl DynTool for RememberTool {
             return Ok("No content to remember. Please provide 'content' with the fact to save.".to_string());
         }
         let daily = args["daily"].as_bool().unwrap_or(false);
+        #[cfg(feature = "memory-index")]
+        {
+            use crate::agent::memory_backend::{is_sqlite_backend, MemoryBackend, SqliteMemoryBackend};
+            let cfg = self.shared_config.read().await.clone();
+            if is_sqlite_backend(&cfg.memory) {
+                let backend = SqliteMemoryBackend::new(Arc::new(cfg));
+                let today = Local::now().date_naive();
+                return if daily {
+                    backend.append_daily_note(&agent_id, today, content)?;
+                    Ok(format!("Written to today's note ({}): {}", today.format("%Y-%m-%d"), content))
+                } else {
+                    backend.append_long_term(&agent_id, content)?;
+                    Ok(format!("Written to long-term memory: {}", content))
+                };
+            }
+        }
         if daily {
             Self::append_daily_note_for(&agent_id, content)?;
             let today = Local::now().format("%Y-%m-%d");
@@ -229,7 +245,7 @@ impl DynTool for SearchMemoryTool {
     }
 
     fn description(&self) -> &str {
-        "Search this agent's long-term and daily memory using keyword + vector index (SQLite). Use 'query' for natural language or keywords; optional 'limit' (default 5, max 20)."
+        "Search this agent's long-term and daily memory using keyword + vector index (SQLite). Use 'query' for natural language or keywords; optional 'limit' (default 5, max 20). Each result shows its relevance score, the full chunk, an excerpt with the query terms in **bold**, and the byte offsets of the terms in the chunk."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -244,7 +260,8 @@ impl DynTool for SearchMemoryTool {
     }
 
     async fn call(&self, args: Value) -> Result<String> {
-        use crate::agent::memory_backend::{FileSqliteMemoryBackend, MemoryBackend};
+        use crate::agent::memory_backend::memory_backend_for;
+        use crate::agent::memory_index::{match_ranges, snippet, SNIPPET_CHARS};
 
         let agent_id = context::current_agent_id().unwrap_or_else(|| self.agent_id.clone());
         let q = args["query"].as_str().unwrap_or("").trim();
@@ -253,7 +270,7 @@ impl DynTool for SearchMemoryTool {
         }
         let limit = args["limit"].as_u64().unwrap_or(5).clamp(1, 20) as usize;
         let cfg = self.config.read().await.clone();
-        let backend = FileSqliteMemoryBackend::new(Arc::new(cfg));
+        let backend = memory_backend_for(Arc::new(cfg));
         let hits = backend.search(&agent_id, q, limit)?;
         if hits.is_empty() {
             return Ok("No matching memory chunks (index may be empty; use remember or wait for auto_index).".to_string());
@@ -262,7 +279,20 @@ impl DynTool for SearchMemoryTool {
             .into_iter()
             .map(|c| {
                 let score = c.score.map(|s| format!(" (score {:.3})", s)).unwrap_or_default();
-                format!("- [{}]{} {}", c.source, score, c.content)
+                let excerpt = snippet(&c.content, q, SNIPPET_CHARS).highlighted();
+                let offsets: Vec<String> = match_ranges(&c.content, q)
+                    .into_iter()
+                    .map(|m| format!("{}..{}", m.start, m.end))
+                    .collect();
+                let offsets = if offsets.is_empty() {
+                    "none (semantic match)".to_string()
+                } else {
+                    offsets.join(", ")
+                };
+                format!(
+                    "- [{}]{} {}\n  Excerpt: {}\n  Matches (byte offsets): {}",
+                    c.source, score, c.content, excerpt, offsets
+                )
             })
             .collect();
         Ok(lines.join("\n\n"))
@@ -18,10 +18,12 @@
 pub mod approval;
 pub mod approval_store;
 pub mod approval_tool;
+pub mod audit;
 pub mod browser;
 pub mod code_analyzer;
 pub mod context;
 pub mod diff_viewer;
+pub mod error;
 pub mod filesystem;
 pub mod generation;
 pub mod heartbeat_cron;
@@ -43,10 +45,11 @@ use anyhow::Result;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use tracing::{info, debug};
 
 pub use context::{scope, ToolContext};
+pub use error::ToolError;
 
 /// Context passed when building or extending the tool registry (e.g. for plugins).
 /// Holds references to config, workspace, approval manager, session state, and other
@@ -70,7 +73,7 @@ fn sanitize_args_for_log(tool_name: &str, args: &Value) -> String {
         None => return "args=?".to_string(),
     };
     let part = match tool_name {
-        "read_file" | "write_file" | "edit_file" | "list_dir" | "get_file_info" | "create_dir" => obj
+        "read_file" | "write_file" | "edit_file" | "list_dir" | "get_file_info" | "create_dir" | "watch_file" | "edit_json" => obj
             .get("path")
             .and_then(|v| v.as_str())
             .map(|s| format!("path={}", truncate_for_log(s, 120))),
@@ -123,15 +126,7 @@ fn sanitize_args_for_log(tool_name: &str, args: &Value) -> String {
 }
 
 fn truncate_for_log(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        let mut end = max_len.min(s.len());
-        while end > 0 && !s.is_char_boundary(end) {
-            end -= 1;
-        }
-        format!("{}... ({} chars)", &s[..end], s.len())
-    }
+    truncation::truncate_bytes_with(s, max_len, &truncation::TruncationMarker::LOG)
 }
 
 /// A type-erased tool that can be stored in the registry.
@@ -141,6 +136,12 @@ pub trait DynTool: Send + Sync {
     fn description(&self) -> &str;
     fn parameters_schema(&self) -> Value;
     async fn call(&self, args: Value) -> Result<String>;
+
+    /// Short, non-sensitive summary of `args` for the `tool_execution` log span.
+    /// Return `None` (the default) to use the built-in per-tool logic.
+    fn sanitize_args_for_log(&self, _args: &Value) -> Option<String> {
+        None
+    }
 }
 
 /// Metadata about a registered tool, returned by `list_tools`.
@@ -152,37 +153,85 @@ pub struct ToolInfo {
 }
 
 /// Registry that holds all available tools.
+///
+/// The tool map sits behind a lock so a shared (`Arc`) registry can be swapped wholesale with
+/// [`ToolRegistry::replace_all`] during hot reload; readers always see either the old or the new set.
 pub struct ToolRegistry {
-    tools: HashMap<String, Arc<dyn DynTool>>,
+    tools: RwLock<HashMap<String, Arc<dyn DynTool>>>,
+    /// Check args against each tool's `parameters_schema` before calling it (`tools.validateArgs`).
+    validate_args: bool,
+    /// Longest description [`ToolRegistry::validate`] accepts without a warning; 0 = no limit.
+    max_description_chars: usize,
 }
 
 
 impl ToolRegistry {
     pub fn new() -> Self {
         Self {
-            tools: HashMap::new(),
+            tools: RwLock::new(HashMap::new()),
+            validate_args: true,
+            max_description_chars: DEFAULT_MAX_TOOL_DESCRIPTION_CHARS,
         }
     }
 
+    fn read_tools(&self) -> std::sync::RwLockReadGuard<'_, HashMap<String, Arc<dyn DynTool>>> {
+        self.tools.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn tools_mut(&mut self) -> &mut HashMap<String, Arc<dyn DynTool>> {
+        self.tools.get_mut().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Enable or disable JSON Schema validation of tool args in [`ToolRegistry::execute`].
+    pub fn set_validate_args(&mut self, enabled: bool) {
+        self.validate_args = enabled;
+    }
+
+    /// Description length above which [`ToolRegistry::validate`] reports a tool (`tools.maxDescriptionChars`; 0 = no limit).
+    pub fn set_max_description_chars(&mut self, limit: usize) {
+        self.max_description_chars = limit;
+    }
+
     /// Register a tool. Returns an error if a tool with the same name already exists.
     pub fn register(&mut self, tool: Arc<dyn DynTool>) -> Result<()> {
         let name = tool.name().to_string();
-        if self.tools.contains_key(&name) {
+        let tools = self.tools_mut();
+        if tools.contains_key(&name) {
             anyhow::bail!("Tool '{}' is already registered", name);
         }
-        self.tools.insert(name, tool);
+        tools.insert(name, tool);
+        Ok(())
+    }
+
+    /// Replace the whole tool set in one step. The new map is built first; if `tools` contains
+    /// duplicate names nothing is changed and an error is returned.
+    pub fn replace_all(&self, tools: Vec<Arc<dyn DynTool>>) -> Result<()> {
+        let mut next: HashMap<String, Arc<dyn DynTool>> = HashMap::with_capacity(tools.len());
+        for tool in tools {
+            let name = tool.name().to_string();
+            if next.contains_key(&name) {
+                anyhow::bail!("Duplicate tool name '{}' in replacement set", name);
+            }
+            next.insert(name, tool);
+        }
+        *self.tools.write().unwrap_or_else(|e| e.into_inner()) = next;
         Ok(())
     }
 
+    /// All currently registered tools (a point-in-time copy; e.g. to build a modified set for [`ToolRegistry::replace_all`]).
+    pub fn snapshot(&self) -> Vec<Arc<dyn DynTool>> {
+        self.read_tools().values().cloned().collect()
+    }
+
     /// Remove a tool by name. Returns `Ok(true)` if the tool was found and removed,
     /// `Ok(false)` if no tool with that name was registered.
     pub fn deregister(&mut self, name: &str) -> Result<bool> {
-        Ok(self.tools.remove(name).is_some())
+        Ok(self.tools_mut().remove(name).is_some())
     }
 
     /// Return metadata about all registered tools.
     pub fn list_tools(&self) -> Vec<ToolInfo> {
-        self.tools
+        self.read_tools()
             .values()
             .map(|t| ToolInfo {
                 name: t.name().to_string(),
@@ -192,20 +241,28 @@ impl ToolRegistry {
             .collect()
     }
 
-    pub fn get(&self, name: &str) -> Option<&Arc<dyn DynTool>> {
-        self.tools.get(name)
+    pub fn get(&self, name: &str) -> Option<Arc<dyn DynTool>> {
+        self.read_tools().get(name).cloned()
     }
 
     /// Execute a tool by name. If `message_ctx` is provided:
     /// - add_heartbeat_task / add_cron_task: channel, chat_id, user_id are injected so the task is associated with the current chat.
     /// - submit_approval_response: responder (user_id) is injected.
+    /// - reset_session / summarize_conversation: _session_id is injected so they act on the current conversation.
     /// - exec: _channel, _chat_id, _session_id are injected so approval requests are sent to the current conversation.
+    /// - read_file / write_file / list_dir: _session_id is injected so they use the session's tool sandbox.
     pub async fn execute(
         &self,
         name: &str,
         mut args: Value,
         message_ctx: Option<(&str, &str, &str, &str)>,
     ) -> Result<String> {
+        // Validate what the model sent, before any context fields are injected below.
+        if self.validate_args {
+            if let Some(tool) = self.get(name) {
+                validate_tool_args(name, &tool.parameters_schema(), &args)?;
+            }
+        }
         if let Some((channel, chat_id, user_id, session_id)) = message_ctx {
             if name == "add_heartbeat_task" || name == "add_cron_task" {
                 if let Some(obj) = args.as_object_mut() {
@@ -219,7 +276,12 @@ impl ToolRegistry {
                     obj.insert("responder".into(), serde_json::Value::String(user_id.to_string()));
                 }
             }
-            if name == "reset_session" {
+            if name == "reset_session" || name == "summarize_conversation" {
+                if let Some(obj) = args.as_object_mut() {
+                    obj.insert("_session_id".into(), serde_json::Value::String(session_id.to_string()));
+                }
+            }
+            if name == "read_file" || name == "write_file" || name == "list_dir" {
                 if let Some(obj) = args.as_object_mut() {
                     obj.insert("_session_id".into(), serde_json::Value::String(session_id.to_string()));
                 }
@@ -248,7 +310,7 @@ impl ToolRegistry {
                 }
             }
         }
-        let args_for_log = sanitize_args_for_log(name, &args);
+        let args_for_log = self.args_for_log(name, &args);
         let span = tracing::info_span!(
             "tool_execution",
             tool_name = %name,
@@ -258,7 +320,7 @@ impl ToolRegistry {
 
         debug!(tool_name = %name, args = ?args, "Tool call started");
         let start = std::time::Instant::now();
-        let result = match self.tools.get(name) {
+        let result = match self.get(name) {
             Some(tool) => tool.call(args).await,
             None => anyhow::bail!("Tool '{}' not found", name),
         };
@@ -289,10 +351,17 @@ impl ToolRegistry {
         result
     }
 
+    /// Log summary of a call's args: the tool's own sanitizer if it has one, else the built-in rules.
+    fn args_for_log(&self, name: &str, args: &Value) -> String {
+        self.get(name)
+            .and_then(|tool| tool.sanitize_args_for_log(args))
+            .unwrap_or_else(|| sanitize_args_for_log(name, args))
+    }
+
     /// Return rig-compatible ToolDefinition list for the LLM.
     /// Schemas are normalized so all providers (e.g. OpenAI) accept them (type, properties, additionalProperties).
     pub fn rig_definitions(&self) -> Vec<rig::completion::ToolDefinition> {
-        self.tools
+        self.read_tools()
             .values()
             .map(|t| rig::completion::ToolDefinition {
                 name: t.name().to_string(),
@@ -302,8 +371,50 @@ impl ToolRegistry {
             .collect()
     }
 
-    pub fn names(&self) -> Vec<&str> {
-        self.tools.keys().map(|s| s.as_str()).collect()
+    pub fn names(&self) -> Vec<String> {
+        self.read_tools().keys().cloned().collect()
+    }
+
+    /// Check every registered tool: name usable by providers and not reserved, a description that
+    /// is neither empty nor longer than the configured limit, and a parameters schema that is an object and compiles as JSON Schema.
+    /// Call at startup before [`ToolRegistry::register_list_tools_tool`] (its name is reserved).
+    /// Returns one message per problem, sorted by tool name.
+    pub fn validate(&self) -> std::result::Result<(), Vec<String>> {
+        let mut tools = self.snapshot();
+        tools.sort_by(|a, b| a.name().cmp(b.name()));
+        let mut problems = Vec::new();
+        for tool in &tools {
+            let name = tool.name();
+            if !is_valid_tool_name(name) {
+                problems.push(format!(
+                    "tool '{}': name must be 1-64 characters of letters, digits, '_' or '-'",
+                    name
+                ));
+            }
+            if RESERVED_TOOL_NAMES.contains(&name) {
+                problems.push(format!("tool '{}': name is reserved for a built-in tool", name));
+            }
+            let description_chars = tool.description().trim().chars().count();
+            if description_chars == 0 {
+                problems.push(format!("tool '{}': empty description", name));
+            } else if self.max_description_chars > 0 && description_chars > self.max_description_chars {
+                problems.push(format!(
+                    "tool '{}': description is {} characters, over the limit of {} (tools.maxDescriptionChars)",
+                    name, description_chars, self.max_description_chars
+                ));
+            }
+            let schema = tool.parameters_schema();
+            if !schema.is_object() {
+                problems.push(format!("tool '{}': parameters schema is not a JSON object", name));
+            } else if let Err(e) = jsonschema::validator_for(&schema) {
+                problems.push(format!("tool '{}': invalid parameters schema: {}", name, e));
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
     }
 
     /// Register the list_tools tool with a snapshot of the current registry.
@@ -315,6 +426,72 @@ impl ToolRegistry {
     }
 }
 
+/// Default of `tools.maxDescriptionChars`.
+pub const DEFAULT_MAX_TOOL_DESCRIPTION_CHARS: usize = 1024;
+
+/// Names the registry registers itself; other tools may not use them.
+const RESERVED_TOOL_NAMES: &[&str] = &["list_tools"];
+
+/// Tool names every provider accepts (OpenAI's function name rule).
+fn is_valid_tool_name(name: &str) -> bool {
+    (1..=64).contains(&name.len())
+        && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+}
+
+/// Check `args` against a tool's parameters schema. Schemas that fail to compile are not enforced
+/// (some MCP servers publish non-standard schemas); the tool then validates its own input.
+fn validate_tool_args(tool_name: &str, schema: &Value, args: &Value) -> std::result::Result<(), ToolError> {
+    let validator = match jsonschema::validator_for(schema) {
+        Ok(v) => v,
+        Err(e) => {
+            debug!(tool_name = %tool_name, error = %e, "Tool schema does not compile; skipping args validation");
+            return Ok(());
+        }
+    };
+    let errors: Vec<String> = validator
+        .iter_errors(args)
+        .take(5)
+        .map(|e| {
+            let path = e.instance_path.to_string();
+            if path.is_empty() {
+                e.to_string()
+            } else {
+                format!("{} (at {})", e, path)
+            }
+        })
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ToolError::InvalidArgs {
+            tool: tool_name.to_string(),
+            message: errors.join("; "),
+        })
+    }
+}
+
+/// Tool outcome as the content of the tool-result message, in the shape the model's provider
+/// or agent is configured for (`toolResultFormat`). `Err` carries the failure message.
+pub fn format_tool_result(
+    format: crate::config::ToolResultFormat,
+    tool_name: &str,
+    outcome: &std::result::Result<String, String>,
+) -> String {
+    use crate::config::ToolResultFormat;
+    match (format, outcome) {
+        (ToolResultFormat::Text, Ok(output)) => output.clone(),
+        (ToolResultFormat::Text, Err(e)) => format!("Error: {}", e),
+        (ToolResultFormat::Json, Ok(output)) => serde_json::json!({ "result": output }).to_string(),
+        (ToolResultFormat::Json, Err(e)) => serde_json::json!({ "error": e }).to_string(),
+        (ToolResultFormat::Typed, Ok(output)) => {
+            serde_json::json!({ "tool": tool_name, "ok": true, "output": output }).to_string()
+        }
+        (ToolResultFormat::Typed, Err(e)) => {
+            serde_json::json!({ "tool": tool_name, "ok": false, "error": e }).to_string()
+        }
+    }
+}
+
 /// Normalize a tool's parameters JSON Schema for LLM providers (OpenAI requires `properties` and
 /// `additionalProperties: false`; DeepSeek etc. require `type: "object"`). Use when building
 /// tool definitions for completion requests or when registering tools from external sources (e.g. MCP).
@@ -491,6 +668,253 @@ mod tests {
         assert_eq!(result, "exec_test called");
     }
 
+    /// Tool with a schema that requires a string `path`.
+    struct PathTool;
+
+    #[async_trait::async_trait]
+    impl DynTool for PathTool {
+        fn name(&self) -> &str {
+            "path_tool"
+        }
+        fn description(&self) -> &str {
+            "Needs a path"
+        }
+        fn parameters_schema(&self) -> Value {
+            json!({
+                "type": "object",
+                "properties": {"path": {"type": "string"}},
+                "required": ["path"]
+            })
+        }
+        async fn call(&self, args: Value) -> Result<String> {
+            Ok(format!("read {}", args["path"].as_str().unwrap_or_default()))
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_accepts_args_matching_schema() {
+        let mut reg = ToolRegistry::new();
+        reg.register(Arc::new(PathTool)).unwrap();
+        let result = reg.execute("path_tool", json!({"path": "a.txt"}), None).await.unwrap();
+        assert_eq!(result, "read a.txt");
+    }
+
+    #[tokio::test]
+    async fn execute_rejects_args_violating_schema() {
+        let mut reg = ToolRegistry::new();
+        reg.register(Arc::new(PathTool)).unwrap();
+        let err = reg.execute("path_tool", json!({"path": 42}), None).await.unwrap_err();
+        match err.downcast_ref::<ToolError>() {
+            Some(ToolError::InvalidArgs { tool, message }) => {
+                assert_eq!(tool, "path_tool");
+                assert!(message.contains("/path"), "message was: {message}");
+            }
+            other => panic!("expected InvalidArgs, got {other:?}"),
+        }
+
+        let err = reg.execute("path_tool", json!({}), None).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ToolError>(), Some(ToolError::InvalidArgs { .. })));
+    }
+
+    #[tokio::test]
+    async fn execute_skips_validation_when_disabled() {
+        let mut reg = ToolRegistry::new();
+        reg.register(Arc::new(PathTool)).unwrap();
+        reg.set_validate_args(false);
+        let result = reg.execute("path_tool", json!({"path": 42}), None).await.unwrap();
+        assert_eq!(result, "read ");
+    }
+
+    /// Custom tool that summarizes its own args for logs.
+    struct SecretTool;
+
+    #[async_trait::async_trait]
+    impl DynTool for SecretTool {
+        fn name(&self) -> &str {
+            "secret_tool"
+        }
+        fn description(&self) -> &str {
+            "Takes a token"
+        }
+        fn parameters_schema(&self) -> Value {
+            json!({"type": "object", "properties": {"token": {"type": "string"}}})
+        }
+        async fn call(&self, _args: Value) -> Result<String> {
+            Ok("ok".to_string())
+        }
+        fn sanitize_args_for_log(&self, args: &Value) -> Option<String> {
+            let len = args["token"].as_str().map(str::len).unwrap_or(0);
+            Some(format!("token_len={}", len))
+        }
+    }
+
+    /// Tool with arbitrary metadata, for [`ToolRegistry::validate`].
+    struct SchemaTool {
+        tool_name: &'static str,
+        tool_desc: &'static str,
+        schema: Value,
+    }
+
+    #[async_trait::async_trait]
+    impl DynTool for SchemaTool {
+        fn name(&self) -> &str {
+            self.tool_name
+        }
+        fn description(&self) -> &str {
+            self.tool_desc
+        }
+        fn parameters_schema(&self) -> Value {
+            self.schema.clone()
+        }
+        async fn call(&self, _args: Value) -> Result<String> {
+            Ok("ok".to_string())
+        }
+    }
+
+    #[test]
+    fn validate_accepts_well_formed_tools() {
+        let mut reg = ToolRegistry::new();
+        reg.register(fake_tool("alpha")).unwrap();
+        reg.register(Arc::new(PathTool)).unwrap();
+        assert_eq!(reg.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_flags_invalid_schema_and_empty_description() {
+        let mut reg = ToolRegistry::new();
+        reg.register(fake_tool("fine")).unwrap();
+        reg.register(Arc::new(SchemaTool {
+            tool_name: "bad_schema",
+            tool_desc: "Has a broken schema",
+            schema: json!({"type": "object", "properties": {"n": {"type": "integr"}}}),
+        }))
+        .unwrap();
+        reg.register(Arc::new(SchemaTool {
+            tool_name: "no_desc",
+            tool_desc: "  ",
+            schema: json!({"type": "object", "properties": {}}),
+        }))
+        .unwrap();
+
+        let problems = reg.validate().unwrap_err();
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert!(problems[0].starts_with("tool 'bad_schema': invalid parameters schema"), "{:?}", problems);
+        assert_eq!(problems[1], "tool 'no_desc': empty description");
+    }
+
+    #[test]
+    fn validate_flags_over_long_description_against_configured_limit() {
+        let mut reg = ToolRegistry::new();
+        reg.register(Arc::new(FakeTool::new("wordy", &"x".repeat(41)))).unwrap();
+        reg.register(Arc::new(FakeTool::new("empty", ""))).unwrap();
+        reg.register(Arc::new(FakeTool::new("short", "Does one thing"))).unwrap();
+        assert_eq!(reg.validate().unwrap_err(), vec!["tool 'empty': empty description".to_string()]);
+
+        reg.set_max_description_chars(40);
+        let problems = reg.validate().unwrap_err();
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert_eq!(problems[0], "tool 'empty': empty description");
+        assert!(problems[1].starts_with("tool 'wordy': description is 41 characters, over the limit of 40"));
+
+        reg.set_max_description_chars(0);
+        assert_eq!(reg.validate().unwrap_err().len(), 1, "0 disables the length check");
+    }
+
+    #[test]
+    fn validate_flags_reserved_and_malformed_names() {
+        let mut reg = ToolRegistry::new();
+        reg.register(fake_tool("list_tools")).unwrap();
+        reg.register(fake_tool("has space")).unwrap();
+        let problems = reg.validate().unwrap_err();
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert!(problems[0].contains("'has space': name must be"));
+        assert!(problems[1].contains("'list_tools': name is reserved"));
+    }
+
+    #[test]
+    fn tool_result_text_format_passes_output_through() {
+        use crate::config::ToolResultFormat;
+        let ok = Ok("line 1\nline 2".to_string());
+        assert_eq!(format_tool_result(ToolResultFormat::Text, "read_file", &ok), "line 1\nline 2");
+        let err = Err("no such file".to_string());
+        assert_eq!(format_tool_result(ToolResultFormat::Text, "read_file", &err), "Error: no such file");
+    }
+
+    #[test]
+    fn tool_result_json_format_wraps_result_or_error() {
+        use crate::config::ToolResultFormat;
+        let ok = format_tool_result(ToolResultFormat::Json, "read_file", &Ok("say \"hi\"".to_string()));
+        assert_eq!(serde_json::from_str::<Value>(&ok).unwrap(), json!({"result": "say \"hi\""}));
+        let err = format_tool_result(ToolResultFormat::Json, "read_file", &Err("denied".to_string()));
+        assert_eq!(serde_json::from_str::<Value>(&err).unwrap(), json!({"error": "denied"}));
+    }
+
+    #[test]
+    fn tool_result_typed_format_names_tool_and_status() {
+        use crate::config::ToolResultFormat;
+        let ok = format_tool_result(ToolResultFormat::Typed, "exec", &Ok("done".to_string()));
+        assert_eq!(
+            serde_json::from_str::<Value>(&ok).unwrap(),
+            json!({"tool": "exec", "ok": true, "output": "done"})
+        );
+        let err = format_tool_result(ToolResultFormat::Typed, "exec", &Err("timed out".to_string()));
+        assert_eq!(
+            serde_json::from_str::<Value>(&err).unwrap(),
+            json!({"tool": "exec", "ok": false, "error": "timed out"})
+        );
+    }
+
+    #[test]
+    fn args_for_log_prefers_tool_sanitizer() {
+        let mut reg = ToolRegistry::new();
+        reg.register(Arc::new(SecretTool)).unwrap();
+        let summary = reg.args_for_log("secret_tool", &json!({"token": "abcdef"}));
+        assert_eq!(summary, "token_len=6");
+        assert!(!summary.contains("abcdef"));
+    }
+
+    #[test]
+    fn args_for_log_falls_back_to_builtin_rules() {
+        let mut reg = ToolRegistry::new();
+        reg.register(fake_tool("read_file")).unwrap();
+        reg.register(fake_tool("custom")).unwrap();
+        assert_eq!(reg.args_for_log("read_file", &json!({"path": "a.txt"})), "path=a.txt");
+        assert_eq!(reg.args_for_log("custom", &json!({"x": 1})), "args=...");
+    }
+
+    #[tokio::test]
+    async fn replace_all_swaps_whole_tool_set() {
+        let mut reg = ToolRegistry::new();
+        reg.register(fake_tool("old_a")).unwrap();
+        reg.register(fake_tool("old_b")).unwrap();
+
+        reg.replace_all(vec![fake_tool("new_a"), fake_tool("new_b"), fake_tool("new_c")])
+            .unwrap();
+
+        let mut names = reg.names();
+        names.sort();
+        assert_eq!(names, vec!["new_a", "new_b", "new_c"]);
+        assert!(reg.get("old_a").is_none());
+        assert_eq!(reg.execute("new_c", json!({}), None).await.unwrap(), "new_c called");
+    }
+
+    #[test]
+    fn replace_all_with_duplicates_keeps_old_set() {
+        let mut reg = ToolRegistry::new();
+        reg.register(fake_tool("keep_a")).unwrap();
+        reg.register(fake_tool("keep_b")).unwrap();
+
+        let err = reg
+            .replace_all(vec![fake_tool("x"), fake_tool("dup"), fake_tool("dup")])
+            .unwrap_err();
+        assert!(err.to_string().contains("dup"), "error was: {err}");
+
+        let mut names = reg.names();
+        names.sort();
+        assert_eq!(names, vec!["keep_a", "keep_b"]);
+        assert_eq!(reg.snapshot().len(), 2);
+    }
+
     #[tokio::test]
     async fn execute_fails_after_deregister() {
         let mut reg = ToolRegistry::new();
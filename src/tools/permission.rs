@@ -2,8 +2,11 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock};
 use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::info;
+
+use crate::tools::audit::{ToolAuditEvent, ToolAuditLog};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
@@ -23,6 +26,14 @@ pub struct PermissionRule {
     pub description: Option<String>,
 }
 
+/// Result of evaluating a command against the rules: the level and the index of the rule
+/// that produced it (`None` when no rule matched and the default level applies).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PermissionDecision {
+    pub level: PermissionLevel,
+    pub rule_index: Option<usize>,
+}
+
 /// Performance monitoring metrics.
 #[derive(Debug, Default)]
 pub struct PermissionMetrics {
@@ -127,12 +138,14 @@ pub struct CommandPermissionPolicy {
     compiled_rules: Vec<CompiledRule>,
     /// Default permission level when no rule matches.
     pub default_level: PermissionLevel,
-    /// Permission check result cache (command -> level).
-    cache: RwLock<HashMap<String, PermissionLevel>>,
+    /// Permission check result cache (command -> decision).
+    cache: RwLock<HashMap<String, PermissionDecision>>,
     /// Cache size limit.
     cache_size_limit: usize,
     /// Performance metrics.
     metrics: PermissionMetrics,
+    /// Where each decision is recorded, when set.
+    audit_log: Option<Arc<ToolAuditLog>>,
 }
 
 fn default_permission_level() -> PermissionLevel {
@@ -158,8 +171,20 @@ impl CommandPermissionPolicy {
             cache: RwLock::new(HashMap::new()),
             cache_size_limit: 1000,
             metrics: PermissionMetrics::default(),
+            audit_log: None,
         }
     }
+
+    /// Record every decision made by [`Self::check_permission`] in `log`.
+    pub fn with_audit_log(mut self, log: Arc<ToolAuditLog>) -> Self {
+        self.audit_log = Some(log);
+        self
+    }
+
+    /// The audit log decisions are recorded in, if any.
+    pub fn audit_log(&self) -> Option<&Arc<ToolAuditLog>> {
+        self.audit_log.as_ref()
+    }
     
     /// Check command permission level (with caching).
     pub fn check_permission(&self, command: &str) -> PermissionLevel {
@@ -171,11 +196,12 @@ impl CommandPermissionPolicy {
         // Try to read from cache
         {
             let cache = self.cache.read().unwrap();
-            if let Some(&level) = cache.get(&lower) {
+            if let Some(&decision) = cache.get(&lower) {
                 // Cache hit
                 self.metrics.cache_hits.fetch_add(1, Ordering::Relaxed);
-                self.record_permission_level(level);
-                return level;
+                self.record_permission_level(decision.level);
+                self.record_decision(command, decision);
+                return decision.level;
             }
         }
         
@@ -183,10 +209,12 @@ impl CommandPermissionPolicy {
         self.metrics.cache_misses.fetch_add(1, Ordering::Relaxed);
         
         // Perform actual check
-        let level = self.check_permission_uncached(&lower);
+        let decision = self.evaluate(&lower);
+        let level = decision.level;
         
         // Record permission level stats
         self.record_permission_level(level);
+        self.record_decision(command, decision);
         
         // Write to cache
         {
@@ -204,11 +232,31 @@ impl CommandPermissionPolicy {
                 }
             }
             
-            cache.insert(lower, level);
+            cache.insert(lower, decision);
         }
         
         level
     }
+
+    /// Emit a tracing event for `decision` and add it to the audit log.
+    fn record_decision(&self, command: &str, decision: PermissionDecision) {
+        let pattern = decision.rule_index.map(|i| self.rules[i].pattern.clone());
+        info!(
+            command = %command,
+            level = ?decision.level,
+            rule_index = ?decision.rule_index,
+            pattern = pattern.as_deref().unwrap_or("<default>"),
+            "Permission decision"
+        );
+        if let Some(log) = &self.audit_log {
+            log.record(ToolAuditEvent::PermissionDecision {
+                command: command.to_string(),
+                level: decision.level,
+                rule_index: decision.rule_index,
+                pattern,
+            });
+        }
+    }
     
     /// Record permission level stats.
     fn record_permission_level(&self, level: PermissionLevel) {
@@ -235,16 +283,24 @@ impl CommandPermissionPolicy {
         self.metrics.reset();
     }
     
-    /// Check command permission level (no cache).
-    fn check_permission_uncached(&self, command: &str) -> PermissionLevel {
+    /// Evaluate `command` against the rules without touching the cache or metrics.
+    /// Returns the resulting level and which rule (if any) matched first.
+    pub fn evaluate(&self, command: &str) -> PermissionDecision {
+        let lower = command.to_lowercase();
         // Use compiled rules for fast matching
-        for compiled_rule in &self.compiled_rules {
-            if compiled_rule.pattern.matches(command) {
-                return compiled_rule.level;
+        for (index, compiled_rule) in self.compiled_rules.iter().enumerate() {
+            if compiled_rule.pattern.matches(&lower) {
+                return PermissionDecision {
+                    level: compiled_rule.level,
+                    rule_index: Some(index),
+                };
             }
         }
-        
-        self.default_level
+
+        PermissionDecision {
+            level: self.default_level,
+            rule_index: None,
+        }
     }
     
     /// Clear the cache.
@@ -479,6 +535,52 @@ mod tests {
         let policy = CommandPermissionPolicy::from_json(json).unwrap();
         assert_eq!(policy.default_level, PermissionLevel::RequireApproval);
     }
+
+    #[test]
+    fn test_decisions_are_recorded_in_audit_log() {
+        let log = Arc::new(ToolAuditLog::default());
+        let policy = CommandPermissionPolicy::new(
+            vec![
+                PermissionRule {
+                    pattern: "git status*".to_string(),
+                    level: PermissionLevel::Allow,
+                    description: None,
+                },
+                PermissionRule {
+                    pattern: "*rm -rf*".to_string(),
+                    level: PermissionLevel::Deny,
+                    description: None,
+                },
+            ],
+            PermissionLevel::RequireApproval,
+        )
+        .with_audit_log(Arc::clone(&log));
+
+        policy.check_permission("rm -rf /tmp/x");
+        // Cached decisions are still audited with their rule
+        policy.check_permission("rm -rf /tmp/x");
+        policy.check_permission("make deploy");
+
+        let entries = log.entries();
+        assert_eq!(entries.len(), 3);
+        let denied = ToolAuditEvent::PermissionDecision {
+            command: "rm -rf /tmp/x".to_string(),
+            level: PermissionLevel::Deny,
+            rule_index: Some(1),
+            pattern: Some("*rm -rf*".to_string()),
+        };
+        assert_eq!(entries[0].event, denied);
+        assert_eq!(entries[1].event, denied);
+        assert_eq!(
+            entries[2].event,
+            ToolAuditEvent::PermissionDecision {
+                command: "make deploy".to_string(),
+                level: PermissionLevel::RequireApproval,
+                rule_index: None,
+                pattern: None,
+            }
+        );
+    }
 }
 
     #[test]
@@ -1,9 +1,18 @@
-//! Session tools: list_sessions and reset_session.
+//! Session tools: list_sessions, reset_session and summarize_conversation.
+
+use std::sync::Arc;
 
 use anyhow::Result;
+use rig::message::Message;
 use serde_json::{json, Value};
+use tokio::sync::RwLock;
 
+use crate::agent::channel_models::ChannelModels;
+use crate::agent::session::SessionMessage;
+use crate::agent::session_compactor::{summarize_messages, write_summary_to_memory};
+use crate::agent::session_id::SessionId;
 use crate::agent::session_state::SharedSessionState;
+use crate::config::Config;
 use crate::tools::DynTool;
 
 /// Tool to list active conversation sessions (channel, scope, identifier, agent_id, message count, running status).
@@ -151,3 +160,178 @@ impl DynTool for ResetSessionTool {
         Ok("Session cleared. The conversation will continue as a fresh chat.".to_string())
     }
 }
+
+/// Tool that summarizes the current conversation with the model, on request (a TL;DR of a long
+/// thread). Uses the same summary prompt as history compression and, like it, appends the summary
+/// to the agent's memory when `memory.compression.summaryWriteToMemory` is on. The summary is
+/// written by the model the conversation's turns use (the channel's `model` override, if any).
+pub struct SummarizeConversationTool {
+    session_state: SharedSessionState,
+    models: Arc<ChannelModels>,
+    config: Arc<RwLock<Config>>,
+}
+
+impl SummarizeConversationTool {
+    pub fn new(
+        session_state: SharedSessionState,
+        models: Arc<ChannelModels>,
+        config: Arc<RwLock<Config>>,
+    ) -> Self {
+        Self {
+            session_state,
+            models,
+            config,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl DynTool for SummarizeConversationTool {
+    fn name(&self) -> &str {
+        "summarize_conversation"
+    }
+
+    fn description(&self) -> &str {
+        "Summarize the current conversation in short bullet points and return the summary. Use when the user asks for a TL;DR, recap or summary of this chat. The current session is inferred from context. Optional arg: write_to_memory (save the summary to long-term memory; defaults to the memory.compression.summaryWriteToMemory setting)."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "write_to_memory": {
+                    "type": "boolean",
+                    "description": "Optional. Also append the summary to long-term memory. Defaults to the configured summaryWriteToMemory."
+                }
+            },
+            "required": []
+        })
+    }
+
+    async fn call(&self, args: Value) -> Result<String> {
+        let session_key = match args.get("_session_id").and_then(|v| v.as_str()) {
+            Some(k) => k.to_string(),
+            None => {
+                return Ok("Cannot summarize: current session id not available (summarize_conversation must be called from a conversation).".to_string());
+            }
+        };
+        let session_id = SessionId::parse(&session_key)?;
+        // The session manager keeps the user and assistant text of the conversation; the in-memory
+        // history is locked by the running turn.
+        let messages: Vec<Message> = {
+            let sm = self.session_state.session_manager.read().await;
+            sm.get_history(&session_id)
+                .map(|history| history.iter().map(SessionMessage::to_message).collect())
+                .unwrap_or_default()
+        };
+        let cfg = self.config.read().await.clone();
+        let agent_provider = cfg
+            .main_agent
+            .agents
+            .iter()
+            .find(|a| a.name == session_id.agent_id)
+            .and_then(|a| a.provider.clone())
+            .unwrap_or_else(|| cfg.main_agent.provider.clone());
+        let model = self.models.for_channel(&cfg, &session_id.channel, &agent_provider);
+        let summary = match summarize_messages(model.as_ref(), &messages).await? {
+            Some(s) => s,
+            None => return Ok("Nothing to summarize yet: this conversation has no messages.".to_string()),
+        };
+        let write_to_memory = args
+            .get("write_to_memory")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(cfg.memory.compression.summary_write_to_memory);
+        if write_to_memory {
+            write_summary_to_memory(&cfg, &session_id.agent_id, "Conversation summary", &summary);
+        }
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::session::SessionStore;
+    use crate::agent::session_id::SessionScope;
+    use crate::rig_provider::SynbotCompletionModel;
+    use rig::completion::{CompletionError, CompletionRequest, CompletionResponse};
+    use rig::message::AssistantContent;
+    use rig::OneOrMany;
+    use std::future::Future;
+    use std::pin::Pin;
+
+    /// Fake LLM that records the prompt it was given and answers with a fixed summary.
+    #[derive(Default)]
+    struct SummaryModel {
+        prompts: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl SynbotCompletionModel for SummaryModel {
+        fn completion(
+            &self,
+            request: CompletionRequest,
+        ) -> Pin<
+            Box<dyn Future<Output = Result<CompletionResponse<()>, CompletionError>> + Send + '_>,
+        > {
+            self.prompts
+                .lock()
+                .unwrap()
+                .push(format!("{:?}", request.chat_history));
+            Box::pin(async {
+                Ok(CompletionResponse {
+                    choice: OneOrMany::one(AssistantContent::text("- Deploy moved to Friday")),
+                    usage: rig::completion::Usage {
+                        input_tokens: 0,
+                        output_tokens: 0,
+                        total_tokens: 0,
+                        cached_input_tokens: 0,
+                    },
+                    raw_response: (),
+                })
+            })
+        }
+    }
+
+    fn session_message(role: &str, content: &str) -> SessionMessage {
+        SessionMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn summarizes_current_session_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = SharedSessionState::new(SessionStore::new(dir.path()));
+        let session_id = SessionId::full("main", "telegram", SessionScope::Dm, "alice");
+        {
+            let mut sm = state.session_manager.write().await;
+            sm.append(&session_id, session_message("user", "Can we deploy on Thursday?"));
+            sm.append(&session_id, session_message("assistant", "Thursday is frozen; Friday works."));
+        }
+        let model = Arc::new(SummaryModel::default());
+        let tool = SummarizeConversationTool::new(
+            state,
+            Arc::new(ChannelModels::new(model.clone())),
+            Arc::new(RwLock::new(Config::default())),
+        );
+
+        let summary = tool
+            .call(json!({ "_session_id": session_id.format(), "write_to_memory": false }))
+            .await
+            .unwrap();
+        assert_eq!(summary, "- Deploy moved to Friday");
+        let prompts = model.prompts.lock().unwrap().clone();
+        assert_eq!(prompts.len(), 1);
+        assert!(prompts[0].contains("Can we deploy on Thursday?"));
+        assert!(prompts[0].contains("Friday works."));
+
+        let empty = tool
+            .call(json!({ "_session_id": "agent:main:telegram:dm:bob", "write_to_memory": false }))
+            .await
+            .unwrap();
+        assert!(empty.starts_with("Nothing to summarize"));
+        assert!(tool.call(json!({})).await.unwrap().starts_with("Cannot summarize"));
+    }
+}
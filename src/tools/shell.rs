@@ -82,12 +82,64 @@ fn decode_output_bytes(bytes: &[u8]) -> String {
 // CommandPolicy – configurable deny/allow pattern matching
 // ---------------------------------------------------------------------------
 
+/// Prefix for patterns matched verbatim against the raw command (no whitespace
+/// collapsing, always case-sensitive).
+const EXACT_PATTERN_PREFIX: &str = "exact:";
+
+/// Prefix for patterns matched as whole words in command position: the first word of each
+/// command in the line (after `;`, `&`, `|`, `(` or a backtick, path stripped), or the word after
+/// a wrapper such as `sudo`. `cmd:nc` catches `nc -l 80` and `sudo /bin/nc` but not `rsync`.
+const COMMAND_PATTERN_PREFIX: &str = "cmd:";
+
+/// Words that run the command after them, so that word is in command position too.
+const COMMAND_WRAPPERS: &[&str] = &[
+    "sudo", "doas", "env", "exec", "nohup", "nice", "time", "command", "xargs", "busybox",
+];
+
+/// Whether `pattern` (its words, already case-folded as needed) starts a command in `command`.
+fn matches_command_word(command: &str, pattern: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split_whitespace().collect();
+    if pattern.is_empty() {
+        return false;
+    }
+    command
+        .split([';', '&', '|', '(', ')', '`', '\n'])
+        .any(|segment| {
+            let words: Vec<&str> = segment.split_whitespace().collect();
+            let mut pos = 0;
+            while pos < words.len() {
+                // Leading `VAR=value` assignments are not the command.
+                if words[pos].contains('=') && !words[pos].starts_with('-') {
+                    pos += 1;
+                    continue;
+                }
+                let word = words[pos].rsplit('/').next().unwrap_or(words[pos]);
+                if word == pattern[0]
+                    && words.len() >= pos + pattern.len()
+                    && words[pos + 1..pos + pattern.len()] == pattern[1..]
+                {
+                    return true;
+                }
+                if !COMMAND_WRAPPERS.contains(&word) {
+                    return false;
+                }
+                pos += 1;
+                while pos < words.len() && words[pos].starts_with('-') {
+                    pos += 1;
+                }
+            }
+            false
+        })
+}
+
 /// Command security policy that validates commands against configurable
 /// deny and allow pattern lists before execution.
 #[derive(Debug, Clone)]
 pub struct CommandPolicy {
     pub deny_patterns: Vec<String>,
     pub allow_patterns: Option<Vec<String>>,
+    /// When false (default), commands and patterns are case-folded before matching.
+    pub case_sensitive: bool,
 }
 
 impl CommandPolicy {
@@ -96,20 +148,51 @@ impl CommandPolicy {
         Self {
             deny_patterns,
             allow_patterns,
+            case_sensitive: false,
         }
     }
 
+    /// Match patterns case-sensitively (`tools.exec.patternCaseSensitive`).
+    pub fn with_case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    /// Collapse whitespace runs to single spaces and, unless case-sensitive, lowercase.
+    fn normalize(&self, text: &str) -> String {
+        let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if self.case_sensitive {
+            collapsed
+        } else {
+            collapsed.to_lowercase()
+        }
+    }
+
+    fn matches(&self, raw: &str, normalized: &str, pattern: &str) -> bool {
+        if let Some(exact) = pattern.strip_prefix(EXACT_PATTERN_PREFIX) {
+            return raw.contains(exact);
+        }
+        if let Some(words) = pattern.strip_prefix(COMMAND_PATTERN_PREFIX) {
+            // Split the raw command: newlines separate commands but normalizing collapses them.
+            let command = if self.case_sensitive { raw.to_string() } else { raw.to_lowercase() };
+            return matches_command_word(&command, &self.normalize(words));
+        }
+        normalized.contains(&self.normalize(pattern))
+    }
+
     /// Validate whether a command is allowed to execute.
     ///
-    /// Checks deny patterns first – if any deny pattern matches (case-insensitive
-    /// substring), the command is rejected.  Then, if an allow list is configured,
-    /// the command must match at least one allow pattern to be accepted.
+    /// Checks deny patterns first – if any deny pattern matches (substring after
+    /// whitespace collapsing and, by default, case-folding), the command is rejected.
+    /// Then, if an allow list is configured, the command must match at least one
+    /// allow pattern to be accepted. `exact:` patterns match the raw command verbatim;
+    /// `cmd:` patterns match only in command position.
     pub fn validate(&self, command: &str) -> std::result::Result<(), String> {
-        let lower = command.to_lowercase();
+        let normalized = self.normalize(command);
 
         // 1. Check deny patterns – reject if any match
         for pat in &self.deny_patterns {
-            if lower.contains(&pat.to_lowercase()) {
+            if self.matches(command, &normalized, pat) {
                 return Err(format!(
                     "Command rejected: matches deny pattern '{}'. Command: {}",
                     pat, command
@@ -121,7 +204,7 @@ impl CommandPolicy {
         if let Some(ref allow) = self.allow_patterns {
             let allowed = allow
                 .iter()
-                .any(|pat| lower.contains(&pat.to_lowercase()));
+                .any(|pat| self.matches(command, &normalized, pat));
             if !allowed {
                 return Err(format!(
                     "Command rejected: does not match any allow pattern. Command: {}",
@@ -148,6 +231,7 @@ impl Default for CommandPolicy {
                 "fork bomb".to_string(),
             ],
             allow_patterns: None,
+            case_sensitive: false,
         }
     }
 }
@@ -302,7 +386,7 @@ impl DynTool for ExecTool {
 
         let approval_cwd = match &self.sandbox_context {
             Some(ctx) => match ctx.exec_kind() {
-                crate::sandbox::types::ToolSandboxExecKind::Docker => "/workspace".to_string(),
+                crate::sandbox::types::ToolSandboxExecKind::Docker => ctx.workspace_dir().to_string(),
                 crate::sandbox::types::ToolSandboxExecKind::HostNative => cwd.display().to_string(),
             },
             None => cwd.display().to_string(),
@@ -403,17 +487,16 @@ impl DynTool for ExecTool {
         };
         let timeout_duration = Duration::from_secs(effective_timeout_secs);
 
-        // Run inside tool sandbox when configured (Docker: sh -c + /workspace; host-native: cmd or sh + real cwd).
+        // Run inside tool sandbox when configured (Docker: sh -c + workspace mount; host-native: cmd or sh + real cwd).
         if let Some(ref delegate) = self.sandbox_context {
             let timeout = timeout_duration;
             let exec_kind = delegate.exec_kind();
-            let sandbox_id = delegate.sandbox_id().to_string();
             let (command, shell_args, sandbox_cwd_buf, working_dir_display) = match exec_kind {
                 crate::sandbox::types::ToolSandboxExecKind::Docker => (
                     "sh".to_string(),
                     vec!["-c".to_string(), cmd_str.to_string()],
-                    "/workspace".to_string(),
-                    "/workspace".to_string(),
+                    delegate.workspace_dir().to_string(),
+                    delegate.workspace_dir().to_string(),
                 ),
                 crate::sandbox::types::ToolSandboxExecKind::HostNative => {
                     let wd = cwd.display().to_string();
@@ -440,9 +523,14 @@ impl DynTool for ExecTool {
             };
             let exec_result = match delegate {
                 crate::sandbox::ToolSandboxDelegate::Local { manager, .. } => {
+                    // Used by per-session isolation; the shared sandbox ignores it.
+                    let session_id = args["_session_id"]
+                        .as_str()
+                        .map(String::from)
+                        .or_else(|| self.session_id.clone());
                     manager
-                        .execute_in_sandbox(
-                            &sandbox_id,
+                        .exec_in_session_tool_sandbox(
+                            session_id.as_deref(),
                             &command,
                             &shell_args,
                             timeout,
@@ -454,7 +542,7 @@ impl DynTool for ExecTool {
                 crate::sandbox::ToolSandboxDelegate::Remote { client, .. } => {
                     client
                         .execute(
-                            &sandbox_id,
+                            delegate.sandbox_id(),
                             &command,
                             &shell_args,
                             timeout,
@@ -531,7 +619,7 @@ impl DynTool for ExecTool {
             }
         }
 
-        let output = tokio::time::timeout(
+        let output = crate::tools::context::cancellable(tokio::time::timeout(
             timeout_duration,
             Command::new(if cfg!(windows) { "cmd" } else { "sh" })
                 .args(if cfg!(windows) {
@@ -540,9 +628,11 @@ impl DynTool for ExecTool {
                     vec!["-c", &cmd_str]
                 })
                 .current_dir(&cwd)
+                // Kill the child when the future is dropped (timeout or /cancel of the turn).
+                .kill_on_drop(true)
                 .output(),
-        )
-        .await
+        ))
+        .await?
         .map_err(|_| anyhow::anyhow!("Command timed out after {}s", effective_timeout_secs))??;
 
         let duration_ms = start.elapsed().as_millis() as u64;
@@ -688,6 +778,60 @@ mod tests {
         assert!(policy.validate("dangerous").is_err());
     }
 
+    #[test]
+    fn policy_deny_catches_case_and_whitespace_evasion() {
+        let policy = CommandPolicy::default();
+        assert!(policy.validate("RM -RF /").is_err());
+        assert!(policy.validate("rm  -rf  /").is_err());
+        assert!(policy.validate("rm\t-rf \t/").is_err());
+        assert!(policy.validate("  Rm   -Rf   /  ").is_err());
+        assert!(policy.validate("rm -rf ./build").is_ok());
+    }
+
+    #[test]
+    fn policy_case_sensitive_flag_and_exact_patterns() {
+        let policy = CommandPolicy::new(vec!["rm  -rf /".to_string()], None).with_case_sensitive(true);
+        assert!(policy.validate("rm -rf /").is_err());
+        assert!(policy.validate("rm   -rf /").is_err());
+        assert!(policy.validate("RM -RF /").is_ok());
+
+        let exact = CommandPolicy::new(vec!["exact:a  b".to_string()], None);
+        assert!(exact.validate("echo a  b").is_err());
+        assert!(exact.validate("echo a b").is_ok());
+        assert!(exact.validate("echo A  B").is_ok());
+    }
+
+    #[test]
+    fn command_word_patterns_match_only_in_command_position() {
+        let policy = CommandPolicy::new(
+            vec!["cmd:nc".to_string(), "cmd:ftp".to_string(), "cmd:passwd".to_string()],
+            None,
+        );
+        for blocked in ["nc -l 8080", "echo hi | NC host 80", "sudo -n /usr/bin/nc host", "ls; ftp host", "env A=1 passwd", "cd /tmp\npasswd"] {
+            assert!(policy.validate(blocked).is_err(), "{} should be denied", blocked);
+        }
+        for allowed in ["rsync -av src/ host:dst/", "sftp user@host", "cat /etc/passwd", "echo nc", "grep -r ftp ."] {
+            assert!(policy.validate(allowed).is_ok(), "{} should be allowed", allowed);
+        }
+    }
+
+    #[test]
+    fn network_and_privilege_presets_spare_lookalike_commands() {
+        let deny = crate::config::expand_pattern_presets(&[
+            "preset:destructive".to_string(),
+            "preset:network".to_string(),
+            "preset:privilege".to_string(),
+        ])
+        .unwrap();
+        let policy = CommandPolicy::new(deny, None);
+        for allowed in ["ncdu /var", "ssh-keygen -t ed25519", "sftp-server --help", "cat /etc/passwd", "grep halt notes.txt", "asphalt --version"] {
+            assert!(policy.validate(allowed).is_ok(), "{} should be allowed", allowed);
+        }
+        for blocked in ["nc -l 4444", "sftp user@host", "rsync -av a host:b", "sudo passwd root", "halt", "curl https://x"] {
+            assert!(policy.validate(blocked).is_err(), "{} should be denied", blocked);
+        }
+    }
+
     #[test]
     fn policy_deny_pattern_match_returns_descriptive_error() {
         let policy = CommandPolicy::new(vec!["rm -rf /".to_string()], None);
@@ -4,6 +4,8 @@
 //! of content, inserting a truncation marker in the middle. This ensures that
 //! both the beginning context and the final results of tool output are retained.
 
+use unicode_segmentation::UnicodeSegmentation;
+
 /// Result of a truncation operation, including metadata about what was truncated.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TruncationResult {
@@ -148,6 +150,75 @@ pub fn smart_truncate_streams(
     )
 }
 
+// ---------------------------------------------------------------------------
+// Marked truncation (log fields, chat previews)
+// ---------------------------------------------------------------------------
+
+/// What a truncated string reports after its marker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthReport {
+    /// Marker only.
+    None,
+    /// ` (N chars)` with the original length.
+    Total,
+    /// ` (N more chars)` with the number of characters dropped.
+    Omitted,
+}
+
+/// Suffix appended to truncated text. Each surface picks the style it wants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TruncationMarker {
+    pub marker: &'static str,
+    pub report: LengthReport,
+}
+
+impl TruncationMarker {
+    /// Log fields: `text... (N chars)`.
+    pub const LOG: Self = Self {
+        marker: "...",
+        report: LengthReport::Total,
+    };
+    /// Tool progress previews in channels: `text...`.
+    pub const DOTS: Self = Self {
+        marker: "...",
+        report: LengthReport::None,
+    };
+    /// Chat text: `text…`.
+    pub const ELLIPSIS: Self = Self {
+        marker: "…",
+        report: LengthReport::None,
+    };
+
+    fn apply(&self, kept: &str, total_chars: usize, omitted_chars: usize) -> String {
+        match self.report {
+            LengthReport::None => format!("{}{}", kept, self.marker),
+            LengthReport::Total => format!("{}{} ({} chars)", kept, self.marker, total_chars),
+            LengthReport::Omitted => format!("{}{} ({} more chars)", kept, self.marker, omitted_chars),
+        }
+    }
+}
+
+/// Keep at most `max_bytes` bytes of `s` (never splitting a character) and append
+/// `marker` when anything was cut. Counts in the report are in characters.
+pub fn truncate_bytes_with(s: &str, max_bytes: usize, marker: &TruncationMarker) -> String {
+    if s.len() <= max_bytes {
+        return s.to_string();
+    }
+    let kept = safe_prefix(s, max_bytes);
+    let omitted = s[kept.len()..].chars().count();
+    marker.apply(kept, kept.chars().count() + omitted, omitted)
+}
+
+/// Keep at most `max_chars` characters of `s` and append `marker` when anything was cut.
+pub fn truncate_chars_with(s: &str, max_chars: usize, marker: &TruncationMarker) -> String {
+    let total = s.chars().count();
+    if total <= max_chars {
+        return s.to_string();
+    }
+    let end = s.char_indices().nth(max_chars).map(|(i, _)| i).unwrap_or(s.len());
+    marker.apply(&s[..end], total, total - max_chars)
+}
+
 // ---------------------------------------------------------------------------
 // UTF-8–safe slicing helpers
 // ---------------------------------------------------------------------------
@@ -165,6 +236,25 @@ fn safe_prefix(s: &str, max_bytes: usize) -> &str {
     &s[..end]
 }
 
+/// Return the longest prefix of `s` that is at most `max_bytes` bytes and ends on
+/// a grapheme-cluster boundary, so combining marks and emoji ZWJ / modifier
+/// sequences are never cut in half. Use for user-facing text; logs can use the
+/// cheaper char-boundary slicing.
+pub fn grapheme_prefix(s: &str, max_bytes: usize) -> &str {
+    if max_bytes >= s.len() {
+        return s;
+    }
+    let mut end = 0;
+    for (start, grapheme) in s.grapheme_indices(true) {
+        let next = start + grapheme.len();
+        if next > max_bytes {
+            break;
+        }
+        end = next;
+    }
+    &s[..end]
+}
+
 /// Return the longest suffix of `s` that is at most `max_bytes` bytes and
 /// does not split a multi-byte UTF-8 character.
 fn safe_suffix(s: &str, max_bytes: usize) -> &str {
@@ -405,4 +495,64 @@ mod tests {
         assert_eq!(out_r.strategy, "head-tail");
         assert_eq!(err_r.strategy, "head-tail");
     }
+
+    // ---- marked truncation ----
+
+    #[test]
+    fn marked_truncation_leaves_short_text_alone() {
+        assert_eq!(truncate_bytes_with("short", 5, &TruncationMarker::LOG), "short");
+        assert_eq!(truncate_chars_with("héllo", 5, &TruncationMarker::ELLIPSIS), "héllo");
+    }
+
+    #[test]
+    fn marked_truncation_reports_total_and_omitted_counts() {
+        assert_eq!(truncate_bytes_with("abcdefghij", 4, &TruncationMarker::LOG), "abcd... (10 chars)");
+        let omitted = TruncationMarker {
+            marker: "...",
+            report: LengthReport::Omitted,
+        };
+        assert_eq!(truncate_chars_with("abcdefghij", 4, &omitted), "abcd... (6 more chars)");
+        // Bytes budget, character counts: "é" is 2 bytes.
+        assert_eq!(truncate_bytes_with("ééééé", 5, &omitted), "éé... (3 more chars)");
+    }
+
+    #[test]
+    fn marked_truncation_uses_custom_marker() {
+        assert_eq!(truncate_chars_with("héllo wörld", 5, &TruncationMarker::ELLIPSIS), "héllo…");
+        assert_eq!(truncate_chars_with("héllo wörld", 5, &TruncationMarker::DOTS), "héllo...");
+        let custom = TruncationMarker {
+            marker: " [cut]",
+            report: LengthReport::Total,
+        };
+        assert_eq!(truncate_chars_with("héllo wörld", 5, &custom), "héllo [cut] (11 chars)");
+    }
+
+    // ---- grapheme_prefix ----
+
+    #[test]
+    fn grapheme_prefix_keeps_combining_characters_together() {
+        // "e" + COMBINING ACUTE ACCENT is one grapheme of 3 bytes.
+        let s = "cafe\u{301} noir";
+        assert_eq!(grapheme_prefix(s, 4), "caf");
+        assert_eq!(grapheme_prefix(s, 5), "caf");
+        assert_eq!(grapheme_prefix(s, 6), "cafe\u{301}");
+        // The char-boundary slice would have cut off the accent.
+        assert_eq!(safe_prefix(s, 4), "cafe");
+    }
+
+    #[test]
+    fn grapheme_prefix_never_splits_zwj_sequences() {
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}"; // 👨‍👩‍👧, 18 bytes
+        let s = format!("hi {}{}", family, family);
+        assert_eq!(grapheme_prefix(&s, 3), "hi ");
+        for budget in 3..21 {
+            assert_eq!(grapheme_prefix(&s, budget), "hi ", "budget {}", budget);
+        }
+        assert_eq!(grapheme_prefix(&s, 21), format!("hi {}", family));
+        assert_eq!(grapheme_prefix(&s, 1000), s);
+
+        let thumbs = "\u{1F44D}\u{1F3FD}"; // 👍🏽 with skin-tone modifier
+        assert_eq!(grapheme_prefix(thumbs, 4), "");
+        assert_eq!(grapheme_prefix(thumbs, 8), thumbs);
+    }
 }
@@ -9,6 +9,9 @@
 
 use anyhow::{Context, Result};
 use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 use crate::config::WebSearchBackend;
 use crate::tools::DynTool;
@@ -24,86 +27,362 @@ fn build_client() -> Result<reqwest::Client> {
         .build()?)
 }
 
+// ---------------------------------------------------------------------------
+// Conditional requests (ETag / Last-Modified)
+// ---------------------------------------------------------------------------
+
+const CONDITIONAL_CACHE_MAX_ENTRIES: usize = 256;
+
+struct CachedBody {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: String,
+    stored_at: Instant,
+}
+
+/// Remembers validators and bodies of upstream responses (keyed by the request URL in
+/// [`crate::url_utils::normalize`] form) so a repeated
+/// query is revalidated with `If-None-Match` / `If-Modified-Since` and served from cache on 304.
+/// Only responses that carry an `ETag` or `Last-Modified` header are stored.
+#[derive(Default)]
+pub struct ConditionalCache {
+    entries: Mutex<HashMap<String, CachedBody>>,
+}
+
+impl ConditionalCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Send `request` (adding validators when a cached copy exists) and return the response body.
+    async fn get_text(
+        &self,
+        client: &reqwest::Client,
+        request: reqwest::RequestBuilder,
+    ) -> Result<String> {
+        use reqwest::header::{HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+
+        let mut request = request.build()?;
+        let key = crate::url_utils::normalize(request.url().as_str());
+        let validators = {
+            let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+            entries
+                .get(&key)
+                .map(|c| (c.etag.clone(), c.last_modified.clone()))
+        };
+        if let Some((etag, last_modified)) = validators {
+            if let Some(v) = etag.and_then(|s| HeaderValue::from_str(&s).ok()) {
+                request.headers_mut().insert(IF_NONE_MATCH, v);
+            }
+            if let Some(v) = last_modified.and_then(|s| HeaderValue::from_str(&s).ok()) {
+                request.headers_mut().insert(IF_MODIFIED_SINCE, v);
+            }
+        }
+
+        let resp = client.execute(request).await?;
+        if resp.status() == reqwest::StatusCode::NOT_MODIFIED {
+            let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+            return match entries.get(&key) {
+                Some(cached) => {
+                    tracing::debug!(url = %key, "Upstream returned 304; serving cached body");
+                    Ok(cached.body.clone())
+                }
+                None => anyhow::bail!("upstream returned 304 Not Modified but no cached body exists"),
+            };
+        }
+
+        let header = |name: reqwest::header::HeaderName| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let cacheable = resp.status().is_success() && (etag.is_some() || last_modified.is_some());
+        let body = resp.text().await?;
+
+        if cacheable {
+            let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+            if entries.len() >= CONDITIONAL_CACHE_MAX_ENTRIES && !entries.contains_key(&key) {
+                if let Some(oldest) = entries
+                    .iter()
+                    .min_by_key(|(_, c)| c.stored_at)
+                    .map(|(k, _)| k.clone())
+                {
+                    entries.remove(&oldest);
+                }
+            }
+            entries.insert(
+                key,
+                CachedBody {
+                    etag,
+                    last_modified,
+                    body: body.clone(),
+                    stored_at: Instant::now(),
+                },
+            );
+        }
+        Ok(body)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Backend implementations
 // ---------------------------------------------------------------------------
 
-async fn search_duckduckgo(query: &str, count: usize) -> Result<Vec<SearchResult>> {
-    let client = build_client()?;
+const BRAVE_SEARCH_URL: &str = "https://api.search.brave.com/res/v1/web/search";
+const BRAVE_MAX_PAGE: usize = 9;
 
-    // DDG lite HTML endpoint — no JS, no API key
-    let resp = client
-        .get("https://html.duckduckgo.com/html/")
-        .query(&[("q", query)])
+const DUCKDUCKGO_HTML_URL: &str = "https://html.duckduckgo.com/html/";
+const DUCKDUCKGO_LITE_URL: &str = "https://lite.duckduckgo.com/lite/";
+
+const DDG_UNPARSEABLE: &str =
+    "search backend returned no parseable results (markup may have changed)";
+
+async fn search_duckduckgo(
+    client: &reqwest::Client,
+    html_url: &str,
+    lite_url: Option<&str>,
+    query: &str,
+    count: usize,
+    offset: usize,
+) -> Result<SearchPage> {
+    let primary = fetch_duckduckgo(client, html_url, query, count, offset).await;
+    let results = match (primary, lite_url) {
+        (Ok(results), _) => results,
+        (Err(e), Some(lite_url)) => {
+            log::warn!("DuckDuckGo HTML search failed ({:#}); retrying with lite endpoint", e);
+            fetch_duckduckgo(client, lite_url, query, count, offset)
+                .await
+                .context("DuckDuckGo lite fallback failed")?
+        }
+        (Err(e), None) => return Err(e),
+    };
+    Ok(SearchPage::window(results, count))
+}
+
+async fn fetch_duckduckgo(
+    client: &reqwest::Client,
+    url: &str,
+    query: &str,
+    count: usize,
+    offset: usize,
+) -> Result<Vec<SearchResult>> {
+    let mut request = client.get(url).query(&[("q", query)]);
+    if offset > 0 {
+        // Same params the "Next" form on both endpoints submits: s = results to skip.
+        request = request.query(&[("s", offset.to_string()), ("dc", (offset + 1).to_string())]);
+    }
+    let resp = request
         .send()
         .await
         .context("DuckDuckGo request failed")?
+        .error_for_status()
+        .context("DuckDuckGo request failed")?
         .text()
         .await?;
+    parse_duckduckgo_html(&resp, count)
+}
 
-    // Minimal HTML scraping: extract result blocks
-    // Each result looks like:
-    //   <a class="result__a" href="...">title</a>
-    //   <a class="result__snippet">snippet</a>
-    let mut results = Vec::new();
+/// One known DuckDuckGo result markup: how to recognise a result title link and its snippet.
+struct DdgLayout {
+    link_markers: &'static [&'static str],
+    snippet_markers: &'static [&'static str],
+}
+
+/// Layouts tried in order; the first one that yields results wins.
+const DDG_LAYOUTS: &[DdgLayout] = &[
+    // html.duckduckgo.com: <a class="result__a" href="...">title</a> ... <a class="result__snippet">
+    DdgLayout {
+        link_markers: &[r#"class="result__a""#],
+        snippet_markers: &[r#"class="result__snippet""#],
+    },
+    // Newer result markup: <a data-testid="result-title-a" ...> ... <div data-result="snippet">
+    DdgLayout {
+        link_markers: &[r#"data-testid="result-title-a""#],
+        snippet_markers: &[r#"data-result="snippet""#],
+    },
+    // lite.duckduckgo.com: <a href="..." class='result-link'> ... <td class='result-snippet'>
+    DdgLayout {
+        link_markers: &["class='result-link'", r#"class="result-link""#],
+        snippet_markers: &["class='result-snippet'", r#"class="result-snippet""#],
+    },
+];
+
+/// Parse a DuckDuckGo results page. A page that explicitly says there are no results yields an
+/// empty list; a page none of the known layouts can parse is an error rather than a silent empty.
+fn parse_duckduckgo_html(html: &str, count: usize) -> Result<Vec<SearchResult>> {
+    for layout in DDG_LAYOUTS {
+        let results = parse_ddg_layout(html, layout, count);
+        if !results.is_empty() {
+            return Ok(results);
+        }
+    }
+    if html.contains(r#"class="no-results""#) || html.contains("No results.") {
+        return Ok(Vec::new());
+    }
+    anyhow::bail!(DDG_UNPARSEABLE)
+}
+
+fn parse_ddg_layout(html: &str, layout: &DdgLayout, count: usize) -> Vec<SearchResult> {
+    // (tag start, tag end) of every anchor matching the layout's link markers
+    let mut anchors = Vec::new();
+    let mut pos = 0;
+    while let Some(rel) = html[pos..].find("<a ") {
+        let start = pos + rel;
+        let Some(tag_len) = html[start..].find('>') else {
+            break;
+        };
+        let end = start + tag_len;
+        if layout.link_markers.iter().any(|m| html[start..end].contains(m)) {
+            anchors.push((start, end));
+        }
+        pos = end;
+    }
 
-    // Split on result anchors
-    for chunk in resp.split(r#"class="result__a""#).skip(1) {
+    let mut results = Vec::new();
+    for (i, &(start, end)) in anchors.iter().enumerate() {
         if results.len() >= count {
             break;
         }
-        let url = extract_between(chunk, r#"href=""#, '"').unwrap_or_default();
-        let title = extract_between(chunk, ">", '<').unwrap_or_default();
-        let snippet = extract_between(chunk, r#"class="result__snippet""#, '<')
-            .and_then(|s| extract_between(s, ">", '<'))
-            .unwrap_or_default();
+        let tag = &html[start..end];
+        let url = attr_value(tag, "href").unwrap_or_default();
+        let body = &html[end + 1..];
+        let title = strip_tags(&body[..body.find("</a>").unwrap_or(0)]);
 
-        // DDG wraps URLs in a redirect; unwrap uddg= param when present
-        let clean_url = if url.contains("uddg=") {
-            url.split("uddg=")
-                .nth(1)
-                .and_then(|s| s.split('&').next())
-                .map(|s| urlencoding_decode(s))
-                .unwrap_or_else(|| url.to_string())
-        } else {
-            url.to_string()
-        };
+        // The snippet belongs to this result if it appears before the next result link.
+        let region_end = anchors.get(i + 1).map(|&(s, _)| s).unwrap_or(html.len());
+        let region = &html[end + 1..region_end];
+        let snippet = layout
+            .snippet_markers
+            .iter()
+            .find_map(|m| region.find(m).map(|p| &region[p..]))
+            .and_then(|s| s.find('>').map(|p| &s[p + 1..]))
+            .map(|s| {
+                let close = ["</a>", "</td>", "</div>", "</span>"]
+                    .iter()
+                    .filter_map(|c| s.find(c))
+                    .min()
+                    .unwrap_or(s.len());
+                strip_tags(&s[..close])
+            })
+            .unwrap_or_default();
 
+        let url = html_unescape(&url);
+        // Sponsored results go through the ad redirect; skip them.
+        if url.contains("duckduckgo.com/y.js") {
+            continue;
+        }
+        let clean_url = clean_duckduckgo_url(&url);
         if !clean_url.is_empty() && !title.is_empty() {
             results.push(SearchResult {
-                title: html_unescape(title.trim()),
+                title: html_unescape(&title),
                 url: clean_url,
-                snippet: html_unescape(snippet.trim()),
+                snippet: html_unescape(&snippet),
             });
         }
     }
+    results
+}
 
-    Ok(results)
+/// DDG wraps URLs in a redirect; unwrap the uddg= param when present.
+fn clean_duckduckgo_url(url: &str) -> String {
+    if url.contains("uddg=") {
+        url.split("uddg=")
+            .nth(1)
+            .and_then(|s| s.split('&').next())
+            .map(urlencoding_decode)
+            .unwrap_or_else(|| url.to_string())
+    } else if let Some(rest) = url.strip_prefix("//") {
+        format!("https://{}", rest)
+    } else {
+        url.to_string()
+    }
 }
 
-async fn search_searxng(base_url: &str, query: &str, count: usize) -> Result<Vec<SearchResult>> {
-    let client = build_client()?;
+/// Most SearxNG pages fetched for one call, so a large `count` can't fan out unbounded.
+const SEARXNG_MAX_PAGES: usize = 10;
+
+/// SearxNG pages by number (1-based) with an instance-defined page size, independent of
+/// `count`. The first page gives the page size; pages are then fetched from the one holding
+/// `offset` until `count` results are collected or a short page ends the results.
+async fn search_searxng(
+    client: &reqwest::Client,
+    cache: &ConditionalCache,
+    base_url: &str,
+    query: &str,
+    count: usize,
+    offset: usize,
+) -> Result<SearchPage> {
     let url = format!("{}/search", base_url.trim_end_matches('/'));
 
-    let resp = client
-        .get(&url)
-        .query(&[
-            ("q", query),
-            ("format", "json"),
-            ("categories", "general"),
-        ])
-        .send()
-        .await
-        .context("SearxNG request failed")?
-        .json::<Value>()
+    let first = fetch_searxng_page(client, cache, &url, query, 1).await?;
+    let page_size = first.len();
+    if page_size == 0 || count == 0 {
+        return Ok(SearchPage::default());
+    }
+
+    let mut pageno = offset / page_size + 1;
+    let mut page = if pageno == 1 {
+        first
+    } else {
+        fetch_searxng_page(client, cache, &url, query, pageno).await?
+    };
+    let mut skip = offset % page_size;
+    let mut results = Vec::new();
+    let mut fetched = 1;
+    loop {
+        let page_len = page.len();
+        results.extend(page.into_iter().skip(skip));
+        skip = 0;
+        if results.len() > count {
+            results.truncate(count);
+            return Ok(SearchPage { results, has_more: true });
+        }
+        // A short page is the last one; a full page may be followed by more.
+        let last_page = page_len < page_size;
+        if results.len() == count || last_page || fetched >= SEARXNG_MAX_PAGES {
+            return Ok(SearchPage { results, has_more: !last_page });
+        }
+        pageno += 1;
+        fetched += 1;
+        page = fetch_searxng_page(client, cache, &url, query, pageno).await?;
+    }
+}
+
+async fn fetch_searxng_page(
+    client: &reqwest::Client,
+    cache: &ConditionalCache,
+    url: &str,
+    query: &str,
+    pageno: usize,
+) -> Result<Vec<SearchResult>> {
+    let pageno = pageno.to_string();
+    let request = client.get(url).query(&[
+        ("q", query),
+        ("format", "json"),
+        ("categories", "general"),
+        ("pageno", pageno.as_str()),
+    ]);
+    let body = cache
+        .get_text(client, request)
         .await
-        .context("SearxNG response parse failed")?;
+        .context("SearxNG request failed")?;
+    let resp: Value = serde_json::from_str(&body).context("SearxNG response parse failed")?;
 
-    let results = resp["results"]
+    Ok(resp["results"]
         .as_array()
         .map(|arr| {
             arr.iter()
-                .take(count)
                 .map(|r| SearchResult {
                     title: r["title"].as_str().unwrap_or("").to_string(),
                     url: r["url"].as_str().unwrap_or("").to_string(),
@@ -111,24 +390,34 @@ async fn search_searxng(base_url: &str, query: &str, count: usize) -> Result<Vec
                 })
                 .collect()
         })
-        .unwrap_or_default();
-
-    Ok(results)
+        .unwrap_or_default())
 }
 
-async fn search_brave(api_key: &str, query: &str, count: usize) -> Result<Vec<SearchResult>> {
-    let client = build_client()?;
-
-    let resp = client
-        .get("https://api.search.brave.com/res/v1/web/search")
-        .header("X-Subscription-Token", api_key)
-        .query(&[("q", query), ("count", &count.to_string())])
-        .send()
-        .await
-        .context("Brave Search request failed")?
-        .json::<Value>()
+async fn search_brave(
+    client: &reqwest::Client,
+    cache: &ConditionalCache,
+    endpoint: &str,
+    api_key: &str,
+    query: &str,
+    count: usize,
+    offset: usize,
+) -> Result<SearchPage> {
+    // Brave's `offset` is a page index (0-based, at most 9) in units of `count`. An offset
+    // inside a page returns the rest of that page.
+    let page = offset / count.max(1);
+    if page > BRAVE_MAX_PAGE {
+        return Ok(SearchPage::default());
+    }
+    let request = client.get(endpoint).header("X-Subscription-Token", api_key).query(&[
+        ("q", query),
+        ("count", &count.to_string()),
+        ("offset", &page.to_string()),
+    ]);
+    let body = cache
+        .get_text(client, request)
         .await
-        .context("Brave Search response parse failed")?;
+        .context("Brave Search request failed")?;
+    let resp: Value = serde_json::from_str(&body).context("Brave Search response parse failed")?;
 
     let results = resp["web"]["results"]
         .as_array()
@@ -140,20 +429,33 @@ async fn search_brave(api_key: &str, query: &str, count: usize) -> Result<Vec<Se
                     url: r["url"].as_str().unwrap_or("").to_string(),
                     snippet: r["description"].as_str().unwrap_or("").to_string(),
                 })
-                .collect()
+                .collect::<Vec<_>>()
         })
         .unwrap_or_default();
 
-    Ok(results)
+    let has_more = resp["query"]["more_results_available"]
+        .as_bool()
+        .unwrap_or(results.len() >= count);
+    Ok(SearchPage { results, has_more }.skip(offset % count.max(1)))
 }
 
-async fn search_tavily(api_key: &str, query: &str, count: usize) -> Result<Vec<SearchResult>> {
+/// Tavily has no paging; fetch `offset + count` results (max 20) and drop the first `offset`.
+async fn search_tavily(
+    api_key: &str,
+    query: &str,
+    count: usize,
+    offset: usize,
+) -> Result<SearchPage> {
     let client = build_client()?;
     let count = count.min(20);
+    let limit = (offset + count).min(20);
+    if offset >= limit {
+        return Ok(SearchPage::default());
+    }
 
     let body = serde_json::json!({
         "query": query,
-        "max_results": count,
+        "max_results": limit,
         "search_depth": "basic"
     });
 
@@ -173,6 +475,7 @@ async fn search_tavily(api_key: &str, query: &str, count: usize) -> Result<Vec<S
         .as_array()
         .map(|arr| {
             arr.iter()
+                .skip(offset)
                 .take(count)
                 .map(|r| SearchResult {
                     title: r["title"].as_str().unwrap_or("").to_string(),
@@ -183,13 +486,24 @@ async fn search_tavily(api_key: &str, query: &str, count: usize) -> Result<Vec<S
         })
         .unwrap_or_default();
 
-    Ok(results)
+    let mut page = SearchPage::window(results, count);
+    page.has_more &= offset + count < 20;
+    Ok(page)
 }
 
 /// Firecrawl v2 Search API: POST /v2/search, returns data.web[] with title, description, url.
-async fn search_firecrawl(api_key: &str, query: &str, count: usize) -> Result<Vec<SearchResult>> {
+/// No paging either; like Tavily, over-fetch `offset + count` (max 100) and skip.
+async fn search_firecrawl(
+    api_key: &str,
+    query: &str,
+    count: usize,
+    offset: usize,
+) -> Result<SearchPage> {
     let client = build_client()?;
-    let limit = count.min(100);
+    let limit = (offset + count).min(100);
+    if offset >= limit {
+        return Ok(SearchPage::default());
+    }
 
     let body = serde_json::json!({
         "query": query,
@@ -220,6 +534,7 @@ async fn search_firecrawl(api_key: &str, query: &str, count: usize) -> Result<Ve
         .as_array()
         .map(|arr| {
             arr.iter()
+                .skip(offset)
                 .take(count)
                 .map(|r| SearchResult {
                     title: r["title"].as_str().unwrap_or("").to_string(),
@@ -230,7 +545,9 @@ async fn search_firecrawl(api_key: &str, query: &str, count: usize) -> Result<Ve
         })
         .unwrap_or_default();
 
-    Ok(results)
+    let mut page = SearchPage::window(results, count);
+    page.has_more &= offset + count < 100;
+    Ok(page)
 }
 
 // ---------------------------------------------------------------------------
@@ -243,6 +560,27 @@ struct SearchResult {
     snippet: String,
 }
 
+/// One page of results plus whether the backend has more after it.
+#[derive(Default)]
+struct SearchPage {
+    results: Vec<SearchResult>,
+    has_more: bool,
+}
+
+impl SearchPage {
+    /// For backends that don't report it, a full page is taken to mean there may be more.
+    fn window(results: Vec<SearchResult>, count: usize) -> Self {
+        let has_more = count > 0 && results.len() >= count;
+        Self { results, has_more }
+    }
+
+    /// Drop the first `n` results, for offsets that fall inside a backend page.
+    fn skip(mut self, n: usize) -> Self {
+        self.results.drain(..n.min(self.results.len()));
+        self
+    }
+}
+
 fn format_results(results: &[SearchResult]) -> String {
     if results.is_empty() {
         return "No results found.".to_string();
@@ -254,6 +592,22 @@ fn format_results(results: &[SearchResult]) -> String {
         .join("\n")
 }
 
+/// Results followed by a paging line the model can act on.
+fn format_page(page: &SearchPage, count: usize, offset: usize) -> String {
+    let mut out = format_results(&page.results);
+    if page.has_more {
+        let next_offset = offset + page.results.len();
+        out.push_str(&format!(
+            "\n\n[More results available: next page={} (offset={})]",
+            next_offset / count.max(1) + 1,
+            next_offset
+        ));
+    } else if offset > 0 || !page.results.is_empty() {
+        out.push_str("\n\n[No more results]");
+    }
+    out
+}
+
 // ---------------------------------------------------------------------------
 // WebSearchTool
 // ---------------------------------------------------------------------------
@@ -270,6 +624,10 @@ pub struct WebSearchTool {
     pub searxng_url: String,
     /// Max results
     pub count: u32,
+    /// Validators and bodies for conditional requests (Brave, SearxNG).
+    pub http_cache: Arc<ConditionalCache>,
+    /// Retry DuckDuckGo searches against the lite endpoint when the HTML endpoint fails.
+    pub ddg_lite_fallback: bool,
 }
 
 impl WebSearchTool {
@@ -295,6 +653,8 @@ impl WebSearchTool {
             firecrawl_api_key: cfg.firecrawl_api_key.clone(),
             searxng_url: cfg.searxng_url.clone(),
             count: cfg.search_count,
+            http_cache: Arc::new(ConditionalCache::new()),
+            ddg_lite_fallback: cfg.duck_duck_go_lite_fallback,
         }
     }
 }
@@ -321,6 +681,16 @@ impl DynTool for WebSearchTool {
                     "type": "integer",
                     "description": "Number of results to return (default 5)",
                     "default": 5
+                },
+                "page": {
+                    "type": "integer",
+                    "description": "1-based page of results to return (default 1)",
+                    "minimum": 1
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "Number of results to skip; overrides page when set",
+                    "minimum": 0
                 }
             },
             "required": ["query"]
@@ -333,36 +703,61 @@ impl DynTool for WebSearchTool {
             anyhow::bail!("query must not be empty");
         }
         let count = args["count"].as_u64().unwrap_or(self.count as u64) as usize;
+        let offset = match args["offset"].as_u64() {
+            Some(offset) => offset as usize,
+            None => (args["page"].as_u64().unwrap_or(1).max(1) as usize - 1) * count,
+        };
 
-        let results = match &self.backend {
-            WebSearchBackend::DuckDuckGo => search_duckduckgo(query, count).await?,
+        let page = match &self.backend {
+            WebSearchBackend::DuckDuckGo => {
+                let lite = self.ddg_lite_fallback.then_some(DUCKDUCKGO_LITE_URL);
+                search_duckduckgo(&build_client()?, DUCKDUCKGO_HTML_URL, lite, query, count, offset)
+                    .await?
+            }
             WebSearchBackend::SearxNG => {
                 if self.searxng_url.is_empty() {
                     anyhow::bail!("searxng_url is not configured");
                 }
-                search_searxng(&self.searxng_url, query, count).await?
+                search_searxng(
+                    &build_client()?,
+                    &self.http_cache,
+                    &self.searxng_url,
+                    query,
+                    count,
+                    offset,
+                )
+                .await?
             }
             WebSearchBackend::Brave => {
                 if self.brave_api_key.is_empty() {
                     anyhow::bail!("brave_api_key is not configured");
                 }
-                search_brave(&self.brave_api_key, query, count).await?
+                search_brave(
+                    &build_client()?,
+                    &self.http_cache,
+                    BRAVE_SEARCH_URL,
+                    &self.brave_api_key,
+                    query,
+                    count,
+                    offset,
+                )
+                .await?
             }
             WebSearchBackend::Tavily => {
                 if self.tavily_api_key.is_empty() {
                     anyhow::bail!("tavily_api_key is not configured");
                 }
-                search_tavily(&self.tavily_api_key, query, count).await?
+                search_tavily(&self.tavily_api_key, query, count, offset).await?
             }
             WebSearchBackend::Firecrawl => {
                 if self.firecrawl_api_key.is_empty() {
                     anyhow::bail!("firecrawl_api_key is not configured");
                 }
-                search_firecrawl(&self.firecrawl_api_key, query, count).await?
+                search_firecrawl(&self.firecrawl_api_key, query, count, offset).await?
             }
         };
 
-        Ok(format_results(&results))
+        Ok(format_page(&page, count, offset))
     }
 }
 
@@ -370,7 +765,36 @@ impl DynTool for WebSearchTool {
 // WebFetchTool
 // ---------------------------------------------------------------------------
 
-pub struct WebFetchTool;
+#[derive(Default)]
+pub struct WebFetchTool {
+    /// Hosts that may be fetched, redirects included (`tools.web.fetchAllowedHosts`); empty allows any.
+    pub allowed_hosts: Vec<String>,
+    /// Validators and bodies of fetched pages, so refetching an unchanged page is a 304.
+    pub http_cache: Arc<ConditionalCache>,
+}
+
+impl WebFetchTool {
+    pub fn from_config(cfg: &crate::config::WebToolConfig) -> Self {
+        Self {
+            allowed_hosts: cfg.fetch_allowed_hosts.clone(),
+            http_cache: Arc::new(ConditionalCache::new()),
+        }
+    }
+}
+
+/// Follows up to 10 redirects (reqwest's default), each only to an allowed host.
+fn allowlist_redirect_policy(allowed_hosts: Vec<String>) -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() >= 10 {
+            attempt.error("too many redirects")
+        } else if crate::url_utils::host_allowed(attempt.url().as_str(), &allowed_hosts).unwrap_or(false) {
+            attempt.follow()
+        } else {
+            let msg = format!("redirect to {} is outside tools.web.fetchAllowedHosts", attempt.url());
+            attempt.error(msg)
+        }
+    })
+}
 
 #[async_trait::async_trait]
 impl DynTool for WebFetchTool {
@@ -403,11 +827,19 @@ impl DynTool for WebFetchTool {
 
         let url = crate::url_utils::normalize_http_url(raw_url)
             .with_context(|| format!("invalid or unsupported URL: {}", raw_url))?;
+        if !crate::url_utils::host_allowed(&url, &self.allowed_hosts)? {
+            anyhow::bail!("host of {} is not in tools.web.fetchAllowedHosts", url);
+        }
+        if crate::url_utils::url_host(&url).is_ok_and(|h| h.is_mixed_script()) {
+            tracing::warn!(url = %url, "web_fetch host mixes Latin/Greek/Cyrillic letters (possible IDN homograph)");
+        }
 
         let client = reqwest::Client::builder()
             .timeout(std::time::Duration::from_secs(30))
+            .redirect(allowlist_redirect_policy(self.allowed_hosts.clone()))
             .build()?;
-        let mut content = client.get(&url).send().await?.text().await?;
+        let fetch = self.http_cache.get_text(&client, client.get(&url));
+        let mut content = crate::tools::context::cancellable(fetch).await??;
 
         if content.len() > max_chars {
             content.truncate(max_chars);
@@ -421,11 +853,42 @@ impl DynTool for WebFetchTool {
 // Tiny HTML helpers (avoid pulling in a full HTML parser)
 // ---------------------------------------------------------------------------
 
-fn extract_between<'a>(s: &'a str, start: &str, end: char) -> Option<&'a str> {
-    let pos = s.find(start)?;
-    let after = &s[pos + start.len()..];
-    let end_pos = after.find(end)?;
-    Some(&after[..end_pos])
+/// Value of `name="..."` or `name='...'` inside a single tag.
+fn attr_value(tag: &str, name: &str) -> Option<String> {
+    let needle = format!("{}=", name);
+    let mut search = tag;
+    while let Some(pos) = search.find(&needle) {
+        // Require a word boundary so `href` does not match `data-href`.
+        let boundary = search[..pos]
+            .chars()
+            .last()
+            .is_none_or(|c| c.is_whitespace());
+        let after = &search[pos + needle.len()..];
+        if boundary {
+            let quote = after.chars().next()?;
+            if quote == '"' || quote == '\'' {
+                let value = &after[1..];
+                return value.find(quote).map(|end| value[..end].to_string());
+            }
+        }
+        search = after;
+    }
+    None
+}
+
+/// Drop markup and collapse whitespace: `<b>Rust</b>\n  lang` -> `Rust lang`.
+fn strip_tags(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
 fn html_unescape(s: &str) -> String {
@@ -456,3 +919,372 @@ fn urlencoding_decode(s: &str) -> String {
     }
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Response from the mock server: status, extra headers, body.
+    type MockResponse = (u16, Vec<(&'static str, String)>, String);
+
+    /// Minimal HTTP/1.1 server for tests: answers every request with `respond(request_head)`.
+    async fn spawn_mock<F>(respond: F) -> String
+    where
+        F: Fn(&str) -> MockResponse + Send + Sync + 'static,
+    {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let respond = Arc::new(respond);
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    break;
+                };
+                let respond = Arc::clone(&respond);
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 1024];
+                    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match socket.read(&mut chunk).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                        }
+                    }
+                    let head = String::from_utf8_lossy(&buf).to_string();
+                    let (status, headers, body) = respond(&head);
+                    let mut out = format!(
+                        "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n",
+                        status,
+                        body.len()
+                    );
+                    for (k, v) in headers {
+                        out.push_str(&format!("{}: {}\r\n", k, v));
+                    }
+                    out.push_str("\r\n");
+                    out.push_str(&body);
+                    let _ = socket.write_all(out.as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    fn test_client() -> reqwest::Client {
+        reqwest::Client::builder().no_proxy().build().unwrap()
+    }
+
+    fn header_value<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+        head.lines().find_map(|line| {
+            let (k, v) = line.split_once(':')?;
+            k.trim().eq_ignore_ascii_case(name).then(|| v.trim())
+        })
+    }
+
+    #[tokio::test]
+    async fn fetch_refuses_hosts_outside_allowlist() {
+        let tool = WebFetchTool {
+            allowed_hosts: vec!["docs.rs".to_string(), "10.0.0.5".to_string()],
+            ..Default::default()
+        };
+        for url in ["http://127.0.0.1/", "http://[::1]:8080/", "http://[::ffff:127.0.0.1]/", "https://docs.rs.evil.io/"] {
+            let err = tool.call(json!({ "url": url })).await.unwrap_err();
+            assert!(err.to_string().contains("fetchAllowedHosts"), "{}: {}", url, err);
+        }
+    }
+
+    #[tokio::test]
+    async fn searxng_reuses_cached_body_on_304() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let hits_srv = Arc::clone(&hits);
+        let base = spawn_mock(move |head| {
+            // The single-result first page reads as full, so page 2 is asked for too
+            if query_param(head, "pageno").as_deref() != Some("1") {
+                return (200, vec![], json!({"results": []}).to_string());
+            }
+            let n = hits_srv.fetch_add(1, Ordering::SeqCst);
+            if header_value(head, "if-none-match") == Some("\"v1\"") {
+                return (304, vec![("ETag", "\"v1\"".to_string())], String::new());
+            }
+            assert_eq!(n, 0, "second request must be conditional");
+            let body = json!({"results": [{"title": "Rust", "url": "https://rust-lang.org", "content": "A language"}]});
+            (200, vec![("ETag", "\"v1\"".to_string())], body.to_string())
+        })
+        .await;
+
+        let client = test_client();
+        let cache = ConditionalCache::new();
+        let first = search_searxng(&client, &cache, &base, "rust", 5, 0).await.unwrap();
+        let second = search_searxng(&client, &cache, &base, "rust", 5, 0).await.unwrap();
+
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+        assert_eq!(first.results.len(), 1);
+        assert_eq!(second.results.len(), 1);
+        assert_eq!(second.results[0].url, "https://rust-lang.org");
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn brave_sends_if_modified_since_and_uses_cache() {
+        let base = spawn_mock(|head| {
+            if header_value(head, "if-modified-since") == Some("Wed, 21 Oct 2026 07:28:00 GMT") {
+                return (304, vec![], String::new());
+            }
+            let body = json!({"web": {"results": [{"title": "Brave", "url": "https://brave.com", "description": "d"}]}});
+            (
+                200,
+                vec![("Last-Modified", "Wed, 21 Oct 2026 07:28:00 GMT".to_string())],
+                body.to_string(),
+            )
+        })
+        .await;
+
+        let client = test_client();
+        let cache = ConditionalCache::new();
+        let endpoint = format!("{}/res/v1/web/search", base);
+        search_brave(&client, &cache, &endpoint, "key", "q", 3, 0).await.unwrap();
+        let again = search_brave(&client, &cache, &endpoint, "key", "q", 3, 0).await.unwrap();
+        assert_eq!(again.results.len(), 1);
+        assert_eq!(again.results[0].title, "Brave");
+    }
+
+    #[tokio::test]
+    async fn responses_without_validators_are_not_cached() {
+        let base = spawn_mock(|_| (200, vec![], json!({"results": []}).to_string())).await;
+        let client = test_client();
+        let cache = ConditionalCache::new();
+        search_searxng(&client, &cache, &base, "x", 5, 0).await.unwrap();
+        assert!(cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cache_keys_on_normalized_url() {
+        let base = spawn_mock(|head| {
+            if header_value(head, "if-none-match").is_some() {
+                return (304, vec![], String::new());
+            }
+            let target = head.lines().next().unwrap().split_whitespace().nth(1).unwrap().to_string();
+            (200, vec![("ETag", format!("\"{}\"", target))], target)
+        })
+        .await;
+
+        let client = test_client();
+        let cache = ConditionalCache::new();
+        let urls = ["/page/", "/page", "/q?a=1&b=2", "/q?b=2&a=1"].map(|p| format!("{}{}", base, p));
+        for url in &urls {
+            let body = cache.get_text(&client, client.get(url)).await.unwrap();
+            assert_eq!(body, url.trim_start_matches(base.as_str()), "served another URL's cached body");
+        }
+        assert_eq!(cache.len(), 4);
+        let host_case = base.replace("http://", "HTTP://");
+        let same_page = [format!("{}/page", host_case), format!("{}/page?", base), format!("{}/x/../page#top", base)];
+        for same in same_page {
+            let again = cache.get_text(&client, client.get(&same)).await.unwrap();
+            assert_eq!(again, "/page", "{}", same);
+        }
+        assert_eq!(cache.len(), 4);
+    }
+
+    /// Value of a query parameter in the request line of `head`.
+    fn query_param(head: &str, name: &str) -> Option<String> {
+        let target = head.lines().next()?.split_whitespace().nth(1)?;
+        let query = target.split_once('?')?.1;
+        query.split('&').find_map(|kv| {
+            let (k, v) = kv.split_once('=')?;
+            (k == name).then(|| v.to_string())
+        })
+    }
+
+    #[tokio::test]
+    async fn searxng_pages_return_distinct_results() {
+        let base = spawn_mock(|head| {
+            let page = query_param(head, "pageno").unwrap_or_default();
+            let results: Vec<Value> = match page.as_str() {
+                "1" => (0..2).map(|i| json!({"title": format!("p1-{}", i), "url": format!("https://a/{}", i)})).collect(),
+                "2" => (0..2).map(|i| json!({"title": format!("p2-{}", i), "url": format!("https://b/{}", i)})).collect(),
+                _ => vec![],
+            };
+            (200, vec![], json!({ "results": results }).to_string())
+        })
+        .await;
+        let client = test_client();
+        let cache = ConditionalCache::new();
+
+        let page1 = search_searxng(&client, &cache, &base, "q", 2, 0).await.unwrap();
+        let page2 = search_searxng(&client, &cache, &base, "q", 2, 2).await.unwrap();
+        let page3 = search_searxng(&client, &cache, &base, "q", 2, 4).await.unwrap();
+
+        assert_eq!(page1.results[0].title, "p1-0");
+        assert_eq!(page2.results[0].title, "p2-0");
+        assert!(page1.has_more && page2.has_more);
+        assert!(page3.results.is_empty());
+        assert!(!page3.has_more);
+        assert!(format_page(&page3, 2, 4).ends_with("[No more results]"));
+        assert!(format_page(&page1, 2, 0).contains("next page=2 (offset=2)"));
+
+        // An offset inside a page reads on into the next one (here empty, so the results end)
+        let mid = search_searxng(&client, &cache, &base, "q", 2, 3).await.unwrap();
+        let titles: Vec<&str> = mid.results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, ["p2-1"]);
+        assert!(format_page(&mid, 2, 3).ends_with("[No more results]"));
+    }
+
+    #[tokio::test]
+    async fn searxng_pages_by_instance_page_size_not_count() {
+        // 45 results served 20 per page: pages 1 and 2 full, page 3 short
+        let base = spawn_mock(|head| {
+            let page: usize = query_param(head, "pageno").and_then(|p| p.parse().ok()).unwrap_or(1);
+            let results: Vec<Value> = ((page - 1) * 20..(page * 20).min(45))
+                .map(|i| json!({"title": format!("r{}", i), "url": format!("https://r/{}", i)}))
+                .collect();
+            (200, vec![], json!({ "results": results }).to_string())
+        })
+        .await;
+        let client = test_client();
+        let cache = ConditionalCache::new();
+        let titles = |page: &SearchPage| page.results.iter().map(|r| r.title.clone()).collect::<Vec<_>>();
+
+        // count < page size: the second window is results 5..10 of page 1, not page 2
+        let second = search_searxng(&client, &cache, &base, "q", 5, 5).await.unwrap();
+        assert_eq!(titles(&second), ["r5", "r6", "r7", "r8", "r9"]);
+        assert!(second.has_more);
+
+        // A window spanning two pages
+        let span = search_searxng(&client, &cache, &base, "q", 5, 18).await.unwrap();
+        assert_eq!(titles(&span), ["r18", "r19", "r20", "r21", "r22"]);
+
+        // count > page size: pages are combined and has_more reflects the pages left
+        let big = search_searxng(&client, &cache, &base, "q", 30, 0).await.unwrap();
+        assert_eq!(big.results.len(), 30);
+        assert_eq!(big.results[29].title, "r29");
+        assert!(big.has_more);
+        assert!(format_page(&big, 30, 0).contains("(offset=30)"));
+
+        let rest = search_searxng(&client, &cache, &base, "q", 30, 30).await.unwrap();
+        assert_eq!(rest.results.len(), 15);
+        assert!(!rest.has_more);
+    }
+
+    #[tokio::test]
+    async fn brave_pages_use_offset_and_more_results_flag() {
+        let base = spawn_mock(|head| {
+            let offset = query_param(head, "offset").unwrap_or_default();
+            let body = json!({
+                "query": {"more_results_available": offset == "0"},
+                "web": {"results": [{"title": format!("page-{}", offset), "url": format!("https://brave/{}", offset)}]}
+            });
+            (200, vec![], body.to_string())
+        })
+        .await;
+        let client = test_client();
+        let cache = ConditionalCache::new();
+        let endpoint = format!("{}/res/v1/web/search", base);
+
+        let page1 = search_brave(&client, &cache, &endpoint, "key", "q", 3, 0).await.unwrap();
+        let page2 = search_brave(&client, &cache, &endpoint, "key", "q", 3, 3).await.unwrap();
+        assert_eq!(page1.results[0].title, "page-0");
+        assert_eq!(page2.results[0].title, "page-1");
+        assert!(page1.has_more);
+        assert!(!page2.has_more);
+
+        let beyond = search_brave(&client, &cache, &endpoint, "key", "q", 3, 30).await.unwrap();
+        assert!(beyond.results.is_empty());
+    }
+
+    const DDG_HTML: &str = include_str!("../../tests/fixtures/duckduckgo/html_results.html");
+    const DDG_LITE: &str = include_str!("../../tests/fixtures/duckduckgo/lite_results.html");
+    const DDG_ALT: &str = include_str!("../../tests/fixtures/duckduckgo/alt_results.html");
+
+    #[test]
+    fn parses_duckduckgo_html_layout() {
+        let results = parse_duckduckgo_html(DDG_HTML, 10).unwrap();
+        assert_eq!(results.len(), 3, "ad result must be skipped");
+        assert_eq!(results[0].title, "Rust Programming Language");
+        assert_eq!(results[0].url, "https://www.rust-lang.org/");
+        assert_eq!(
+            results[0].snippet,
+            "A language empowering everyone to build reliable and efficient software."
+        );
+        assert_eq!(results[1].title, "The Rust Programming Language & You");
+        assert_eq!(
+            results[2].url,
+            "https://en.wikipedia.org/wiki/Rust_(programming_language)"
+        );
+        assert!(results[2].snippet.is_empty());
+
+        assert_eq!(parse_duckduckgo_html(DDG_HTML, 1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn parses_duckduckgo_lite_and_alternate_layouts() {
+        for html in [DDG_LITE, DDG_ALT] {
+            let results = parse_duckduckgo_html(html, 10).unwrap();
+            assert_eq!(results.len(), 2);
+            assert_eq!(results[0].title, "Rust Programming Language");
+            assert_eq!(results[0].url, "https://www.rust-lang.org/");
+            assert_eq!(
+                results[0].snippet,
+                "A language empowering everyone to build reliable and efficient software."
+            );
+            assert_eq!(results[1].url, "https://doc.rust-lang.org/book/");
+        }
+    }
+
+    #[test]
+    fn unknown_duckduckgo_markup_is_an_error() {
+        let err = parse_duckduckgo_html("<html><body><div class=\"x\">?</div></body></html>", 5)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("markup may have changed"));
+
+        let empty = parse_duckduckgo_html(r#"<div class="no-results">No results.</div>"#, 5).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[tokio::test]
+    async fn duckduckgo_falls_back_to_lite_endpoint() {
+        let base = spawn_mock(|head| {
+            if head.starts_with("GET /lite/") {
+                (200, vec![], DDG_LITE.to_string())
+            } else {
+                (200, vec![], "<html><body>new layout</body></html>".to_string())
+            }
+        })
+        .await;
+        let client = test_client();
+        let html_url = format!("{}/html/", base);
+        let lite_url = format!("{}/lite/", base);
+
+        let page = search_duckduckgo(&client, &html_url, Some(&lite_url), "rust", 5, 0)
+            .await
+            .unwrap();
+        assert_eq!(page.results.len(), 2);
+
+        let err = search_duckduckgo(&client, &html_url, None, "rust", 5, 0)
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("no parseable results"));
+    }
+
+    #[tokio::test]
+    async fn duckduckgo_second_page_sends_skip_param() {
+        let base = spawn_mock(|head| match query_param(head, "s").as_deref() {
+            None => (200, vec![], DDG_HTML.to_string()),
+            Some("2") => (200, vec![], DDG_ALT.to_string()),
+            Some(_) => (200, vec![], r#"<div class="no-results">No results.</div>"#.to_string()),
+        })
+        .await;
+        let client = test_client();
+        let url = format!("{}/html/", base);
+
+        let page1 = search_duckduckgo(&client, &url, None, "rust", 2, 0).await.unwrap();
+        let page2 = search_duckduckgo(&client, &url, None, "rust", 2, 2).await.unwrap();
+        let page3 = search_duckduckgo(&client, &url, None, "rust", 2, 4).await.unwrap();
+        assert_eq!(page1.results[1].title, "The Rust Programming Language & You");
+        assert_eq!(page2.results[1].title, "The Rust Programming Language");
+        assert!(page3.results.is_empty() && !page3.has_more);
+    }
+}
@@ -1,9 +1,172 @@
 //! URL normalization for HTTP clients (e.g. punycode for IDN) to avoid
-//! "invalid international domain name" errors from reqwest/url.
+//! "invalid international domain name" errors from reqwest/url, and host extraction for
+//! allowlist checks (IPv6 literals, zone ids, IPv4-mapped addresses, IDN hosts compared in
+//! punycode, mixed-script homograph detection).
+
+use std::net::IpAddr;
 
 use anyhow::{Context, Result};
 use url::Url;
 
+/// Canonical form of `url`, so spellings of the same request give one string (cache keys,
+/// comparisons): lowercase scheme and host, no default port, dot segments resolved, no fragment
+/// and no empty query. A trailing slash and the query parameter order are kept, since servers
+/// may answer differently for them. Input that does not parse is returned trimmed.
+pub fn normalize(url: &str) -> String {
+    let s = url.trim();
+    // Parsing does the scheme/host case, default port, IDN and dot-segment work.
+    let Ok(mut u) = Url::parse(s) else {
+        return s.to_string();
+    };
+    u.set_fragment(None);
+    if u.query() == Some("") {
+        u.set_query(None);
+    }
+    u.to_string()
+}
+
+/// Host of a URL or allowlist entry, normalized so equal targets compare equal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlHost {
+    /// Lowercase ASCII domain (IDN labels in punycode) without a trailing dot, so a lookalike
+    /// Unicode host never equals the ASCII name it imitates.
+    Domain(String),
+    /// IP literal. IPv4-mapped IPv6 (`::ffff:10.0.0.1`) is stored as IPv4, and the zone id of a
+    /// scoped IPv6 address (`fe80::1%eth0`) is dropped: it only picks the interface.
+    Ip(IpAddr),
+}
+
+impl UrlHost {
+    /// Parse a bare host: `example.com`, `10.0.0.1`, `::1`, `[::1]`, `[fe80::1%25eth0]`.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let host = raw.trim();
+        let host = host
+            .strip_prefix('[')
+            .and_then(|h| h.strip_suffix(']'))
+            .unwrap_or(host);
+        // Zone ids are written `%eth0`, or `%25eth0` when percent-encoded in a URL.
+        let addr = host.split('%').next().unwrap_or(host);
+        if let Ok(ip) = addr.parse::<IpAddr>() {
+            return Some(Self::Ip(canonical_ip(ip)));
+        }
+        let domain = host.trim_end_matches('.');
+        let (wildcard, name) = match domain.strip_prefix("*.") {
+            Some(name) => ("*.", name),
+            None => ("", domain),
+        };
+        if name.is_empty()
+            || name.contains(|c: char| c.is_whitespace() || "/?#@[]%:\\*".contains(c))
+        {
+            return None;
+        }
+        let ascii = idna::domain_to_ascii(name).ok()?;
+        Some(Self::Domain(format!("{}{}", wildcard, ascii)))
+    }
+
+    /// True when a label of this domain mixes Latin, Greek or Cyrillic letters, as in `exаmple`
+    /// with a Cyrillic `а`: the usual homograph trick. Punycode labels are decoded first.
+    pub fn is_mixed_script(&self) -> bool {
+        let Self::Domain(domain) = self else {
+            return false;
+        };
+        let (unicode, _) = idna::domain_to_unicode(domain);
+        unicode.split('.').any(label_mixes_scripts)
+    }
+
+    /// Whether this allowlist entry admits `host`: the same IP, or the same domain or one of its
+    /// subdomains. A leading `*.` on a domain entry is accepted and means the same thing.
+    pub fn covers(&self, host: &UrlHost) -> bool {
+        match (self, host) {
+            (Self::Ip(entry), Self::Ip(ip)) => entry == ip,
+            (Self::Domain(entry), Self::Domain(domain)) => {
+                let entry = entry.strip_prefix("*.").unwrap_or(entry);
+                domain == entry
+                    || domain
+                        .strip_suffix(entry)
+                        .is_some_and(|prefix| prefix.ends_with('.'))
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+}
+
+/// Script of a letter among those that look alike; `None` for digits, `-` and other scripts.
+fn confusable_script(c: char) -> Option<Script> {
+    match c as u32 {
+        _ if c.is_ascii_alphabetic() => Some(Script::Latin),
+        0x00C0..=0x024F | 0x1E00..=0x1EFF => Some(Script::Latin),
+        0x0370..=0x03FF | 0x1F00..=0x1FFF => Some(Script::Greek),
+        0x0400..=0x052F => Some(Script::Cyrillic),
+        _ => None,
+    }
+}
+
+fn label_mixes_scripts(label: &str) -> bool {
+    let mut scripts = label.chars().filter_map(confusable_script);
+    match scripts.next() {
+        Some(first) => scripts.any(|s| s != first),
+        None => false,
+    }
+}
+
+/// `::ffff:a.b.c.d` reaches the same IPv4 host as `a.b.c.d`, so compare it as IPv4.
+fn canonical_ip(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(IpAddr::V6(v6)),
+        v4 => v4,
+    }
+}
+
+/// Host of an absolute URL, normalized with [`UrlHost::parse`]. Handles bracketed IPv6 with a
+/// port (`http://[::1]:8080/`), userinfo and zone ids, which the `url` crate rejects.
+pub fn url_host(s: &str) -> Result<UrlHost> {
+    if let Ok(u) = Url::parse(s.trim()) {
+        let host = match u.host() {
+            Some(url::Host::Domain(d)) => UrlHost::parse(d),
+            Some(url::Host::Ipv4(ip)) => Some(UrlHost::Ip(IpAddr::V4(ip))),
+            Some(url::Host::Ipv6(ip)) => Some(UrlHost::Ip(canonical_ip(IpAddr::V6(ip)))),
+            None => None,
+        };
+        return host.context("URL has no host");
+    }
+
+    let (_, rest) = s
+        .trim()
+        .split_once("://")
+        .context("URL must include scheme (e.g. https://)")?;
+    let authority = &rest[..rest.find(['/', '?', '#']).unwrap_or(rest.len())];
+    let host_port = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+    let host = if host_port.starts_with('[') {
+        let end = host_port.find(']').context("unclosed '[' in URL host")?;
+        &host_port[..=end]
+    } else if host_port.matches(':').count() > 1 {
+        anyhow::bail!("IPv6 address in URL must be in brackets: {}", host_port);
+    } else {
+        host_port.split(':').next().unwrap_or(host_port)
+    };
+    UrlHost::parse(host).with_context(|| format!("invalid host in URL: {}", host))
+}
+
+/// Whether `url` may be fetched under `allowlist` (hosts, IPs or `*.domain`); an empty list
+/// allows every host. Entries that are not valid hosts never match.
+pub fn host_allowed(url: &str, allowlist: &[String]) -> Result<bool> {
+    if allowlist.is_empty() {
+        return Ok(true);
+    }
+    let host = url_host(url)?;
+    Ok(allowlist
+        .iter()
+        .filter_map(|entry| UrlHost::parse(entry))
+        .any(|entry| entry.covers(&host)))
+}
+
 /// Normalizes a URL string so it is safe to use with reqwest: ensures the host
 /// is ASCII (converts IDN to punycode if needed). Returns the normalized URL string.
 pub fn normalize_http_url(s: &str) -> Result<String> {
@@ -44,7 +207,7 @@ pub fn normalize_http_url(s: &str) -> Result<String> {
         Some(_) => {
             // IPv6 [::1] or [::1]:port
             let bracket_end = authority.find(']').unwrap_or(0) + 1;
-            let host = authority[..bracket_end].trim_start_matches('[').trim_end_matches(']');
+            let host = &authority[..bracket_end];
             let port_part = authority[bracket_end..].trim_start_matches(':');
             (host, port_part)
         }
@@ -112,4 +275,96 @@ mod tests {
         assert!(normalize_http_url("").is_err());
         assert!(normalize_http_url("   ").is_err());
     }
+
+    #[test]
+    fn equivalent_urls_normalize_identically() {
+        let same = [
+            "HTTP://Example.com/./a/../b",
+            "http://example.com:80/b",
+            "http://example.com/c/../b#section",
+            "  http://example.com/b?  ",
+        ];
+        for u in same {
+            assert_eq!(normalize(u), "http://example.com/b", "{}", u);
+        }
+        assert_eq!(normalize("https://example.com:443"), "https://example.com/");
+        assert_eq!(normalize("https://example.com/"), "https://example.com/");
+        assert_eq!(normalize("https://example.com:8443/b"), "https://example.com:8443/b");
+        assert_eq!(normalize("not a url"), "not a url");
+    }
+
+    #[test]
+    fn trailing_slash_and_query_order_are_kept() {
+        assert_eq!(normalize("http://EXAMPLE.com/b/"), "http://example.com/b/");
+        let a = "https://api.example.com/search?q=rust&count=5";
+        let b = "https://api.example.com/search?count=5&q=rust";
+        assert_eq!(normalize(a), a);
+        assert_ne!(normalize(a), normalize(b));
+    }
+
+    fn ip(s: &str) -> UrlHost {
+        UrlHost::Ip(s.parse().unwrap())
+    }
+
+    #[test]
+    fn bracketed_ipv6_host_with_port() {
+        assert_eq!(url_host("http://[::1]:8080/api").unwrap(), ip("::1"));
+        assert_eq!(url_host("https://user@[2001:DB8::1]/").unwrap(), ip("2001:db8::1"));
+        assert!(url_host("http://::1:8080/").is_err());
+        assert!(host_allowed("http://[::1]:8080/", &["::1".to_string()]).unwrap());
+        assert!(host_allowed("http://[::1]:8080/", &["[::1]".to_string()]).unwrap());
+        assert!(!host_allowed("http://[::2]/", &["::1".to_string()]).unwrap());
+    }
+
+    #[test]
+    fn ipv6_zone_id_is_dropped() {
+        assert_eq!(url_host("http://[fe80::1%25eth0]:80/").unwrap(), ip("fe80::1"));
+        assert_eq!(url_host("http://[fe80::1%eth0]/").unwrap(), ip("fe80::1"));
+        assert!(host_allowed("http://[fe80::1%25en0]/", &["fe80::1".to_string()]).unwrap());
+    }
+
+    #[test]
+    fn ipv4_mapped_ipv6_matches_ipv4_entry() {
+        assert_eq!(url_host("http://[::ffff:127.0.0.1]/").unwrap(), ip("127.0.0.1"));
+        assert_eq!(url_host("http://[::ffff:7f00:1]/").unwrap(), ip("127.0.0.1"));
+        let allow = vec!["10.0.0.5".to_string()];
+        assert!(host_allowed("http://[::ffff:10.0.0.5]:9000/", &allow).unwrap());
+        // Blocking by omission works too: a mapped address is not a way around the list.
+        assert!(!host_allowed("http://[::ffff:127.0.0.1]/", &allow).unwrap());
+    }
+
+    #[test]
+    fn idn_hosts_compare_in_punycode() {
+        assert_eq!(
+            UrlHost::parse("Bücher.example"),
+            Some(UrlHost::Domain("xn--bcher-kva.example".to_string()))
+        );
+        assert!(host_allowed("https://xn--bcher-kva.example/", &["bücher.example".to_string()]).unwrap());
+        assert!(host_allowed("https://bücher.example/a", &["xn--bcher-kva.example".to_string()]).unwrap());
+        assert!(!UrlHost::parse("bücher.example").unwrap().is_mixed_script());
+    }
+
+    #[test]
+    fn homograph_is_distinguished_and_flagged() {
+        // "exаmple.com" with a Cyrillic 'а' (U+0430).
+        let spoof = "https://ex\u{0430}mple.com/login";
+        let allow = vec!["example.com".to_string()];
+        assert!(!host_allowed(spoof, &allow).unwrap());
+        let host = url_host(spoof).unwrap();
+        assert!(matches!(&host, UrlHost::Domain(d) if d.starts_with("xn--")));
+        assert!(host.is_mixed_script());
+        assert!(url_host("https://xn--e1afmkfd.xn--p1ai/").map(|h| !h.is_mixed_script()).unwrap());
+        assert!(!url_host("https://example.com/").unwrap().is_mixed_script());
+    }
+
+    #[test]
+    fn domain_entries_cover_subdomains() {
+        let allow = vec!["Example.com.".to_string(), "*.docs.rs".to_string()];
+        assert!(host_allowed("https://example.com/a", &allow).unwrap());
+        assert!(host_allowed("https://API.example.com:8443/", &allow).unwrap());
+        assert!(host_allowed("https://serde.docs.rs/", &allow).unwrap());
+        assert!(!host_allowed("https://badexample.com/", &allow).unwrap());
+        assert!(!host_allowed("https://example.com.evil.io/", &allow).unwrap());
+        assert!(host_allowed("https://anything.io/", &[]).unwrap());
+    }
 }
@@ -1,4 +1,4 @@
-use actix_web::{error::ResponseError, http::StatusCode, web, HttpResponse, Result};
+use actix_web::{error::ResponseError, http::StatusCode, web, HttpMessage, HttpRequest, HttpResponse, Result};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use crate::web::state::AppState;
@@ -66,6 +66,8 @@ pub enum ApiError {
     BadRequest(String),
     InternalError(String),
     Unauthorized(String),
+    /// The edited config failed `validate_config`.
+    InvalidConfig(Vec<crate::config::ValidationError>),
 }
 
 impl fmt::Display for ApiError {
@@ -75,6 +77,9 @@ impl fmt::Display for ApiError {
             ApiError::BadRequest(msg) => write!(f, "Bad request: {}", msg),
             ApiError::InternalError(msg) => write!(f, "Internal error: {}", msg),
             ApiError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
+            ApiError::InvalidConfig(errors) => {
+                write!(f, "Config validation failed: {} error(s)", errors.len())
+            }
         }
     }
 }
@@ -86,16 +91,26 @@ impl ResponseError for ApiError {
             ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
             ApiError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::InvalidConfig(_) => StatusCode::BAD_REQUEST,
         }
     }
 
     fn error_response(&self) -> HttpResponse {
         let status = self.status_code();
+        if let ApiError::InvalidConfig(errors) = self {
+            // The web UI reads the field errors from `data.validationErrors`.
+            return HttpResponse::build(status).json(serde_json::json!({
+                "success": false,
+                "error": "Config validation failed",
+                "data": { "validationErrors": errors }
+            }));
+        }
         let code = match self {
             ApiError::NotFound(_) => "NOT_FOUND",
             ApiError::BadRequest(_) => "BAD_REQUEST",
             ApiError::InternalError(_) => "INTERNAL_ERROR",
             ApiError::Unauthorized(_) => "UNAUTHORIZED",
+            ApiError::InvalidConfig(_) => "VALIDATION_ERROR",
         };
 
         let error_response = ErrorResponse::new(self.to_string(), code.to_string());
@@ -110,6 +125,14 @@ impl From<anyhow::Error> for ApiError {
     }
 }
 
+/// JSON body extractor settings for the API: a malformed, mistyped, or oversized body gets a
+/// 400 with the usual error envelope (`code: BAD_REQUEST`) instead of actix's plain-text reply.
+pub fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default().error_handler(|err, _req| {
+        ApiError::BadRequest(format!("Invalid JSON body: {}", err)).into()
+    })
+}
+
 /// System status response
 #[derive(Serialize)]
 pub struct SystemStatus {
@@ -172,6 +195,23 @@ pub async fn get_status(state: web::Data<AppState>) -> Result<HttpResponse> {
     Ok(HttpResponse::Ok().json(ApiResponse::success(status)))
 }
 
+/// GET /api/version - Returns crate version, git sha, build timestamp, and enabled features
+pub async fn get_version() -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(crate::build_info::build_info())))
+}
+
+/// GET /api/metrics - Returns sandbox counters, gauges and histograms as JSON
+pub async fn get_metrics(state: web::Data<AppState>) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(state.metrics_snapshot())))
+}
+
+/// GET /metrics - Returns the same metrics in the Prometheus text exposition format
+pub async fn get_metrics_prometheus(state: web::Data<AppState>) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4; charset=utf-8")
+        .body(state.metrics_snapshot().to_prometheus()))
+}
+
 /// Paginated response wrapper
 #[derive(Serialize)]
 pub struct PaginatedResponse<T> {
@@ -212,11 +252,71 @@ fn default_page_size() -> usize {
     20
 }
 
+/// Web user of the request: the Basic-auth user, or `web_admin` (the shared web chat user) when auth is off.
+fn web_user(req: &HttpRequest) -> String {
+    req.extensions()
+        .get::<crate::web::AuthenticatedUser>()
+        .map(|u| u.username.clone())
+        .unwrap_or_else(|| crate::web::handlers::ws::WEB_CHAT_USER.to_string())
+}
+
+/// Sessions created through `POST /api/sessions` have the identifier `<user>~<suffix>`.
+const WEB_SESSION_OWNER_SEP: char = '~';
+
+/// Owner of a web session created through the API; `None` for every other session.
+fn web_session_owner(id: &crate::agent::session_id::SessionId) -> Option<&str> {
+    if id.channel != "web" {
+        return None;
+    }
+    id.identifier
+        .as_deref()?
+        .split_once(WEB_SESSION_OWNER_SEP)
+        .map(|(owner, _)| owner)
+}
+
+/// Query of the web chat endpoints (`/ws/chat`, `/api/stream`): `session` selects one of the
+/// user's sessions from `POST /api/sessions`; without it the shared web chat is used.
+#[derive(Debug, Default, Deserialize)]
+pub struct WebChatQuery {
+    pub session: Option<String>,
+}
+
+/// User and chat id of the web chat selected by `session`: the user's web session (its
+/// identifier is the chat id), or the shared `web_admin` chat when `session` is `None`.
+/// Sessions of other users and non-web sessions are not found.
+pub(crate) async fn web_chat_target(
+    req: &HttpRequest,
+    state: &AppState,
+    session: Option<&str>,
+) -> std::result::Result<(String, String), ApiError> {
+    let Some(session) = session else {
+        let shared = crate::web::handlers::ws::WEB_CHAT_USER.to_string();
+        return Ok((shared.clone(), shared));
+    };
+    let session_id = crate::agent::session_id::SessionId::parse(session)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid session ID: {}", e)))?;
+    let user = web_user(req);
+    let exists = state.session_manager.read().await.get_meta(&session_id).is_some();
+    match (web_session_owner(&session_id), session_id.identifier.as_deref()) {
+        (Some(owner), Some(chat_id)) if owner == user && exists => Ok((user, chat_id.to_string())),
+        _ => Err(ApiError::NotFound(format!("Session not found: {}", session))),
+    }
+}
+
+/// Web sessions created by another user are hidden; all other sessions are visible.
+fn session_visible_to(id: &crate::agent::session_id::SessionId, user: &str) -> bool {
+    web_session_owner(id).is_none_or(|owner| owner == user)
+}
+
 /// GET /api/sessions - Returns session list with pagination and filtering
+///
+/// Web sessions created by other users through `POST /api/sessions` are not listed.
 pub async fn get_sessions(
+    req: HttpRequest,
     state: web::Data<AppState>,
     query: web::Query<SessionQuery>,
 ) -> Result<HttpResponse> {
+    let user = web_user(&req);
     let sm = state.session_manager.read().await;
     
     // Get all sessions from SessionManager
@@ -226,6 +326,9 @@ pub async fn get_sessions(
     let filtered: Vec<SessionSummary> = all_sessions
         .into_iter()
         .filter_map(|(meta, message_count)| {
+            if !session_visible_to(&meta.id, &user) {
+                return None;
+            }
             // Apply channel filter
             if let Some(ref channel) = query.channel {
                 if &meta.id.channel != channel {
@@ -324,6 +427,7 @@ pub struct SessionMessageDto {
 
 /// GET /api/sessions/{id} - Returns session details with message history
 pub async fn get_session_by_id(
+    req: HttpRequest,
     state: web::Data<AppState>,
     path: web::Path<String>,
 ) -> Result<HttpResponse> {
@@ -337,6 +441,7 @@ pub async fn get_session_by_id(
     
     // Get session metadata
     let meta = sm.get_meta(&session_id)
+        .filter(|meta| session_visible_to(&meta.id, &web_user(&req)))
         .ok_or_else(|| ApiError::NotFound(format!("Session not found: {}", session_id_str)))?;
     
     // Get message history
@@ -382,6 +487,198 @@ pub async fn get_session_by_id(
     Ok(HttpResponse::Ok().json(ApiResponse::success(detail)))
 }
 
+fn session_meta_dto(meta: &crate::agent::session_manager::SessionMeta) -> SessionMetaDto {
+    SessionMetaDto {
+        id: meta.id.format(),
+        channel: meta.id.channel.clone(),
+        scope: match meta.id.scope.as_ref() {
+            Some(crate::agent::session_id::SessionScope::Dm) => "dm",
+            Some(crate::agent::session_id::SessionScope::Group) => "group",
+            Some(crate::agent::session_id::SessionScope::Topic) => "topic",
+            None => "",
+        }
+        .to_string(),
+        identifier: meta.id.identifier.clone().unwrap_or_default(),
+        created_at: meta.created_at,
+        updated_at: meta.updated_at,
+    }
+}
+
+/// POST /api/sessions - Create a new web conversation session for the authenticated user
+///
+/// The session belongs to the main agent and is only visible to the user who created it.
+pub async fn create_session(req: HttpRequest, state: web::Data<AppState>) -> Result<HttpResponse> {
+    let user = web_user(&req);
+    let suffix = uuid::Uuid::new_v4().simple().to_string();
+    let identifier = format!("{}{}{}", user, WEB_SESSION_OWNER_SEP, &suffix[..12]);
+    let session_id = crate::agent::session_id::SessionId::full(
+        "main",
+        "web",
+        crate::agent::session_id::SessionScope::Dm,
+        &identifier,
+    );
+
+    let mut sm = state.session_manager.write().await;
+    sm.get_or_create(&session_id);
+    let meta = sm
+        .get_meta(&session_id)
+        .ok_or_else(|| ApiError::InternalError("Session was not created".to_string()))?;
+    Ok(HttpResponse::Created().json(ApiResponse::success(session_meta_dto(meta))))
+}
+
+/// DELETE /api/sessions/{id} - Delete one of the user's web sessions
+///
+/// Only web sessions can be deleted here; sessions created by another user are reported as not found.
+pub async fn delete_session(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let session_id_str = path.into_inner();
+    let session_id = crate::agent::session_id::SessionId::parse(&session_id_str)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid session ID: {}", e)))?;
+
+    let mut sm = state.session_manager.write().await;
+    let meta = sm
+        .get_meta(&session_id)
+        .filter(|meta| session_visible_to(&meta.id, &web_user(&req)))
+        .map(session_meta_dto)
+        .ok_or_else(|| ApiError::NotFound(format!("Session not found: {}", session_id_str)))?;
+    if session_id.channel != "web" {
+        return Err(ApiError::BadRequest(format!(
+            "Only web sessions can be deleted: {}",
+            session_id_str
+        ))
+        .into());
+    }
+    sm.remove_session(&session_id);
+    Ok(HttpResponse::Ok().json(ApiResponse::success(meta)))
+}
+
+/// Result of a cancel request for a session's running turn
+#[derive(Serialize)]
+pub struct CancelSessionResponse {
+    pub id: String,
+    pub channel: String,
+    pub chat_id: String,
+}
+
+/// POST /api/sessions/{id}/cancel - Abort the in-flight agent turn of a session
+///
+/// Sends `/cancel` for the session's channel and chat through the bus, so the agent loop
+/// cancels the turn exactly as if the user had typed it in the chat. Web sessions created by
+/// another user are reported as not found.
+pub async fn cancel_session(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let session_id_str = path.into_inner();
+
+    let session_id = crate::agent::session_id::SessionId::parse(&session_id_str)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid session ID: {}", e)))?;
+    if !session_visible_to(&session_id, &web_user(&req)) {
+        return Err(ApiError::NotFound(format!("Session not found: {}", session_id_str)).into());
+    }
+    let chat_id = session_id.identifier.clone().ok_or_else(|| {
+        ApiError::BadRequest(format!(
+            "Session ID has no chat identifier (expected agent:<agentId>:<channel>:<scope>:<identifier>): {}",
+            session_id_str
+        ))
+    })?;
+
+    let msg = crate::bus::InboundMessage {
+        channel: session_id.channel.clone(),
+        sender_id: "web-api".to_string(),
+        chat_id: chat_id.clone(),
+        content: "/cancel".to_string(),
+        timestamp: Utc::now(),
+        media: vec![],
+        metadata: serde_json::json!({ "source": "api" }),
+    };
+    state
+        .inbound_tx
+        .send(msg)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("Failed to send cancel request: {}", e)))?;
+
+    Ok(HttpResponse::Accepted().json(ApiResponse::success(CancelSessionResponse {
+        id: session_id_str,
+        channel: session_id.channel,
+        chat_id,
+    })))
+}
+
+/// GET /api/turns - Returns the agent turns running right now (oldest first)
+pub async fn get_turns(state: web::Data<AppState>) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(state.active_turns.list())))
+}
+
+/// DELETE /api/turns/{id} - Cancel a running turn through its cancellation token
+///
+/// The chat is told that an operator stopped the turn; queued messages of the chat still run.
+pub async fn kill_turn(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let id = path.into_inner();
+    let turn = state
+        .active_turns
+        .cancel(&id)
+        .ok_or_else(|| ApiError::NotFound(format!("No running turn with id {}", id)))?;
+    let _ = state.outbound_tx.send(crate::bus::OutboundMessage::chat(
+        turn.channel.clone(),
+        turn.chat_id.clone(),
+        "[Control] Cancelled. The current turn was stopped by an operator.".to_string(),
+        vec![],
+        None,
+    ));
+    Ok(HttpResponse::Ok().json(ApiResponse::success(turn)))
+}
+
+/// Dead-letter queue contents for API responses
+#[derive(Serialize)]
+pub struct DeadLettersInfo {
+    /// Channels currently down, with the reason.
+    pub channels_down: std::collections::HashMap<String, String>,
+    /// Messages waiting for their channel to recover (oldest first).
+    pub messages: Vec<crate::bus::DeadLetter>,
+}
+
+/// GET /api/deadletters - Outbound messages held back because their channel is down
+pub async fn get_dead_letters(state: web::Data<AppState>) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(DeadLettersInfo {
+        channels_down: state.dead_letters.down_channels(),
+        messages: state.dead_letters.list(),
+    })))
+}
+
+/// GET /api/sandbox/quarantine - Sandboxes stopped for security violations
+pub async fn get_quarantined_sandboxes(state: web::Data<AppState>) -> Result<HttpResponse> {
+    let ids = state
+        .sandbox_manager
+        .as_ref()
+        .map(|m| m.quarantined_sandboxes())
+        .unwrap_or_default();
+    Ok(HttpResponse::Ok().json(ApiResponse::success(ids)))
+}
+
+/// DELETE /api/sandbox/quarantine/{id} - Allow executions in a quarantined sandbox again
+pub async fn release_quarantined_sandbox(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let id = path.into_inner();
+    let released = state
+        .sandbox_manager
+        .as_ref()
+        .is_some_and(|m| m.release_quarantine(&id));
+    if !released {
+        return Err(ApiError::NotFound(format!("Sandbox {} is not quarantined", id)).into());
+    }
+    Ok(HttpResponse::Ok().json(ApiResponse::success(id)))
+}
+
 /// Channel information for API responses
 #[derive(Serialize)]
 pub struct ChannelInfo {
@@ -483,6 +780,20 @@ pub async fn get_channels(state: web::Data<AppState>) -> Result<HttpResponse> {
             });
         }
     }
+    // The inbound route is served by this server; the outbound side is as good as the last POST.
+    for c in &cfg.channels.webhook {
+        channels.push(ChannelInfo {
+            name: c.name.clone(),
+            enabled: c.enabled,
+            status: if !c.enabled {
+                ChannelStatus::Disabled
+            } else if state.dead_letters.is_down(&c.name) {
+                ChannelStatus::Error
+            } else {
+                ChannelStatus::Connected
+            },
+        });
+    }
     Ok(HttpResponse::Ok().json(ApiResponse::success(channels)))
 }
 
@@ -593,6 +904,188 @@ pub async fn update_cron_job(
     Ok(HttpResponse::Ok().json(ApiResponse::success(job_info)))
 }
 
+/// Heartbeat runtime state
+#[derive(Serialize)]
+pub struct HeartbeatStatus {
+    pub enabled: bool,
+    pub paused: bool,
+    pub interval: u64,
+    pub task_count: usize,
+}
+
+async fn heartbeat_status(state: &AppState) -> HeartbeatStatus {
+    let cfg = state.config.read().await;
+    HeartbeatStatus {
+        enabled: cfg.heartbeat.enabled,
+        paused: state.heartbeat_control.is_paused(),
+        interval: cfg.heartbeat.interval,
+        task_count: cfg.heartbeat.tasks.len(),
+    }
+}
+
+/// GET /api/heartbeat - Returns heartbeat enabled/paused state
+pub async fn get_heartbeat_status(state: web::Data<AppState>) -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiResponse::success(heartbeat_status(&state).await)))
+}
+
+/// POST /api/heartbeat/pause - Skip scheduled heartbeat runs until resumed
+pub async fn pause_heartbeat(state: web::Data<AppState>) -> Result<HttpResponse> {
+    state.heartbeat_control.pause();
+    Ok(HttpResponse::Ok().json(ApiResponse::success(heartbeat_status(&state).await)))
+}
+
+/// POST /api/heartbeat/resume - Resume scheduled heartbeat runs
+pub async fn resume_heartbeat(state: web::Data<AppState>) -> Result<HttpResponse> {
+    state.heartbeat_control.resume();
+    Ok(HttpResponse::Ok().json(ApiResponse::success(heartbeat_status(&state).await)))
+}
+
+/// POST /api/heartbeat/run-now - Run all heartbeat tasks immediately (also while paused)
+pub async fn run_heartbeat_now(state: web::Data<AppState>) -> Result<HttpResponse> {
+    let status = heartbeat_status(&state).await;
+    if !status.enabled {
+        return Err(ApiError::BadRequest("Heartbeat is disabled in config".to_string()).into());
+    }
+    state.heartbeat_control.run_now();
+    Ok(HttpResponse::Accepted().json(ApiResponse::success(status)))
+}
+
+/// A heartbeat or config cron task with its position in the config list, which is the id
+/// used by the PUT / DELETE endpoints.
+#[derive(Serialize)]
+pub struct IndexedTask<T> {
+    pub index: usize,
+    #[serde(flatten)]
+    pub task: T,
+}
+
+fn indexed<T: Clone>(tasks: &[T]) -> Vec<IndexedTask<T>> {
+    tasks
+        .iter()
+        .cloned()
+        .enumerate()
+        .map(|(index, task)| IndexedTask { index, task })
+        .collect()
+}
+
+enum TaskEdit<T> {
+    Add(T),
+    Replace(usize, T),
+    Remove(usize),
+}
+
+/// Apply `edit` to the task list picked by `tasks` on a copy of the running config, validate
+/// and save it, then swap it in. The cron runner and heartbeat read the shared config on each
+/// tick, so the change takes effect without a restart. Returns the list after the edit.
+async fn edit_config_tasks<T: Clone>(
+    state: &AppState,
+    tasks: fn(&mut crate::config::Config) -> &mut Vec<T>,
+    edit: TaskEdit<T>,
+) -> std::result::Result<Vec<T>, ApiError> {
+    use crate::config::{save_config, validate_config};
+
+    let mut running = state.config.write().await;
+    let mut updated = running.clone();
+    let list = tasks(&mut updated);
+    let missing = |index: usize| ApiError::NotFound(format!("No task at index {}", index));
+    match edit {
+        TaskEdit::Add(task) => list.push(task),
+        TaskEdit::Replace(index, task) => *list.get_mut(index).ok_or_else(|| missing(index))? = task,
+        TaskEdit::Remove(index) => {
+            if index >= list.len() {
+                return Err(missing(index));
+            }
+            list.remove(index);
+        }
+    }
+    validate_config(&updated).map_err(ApiError::InvalidConfig)?;
+    save_config(&updated, Some(state.config_path.as_path()))
+        .map_err(|e| ApiError::InternalError(format!("Failed to write config file: {}", e)))?;
+    let list = tasks(&mut updated).clone();
+    *running = updated;
+    Ok(list)
+}
+
+fn cron_tasks(cfg: &mut crate::config::Config) -> &mut Vec<crate::config::CronTaskConfig> {
+    &mut cfg.cron.tasks
+}
+
+fn heartbeat_tasks(cfg: &mut crate::config::Config) -> &mut Vec<crate::config::HeartbeatTask> {
+    &mut cfg.heartbeat.tasks
+}
+
+/// GET /api/cron/tasks - Returns the scheduled tasks from `cron.tasks` in config
+pub async fn get_cron_tasks(state: web::Data<AppState>) -> Result<HttpResponse> {
+    let cfg = state.config.read().await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(indexed(&cfg.cron.tasks))))
+}
+
+/// POST /api/cron/tasks - Append a task to `cron.tasks`
+pub async fn create_cron_task(
+    state: web::Data<AppState>,
+    body: web::Json<crate::config::CronTaskConfig>,
+) -> Result<HttpResponse> {
+    let tasks = edit_config_tasks(&state, cron_tasks, TaskEdit::Add(body.into_inner())).await?;
+    Ok(HttpResponse::Created().json(ApiResponse::success(indexed(&tasks))))
+}
+
+/// PUT /api/cron/tasks/{index} - Replace a task in `cron.tasks`
+pub async fn update_cron_task(
+    state: web::Data<AppState>,
+    path: web::Path<usize>,
+    body: web::Json<crate::config::CronTaskConfig>,
+) -> Result<HttpResponse> {
+    let edit = TaskEdit::Replace(path.into_inner(), body.into_inner());
+    let tasks = edit_config_tasks(&state, cron_tasks, edit).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(indexed(&tasks))))
+}
+
+/// DELETE /api/cron/tasks/{index} - Remove a task from `cron.tasks`
+pub async fn delete_cron_task(
+    state: web::Data<AppState>,
+    path: web::Path<usize>,
+) -> Result<HttpResponse> {
+    let tasks = edit_config_tasks(&state, cron_tasks, TaskEdit::Remove(path.into_inner())).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(indexed(&tasks))))
+}
+
+/// GET /api/heartbeat/tasks - Returns the heartbeat tasks from config
+pub async fn get_heartbeat_tasks(state: web::Data<AppState>) -> Result<HttpResponse> {
+    let cfg = state.config.read().await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(indexed(&cfg.heartbeat.tasks))))
+}
+
+/// POST /api/heartbeat/tasks - Append a heartbeat task
+pub async fn create_heartbeat_task(
+    state: web::Data<AppState>,
+    body: web::Json<crate::config::HeartbeatTask>,
+) -> Result<HttpResponse> {
+    let tasks =
+        edit_config_tasks(&state, heartbeat_tasks, TaskEdit::Add(body.into_inner())).await?;
+    Ok(HttpResponse::Created().json(ApiResponse::success(indexed(&tasks))))
+}
+
+/// PUT /api/heartbeat/tasks/{index} - Replace a heartbeat task
+pub async fn update_heartbeat_task(
+    state: web::Data<AppState>,
+    path: web::Path<usize>,
+    body: web::Json<crate::config::HeartbeatTask>,
+) -> Result<HttpResponse> {
+    let edit = TaskEdit::Replace(path.into_inner(), body.into_inner());
+    let tasks = edit_config_tasks(&state, heartbeat_tasks, edit).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(indexed(&tasks))))
+}
+
+/// DELETE /api/heartbeat/tasks/{index} - Remove a heartbeat task
+pub async fn delete_heartbeat_task(
+    state: web::Data<AppState>,
+    path: web::Path<usize>,
+) -> Result<HttpResponse> {
+    let tasks =
+        edit_config_tasks(&state, heartbeat_tasks, TaskEdit::Remove(path.into_inner())).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(indexed(&tasks))))
+}
+
 /// Agent information for API responses
 #[derive(Serialize)]
 pub struct AgentInfo {
@@ -772,15 +1265,7 @@ pub async fn put_config(
         ApiError::BadRequest(format!("Invalid config JSON: {}", e))
     })?;
 
-    if let Err(errors) = validate_config(&merged) {
-        return Ok(
-            HttpResponse::BadRequest().json(serde_json::json!({
-                "success": false,
-                "error": "Config validation failed",
-                "data": { "validationErrors": errors }
-            })),
-        );
-    }
+    validate_config(&merged).map_err(ApiError::InvalidConfig)?;
 
     save_config(&merged, Some(state.config_path.as_path())).map_err(|e| {
         ApiError::InternalError(format!("Failed to write config file: {}", e))
@@ -842,6 +1327,33 @@ pub async fn get_logs(
     Ok(HttpResponse::Ok().json(ApiResponse::success(response)))
 }
 
+/// Request body for changing the log level
+#[derive(Deserialize)]
+pub struct LogLevelRequest {
+    /// `trace`, `debug`, `info`, `warn` or `error`
+    pub level: String,
+    /// Target to change (e.g. `synbot::channels`); synbot's own level when omitted
+    #[serde(default)]
+    pub module: Option<String>,
+}
+
+/// Log filter in effect after a change
+#[derive(Serialize)]
+pub struct LogLevelResponse {
+    pub filter: String,
+}
+
+/// POST /api/log/level - Change the log level at runtime, optionally for one module
+pub async fn set_log_level(body: web::Json<LogLevelRequest>) -> Result<HttpResponse> {
+    let control = crate::logging::log_level_control()
+        .ok_or_else(|| ApiError::InternalError("Logging is not initialized".to_string()))?;
+    let filter = control
+        .set_level(&body.level, body.module.as_deref())
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    tracing::info!(filter = %filter, "Log level changed");
+    Ok(HttpResponse::Ok().json(ApiResponse::success(LogLevelResponse { filter })))
+}
+
 /// Approval history entry DTO
 #[derive(Serialize)]
 pub struct ApprovalHistoryDto {
@@ -855,6 +1367,8 @@ pub struct ApprovalHistoryDto {
     pub timestamp: DateTime<Utc>,
     pub timeout_secs: u64,
     pub status: String,
+    /// For `timeout`: whether the command was approved (`denyOnTimeout: false`) or denied.
+    pub approved_on_timeout: Option<bool>,
     pub responder: Option<String>,
     pub completed_at: Option<DateTime<Utc>>,
 }
@@ -905,7 +1419,7 @@ pub async fn get_approval_history(
                     ApprovalStatus::Pending => "pending",
                     ApprovalStatus::Approved(_) => "approved",
                     ApprovalStatus::Rejected(_) => "rejected",
-                    ApprovalStatus::Timeout => "timeout",
+                    ApprovalStatus::Timeout { .. } => "timeout",
                 };
                 if status_str != st {
                     return false;
@@ -915,6 +1429,10 @@ pub async fn get_approval_history(
             true
         })
         .map(|(request, status)| {
+            let approved_on_timeout = match status {
+                ApprovalStatus::Timeout { approved } => Some(approved),
+                _ => None,
+            };
             let (status_str, responder, completed_at) = match status {
                 ApprovalStatus::Pending => ("pending".to_string(), None, None),
                 ApprovalStatus::Approved(response) => {
@@ -923,7 +1441,7 @@ pub async fn get_approval_history(
                 ApprovalStatus::Rejected(response) => {
                     ("rejected".to_string(), Some(response.responder.clone()), Some(response.timestamp))
                 }
-                ApprovalStatus::Timeout => ("timeout".to_string(), None, None),
+                ApprovalStatus::Timeout { .. } => ("timeout".to_string(), None, None),
             };
             
             ApprovalHistoryDto {
@@ -937,6 +1455,7 @@ pub async fn get_approval_history(
                 timestamp: request.timestamp,
                 timeout_secs: request.timeout_secs,
                 status: status_str,
+                approved_on_timeout,
                 responder,
                 completed_at,
             }
@@ -1011,6 +1530,44 @@ pub async fn get_pending_approvals(
     Ok(HttpResponse::Ok().json(ApiResponse::success(pending)))
 }
 
+/// Approval stats DTO
+#[derive(Serialize)]
+pub struct ApprovalStatsDto {
+    pub pending: usize,
+    pub approved_total: u64,
+    pub denied_total: u64,
+    pub expired_total: u64,
+    pub oldest_pending_age_secs: Option<u64>,
+}
+
+/// GET /api/approvals/stats - Returns pending count, oldest pending age and outcome totals
+pub async fn get_approval_stats(
+    state: web::Data<AppState>,
+) -> Result<HttpResponse> {
+    let stats = state.approval_manager.stats().await;
+    let dto = ApprovalStatsDto {
+        pending: stats.pending,
+        approved_total: stats.approved_total,
+        denied_total: stats.denied_total,
+        expired_total: stats.expired_total,
+        oldest_pending_age_secs: stats.oldest_pending_age.map(|age| age.as_secs()),
+    };
+    Ok(HttpResponse::Ok().json(ApiResponse::success(dto)))
+}
+
+/// GET /api/permissions/audit - Returns recorded permission decisions (oldest first)
+pub async fn get_permission_audit(
+    state: web::Data<AppState>,
+) -> Result<HttpResponse> {
+    let entries = state
+        .permission_policy
+        .as_ref()
+        .and_then(|policy| policy.audit_log())
+        .map(|log| log.entries())
+        .unwrap_or_default();
+    Ok(HttpResponse::Ok().json(ApiResponse::success(entries)))
+}
+
 /// Request body for approval response
 #[derive(Deserialize)]
 pub struct ApprovalResponseRequest {
@@ -1,7 +1,15 @@
+#[cfg(feature = "web")]
 pub mod api;
 pub mod config_redact;
 pub mod sanitize;
+#[cfg(feature = "web")]
+pub mod sse;
+#[cfg(feature = "web")]
 pub mod static_files;
+#[cfg(feature = "web")]
+pub mod webhook;
+#[cfg(feature = "web")]
 pub mod ws;
 
+#[cfg(feature = "web")]
 pub use api::{ApiResponse, ErrorResponse};
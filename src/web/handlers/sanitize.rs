@@ -321,6 +321,8 @@ mod tests {
                 dingtalk: vec![],
                 whatsapp: None,
                 irc: None,
+                webhook: vec![],
+                ..Default::default()
             },
             providers: ProvidersConfig {
                 anthropic: ProviderEntry {
@@ -364,6 +366,7 @@ mod tests {
                     ..Default::default()
                 },
                 extra: std::collections::HashMap::new(),
+                ..Default::default()
             },
             web: WebConfig {
                 enabled: true,
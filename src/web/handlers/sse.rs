@@ -0,0 +1,119 @@
+//! Server-sent events stream of the web chat (`GET /api/stream`).
+//!
+//! A one-way alternative to `/ws/chat` for clients behind proxies that mishandle WebSockets.
+//! `?session=<id>` streams one of the user's web sessions, like `/ws/chat?session=<id>`.
+//! Each outbound message of the web chat becomes one event named after its `type`
+//! (`chat_response`, `tool_progress`, `approval_request`) with the same JSON payload the
+//! WebSocket sends. Comment lines are sent periodically so idle connections stay open.
+
+use std::time::Duration;
+
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use bytes::Bytes;
+use futures_util::Stream;
+use tokio::sync::broadcast;
+use tokio::time::{Instant, Interval};
+
+use crate::bus::OutboundMessage;
+use crate::web::handlers::api::{web_chat_target, WebChatQuery};
+use crate::web::handlers::ws::{server_message_for, WsServerMessage};
+use crate::web::state::AppState;
+
+/// Interval between keepalive comments.
+pub const SSE_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Format one SSE event.
+fn sse_event(msg: &WsServerMessage) -> Option<Bytes> {
+    let data = serde_json::to_value(msg).ok()?;
+    let event = data["type"].as_str().unwrap_or("message").to_string();
+    Some(Bytes::from(format!("event: {}\ndata: {}\n\n", event, data)))
+}
+
+/// Logs when the client goes away: actix drops the response stream on disconnect.
+struct StreamGuard;
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        tracing::debug!("SSE client disconnected");
+    }
+}
+
+struct StreamState {
+    rx: broadcast::Receiver<OutboundMessage>,
+    /// Chat id of the streamed web chat
+    chat_id: String,
+    keepalive: Interval,
+    state: web::Data<AppState>,
+    _guard: StreamGuard,
+}
+
+impl StreamState {
+    /// Next chunk to write: an event for the web chat or a keepalive comment.
+    /// `None` when the bus has shut down.
+    async fn next_chunk(&mut self) -> Option<Bytes> {
+        loop {
+            tokio::select! {
+                _ = self.keepalive.tick() => return Some(Bytes::from_static(b": keepalive\n\n")),
+                recv = self.rx.recv() => match recv {
+                    Ok(msg) => {
+                        if msg.channel != "web" || msg.chat_id != self.chat_id {
+                            continue;
+                        }
+                        let show_tool_progress = {
+                            let cfg = self.state.config.read().await;
+                            cfg.show_tool_calls && cfg.web.show_tool_calls
+                        };
+                        if let Some(event) = server_message_for(msg.message_type, show_tool_progress)
+                            .as_ref()
+                            .and_then(sse_event)
+                        {
+                            return Some(event);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "SSE client fell behind; skipped outbound messages");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                },
+            }
+        }
+    }
+}
+
+fn event_stream(
+    state: web::Data<AppState>,
+    chat_id: String,
+    keepalive: Duration,
+) -> impl Stream<Item = Result<Bytes, actix_web::Error>> {
+    let initial = StreamState {
+        rx: state.delivery_tx.subscribe(),
+        chat_id,
+        keepalive: tokio::time::interval_at(Instant::now() + keepalive, keepalive),
+        state,
+        _guard: StreamGuard,
+    };
+    let connected = futures_util::stream::once(async {
+        Ok::<_, actix_web::Error>(Bytes::from_static(b": connected\n\n"))
+    });
+    let events = futures_util::stream::unfold(initial, |mut s| async move {
+        let chunk = s.next_chunk().await?;
+        Some((Ok(chunk), s))
+    });
+    futures_util::StreamExt::chain(connected, events)
+}
+
+/// GET /api/stream - Server-sent events with the web chat's outbound messages
+pub async fn stream_events(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<WebChatQuery>,
+) -> Result<HttpResponse> {
+    let (_, chat_id) = web_chat_target(&req, &state, query.session.as_deref()).await?;
+    tracing::debug!(chat_id = %chat_id, "SSE client connected");
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        // Stop nginx from buffering the stream.
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(event_stream(state, chat_id, SSE_KEEPALIVE_INTERVAL)))
+}
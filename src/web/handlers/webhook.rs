@@ -0,0 +1,49 @@
+//! Inbound route of the webhook channels (`POST /webhook/{name}`).
+//!
+//! Sits outside the Basic-auth `/api` scope: callers are other systems, which prove themselves
+//! with the channel's HMAC signature instead. See [`crate::channels::webhook`].
+
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+
+use crate::channels::webhook::{map_inbound, verify_signature, SIGNATURE_HEADER};
+use crate::web::handlers::api::{ApiError, ApiResponse};
+use crate::web::state::AppState;
+
+/// POST /webhook/{name} - Deliver a message to the enabled webhook channel `name`
+pub async fn receive_webhook(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+    req: HttpRequest,
+    body: web::Bytes,
+) -> Result<HttpResponse> {
+    let name = path.into_inner();
+    let config = {
+        let cfg = state.config.read().await;
+        cfg.channels
+            .webhook
+            .iter()
+            .find(|c| c.enabled && c.name == name)
+            .cloned()
+    }
+    .ok_or_else(|| ApiError::NotFound(format!("No enabled webhook channel named {}", name)))?;
+
+    let signature = req
+        .headers()
+        .get(SIGNATURE_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if !verify_signature(&config.secret, &body, signature) {
+        tracing::warn!(channel = %name, "Webhook request with missing or invalid signature");
+        return Err(ApiError::Unauthorized(format!("invalid {} header", SIGNATURE_HEADER)).into());
+    }
+
+    let payload: serde_json::Value = serde_json::from_slice(&body)
+        .map_err(|e| ApiError::BadRequest(format!("body is not JSON: {}", e)))?;
+    let inbound = map_inbound(&config, &payload).map_err(ApiError::BadRequest)?;
+    state
+        .inbound_tx
+        .send(inbound)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("Failed to queue message: {}", e)))?;
+    Ok(HttpResponse::Accepted().json(ApiResponse::success(serde_json::json!({ "queued": true }))))
+}
@@ -10,6 +10,9 @@ use crate::bus::{InboundMessage, OutboundMessage};
 use crate::web::channel::{WebChannel, WebSocketConnection};
 use crate::web::state::AppState;
 
+/// Chat id of the shared web chat, used when no session is selected (see [`crate::web::handlers::api::WebChatQuery`]).
+pub const WEB_CHAT_USER: &str = "web_admin";
+
 /// How often heartbeat pings are sent
 const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
 
@@ -79,6 +82,8 @@ pub struct WsSession {
     id: String,
     /// User ID (from auth or generated)
     user_id: String,
+    /// Chat id of the selected web session (the shared web chat by default)
+    chat_id: String,
     /// Last heartbeat time
     hb: Instant,
     /// Application state
@@ -88,10 +93,11 @@ pub struct WsSession {
 }
 
 impl WsSession {
-    pub fn new(user_id: String, state: web::Data<AppState>) -> Self {
+    pub fn new(user_id: String, chat_id: String, state: web::Data<AppState>) -> Self {
         Self {
             id: Uuid::new_v4().to_string(),
             user_id,
+            chat_id,
             hb: Instant::now(),
             state,
             web_channel: None,
@@ -132,7 +138,7 @@ impl Actor for WsSession {
         // Create web channel
         let web_channel = WebChannel::new(
             self.state.inbound_tx.clone(),
-            self.state.outbound_tx.clone(),
+            self.state.delivery_tx.clone(),
         );
 
         // Register connection
@@ -154,14 +160,14 @@ impl Actor for WsSession {
 
         // Subscribe to outbound messages
         let mut outbound_rx = web_channel.subscribe_outbound();
-        let user_id = self.user_id.clone();
+        let chat_id = self.chat_id.clone();
         let addr = ctx.address();
 
         ctx.spawn(
             async move {
                 while let Ok(msg) = outbound_rx.recv().await {
-                    // Only forward messages for web channel and this user
-                    if msg.channel == "web" && msg.chat_id == user_id {
+                    // Only forward messages for web channel and this chat
+                    if msg.channel == "web" && msg.chat_id == chat_id {
                         addr.do_send(OutboundMessageWrapper(msg));
                     }
                 }
@@ -180,9 +186,9 @@ impl Actor for WsSession {
             },
         );
 
-        // Load and send history for this channel (web) and user: all sessions (main + roles) so the UI shows the full thread.
+        // Load and send history for this channel (web) and chat: all sessions (main + roles) so the UI shows the full thread.
         let state = self.state.clone();
-        let user_id = self.user_id.clone();
+        let user_id = self.chat_id.clone();
         let addr = ctx.address();
         ctx.spawn(
             async move {
@@ -268,7 +274,7 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for WsSession {
                         let inbound_msg = InboundMessage {
                             channel: "web".to_string(),
                             sender_id: self.user_id.clone(),
-                            chat_id: self.user_id.clone(),
+                            chat_id: self.chat_id.clone(),
                             content,
                             timestamp: Utc::now(),
                             media: vec![],
@@ -363,38 +369,46 @@ impl Handler<OutboundMessageWrapper> for WsSession {
     type Result = ();
 
     fn handle(&mut self, msg: OutboundMessageWrapper, ctx: &mut Self::Context) {
-        let send = match msg.0.message_type {
-            crate::bus::OutboundMessageType::Chat { content, .. } => {
-                Some(WsServerMessage::ChatResponse {
-                    content,
-                    timestamp: Utc::now(),
-                })
-            }
-            crate::bus::OutboundMessageType::ApprovalRequest { request } => {
-                Some(WsServerMessage::ApprovalRequest { request })
-            }
-            crate::bus::OutboundMessageType::ToolProgress {
-                tool_name,
-                status,
-                result_preview,
-            } => {
-                if self.state.show_tool_progress_for_ws() {
-                    Some(WsServerMessage::ToolProgress {
-                        tool_name,
-                        status,
-                        result_preview,
-                    })
-                } else {
-                    None
-                }
-            }
-        };
-        if let Some(server_msg) = send {
+        let is_tool_progress = matches!(
+            msg.0.message_type,
+            crate::bus::OutboundMessageType::ToolProgress { .. }
+        );
+        let show_tool_progress = is_tool_progress && self.state.show_tool_progress_for_ws();
+        if let Some(server_msg) = server_message_for(msg.0.message_type, show_tool_progress) {
             self.send_message(ctx, server_msg);
         }
     }
 }
 
+/// Client-facing form of an outbound message for the web chat (WebSocket and SSE).
+/// `None` for messages the web UI does not show (typing indicators, hidden tool progress).
+pub fn server_message_for(
+    message_type: crate::bus::OutboundMessageType,
+    show_tool_progress: bool,
+) -> Option<WsServerMessage> {
+    match message_type {
+        crate::bus::OutboundMessageType::Chat { content, .. } => {
+            Some(WsServerMessage::ChatResponse {
+                content,
+                timestamp: Utc::now(),
+            })
+        }
+        crate::bus::OutboundMessageType::ApprovalRequest { request } => {
+            Some(WsServerMessage::ApprovalRequest { request })
+        }
+        crate::bus::OutboundMessageType::ToolProgress {
+            tool_name,
+            status,
+            result_preview,
+        } => show_tool_progress.then_some(WsServerMessage::ToolProgress {
+            tool_name,
+            status,
+            result_preview,
+        }),
+        crate::bus::OutboundMessageType::Typing => None,
+    }
+}
+
 /// Wrapper for history messages to be sent as actor messages
 #[derive(ActixMessage)]
 #[rtype(result = "()")]
@@ -414,18 +428,21 @@ impl Handler<HistoryMessageWrapper> for WsSession {
 }
 
 /// WebSocket route handler
+///
+/// `?session=<id>` chats in one of the user's web sessions; without it all connections share
+/// the web admin conversation.
 pub async fn ws_chat(
     req: HttpRequest,
     stream: web::Payload,
     state: web::Data<AppState>,
+    query: web::Query<crate::web::handlers::api::WebChatQuery>,
 ) -> Result<HttpResponse, Error> {
-    // Use a fixed user_id for web channel since it's a global management interface
-    // All connections share the same session
-    let user_id = "web_admin".to_string();
+    let (user_id, chat_id) =
+        crate::web::handlers::api::web_chat_target(&req, &state, query.session.as_deref()).await?;
 
-    tracing::info!("WebSocket connection for web admin interface");
+    tracing::info!(chat_id = %chat_id, "WebSocket connection for web chat");
 
-    let ws_session = WsSession::new(user_id, state);
+    let ws_session = WsSession::new(user_id, chat_id, state);
     let resp = ws::start(ws_session, &req, stream)?;
 
     Ok(resp)
@@ -1,17 +1,26 @@
 use chrono::{DateTime, Utc};
 use serde::Serialize;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::sync::Arc;
 use tokio::sync::{broadcast, RwLock};
 use tracing::Level;
 
-/// A log entry stored in the buffer
+/// A log entry stored in the buffer, as sent to the web UI (`/api/logs` and the logs WebSocket):
+///
+/// ```json
+/// { "timestamp": "2026-01-01T12:00:00Z", "level": "INFO", "target": "synbot::channels",
+///   "message": "sent", "fields": { "chat_id": "c1", "attempts": 3 } }
+/// ```
+///
+/// `fields` holds the event's other fields as JSON values (strings, numbers, booleans; values
+/// recorded with `?` as their debug text) and is `{}` when there are none.
 #[derive(Debug, Clone, Serialize)]
 pub struct LogEntry {
     pub timestamp: DateTime<Utc>,
     pub level: String,
     pub target: String,
     pub message: String,
+    pub fields: BTreeMap<String, serde_json::Value>,
 }
 
 impl LogEntry {
@@ -21,6 +30,7 @@ impl LogEntry {
             level: level.to_string(),
             target,
             message,
+            fields: BTreeMap::new(),
         }
     }
 }
@@ -1,14 +1,27 @@
+//! Admin web UI and API. The server, handlers and web channel need the `web` feature; the log
+//! buffer and config redaction are always built (used by logging and `synbot diagnostics`).
+
+#[cfg(feature = "web")]
 pub mod auth;
+#[cfg(feature = "web")]
 pub mod channel;
+#[cfg(feature = "web")]
 pub mod cors;
 pub mod handlers;
 pub mod log_buffer;
+#[cfg(feature = "web")]
 pub mod server;
+#[cfg(feature = "web")]
 pub mod state;
 
+#[cfg(feature = "web")]
 pub use auth::{AuthenticatedUser, BasicAuth};
+#[cfg(feature = "web")]
 pub use channel::WebChannel;
+#[cfg(feature = "web")]
 pub use cors::Cors;
 pub use log_buffer::{create_log_buffer, LogBuffer, LogEntry, SharedLogBuffer};
+#[cfg(feature = "web")]
 pub use server::start_web_server;
+#[cfg(feature = "web")]
 pub use state::AppState;
@@ -1,5 +1,5 @@
 use crate::config::WebConfig;
-use crate::web::handlers::{api, static_files, ws};
+use crate::web::handlers::{api, sse, static_files, webhook, ws};
 use crate::web::state::AppState;
 use crate::web::{BasicAuth, Cors};
 use actix_web::{web, App, HttpServer};
@@ -22,26 +22,61 @@ pub async fn start_web_server(config: WebConfig, state: AppState) -> Result<()>
         App::new()
             .wrap(cors.clone())
             .app_data(web::Data::new(state.clone()))
+            .app_data(api::json_config())
             // API routes (protected by auth if configured)
             .service(
                 web::scope("/api")
                     .wrap(auth.clone())
                     .route("/status", web::get().to(api::get_status))
+                    .route("/version", web::get().to(api::get_version))
+                    .route("/metrics", web::get().to(api::get_metrics))
                     .route("/sessions", web::get().to(api::get_sessions))
+                    .route("/sessions", web::post().to(api::create_session))
                     .route("/sessions/{id}", web::get().to(api::get_session_by_id))
+                    .route("/sessions/{id}", web::delete().to(api::delete_session))
+                    .route("/sessions/{id}/cancel", web::post().to(api::cancel_session))
+                    .route("/stream", web::get().to(sse::stream_events))
+                    .route("/turns", web::get().to(api::get_turns))
+                    .route("/turns/{id}", web::delete().to(api::kill_turn))
+                    .route("/deadletters", web::get().to(api::get_dead_letters))
+                    .route("/sandbox/quarantine", web::get().to(api::get_quarantined_sandboxes))
+                    .route("/sandbox/quarantine/{id}", web::delete().to(api::release_quarantined_sandbox))
                     .route("/channels", web::get().to(api::get_channels))
                     .route("/cron", web::get().to(api::get_cron_jobs))
                     .route("/cron/{id}", web::patch().to(api::update_cron_job))
+                    .route("/cron/tasks", web::get().to(api::get_cron_tasks))
+                    .route("/cron/tasks", web::post().to(api::create_cron_task))
+                    .route("/cron/tasks/{index}", web::put().to(api::update_cron_task))
+                    .route("/cron/tasks/{index}", web::delete().to(api::delete_cron_task))
+                    .route("/heartbeat", web::get().to(api::get_heartbeat_status))
+                    .route("/heartbeat/pause", web::post().to(api::pause_heartbeat))
+                    .route("/heartbeat/resume", web::post().to(api::resume_heartbeat))
+                    .route("/heartbeat/run-now", web::post().to(api::run_heartbeat_now))
+                    .route("/heartbeat/tasks", web::get().to(api::get_heartbeat_tasks))
+                    .route("/heartbeat/tasks", web::post().to(api::create_heartbeat_task))
+                    .route("/heartbeat/tasks/{index}", web::put().to(api::update_heartbeat_task))
+                    .route("/heartbeat/tasks/{index}", web::delete().to(api::delete_heartbeat_task))
                     .route("/agents", web::get().to(api::get_agents))
                     .route("/skills", web::get().to(api::get_skills))
                     .route("/skills/{name}", web::get().to(api::get_skill_by_name))
                     .route("/config", web::get().to(api::get_config))
                     .route("/config", web::put().to(api::put_config))
                     .route("/logs", web::get().to(api::get_logs))
+                    .route("/log/level", web::post().to(api::set_log_level))
                     .route("/approvals/history", web::get().to(api::get_approval_history))
                     .route("/approvals/pending", web::get().to(api::get_pending_approvals))
+                    .route("/approvals/stats", web::get().to(api::get_approval_stats))
+                    .route("/permissions/audit", web::get().to(api::get_permission_audit))
                     .route("/approvals/{id}/respond", web::post().to(api::submit_approval_response))
             )
+            // Prometheus scrape endpoint (same auth as the API)
+            .service(
+                web::resource("/metrics")
+                    .wrap(auth.clone())
+                    .route(web::get().to(api::get_metrics_prometheus)),
+            )
+            // Webhook channels (authenticated by their HMAC signature, not Basic auth)
+            .route("/webhook/{name}", web::post().to(webhook::receive_webhook))
             // WebSocket routes
             .route("/ws/chat", web::get().to(ws::ws_chat))
             .route("/ws/logs", web::get().to(ws::ws_logs))
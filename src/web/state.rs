@@ -21,9 +21,22 @@ pub struct AppState {
     pub skills_loader: Arc<dyn SkillProvider>,
     pub inbound_tx: mpsc::Sender<InboundMessage>,
     pub outbound_tx: broadcast::Sender<OutboundMessage>,
+    /// What web connections subscribe to: `outbound_tx`, or the bus's delivery side when
+    /// response transforms run in between.
+    pub delivery_tx: broadcast::Sender<OutboundMessage>,
     pub log_buffer: SharedLogBuffer,
     pub approval_manager: Arc<crate::tools::approval::ApprovalManager>,
     pub permission_policy: Option<Arc<crate::tools::permission::CommandPermissionPolicy>>,
+    /// Pause/resume/run-now handle for the heartbeat service.
+    pub heartbeat_control: Arc<crate::heartbeat::HeartbeatControl>,
+    /// Sandbox monitoring metrics; `None` when no sandbox is configured.
+    pub sandbox_monitoring: Option<Arc<crate::sandbox::MonitoringModule>>,
+    /// Sandbox manager, for listing and releasing quarantined sandboxes; `None` without a sandbox.
+    pub sandbox_manager: Option<Arc<crate::sandbox::SandboxManager>>,
+    /// Running agent turns, shared with the agent loop.
+    pub active_turns: Arc<crate::agent::active_turns::ActiveTurns>,
+    /// Outbound messages held back while their channel is down, shared with the channels.
+    pub dead_letters: Arc<crate::bus::DeadLetterQueue>,
 }
 
 impl AppState {
@@ -40,6 +53,10 @@ impl AppState {
         approval_manager: Arc<crate::tools::approval::ApprovalManager>,
         permission_policy: Option<Arc<crate::tools::permission::CommandPermissionPolicy>>,
     ) -> Self {
+        let dead_letters = Arc::new(crate::bus::DeadLetterQueue::new(
+            outbound_tx.clone(),
+            crate::bus::DEAD_LETTER_CAPACITY,
+        ));
         Self {
             config,
             config_path,
@@ -48,13 +65,72 @@ impl AppState {
             agent_registry,
             skills_loader,
             inbound_tx,
+            delivery_tx: outbound_tx.clone(),
             outbound_tx,
             log_buffer,
             approval_manager,
             permission_policy,
+            heartbeat_control: Arc::new(crate::heartbeat::HeartbeatControl::new()),
+            sandbox_monitoring: None,
+            sandbox_manager: None,
+            active_turns: Arc::new(crate::agent::active_turns::ActiveTurns::new()),
+            dead_letters,
         }
     }
 
+    /// Use the heartbeat control shared with the running heartbeat service.
+    pub fn with_heartbeat_control(
+        mut self,
+        control: Arc<crate::heartbeat::HeartbeatControl>,
+    ) -> Self {
+        self.heartbeat_control = control;
+        self
+    }
+
+    /// Use the running-turn registry shared with the agent loop.
+    pub fn with_active_turns(
+        mut self,
+        turns: Arc<crate::agent::active_turns::ActiveTurns>,
+    ) -> Self {
+        self.active_turns = turns;
+        self
+    }
+
+    /// Subscribe web connections to the bus's delivery side (see `MessageBus::delivery_tx_clone`).
+    pub fn with_delivery(mut self, delivery_tx: broadcast::Sender<OutboundMessage>) -> Self {
+        self.delivery_tx = delivery_tx;
+        self
+    }
+
+    /// Use the bus's dead-letter queue (the one the channels report to).
+    pub fn with_dead_letters(mut self, queue: Arc<crate::bus::DeadLetterQueue>) -> Self {
+        self.dead_letters = queue;
+        self
+    }
+
+    /// Serve metrics from the sandbox manager's monitoring module.
+    pub fn with_sandbox_monitoring(
+        mut self,
+        monitoring: Arc<crate::sandbox::MonitoringModule>,
+    ) -> Self {
+        self.sandbox_monitoring = Some(monitoring);
+        self
+    }
+
+    /// Serve the quarantine endpoints from the running sandbox manager.
+    pub fn with_sandbox_manager(mut self, manager: Arc<crate::sandbox::SandboxManager>) -> Self {
+        self.sandbox_manager = Some(manager);
+        self
+    }
+
+    /// Current sandbox metrics (all zero when no sandbox is configured).
+    pub fn metrics_snapshot(&self) -> crate::sandbox::MetricsSnapshot {
+        self.sandbox_monitoring
+            .as_ref()
+            .map(|m| m.snapshot())
+            .unwrap_or_default()
+    }
+
     /// Read `show_toolCalls` flags synchronously (e.g. actix WebSocket actor).
     pub fn show_tool_progress_for_ws(&self) -> bool {
         match tokio::runtime::Handle::try_current() {
@@ -26,13 +26,17 @@ impl PendingConfirmStore {
     }
 
     /// Take the pending definition if the user's message is classified as approval (agree/confirm).
-    /// Uses the same LLM-based classifier as command approval so any language works.
+    /// Uses the same LLM-based classifier as command approval so any language works; the model is
+    /// only asked when a definition is pending for the session.
     pub async fn take_if_confirm(
         &self,
         session_key: &str,
         content: &str,
         model: &dyn SynbotCompletionModel,
     ) -> Option<WorkflowDef> {
+        if !self.inner.read().await.contains_key(session_key) {
+            return None;
+        }
         match approval_classifier::classify_approval_response(model, content).await {
             Some(true) => self.inner.write().await.remove(session_key),
             _ => None,
@@ -86,4 +90,53 @@ mod tests {
         let store = PendingConfirmStore::new();
         assert!(store.remove("nobody").await.is_none());
     }
+
+    /// Fake LLM that counts its calls and classifies every message as approval.
+    #[derive(Default)]
+    struct ApprovingModel {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl SynbotCompletionModel for ApprovingModel {
+        fn completion(
+            &self,
+            _request: rig::completion::CompletionRequest,
+        ) -> std::pin::Pin<
+            Box<
+                dyn std::future::Future<
+                        Output = Result<
+                            rig::completion::CompletionResponse<()>,
+                            rig::completion::CompletionError,
+                        >,
+                    > + Send
+                    + '_,
+            >,
+        > {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Box::pin(async {
+                Ok(rig::completion::CompletionResponse {
+                    choice: rig::OneOrMany::one(rig::message::AssistantContent::text("APPROVE")),
+                    usage: rig::completion::Usage {
+                        input_tokens: 0,
+                        output_tokens: 0,
+                        total_tokens: 0,
+                        cached_input_tokens: 0,
+                    },
+                    raw_response: (),
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn take_if_confirm_asks_the_model_only_when_pending() {
+        let store = PendingConfirmStore::new();
+        let model = ApprovingModel::default();
+        assert!(store.take_if_confirm("session1", "yes", &model).await.is_none());
+        assert_eq!(model.calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        store.set("session1", sample_def()).await;
+        assert!(store.take_if_confirm("session1", "yes", &model).await.is_some());
+        assert_eq!(model.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
 }
@@ -65,6 +65,7 @@ pub fn temp_workspace() -> (tempfile::TempDir, PathBuf) {
 
 /// Create test AppState with approval manager for testing.
 /// Use for API tests (approval history, pending, respond) and any test that needs a full web AppState.
+#[cfg(feature = "web")]
 pub async fn create_test_app_state_with_approval(
     inbound_tx: tokio::sync::mpsc::Sender<synbot::bus::InboundMessage>,
     outbound_tx: tokio::sync::broadcast::Sender<synbot::bus::OutboundMessage>,
@@ -144,20 +145,21 @@ impl synbot::rig_provider::SynbotCompletionModel for MockCompletionModel {
         >,
     > {
         let text = self.response.clone();
-        Box::pin(async move {
-            use rig::message::AssistantContent;
-            let choice = rig::OneOrMany::one(AssistantContent::text(&text));
-            Ok(rig::completion::request::CompletionResponse {
-                choice,
-                usage: rig::completion::Usage {
-                    input_tokens: 0,
-                    output_tokens: 0,
-                    total_tokens: 0,
-                    cached_input_tokens: 0,
-                },
-                raw_response: (),
-            })
-        })
+        Box::pin(async move { Ok(text_response(text)) })
+    }
+}
+
+/// A completion answering with `text` alone and zero usage, for mock models.
+pub fn text_response(text: impl Into<String>) -> rig::completion::request::CompletionResponse<()> {
+    rig::completion::request::CompletionResponse {
+        choice: rig::OneOrMany::one(rig::message::AssistantContent::text(text)),
+        usage: rig::completion::Usage {
+            input_tokens: 0,
+            output_tokens: 0,
+            total_tokens: 0,
+            cached_input_tokens: 0,
+        },
+        raw_response: (),
     }
 }
 
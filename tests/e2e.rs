@@ -1,5 +1,6 @@
 //! E2E tests: config reload, timeouts, concurrency, etc.
-//! Run with: `cargo test --test e2e`
+//! Run with: `cargo test --test e2e` (add `--features mock-provider,test-harness` for the tests
+//! using the scripted provider and the bus harness).
 
 mod common;
 
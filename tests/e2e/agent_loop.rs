@@ -6,6 +6,8 @@
 //! Requirements: 12.1, 12.2, 12.3, 12.4
 //! Run with: `cargo test --test e2e agent_loop`
 
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
@@ -16,7 +18,9 @@ use synbot::agent::role_registry::RoleRegistry;
 use synbot::agent::session::SessionStore;
 use synbot::agent::session_state::SharedSessionState;
 use synbot::bus::{InboundMessage, OutboundMessage, OutboundMessageType};
-use synbot::config::{Config, MainAgent};
+use synbot::config::{AgentConfig, Config, IdentityLinkConfig, MainAgent, TelegramConfig};
+use synbot::cron::config_runner::{CronRunTracker, CRON_TASK_META};
+use synbot::rig_provider::SynbotCompletionModel;
 use synbot::tools::ToolRegistry;
 
 use super::common;
@@ -61,20 +65,111 @@ async fn build_agent_loop(
     Arc<Mutex<AgentLoop>>,
     mpsc::Sender<InboundMessage>,
     broadcast::Receiver<OutboundMessage>,
+) {
+    let mock_model = Arc::new(common::mock_completion_model("Hello from mock model"));
+    build_agent_loop_with_model(config, mock_model).await
+}
+
+/// Same as [`build_agent_loop`] but with a caller-provided completion model.
+async fn build_agent_loop_with_model(
+    config: &Config,
+    model: Arc<dyn SynbotCompletionModel>,
+) -> (
+    Arc<Mutex<AgentLoop>>,
+    mpsc::Sender<InboundMessage>,
+    broadcast::Receiver<OutboundMessage>,
+) {
+    build_agent_loop_with_tools(config, model, ToolRegistry::new()).await
+}
+
+/// Same as [`build_agent_loop_with_model`] but with the tools the agent may call.
+async fn build_agent_loop_with_tools(
+    config: &Config,
+    model: Arc<dyn SynbotCompletionModel>,
+    tools: ToolRegistry,
+) -> (
+    Arc<Mutex<AgentLoop>>,
+    mpsc::Sender<InboundMessage>,
+    broadcast::Receiver<OutboundMessage>,
+) {
+    build_agent_loop_with_agents(config, model, tools, vec![]).await
+}
+
+/// Same as [`build_agent_loop_with_tools`] plus extra agents; each agent's role directory gets an
+/// AGENTS.md reading "You are the <role> role."
+async fn build_agent_loop_with_agents(
+    config: &Config,
+    model: Arc<dyn SynbotCompletionModel>,
+    tools: ToolRegistry,
+    agents: Vec<AgentConfig>,
+) -> (
+    Arc<Mutex<AgentLoop>>,
+    mpsc::Sender<InboundMessage>,
+    broadcast::Receiver<OutboundMessage>,
 ) {
     let (inbound_tx, inbound_rx) = mpsc::channel::<InboundMessage>(32);
     let (outbound_tx, outbound_rx) = broadcast::channel::<OutboundMessage>(64);
+    let loop_ref =
+        spawn_agent_loop(config, model, tools, agents, inbound_rx, outbound_tx, None).await;
+    (loop_ref, inbound_tx, outbound_rx)
+}
 
+/// Same as [`build_agent_loop_with_model`] with the end of config cron runs reported to `cron_runs`.
+async fn build_agent_loop_with_cron_runs(
+    config: &Config,
+    model: Arc<dyn SynbotCompletionModel>,
+    cron_runs: Arc<CronRunTracker>,
+) -> (
+    Arc<Mutex<AgentLoop>>,
+    mpsc::Sender<InboundMessage>,
+    broadcast::Receiver<OutboundMessage>,
+) {
+    let (inbound_tx, inbound_rx) = mpsc::channel::<InboundMessage>(32);
+    let (outbound_tx, outbound_rx) = broadcast::channel::<OutboundMessage>(64);
+    let loop_ref = spawn_agent_loop(
+        config,
+        model,
+        ToolRegistry::new(),
+        vec![],
+        inbound_rx,
+        outbound_tx,
+        Some(cron_runs),
+    )
+    .await;
+    (loop_ref, inbound_tx, outbound_rx)
+}
+
+/// Build the AgentLoop on the given bus ends and run it in the background.
+async fn spawn_agent_loop(
+    config: &Config,
+    model: Arc<dyn SynbotCompletionModel>,
+    tools: ToolRegistry,
+    agents: Vec<AgentConfig>,
+    inbound_rx: mpsc::Receiver<InboundMessage>,
+    outbound_tx: broadcast::Sender<OutboundMessage>,
+    cron_runs: Option<Arc<CronRunTracker>>,
+) -> Arc<Mutex<AgentLoop>> {
     let (_dir, workspace) = common::temp_workspace();
     let session_store = SessionStore::new(workspace.as_path() as &std::path::Path);
     let session_state = SharedSessionState::new(session_store);
-    let tools = Arc::new(ToolRegistry::new());
+    session_state
+        .session_manager
+        .write()
+        .await
+        .set_identity_links(&config.identity_links);
+    let tools = Arc::new(tools);
 
     // Build a minimal agent registry with a "main" agent
     let mut agent_registry = AgentRegistry::new();
     let (_roles_dir, roles_path) = common::temp_workspace();
     // Create a minimal "main" role directory so load_from_config succeeds
     std::fs::create_dir_all(roles_path.join("main")).expect("create main role dir");
+    for agent in &agents {
+        let role_dir = roles_path.join(&agent.role);
+        std::fs::create_dir_all(&role_dir).expect("create role dir");
+        std::fs::write(role_dir.join("AGENTS.md"), format!("You are the {} role.", agent.role))
+            .expect("write role prompt");
+    }
     let mut role_registry = RoleRegistry::new();
     role_registry.load_from_dirs(&roles_path).expect("load roles");
     let main_agent = MainAgent {
@@ -86,20 +181,25 @@ async fn build_agent_loop(
         max_tool_iterations: 3,
         max_consecutive_tool_errors: 3,
         max_chat_history_messages: 20,
+        max_repeated_tool_calls: 3,
         max_concurrent_subagents: 1,
         subagent_task_timeout_secs: 30,
-        agents: vec![],
+        max_concurrent_turns: 2,
+        reasoning_effort: None,
+        thinking_budget: None,
+        show_reasoning: false,
+        default_role: None,
+        language_roles: Default::default(),
+        agents,
     };
     agent_registry
         .load_from_config(&main_agent, &config.providers, &role_registry, &workspace)
         .expect("load agent registry");
     let agent_registry = Arc::new(agent_registry);
 
-    let mock_model = Arc::new(common::mock_completion_model("Hello from mock model"));
-
     let shared_config = Arc::new(RwLock::new(config.clone()));
     let agent_loop = AgentLoop::new(
-        mock_model,
+        model,
         workspace,
         tools,
         3, // max_iterations
@@ -112,6 +212,10 @@ async fn build_agent_loop(
         shared_config,
     )
     .await;
+    let agent_loop = match cron_runs {
+        Some(tracker) => agent_loop.with_cron_runs(tracker),
+        None => agent_loop,
+    };
 
     let loop_ref = Arc::new(Mutex::new(agent_loop));
     let loop_ref_run = loop_ref.clone();
@@ -121,7 +225,7 @@ async fn build_agent_loop(
         let _ = AgentLoop::run(loop_ref_run, inbound_rx).await;
     });
 
-    (loop_ref, inbound_tx, outbound_rx)
+    loop_ref
 }
 
 // ---------------------------------------------------------------------------
@@ -156,6 +260,35 @@ async fn test_inbound_message_produces_outbound_response() {
     );
 }
 
+#[cfg(feature = "test-harness")]
+#[tokio::test]
+async fn test_simple_turn_outbound_messages_with_bus_harness() {
+    use synbot::bus_test_harness::BusTestHarness;
+
+    let config = common::default_test_config();
+    let mut bus = BusTestHarness::new();
+    let model = Arc::new(common::mock_completion_model("Hello from mock model"));
+    let _loop_ref = spawn_agent_loop(
+        &config,
+        model,
+        ToolRegistry::new(),
+        vec![],
+        bus.inbound_receiver(),
+        bus.outbound_sender(),
+        None,
+    )
+    .await;
+
+    bus.send_text("telegram", "chat-harness", "Hello agent").await;
+    let reply = bus.expect_chat_containing("chat-harness", "Hello from mock model").await;
+    assert_eq!(reply.channel, "telegram");
+    assert!(
+        bus.seen().iter().all(|m| m.chat_id == "chat-harness"
+            && !matches!(m.message_type, OutboundMessageType::ToolProgress { .. })),
+        "A turn without tool calls sends no tool progress and stays in its chat"
+    );
+}
+
 // ---------------------------------------------------------------------------
 // Requirement 12.3 — max_iterations terminates the loop
 // ---------------------------------------------------------------------------
@@ -222,6 +355,281 @@ async fn test_concurrent_sessions_are_isolated() {
     assert!(!b_to_a, "Session B messages should not appear in session A's channel");
 }
 
+// ---------------------------------------------------------------------------
+// maxConcurrentTurns — global turn limit
+// ---------------------------------------------------------------------------
+
+/// Mock model that records how many completion calls are in flight at once.
+struct ConcurrencyProbeModel {
+    in_flight: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl SynbotCompletionModel for ConcurrencyProbeModel {
+    fn completion(
+        &self,
+        _request: rig::completion::request::CompletionRequest,
+    ) -> Pin<
+        Box<
+            dyn std::future::Future<
+                    Output = Result<
+                        rig::completion::request::CompletionResponse<()>,
+                        rig::completion::request::CompletionError,
+                    >,
+                > + Send
+                + '_,
+        >,
+    > {
+        Box::pin(async move {
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(common::text_response("done"))
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_max_concurrent_turns_limits_in_flight_provider_calls() {
+    let mut config = common::default_test_config();
+    config.main_agent.max_concurrent_turns = 2;
+    let probe = Arc::new(ConcurrencyProbeModel {
+        in_flight: AtomicUsize::new(0),
+        peak: AtomicUsize::new(0),
+    });
+    let (_loop_ref, inbound_tx, mut outbound_rx) =
+        build_agent_loop_with_model(&config, probe.clone()).await;
+
+    for chat in ["turn-1", "turn-2", "turn-3"] {
+        inbound_tx
+            .send(inbound("telegram", chat, "Hello"))
+            .await
+            .expect("send inbound");
+    }
+
+    let msgs = collect_outbound(&mut outbound_rx, 3000).await;
+    for chat in ["turn-1", "turn-2", "turn-3"] {
+        assert!(
+            msgs.iter().any(|m| m.chat_id == chat
+                && matches!(m.message_type, OutboundMessageType::Chat { .. })),
+            "Queued turn for {} should still complete",
+            chat
+        );
+    }
+    assert_eq!(
+        probe.peak.load(Ordering::SeqCst),
+        2,
+        "No more than two provider calls may be in flight with maxConcurrentTurns = 2"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Per-chat queue — messages to a busy chat wait for its turn
+// ---------------------------------------------------------------------------
+
+/// Mock model that answers `reply:<word>` for the last user message containing "first",
+/// "second" or "third"; earlier words take longer, so without queueing replies would be reversed.
+struct SlowEchoModel;
+
+impl SynbotCompletionModel for SlowEchoModel {
+    fn completion(
+        &self,
+        request: rig::completion::request::CompletionRequest,
+    ) -> Pin<
+        Box<
+            dyn std::future::Future<
+                    Output = Result<
+                        rig::completion::request::CompletionResponse<()>,
+                        rig::completion::request::CompletionError,
+                    >,
+                > + Send
+                + '_,
+        >,
+    > {
+        let last_user = request
+            .chat_history
+            .iter()
+            .filter_map(|m| match m {
+                rig::message::Message::User { content } => Some(content),
+                _ => None,
+            })
+            .last()
+            .into_iter()
+            .flat_map(|content| content.iter())
+            .filter_map(|c| match c {
+                rig::message::UserContent::Text(t) => Some(t.text.as_str()),
+                _ => None,
+            })
+            .collect::<String>();
+        let (word, delay_ms) = [("first", 600), ("second", 300), ("third", 50)]
+            .into_iter()
+            .find(|(word, _)| last_user.contains(word))
+            .unwrap_or(("unknown", 0));
+        Box::pin(async move {
+            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            Ok(common::text_response(format!("reply:{}", word)))
+        })
+    }
+}
+
+/// `(chat_id, reply)` of each `reply:` chat message, in arrival order.
+fn echo_replies(msgs: &[OutboundMessage]) -> Vec<(String, String)> {
+    msgs.iter()
+        .filter_map(|m| match &m.message_type {
+            OutboundMessageType::Chat { content, .. } if content.starts_with("reply:") => {
+                Some((m.chat_id.clone(), content.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_rapid_messages_to_one_chat_reply_in_order() {
+    let config = common::default_test_config();
+    let (_loop_ref, inbound_tx, mut outbound_rx) =
+        build_agent_loop_with_model(&config, Arc::new(SlowEchoModel)).await;
+
+    for word in ["first", "second", "third"] {
+        inbound_tx
+            .send(inbound("telegram", "c1", word))
+            .await
+            .expect("send inbound");
+    }
+
+    let msgs = collect_outbound(&mut outbound_rx, 3000).await;
+    let replies: Vec<String> = echo_replies(&msgs).into_iter().map(|(_, r)| r).collect();
+    assert_eq!(replies, ["reply:first", "reply:second", "reply:third"]);
+}
+
+#[tokio::test]
+async fn test_queued_chat_does_not_hold_back_other_chats() {
+    let config = common::default_test_config();
+    let (_loop_ref, inbound_tx, mut outbound_rx) =
+        build_agent_loop_with_model(&config, Arc::new(SlowEchoModel)).await;
+
+    for (chat, word) in [("c1", "first"), ("c1", "second"), ("c2", "third")] {
+        inbound_tx
+            .send(inbound("telegram", chat, word))
+            .await
+            .expect("send inbound");
+    }
+
+    let msgs = collect_outbound(&mut outbound_rx, 3000).await;
+    // c2 finishes while c1's first turn is still running; c1's second message waits for it.
+    let replies = echo_replies(&msgs);
+    let expected = [("c2", "reply:third"), ("c1", "reply:first"), ("c1", "reply:second")];
+    assert_eq!(
+        replies,
+        expected.map(|(c, r)| (c.to_string(), r.to_string())).to_vec()
+    );
+}
+
+#[tokio::test]
+async fn test_messages_that_do_not_trigger_the_agent_are_not_queued_as_turns() {
+    let config = common::default_test_config();
+    let (_loop_ref, inbound_tx, mut outbound_rx) =
+        build_agent_loop_with_model(&config, Arc::new(SlowEchoModel)).await;
+
+    let mut aside = inbound("telegram", "c1", "an aside about the third point");
+    aside.metadata = serde_json::json!({ "trigger_agent": false });
+    for msg in [inbound("telegram", "c1", "first"), aside, inbound("telegram", "c1", "second")] {
+        inbound_tx.send(msg).await.expect("send inbound");
+    }
+
+    let msgs = collect_outbound(&mut outbound_rx, 3000).await;
+    let replies: Vec<String> = echo_replies(&msgs).into_iter().map(|(_, r)| r).collect();
+    assert_eq!(replies, ["reply:first", "reply:second"]);
+    assert!(
+        msgs.iter().all(|m| !matches!(&m.message_type,
+            OutboundMessageType::Chat { content, .. } if content.contains("Busy"))),
+        "A message kept for history must not count against the busy queue"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Scripted multi-turn tool use (MockProvider)
+// Run with: `cargo test --test e2e --features mock-provider`
+// ---------------------------------------------------------------------------
+
+/// Tool that looks up a city's weather and counts its calls.
+#[cfg(feature = "mock-provider")]
+struct WeatherTool {
+    calls: AtomicUsize,
+}
+
+#[cfg(feature = "mock-provider")]
+#[async_trait::async_trait]
+impl synbot::tools::DynTool for WeatherTool {
+    fn name(&self) -> &str {
+        "get_weather"
+    }
+    fn description(&self) -> &str {
+        "Current weather for a city"
+    }
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": { "city": { "type": "string" } },
+            "required": ["city"]
+        })
+    }
+    async fn call(&self, args: serde_json::Value) -> anyhow::Result<String> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let city = args["city"].as_str().unwrap_or("unknown");
+        Ok(format!("{}: 18C, light rain", city))
+    }
+}
+
+#[cfg(feature = "mock-provider")]
+#[tokio::test]
+async fn test_mock_provider_drives_tool_call_then_final_answer() {
+    use synbot::mock_provider::{register_mock_provider, MockProvider};
+
+    let mock = Arc::new(
+        MockProvider::new()
+            .tool_call("get_weather", serde_json::json!({ "city": "Oslo" }))
+            .text("It is 18C with light rain in Oslo."),
+    );
+    register_mock_provider("mock", Arc::clone(&mock));
+    let model = synbot::rig_provider::build_completion_model("mock", "mock-model", "", None)
+        .expect("mock provider should be registered");
+
+    let weather = Arc::new(WeatherTool {
+        calls: AtomicUsize::new(0),
+    });
+    let mut tools = ToolRegistry::new();
+    tools.register(weather.clone()).expect("register tool");
+
+    let config = common::default_test_config();
+    let (_loop_ref, inbound_tx, mut outbound_rx) =
+        build_agent_loop_with_tools(&config, model, tools).await;
+
+    inbound_tx
+        .send(inbound("telegram", "chat-weather", "What's the weather in Oslo?"))
+        .await
+        .expect("send inbound");
+
+    let msgs = collect_outbound(&mut outbound_rx, 3000).await;
+    assert_eq!(weather.calls.load(Ordering::SeqCst), 1, "Tool should run exactly once");
+    assert!(
+        msgs.iter().any(|m| m.chat_id == "chat-weather"
+            && matches!(&m.message_type, OutboundMessageType::Chat { content, .. }
+                if content.contains("18C with light rain in Oslo"))),
+        "Final scripted answer should reach the chat"
+    );
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2, "Model should be called for the tool call and the final answer");
+    let second = serde_json::to_string(&requests[1].chat_history).expect("serialize history");
+    assert!(
+        second.contains("Oslo: 18C, light rain"),
+        "Second request should carry the tool result"
+    );
+    assert_eq!(mock.remaining(), 0);
+}
+
 // ---------------------------------------------------------------------------
 // Requirement 12.2 — tool call failure returns user-readable error
 // ---------------------------------------------------------------------------
@@ -263,3 +671,386 @@ async fn test_control_command_status_returns_response() {
     });
     assert!(status_response, "Should receive a response to /status");
 }
+
+#[tokio::test]
+async fn test_linked_identities_share_turns_and_stop_across_channels() {
+    let mut config = common::default_test_config();
+    config.identity_links = vec![IdentityLinkConfig {
+        name: "alice".to_string(),
+        members: vec!["telegram:111".to_string(), "discord:222".to_string()],
+    }];
+    let probe = Arc::new(ConcurrencyProbeModel {
+        in_flight: AtomicUsize::new(0),
+        peak: AtomicUsize::new(0),
+    });
+    let (_loop_ref, inbound_tx, mut outbound_rx) =
+        build_agent_loop_with_model(&config, probe.clone()).await;
+
+    inbound_tx
+        .send(inbound("telegram", "111", "first question"))
+        .await
+        .expect("send telegram");
+    inbound_tx
+        .send(inbound("discord", "222", "second question"))
+        .await
+        .expect("send discord");
+    let msgs = collect_outbound(&mut outbound_rx, 1500).await;
+    for (channel, chat) in [("telegram", "111"), ("discord", "222")] {
+        assert!(
+            msgs.iter().any(|m| m.channel == channel && m.chat_id == chat
+                && matches!(m.message_type, OutboundMessageType::Chat { .. })),
+            "{} should get its reply",
+            channel
+        );
+    }
+    assert_eq!(
+        probe.peak.load(Ordering::SeqCst),
+        1,
+        "Turns of one linked conversation must not run concurrently"
+    );
+
+    // /stop from the other channel cancels the running turn.
+    inbound_tx
+        .send(inbound("telegram", "111", "long question"))
+        .await
+        .expect("send telegram");
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    inbound_tx
+        .send(inbound("discord", "222", "/stop"))
+        .await
+        .expect("send /stop");
+    let msgs = collect_outbound(&mut outbound_rx, 500).await;
+    assert!(msgs.iter().any(|m| m.channel == "discord" && matches!(&m.message_type,
+        OutboundMessageType::Chat { content, .. } if content.contains("The current turn was stopped"))));
+}
+
+#[tokio::test]
+async fn test_stop_releases_queued_cron_run() {
+    let config = common::default_test_config();
+    let tracker = Arc::new(CronRunTracker::new());
+    let slow = Arc::new(ConcurrencyProbeModel {
+        in_flight: AtomicUsize::new(0),
+        peak: AtomicUsize::new(0),
+    });
+    let (_loop_ref, inbound_tx, mut outbound_rx) =
+        build_agent_loop_with_cron_runs(&config, slow, tracker.clone()).await;
+
+    inbound_tx
+        .send(inbound("telegram", "chat-cron", "long question"))
+        .await
+        .expect("send inbound");
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // Same shape as the message ConfigCronRunner sends; it queues behind the running turn.
+    assert!(tracker.try_begin("nightly"));
+    let mut cron = inbound("telegram", "chat-cron", "summarize the day");
+    cron.metadata = serde_json::json!({ "trigger_agent": true, CRON_TASK_META: "nightly" });
+    inbound_tx.send(cron).await.expect("send cron message");
+    inbound_tx
+        .send(inbound("telegram", "chat-cron", "/stop"))
+        .await
+        .expect("send /stop");
+
+    let msgs = collect_outbound(&mut outbound_rx, 500).await;
+    assert!(msgs.iter().any(|m| matches!(&m.message_type,
+        OutboundMessageType::Chat { content, .. } if content.contains("1 queued message(s) were dropped"))));
+    assert!(
+        !tracker.is_running("nightly"),
+        "A dropped cron message must end its run so the next one can fire"
+    );
+    assert!(tracker.try_begin("nightly"));
+}
+
+#[tokio::test]
+async fn test_channel_command_releases_cron_run() {
+    let mut config = common::default_test_config();
+    config.channels.telegram = vec![serde_json::from_value(serde_json::json!({
+        "name": "telegram",
+        "commands": [{ "prefix": "!status", "action": "health" }]
+    }))
+    .expect("telegram config")];
+    let tracker = Arc::new(CronRunTracker::new());
+    let probe = Arc::new(ConcurrencyProbeModel {
+        in_flight: AtomicUsize::new(0),
+        peak: AtomicUsize::new(0),
+    });
+    let (_loop_ref, inbound_tx, mut outbound_rx) =
+        build_agent_loop_with_cron_runs(&config, probe.clone(), tracker.clone()).await;
+
+    // A cron task whose text is a channel command is answered without a turn.
+    assert!(tracker.try_begin("status"));
+    let mut cron = inbound("telegram", "chat-cron-cmd", "!status");
+    cron.metadata = serde_json::json!({ "trigger_agent": true, CRON_TASK_META: "status" });
+    inbound_tx.send(cron).await.expect("send cron message");
+
+    let msgs = collect_outbound(&mut outbound_rx, 500).await;
+    assert!(msgs.iter().any(|m| matches!(&m.message_type,
+        OutboundMessageType::Chat { content, .. } if content.starts_with("[Health]"))));
+    assert_eq!(probe.peak.load(Ordering::SeqCst), 0);
+    assert!(
+        !tracker.is_running("status"),
+        "A cron message answered by a channel command must end its run"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Channel commands — prefix table handled before the model
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_channel_command_short_circuits_model_and_non_match_passes_through() {
+    let mut config = common::default_test_config();
+    config.channels.telegram = vec![serde_json::from_value(serde_json::json!({
+        "name": "telegram",
+        "commands": [
+            { "prefix": "!help", "action": "reply", "text": "Ask me anything." },
+            { "prefix": "!status", "action": "health" }
+        ]
+    }))
+    .expect("telegram config")];
+    let probe = Arc::new(ConcurrencyProbeModel {
+        in_flight: AtomicUsize::new(0),
+        peak: AtomicUsize::new(0),
+    });
+    let (_loop_ref, inbound_tx, mut outbound_rx) =
+        build_agent_loop_with_model(&config, probe.clone()).await;
+
+    inbound_tx
+        .send(inbound("telegram", "chat-cmd", "!HELP "))
+        .await
+        .expect("send !help");
+    inbound_tx
+        .send(inbound("telegram", "chat-cmd", "!status"))
+        .await
+        .expect("send !status");
+    let msgs = collect_outbound(&mut outbound_rx, 1000).await;
+    let replies: Vec<&str> = msgs
+        .iter()
+        .filter_map(|m| match &m.message_type {
+            OutboundMessageType::Chat { content, .. } if m.chat_id == "chat-cmd" => Some(content.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(replies.len(), 2, "got {:?}", replies);
+    assert_eq!(replies[0], "Ask me anything.");
+    assert!(replies[1].starts_with("[Health] OK."), "got {:?}", replies[1]);
+    assert_eq!(probe.peak.load(Ordering::SeqCst), 0, "Commands must not reach the model");
+
+    // "!helpme" is not "!help": it goes to the agent as usual.
+    inbound_tx
+        .send(inbound("telegram", "chat-cmd", "!helpme with this"))
+        .await
+        .expect("send non-matching message");
+    let msgs = collect_outbound(&mut outbound_rx, 3000).await;
+    assert!(msgs.iter().any(|m| matches!(&m.message_type,
+        OutboundMessageType::Chat { content, .. } if content == "done")));
+    assert_eq!(probe.peak.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_channel_command_ignores_messages_that_do_not_trigger_the_agent() {
+    let mut config = common::default_test_config();
+    config.channels.telegram = vec![serde_json::from_value(serde_json::json!({
+        "name": "telegram",
+        "commands": [{ "prefix": "!help", "action": "reply", "text": "Ask me anything." }]
+    }))
+    .expect("telegram config")];
+    let (_loop_ref, inbound_tx, mut outbound_rx) = build_agent_loop(&config).await;
+
+    // e.g. a group message not addressed to the bot
+    let mut msg = inbound("telegram", "chat-silent", "!help");
+    msg.metadata = serde_json::json!({ "trigger_agent": false });
+    inbound_tx.send(msg).await.expect("send inbound");
+
+    let msgs = collect_outbound(&mut outbound_rx, 500).await;
+    assert!(
+        msgs.iter().all(|m| m.chat_id != "chat-silent"),
+        "got {:?}",
+        msgs.iter().map(|m| &m.message_type).collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn test_inbound_limit_rejects_only_messages_that_trigger_the_agent() {
+    let mut config = common::default_test_config();
+    config.channels.telegram = vec![serde_json::from_value(serde_json::json!({
+        "name": "telegram",
+        "inboundLimit": { "maxChars": 5, "policy": "reject" }
+    }))
+    .expect("telegram config")];
+    let (_loop_ref, inbound_tx, mut outbound_rx) = build_agent_loop(&config).await;
+
+    let mut silent = inbound("telegram", "chat-silent", "far too long for the limit");
+    silent.metadata = serde_json::json!({ "trigger_agent": false });
+    inbound_tx.send(silent).await.expect("send silent message");
+    inbound_tx
+        .send(inbound("telegram", "chat-loud", "far too long for the limit"))
+        .await
+        .expect("send triggering message");
+
+    let msgs = collect_outbound(&mut outbound_rx, 1000).await;
+    assert!(msgs.iter().all(|m| m.chat_id != "chat-silent"));
+    assert!(msgs.iter().any(|m| m.chat_id == "chat-loud" && matches!(&m.message_type,
+        OutboundMessageType::Chat { content, .. } if content.starts_with("Your message is too long"))));
+}
+
+// ---------------------------------------------------------------------------
+// Heartbeat / cron task roles
+// ---------------------------------------------------------------------------
+
+/// Mock model that records the preamble and max_tokens of every request.
+#[derive(Default)]
+struct RecordingModel {
+    requests: std::sync::Mutex<Vec<(Option<String>, Option<u64>)>>,
+}
+
+impl SynbotCompletionModel for RecordingModel {
+    fn completion(
+        &self,
+        request: rig::completion::request::CompletionRequest,
+    ) -> Pin<
+        Box<
+            dyn std::future::Future<
+                    Output = Result<
+                        rig::completion::request::CompletionResponse<()>,
+                        rig::completion::request::CompletionError,
+                    >,
+                > + Send
+                + '_,
+        >,
+    > {
+        self.requests
+            .lock()
+            .unwrap()
+            .push((request.preamble.clone(), request.max_tokens));
+        Box::pin(async move { Ok(common::text_response("disk is fine")) })
+    }
+}
+
+/// Agent `ops_bot` for the `ops` role, told apart from the main agent by its `max_tokens` of 256.
+fn ops_agent() -> AgentConfig {
+    AgentConfig {
+        name: "ops_bot".to_string(),
+        role: "ops".to_string(),
+        provider: None,
+        model: None,
+        max_tokens: Some(256),
+        temperature: None,
+        max_iterations: None,
+        reasoning_effort: None,
+        thinking_budget: None,
+        skills: vec![],
+        tools: vec![],
+        tool_result_format: None,
+        tools_enabled: true,
+    }
+}
+
+#[tokio::test]
+async fn test_heartbeat_task_with_role_runs_as_that_roles_agent() {
+    let config = common::default_test_config();
+    let ops = ops_agent();
+    let model = Arc::new(RecordingModel::default());
+    let (_loop_ref, inbound_tx, mut outbound_rx) =
+        build_agent_loop_with_agents(&config, model.clone(), ToolRegistry::new(), vec![ops]).await;
+
+    // Same shape as the message HeartbeatService sends for a task with `role: "ops"`.
+    let mut msg = inbound("telegram", "chat-hb", "check disk usage");
+    msg.metadata = serde_json::json!({ "source": "heartbeat", "on_error": "post", "role": "ops" });
+    inbound_tx.send(msg).await.expect("send heartbeat task");
+
+    let msgs = collect_outbound(&mut outbound_rx, 3000).await;
+    assert!(
+        msgs.iter().any(|m| m.chat_id == "chat-hb"
+            && matches!(&m.message_type, OutboundMessageType::Chat { content, .. } if content == "disk is fine")),
+        "The agent's answer should be posted to the task's chat"
+    );
+    let requests = model.requests.lock().unwrap().clone();
+    assert_eq!(requests.len(), 1);
+    let (preamble, max_tokens) = &requests[0];
+    assert!(
+        preamble.as_deref().unwrap_or("").contains("You are the ops role."),
+        "Task should run with the ops role's prompt: {:?}",
+        preamble
+    );
+    assert_eq!(*max_tokens, Some(256), "Task should use the ops agent's settings");
+}
+
+// ---------------------------------------------------------------------------
+// Per-channel model / role overrides
+// ---------------------------------------------------------------------------
+
+#[cfg(feature = "mock-provider")]
+#[tokio::test]
+async fn test_channel_model_override_is_used_and_other_channels_keep_the_default() {
+    use synbot::mock_provider::{register_mock_provider, MockProvider};
+
+    let cheap = Arc::new(MockProvider::new().text("from the cheap model"));
+    register_mock_provider("mock-cheap", Arc::clone(&cheap));
+    let mut config = common::default_test_config();
+    config.channels.telegram.push(TelegramConfig {
+        name: "noisy".to_string(),
+        provider: Some("mock-cheap".to_string()),
+        model: Some("cheap-model".to_string()),
+        ..Default::default()
+    });
+    let default_model = Arc::new(RecordingModel::default());
+    let (_loop_ref, inbound_tx, mut outbound_rx) =
+        build_agent_loop_with_model(&config, default_model.clone()).await;
+
+    inbound_tx
+        .send(inbound("noisy", "chat-noisy", "status?"))
+        .await
+        .expect("send inbound");
+    let msgs = collect_outbound(&mut outbound_rx, 3000).await;
+    assert!(
+        msgs.iter().any(|m| m.chat_id == "chat-noisy"
+            && matches!(&m.message_type, OutboundMessageType::Chat { content, .. } if content == "from the cheap model")),
+        "The bound channel should be answered by its override model"
+    );
+    assert_eq!(cheap.requests().len(), 1);
+    assert!(default_model.requests.lock().unwrap().is_empty());
+
+    inbound_tx
+        .send(inbound("telegram", "chat-default", "status?"))
+        .await
+        .expect("send inbound");
+    let msgs = collect_outbound(&mut outbound_rx, 3000).await;
+    assert!(
+        msgs.iter().any(|m| m.chat_id == "chat-default"
+            && matches!(&m.message_type, OutboundMessageType::Chat { content, .. } if content == "disk is fine")),
+        "A channel without an override should use the default model"
+    );
+    assert_eq!(default_model.requests.lock().unwrap().len(), 1);
+    assert_eq!(cheap.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_channel_role_override_routes_to_that_roles_agent() {
+    let mut config = common::default_test_config();
+    config.channels.telegram.push(TelegramConfig {
+        name: "ops-chat".to_string(),
+        role: Some("ops".to_string()),
+        ..Default::default()
+    });
+    let ops = ops_agent();
+    let model = Arc::new(RecordingModel::default());
+    let (_loop_ref, inbound_tx, mut outbound_rx) =
+        build_agent_loop_with_agents(&config, model.clone(), ToolRegistry::new(), vec![ops]).await;
+
+    inbound_tx
+        .send(inbound("ops-chat", "chat-ops", "check disk usage"))
+        .await
+        .expect("send inbound");
+    let msgs = collect_outbound(&mut outbound_rx, 3000).await;
+    assert!(msgs.iter().any(|m| m.chat_id == "chat-ops"));
+    let requests = model.requests.lock().unwrap().clone();
+    assert_eq!(requests.len(), 1);
+    let (preamble, max_tokens) = &requests[0];
+    assert!(
+        preamble.as_deref().unwrap_or("").contains("You are the ops role."),
+        "The channel's role should pick the ops agent: {:?}",
+        preamble
+    );
+    assert_eq!(*max_tokens, Some(256));
+}
@@ -23,6 +23,7 @@ fn test_ctx() -> ChannelStartContext {
         completion_model: None,
         outbound_tx: Some(outbound_tx),
         config_path: None,
+        dead_letters: None,
     }
 }
 
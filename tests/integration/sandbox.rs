@@ -1,15 +1,14 @@
-//! Integration tests for sandbox core data structures.
+//! Integration tests for sandbox core data structures and the tools that run in a tool sandbox.
 //! Run with: `cargo test --test integration sandbox`
 
 use synbot::sandbox::{
-    ConfigurationManager, FilesystemConfig, MonitoringConfig, NetworkConfig, ProcessConfig,
-    ResourceConfig, SandboxConfig, SandboxError,
+    ConfigurationManager, FilesystemConfig, HardeningConfig, MonitoringConfig, NetworkConfig,
+    ProcessConfig, ResourceConfig, SandboxConfig, SandboxError,
 };
 
-#[test]
-fn test_sandbox_config_serialization() {
-    let config = SandboxConfig {
-        sandbox_id: "test-001".to_string(),
+fn sandbox_config(sandbox_id: &str) -> SandboxConfig {
+    SandboxConfig {
+        sandbox_id: sandbox_id.to_string(),
         platform: "linux".to_string(),
         filesystem: FilesystemConfig {
             readonly_paths: vec!["/usr".to_string()],
@@ -21,6 +20,7 @@ fn test_sandbox_config_serialization() {
             enabled: false,
             allowed_hosts: vec![],
             allowed_ports: vec![],
+            host_network: false,
         },
         resources: ResourceConfig {
             max_memory: 1024 * 1024 * 1024,
@@ -36,7 +36,13 @@ fn test_sandbox_config_serialization() {
         delete_on_start: false,
         requested_tool_sandbox_type: None,
         image: None,
-    };
+        hardening: HardeningConfig::default(),
+    }
+}
+
+#[test]
+fn test_sandbox_config_serialization() {
+    let config = sandbox_config("test-001");
 
     let json = serde_json::to_string(&config).unwrap();
     let deserialized: SandboxConfig = serde_json::from_str(&json).unwrap();
@@ -71,3 +77,151 @@ fn test_sandbox_error_display() {
     let err = SandboxError::Configuration("invalid field".to_string());
     assert_eq!(err.to_string(), "Configuration error: invalid field");
 }
+
+/// Fake container: runs commands on the host with `/workspace` mapped to its own directory,
+/// so files created in it are not visible in the host workspace.
+#[cfg(unix)]
+struct DirSandbox {
+    sandbox_id: String,
+    workspace: std::path::PathBuf,
+    state: synbot::sandbox::SandboxState,
+}
+
+#[cfg(unix)]
+impl synbot::sandbox::Sandbox for DirSandbox {
+    fn start(&mut self) -> synbot::sandbox::Result<()> {
+        self.state = synbot::sandbox::SandboxState::Running;
+        Ok(())
+    }
+
+    fn stop(&mut self) -> synbot::sandbox::Result<()> {
+        self.state = synbot::sandbox::SandboxState::Stopped;
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        command: &str,
+        args: &[String],
+        _timeout: std::time::Duration,
+        working_dir: Option<&str>,
+    ) -> synbot::sandbox::Result<synbot::sandbox::ExecutionResult> {
+        let root = self.workspace.to_string_lossy().into_owned();
+        let output = std::process::Command::new(command)
+            .args(args.iter().map(|a| a.replace("/workspace", &root)))
+            .current_dir(working_dir.map(|d| d.replace("/workspace", &root)).unwrap_or(root.clone()))
+            .output()
+            .map_err(|e| SandboxError::ExecutionFailed(e.to_string()))?;
+        Ok(synbot::sandbox::ExecutionResult {
+            exit_code: output.status.code().unwrap_or(-1),
+            stdout: output.stdout,
+            stderr: output.stderr,
+            duration: std::time::Duration::from_millis(1),
+            error: None,
+        })
+    }
+
+    fn get_status(&self) -> synbot::sandbox::SandboxStatus {
+        synbot::sandbox::SandboxStatus {
+            sandbox_id: self.sandbox_id.clone(),
+            state: self.state.clone(),
+            created_at: chrono::Utc::now(),
+            started_at: None,
+            stopped_at: None,
+            error: None,
+        }
+    }
+
+    fn health_check(&self) -> synbot::sandbox::HealthStatus {
+        synbot::sandbox::HealthStatus {
+            healthy: true,
+            checks: std::collections::HashMap::new(),
+            message: "Healthy".to_string(),
+        }
+    }
+
+    fn get_info(&self) -> synbot::sandbox::SandboxInfo {
+        synbot::sandbox::SandboxInfo {
+            sandbox_id: self.sandbox_id.clone(),
+            platform: "test".to_string(),
+            sandbox_type: "dir".to_string(),
+        }
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_file_tools_see_files_created_by_exec_in_tool_sandbox() {
+    use std::sync::Arc;
+    use synbot::sandbox::{Sandbox, SandboxManager, ToolSandboxDelegate, ToolSandboxExecKind};
+    use synbot::tools::filesystem::{ListDirTool, ReadFileTool, WriteFileTool};
+    use synbot::tools::DynTool;
+
+    let host_workspace = tempfile::tempdir().unwrap();
+    let container_workspace = tempfile::tempdir().unwrap();
+    let container_path = container_workspace.path().to_path_buf();
+    let manager = SandboxManager::with_defaults().with_tool_sandbox_factory(Box::new(
+        move |config: SandboxConfig| {
+            Ok(Box::new(DirSandbox {
+                sandbox_id: config.sandbox_id,
+                workspace: container_path.clone(),
+                state: synbot::sandbox::SandboxState::Created,
+            }) as Box<dyn Sandbox>)
+        },
+    ));
+    manager.set_tool_sandbox_config(sandbox_config("synbot-tool")).await;
+    // Tool sandbox creation refuses to run as root on Linux; check that instead.
+    #[cfg(target_os = "linux")]
+    if nix::unistd::Uid::effective().is_root() {
+        let err = manager
+            .exec_in_tool_sandbox("true", &[], std::time::Duration::from_secs(5), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, synbot::sandbox::SandboxError::SecurityViolation(_)), "{:?}", err);
+        return;
+    }
+    let sandbox_context = Some(ToolSandboxDelegate::Local {
+        manager: Arc::new(manager),
+        sandbox_id: "synbot-tool".to_string(),
+        kind: ToolSandboxExecKind::Docker,
+        workspace_dir: "/workspace".to_string(),
+    });
+
+    let exec = sandbox_context
+        .as_ref()
+        .unwrap()
+        .exec(
+            None,
+            "sh",
+            &["-c".to_string(), "mkdir -p out && echo hello > /workspace/out/made.txt".to_string()],
+            std::time::Duration::from_secs(5),
+            Some("/workspace"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(exec.exit_code, 0);
+    assert!(!host_workspace.path().join("out/made.txt").exists());
+
+    let workspace = host_workspace.path().to_path_buf();
+    let read = ReadFileTool { workspace: workspace.clone(), restrict: true, sandbox_context: sandbox_context.clone() };
+    assert_eq!(read.call(serde_json::json!({ "path": "out/made.txt" })).await.unwrap(), "hello\n");
+    assert_eq!(
+        read.call(serde_json::json!({ "path": "/workspace/out/made.txt" })).await.unwrap(),
+        "hello\n"
+    );
+    assert!(read.call(serde_json::json!({ "path": "out/missing.txt" })).await.is_err());
+
+    let write = WriteFileTool { workspace: workspace.clone(), restrict: true, sandbox_context: sandbox_context.clone() };
+    write
+        .call(serde_json::json!({ "path": "notes/a.md", "content": "line 1\nline 2" }))
+        .await
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(container_workspace.path().join("notes/a.md")).unwrap(),
+        "line 1\nline 2"
+    );
+
+    let list = ListDirTool { workspace, restrict: true, sandbox_context };
+    let listing = list.call(serde_json::json!({ "path": "." })).await.unwrap();
+    assert_eq!(listing, "Directories:\n  notes (dir)\n  out (dir)");
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "web")]
+
 use actix_web::{test, web, App};
 use synbot::tools::approval::ApprovalManager;
 use synbot::web::handlers::api;
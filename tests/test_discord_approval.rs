@@ -2,6 +2,8 @@
 //!
 //! 测试 Discord 渠道的审批请求和响应处理
 
+#![cfg(feature = "discord")]
+
 use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc};
 use synbot::bus::{InboundMessage, OutboundMessage, OutboundMessageType};
@@ -24,6 +26,16 @@ fn create_test_channel(
         show_tool_calls: true,
         group_my_name: None,
         default_agent: "main".to_string(),
+        inbound_limit: None,
+        outbound_min_interval_ms: None,
+        commands: Vec::new(),
+        tools_enabled: true,
+        provider: None,
+        model: None,
+        role: None,
+        typing_indicator: false,
+        mention_mode: Default::default(),
+        slash_commands: false,
     };
 
     let mut channel = DiscordChannel::new(
@@ -58,6 +70,7 @@ async fn test_approval_manager_integration() {
         timestamp: chrono::Utc::now(),
         timeout_secs: 300,
         display_message: None,
+        approver_id: None,
     };
     
     // 在后台任务中模拟审批响应
@@ -109,6 +122,7 @@ async fn test_approval_rejection() {
         timestamp: chrono::Utc::now(),
         timeout_secs: 300,
         display_message: None,
+        approver_id: None,
     };
     
     // 在后台任务中模拟拒绝响应
@@ -160,6 +174,7 @@ async fn test_approval_timeout() {
         timestamp: chrono::Utc::now(),
         timeout_secs: 1, // 1 秒超时
         display_message: None,
+        approver_id: None,
     };
     
     // 不发送响应，让请求超时
@@ -204,6 +219,7 @@ async fn test_outbound_approval_request_formatting() {
         timestamp: chrono::Utc::now(),
         timeout_secs: 300,
         display_message: None,
+        approver_id: None,
     };
     
     let msg = OutboundMessage {
@@ -343,7 +359,7 @@ async fn test_approval_history() {
 
 #[tokio::test]
 async fn test_discord_message_splitting() {
-    use synbot::channels::discord::split_message;
+    use synbot::channels::{split_message, LengthUnit};
     
     // 测试审批请求消息不会超过 Discord 的 2000 字符限制
     let request = ApprovalRequest {
@@ -357,6 +373,7 @@ async fn test_discord_message_splitting() {
         timestamp: chrono::Utc::now(),
         timeout_secs: 300,
         display_message: None,
+        approver_id: None,
     };
     
     let formatted = format!(
@@ -376,7 +393,7 @@ async fn test_discord_message_splitting() {
         request.timeout_secs
     );
     
-    let chunks = split_message(&formatted, 2000);
+    let chunks = split_message(&formatted, 2000, LengthUnit::Chars);
     
     // 验证每个分块都不超过限制
     for chunk in &chunks {
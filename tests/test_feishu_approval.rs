@@ -2,6 +2,8 @@
 //!
 //! 测试飞书渠道的审批请求和响应处理
 
+#![cfg(feature = "feishu")]
+
 use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc};
 use synbot::bus::{InboundMessage, OutboundMessage, OutboundMessageType};
@@ -25,6 +27,16 @@ fn create_test_channel(
         show_tool_calls: true,
         group_my_name: None,
         default_agent: "main".to_string(),
+        inbound_limit: None,
+        outbound_min_interval_ms: None,
+        commands: Vec::new(),
+        tools_enabled: true,
+        provider: None,
+        model: None,
+        role: None,
+        mention_mode: Default::default(),
+        message_format: Default::default(),
+        max_media_mb: None,
     };
     
     let mut channel = FeishuChannel::new(config, inbound_tx, outbound_rx, true, 500, None, None);
@@ -51,6 +63,7 @@ async fn test_approval_manager_integration() {
         timestamp: chrono::Utc::now(),
         timeout_secs: 300,
         display_message: None,
+        approver_id: None,
     };
     
     // 在后台任务中模拟审批响应
@@ -102,6 +115,7 @@ async fn test_approval_rejection() {
         timestamp: chrono::Utc::now(),
         timeout_secs: 300,
         display_message: None,
+        approver_id: None,
     };
     
     // 在后台任务中模拟拒绝响应
@@ -153,6 +167,7 @@ async fn test_approval_timeout() {
         timestamp: chrono::Utc::now(),
         timeout_secs: 1, // 1 秒超时
         display_message: None,
+        approver_id: None,
     };
     
     // 不发送响应，让请求超时
@@ -197,6 +212,7 @@ async fn test_outbound_approval_request_formatting() {
         timestamp: chrono::Utc::now(),
         timeout_secs: 300,
         display_message: None,
+        approver_id: None,
     };
     
     let msg = OutboundMessage {
@@ -395,6 +411,7 @@ async fn test_feishu_approval_request_format() {
         timestamp: chrono::Utc::now(),
         timeout_secs: 300,
         display_message: None,
+        approver_id: None,
     };
     
     let formatted = format!(
@@ -2,7 +2,7 @@
 
 use synbot::sandbox::{
     SandboxConfig, FilesystemConfig, NetworkConfig, ResourceConfig,
-    ProcessConfig, MonitoringConfig, ConfigurationManager, SandboxError,
+    ProcessConfig, MonitoringConfig, ConfigurationManager, SandboxError, HardeningConfig,
 };
 
 #[test]
@@ -20,6 +20,7 @@ fn test_sandbox_config_serialization() {
             enabled: false,
             allowed_hosts: vec![],
             allowed_ports: vec![],
+            host_network: false,
         },
         resources: ResourceConfig {
             max_memory: 1024 * 1024 * 1024,
@@ -35,6 +36,7 @@ fn test_sandbox_config_serialization() {
         delete_on_start: false,
         requested_tool_sandbox_type: None,
         image: None,
+        hardening: HardeningConfig::default(),
     };
     
     // Serialize to JSON
@@ -8,7 +8,7 @@ use synbot::sandbox::{
     SandboxManager, SandboxConfig, FilesystemConfig, NetworkConfig,
     ResourceConfig, ProcessConfig, MonitoringConfig, ConfigurationManager,
     MonitoringModule, LogOutput, AuditConfig,
-    MetricsConfig, PlatformDetector,
+    MetricsConfig, PlatformDetector, HardeningConfig,
 };
 use std::sync::Arc;
 use std::time::Duration;
@@ -175,6 +175,7 @@ async fn test_cross_component_error_handling() {
             enabled: false,
             allowed_hosts: vec![],
             allowed_ports: vec![],
+            host_network: false,
         },
         resources: ResourceConfig {
             max_memory: 0, // Invalid: too small
@@ -190,6 +191,7 @@ async fn test_cross_component_error_handling() {
         delete_on_start: false,
         requested_tool_sandbox_type: None,
         image: None,
+        hardening: HardeningConfig::default(),
     };
     
     let result = manager.create_app_sandbox(invalid_config).await;
@@ -321,6 +323,7 @@ fn create_test_app_sandbox_config(sandbox_id: &str) -> SandboxConfig {
             enabled: true,
             allowed_hosts: vec!["api.example.com".to_string()],
             allowed_ports: vec![80, 443],
+            host_network: false,
         },
         resources: ResourceConfig {
             max_memory: 1024 * 1024 * 1024, // 1GB
@@ -336,6 +339,7 @@ fn create_test_app_sandbox_config(sandbox_id: &str) -> SandboxConfig {
         delete_on_start: false,
         requested_tool_sandbox_type: None,
         image: None,
+        hardening: HardeningConfig::default(),
     }
 }
 
@@ -353,6 +357,7 @@ fn create_test_tool_sandbox_config(sandbox_id: &str) -> SandboxConfig {
             enabled: false,
             allowed_hosts: vec![],
             allowed_ports: vec![],
+            host_network: false,
         },
         resources: ResourceConfig {
             max_memory: 512 * 1024 * 1024, // 512MB
@@ -368,6 +373,7 @@ fn create_test_tool_sandbox_config(sandbox_id: &str) -> SandboxConfig {
         delete_on_start: false,
         requested_tool_sandbox_type: None,
         image: None,
+        hardening: HardeningConfig::default(),
     }
 }
 
@@ -2,6 +2,8 @@
 //!
 //! 测试 Telegram 渠道的审批请求和响应处理
 
+#![cfg(feature = "telegram")]
+
 use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc};
 use synbot::bus::{InboundMessage, OutboundMessage, OutboundMessageType};
@@ -25,6 +27,14 @@ fn create_test_channel(
         show_tool_calls: true,
         group_my_name: None,
         default_agent: "main".to_string(),
+        inbound_limit: None,
+        outbound_min_interval_ms: None,
+        commands: Vec::new(),
+        tools_enabled: true,
+        provider: None,
+        model: None,
+        role: None,
+        typing_indicator: false,
     };
     
     let mut channel =
@@ -52,6 +62,7 @@ async fn test_approval_manager_integration() {
         timestamp: chrono::Utc::now(),
         timeout_secs: 300,
         display_message: None,
+        approver_id: None,
     };
     
     // 在后台任务中模拟审批响应
@@ -103,6 +114,7 @@ async fn test_approval_rejection() {
         timestamp: chrono::Utc::now(),
         timeout_secs: 300,
         display_message: None,
+        approver_id: None,
     };
     
     // 在后台任务中模拟拒绝响应
@@ -154,6 +166,7 @@ async fn test_approval_timeout() {
         timestamp: chrono::Utc::now(),
         timeout_secs: 1, // 1 秒超时
         display_message: None,
+        approver_id: None,
     };
     
     // 不发送响应，让请求超时
@@ -198,6 +211,7 @@ async fn test_outbound_approval_request_formatting() {
         timestamp: chrono::Utc::now(),
         timeout_secs: 300,
         display_message: None,
+        approver_id: None,
     };
     
     let msg = OutboundMessage {
@@ -9,6 +9,8 @@
 //!
 //! Run with: `cargo test --test web_api_tests`
 
+#![cfg(feature = "web")]
+
 use actix_web::{test, web, App};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use std::sync::Arc;
@@ -58,6 +60,51 @@ async fn test_get_status_returns_200() {
     assert!(body["data"]["running"].as_bool().unwrap_or(false));
 }
 
+#[actix_web::test]
+async fn test_get_version_returns_build_info() {
+    let app = test::init_service(
+        App::new().route("/api/version", web::get().to(api::get_version)),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/api/version").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["success"], true);
+    assert_eq!(body["data"]["version"], env!("CARGO_PKG_VERSION"));
+    assert!(body["data"]["git_sha"].is_string());
+    assert!(body["data"]["build_timestamp"].is_string());
+    assert!(body["data"]["features"].is_array());
+}
+
+#[actix_web::test]
+async fn test_metrics_endpoints_share_snapshot() {
+    let monitoring = Arc::new(synbot::sandbox::MonitoringModule::new(Default::default()));
+    monitoring.record_tool_execution(std::time::Duration::from_millis(30), false);
+    let state = create_test_state().await.with_sandbox_monitoring(monitoring);
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(state))
+            .route("/api/metrics", web::get().to(api::get_metrics))
+            .route("/metrics", web::get().to(api::get_metrics_prometheus)),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/api/metrics").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["success"], true);
+    assert_eq!(body["data"]["counters"]["toolExecutions"], 1);
+    assert_eq!(body["data"]["counters"]["toolFailures"], 1);
+
+    let req = test::TestRequest::get().uri("/metrics").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let text = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert!(text.contains("synbot_tool_failures_total 1\n"));
+}
+
 #[actix_web::test]
 async fn test_get_sessions_returns_200_with_empty_list() {
     let state = create_test_state().await;
@@ -560,3 +607,584 @@ async fn test_get_pending_approvals_returns_200() {
     assert_eq!(body["success"], true);
     assert!(body["data"].is_array());
 }
+
+#[actix_web::test]
+async fn test_get_approval_stats_returns_200() {
+    let state = create_test_state().await;
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(state))
+            .route(
+                "/api/approvals/stats",
+                web::get().to(api::get_approval_stats),
+            ),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/approvals/stats")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["success"], true);
+    assert_eq!(body["data"]["pending"], 0);
+    assert_eq!(body["data"]["approved_total"], 0);
+    assert!(body["data"]["oldest_pending_age_secs"].is_null());
+}
+
+#[actix_web::test]
+async fn test_cancel_session_forwards_cancel_to_bus() {
+    let (inbound_tx, mut inbound_rx) = tokio::sync::mpsc::channel(100);
+    let (outbound_tx, _) = tokio::sync::broadcast::channel(100);
+    let approval_manager = Arc::new(ApprovalManager::new());
+    let state =
+        common::create_test_app_state_with_approval(inbound_tx, outbound_tx, approval_manager).await;
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(state))
+            .route("/api/sessions/{id}/cancel", web::post().to(api::cancel_session)),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/sessions/agent:main:telegram:dm:chat-42/cancel")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 202);
+
+    let msg = inbound_rx.try_recv().expect("cancel should be sent on the bus");
+    assert_eq!(msg.channel, "telegram");
+    assert_eq!(msg.chat_id, "chat-42");
+    assert_eq!(msg.content, "/cancel");
+}
+
+#[actix_web::test]
+async fn test_cancel_session_rejects_id_without_chat() {
+    let state = create_test_state().await;
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(state))
+            .route("/api/sessions/{id}/cancel", web::post().to(api::cancel_session)),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/sessions/agent:main:telegram/cancel")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[actix_web::test]
+async fn test_heartbeat_pause_resume_and_run_now() {
+    let state = create_test_state().await;
+    let control = Arc::clone(&state.heartbeat_control);
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(state))
+            .route("/api/heartbeat", web::get().to(api::get_heartbeat_status))
+            .route("/api/heartbeat/pause", web::post().to(api::pause_heartbeat))
+            .route("/api/heartbeat/resume", web::post().to(api::resume_heartbeat))
+            .route("/api/heartbeat/run-now", web::post().to(api::run_heartbeat_now)),
+    )
+    .await;
+
+    let req = test::TestRequest::post().uri("/api/heartbeat/pause").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["data"]["paused"], true);
+    assert!(control.is_paused());
+
+    let req = test::TestRequest::get().uri("/api/heartbeat").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["data"]["paused"], true);
+
+    let req = test::TestRequest::post().uri("/api/heartbeat/run-now").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 202);
+
+    let req = test::TestRequest::post().uri("/api/heartbeat/resume").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["data"]["paused"], false);
+    assert!(!control.is_paused());
+}
+
+#[actix_web::test]
+async fn test_running_turn_is_listed_and_can_be_killed() {
+    let state = create_test_state().await;
+    let turns = Arc::clone(&state.active_turns);
+    let token = tokio_util::sync::CancellationToken::new();
+    let id = turns.start("telegram", "chat-42", token.clone());
+    turns.set_current_tool("telegram:chat-42", Some("exec"));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(state))
+            .route("/api/turns", web::get().to(api::get_turns))
+            .route("/api/turns/{id}", web::delete().to(api::kill_turn)),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/api/turns").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let listed = body["data"].as_array().unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0]["id"], id.as_str());
+    assert_eq!(listed[0]["session_key"], "telegram:chat-42");
+    assert_eq!(listed[0]["current_tool"], "exec");
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/turns/{}", id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(token.is_cancelled());
+
+    let req = test::TestRequest::get().uri("/api/turns").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(body["data"].as_array().unwrap().is_empty());
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/turns/{}", id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+// ---------------------------------------------------------------------------
+// Dead letters (GET /api/deadletters)
+// ---------------------------------------------------------------------------
+
+#[actix_web::test]
+async fn test_dead_letters_lists_messages_for_down_channels() {
+    let state = create_test_state().await;
+    let dead_letters = state.dead_letters.clone();
+    let mut outbound_rx = state.outbound_tx.subscribe();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(state))
+            .route("/api/deadletters", web::get().to(api::get_dead_letters)),
+    )
+    .await;
+
+    dead_letters.mark_down("telegram", "retries exhausted");
+    assert!(dead_letters.divert(&synbot::bus::OutboundMessage::chat(
+        "telegram".to_string(),
+        "chat-7".to_string(),
+        "reply while offline".to_string(),
+        vec![],
+        None,
+    )));
+
+    let req = test::TestRequest::get().uri("/api/deadletters").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["data"]["channels_down"]["telegram"], "retries exhausted");
+    let messages = body["data"]["messages"].as_array().unwrap();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0]["message"]["chat_id"], "chat-7");
+    assert_eq!(messages[0]["message"]["content"], "reply while offline");
+
+    assert_eq!(dead_letters.mark_up("telegram"), 1);
+    let retried = outbound_rx.try_recv().expect("retried message");
+    assert_eq!(retried.chat_id, "chat-7");
+    let req = test::TestRequest::get().uri("/api/deadletters").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(body["data"]["messages"].as_array().unwrap().is_empty());
+    assert!(body["data"]["channels_down"].as_object().unwrap().is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// Web session management — create, list, delete, per-user isolation
+// ---------------------------------------------------------------------------
+
+/// Request as if the auth middleware had authenticated `username`.
+fn as_user<R: actix_web::HttpMessage>(req: R, username: &str) -> R {
+    req.extensions_mut().insert(synbot::web::AuthenticatedUser {
+        username: username.to_string(),
+    });
+    req
+}
+
+#[actix_web::test]
+async fn test_web_sessions_create_list_delete_are_scoped_per_user() {
+    let state = create_test_state().await;
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(state))
+            .route("/api/sessions", web::get().to(api::get_sessions))
+            .route("/api/sessions", web::post().to(api::create_session))
+            .route("/api/sessions/{id}", web::get().to(api::get_session_by_id))
+            .route("/api/sessions/{id}", web::delete().to(api::delete_session))
+            .route("/api/sessions/{id}/cancel", web::post().to(api::cancel_session)),
+    )
+    .await;
+
+    // alice creates a session
+    let req = as_user(test::TestRequest::post().uri("/api/sessions").to_request(), "alice");
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let id = body["data"]["id"].as_str().unwrap().to_string();
+    assert_eq!(body["data"]["channel"], "web");
+    assert!(body["data"]["identifier"].as_str().unwrap().starts_with("alice~"));
+
+    let ids_for = |body: &serde_json::Value| -> Vec<String> {
+        body["data"]["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s["id"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    // alice sees it, bob does not
+    let req = as_user(test::TestRequest::get().uri("/api/sessions").to_request(), "alice");
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(ids_for(&body), vec![id.clone()]);
+
+    let req = as_user(test::TestRequest::get().uri("/api/sessions").to_request(), "bob");
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(ids_for(&body).is_empty());
+
+    let req = as_user(
+        test::TestRequest::get().uri(&format!("/api/sessions/{}", id)).to_request(),
+        "bob",
+    );
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    // bob cannot delete it
+    let req = as_user(
+        test::TestRequest::delete().uri(&format!("/api/sessions/{}", id)).to_request(),
+        "bob",
+    );
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    // bob cannot cancel its turn either
+    let req = as_user(
+        test::TestRequest::post().uri(&format!("/api/sessions/{}/cancel", id)).to_request(),
+        "bob",
+    );
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    // alice can, after which it is gone
+    let req = as_user(
+        test::TestRequest::delete().uri(&format!("/api/sessions/{}", id)).to_request(),
+        "alice",
+    );
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = as_user(test::TestRequest::get().uri("/api/sessions").to_request(), "alice");
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(ids_for(&body).is_empty());
+
+    let req = as_user(
+        test::TestRequest::delete().uri(&format!("/api/sessions/{}", id)).to_request(),
+        "alice",
+    );
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+#[actix_web::test]
+async fn test_delete_session_rejects_non_web_sessions() {
+    let state = create_test_state().await;
+    let sid = synbot::agent::session_id::SessionId::full(
+        "main",
+        "telegram",
+        synbot::agent::session_id::SessionScope::Dm,
+        "chat-1",
+    );
+    state.session_manager.write().await.get_or_create(&sid);
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(state))
+            .route("/api/sessions/{id}", web::delete().to(api::delete_session)),
+    )
+    .await;
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/sessions/{}", sid.format()))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+}
+
+// ---------------------------------------------------------------------------
+// SSE stream (GET /api/stream)
+// ---------------------------------------------------------------------------
+
+#[actix_web::test]
+async fn test_sse_stream_emits_event_for_broadcast_outbound_message() {
+    use actix_web::body::MessageBody;
+
+    let state = create_test_state().await;
+    let outbound_tx = state.outbound_tx.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(state))
+            .route("/api/stream", web::get().to(synbot::web::handlers::sse::stream_events)),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/api/stream").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("content-type").unwrap().to_str().unwrap(),
+        "text/event-stream"
+    );
+
+    // Only the web chat's messages are streamed.
+    outbound_tx
+        .send(synbot::bus::OutboundMessage::chat(
+            "telegram".to_string(),
+            "chat-1".to_string(),
+            "not for the web".to_string(),
+            vec![],
+            None,
+        ))
+        .unwrap();
+    outbound_tx
+        .send(synbot::bus::OutboundMessage::chat(
+            "web".to_string(),
+            "web_admin".to_string(),
+            "hello over sse".to_string(),
+            vec![],
+            None,
+        ))
+        .unwrap();
+
+    let mut body = Box::pin(resp.into_body());
+    let mut received = String::new();
+    let read = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        while !received.contains("hello over sse") {
+            let chunk = std::future::poll_fn(|cx| body.as_mut().poll_next(cx))
+                .await
+                .expect("stream ended early")
+                .expect("stream error");
+            received.push_str(&String::from_utf8_lossy(&chunk));
+        }
+    })
+    .await;
+    assert!(read.is_ok(), "no SSE event within 5s, got: {}", received);
+    assert!(received.starts_with(": connected\n\n"));
+    assert!(received.contains("event: chat_response\ndata: "));
+    assert!(!received.contains("not for the web"));
+}
+
+#[actix_web::test]
+async fn test_sse_stream_of_a_web_session_is_scoped_to_its_owner() {
+    use actix_web::body::MessageBody;
+
+    let state = create_test_state().await;
+    let outbound_tx = state.outbound_tx.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(state))
+            .route("/api/sessions", web::post().to(api::create_session))
+            .route("/api/stream", web::get().to(synbot::web::handlers::sse::stream_events)),
+    )
+    .await;
+
+    let req = as_user(test::TestRequest::post().uri("/api/sessions").to_request(), "alice");
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let id = body["data"]["id"].as_str().unwrap().to_string();
+    let chat_id = body["data"]["identifier"].as_str().unwrap().to_string();
+
+    let uri = format!("/api/stream?session={}", id);
+    let req = as_user(test::TestRequest::get().uri(&uri).to_request(), "bob");
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = as_user(test::TestRequest::get().uri(&uri).to_request(), "alice");
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    // The shared web chat is not part of the session.
+    for (chat, content) in [("web_admin", "shared chat"), (chat_id.as_str(), "in alice's session")] {
+        outbound_tx
+            .send(synbot::bus::OutboundMessage::chat(
+                "web".to_string(),
+                chat.to_string(),
+                content.to_string(),
+                vec![],
+                None,
+            ))
+            .unwrap();
+    }
+
+    let mut body = Box::pin(resp.into_body());
+    let mut received = String::new();
+    let read = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        while !received.contains("in alice's session") {
+            let chunk = std::future::poll_fn(|cx| body.as_mut().poll_next(cx))
+                .await
+                .expect("stream ended early")
+                .expect("stream error");
+            received.push_str(&String::from_utf8_lossy(&chunk));
+        }
+    })
+    .await;
+    assert!(read.is_ok(), "no SSE event within 5s, got: {}", received);
+    assert!(!received.contains("shared chat"));
+}
+
+// ---------------------------------------------------------------------------
+// Config cron / heartbeat task CRUD
+// ---------------------------------------------------------------------------
+
+/// State whose config has the web chat enabled, so tasks can target the "web" channel.
+async fn create_task_test_state() -> AppState {
+    let state = create_test_state().await;
+    state.config.write().await.web.enabled = true;
+    state
+}
+
+fn cron_task_json(schedule: &str) -> serde_json::Value {
+    serde_json::json!({
+        "schedule": schedule,
+        "description": "daily report",
+        "command": "summarize yesterday",
+        "channel": "web",
+        "userId": "web_admin"
+    })
+}
+
+#[actix_web::test]
+async fn test_create_cron_task_saves_and_applies_config() {
+    let state = create_task_test_state().await;
+    let config = Arc::clone(&state.config);
+    let config_path = state.config_path.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(state))
+            .route("/api/cron/tasks", web::get().to(api::get_cron_tasks))
+            .route("/api/cron/tasks", web::post().to(api::create_cron_task)),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/cron/tasks")
+        .set_json(cron_task_json("0 9 * * *"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"][0]["index"], 0);
+    assert_eq!(body["data"][0]["schedule"], "0 9 * * *");
+
+    assert_eq!(config.read().await.cron.tasks.len(), 1);
+    let saved = std::fs::read_to_string(&config_path).expect("config file written");
+    assert!(saved.contains("summarize yesterday"));
+
+    let req = test::TestRequest::get().uri("/api/cron/tasks").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+    let _ = std::fs::remove_file(&config_path);
+}
+
+#[actix_web::test]
+async fn test_create_cron_task_rejects_invalid_schedule() {
+    let state = create_task_test_state().await;
+    let config = Arc::clone(&state.config);
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(state))
+            .route("/api/cron/tasks", web::post().to(api::create_cron_task)),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/cron/tasks")
+        .set_json(cron_task_json("every day at nine"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["success"], false);
+    let errs = body["data"]["validationErrors"].as_array().unwrap();
+    assert!(errs.iter().any(|e| e["field"] == "cron.tasks[0].schedule"));
+    assert!(config.read().await.cron.tasks.is_empty(), "running config unchanged");
+}
+
+#[actix_web::test]
+async fn test_delete_heartbeat_task_by_index() {
+    let state = create_task_test_state().await;
+    let config = Arc::clone(&state.config);
+    let config_path = state.config_path.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(state))
+            .route("/api/heartbeat/tasks", web::post().to(api::create_heartbeat_task))
+            .route("/api/heartbeat/tasks/{index}", web::delete().to(api::delete_heartbeat_task)),
+    )
+    .await;
+
+    for target in ["check disk usage", "check inbox"] {
+        let req = test::TestRequest::post()
+            .uri("/api/heartbeat/tasks")
+            .set_json(serde_json::json!({
+                "channel": "web",
+                "chatId": "web_admin",
+                "userId": "web_admin",
+                "target": target
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+    }
+
+    let req = test::TestRequest::delete().uri("/api/heartbeat/tasks/0").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+    assert_eq!(body["data"][0]["target"], "check inbox");
+    let tasks = config.read().await.heartbeat.tasks.clone();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].target, "check inbox");
+
+    let req = test::TestRequest::delete().uri("/api/heartbeat/tasks/5").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    let _ = std::fs::remove_file(&config_path);
+}
+
+#[actix_web::test]
+async fn test_malformed_json_body_returns_400_envelope() {
+    let state = create_test_state().await;
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(state))
+            .app_data(api::json_config())
+            .route("/api/config", web::put().to(api::put_config))
+            .route(
+                "/api/approvals/{id}/respond",
+                web::post().to(api::submit_approval_response),
+            ),
+    )
+    .await;
+
+    for (req, what) in [
+        (
+            test::TestRequest::put()
+                .uri("/api/config")
+                .insert_header(("content-type", "application/json"))
+                .set_payload("{\"mainAgent\": {"),
+            "truncated JSON",
+        ),
+        (
+            test::TestRequest::post()
+                .uri("/api/approvals/abc/respond")
+                .insert_header(("content-type", "application/json"))
+                .set_payload("{\"approved\": \"maybe\"}"),
+            "wrong field type",
+        ),
+    ] {
+        let resp = test::call_service(&app, req.to_request()).await;
+        assert_eq!(resp.status(), 400, "{}", what);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["code"], "BAD_REQUEST", "{}", what);
+        assert!(
+            body["error"].as_str().unwrap().contains("Invalid JSON body"),
+            "{}: {}",
+            what,
+            body
+        );
+    }
+}
@@ -17,7 +17,7 @@ use synbot::sandbox::{
     manager::SandboxManager,
     types::{
         SandboxConfig, FilesystemConfig, NetworkConfig, ResourceConfig,
-        ProcessConfig, MonitoringConfig,
+        ProcessConfig, MonitoringConfig, HardeningConfig,
     },
 };
 use futures_util::future;
@@ -37,6 +37,7 @@ fn create_benchmark_config(sandbox_id: &str, platform: &str) -> SandboxConfig {
             enabled: false,
             allowed_hosts: vec![],
             allowed_ports: vec![],
+            host_network: false,
         },
         resources: ResourceConfig {
             max_memory: 512 * 1024 * 1024, // 512MB
@@ -47,9 +48,12 @@ fn create_benchmark_config(sandbox_id: &str, platform: &str) -> SandboxConfig {
             allow_fork: false,
             max_processes: 10,
         },
+        child_work_dir: None,
         monitoring: MonitoringConfig::default(),
         delete_on_start: false,
         requested_tool_sandbox_type: None,
+        image: None,
+        hardening: HardeningConfig::default(),
     }
 }
 
@@ -5,6 +5,10 @@ import type {
   SessionDetail,
   ChannelInfo,
   CronJobInfo,
+  CronTask,
+  CronTaskInput,
+  HeartbeatTask,
+  HeartbeatTaskInput,
   AgentInfo,
   SkillInfo,
   SkillDetail,
@@ -130,6 +134,47 @@ export class ApiClient {
     return response.data.data!;
   }
 
+  // Scheduled tasks in config
+  async getCronTasks(): Promise<CronTask[]> {
+    const response = await this.client.get<ApiResponse<CronTask[]>>('/api/cron/tasks');
+    return response.data.data!;
+  }
+
+  async createCronTask(task: CronTaskInput): Promise<CronTask[]> {
+    const response = await this.client.post<ApiResponse<CronTask[]>>('/api/cron/tasks', task);
+    return response.data.data!;
+  }
+
+  async updateCronTask(index: number, task: CronTaskInput): Promise<CronTask[]> {
+    const response = await this.client.put<ApiResponse<CronTask[]>>(`/api/cron/tasks/${index}`, task);
+    return response.data.data!;
+  }
+
+  async deleteCronTask(index: number): Promise<CronTask[]> {
+    const response = await this.client.delete<ApiResponse<CronTask[]>>(`/api/cron/tasks/${index}`);
+    return response.data.data!;
+  }
+
+  async getHeartbeatTasks(): Promise<HeartbeatTask[]> {
+    const response = await this.client.get<ApiResponse<HeartbeatTask[]>>('/api/heartbeat/tasks');
+    return response.data.data!;
+  }
+
+  async createHeartbeatTask(task: HeartbeatTaskInput): Promise<HeartbeatTask[]> {
+    const response = await this.client.post<ApiResponse<HeartbeatTask[]>>('/api/heartbeat/tasks', task);
+    return response.data.data!;
+  }
+
+  async updateHeartbeatTask(index: number, task: HeartbeatTaskInput): Promise<HeartbeatTask[]> {
+    const response = await this.client.put<ApiResponse<HeartbeatTask[]>>(`/api/heartbeat/tasks/${index}`, task);
+    return response.data.data!;
+  }
+
+  async deleteHeartbeatTask(index: number): Promise<HeartbeatTask[]> {
+    const response = await this.client.delete<ApiResponse<HeartbeatTask[]>>(`/api/heartbeat/tasks/${index}`);
+    return response.data.data!;
+  }
+
   // Agents
   async getAgents(): Promise<AgentInfo[]> {
     const response = await this.client.get<ApiResponse<AgentInfo[]>>('/api/agents');
@@ -0,0 +1,274 @@
+import React, { useEffect, useState } from 'react'
+import { AxiosError } from 'axios'
+import { apiClient } from '../api/client'
+import type {
+  CronTask,
+  CronTaskInput,
+  HeartbeatTask,
+  HeartbeatTaskInput,
+  ValidationErrorItem,
+} from '../types/api'
+import { useI18n } from '../i18n/I18nContext'
+
+const emptyCron: CronTaskInput = {
+  schedule: '',
+  description: '',
+  enabled: true,
+  command: '',
+  channel: '',
+  userId: '',
+}
+
+const emptyHeartbeat: HeartbeatTaskInput = {
+  channel: '',
+  chatId: '',
+  userId: '',
+  target: '',
+}
+
+const inputClass =
+  'w-full px-3 py-2 rounded border border-gray-300 bg-surface text-text text-sm'
+
+/** Cron and heartbeat tasks stored in config; edits are saved and applied by the server. */
+const ScheduledTasks: React.FC = () => {
+  const [cronTasks, setCronTasks] = useState<CronTask[]>([])
+  const [heartbeatTasks, setHeartbeatTasks] = useState<HeartbeatTask[]>([])
+  const [newCron, setNewCron] = useState<CronTaskInput>(emptyCron)
+  const [newHeartbeat, setNewHeartbeat] = useState<HeartbeatTaskInput>(emptyHeartbeat)
+  const [busy, setBusy] = useState(false)
+  const [error, setError] = useState<string | null>(null)
+  const [validationErrors, setValidationErrors] = useState<ValidationErrorItem[]>([])
+  const { t } = useI18n()
+
+  const fetchTasks = async () => {
+    try {
+      const [cron, heartbeat] = await Promise.all([
+        apiClient.getCronTasks(),
+        apiClient.getHeartbeatTasks(),
+      ])
+      setCronTasks(cron)
+      setHeartbeatTasks(heartbeat)
+    } catch (err) {
+      setError(t('cron.tasks.failedToFetch'))
+      console.error(err)
+    }
+  }
+
+  useEffect(() => {
+    fetchTasks()
+  }, [])
+
+  /** Run one edit; on success the returned list replaces the shown one. */
+  const run = async <T,>(edit: () => Promise<T[]>, apply: (tasks: T[]) => void) => {
+    setBusy(true)
+    setError(null)
+    setValidationErrors([])
+    try {
+      apply(await edit())
+      return true
+    } catch (err) {
+      if (err instanceof AxiosError && err.response) {
+        const body = err.response.data as {
+          data?: { validationErrors?: ValidationErrorItem[] }
+          error?: string
+        }
+        setValidationErrors(body.data?.validationErrors ?? [])
+        setError(body.error ?? t('cron.tasks.saveFailed'))
+      } else {
+        setError(t('cron.tasks.saveFailed'))
+      }
+      console.error(err)
+      return false
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  const toInput = <T extends { index: number }>({ index: _index, ...rest }: T) => rest
+
+  const addCron = async () => {
+    if (await run(() => apiClient.createCronTask(newCron), setCronTasks)) {
+      setNewCron(emptyCron)
+    }
+  }
+
+  const addHeartbeat = async () => {
+    if (await run(() => apiClient.createHeartbeatTask(newHeartbeat), setHeartbeatTasks)) {
+      setNewHeartbeat(emptyHeartbeat)
+    }
+  }
+
+  return (
+    <div className="space-y-6 mb-8">
+      {error && (
+        <div className="bg-red-50 border border-red-200 rounded-lg p-4">
+          <p className="text-red-800">{error}</p>
+          {validationErrors.length > 0 && (
+            <ul className="mt-2 text-sm text-red-700 list-disc pl-5">
+              {validationErrors.map((e) => (
+                <li key={e.field}>
+                  <code>{e.field}</code>: {e.constraint}
+                </li>
+              ))}
+            </ul>
+          )}
+        </div>
+      )}
+
+      <section className="bg-surface rounded-lg shadow p-6">
+        <h3 className="text-lg font-semibold text-text">{t('cron.tasks.cronTitle')}</h3>
+        <p className="text-sm text-text-secondary mt-1">{t('cron.tasks.cronDescription')}</p>
+        <div className="mt-4 space-y-3">
+          {cronTasks.map((task) => (
+            <div
+              key={task.index}
+              className="flex items-start justify-between border-b border-gray-200 pb-3"
+            >
+              <div className="flex-1">
+                <p className="text-sm text-text">
+                  <code className="bg-gray-100 px-2 py-0.5 rounded">{task.schedule}</code>{' '}
+                  {task.description || task.command}
+                </p>
+                <p className="text-xs text-gray-500 mt-1">
+                  {task.channel} / {task.chatId || task.userId}
+                </p>
+              </div>
+              <div className="flex gap-2">
+                <button
+                  onClick={() =>
+                    run(
+                      () =>
+                        apiClient.updateCronTask(task.index, {
+                          ...toInput(task),
+                          enabled: !task.enabled,
+                        }),
+                      setCronTasks,
+                    )
+                  }
+                  disabled={busy}
+                  className={`px-3 py-1 rounded text-sm font-medium ${
+                    task.enabled
+                      ? 'bg-red-100 text-red-700 hover:bg-red-200'
+                      : 'bg-green-100 text-green-700 hover:bg-green-200'
+                  } disabled:opacity-50`}
+                >
+                  {task.enabled ? t('cron.disable') : t('cron.enable')}
+                </button>
+                <button
+                  onClick={() => run(() => apiClient.deleteCronTask(task.index), setCronTasks)}
+                  disabled={busy}
+                  className="px-3 py-1 rounded text-sm font-medium bg-gray-100 text-gray-700 hover:bg-gray-200 disabled:opacity-50"
+                >
+                  {t('cron.tasks.delete')}
+                </button>
+              </div>
+            </div>
+          ))}
+          {cronTasks.length === 0 && (
+            <p className="text-sm text-text-secondary">{t('cron.tasks.noTasks')}</p>
+          )}
+        </div>
+        <div className="mt-4 grid grid-cols-1 md:grid-cols-5 gap-2">
+          <input
+            className={inputClass}
+            placeholder={t('cron.tasks.schedulePlaceholder')}
+            value={newCron.schedule}
+            onChange={(e) => setNewCron({ ...newCron, schedule: e.target.value })}
+          />
+          <input
+            className={inputClass}
+            placeholder={t('cron.tasks.command')}
+            value={newCron.command}
+            onChange={(e) => setNewCron({ ...newCron, command: e.target.value })}
+          />
+          <input
+            className={inputClass}
+            placeholder={t('cron.tasks.channel')}
+            value={newCron.channel}
+            onChange={(e) => setNewCron({ ...newCron, channel: e.target.value })}
+          />
+          <input
+            className={inputClass}
+            placeholder={t('cron.tasks.userId')}
+            value={newCron.userId}
+            onChange={(e) => setNewCron({ ...newCron, userId: e.target.value })}
+          />
+          <button
+            onClick={addCron}
+            disabled={busy}
+            className="px-4 py-2 rounded-lg font-medium bg-primary text-white hover:opacity-90 disabled:opacity-50"
+          >
+            {t('cron.tasks.add')}
+          </button>
+        </div>
+      </section>
+
+      <section className="bg-surface rounded-lg shadow p-6">
+        <h3 className="text-lg font-semibold text-text">{t('cron.tasks.heartbeatTitle')}</h3>
+        <p className="text-sm text-text-secondary mt-1">{t('cron.tasks.heartbeatDescription')}</p>
+        <div className="mt-4 space-y-3">
+          {heartbeatTasks.map((task) => (
+            <div
+              key={task.index}
+              className="flex items-start justify-between border-b border-gray-200 pb-3"
+            >
+              <div className="flex-1">
+                <p className="text-sm text-text">{task.target}</p>
+                <p className="text-xs text-gray-500 mt-1">
+                  {task.channel} / {task.chatId}
+                </p>
+              </div>
+              <button
+                onClick={() =>
+                  run(() => apiClient.deleteHeartbeatTask(task.index), setHeartbeatTasks)
+                }
+                disabled={busy}
+                className="px-3 py-1 rounded text-sm font-medium bg-gray-100 text-gray-700 hover:bg-gray-200 disabled:opacity-50"
+              >
+                {t('cron.tasks.delete')}
+              </button>
+            </div>
+          ))}
+          {heartbeatTasks.length === 0 && (
+            <p className="text-sm text-text-secondary">{t('cron.tasks.noTasks')}</p>
+          )}
+        </div>
+        <div className="mt-4 grid grid-cols-1 md:grid-cols-5 gap-2">
+          <input
+            className={inputClass}
+            placeholder={t('cron.tasks.target')}
+            value={newHeartbeat.target}
+            onChange={(e) => setNewHeartbeat({ ...newHeartbeat, target: e.target.value })}
+          />
+          <input
+            className={inputClass}
+            placeholder={t('cron.tasks.channel')}
+            value={newHeartbeat.channel}
+            onChange={(e) => setNewHeartbeat({ ...newHeartbeat, channel: e.target.value })}
+          />
+          <input
+            className={inputClass}
+            placeholder={t('cron.tasks.chatId')}
+            value={newHeartbeat.chatId}
+            onChange={(e) => setNewHeartbeat({ ...newHeartbeat, chatId: e.target.value })}
+          />
+          <input
+            className={inputClass}
+            placeholder={t('cron.tasks.userId')}
+            value={newHeartbeat.userId}
+            onChange={(e) => setNewHeartbeat({ ...newHeartbeat, userId: e.target.value })}
+          />
+          <button
+            onClick={addHeartbeat}
+            disabled={busy}
+            className="px-4 py-2 rounded-lg font-medium bg-primary text-white hover:opacity-90 disabled:opacity-50"
+          >
+            {t('cron.tasks.add')}
+          </button>
+        </div>
+      </section>
+    </div>
+  )
+}
+
+export default ScheduledTasks
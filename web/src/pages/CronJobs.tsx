@@ -2,6 +2,7 @@ import React, { useEffect, useState } from 'react'
 import { apiClient } from '../api/client'
 import type { CronJobInfo } from '../types/api'
 import { useI18n } from '../i18n/I18nContext'
+import ScheduledTasks from '../components/ScheduledTasks'
 
 const CronJobs: React.FC = () => {
   const [jobs, setJobs] = useState<CronJobInfo[]>([])
@@ -69,6 +70,8 @@ const CronJobs: React.FC = () => {
         <p className="text-text-secondary mt-1">{t('cron.description')}</p>
       </div>
 
+      <ScheduledTasks />
+
       <div className="space-y-4">
         {jobs.map((job) => (
           <div
@@ -230,6 +230,11 @@ const Logs: React.FC = () => {
                     </span>
                     <span className="text-text flex-1 break-words">
                       {log.message}
+                      {Object.entries(log.fields ?? {}).map(([key, value]) => (
+                        <span key={key} className="ml-2 text-text-secondary">
+                          {key}={typeof value === 'string' ? value : JSON.stringify(value)}
+                        </span>
+                      ))}
                     </span>
                   </div>
                 </div>
@@ -79,6 +79,35 @@ export interface CronJobInfo {
   payload: Record<string, unknown>;
 }
 
+// Scheduled tasks from config (cron.tasks / heartbeat.tasks); index is the position in the list
+export interface CronTask {
+  index: number;
+  schedule: string;
+  description: string;
+  enabled: boolean;
+  command: string;
+  channel: string;
+  userId: string;
+  chatId?: string | null;
+  misfirePolicy?: string;
+  overlapPolicy?: string;
+  role?: string | null;
+}
+
+export interface HeartbeatTask {
+  index: number;
+  channel: string;
+  chatId: string;
+  userId: string;
+  target: string;
+  type?: 'agent' | 'health_report';
+  onError?: string;
+  role?: string | null;
+}
+
+export type CronTaskInput = Omit<CronTask, 'index'>;
+export type HeartbeatTaskInput = Omit<HeartbeatTask, 'index'>;
+
 // Agent Types
 export interface AgentInfo {
   name: string;
@@ -131,6 +160,7 @@ export interface LogEntry {
   level: string;
   target: string;
   message: string;
+  fields: Record<string, unknown>;
 }
 
 export interface LogQueryParams {